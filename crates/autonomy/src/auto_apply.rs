@@ -0,0 +1,274 @@
+//! Auto-Apply Tier for trivial proposals
+//!
+//! A proposal is applied immediately (instead of waiting for approval) only when:
+//! - `AUTONOMY_AUTO_APPLY=true`
+//! - the reviewer rated it `risk: trivial`
+//! - every file it touches sits under an allowlisted path and none is hard-excluded
+//! - the plan does not require shell execution
+//!
+//! Touched files are snapshotted first; if execution or verification fails,
+//! the snapshots are restored and the proposal stays PENDING.
+
+use openspore_brain::Brain;
+use openspore_memory::MemorySystem;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Paths (relative to the app root) that trivial proposals may modify
+const ALLOWED_PREFIXES: [&str; 5] = [
+    ".watchmanignore",
+    "workspace/knowledge/",
+    "workspace/context/",
+    "workspace/autonomy/",
+    "skills/",
+];
+
+/// Paths that can never be auto-applied, whatever the reviewer says
+const HARD_EXCLUDED: [&str; 8] = [
+    "workspace/identity/",
+    ".env",
+    "openspore.",
+    "crates/",
+    "Cargo.toml",
+    "install.sh",
+    "workspace/context/LOGS.md",
+    "workspace/context/session_summary.md",
+];
+
+/// Plan keywords that imply shell execution
+const EXEC_MARKERS: [&str; 6] = ["[exec", "exec:", "shell", "terminal", "run the command", "install"];
+
+pub enum AutoApplyOutcome {
+    Applied { diff: String },
+    RolledBack { reason: String },
+}
+
+pub struct AutoApplier;
+
+impl AutoApplier {
+    /// Gating matrix: returns Err(reason) when the proposal must stay a normal pending one
+    pub fn check_eligibility(enabled: bool, risk: &str, files: &[String], plan: &str) -> Result<(), String> {
+        if !enabled {
+            return Err("auto-apply disabled".to_string());
+        }
+        if !risk.eq_ignore_ascii_case("trivial") {
+            return Err(format!("risk is '{}', not trivial", risk));
+        }
+        if files.is_empty() {
+            return Err("plan does not declare the files it touches".to_string());
+        }
+
+        for file in files {
+            let rel = normalize(file);
+            if rel.contains("..") {
+                return Err(format!("path escapes the workspace: {}", file));
+            }
+            if HARD_EXCLUDED.iter().any(|ex| rel.starts_with(ex)) {
+                return Err(format!("hard-excluded path: {}", file));
+            }
+            if !ALLOWED_PREFIXES.iter().any(|p| rel.starts_with(p)) {
+                return Err(format!("path not allowlisted: {}", file));
+            }
+        }
+
+        let lower_plan = plan.to_lowercase();
+        if let Some(marker) = EXEC_MARKERS.iter().find(|m| lower_plan.contains(*m)) {
+            return Err(format!("plan requires execution ('{}')", marker));
+        }
+
+        Ok(())
+    }
+
+    /// Snapshot, execute through the brain, verify, and roll back on failure
+    pub async fn apply(brain: &Brain, memory: &MemorySystem, proposal_id: &str, plan: &str, files: &[String]) -> AutoApplyOutcome {
+        let root = memory.project_root.clone();
+        let targets: Vec<PathBuf> = files.iter().map(|f| root.join(normalize(f))).collect();
+
+        // 1. Undo snapshots (None = file did not exist)
+        let snapshots: Vec<(PathBuf, Option<String>)> = targets.iter()
+            .map(|p| (p.clone(), std::fs::read_to_string(p).ok()))
+            .collect();
+
+        let snapshot_dir = root.join("workspace/autonomy/snapshots").join(proposal_id);
+        if std::fs::create_dir_all(&snapshot_dir).is_ok() {
+            for (path, content) in &snapshots {
+                if let (Some(c), Some(name)) = (content, path.file_name()) {
+                    let _ = std::fs::write(snapshot_dir.join(name), c);
+                }
+            }
+        }
+
+        // 2. Execute with a constrained role
+        let file_list = targets.iter().map(|p| format!("- {}", p.display())).collect::<Vec<_>>().join("\n");
+        let exec_prompt = format!(r#"You are the 'Executor Spore'. Apply this small, pre-approved change.
+You may ONLY modify these files:
+{file_list}
+
+Do NOT use EXEC, DELEGATE, or touch any other file. Read each file before editing it.
+
+<ACTION_PLAN>
+{plan}
+</ACTION_PLAN>
+
+When finished, reply with a one-line summary."#);
+
+        info!("🤖 Auto-apply: executing trivial proposal {}", proposal_id);
+        let result = brain.think(&exec_prompt).await;
+
+        // 3. Verify
+        let mut diff = String::new();
+        let mut failure = None;
+
+        if result.starts_with("Errors:") {
+            failure = Some(format!("executor failed: {}", result));
+        }
+
+        for (path, before) in &snapshots {
+            let after = std::fs::read_to_string(path).ok();
+            match (before, &after) {
+                (Some(b), Some(a)) if b != a => {
+                    if a.trim().is_empty() {
+                        failure.get_or_insert_with(|| format!("{} was emptied", path.display()));
+                    }
+                    diff.push_str(&format!("--- {}\n{}\n", path.display(), diffy::create_patch(b, a)));
+                }
+                (None, Some(a)) => {
+                    diff.push_str(&format!("--- {} (new)\n{}\n", path.display(), diffy::create_patch("", a)));
+                }
+                (Some(_), None) => {
+                    failure.get_or_insert_with(|| format!("{} was deleted", path.display()));
+                }
+                _ => {}
+            }
+        }
+
+        if failure.is_none() && diff.is_empty() {
+            failure = Some("no changes were made".to_string());
+        }
+
+        match failure {
+            None => AutoApplyOutcome::Applied { diff },
+            Some(reason) => {
+                warn!("↩️ Auto-apply failed ({}). Rolling back.", reason);
+                Self::rollback(&snapshots);
+                AutoApplyOutcome::RolledBack { reason }
+            }
+        }
+    }
+
+    fn rollback(snapshots: &[(PathBuf, Option<String>)]) {
+        for (path, content) in snapshots {
+            match content {
+                Some(c) => { let _ = std::fs::write(path, c); }
+                None => { let _ = std::fs::remove_file(path); }
+            }
+        }
+    }
+
+    /// Rewrite the `status:` line of a proposal and append a section
+    pub fn update_proposal(path: &Path, status: &str, section: &str) {
+        if let Err(e) = crate::proposals::set_status(path, status, Some(section)) {
+            warn!("Could not update proposal {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn normalize(path: &str) -> String {
+    let root = openspore_core::path_utils::get_app_root();
+    let p = path.trim();
+    let rel = Path::new(p).strip_prefix(&root).map(|r| r.to_string_lossy().to_string()).unwrap_or_else(|_| p.to_string());
+    rel.trim_start_matches("./").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_brain::test_support::{brain_in, fake_llm};
+
+    const NOTE: &str = "workspace/knowledge/rust.md";
+
+    fn eligible(enabled: bool, risk: &str, files: &[&str], plan: &str) -> Result<(), String> {
+        let files: Vec<String> = files.iter().map(|f| f.to_string()).collect();
+        AutoApplier::check_eligibility(enabled, risk, &files, plan)
+    }
+
+    #[test]
+    fn gating_matrix() {
+        let plan = "Fix the typo in the heading";
+        assert!(eligible(true, "trivial", &[NOTE], plan).is_ok());
+        assert!(eligible(true, "Trivial", &["./workspace/context/notes.md", ".watchmanignore"], plan).is_ok());
+
+        let refusals = [
+            (eligible(false, "trivial", &[NOTE], plan), "disabled"),
+            (eligible(true, "low", &[NOTE], plan), "not trivial"),
+            (eligible(true, "trivial", &[], plan), "does not declare"),
+            (eligible(true, "trivial", &["workspace/knowledge/../identity/soul.md"], plan), "escapes"),
+            (eligible(true, "trivial", &["workspace/identity/soul.md"], plan), "hard-excluded"),
+            (eligible(true, "trivial", &["workspace/context/LOGS.md"], plan), "hard-excluded"),
+            (eligible(true, "trivial", &[".env"], plan), "hard-excluded"),
+            (eligible(true, "trivial", &["README.md"], plan), "not allowlisted"),
+            (eligible(true, "trivial", &[NOTE, "src/main.rs"], plan), "not allowlisted"),
+            (eligible(true, "trivial", &[NOTE], "Run the command cargo fmt"), "requires execution"),
+            (eligible(true, "trivial", &[NOTE], "[EXEC: rm -rf /]"), "requires execution"),
+        ];
+        for (result, reason) in refusals {
+            let error = result.expect_err(reason);
+            assert!(error.contains(reason), "{} (expected {})", error, reason);
+        }
+    }
+
+    /// An executor that reads the note, then writes `content` to it
+    async fn executor_writing(root: &Path, content: &'static str) -> Brain {
+        let note = root.join(NOTE);
+        let (url, _) = fake_llm(move |request| {
+            let rounds = request["messages"].as_array().into_iter().flatten().filter(|m| m["role"] == "assistant").count();
+            match rounds {
+                0 => format!("[READ_FILE: \"{}\"]", note.display()),
+                1 => format!("[WRITE_FILE: \"{}\" <<<EOF\n{}\nEOF]", note.display(), content),
+                _ => "Heading fixed.".to_string(),
+            }
+        }).await;
+        brain_in(root, &url).await
+    }
+
+    #[tokio::test]
+    async fn applied_change_comes_with_its_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = executor_writing(dir.path(), "# Rust notes").await;
+        std::fs::write(dir.path().join(NOTE), "# Rust ntoes\n").unwrap();
+
+        let outcome = AutoApplier::apply(&brain, &brain.memory, "p1", "Fix the typo", &[NOTE.to_string()]).await;
+
+        let AutoApplyOutcome::Applied { diff } = outcome else { panic!("rolled back") };
+        assert!(diff.contains("-# Rust ntoes") && diff.contains("+# Rust notes"), "{}", diff);
+        assert_eq!(std::fs::read_to_string(dir.path().join(NOTE)).unwrap(), "# Rust notes");
+        assert!(dir.path().join("workspace/autonomy/snapshots/p1/rust.md").exists());
+    }
+
+    #[tokio::test]
+    async fn emptied_file_is_rolled_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = executor_writing(dir.path(), "").await;
+        std::fs::write(dir.path().join(NOTE), "# Rust ntoes\n").unwrap();
+
+        let outcome = AutoApplier::apply(&brain, &brain.memory, "p2", "Fix the typo", &[NOTE.to_string()]).await;
+
+        let AutoApplyOutcome::RolledBack { reason } = outcome else { panic!("applied") };
+        assert!(reason.contains("was emptied"), "{}", reason);
+        assert_eq!(std::fs::read_to_string(dir.path().join(NOTE)).unwrap(), "# Rust ntoes\n");
+    }
+
+    #[test]
+    fn rollback_restores_edits_and_removes_new_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (edited, created) = (dir.path().join("edited.md"), dir.path().join("created.md"));
+        let snapshots = vec![(edited.clone(), Some("before".to_string())), (created.clone(), None)];
+        std::fs::write(&edited, "after").unwrap();
+        std::fs::write(&created, "new").unwrap();
+
+        AutoApplier::rollback(&snapshots);
+
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "before");
+        assert!(!created.exists());
+    }
+}
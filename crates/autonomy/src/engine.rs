@@ -5,6 +5,11 @@ use std::path::PathBuf;
 use tracing::{info, error, warn};
 use anyhow::Result;
 use chrono;
+use crate::auto_apply::{AutoApplier, AutoApplyOutcome};
+use openspore_core::event_bus::{self, SystemEvent};
+
+/// Post-mortem summaries added to the logs of one run at most
+const MAX_FAILURE_SUMMARIES: usize = 5;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Idea {
@@ -48,6 +53,33 @@ impl Default for AutonomyState {
 pub struct AutonomyEngine;
 
 impl AutonomyEngine {
+    /// Run the engine and announce a new proposal on the event bus. Returns
+    /// the report line ("✨ NEW PROPOSAL: ...") when one was written.
+    pub async fn run_and_publish(brain: &Brain, memory: &MemorySystem) -> Result<Option<String>> {
+        let Some(proposal_path) = Self::run(brain, memory).await? else { return Ok(None) };
+        let filename = proposal_path.file_name().and_then(|f| f.to_str()).unwrap_or("proposal.md");
+
+        // Parse title and status from file
+        let content = std::fs::read_to_string(&proposal_path).unwrap_or_default();
+        let title = content.lines()
+            .find(|line| line.starts_with("title: "))
+            .map(|line| line.trim_start_matches("title: ").trim().to_string())
+            .unwrap_or_else(|| filename.to_string());
+        let auto_applied = content.lines().any(|line| line.trim() == "status: AUTO_APPLIED");
+        event_bus::publish(SystemEvent::ProposalCreated {
+            title: title.clone(),
+            path: proposal_path.clone(),
+            auto_applied,
+        });
+
+        Ok(Some(if auto_applied {
+            format!("🤖 AUTO-APPLIED: {} (see {})", title, filename)
+        } else {
+            let id = proposal_path.file_stem().and_then(|s| s.to_str()).unwrap_or("proposal");
+            format!("✨ NEW PROPOSAL: {} (awaiting approval: openspore proposal approve {})", title, id)
+        }))
+    }
+
     pub async fn run(brain: &Brain, memory: &MemorySystem) -> Result<Option<PathBuf>> {
         info!("💡 Autonomy: Scanning for improvement opportunities (Chain of Thought mode)...");
 
@@ -72,12 +104,27 @@ impl AutonomyEngine {
 
         info!("📅 Fetching logs since: {}", cutoff.format("%Y-%m-%d %H:%M:%S"));
 
-        let recent_logs = memory.get_logs_since(cutoff).unwrap_or_else(|e| {
+        let mut recent_logs = memory.get_logs_since(cutoff).unwrap_or_else(|e| {
             warn!("Failed to retrieve recent logs: {}. Falling back to recent memories.", e);
             memory.get_memories("context").iter().rev().take(5)
                 .map(|m| m.content.clone()).collect::<Vec<_>>().join("\n\n")
         });
 
+        // Post-mortems: new failures, and problems that keep coming back across the kept bundles
+        let failures = memory.failures_since(cutoff);
+        if !failures.is_empty() {
+            let patterns = openspore_memory::failure_patterns(&memory.list_failures());
+            let mut section = String::from("\n\n<RECENT_FAILURES>\n");
+            for bundle in failures.iter().take(MAX_FAILURE_SUMMARIES) {
+                section.push_str(&format!("- {} (post-mortem: {})\n", bundle.record.summary(), bundle.path.display()));
+            }
+            for (signature, count) in patterns {
+                section.push_str(&format!("- Recurring ({}x): {}\n", count, signature));
+            }
+            section.push_str("</RECENT_FAILURES>");
+            recent_logs.push_str(&section);
+        }
+
         if recent_logs.trim().is_empty() {
             info!("💤 No new logs since last scan. Skipping autonomy cycle.");
             return Ok(None);
@@ -94,6 +141,8 @@ Path A: Core Maintenance/System Improvement (Internal refactors, fixes, performa
 Path B: Contextual Value/User Support (Researching topics mentioned in logs, proactive lookups, automating tasks).
 Path C: Strategic Exploration (Experimental features, new capabilities, scouting trends).
 
+<RECENT_FAILURES>, when present, lists cycles that ended in error with their post-mortem bundles; a recurring failure is strong evidence for Path A.
+
 Directive: Avoid over-engineering. If a problem can be fixed with 10 lines of code, do not propose a 4-task research-and-audit project.
 SYSTEM DIRECTIVE: You are in a reasoning phase. DO NOT USE ANY TOOLS. DO NOT add any conversational text. Respond ONLY with the JSON object below.
 
@@ -136,7 +185,7 @@ Output JSON ONLY:
             }
         };
 
-        if brainstorm_json["paths"].as_array().map_or(true, |a| a.is_empty()) {
+        if brainstorm_json["paths"].as_array().is_none_or(|a| a.is_empty()) {
             error!("CoT Error [Phase 1]: No reasoning paths generated.");
             return Ok(None);
         }
@@ -217,10 +266,15 @@ You are the 'Reviewer Spore'. Audit this proposed Action Plan.
 Plan Title: {title}
 Implementation: {plan}
 
+Rate the risk: "trivial" (tiny, reversible text edit such as fixing a typo or adding an ignore entry), "normal", or "high".
+List every file (relative to the engine root) the plan would create or modify.
+
 Output JSON ONLY:
 {{
     "status": "GREEN_LIGHT" | "REJECT",
-    "reason": "..."
+    "reason": "...",
+    "risk": "trivial" | "normal" | "high",
+    "files": ["relative/path.md"]
 }}
 "#,
             title = idea.title,
@@ -251,9 +305,32 @@ Output JSON ONLY:
         }
 
         // 4. Create Proposal
-        let proposal_path = Self::create_proposal(&idea, memory)?;
+        let risk = review_json["risk"].as_str().unwrap_or("normal").to_lowercase();
+        let files: Vec<String> = review_json["files"].as_array()
+            .map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+
+        let proposal_path = Self::create_proposal(&idea, &risk, memory)?;
         info!("✨ NEW PROPOSAL GENERATED (via CoT): {}", proposal_path.display());
 
+        // 5. Auto-apply tier (trivial + allowlisted only)
+        let plan = idea.format_plan();
+        match AutoApplier::check_eligibility(brain.config.autonomy_auto_apply, &risk, &files, &plan) {
+            Ok(()) => {
+                let proposal_id = proposal_path.file_stem().and_then(|s| s.to_str()).unwrap_or("proposal").to_string();
+                match AutoApplier::apply(brain, memory, &proposal_id, &plan, &files).await {
+                    AutoApplyOutcome::Applied { diff } => {
+                        info!("✅ Auto-applied trivial proposal {}", proposal_id);
+                        AutoApplier::update_proposal(&proposal_path, "AUTO_APPLIED", &format!("## Auto-Apply Result\nApplied at {}\n\n```diff\n{}```", chrono::Local::now().to_rfc3339(), diff));
+                    }
+                    AutoApplyOutcome::RolledBack { reason } => {
+                        AutoApplier::update_proposal(&proposal_path, "PENDING", &format!("## Auto-Apply Attempt\nRolled back: {}\nThis proposal now needs manual approval.", reason));
+                    }
+                }
+            }
+            Err(reason) => info!("⏸️ Proposal kept for review ({})", reason),
+        }
+
         Ok(Some(proposal_path))
     }
 
@@ -261,15 +338,15 @@ Output JSON ONLY:
         let first_brace = raw.find('{');
         let last_brace = raw.rfind('}');
 
-        if let (Some(start), Some(end)) = (first_brace, last_brace) {
-            if end > start {
-                return &raw[start..=end];
-            }
+        if let (Some(start), Some(end)) = (first_brace, last_brace)
+            && end > start
+        {
+            return &raw[start..=end];
         }
         "{}" // Return empty object if no braces found to prevent parsing non-JSON text
     }
 
-    fn create_proposal(idea: &Idea, memory: &MemorySystem) -> Result<PathBuf> {
+    fn create_proposal(idea: &Idea, risk: &str, memory: &MemorySystem) -> Result<PathBuf> {
         let id = format!("proposal_{}", chrono::Local::now().format("%Y%m%d_%H%M%S"));
         let filename = format!("{}.md", id);
         let proposals_dir = memory.project_root.join("workspace/autonomy/proposals");
@@ -283,6 +360,7 @@ id: {id}
 title: {title}
 type: {idea_type}
 status: PENDING
+risk: {risk}
 created: {created}
 ---
 
@@ -297,6 +375,7 @@ created: {created}
             id = id,
             title = idea.title,
             idea_type = idea.idea_type,
+            risk = risk,
             created = chrono::Local::now().to_rfc3339(),
             description = idea.description,
             plan = idea.format_plan()
@@ -2,6 +2,7 @@ use openspore_brain::Brain;
 use openspore_memory::MemorySystem;
 use openspore_telegram::TelegramChannel;
 use crate::engine::AutonomyEngine;
+use openspore_core::event_bus::{self, SystemEvent};
 use chrono::Timelike;
 use tracing::{info, error};
 use anyhow::Result;
@@ -10,7 +11,8 @@ use sysinfo::Disks;
 pub struct Heartbeat;
 
 impl Heartbeat {
-    pub async fn run(brain: &Brain, memory: &MemorySystem, telegram: Option<&TelegramChannel>) -> Result<()> {
+    /// `with_engine`: also run the autonomy engine and report its proposal
+    pub async fn run(brain: &Brain, memory: &MemorySystem, telegram: Option<&TelegramChannel>, with_engine: bool) -> Result<()> {
         info!("--- OpenSpore Heartbeat ---");
         if telegram.is_none() {
             info!("📡 Heartbeat: Telegram is not configured, running as local-only report.");
@@ -39,9 +41,16 @@ impl Heartbeat {
             logs.push("📝 Journal: Pending (22:00)".to_string());
         }
 
-        // 3. Engine Integrity
-        let mut doctor = openspore_doctor::SporeDoctor::new();
-        let doctor_ok = doctor.check_all();
+        // 3. Watchman (per watched directory, when it ran in any process)
+        if let Some(stats) = openspore_core::watch::WatchStats::load(&memory.project_root) {
+            logs.push(stats.summary());
+        }
+
+        // 4. Engine Integrity
+        let doctor_ok = openspore_doctor::SporeDoctor::new()
+            .run(openspore_doctor::DoctorMode::Fix)
+            .issues
+            .is_empty();
 
         if doctor_ok {
             logs.push("📁 Engine: Healthy (Doctor Verified)".to_string());
@@ -50,25 +59,10 @@ impl Heartbeat {
             status = "🟡 CAUTION";
         }
 
-        // 4. Trigger Autonomy Engine
-        if let Ok(enabled) = std::env::var("AUTONOMY_ENABLED") {
-            if enabled == "true" {
-                match AutonomyEngine::run(brain, memory).await {
-                    Ok(Some(proposal_path)) => {
-                        let filename = proposal_path.file_name().and_then(|f| f.to_str()).unwrap_or("proposal.md");
-
-                        // Parse title from file
-                        let title = if let Ok(content) = std::fs::read_to_string(&proposal_path) {
-                            content.lines()
-                                .find(|line| line.starts_with("title: "))
-                                .map(|line| line.trim_start_matches("title: ").trim().to_string())
-                                .unwrap_or_else(|| filename.to_string())
-                        } else {
-                            filename.to_string()
-                        };
-
-                        logs.push(format!("✨ NEW PROPOSAL: {}", title));
-                    },
+        // 5. Trigger Autonomy Engine (the scheduler runs it on its own interval instead)
+        if with_engine && brain.config.autonomy_enabled {
+                match AutonomyEngine::run_and_publish(brain, memory).await {
+                    Ok(Some(line)) => logs.push(line),
                     Ok(None) => {},
                     Err(e) => {
                         error!("Autonomy Error: {}", e);
@@ -76,9 +70,8 @@ impl Heartbeat {
                     }
                 }
             }
-        }
 
-        // 5. Send Combined Report
+        // 6. Send Combined Report
         let time = chrono::Local::now().format("%H:%M:%S").to_string();
         let report = format!(
             "💓 *OpenSpore Heartbeat*\n\nStatus: *{}*\n\n{}\n\n_{}_",
@@ -88,6 +81,10 @@ impl Heartbeat {
         );
 
         info!("--- Heartbeat Result ---\n{}", report);
+        event_bus::publish(SystemEvent::HeartbeatCompleted {
+            status: status.to_string(),
+            report: report.clone(),
+        });
 
          if let Some(tg) = telegram {
              info!("📡 Heartbeat: Attempting to send report via Telegram...");
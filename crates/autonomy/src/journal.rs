@@ -29,6 +29,10 @@ impl DailyJournal {
 
         // 2. Synthesize
         let synthesis = Self::synthesize_text(brain, &aggregated_context, &today_str).await;
+        if let Some(e) = openspore_brain::failed_reply_error(&synthesis) {
+            // Nothing is written or archived, so a retry sees the same context
+            return Err(anyhow::anyhow!("Synthesis failed: {}", e));
+        }
 
         if !journal_dir.exists() {
             fs::create_dir_all(&journal_dir)?;
@@ -38,12 +42,17 @@ impl DailyJournal {
             // Append mode
             let timestamp = chrono::Local::now().format("%H:%M:%S");
             let append_content = format!("\n\n---\n\n## 🔄 Update [{}]\n\n{}", timestamp, synthesis.trim());
+            // Sealed updates go on their own line after the existing content
+            let append_content = if memory.encrypt_memory {
+                format!("\n{}", memory.seal_if_enabled(&append_content)?)
+            } else {
+                append_content
+            };
 
             // Read existing to append (or just use append open option)
             // Using fs::OpenOptions for atomic append is cleaner
             use std::io::Write;
             let mut file = fs::OpenOptions::new()
-                .write(true)
                 .append(true)
                 .open(&journal_path)?;
 
@@ -51,7 +60,7 @@ impl DailyJournal {
             info!("✅ Journal updated (appended) at {}", journal_path.display());
         } else {
             // Create mode
-            fs::write(&journal_path, synthesis.trim())?;
+            fs::write(&journal_path, memory.seal_if_enabled(synthesis.trim())?)?;
             info!("✅ Journal created at {}", journal_path.display());
         }
 
@@ -63,7 +72,7 @@ impl DailyJournal {
     }
 
     fn collect_context(memory: &MemorySystem) -> Result<(String, Vec<PathBuf>)> {
-        let context_dir = memory.project_root.join("workspace/context");
+        let context_dir = memory.context_dir();
         if !context_dir.exists() {
             std::fs::create_dir_all(&context_dir)?;
         }
@@ -82,7 +91,7 @@ impl DailyJournal {
             interactions.sort();
 
             for path in &interactions {
-                if let Ok(content) = fs::read_to_string(path) {
+                if let Ok(content) = memory.read_text(path) {
                     aggregated_context.push_str(&format!("\n\n---\n\n{}", content));
                 }
             }
@@ -90,10 +99,10 @@ impl DailyJournal {
 
         // Include LOGS.md if it exists
         let log_path = context_dir.join("LOGS.md");
-        if log_path.exists() {
-            if let Ok(raw_log) = fs::read_to_string(&log_path) {
-                aggregated_context.push_str(&format!("\n\n### RAW_ACTIVE_CONTEXT\n{}", raw_log));
-            }
+        if log_path.exists()
+            && let Ok(raw_log) = memory.read_text(&log_path)
+        {
+            aggregated_context.push_str(&format!("\n\n### RAW_ACTIVE_CONTEXT\n{}", raw_log));
         }
 
         Ok((aggregated_context, interactions))
@@ -148,19 +157,19 @@ Rules:
     }
 
     fn clear_active_logs(memory: &MemorySystem) -> Result<()> {
-        let log_path = memory.project_root.join("workspace/context/LOGS.md");
-        if log_path.exists() {
-            if let Ok(content) = fs::read_to_string(&log_path) {
-                let lines: Vec<&str> = content.lines().collect();
-                if lines.len() > 50 {
-                    let tail = lines[lines.len()-50..].join("\n");
-                    fs::write(&log_path, format!("... [Rotated] ...\n{}", tail))?;
-                    info!("⏳ LOGS.md rotated (preserved last 50 lines).");
-                } else {
-                    // Less than 50 lines, just leave it or reset if desired.
-                    // Here we'll just leave it to keep the context.
-                    info!("📝 LOGS.md is short, skipping rotation.");
-                }
+        let log_path = memory.context_dir().join("LOGS.md");
+        if log_path.exists()
+            && let Ok(content) = fs::read_to_string(&log_path)
+        {
+            let lines: Vec<&str> = content.lines().collect();
+            if lines.len() > 50 {
+                let tail = lines[lines.len()-50..].join("\n");
+                fs::write(&log_path, format!("... [Rotated] ...\n{}", tail))?;
+                info!("⏳ LOGS.md rotated (preserved last 50 lines).");
+            } else {
+                // Less than 50 lines, just leave it or reset if desired.
+                // Here we'll just leave it to keep the context.
+                info!("📝 LOGS.md is short, skipping rotation.");
             }
         }
         Ok(())
@@ -2,8 +2,14 @@ pub mod heartbeat;
 pub mod engine;
 pub mod journal;
 pub mod scheduler;
+pub mod auto_apply;
+pub mod proposals;
+pub mod pipelines;
+pub mod retry;
 
 pub use heartbeat::Heartbeat;
 pub use engine::AutonomyEngine;
 pub use journal::DailyJournal;
+pub use proposals::ProposalStore;
+pub use pipelines::PipelineStore;
 pub use scheduler::SporeScheduler;
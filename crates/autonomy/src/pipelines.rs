@@ -0,0 +1,694 @@
+//! Pipelines: named routines of skill calls and think steps
+//!
+//! workspace/pipelines/<name>.yaml lists steps that run in order. A step
+//! either calls a skill with fixed, templated arguments (`skill` + `args`, a
+//! string or a mapping sent as JSON) or runs a think cycle on a prompt
+//! (`think`). `{{vars.<name>}}` (declared under `vars`, overridable per
+//! run), `{{steps.<n>.output}}` or `{{steps.<name>.output}}` of an earlier
+//! step, `{{date}}`, `{{time}}`, `{{workspace}}` and `{{pipeline}}` are
+//! filled in before a step runs; any other placeholder is an error when the
+//! file is loaded. Skill steps go through the gates of the tool loop
+//! (ALLOWED_SKILLS/DENIED_SKILLS, approval, audit), think steps are cycles of
+//! the "pipeline" channel. When a step fails, its `on_error` (or the
+//! pipeline's) decides: abort the run, continue with the next step (steps
+//! that use the failed step's output are skipped), or ask through the
+//! approval gate, which unattended runs answer with no. The report of each
+//! run goes to the journal. A `schedule` (cron expression) runs the pipeline
+//! from the scheduler.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Local};
+use openspore_brain::events::BrainEvent;
+use openspore_brain::Brain;
+use openspore_core::fmt_utils::preview;
+use openspore_core::schedule::{parse_cron, Trigger};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Channel of think steps in the audit log
+const PIPELINE_CHANNEL: &str = "pipeline";
+
+/// Skill steps may not call: a pipeline would start pipelines
+const RUN_PIPELINE_SKILL: &str = "run_pipeline";
+
+/// Placeholders filled in for every step
+const BUILTIN_FIELDS: [&str; 4] = ["date", "time", "workspace", "pipeline"];
+
+/// What happens when a step fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorPolicy {
+    /// Stop the run (the default)
+    #[default]
+    Abort,
+    /// Go on with the next step
+    Continue,
+    /// Ask through the approval gate whether to go on
+    Ask,
+}
+
+impl ErrorPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorPolicy::Abort => "abort",
+            ErrorPolicy::Continue => "continue",
+            ErrorPolicy::Ask => "ask",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub skill: Option<String>,
+    /// A string, or a mapping sent to the skill as JSON
+    #[serde(default)]
+    pub args: Value,
+    #[serde(default)]
+    pub think: Option<String>,
+    #[serde(default)]
+    pub on_error: Option<ErrorPolicy>,
+}
+
+impl Step {
+    /// "notes" or "step 2" (`index` counts from 0)
+    pub fn label(&self, index: usize) -> String {
+        self.name.clone().unwrap_or_else(|| format!("step {}", index + 1))
+    }
+
+    /// The skill it calls, or "think"
+    pub fn kind(&self) -> &str {
+        self.skill.as_deref().unwrap_or("think")
+    }
+}
+
+/// Layout of a pipeline file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PipelineFile {
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    schedule: Option<String>,
+    #[serde(default)]
+    on_error: ErrorPolicy,
+    #[serde(default)]
+    vars: BTreeMap<String, Value>,
+    #[serde(default)]
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    /// File name without the extension
+    pub name: String,
+    pub path: PathBuf,
+    pub description: String,
+    /// Cron expression of scheduled runs
+    pub schedule: Option<String>,
+    pub on_error: ErrorPolicy,
+    /// Default values of the variables
+    pub vars: BTreeMap<String, String>,
+    pub steps: Vec<Step>,
+}
+
+fn placeholder_pattern() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{\{\s*([\w.\-]+)\s*\}\}").expect("valid regex"))
+}
+
+/// Text of a YAML scalar as a template sees it
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Every string of `value`, nested ones included
+fn strings_of(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().flat_map(strings_of).collect(),
+        Value::Object(map) => map.values().flat_map(strings_of).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `text` with its placeholders replaced by `values`; Err with the first one
+/// that has no value
+pub fn render(text: &str, values: &HashMap<String, String>) -> Result<String, String> {
+    let mut missing = None;
+    let rendered = placeholder_pattern().replace_all(text, |c: &regex::Captures| {
+        match values.get(&c[1]) {
+            Some(value) => value.clone(),
+            None => {
+                missing.get_or_insert_with(|| c[1].to_string());
+                String::new()
+            }
+        }
+    });
+    match missing {
+        Some(key) => Err(key),
+        None => Ok(rendered.into_owned()),
+    }
+}
+
+/// `args` rendered for a skill: a string as is, a mapping (strings rendered one by one) as JSON
+pub fn render_args(args: &Value, values: &HashMap<String, String>) -> Result<String, String> {
+    fn render_value(value: &Value, values: &HashMap<String, String>) -> Result<Value, String> {
+        Ok(match value {
+            Value::String(s) => Value::String(render(s, values)?),
+            Value::Array(items) => Value::Array(items.iter().map(|v| render_value(v, values)).collect::<Result<_, _>>()?),
+            Value::Object(map) => Value::Object(map.iter().map(|(k, v)| Ok((k.clone(), render_value(v, values)?))).collect::<Result<_, String>>()?),
+            other => other.clone(),
+        })
+    }
+    match args {
+        Value::String(s) => render(s, values),
+        Value::Null => Ok(String::new()),
+        other => Ok(render_value(other, values)?.to_string()),
+    }
+}
+
+impl Pipeline {
+    /// Parse and check a pipeline file
+    pub fn parse(name: &str, path: &Path, text: &str) -> Result<Self> {
+        let file: PipelineFile = serde_yaml::from_str(text).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        let pipeline = Self {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            description: file.description.trim().to_string(),
+            schedule: file.schedule.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            on_error: file.on_error,
+            vars: file.vars.iter().map(|(k, v)| (k.clone(), scalar_text(v))).collect(),
+            steps: file.steps,
+        };
+        pipeline.validate().map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        Ok(pipeline)
+    }
+
+    /// Steps well formed, placeholders known when their step runs, schedule parseable
+    fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            bail!("no steps");
+        }
+        if let Some(schedule) = &self.schedule {
+            parse_cron(schedule).map_err(|e| anyhow!("schedule \"{}\" is not a valid cron expression: {}", schedule, e))?;
+        }
+        let mut earlier: Vec<String> = Vec::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            let label = step.label(i);
+            match (&step.skill, &step.think) {
+                (Some(_), Some(_)) => bail!("{} has both skill and think; a step does one", label),
+                (None, None) => bail!("{} needs a skill or a think prompt", label),
+                (None, Some(_)) if !step.args.is_null() => bail!("{} has args but no skill", label),
+                (Some(skill), _) if skill.eq_ignore_ascii_case(RUN_PIPELINE_SKILL) => bail!("{}: a pipeline cannot run pipelines", label),
+                _ => {}
+            }
+            if let Some(name) = &step.name {
+                if name.is_empty() || name.chars().all(|c| c.is_ascii_digit()) || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                    bail!("step name \"{}\": use letters, digits, - and _ (not only digits)", name);
+                }
+                if self.steps[..i].iter().any(|s| s.name.as_deref() == Some(name)) {
+                    bail!("two steps are named \"{}\"", name);
+                }
+            }
+
+            let texts: Vec<&str> = match &step.think {
+                Some(prompt) => vec![prompt.as_str()],
+                None => strings_of(&step.args),
+            };
+            for text in texts {
+                for c in placeholder_pattern().captures_iter(text) {
+                    let key = &c[1];
+                    let known = BUILTIN_FIELDS.contains(&key)
+                        || key.strip_prefix("vars.").is_some_and(|var| self.vars.contains_key(var))
+                        || key.strip_prefix("steps.").and_then(|r| r.strip_suffix(".output")).is_some_and(|r| earlier.iter().any(|e| e == r));
+                    if !known {
+                        bail!("{} uses {{{{{}}}}}, which is not a declared variable, an earlier step's output or one of {}", label, key, BUILTIN_FIELDS.join(", "));
+                    }
+                }
+            }
+            earlier.push((i + 1).to_string());
+            earlier.extend(step.name.clone());
+        }
+        Ok(())
+    }
+
+    /// When the scheduler runs it, if it has a schedule
+    pub fn trigger(&self) -> Option<Trigger> {
+        let expression = self.schedule.clone()?;
+        let cron = parse_cron(&expression).ok()?;
+        Some(Trigger::Cron { expression, cron: Box::new(cron) })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Ok,
+    Failed,
+    /// It needed the output of a step that failed
+    Skipped,
+    /// The run was aborted before it
+    NotRun,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepReport {
+    /// From 1
+    pub step: usize,
+    pub label: String,
+    pub kind: String,
+    pub status: StepStatus,
+    pub output: String,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Completed,
+    /// Every step ran, some failed or were skipped
+    CompletedWithErrors,
+    Aborted,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Completed => "completed",
+            RunStatus::CompletedWithErrors => "completed with errors",
+            RunStatus::Aborted => "aborted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineReport {
+    pub pipeline: String,
+    /// Cycle id of the skill calls in the audit log
+    pub run: String,
+    pub started: DateTime<Local>,
+    pub status: RunStatus,
+    pub vars: BTreeMap<String, String>,
+    pub steps: Vec<StepReport>,
+}
+
+impl PipelineReport {
+    /// Output of the last step that produced one
+    pub fn final_output(&self) -> Option<&str> {
+        self.steps.iter().rev().find(|s| s.status == StepStatus::Ok).map(|s| s.output.as_str())
+    }
+
+    /// Journal entry: the outcome and one line per step
+    pub fn journal_entry(&self) -> String {
+        let mut entry = format!("\nPIPELINE [{}, run: {}] {}\n", self.pipeline, self.run, self.status.as_str());
+        for step in &self.steps {
+            let outcome = match (&step.status, &step.error) {
+                (StepStatus::Ok, _) => format!("ok in {:.1}s", step.duration_ms as f64 / 1000.0),
+                (StepStatus::NotRun, _) => "not run".to_string(),
+                (status, Some(error)) => format!("{}: {}", if *status == StepStatus::Skipped { "skipped" } else { "failed" }, preview(error, 200)),
+                (_, None) => "failed".to_string(),
+            };
+            entry.push_str(&format!("- {} {} ({}): {}\n", step.step, step.label, step.kind, outcome));
+        }
+        entry
+    }
+}
+
+/// Why a step produced no output
+enum StepFailure {
+    Failed(String),
+    Skipped(String),
+}
+
+/// Error of a skill result: a refusal, or JSON with `"success": false`
+fn skill_error(output: &str) -> Option<String> {
+    let json: Value = serde_json::from_str(output.trim()).ok()?;
+    if json.get("success").and_then(Value::as_bool) != Some(false) {
+        return None;
+    }
+    Some(json.get("error").map(scalar_text).filter(|e| !e.is_empty()).unwrap_or_else(|| preview(output, 300)))
+}
+
+/// Run a pipeline. `overrides` replace declared variables; `observer`
+/// receives the events of the steps and answers their approval requests
+/// (without one, gated calls and `ask` are refused). Err when the run cannot
+/// start; failed steps are in the report, which is also journaled.
+pub async fn run(brain: &Brain, pipeline: &Pipeline, overrides: &BTreeMap<String, String>, observer: Option<mpsc::Sender<BrainEvent>>) -> Result<PipelineReport> {
+    if let Some(unknown) = overrides.keys().find(|k| !pipeline.vars.contains_key(*k)) {
+        bail!(
+            "{} is not a variable of {} (declared: {})",
+            unknown,
+            pipeline.name,
+            if pipeline.vars.is_empty() { "none".to_string() } else { pipeline.vars.keys().cloned().collect::<Vec<_>>().join(", ") }
+        );
+    }
+    if let Some(step) = pipeline.steps.iter().find(|s| s.skill.as_ref().is_some_and(|skill| brain.skills().get(skill).is_none())) {
+        bail!("{} calls the unknown skill {}", pipeline.name, step.kind());
+    }
+
+    let mut vars = pipeline.vars.clone();
+    vars.extend(overrides.clone());
+    let started = Local::now();
+    let run_id = format!("pipeline-{}-{}", pipeline.name, started.format("%Y%m%d%H%M%S%3f"));
+    let mut values: HashMap<String, String> = vars.iter().map(|(k, v)| (format!("vars.{}", k), v.clone())).collect();
+    values.insert("date".to_string(), started.format("%Y-%m-%d").to_string());
+    values.insert("time".to_string(), started.format("%H:%M").to_string());
+    values.insert("workspace".to_string(), brain.memory.memory_root.display().to_string());
+    values.insert("pipeline".to_string(), pipeline.name.clone());
+
+    info!("🧪 Pipeline {}: {} step(s), run {}", pipeline.name, pipeline.steps.len(), run_id);
+    let mut steps = Vec::new();
+    let mut aborted = false;
+    for (i, step) in pipeline.steps.iter().enumerate() {
+        let label = step.label(i);
+        let mut report = StepReport {
+            step: i + 1,
+            label: label.clone(),
+            kind: step.kind().to_string(),
+            status: StepStatus::NotRun,
+            output: String::new(),
+            error: None,
+            duration_ms: 0,
+        };
+        if aborted {
+            steps.push(report);
+            continue;
+        }
+
+        let clock = Instant::now();
+        let outcome = run_step(brain, step, &values, &run_id, observer.clone()).await;
+        report.duration_ms = clock.elapsed().as_millis() as u64;
+        match outcome {
+            Ok(output) => {
+                info!("✅ Pipeline {}: {} done", pipeline.name, label);
+                values.insert(format!("steps.{}.output", i + 1), output.clone());
+                if let Some(name) = &step.name {
+                    values.insert(format!("steps.{}.output", name), output.clone());
+                }
+                report.status = StepStatus::Ok;
+                report.output = output;
+            }
+            Err(StepFailure::Skipped(reason)) => {
+                info!("⏭️ Pipeline {}: {} skipped: {}", pipeline.name, label, reason);
+                report.status = StepStatus::Skipped;
+                report.error = Some(reason);
+            }
+            Err(StepFailure::Failed(error)) => {
+                warn!("❌ Pipeline {}: {} failed: {}", pipeline.name, label, preview(&error, 200));
+                report.status = StepStatus::Failed;
+                report.error = Some(error.clone());
+                aborted = match step.on_error.unwrap_or(pipeline.on_error) {
+                    ErrorPolicy::Continue => false,
+                    ErrorPolicy::Abort => true,
+                    ErrorPolicy::Ask => {
+                        let question = format!("{}: continue after {} ({}) failed: {}", pipeline.name, label, step.kind(), preview(&error, 200));
+                        brain.approvals.check("pipeline", &question, false, true, observer.as_ref()).await.is_err()
+                    }
+                };
+            }
+        }
+        steps.push(report);
+    }
+
+    let failures = steps.iter().any(|s| s.status != StepStatus::Ok);
+    let status = if aborted { RunStatus::Aborted } else if failures { RunStatus::CompletedWithErrors } else { RunStatus::Completed };
+    let report = PipelineReport { pipeline: pipeline.name.clone(), run: run_id, started, status, vars, steps };
+    info!("🧪 Pipeline {} {}", pipeline.name, status.as_str());
+    if let Err(e) = brain.memory.save_journal(&report.journal_entry()).await {
+        warn!("Could not journal the run of pipeline {}: {}", pipeline.name, e);
+    }
+    Ok(report)
+}
+
+async fn run_step(brain: &Brain, step: &Step, values: &HashMap<String, String>, run_id: &str, observer: Option<mpsc::Sender<BrainEvent>>) -> Result<String, StepFailure> {
+    // A placeholder without a value is the output of a step that did not succeed (the rest was checked on load)
+    let missing = |key: String| StepFailure::Skipped(format!("{{{{{}}}}} has no value: that step did not succeed", key));
+    match (&step.skill, &step.think) {
+        (Some(skill), _) => {
+            let arg = render_args(&step.args, values).map_err(missing)?;
+            let output = brain.call_skill(skill, &arg, run_id, observer).await.map_err(StepFailure::Failed)?;
+            match skill_error(&output) {
+                Some(error) => Err(StepFailure::Failed(error)),
+                None => Ok(output),
+            }
+        }
+        (None, Some(prompt)) => {
+            let prompt = render(prompt, values).map_err(missing)?;
+            let thinker = brain.clone_brain().with_channel(PIPELINE_CHANNEL);
+            let (reply, stats) = thinker.think_observed_with_stats(&prompt, observer).await;
+            if let Some(error) = openspore_brain::failed_reply_error(&reply) {
+                return Err(StepFailure::Failed(format!("{} (cycle {})", error, stats.cycle_id)));
+            }
+            if reply.contains(openspore_brain::CYCLE_FAILED_MARKER) {
+                return Err(StepFailure::Failed(format!("cycle {} failed: {}", stats.cycle_id, preview(&reply, 300))));
+            }
+            Ok(reply.trim().to_string())
+        }
+        (None, None) => Err(StepFailure::Failed("no skill or think prompt".to_string())),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PipelineStore {
+    dir: PathBuf,
+}
+
+impl PipelineStore {
+    /// Pipelines of `workspace`/pipelines
+    pub fn new(workspace: &Path) -> Self {
+        Self { dir: workspace.join(openspore_memory::PIPELINES_DIR) }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Every pipeline file by name, with its parse error if it has one
+    pub fn list(&self) -> Vec<(String, Result<Pipeline>)> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else { return Vec::new() };
+        let mut pipelines: Vec<(String, Result<Pipeline>)> = entries.flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "yaml" || e == "yml"))
+            .filter_map(|p| {
+                let name = p.file_stem()?.to_str()?.to_string();
+                let pipeline = std::fs::read_to_string(&p)
+                    .with_context(|| format!("Cannot read {}", p.display()))
+                    .and_then(|text| Pipeline::parse(&name, &p, &text));
+                Some((name, pipeline))
+            })
+            .collect();
+        pipelines.sort_by(|a, b| a.0.cmp(&b.0));
+        pipelines
+    }
+
+    /// The pipeline `name` (the file name without .yaml)
+    pub fn find(&self, name: &str) -> Result<Pipeline> {
+        let name = name.trim().trim_end_matches(".yaml").trim_end_matches(".yml");
+        if name.is_empty() {
+            bail!("Which pipeline? `openspore pipeline list` shows them");
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            bail!("\"{}\" is not a pipeline name", name);
+        }
+        let pipelines = self.list();
+        match pipelines.into_iter().find(|(n, _)| n == name) {
+            Some((_, pipeline)) => pipeline,
+            None => Err(anyhow!("No pipeline named \"{}\" in {}", name, self.dir.display())),
+        }
+    }
+
+    /// Pipelines with a schedule
+    pub fn scheduled(&self) -> Vec<Pipeline> {
+        self.list().into_iter()
+            .filter_map(|(_, pipeline)| pipeline.ok())
+            .filter(|p| p.schedule.is_some())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_brain::test_support::{brain_in, fake_llm};
+
+    fn pipeline(yaml: &str) -> Result<Pipeline> {
+        Pipeline::parse("daily", Path::new("pipelines/daily.yaml"), yaml)
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        let values = values(&[("vars.topic", "rust"), ("steps.notes.output", "two notes")]);
+        assert_eq!(render("{{vars.topic}}: {{ steps.notes.output }}", &values).unwrap(), "rust: two notes");
+        assert_eq!(render("{{vars.topic}} {{steps.2.output}} {{date}}", &values).unwrap_err(), "steps.2.output");
+
+        let args = serde_json::json!({"path": "{{vars.topic}}.md", "tags": ["{{vars.topic}}", 3], "limit": 10});
+        let rendered: Value = serde_json::from_str(&render_args(&args, &values).unwrap()).unwrap();
+        assert_eq!(rendered, serde_json::json!({"path": "rust.md", "tags": ["rust", 3], "limit": 10}));
+        assert_eq!(render_args(&Value::String("{{vars.topic}} --lines=1-2".into()), &values).unwrap(), "rust --lines=1-2");
+        assert_eq!(render_args(&Value::Null, &values).unwrap(), "");
+        assert_eq!(render_args(&serde_json::json!({"q": "{{vars.other}}"}), &values).unwrap_err(), "vars.other");
+    }
+
+    #[test]
+    fn file_is_checked_on_load() {
+        let loaded = pipeline("on_error: continue\nvars:\n  days: 3\n  topic:\nsteps:\n  - name: notes\n    skill: read_file\n    args: \"{{vars.topic}}\"\n  - think: \"{{steps.notes.output}} {{steps.1.output}} {{vars.days}} {{date}}\"\n").unwrap();
+        assert_eq!(loaded.on_error, ErrorPolicy::Continue);
+        assert_eq!(loaded.vars, BTreeMap::from([("days".to_string(), "3".to_string()), ("topic".to_string(), String::new())]));
+        assert_eq!((loaded.steps[0].label(0), loaded.steps[1].label(1), loaded.steps[1].kind()), ("notes".to_string(), "step 2".to_string(), "think"));
+
+        let refusals = [
+            ("steps: []", "no steps"),
+            ("steps:\n  - think: \"{{vars.topic}}\"", "not a declared variable"),
+            ("steps:\n  - think: \"{{steps.2.output}}\"\n  - think: hi", "not a declared variable"),
+            ("steps:\n  - name: a\n    think: \"{{steps.a.output}}\"", "not a declared variable"),
+            ("steps:\n  - skill: read_file\n    think: hi", "has both skill and think"),
+            ("steps:\n  - name: x\n", "needs a skill or a think prompt"),
+            ("steps:\n  - think: hi\n    args: x", "has args but no skill"),
+            ("steps:\n  - skill: RUN_PIPELINE", "cannot run pipelines"),
+            ("steps:\n  - name: \"12\"\n    think: hi", "not only digits"),
+            ("steps:\n  - name: a\n    think: hi\n  - name: a\n    think: hi", "two steps are named"),
+            ("schedule: every day\nsteps:\n  - think: hi", "not a valid cron expression"),
+            ("steps:\n  - think: hi\n    retry: 2", "unknown field"),
+        ];
+        for (yaml, reason) in refusals {
+            let error = pipeline(yaml).expect_err(reason).to_string();
+            assert!(error.starts_with("pipelines/daily.yaml: ") && error.contains(reason), "{} (expected {})", error, reason);
+        }
+    }
+
+    /// A pipeline over two files: `missing` fails, `notes` reads; the think step uses both
+    async fn file_pipeline(dir: &Path, policy: &str) -> (Brain, Pipeline, openspore_brain::test_support::Requests) {
+        let (url, requests) = fake_llm(|_| "Summary done.".to_string()).await;
+        let brain = brain_in(dir, &url).await;
+        std::fs::write(dir.join("notes.md"), "remember the milk\n").unwrap();
+        let yaml = format!(
+            "on_error: {}\nvars:\n  missing: {}\n  notes: {}\nsteps:\n  - name: gone\n    skill: read_file\n    args: \"{{{{vars.missing}}}}\"\n  - think: \"Summarize {{{{steps.gone.output}}}}\"\n  - name: notes\n    skill: read_file\n    args:\n      path: \"{{{{vars.notes}}}}\"\n  - think: \"Summarize {{{{steps.notes.output}}}} for {{{{pipeline}}}}\"\n",
+            policy,
+            dir.join("missing.md").display(),
+            dir.join("notes.md").display()
+        );
+        (brain, pipeline(&yaml).unwrap(), requests)
+    }
+
+    fn statuses(report: &PipelineReport) -> Vec<StepStatus> {
+        report.steps.iter().map(|s| s.status).collect()
+    }
+
+    #[tokio::test]
+    async fn continue_skips_the_steps_that_need_the_failed_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let (brain, pipeline, requests) = file_pipeline(dir.path(), "continue").await;
+
+        let report = run(&brain, &pipeline, &BTreeMap::new(), None).await.unwrap();
+
+        assert_eq!(report.status, RunStatus::CompletedWithErrors);
+        assert_eq!(statuses(&report), [StepStatus::Failed, StepStatus::Skipped, StepStatus::Ok, StepStatus::Ok]);
+        assert!(report.steps[1].error.as_deref().unwrap().contains("{{steps.gone.output}} has no value"), "{:?}", report.steps[1]);
+        assert!(report.steps[2].output.contains("remember the milk"), "{}", report.steps[2].output);
+        assert_eq!(report.final_output(), Some("Summary done."));
+        assert!(requests.mentioning("remember the milk") > 0 && requests.mentioning(" for daily") > 0, "the think step got the read output");
+
+        let entry = report.journal_entry();
+        assert!(entry.contains("PIPELINE [daily, run: pipeline-daily-") && entry.contains("completed with errors"), "{}", entry);
+        assert!(entry.contains("- 1 gone (read_file): failed: ") && entry.contains("- 2 step 2 (think): skipped: "), "{}", entry);
+    }
+
+    #[tokio::test]
+    async fn abort_stops_at_the_first_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let (brain, pipeline, requests) = file_pipeline(dir.path(), "abort").await;
+
+        let report = run(&brain, &pipeline, &BTreeMap::new(), None).await.unwrap();
+
+        assert_eq!(report.status, RunStatus::Aborted);
+        assert_eq!(statuses(&report), [StepStatus::Failed, StepStatus::NotRun, StepStatus::NotRun, StepStatus::NotRun]);
+        assert_eq!(report.final_output(), None);
+        assert!(requests.is_empty(), "no think step ran");
+        assert!(report.journal_entry().contains("- 4 step 4 (think): not run"));
+    }
+
+    #[tokio::test]
+    async fn step_policy_overrides_the_pipeline_and_overrides_replace_vars() {
+        let dir = tempfile::tempdir().unwrap();
+        let (brain, mut pipeline, _) = file_pipeline(dir.path(), "abort").await;
+        pipeline.steps[0].on_error = Some(ErrorPolicy::Continue);
+
+        let notes = dir.path().join("notes.md").display().to_string();
+        let overrides = BTreeMap::from([("missing".to_string(), notes)]);
+        let report = run(&brain, &pipeline, &overrides, None).await.unwrap();
+        assert_eq!(report.status, RunStatus::Completed);
+        assert_eq!(report.vars["missing"], report.vars["notes"]);
+
+        let unknown = BTreeMap::from([("topic".to_string(), "x".to_string())]);
+        let error = run(&brain, &pipeline, &unknown, None).await.unwrap_err().to_string();
+        assert_eq!(error, "topic is not a variable of daily (declared: missing, notes)");
+    }
+
+    #[tokio::test]
+    async fn ask_goes_on_only_when_approved() {
+        let dir = tempfile::tempdir().unwrap();
+        let (brain, pipeline, _) = file_pipeline(dir.path(), "ask").await;
+
+        let unattended = run(&brain, &pipeline, &BTreeMap::new(), None).await.unwrap();
+        assert_eq!(unattended.status, RunStatus::Aborted, "nobody present answers no");
+
+        let (tx, mut rx) = mpsc::channel(16);
+        let approvals = brain.approvals.clone();
+        let asked = tokio::spawn(async move {
+            let mut questions = Vec::new();
+            while let Some(event) = rx.recv().await {
+                if let BrainEvent::ApprovalRequest { id, name, arg } = event {
+                    questions.push((name, arg));
+                    approvals.respond(id, openspore_brain::approval::ApprovalDecision::Approve);
+                }
+            }
+            questions
+        });
+        let approved = run(&brain, &pipeline, &BTreeMap::new(), Some(tx)).await.unwrap();
+        assert_eq!(approved.status, RunStatus::CompletedWithErrors);
+        assert_eq!(statuses(&approved), [StepStatus::Failed, StepStatus::Skipped, StepStatus::Ok, StepStatus::Ok]);
+
+        let questions = asked.await.unwrap();
+        assert_eq!(questions.len(), 1, "{:?}", questions);
+        assert_eq!(questions[0].0, "pipeline");
+        assert!(questions[0].1.starts_with("daily: continue after gone (read_file) failed: "), "{}", questions[0].1);
+    }
+
+    #[test]
+    fn store_finds_pipelines_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PipelineStore::new(dir.path());
+        std::fs::create_dir_all(store.dir()).unwrap();
+        std::fs::write(store.dir().join("daily.yaml"), "schedule: \"0 8 * * *\"\nsteps:\n  - think: hi\n").unwrap();
+        std::fs::write(store.dir().join("broken.yml"), "steps: []\n").unwrap();
+        std::fs::write(store.dir().join("notes.txt"), "not a pipeline").unwrap();
+
+        let names: Vec<String> = store.list().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["broken", "daily"]);
+        assert!(store.find("daily.yaml").unwrap().trigger().is_some());
+        assert!(store.find("broken").unwrap_err().to_string().contains("no steps"));
+        assert!(store.find("../daily").unwrap_err().to_string().contains("is not a pipeline name"));
+        assert!(store.find("weekly").unwrap_err().to_string().starts_with("No pipeline named \"weekly\""));
+        assert_eq!(store.scheduled().len(), 1);
+    }
+}
@@ -0,0 +1,223 @@
+//! Review gate for autonomy proposals
+//!
+//! The engine writes proposals to workspace/autonomy/proposals with `status:
+//! PENDING` in their frontmatter. Approving one (`openspore proposal approve`
+//! or `/proposal approve` in the TUI) marks it EXECUTING and hands its Action
+//! Plan to an Executor Spore cycle that may use tools. That cycle goes through
+//! the usual approval gate and exec policy, so APPROVAL_MODE, APPROVAL_ALLOW
+//! and Safe Mode apply as for any other turn. The proposal then ends EXECUTED
+//! or FAILED with the cycle id, where its journal entries are and the
+//! executor's summary. Rejecting sets REJECTED and records the reason.
+
+use anyhow::{anyhow, bail, Result};
+use openspore_brain::events::BrainEvent;
+use openspore_brain::Brain;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Location of the proposals, relative to the app root
+pub const PROPOSALS_DIR: &str = "workspace/autonomy/proposals";
+
+pub const PENDING: &str = "PENDING";
+pub const EXECUTING: &str = "EXECUTING";
+pub const EXECUTED: &str = "EXECUTED";
+pub const FAILED: &str = "FAILED";
+pub const REJECTED: &str = "REJECTED";
+
+/// Channel of executor cycles in the audit log
+const EXECUTOR_CHANNEL: &str = "proposal";
+
+#[derive(Debug, Clone)]
+pub struct Proposal {
+    pub id: String,
+    pub title: String,
+    pub kind: String,
+    pub status: String,
+    pub risk: String,
+    /// RFC 3339, as written by the engine
+    pub created: String,
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl Proposal {
+    fn parse(path: &Path, content: String) -> Self {
+        let meta = openspore_memory::parse_frontmatter(&content);
+        let field = |key: &str| meta.get(key).cloned().unwrap_or_default();
+        let id = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+        Self {
+            title: meta.get("title").cloned().unwrap_or_else(|| id.clone()),
+            id,
+            kind: field("type"),
+            status: meta.get("status").map(|s| s.to_uppercase()).unwrap_or_else(|| PENDING.to_string()),
+            risk: field("risk"),
+            created: field("created"),
+            path: path.to_path_buf(),
+            content,
+        }
+    }
+
+    /// Text of the `## Action Plan` section
+    pub fn action_plan(&self) -> String {
+        section(&self.content, "Action Plan")
+    }
+
+    /// Whether it can be approved (a failed run may be approved again)
+    pub fn is_approvable(&self) -> bool {
+        self.status == PENDING || self.status == FAILED
+    }
+}
+
+/// Body of the `## <name>` section of `content`, up to the next `## ` heading
+fn section(content: &str, name: &str) -> String {
+    let heading = format!("## {}", name);
+    let mut lines = content.lines().skip_while(|l| l.trim() != heading);
+    if lines.next().is_none() {
+        return String::new();
+    }
+    lines.take_while(|l| !l.starts_with("## ")).collect::<Vec<_>>().join("\n").trim().to_string()
+}
+
+/// Rewrite the `status:` line of a proposal and append `section`, if any
+pub fn set_status(path: &Path, status: &str, section: Option<&str>) -> Result<()> {
+    let content = std::fs::read_to_string(path)?;
+    let mut updated = content.lines()
+        .map(|l| if l.starts_with("status: ") { format!("status: {}", status) } else { l.to_string() })
+        .collect::<Vec<_>>()
+        .join("\n");
+    updated.push('\n');
+    if let Some(section) = section {
+        updated.push('\n');
+        updated.push_str(section.trim_end());
+        updated.push('\n');
+    }
+    std::fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Outcome of an executor cycle
+#[derive(Debug, Clone)]
+pub struct Execution {
+    /// EXECUTED or FAILED
+    pub status: &'static str,
+    pub cycle: String,
+    pub reply: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProposalStore {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+impl ProposalStore {
+    pub fn new(project_root: &Path) -> Self {
+        Self { root: project_root.to_path_buf(), dir: project_root.join(PROPOSALS_DIR) }
+    }
+
+    /// All proposals, newest first
+    pub fn list(&self) -> Vec<Proposal> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else { return Vec::new() };
+        let mut proposals: Vec<Proposal> = entries.flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "md"))
+            .filter_map(|p| std::fs::read_to_string(&p).ok().map(|c| Proposal::parse(&p, c)))
+            .collect();
+        proposals.sort_by(|a, b| b.id.cmp(&a.id));
+        proposals
+    }
+
+    /// The proposal `query` names: its id (or file name), else the only id containing it
+    pub fn find(&self, query: &str) -> Result<Proposal> {
+        let query = query.trim().trim_end_matches(".md");
+        if query.is_empty() {
+            bail!("Which proposal? `openspore proposal list` shows the ids");
+        }
+        let proposals = self.list();
+        if let Some(exact) = proposals.iter().find(|p| p.id == query) {
+            return Ok(exact.clone());
+        }
+        let matches: Vec<&Proposal> = proposals.iter().filter(|p| p.id.contains(query)).collect();
+        match matches.as_slice() {
+            [one] => Ok((*one).clone()),
+            [] => Err(anyhow!("No proposal matches \"{}\"", query)),
+            many => Err(anyhow!(
+                "\"{}\" matches {} proposals: {}",
+                query,
+                many.len(),
+                many.iter().map(|p| p.id.as_str()).collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+
+    pub fn reject(&self, query: &str, reason: &str) -> Result<Proposal> {
+        let proposal = self.find(query)?;
+        if !proposal.is_approvable() {
+            bail!("{} is {}; only PENDING or FAILED proposals can be rejected", proposal.id, proposal.status);
+        }
+        let reason = if reason.trim().is_empty() { "no reason given" } else { reason.trim() };
+        let note = format!("## Rejected\n- At: {}\n- Reason: {}", chrono::Local::now().to_rfc3339(), reason);
+        set_status(&proposal.path, REJECTED, Some(&note))?;
+        info!("🚫 Proposal {} rejected: {}", proposal.id, reason);
+        self.find(&proposal.id)
+    }
+
+    /// Mark a proposal EXECUTING so it is not started twice. Run execute() next.
+    pub fn begin(&self, query: &str) -> Result<Proposal> {
+        let proposal = self.find(query)?;
+        if !proposal.is_approvable() {
+            bail!("{} is {}; only PENDING or FAILED proposals can be approved", proposal.id, proposal.status);
+        }
+        if proposal.action_plan().is_empty() {
+            bail!("{} has no Action Plan section", proposal.id);
+        }
+        set_status(&proposal.path, EXECUTING, None)?;
+        Ok(proposal)
+    }
+
+    /// Run the Action Plan of a proposal begin() returned, then record the
+    /// outcome. `observer` receives the cycle's events (and its approval requests).
+    pub async fn execute(&self, brain: &Brain, proposal: &Proposal, observer: Option<mpsc::Sender<BrainEvent>>) -> Execution {
+        let prompt = format!(r#"You are the 'Executor Spore'. The user approved this proposal; carry out its Action Plan now.
+Stay within the plan and do not start unrelated work. Use tools where a step needs them. Gated tools still ask for approval: when one is denied, skip that step and say so.
+
+Proposal: {title} ({id})
+
+<ACTION_PLAN>
+{plan}
+</ACTION_PLAN>
+
+When finished, summarize what was done and anything left undone."#,
+            title = proposal.title,
+            id = proposal.id,
+            plan = proposal.action_plan()
+        );
+
+        info!("▶️ Executing proposal {}", proposal.id);
+        let executor = brain.clone_brain().with_channel(EXECUTOR_CHANNEL);
+        let (reply, stats) = executor.think_observed_with_stats(&prompt, observer).await;
+        let failed = openspore_brain::failed_reply_error(&reply).is_some() || reply.contains(openspore_brain::CYCLE_FAILED_MARKER);
+        let status = if failed { FAILED } else { EXECUTED };
+
+        let journal = brain.memory.context_dir().join("LOGS.md");
+        let journal = journal.strip_prefix(&self.root).unwrap_or(&journal).display().to_string();
+        let mut note = format!(
+            "## Execution\n- Finished: {}\n- Status: {}\n- Cycle: {}\n- Journal: {} (entries of cycle {})\n",
+            chrono::Local::now().to_rfc3339(),
+            status,
+            stats.cycle_id,
+            journal,
+            stats.cycle_id
+        );
+        if failed {
+            note.push_str(&format!("- Post-mortem: `openspore failures show {}`\n", stats.cycle_id));
+        }
+        note.push_str(&format!("\n{}", reply.trim()));
+        if let Err(e) = set_status(&proposal.path, status, Some(&note)) {
+            warn!("Could not record the outcome of proposal {}: {}", proposal.id, e);
+        }
+        info!("{} Proposal {} {}", if failed { "❌" } else { "✅" }, proposal.id, status);
+        Execution { status, cycle: stats.cycle_id, reply }
+    }
+}
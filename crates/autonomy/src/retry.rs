@@ -0,0 +1,334 @@
+//! Retries of failed scheduled jobs
+//!
+//! A failed job is re-queued with exponential backoff up to its retry limit.
+//! The queue lives in workspace/autonomy/retries.json so pending retries
+//! survive a restart, and every run is appended to job_history.jsonl.
+//! Rate-limited failures are postponed without using up an attempt.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use openspore_core::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Retries are abandoned this long after the first failure, whatever the policy
+const MAX_RETRY_WINDOW_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledJob {
+    Heartbeat,
+    Journal,
+    /// Autonomy engine (Tree of Thought proposals)
+    Engine,
+}
+
+impl ScheduledJob {
+    pub const ALL: [ScheduledJob; 3] = [ScheduledJob::Heartbeat, ScheduledJob::Journal, ScheduledJob::Engine];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScheduledJob::Heartbeat => "heartbeat",
+            ScheduledJob::Journal => "journal",
+            ScheduledJob::Engine => "engine",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub backoff_minutes: u64,
+}
+
+impl RetryPolicy {
+    pub fn for_job(job: ScheduledJob, config: &AppConfig) -> Self {
+        match job {
+            ScheduledJob::Journal => Self {
+                retries: config.journal_retries.unwrap_or(3),
+                backoff_minutes: config.journal_retry_backoff_minutes.unwrap_or(30),
+            },
+            ScheduledJob::Heartbeat => Self {
+                retries: config.heartbeat_retries.unwrap_or(0),
+                backoff_minutes: config.heartbeat_retry_backoff_minutes.unwrap_or(15),
+            },
+            // The next run is one engine interval away
+            ScheduledJob::Engine => Self { retries: 0, backoff_minutes: 15 },
+        }
+    }
+
+    /// Delay before retry number `attempt` (1-based): backoff, 2x backoff, 4x...
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(6);
+        Duration::minutes((self.backoff_minutes.max(1) * factor) as i64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRetry {
+    pub job: ScheduledJob,
+    /// Retries already made
+    pub attempt: u32,
+    pub due: DateTime<Local>,
+    pub first_failed: DateTime<Local>,
+    pub last_error: String,
+}
+
+/// What happened to a failed run
+#[derive(Debug)]
+pub enum FailureOutcome {
+    Retrying { attempt: u32, due: DateTime<Local> },
+    /// Rate limited: same attempt, later
+    Postponed { due: DateTime<Local> },
+    Exhausted { attempts: u32 },
+}
+
+#[derive(Serialize)]
+struct HistoryEntry<'a> {
+    time: String,
+    job: &'a str,
+    attempt: u32,
+    status: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+/// 429s and provider "rate limit" errors are worth waiting out
+pub fn is_rate_limited(error: &str) -> bool {
+    let lower = error.to_lowercase();
+    lower.contains("429") || lower.contains("rate limit") || lower.contains("too many requests")
+}
+
+/// Persisted retry queue (clones share the same lock)
+#[derive(Clone)]
+pub struct RetryQueue {
+    dir: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl RetryQueue {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            dir: project_root.join("workspace").join("autonomy"),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn queue_path(&self) -> PathBuf {
+        self.dir.join("retries.json")
+    }
+
+    pub fn history_path(&self) -> PathBuf {
+        self.dir.join("job_history.jsonl")
+    }
+
+    fn load(&self) -> Vec<PendingRetry> {
+        std::fs::read_to_string(self.queue_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, pending: &[PendingRetry]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.queue_path(), serde_json::to_string_pretty(pending)?)?;
+        Ok(())
+    }
+
+    fn record(&self, job: ScheduledJob, attempt: u32, status: &str, error: Option<&str>) {
+        use std::io::Write;
+        let entry = HistoryEntry { time: Local::now().to_rfc3339(), job: job.name(), attempt, status, error };
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(_) => return,
+        };
+        let written = std::fs::create_dir_all(&self.dir).and_then(|_| {
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(self.history_path())?;
+            writeln!(file, "{}", line)
+        });
+        if let Err(e) = written {
+            warn!("Failed to write job history: {}", e);
+        }
+    }
+
+    /// Retries whose time has come (removed from the queue; a new failure re-adds them)
+    pub async fn take_due(&self, now: DateTime<Local>) -> Vec<PendingRetry> {
+        let _guard = self.lock.lock().await;
+        let (due, waiting): (Vec<_>, Vec<_>) = self.load().into_iter().partition(|r| r.due <= now);
+        if !due.is_empty()
+            && let Err(e) = self.save(&waiting)
+        {
+            warn!("Failed to save retry queue: {}", e);
+        }
+        due
+    }
+
+    /// Whether a retry of `job` is already queued (the regular run then stands down)
+    pub async fn is_pending(&self, job: ScheduledJob) -> bool {
+        let _guard = self.lock.lock().await;
+        self.load().iter().any(|r| r.job == job)
+    }
+
+    /// Record a successful run
+    pub async fn succeeded(&self, job: ScheduledJob, attempt: u32) {
+        let _guard = self.lock.lock().await;
+        if attempt > 0 {
+            info!("🔁 Scheduler: {} succeeded on retry {}", job.name(), attempt);
+        }
+        self.record(job, attempt, "succeeded", None);
+    }
+
+    /// Record a failure and queue the next retry if the policy allows one.
+    /// `first_failed` is carried over from the retry being run, if any.
+    pub async fn failed(&self, job: ScheduledJob, attempt: u32, first_failed: Option<DateTime<Local>>, error: &str, policy: RetryPolicy) -> FailureOutcome {
+        let _guard = self.lock.lock().await;
+        let now = Local::now();
+        let first_failed = first_failed.unwrap_or(now);
+        let expired = now - first_failed >= Duration::hours(MAX_RETRY_WINDOW_HOURS);
+
+        let outcome = if expired {
+            FailureOutcome::Exhausted { attempts: attempt }
+        } else if is_rate_limited(error) && policy.retries > 0 {
+            FailureOutcome::Postponed { due: now + policy.delay(attempt.max(1)) }
+        } else if attempt < policy.retries {
+            FailureOutcome::Retrying { attempt: attempt + 1, due: now + policy.delay(attempt + 1) }
+        } else {
+            FailureOutcome::Exhausted { attempts: attempt }
+        };
+
+        let (status, next) = match &outcome {
+            FailureOutcome::Retrying { attempt, due } => ("failed", Some((*attempt, *due))),
+            FailureOutcome::Postponed { due } => ("rate_limited", Some((attempt, *due))),
+            FailureOutcome::Exhausted { .. } => ("exhausted", None),
+        };
+        self.record(job, attempt, status, Some(error));
+
+        if let Some((next_attempt, due)) = next {
+            let mut pending = self.load();
+            pending.retain(|r| r.job != job);
+            pending.push(PendingRetry { job, attempt: next_attempt, due, first_failed, last_error: error.to_string() });
+            if let Err(e) = self.save(&pending) {
+                warn!("Failed to save retry queue: {}", e);
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(retries: u32) -> RetryPolicy {
+        RetryPolicy { retries, backoff_minutes: 10 }
+    }
+
+    /// A job that fails with `errors` in turn, then succeeds; run the way the
+    /// scheduler does, taking each retry as soon as it is due. Returns the
+    /// attempts made and the final outcome (None on success).
+    async fn run_mock(queue: &RetryQueue, policy: RetryPolicy, errors: &[&str]) -> (u32, Option<FailureOutcome>) {
+        let mut errors = errors.iter();
+        let mut retry: Option<PendingRetry> = None;
+        let mut runs = 0;
+        loop {
+            runs += 1;
+            let attempt = retry.as_ref().map(|r| r.attempt).unwrap_or(0);
+            let Some(error) = errors.next() else {
+                queue.succeeded(ScheduledJob::Journal, attempt).await;
+                return (runs, None);
+            };
+            let outcome = queue.failed(ScheduledJob::Journal, attempt, retry.map(|r| r.first_failed), error, policy).await;
+            if let FailureOutcome::Exhausted { .. } = outcome {
+                return (runs, Some(outcome));
+            }
+            assert!(queue.is_pending(ScheduledJob::Journal).await);
+            let mut due = queue.take_due(Local::now() + Duration::days(1)).await;
+            assert_eq!(due.len(), 1);
+            retry = due.pop();
+        }
+    }
+
+    fn history(queue: &RetryQueue) -> Vec<(u32, String)> {
+        std::fs::read_to_string(queue.history_path()).unwrap_or_default().lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .map(|v| (v["attempt"].as_u64().unwrap() as u32, v["status"].as_str().unwrap().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn backoff_doubles_and_is_capped() {
+        let p = policy(3);
+        assert_eq!(p.delay(1), Duration::minutes(10));
+        assert_eq!(p.delay(2), Duration::minutes(20));
+        assert_eq!(p.delay(3), Duration::minutes(40));
+        assert_eq!(p.delay(20), Duration::minutes(640));
+        assert_eq!(RetryPolicy { retries: 1, backoff_minutes: 0 }.delay(1), Duration::minutes(1));
+    }
+
+    #[tokio::test]
+    async fn job_succeeds_on_a_retry() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = RetryQueue::new(dir.path());
+        let (runs, outcome) = run_mock(&queue, policy(3), &["timeout", "connection reset"]).await;
+        assert_eq!(runs, 3);
+        assert!(outcome.is_none());
+        assert!(!queue.is_pending(ScheduledJob::Journal).await);
+        assert_eq!(history(&queue), [(0, "failed".into()), (1, "failed".into()), (2, "succeeded".into())]);
+    }
+
+    #[tokio::test]
+    async fn job_gives_up_after_its_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = RetryQueue::new(dir.path());
+        let (runs, outcome) = run_mock(&queue, policy(2), &["boom"; 5]).await;
+        assert_eq!(runs, 3);
+        assert!(matches!(outcome, Some(FailureOutcome::Exhausted { attempts: 2 })));
+        assert!(!queue.is_pending(ScheduledJob::Journal).await);
+        assert_eq!(history(&queue).last(), Some(&(2, "exhausted".into())));
+
+        // Without a policy the first failure is final
+        let (runs, outcome) = run_mock(&queue, policy(0), &["boom"]).await;
+        assert_eq!(runs, 1);
+        assert!(matches!(outcome, Some(FailureOutcome::Exhausted { attempts: 0 })));
+    }
+
+    #[tokio::test]
+    async fn rate_limits_postpone_without_using_an_attempt() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = RetryQueue::new(dir.path());
+        let (runs, outcome) = run_mock(&queue, policy(1), &["HTTP 429 Too Many Requests", "rate limit exceeded", "boom"]).await;
+        assert!(outcome.is_none());
+        assert_eq!(runs, 4);
+        let statuses: Vec<String> = history(&queue).into_iter().map(|(_, s)| s).collect();
+        assert_eq!(statuses, ["rate_limited", "rate_limited", "failed", "succeeded"]);
+    }
+
+    #[tokio::test]
+    async fn pending_retries_survive_a_restart_and_wait_until_due() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = RetryQueue::new(dir.path());
+        let outcome = queue.failed(ScheduledJob::Heartbeat, 0, None, "boom", policy(2)).await;
+        let FailureOutcome::Retrying { attempt: 1, due } = outcome else { panic!("{:?}", outcome) };
+
+        let restarted = RetryQueue::new(dir.path());
+        assert!(restarted.take_due(Local::now()).await.is_empty());
+        assert!(restarted.is_pending(ScheduledJob::Heartbeat).await);
+        let taken = restarted.take_due(due).await;
+        assert_eq!(taken.len(), 1);
+        assert_eq!((taken[0].job, taken[0].attempt, taken[0].last_error.as_str()), (ScheduledJob::Heartbeat, 1, "boom"));
+        assert!(!restarted.is_pending(ScheduledJob::Heartbeat).await);
+    }
+
+    #[tokio::test]
+    async fn retries_stop_after_the_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = RetryQueue::new(dir.path());
+        let long_ago = Local::now() - Duration::hours(MAX_RETRY_WINDOW_HOURS);
+        let outcome = queue.failed(ScheduledJob::Journal, 1, Some(long_ago), "boom", policy(5)).await;
+        assert!(matches!(outcome, FailureOutcome::Exhausted { attempts: 1 }));
+        assert!(!queue.is_pending(ScheduledJob::Journal).await);
+    }
+}
@@ -1,70 +1,386 @@
 use openspore_brain::Brain;
 use openspore_memory::MemorySystem;
 use openspore_telegram::TelegramChannel;
+use crate::engine::AutonomyEngine;
 use crate::heartbeat::Heartbeat;
 use crate::journal::DailyJournal;
-use chrono::Timelike;
-use tokio::time::{sleep, Duration};
+use crate::pipelines::{self, PipelineStore, RunStatus};
+use crate::retry::{FailureOutcome, PendingRetry, RetryPolicy, RetryQueue, ScheduledJob};
+use chrono::{DateTime, Local, Timelike};
+use openspore_core::config::AppConfig;
+use openspore_core::event_bus::{self, SystemEvent};
+use openspore_core::schedule::{QuietHours, Schedule, Trigger};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use tokio::sync::Notify;
+use tokio::time::Duration;
 use tracing::{info, error};
 
 pub struct SporeScheduler;
 
+/// Longest sleep of the task runner: wall-clock due times are re-checked at
+/// least this often (suspend, clock changes)
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Schedule handed over by reload() and the signal that wakes the runner
+fn pending_reload() -> &'static (std::sync::Mutex<Option<Schedule>>, Notify) {
+    static RELOAD: OnceLock<(std::sync::Mutex<Option<Schedule>>, Notify)> = OnceLock::new();
+    RELOAD.get_or_init(|| (std::sync::Mutex::new(None), Notify::new()))
+}
+
+/// Whether this process runs the scheduler
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Re-read the schedule from the configuration and hand it to the running
+/// scheduler. A misconfigured schedule is returned as an error and the
+/// current one stays. `applied` is false when no scheduler runs here.
+pub fn reload() -> anyhow::Result<(Schedule, bool)> {
+    let config = AppConfig::load()?;
+    let schedule = Schedule::from_config(&config)?;
+    let running = RUNNING.load(Ordering::SeqCst);
+    if running {
+        hand_over(schedule.clone());
+    }
+    Ok((schedule, running))
+}
+
+/// Give the running task runner a new schedule
+fn hand_over(schedule: Schedule) {
+    let (pending, notify) = pending_reload();
+    if let Ok(mut pending) = pending.lock() {
+        *pending = Some(schedule);
+    }
+    notify.notify_one();
+}
+
+/// Apply the schedule of each configuration update (AppConfig::watch); a
+/// misconfigured one is reported and the current schedule stays
+fn follow_config(mut current: String) {
+    let mut updates = AppConfig::watch();
+    tokio::spawn(async move {
+        while updates.changed().await.is_ok() {
+            let config = updates.borrow_and_update().clone();
+            match Schedule::from_config(&config) {
+                Ok(schedule) if describe(&schedule) != current => {
+                    current = describe(&schedule);
+                    hand_over(schedule);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!("🕒 Spore Scheduler: {}. The current schedule stays.", e);
+                    event_bus::publish(SystemEvent::InboxMessage { source: "scheduler".to_string(), text: format!("{}. The current schedule stays.", e) });
+                }
+            }
+        }
+    });
+}
+
+/// "heartbeat every 2h, journal every 1d at 22:00, engine off (disabled)"
+pub fn describe(schedule: &Schedule) -> String {
+    let mut parts: Vec<String> = schedule.tasks.iter().map(|t| format!("{} {}", t.task, t.describe())).collect();
+    if let Some(quiet) = schedule.quiet_hours {
+        parts.push(format!("quiet {}", quiet));
+    }
+    parts.join(", ")
+}
+
+/// Due time of each running task
+type DueTimes = Vec<(ScheduledJob, Trigger, DateTime<Local>)>;
+
+/// First due times of `schedule`; tasks whose trigger did not change keep
+/// their due time from `previous`
+fn due_times(schedule: &Schedule, previous: &DueTimes, now: DateTime<Local>) -> DueTimes {
+    schedule.tasks.iter()
+        .filter_map(|task| {
+            let job = ScheduledJob::ALL.into_iter().find(|j| j.name() == task.task)?;
+            let trigger = task.trigger.clone()?;
+            let kept = previous.iter()
+                .find(|(j, t, _)| *j == job && t.describe(job.name()) == trigger.describe(job.name()))
+                .map(|(_, _, due)| *due);
+            let due = kept.or_else(|| trigger.first_run(job.name(), now))?;
+            Some((job, trigger, due))
+        })
+        .collect()
+}
+
+/// What a scheduled job needs to run
+#[derive(Clone)]
+struct JobContext {
+    brain: Brain,
+    memory: MemorySystem,
+    telegram: Option<TelegramChannel>,
+    retries: RetryQueue,
+    /// Of the current schedule (reloadable)
+    quiet_hours: Arc<RwLock<Option<QuietHours>>>,
+}
+
+impl JobContext {
+    /// Telegram for routine messages: none during quiet hours
+    fn notifier(&self, job: ScheduledJob) -> Option<&TelegramChannel> {
+        let quiet = self.quiet_hours.read().ok().and_then(|q| *q).filter(|q| q.contains(Local::now().time()));
+        match quiet {
+            Some(quiet) if self.telegram.is_some() => {
+                info!("🌙 Scheduler: quiet hours ({}), {} sends no Telegram message", quiet, job.name());
+                None
+            }
+            _ => self.telegram.as_ref(),
+        }
+    }
+
+    async fn execute(&self, job: ScheduledJob) -> anyhow::Result<()> {
+        let telegram = self.notifier(job);
+        match job {
+            // The engine has its own schedule
+            ScheduledJob::Heartbeat => Heartbeat::run(&self.brain, &self.memory, telegram, false).await,
+            ScheduledJob::Journal => {
+                if let Some(path) = DailyJournal::run(&self.brain, &self.memory).await?
+                    && let Some(tg) = telegram
+                {
+                    let _ = tg.send_raw(&format!("📓 Daily Journal synthesized: {}", path.display())).await;
+                }
+                Ok(())
+            }
+            ScheduledJob::Engine => {
+                if let Some(line) = AutonomyEngine::run_and_publish(&self.brain, &self.memory).await?
+                    && let Some(tg) = telegram
+                {
+                    let _ = tg.send_raw(&format!("💡 Autonomy: {}", line)).await;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Run the tasks of `schedule`, sleeping until the earliest due one, and
+    /// switch to a reloaded schedule when one is handed over
+    fn spawn_runner(&self, schedule: Schedule) {
+        let ctx = self.clone();
+        tokio::spawn(async move {
+            let mut due = due_times(&schedule, &Vec::new(), Local::now());
+            loop {
+                let now = Local::now();
+                for (job, trigger, at) in due.iter_mut() {
+                    if *at > now {
+                        continue;
+                    }
+                    if *job == ScheduledJob::Journal {
+                        info!("🕒 Spore Scheduler: Time for daily synthesis!");
+                    }
+                    if !ctx.retries.is_pending(*job).await {
+                        ctx.spawn(*job, None);
+                    }
+                    // A run missed while the machine slept happens once, not in a burst
+                    match trigger.next_run(now) {
+                        Some(next) => *at = next,
+                        None => *at = now + chrono::Duration::days(365),
+                    }
+                }
+
+                let wait = due.iter().map(|(_, _, at)| (*at - now).to_std().unwrap_or_default()).min().unwrap_or(MAX_SLEEP).min(MAX_SLEEP);
+                let (pending, notify) = pending_reload();
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = notify.notified() => {
+                        let Some(schedule) = pending.lock().ok().and_then(|mut p| p.take()) else { continue };
+                        if let Ok(mut quiet) = ctx.quiet_hours.write() {
+                            *quiet = schedule.quiet_hours;
+                        }
+                        due = due_times(&schedule, &due, Local::now());
+                        info!("🕒 Spore Scheduler: schedule reloaded: {}", describe(&schedule));
+                    }
+                }
+            }
+        });
+    }
+
+    /// Run a scheduled pipeline in the background, unless its previous run
+    /// is still going. Nobody watches: gated steps and `ask` are refused.
+    fn spawn_pipeline(&self, pipeline: pipelines::Pipeline, running: Arc<Mutex<HashSet<String>>>) {
+        if !running.lock().unwrap_or_else(|e| e.into_inner()).insert(pipeline.name.clone()) {
+            info!("🧪 Scheduler: pipeline {} is still running, this run is skipped", pipeline.name);
+            return;
+        }
+        let ctx = self.clone();
+        tokio::spawn(async move {
+            info!("🕒 Spore Scheduler: running pipeline {}", pipeline.name);
+            match pipelines::run(&ctx.brain, &pipeline, &BTreeMap::new(), None).await {
+                Ok(report) if report.status != RunStatus::Completed => {
+                    if let Some(tg) = &ctx.telegram {
+                        let _ = tg.send_raw(&format!("🧪 Scheduled pipeline {} {}.{}", pipeline.name, report.status.as_str(), report.journal_entry().trim_end())).await;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => error!("Scheduler: pipeline {} error: {}", pipeline.name, e),
+            }
+            running.lock().unwrap_or_else(|e| e.into_inner()).remove(&pipeline.name);
+        });
+    }
+
+    /// Run a job in the background; failures go through the retry policy
+    fn spawn(&self, job: ScheduledJob, retry: Option<PendingRetry>) {
+        let ctx = self.clone();
+        tokio::spawn(async move {
+            let attempt = retry.as_ref().map(|r| r.attempt).unwrap_or(0);
+            match ctx.execute(job).await {
+                Ok(()) => ctx.retries.succeeded(job, attempt).await,
+                Err(e) => {
+                    let error = e.to_string();
+                    error!("Scheduler: {} error: {}", job.name(), error);
+                    let policy = RetryPolicy::for_job(job, &ctx.brain.config);
+                    match ctx.retries.failed(job, attempt, retry.map(|r| r.first_failed), &error, policy).await {
+                        FailureOutcome::Retrying { attempt, due } => {
+                            info!("🔁 Scheduler: {} retry {}/{} at {}", job.name(), attempt, policy.retries, due.format("%H:%M"));
+                        }
+                        FailureOutcome::Postponed { due } => {
+                            info!("⏳ Scheduler: {} rate limited, postponed to {}", job.name(), due.format("%H:%M"));
+                        }
+                        FailureOutcome::Exhausted { attempts } => {
+                            error!("Scheduler: {} gave up after {} retries", job.name(), attempts);
+                            // Without a retry policy a failure is only logged, as before
+                            if policy.retries > 0
+                                && let Some(tg) = &ctx.telegram
+                            {
+                                let _ = tg.send_raw(&format!("🚨 Scheduled {} failed after {} retries.\nLast error: {}", job.name(), attempts, error)).await;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
 impl SporeScheduler {
     pub async fn start(brain: Brain, memory: MemorySystem, telegram: Option<TelegramChannel>) {
         info!("🕒 Spore Scheduler: Background engine spinning up...");
 
-        let mut last_heartbeat = std::time::Instant::now();
+        // A misconfigured schedule runs none of the tasks until fixed and reloaded
+        let schedule = Schedule::from_config(&brain.config).unwrap_or_else(|e| {
+            error!("🕒 Spore Scheduler: {}. Scheduled tasks are off until it is fixed (/reload).", e);
+            event_bus::publish(SystemEvent::InboxMessage { source: "scheduler".to_string(), text: format!("{}. Scheduled tasks are off until it is fixed (/reload).", e) });
+            Schedule::default()
+        });
+        info!("🕒 Spore Scheduler: {}", describe(&schedule));
 
-        let now = chrono::Local::now();
-        let today = now.format("%Y-%m-%d").to_string();
-        // If we start during the journal hour (22:00), don't trigger immediately
-        let mut last_journal_day = if now.hour() >= 22 {
-            today
-        } else {
-            String::new()
+        let ctx = JobContext {
+            retries: RetryQueue::new(&memory.project_root),
+            brain,
+            memory: memory.clone(),
+            telegram,
+            quiet_hours: Arc::new(RwLock::new(schedule.quiet_hours)),
         };
+        let config = &ctx.brain.config;
+        RUNNING.store(true, Ordering::SeqCst);
+        follow_config(describe(&schedule));
+        ctx.spawn_runner(schedule);
 
+        let mut last_index: Option<std::time::Instant> = None;
+        let mut last_prune: Option<std::time::Instant> = None;
+        let mut last_reap: Option<std::time::Instant> = None;
+        let mut last_consolidation_day = String::new();
+        let pipeline_store = PipelineStore::new(&memory.memory_root);
+        let mut pipeline_due: HashMap<String, (String, DateTime<Local>)> = HashMap::new();
+        let running_pipelines = Arc::new(Mutex::new(HashSet::new()));
+
+        // Maintenance and due retries are checked every minute
+        let mut ticker = tokio::time::interval(Duration::from_secs(60));
         loop {
+            ticker.tick().await;
             let now = chrono::Local::now();
             let today = now.format("%Y-%m-%d").to_string();
 
-            // 1. Heartbeat every 2 hours
-            if last_heartbeat.elapsed() >= Duration::from_secs(2 * 3600) {
-                let brain_ref = brain.clone();
-                let memory_ref = memory.clone();
-                let telegram_ref = telegram.clone();
+            // Retries that are due (persisted, so they survive a restart)
+            for retry in ctx.retries.take_due(now).await {
+                info!("🔁 Spore Scheduler: Retrying {} (attempt {})", retry.job.name(), retry.attempt);
+                ctx.spawn(retry.job, Some(retry));
+            }
 
+            // 1. Workspace INDEX.md every hour (incremental, no LLM)
+            if last_index.is_none_or(|t| t.elapsed() >= Duration::from_secs(3600)) {
+                let memory_ref = memory.clone();
                 tokio::spawn(async move {
-                    if let Err(e) = Heartbeat::run(&brain_ref, &memory_ref, telegram_ref.as_ref()).await {
-                        error!("Scheduler: Heartbeat error: {}", e);
+                    if let Err(e) = memory_ref.regenerate_index(false).await {
+                        error!("Scheduler: Index error: {}", e);
                     }
                 });
-                last_heartbeat = std::time::Instant::now();
+                last_index = Some(std::time::Instant::now());
             }
 
-            // 2. Daily Journal at 22:00
-            if now.hour() == 22 && last_journal_day != today {
-                info!("🕒 Spore Scheduler: Time for daily synthesis!");
-                let brain_ref = brain.clone();
+            // 2. Archive expired learned memories, drop old tombstones, hold
+            //    screenshots/downloads to their limits and, without git, snapshot
+            //    the workspace once a day
+            if last_prune.is_none_or(|t| t.elapsed() >= Duration::from_secs(24 * 3600)) {
                 let memory_ref = memory.clone();
-                let telegram_ref = telegram.clone();
-
+                let (screenshots, downloads) = (config.screenshots_retention(), config.downloads_retention());
                 tokio::spawn(async move {
-                    match DailyJournal::run(&brain_ref, &memory_ref).await {
-                        Ok(Some(path)) => {
-                            if let Some(tg) = telegram_ref {
-                                let _ = tg.send_raw(&format!("📓 Daily Journal synthesized: {}", path.display())).await;
-                            }
-                        },
-                        Ok(None) => {},
-                        Err(e) => error!("Scheduler: Journal error: {}", e),
+                    if let Err(e) = memory_ref.prune_expired().await {
+                        error!("Scheduler: Prune error: {}", e);
+                    }
+                    if let Err(e) = memory_ref.purge_tombstones() {
+                        error!("Scheduler: Tombstone purge error: {}", e);
+                    }
+                    let _ = tokio::task::spawn_blocking(move || {
+                        memory_ref.housekeep_media(screenshots, downloads, false);
+                        if !memory_ref.versioned {
+                            memory_ref.commit("Daily snapshot");
+                        }
+                    }).await;
+                });
+                last_prune = Some(std::time::Instant::now());
+            }
+
+            // 3. Reap orphaned sub-spores every 10 minutes (terminating one blocks)
+            if last_reap.is_none_or(|t| t.elapsed() >= Duration::from_secs(600)) {
+                tokio::task::spawn_blocking(|| {
+                    let report = openspore_swarm::SwarmManager::new().reap(openspore_swarm::REAP_MAX_AGE);
+                    if !report.killed.is_empty() || !report.failed.is_empty() {
+                        info!("🐝 Scheduler: Reaped {} orphaned sub-spore(s), {} would not stop", report.killed.len(), report.failed.len());
                     }
                 });
-                last_journal_day = today;
+                last_reap = Some(std::time::Instant::now());
+            }
+
+            // 4. Fold old exchanges into daily digests at 03:00 (CONSOLIDATE_AFTER_DAYS=0 disables)
+            if now.hour() == 3 && last_consolidation_day != today {
+                if let Some(older_than) = ctx.brain.config.consolidate_after() {
+                    let brain = ctx.brain.clone();
+                    let memory_ref = memory.clone();
+                    tokio::spawn(async move {
+                        let max_files = brain.config.consolidate_max_files();
+                        if let Err(e) = memory_ref.consolidate(&brain, older_than, max_files, false).await {
+                            error!("Scheduler: Consolidation error: {}", e);
+                        }
+                    });
+                }
+                last_consolidation_day = today;
             }
 
-            // Sleep for 1 minute
-            sleep(Duration::from_secs(60)).await;
+            // 5. Pipelines with a schedule, re-read every minute so edits apply
+            //    (a changed expression starts counting anew)
+            let scheduled = pipeline_store.scheduled();
+            pipeline_due.retain(|name, _| scheduled.iter().any(|p| &p.name == name));
+            for pipeline in scheduled {
+                let Some(trigger) = pipeline.trigger() else { continue };
+                let expression = pipeline.schedule.clone().unwrap_or_default();
+                let due = match pipeline_due.get(&pipeline.name) {
+                    Some((known, due)) if *known == expression => *due,
+                    _ => match trigger.next_run(now) {
+                        Some(due) => {
+                            pipeline_due.insert(pipeline.name.clone(), (expression, due));
+                            continue;
+                        }
+                        None => continue,
+                    },
+                };
+                if due > now {
+                    continue;
+                }
+                if let Some(next) = trigger.next_run(now) {
+                    pipeline_due.insert(pipeline.name.clone(), (expression, next));
+                }
+                ctx.spawn_pipeline(pipeline, running_pipelines.clone());
+            }
         }
     }
 }
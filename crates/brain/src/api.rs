@@ -1,21 +1,200 @@
 use crate::{Brain, Message};
-use tracing::warn;
+use crate::tool_calling::{parse_tool_calls, Completion};
+use openspore_core::config::LlmProvider;
+use tracing::{info, warn};
+
+/// Cap on a short reply (classifiers): token budget and stop sequences
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReplyLimit {
+    pub max_tokens: u32,
+    pub stop: &'static [&'static str],
+}
+
+/// Why a completion failed, as far as the request tells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmErrorKind {
+    /// No API key, or the endpoint rejected it (401/403)
+    Auth,
+    /// The endpoint doesn't serve the model (404, "model not found"...)
+    ModelNotFound,
+    /// Nothing answered: DNS, connection refused, timeout
+    Network,
+    /// 429 after the retries
+    RateLimited,
+    /// Server errors, unreadable replies and the rest
+    Other,
+}
+
+/// A failed completion. Carried inside the anyhow::Error of the completion
+/// calls: `LlmError::kind_of(&e)` reads it back.
+#[derive(Debug)]
+pub struct LlmError {
+    pub kind: LlmErrorKind,
+    pub message: String,
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for LlmError {}
+
+impl LlmError {
+    fn error(kind: LlmErrorKind, message: impl Into<String>) -> anyhow::Error {
+        anyhow::Error::new(LlmError { kind, message: message.into() })
+    }
+
+    /// Kind of a completion error; Other for errors that didn't come from a request
+    pub fn kind_of(e: &anyhow::Error) -> LlmErrorKind {
+        e.downcast_ref::<LlmError>().map_or(LlmErrorKind::Other, |e| e.kind)
+    }
+}
+
+/// Outcome of a failed attempt against a single model
+enum AttemptError {
+    /// The model is missing or has no provider: the next fallback may succeed
+    ModelUnavailable(anyhow::Error),
+    /// Auth, malformed request, network... another model won't help
+    Fatal(anyhow::Error),
+}
+
+/// 404, or an error body saying the model itself can't be served
+fn is_model_unavailable(status: u16, body: &str) -> bool {
+    if status == 404 {
+        return true;
+    }
+    let body = body.to_lowercase();
+    matches!(status, 400 | 502 | 503)
+        && body.contains("model")
+        && ["not found", "not a valid model", "unavailable", "no endpoints", "no allowed providers", "does not exist"]
+            .iter()
+            .any(|m| body.contains(m))
+}
 
 impl Brain {
     /// LLM API Call with Retry Logic (Exponential Backoff)
+    /// Usage of standalone calls is recorded as "auxiliary".
     pub async fn complete(&self, messages: &[Message]) -> anyhow::Result<String> {
-        let model = self.get_model();
-        let api_key = std::env::var("OPENROUTER_API_KEY")?;
+        let (content, usage) = self.complete_with_usage(messages).await?;
+        self.record_usage("auxiliary", &usage);
+        Ok(content)
+    }
+
+    /// Same as complete(), but hands the token usage back to the caller
+    pub(crate) async fn complete_with_usage(&self, messages: &[Message]) -> anyhow::Result<(String, crate::usage::TokenUsage)> {
+        let completion = self.complete_turn(messages, None).await?;
+        Ok((completion.content, completion.usage))
+    }
+
+    /// Full completion: optionally sends a `tools` array and parses structured tool calls.
+    /// When the model is unavailable, the configured fallback models are tried in order.
+    pub(crate) async fn complete_turn(&self, messages: &[Message], tools: Option<&serde_json::Value>) -> anyhow::Result<Completion> {
+        let chain = self.model_chain();
+        let mut last_error = None;
+
+        for (i, model) in chain.iter().enumerate() {
+            match self.complete_with_model(model, messages, tools, None).await {
+                Ok(completion) => {
+                    if i > 0 {
+                        info!("🔀 Fallback model {} answered ({} unavailable)", model, chain[0]);
+                    }
+                    return Ok(completion);
+                }
+                Err(AttemptError::ModelUnavailable(e)) => {
+                    warn!("⚠️ Model {} unavailable: {}", model, e);
+                    last_error = Some(e);
+                }
+                Err(AttemptError::Fatal(e)) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No model configured")))
+    }
+
+    /// One completion on the Fast model (no fallbacks: callers treat failure as "skip")
+    pub(crate) async fn complete_fast(&self, messages: &[Message]) -> anyhow::Result<(String, crate::usage::TokenUsage)> {
+        self.complete_fast_limited(messages, None).await
+    }
+
+    /// complete_fast() with the reply held to `limit`
+    pub(crate) async fn complete_fast_limited(&self, messages: &[Message], limit: Option<ReplyLimit>) -> anyhow::Result<(String, crate::usage::TokenUsage)> {
+        match self.complete_with_model(&self.get_fast_model(), messages, None, limit).await {
+            Ok(completion) => Ok((completion.content, completion.usage)),
+            Err(AttemptError::ModelUnavailable(e) | AttemptError::Fatal(e)) => Err(e),
+        }
+    }
+
+    /// Primary model followed by its fallbacks (deduplicated, in order)
+    pub(crate) fn model_chain(&self) -> Vec<String> {
+        let config = self.llm_config();
+        let mut chain = vec![self.get_model()];
+        for model in config.model_fallbacks() {
+            if !chain.contains(&model) {
+                chain.push(model);
+            }
+        }
+        chain
+    }
+
+    async fn complete_with_model(&self, model: &str, messages: &[Message], tools: Option<&serde_json::Value>, limit: Option<ReplyLimit>) -> Result<Completion, AttemptError> {
+        let config = self.llm_config();
+        let provider = config.provider();
 
         // Lower temperature for deterministic code generation (0.7 was too creative/inconsistent)
-        let temp = 0.2;
+        let temp = config.llm_temperature.unwrap_or(0.2);
+        let top_p = config.llm_top_p;
 
-        let body = serde_json::json!({
-            "model": model,
-            "messages": messages,
-            "temperature": temp,
-            "max_tokens": 16384,
-        });
+        let (url, mut body, api_key) = match provider {
+            LlmProvider::Ollama => {
+                let mut body = serde_json::json!({
+                    "model": model,
+                    "messages": messages,
+                    "stream": false,
+                    "options": { "temperature": temp },
+                });
+                if let Some(top_p) = top_p {
+                    body["options"]["top_p"] = top_p.into();
+                }
+                if let Some(limit) = limit {
+                    body["options"]["num_predict"] = limit.max_tokens.into();
+                    body["options"]["stop"] = serde_json::json!(limit.stop);
+                }
+                (config.ollama_chat_url(), body, String::new())
+            }
+            LlmProvider::OpenRouter => {
+                let api_key = config.openrouter_api_key.clone();
+                let mut body = serde_json::json!({
+                    "model": model,
+                    "messages": messages,
+                    "temperature": temp,
+                    "max_tokens": config.llm_max_tokens(),
+                    "usage": { "include": true },
+                });
+                if let Some(top_p) = top_p {
+                    body["top_p"] = top_p.into();
+                }
+                if let Some(limit) = limit {
+                    body["max_tokens"] = limit.max_tokens.into();
+                    body["stop"] = serde_json::json!(limit.stop);
+                }
+                (config.completions_url(), body, api_key)
+            }
+        };
+
+        if let Some(tools) = tools.filter(|t| t.as_array().is_some_and(|a| !a.is_empty())) {
+            body["tools"] = tools.clone();
+        }
+
+        // Local servers (ollama, llama.cpp, vLLM) don't need or reject bearer tokens
+        let is_local = provider == LlmProvider::Ollama || config.is_local_llm();
+        let send_auth = !(api_key.is_empty() && is_local);
+        if !send_auth {
+            tracing::debug!("Local LLM endpoint without key: omitting Authorization header");
+        } else if api_key.is_empty() {
+            return Err(AttemptError::Fatal(LlmError::error(LlmErrorKind::Auth, format!("OPENROUTER_API_KEY is not set (required for {})", url))));
+        }
 
         // Retry logic: 3 attempts with exponential backoff
         let mut attempts = 0;
@@ -24,20 +203,38 @@ impl Brain {
         loop {
             attempts += 1;
 
-            let res = self.client.post("https://openrouter.ai/api/v1/chat/completions")
-                .header("Authorization", format!("Bearer {}", api_key))
+            let mut req = self.client.post(&url);
+            if send_auth {
+                req = req.header("Authorization", format!("Bearer {}", api_key));
+            }
+
+            let res = req
                 .header("Content-Type", "application/json")
                 .header("HTTP-Referer", "https://openspore.net")
                 .header("X-Title", "OpenSpore")
                 .json(&body)
                 .send()
-                .await?;
+                .await
+                .map_err(|e| AttemptError::Fatal(self.map_request_error(e)))?;
 
             let status = res.status();
 
             if status.is_success() {
-                let json: serde_json::Value = res.json().await?;
-                return Ok(json["choices"][0]["message"]["content"].as_str().unwrap_or("").to_string());
+                let json: serde_json::Value = res.json().await
+                    .map_err(|e| AttemptError::Fatal(LlmError::error(LlmErrorKind::Other, format!("Unreadable reply: {}", e))))?;
+                let (message, usage) = match provider {
+                    LlmProvider::Ollama => (&json["message"], crate::usage::TokenUsage::from_ollama_response(&json)),
+                    LlmProvider::OpenRouter => (&json["choices"][0]["message"], crate::usage::TokenUsage::from_response(&json)),
+                };
+                let (tool_calls, raw_tool_calls) = parse_tool_calls(message);
+
+                return Ok(Completion {
+                    content: message["content"].as_str().unwrap_or("").to_string(),
+                    tool_calls,
+                    raw_tool_calls,
+                    usage,
+                    model: model.to_string(),
+                });
             }
 
             // Handle retryable errors (429 Too Many Requests, 500+ Server Errors)
@@ -49,17 +246,191 @@ impl Brain {
             }
 
             // Non-retryable error or max attempts reached
-            return Err(anyhow::anyhow!("API Error: {} (after {} attempts)", status, attempts));
+            let detail = res.text().await.unwrap_or_default();
+            let message = format!("API Error: {} (after {} attempts)", status, attempts);
+            return Err(match status.as_u16() {
+                code if is_model_unavailable(code, &detail) => AttemptError::ModelUnavailable(LlmError::error(LlmErrorKind::ModelNotFound, message)),
+                401 | 403 => AttemptError::Fatal(LlmError::error(LlmErrorKind::Auth, message)),
+                429 => AttemptError::Fatal(LlmError::error(LlmErrorKind::RateLimited, message)),
+                _ => AttemptError::Fatal(LlmError::error(LlmErrorKind::Other, message)),
+            });
+        }
+    }
+
+    pub(crate) fn map_request_error(&self, e: reqwest::Error) -> anyhow::Error {
+        if e.is_timeout() {
+            LlmError::error(LlmErrorKind::Network, format!("LLM request timed out after {}s", self.config.llm_timeout().as_secs()))
+        } else if e.is_connect() || e.is_request() {
+            LlmError::error(LlmErrorKind::Network, e.to_string())
+        } else {
+            e.into()
         }
     }
 
     pub(crate) fn get_model(&self) -> String {
-        self.config.model.clone().unwrap_or("google/gemini-2.0-flash-001".to_string())
+        if let Some(model) = self.model_override() {
+            return model;
+        }
+        let config = self.llm_config();
+        match config.provider() {
+            LlmProvider::Ollama => config.ollama_model_reasoning.clone()
+                .or_else(|| config.ollama_model_fast.clone())
+                .unwrap_or("llama3.1".to_string()),
+            LlmProvider::OpenRouter => config.model.clone().unwrap_or("google/gemini-2.0-flash-001".to_string()),
+        }
+    }
+
+    /// Model of this session's turns: the `/model` override, else the configured primary model
+    pub fn current_model(&self) -> String {
+        self.get_model()
+    }
+
+    pub fn model_override(&self) -> Option<String> {
+        self.model_override.read().ok().and_then(|m| m.clone())
+    }
+
+    /// Use `model` for the rest of the session (None returns to the configured one).
+    /// Fallbacks still follow it.
+    pub fn set_model_override(&self, model: Option<String>) {
+        if let Ok(mut current) = self.model_override.write() {
+            *current = model.map(|m| m.trim().to_string()).filter(|m| !m.is_empty());
+        }
+    }
+
+    /// Cheap model for auxiliary calls, the primary model when none is configured
+    pub(crate) fn get_fast_model(&self) -> String {
+        let config = self.llm_config();
+        let fast = match config.provider() {
+            LlmProvider::Ollama => config.ollama_model_fast.clone(),
+            LlmProvider::OpenRouter => config.model_fast.clone(),
+        };
+        fast.filter(|m| !m.trim().is_empty()).unwrap_or_else(|| self.get_model())
+    }
+
+    /// The configured Fast model, or the primary one when none is set (`/model fast`)
+    pub fn fast_model(&self) -> String {
+        self.get_fast_model()
     }
 
     /// Simple one-shot thought for other modules (Watchman, etc)
     pub async fn think_simple(&self, prompt: &str) -> String {
-        let msgs = vec![Message{role:"user".to_string(), content: prompt.to_string()}];
+        let msgs = vec![Message{role:"user".to_string(), content: prompt.to_string(), ..Default::default()}];
         self.complete(&msgs).await.unwrap_or_else(|e| e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{config_in, slow_llm};
+    use openspore_core::config::AppConfig;
+    use std::time::{Duration, Instant};
+
+    async fn brain_with_timeout(dir: &tempfile::TempDir, delay: Duration, timeout_secs: u64) -> Brain {
+        let (url, _) = slow_llm(delay, |_| "pong".to_string()).await;
+        Brain::new(AppConfig { llm_timeout_secs: Some(timeout_secs), ..config_in(dir.path(), &url) })
+    }
+
+    /// Answer every request with `status` and `body`
+    async fn failing_llm(status: u16, body: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 8192];
+                    let _ = stream.read(&mut buf).await;
+                    let response = format!(
+                        "HTTP/1.1 {} Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status, body.len(), body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        url
+    }
+
+    async fn error_kind(dir: &tempfile::TempDir, url: &str) -> (LlmErrorKind, String) {
+        let error = Brain::new(config_in(dir.path(), url)).complete(&ping()).await.unwrap_err();
+        (LlmError::kind_of(&error), error.to_string())
+    }
+
+    #[test]
+    fn model_unavailable_bodies() {
+        assert!(is_model_unavailable(404, ""));
+        assert!(is_model_unavailable(400, r#"{"error":"Model foo/bar not found"}"#));
+        assert!(is_model_unavailable(503, "No endpoints found for model x"));
+        assert!(!is_model_unavailable(400, "context length exceeded"), "a bad request about something else");
+        assert!(!is_model_unavailable(500, "model not found"), "only 400/502/503 bodies are read");
+        assert!(!is_model_unavailable(401, "no endpoints"));
+    }
+
+    #[tokio::test]
+    async fn failures_are_classified() {
+        let dir = tempfile::tempdir().unwrap();
+        let rejected = failing_llm(401, r#"{"error":"invalid key"}"#).await;
+        assert_eq!(error_kind(&dir, &rejected).await, (LlmErrorKind::Auth, "API Error: 401 Unauthorized (after 1 attempts)".to_string()));
+        let forbidden = failing_llm(403, "").await;
+        assert_eq!(error_kind(&dir, &forbidden).await.0, LlmErrorKind::Auth);
+        let missing = failing_llm(404, "").await;
+        assert_eq!(error_kind(&dir, &missing).await.0, LlmErrorKind::ModelNotFound);
+        let unserved = failing_llm(400, r#"{"error":"test-model is not a valid model ID"}"#).await;
+        assert_eq!(error_kind(&dir, &unserved).await.0, LlmErrorKind::ModelNotFound);
+        let malformed = failing_llm(400, r#"{"error":"bad messages"}"#).await;
+        assert_eq!(error_kind(&dir, &malformed).await.0, LlmErrorKind::Other);
+
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let refused = format!("http://{}/v1", closed.local_addr().unwrap());
+        drop(closed);
+        assert_eq!(error_kind(&dir, &refused).await.0, LlmErrorKind::Network);
+    }
+
+    #[tokio::test]
+    async fn missing_key_fails_before_calling_a_remote_endpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig { openrouter_api_key: String::new(), ..config_in(dir.path(), "https://llm.invalid/v1") };
+        let error = Brain::new(config).complete(&ping()).await.unwrap_err();
+        assert_eq!(LlmError::kind_of(&error), LlmErrorKind::Auth);
+        assert!(error.to_string().starts_with("OPENROUTER_API_KEY is not set"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_survives_the_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let limited = failing_llm(429, "slow down").await;
+        let (kind, message) = error_kind(&dir, &limited).await;
+        assert_eq!(kind, LlmErrorKind::RateLimited);
+        assert!(message.ends_with("(after 3 attempts)"), "{}", message);
+    }
+
+    #[test]
+    fn errors_outside_a_request_are_other() {
+        assert_eq!(LlmError::kind_of(&anyhow::anyhow!("disk full")), LlmErrorKind::Other);
+    }
+
+    fn ping() -> Vec<Message> {
+        vec![Message { role: "user".to_string(), content: "ping".to_string(), ..Default::default() }]
+    }
+
+    #[tokio::test]
+    async fn slow_endpoint_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_with_timeout(&dir, Duration::from_secs(5), 1).await;
+
+        let started = Instant::now();
+        let error = brain.complete(&ping()).await.unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(4), "gave up after the timeout, without retrying");
+        assert_eq!(LlmError::kind_of(&error), LlmErrorKind::Network);
+        assert_eq!(error.to_string(), "LLM request timed out after 1s");
+    }
+
+    #[tokio::test]
+    async fn answer_within_the_timeout_arrives() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_with_timeout(&dir, Duration::from_millis(200), 2).await;
+        assert_eq!(brain.complete(&ping()).await.unwrap(), "pong");
+    }
+}
@@ -0,0 +1,203 @@
+//! Approval gate for tools (APPROVAL_MODE)
+//!
+//! Before a gated tool runs, the tool loop sends BrainEvent::ApprovalRequest to
+//! the observer and waits until the front-end calls respond() with its id. A
+//! denial goes back to the model as the tool's error so it can re-plan.
+//! Cycles without an observer (cron, autonomy, sub-spores, `openspore think`)
+//! have nobody to ask: they run only what APPROVAL_ALLOW lists. Calls a skill
+//! marks as requiring approval (messages to other people) are gated even with
+//! APPROVAL_MODE=off, and "always allow" does not cover them.
+
+use crate::events::BrainEvent;
+use openspore_core::config::AppConfig;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{info, warn};
+
+/// Unanswered requests are denied after this long
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApprovalMode {
+    #[default]
+    Off,
+    /// Only skills marked destructive (exec, file writes, purge...)
+    Destructive,
+    All,
+}
+
+impl ApprovalMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalMode::Off => "off",
+            ApprovalMode::Destructive => "destructive",
+            ApprovalMode::All => "all",
+        }
+    }
+}
+
+impl std::str::FromStr for ApprovalMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "" => Ok(ApprovalMode::Off),
+            "destructive" => Ok(ApprovalMode::Destructive),
+            "all" => Ok(ApprovalMode::All),
+            other => Err(format!("Unknown approval mode: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Approve,
+    /// Approve, and stop asking for this tool until the process ends
+    AlwaysAllow,
+    /// With the reason handed to the model
+    Deny(String),
+}
+
+/// One APPROVAL_ALLOW entry: a tool, optionally limited to arguments starting with a prefix
+#[derive(Debug, Clone)]
+struct AllowRule {
+    tool: String,
+    prefix: Option<String>,
+}
+
+/// Shared by all clones of a Brain
+pub struct ApprovalGate {
+    mode: ApprovalMode,
+    unattended_allow: Vec<AllowRule>,
+    pending: Mutex<HashMap<u64, oneshot::Sender<ApprovalDecision>>>,
+    next_id: AtomicU64,
+    /// Tools approved with AlwaysAllow (lowercase)
+    always: Mutex<HashSet<String>>,
+}
+
+impl ApprovalGate {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mode = config.approval_mode.as_deref()
+            .map(|m| m.parse().unwrap_or_else(|e: String| {
+                warn!("🛂 {}, asking before every tool", e);
+                ApprovalMode::All
+            }))
+            .unwrap_or_default();
+        let unattended_allow = config.approval_allow.as_deref().unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(|entry| match entry.split_once(':') {
+                Some((tool, prefix)) => AllowRule { tool: tool.trim().to_lowercase(), prefix: Some(prefix.trim().to_string()) },
+                None => AllowRule { tool: entry.to_lowercase(), prefix: None },
+            })
+            .collect();
+        Self {
+            mode,
+            unattended_allow,
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            always: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn mode(&self) -> ApprovalMode {
+        self.mode
+    }
+
+    /// Whether calls of a skill need approval at all
+    pub fn is_gated(&self, destructive: bool) -> bool {
+        match self.mode {
+            ApprovalMode::Off => false,
+            ApprovalMode::Destructive => destructive,
+            ApprovalMode::All => true,
+        }
+    }
+
+    /// Ok when the call may run, Err with the message for the model otherwise.
+    /// "Always allow" only covers cycles someone watches, not unattended ones.
+    /// `required` gates the call whatever the mode.
+    pub async fn check(&self, name: &str, arg: &str, destructive: bool, required: bool, tx: Option<&mpsc::Sender<BrainEvent>>) -> Result<(), String> {
+        if !required && !self.is_gated(destructive) {
+            return Ok(());
+        }
+
+        let Some(tx) = tx else {
+            if self.allowed_unattended(name, arg) {
+                info!("🛂 {} allowed unattended (APPROVAL_ALLOW)", name);
+                return Ok(());
+            }
+            warn!("🛂 {} denied: nobody can approve it in this run", name);
+            return Err(format!(
+                "DENIED: {} needs the user's approval{} and nobody is present to give it. Do not retry; continue without it or report what you would have done.",
+                name,
+                if required { "" } else { " (APPROVAL_MODE)" }
+            ));
+        };
+        if !required && self.always.lock().unwrap_or_else(|e| e.into_inner()).contains(&name.to_lowercase()) {
+            return Ok(());
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (respond, decision) = oneshot::channel();
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).insert(id, respond);
+        info!("🛂 Waiting for approval of {} (request {})", name, id);
+
+        let request = BrainEvent::ApprovalRequest { id, name: name.to_string(), arg: arg.to_string() };
+        let decision = if tx.send(request).await.is_err() {
+            ApprovalDecision::Deny("the front-end is gone".to_string())
+        } else {
+            match tokio::time::timeout(APPROVAL_TIMEOUT, decision).await {
+                Ok(Ok(decision)) => decision,
+                Ok(Err(_)) => ApprovalDecision::Deny("the request was dropped".to_string()),
+                Err(_) => ApprovalDecision::Deny(format!("no answer within {}", openspore_core::fmt_utils::humanize_duration(APPROVAL_TIMEOUT))),
+            }
+        };
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+
+        match decision {
+            ApprovalDecision::Approve => Ok(()),
+            ApprovalDecision::AlwaysAllow => {
+                info!("🛂 {} allowed for the rest of the session", name);
+                self.always.lock().unwrap_or_else(|e| e.into_inner()).insert(name.to_lowercase());
+                Ok(())
+            }
+            ApprovalDecision::Deny(reason) => {
+                info!("🛂 {} denied: {}", name, reason);
+                Err(format!("DENIED by the user: {}. Do not retry the same call; re-plan with this in mind.", reason))
+            }
+        }
+    }
+
+    /// Answer a pending request. False when it is unknown (answered or timed out).
+    pub fn respond(&self, id: u64, decision: ApprovalDecision) -> bool {
+        let Some(respond) = self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id) else { return false };
+        respond.send(decision).is_ok()
+    }
+
+    /// Tools approved with AlwaysAllow, sorted
+    pub fn always_allowed(&self) -> Vec<String> {
+        let mut tools: Vec<String> = self.always.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect();
+        tools.sort();
+        tools
+    }
+
+    /// Ask again for every tool
+    pub fn reset_always_allowed(&self) {
+        self.always.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    fn allowed_unattended(&self, name: &str, arg: &str) -> bool {
+        let name = name.to_lowercase();
+        let arg = arg.trim().trim_matches('"').trim_start();
+        self.unattended_allow.iter().any(|rule| {
+            rule.tool == name && rule.prefix.as_deref().is_none_or(|p| {
+                // A prefix vouches for one command, not for whatever is chained after it
+                arg.starts_with(p) && !arg[p.len()..].contains([';', '&', '|', '`', '$', '>', '<', '\n'])
+            })
+        })
+    }
+}
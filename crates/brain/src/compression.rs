@@ -24,7 +24,7 @@ CONSTRUCT A REPLACEMENT SUMMARY THAT:
 
 Return only the new summary text."#, current, new_items);
 
-             match self.complete(&[Message{role:"user".into(), content: compress_prompt}]).await {
+             match self.complete(&[Message{role:"user".into(), content: compress_prompt, ..Default::default()}]).await {
                  Ok(compressed) => {
                      info!("📦 Compressed {} chars -> {} chars", current.len() + new_items.len(), compressed.len());
                      Ok(compressed)
@@ -37,3 +37,28 @@ Return only the new summary text."#, current, new_items);
         })
     }
 }
+
+impl openspore_memory::DigestWriter for Brain {
+    fn digest<'a>(&'a self, day: &'a str, exchanges: &'a str) -> std::pin::Pin<Box<dyn std::future::Future<Output = anyhow::Result<String>> + Send + 'a>> {
+        Box::pin(async move {
+            let digest_prompt = format!(r#"Condense the conversation exchanges of {day} into a daily digest that will replace them in long-term memory.
+
+<EXCHANGES>
+{exchanges}
+</EXCHANGES>
+
+WRITE MARKDOWN THAT:
+1. Lists the topics, decisions, facts learned and open items of the day as short bullets
+2. Keeps names, paths, commands and numbers that may be looked up later
+3. Drops greetings, retries and anything already resolved
+4. STRICTLY stays under 400 words
+
+Return only the digest (no title)."#);
+
+            let (digest, usage) = self.complete_fast(&[Message{role:"user".into(), content: digest_prompt, ..Default::default()}]).await?;
+            self.record_usage("digest", &usage);
+            info!("🗜️ Digest of {}: {} chars -> {} chars", day, exchanges.len(), digest.len());
+            Ok(digest)
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{Brain, Message};
+use openspore_memory::ConflictVersions;
+
+impl Brain {
+    /// Proposed merge of both versions of a sync-conflicted memory, as the full
+    /// file (frontmatter included). Nothing is written; the caller shows it for approval.
+    pub async fn merge_conflict(&self, versions: &ConflictVersions) -> anyhow::Result<String> {
+        let prompt = format!(r#"The same memory file was edited on two devices and the edits overlap. Merge both versions into one file.
+
+VERSION ON THIS DEVICE:
+{}
+
+INCOMING VERSION:
+{}
+
+RULES:
+1. Keep every fact that appears in either version; where they contradict, prefer the more specific or more recent statement and drop the other
+2. Keep the YAML frontmatter block (between --- lines) at the top, combining tags of both versions
+3. Do not add commentary about the merge
+
+Return only the merged file content."#, versions.ours.trim_end(), versions.theirs.trim_end());
+
+        let merged = self.complete(&[Message { role: "user".into(), content: prompt, ..Default::default() }]).await?;
+        Ok(strip_fence(&merged))
+    }
+}
+
+/// Models like to wrap whole files in a ``` block
+fn strip_fence(text: &str) -> String {
+    let trimmed = text.trim();
+    let unfenced = trimmed.strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(|body| body.split_once('\n').map(|(_lang, body)| body).unwrap_or(body));
+    format!("{}\n", unfenced.unwrap_or(trimmed).trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{brain_in, fake_llm};
+    use openspore_memory::{conflict_document, Resolution};
+
+    const OURS: &str = "---\ntype: preferences\ntags: [editor]\n---\n\n# Editor setup\n\nTheme: solarized dark\n";
+    const THEIRS: &str = "---\ntype: preferences\ntags: [editor, laptop]\n---\n\n# Editor setup\n\nTheme: gruvbox\n";
+    const MERGED: &str = "---\ntype: preferences\ntags: [editor, laptop]\n---\n\n# Editor setup\n\nTheme: gruvbox on the laptop, solarized dark on the desktop\n";
+
+    #[test]
+    fn strip_fence_unwraps_whole_file_blocks_only() {
+        assert_eq!(strip_fence("```markdown\n# A\ntext\n```"), "# A\ntext\n");
+        assert_eq!(strip_fence("```\n# A\n```\n"), "# A\n");
+        assert_eq!(strip_fence("# A\n\n```sh\nls\n```\nafter"), "# A\n\n```sh\nls\n```\nafter\n");
+    }
+
+    #[tokio::test]
+    async fn the_llm_proposal_is_only_written_once_approved() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, requests) = fake_llm(|_| format!("```markdown\n{}```", MERGED)).await;
+        let brain = brain_in(dir.path(), &url).await;
+        let path = brain.memory.category_dir("preferences").join("editor_setup.md");
+        std::fs::write(&path, conflict_document(OURS, THEIRS, "preferences/editor_setup.md")).unwrap();
+
+        let versions = brain.memory.read_conflict(&path).unwrap();
+        let proposal = brain.merge_conflict(&versions).await.unwrap();
+        assert_eq!(proposal, MERGED);
+        assert_eq!(requests.len(), 1);
+        let prompt = requests.message(0, "user");
+        assert!(prompt.contains("Theme: solarized dark") && prompt.contains("Theme: gruvbox"));
+        assert!(brain.memory.read_conflict(&path).is_ok(), "proposing writes nothing");
+
+        brain.memory.resolve_conflict(&path, Resolution::Merged(proposal)).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), MERGED);
+        assert!(brain.memory.conflicts().is_empty());
+    }
+}
@@ -2,17 +2,43 @@ use crate::Brain;
 
 pub struct ContextAssembler;
 
+/// Fixed text of the main prompt (preamble, prime directive, tags) in tokens
+const PROMPT_FRAME_TOKENS: usize = 700;
+
+/// Fixed text of the slim (general chat) prompt in tokens
+const SLIM_FRAME_TOKENS: usize = 150;
+
+/// The <CONTEXT_STATS> block, rendered after the budget is known
+const CONTEXT_STATS_TOKENS: usize = 150;
+
+use crate::context_budget::{count_tokens, BudgetSection, ContextBudget, Section};
+use crate::prompt_mode::PromptMode;
+use openspore_memory::SearchResult;
 use openspore_memory::context::WorkingContext;
 
+/// Glossary entries whose term or an alias appears in the message
+fn glossary_excerpt(brain: &Brain, user_prompt: &str) -> String {
+    let lines = brain.memory.glossary().excerpt(user_prompt, openspore_memory::glossary::MAX_EXCERPT_ENTRIES);
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("<GLOSSARY>\nThe user's canonical terms. Use these spellings.\n{}\n</GLOSSARY>", lines.join("\n"))
+}
+
 impl ContextAssembler {
-    pub async fn build_system_prompt(brain: &Brain, user_prompt: &str) -> (String, WorkingContext) {
+    /// The prompt, the session context, and the memories put in it (for citation tracking)
+    pub async fn build_system_prompt(brain: &Brain, user_prompt: &str, is_private: bool, mode: PromptMode) -> (String, WorkingContext, Vec<SearchResult>) {
+        if mode == PromptMode::Slim {
+            return Self::build_slim_prompt(brain, user_prompt, is_private).await;
+        }
+
         // 1. Context Loading
         let ctx_fut = brain.context_manager.get_working_context(Some(brain));
         let memory_fut = brain.memory.search_memories(user_prompt, 3);
         let prefs_fut = async { brain.memory.get_memories("preferences") };
         let identity_fut = async { brain.memory.get_memories("identity") };
 
-        let (session_ctx_res, relevant, prefs, identity) = tokio::join!(
+        let (session_ctx_res, mut relevant, prefs, identity) = tokio::join!(
             ctx_fut,
             memory_fut,
             prefs_fut,
@@ -20,40 +46,35 @@ impl ContextAssembler {
         );
 
         let session_ctx = session_ctx_res.unwrap_or_default();
+        // The glossary gets in as an excerpt (below), not as a whole file
+        let glossary_path = brain.memory.glossary_path();
+        relevant.retain(|s| s.path != glossary_path);
+        let is_spore = brain.spore.is_spore;
         let project_root = brain.config.project_root.display().to_string();
 
         // 2. Format Context
-        let skills = if std::env::var("IS_SPORE").is_ok() {
-            // Sub-spores get all tools EXCEPT 'delegate' to prevent recursion
-            brain.skill_loader.get_system_prompt(&["delegate"])
-        } else {
-             // Parent spore gets everything
-            brain.skill_loader.get_system_prompt(&[])
-        };
+        let excluded = brain.excluded_skills();
+        let excluded: Vec<&str> = excluded.iter().map(String::as_str).collect();
+        let skills = brain.skills().get_tiered_system_prompt(&excluded, user_prompt);
         let time = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
 
-        let summary_str = if !session_ctx.summary.is_empty() && session_ctx.summary != "No session summary available." {
-            format!("<SESSION_SUMMARY>\n{}\n</SESSION_SUMMARY>", session_ctx.summary)
-        } else { "".to_string() };
-
-        let recent_str = if !session_ctx.recent.is_empty() {
-            format!("<RECENT_HISTORY>\n{}\n</RECENT_HISTORY>", session_ctx.recent)
-        } else { "".to_string() };
-
-        let knowledge_str = if !relevant.is_empty() {
-             let items = relevant.iter().map(|s| format!("--- File: {} ---\n{}", s.title, s.content)).collect::<Vec<_>>().join("\n\n");
-             format!("<RELEVANT_KNOWLEDGE>\n{}\n</RELEVANT_KNOWLEDGE>", items)
+        let privacy_str = if is_private {
+            "<PRIVATE_TURN>\nThis exchange is private and will NOT be persisted. Do not save memories, write notes, or record anything from it to the workspace.\n</PRIVATE_TURN>".to_string()
         } else { "".to_string() };
 
-        let prefs_str = if !prefs.is_empty() {
-            let items = prefs.iter().take(5).map(|m| format!("- {}", m.content)).collect::<Vec<_>>().join("\n");
-            format!("<USER_PREFERENCES>\n{}\n</USER_PREFERENCES>", items)
-        } else { "".to_string() };
+        // "new decision record: X" -> point the model at the matching template
+        let templates = brain.memory.list_templates();
+        let template_str = match openspore_memory::templates::detect_template_request(user_prompt, &templates) {
+            Some((template, title)) => {
+                let fields = templates.iter().find(|t| t.name == template).map(|t| t.fields.join(", ")).unwrap_or_default();
+                let args = serde_json::json!({ "template": template, "title": title, "fields": {} });
+                format!("<TEMPLATE_REQUEST>\nThe user is asking for a \"{template}\" note. Create it with [NEW_FROM_TEMPLATE: {args}], filling \"fields\" ({fields}) from the conversation. Do not invent a different format.\n</TEMPLATE_REQUEST>")
+            }
+            None => String::new(),
+        };
 
-        let identity_str = if !identity.is_empty() {
-            let items = identity.iter().map(|m| m.content.clone()).collect::<Vec<_>>().join("\n\n");
-            format!("<IDENTITY>\n{}\n</IDENTITY>", items)
-        } else { "".to_string() };
+        // The user's vocabulary for the terms in this message
+        let glossary_str = glossary_excerpt(brain, user_prompt);
 
         // 3. Dynamic File System Awareness
         let virtual_cwd = openspore_skills::utils::get_virtual_cwd();
@@ -61,12 +82,55 @@ impl ContextAssembler {
         let relative_cwd = virtual_cwd.strip_prefix(project_root_path).unwrap_or(&virtual_cwd);
         let fs_tree = openspore_core::path_utils::get_directory_tree(&virtual_cwd, 2);
 
-        let fs_str = format!("<FILE_SYSTEM_PULSE>\nCURRENT_LOCATION: {} (Relative to Root: ./{})\nSTRUCTURE_AT_LOCATION (Depth 2):\n{}\n</FILE_SYSTEM_PULSE>",
-            virtual_cwd.display(), relative_cwd.display(), fs_tree);
+        let fs_header = format!("CURRENT_LOCATION: {} (Relative to Root: ./{})\nSTRUCTURE_AT_LOCATION (Depth 2):", virtual_cwd.display(), relative_cwd.display());
+
+        // 4. Token budget: trim the sections, least important first, so the
+        // prompt and the reply fit the smallest window of the model chain
+        let followups_str = if brain.suggestion_mode() == crate::suggestions::SuggestionMode::Trailer {
+            crate::suggestions::TRAILER_INSTRUCTION.to_string()
+        } else { "".to_string() };
+        // Sub-spores' lean prompt has no summary, preferences or identity
+        let has_summary = !is_spore && !session_ctx.summary.is_empty() && session_ctx.summary != "No session summary available.";
+        let (prefs, identity) = if is_spore { (Vec::new(), Vec::new()) } else { (prefs, identity) };
+        let mut sections = [
+            BudgetSection::new(Section::FileSystem, fs_tree.lines().map(str::to_string).collect(), false),
+            BudgetSection::new(Section::Knowledge, relevant.iter().map(|s| {
+                // Both sides of a sync conflict: neither may be quoted as fact
+                let flag = if s.is_conflicted() { " [UNRESOLVED SYNC CONFLICT: two versions, do not treat either as current]" } else { "" };
+                format!("--- File: {}{} ---\n{}", s.title, flag, s.content)
+            }).collect(), false),
+            BudgetSection::new(Section::Summary, if has_summary { session_ctx.summary.lines().map(str::to_string).collect() } else { Vec::new() }, false),
+            BudgetSection::new(Section::RecentTurns, session_ctx.recent_exchanges.clone(), true),
+            BudgetSection::new(Section::Preferences, prefs.iter().take(5).map(|m| format!("- {}", m.content)).collect(), false),
+            BudgetSection::new(Section::Identity, identity.iter().map(|m| m.content.clone()).collect(), false),
+        ];
+        let fixed_tokens = PROMPT_FRAME_TOKENS + CONTEXT_STATS_TOKENS
+            + [&skills, &fs_header, &template_str, &privacy_str, &followups_str, &glossary_str].iter().map(|p| count_tokens(p)).sum::<usize>()
+            // The request is sent twice: in the prompt and as the user message
+            + 2 * count_tokens(user_prompt);
+        let budget = ContextBudget::for_models(&brain.config, &brain.model_chain());
+        let report = budget.fit(fixed_tokens, &mut sections);
+        let [fs_lines, knowledge, summary, recent, prefs_items, identity_items] = sections.map(|s| s.items);
+
+        // Memories cut by the budget were not injected
+        relevant.truncate(knowledge.len());
+        if !is_private {
+            brain.memory.publish_usage(&relevant, false);
+        }
+
+        let wrap = |tag: &str, items: &[String], sep: &str| {
+            if items.is_empty() { String::new() } else { format!("<{tag}>\n{}\n</{tag}>", items.join(sep)) }
+        };
+        let summary_str = wrap("SESSION_SUMMARY", &summary, "\n");
+        let recent_str = wrap("RECENT_HISTORY", &recent, "\n\n");
+        let knowledge_str = wrap("RELEVANT_KNOWLEDGE", &knowledge, "\n\n");
+        let prefs_str = wrap("USER_PREFERENCES", &prefs_items, "\n");
+        let identity_str = wrap("IDENTITY", &identity_items, "\n\n");
+        let fs_str = format!("<FILE_SYSTEM_PULSE>\n{}\n{}\n</FILE_SYSTEM_PULSE>", fs_header, fs_lines.join("\n"));
 
         // Swarm Identity Overlays
-        if std::env::var("IS_SPORE").is_ok() {
-            let role = std::env::var("SPORE_ROLE").unwrap_or_else(|_| "Sub-Agent".to_string());
+        if is_spore {
+            let role = brain.spore.role();
 
             // Lean Spore Prompt
             let prompt = format!(r#"You are a specialized OpenSpore Sub-Agent.
@@ -89,12 +153,25 @@ Role: {role}
 
 {recent_str}
 
+{privacy_str}
+
 <TASK>
 {user_prompt}
 </TASK>"#);
-            return (prompt, session_ctx);
+            return (prompt, session_ctx, relevant);
         }
 
+        // Working-context gauges (from the budget estimate, no extra I/O)
+        let stats_str = if brain.config.context_stats_enabled.unwrap_or(true) {
+            crate::context_stats::ContextStats::new(
+                &session_ctx,
+                brain.context_manager.max_raw_items,
+                report.tokens,
+                budget.window,
+                crate::thinking::MAX_TOOL_DEPTH,
+            ).render()
+        } else { "".to_string() };
+
         // Standard Main Agent Prompt
         let prompt = format!(r#"You are OpenSpore, an autonomous AI system.
 Current Time: {time}
@@ -104,6 +181,8 @@ Engine Root: {project_root}
 
 {prefs_str}
 
+{glossary_str}
+
 {knowledge_str}
 
 {skills}
@@ -124,10 +203,167 @@ Engine Root: {project_root}
 
 {recent_str}
 
+{stats_str}
+
+{privacy_str}
+
+{template_str}
+
+{followups_str}
+
+<USER_REQUEST>
+{user_prompt}
+</USER_REQUEST>
+"#);
+        (prompt, session_ctx, relevant)
+    }
+
+    /// General chat: identity, preferences and the recent conversation. No
+    /// skills, memory search, file system or delegation strategy.
+    async fn build_slim_prompt(brain: &Brain, user_prompt: &str, is_private: bool) -> (String, WorkingContext, Vec<SearchResult>) {
+        let session_ctx = brain.context_manager.get_working_context(Some(brain)).await.unwrap_or_default();
+        let prefs = brain.memory.get_memories("preferences");
+        let identity = brain.memory.get_memories("identity");
+        let time = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+
+        let privacy_str = if is_private {
+            "<PRIVATE_TURN>\nThis exchange is private and will NOT be persisted.\n</PRIVATE_TURN>".to_string()
+        } else { "".to_string() };
+
+        let mut sections = [
+            BudgetSection::new(Section::RecentTurns, session_ctx.recent_exchanges.clone(), true),
+            BudgetSection::new(Section::Preferences, prefs.iter().take(5).map(|m| format!("- {}", m.content)).collect(), false),
+            BudgetSection::new(Section::Identity, identity.iter().map(|m| m.content.clone()).collect(), false),
+        ];
+        let glossary_str = glossary_excerpt(brain, user_prompt);
+        let fixed_tokens = SLIM_FRAME_TOKENS + count_tokens(&privacy_str) + count_tokens(&glossary_str) + 2 * count_tokens(user_prompt);
+        ContextBudget::for_models(&brain.config, &brain.model_chain()).fit(fixed_tokens, &mut sections);
+        let [recent, prefs_items, identity_items] = sections.map(|s| s.items);
+
+        let wrap = |tag: &str, items: &[String], sep: &str| {
+            if items.is_empty() { String::new() } else { format!("<{tag}>\n{}\n</{tag}>", items.join(sep)) }
+        };
+        let identity_str = wrap("IDENTITY", &identity_items, "\n\n");
+        let prefs_str = wrap("USER_PREFERENCES", &prefs_items, "\n");
+        let recent_str = wrap("RECENT_HISTORY", &recent, "\n\n");
+
+        let prompt = format!(r#"You are OpenSpore, a personal assistant, talking with the user.
+Current Time: {time}
+
+{identity_str}
+
+{prefs_str}
+
+{glossary_str}
+
+<CONVERSATION_MODE>
+This message is general conversation. Answer directly and naturally in Markdown, in the user's tone. No tools are available this turn: do not write tool calls or describe agent internals. If the user actually wants something done on their machine or workspace, say so briefly and suggest they ask again with "use your tools".
+</CONVERSATION_MODE>
+
+{recent_str}
+
+{privacy_str}
+
 <USER_REQUEST>
 {user_prompt}
 </USER_REQUEST>
 "#);
-        (prompt, session_ctx)
+        (prompt, session_ctx, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{config_in, fake_llm};
+    use openspore_core::config::AppConfig;
+
+    const REQUEST: &str = "What do my notes say about zebra migration routes?";
+
+    /// Memory files of about `tokens` tokens each under `category`
+    fn oversized(brain: &Brain, category: &str, count: usize, tokens: usize) {
+        let dir = brain.memory.category_dir(category);
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..count {
+            let body = format!("{} {}", category, "zebra migration routes ".repeat(tokens / 3));
+            std::fs::write(dir.join(format!("{}_{}.md", category, i)), format!("---\ntype: {}\n---\n\n# {} {}\n\n{}\n", category, category, i, body)).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_memories_are_trimmed_to_the_model_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = fake_llm(|_| String::new()).await;
+        let config = AppConfig {
+            model_context_tokens: Some("test-model=24000".into()),
+            llm_max_tokens: Some(4000),
+            ..config_in(dir.path(), &url)
+        };
+        let brain = Brain::new(config).with_channel("tui");
+        brain.memory.ensure_structure().await.unwrap();
+        oversized(&brain, "knowledge", 6, 6000);
+        oversized(&brain, "preferences", 4, 3000);
+        std::fs::write(brain.memory.category_dir("identity").join("me.md"), "---\ntype: identity\n---\n\nI am Sam, I study zebras.\n").unwrap();
+
+        let (prompt, _, injected) = ContextAssembler::build_system_prompt(&brain, REQUEST, false, PromptMode::Full).await;
+        let limit = ContextBudget::for_models(&brain.config, &brain.model_chain()).prompt_limit();
+        let used = count_tokens(&prompt) + count_tokens(REQUEST);
+        assert!(used <= limit, "{} tokens for a {} token budget", used, limit);
+
+        assert!(prompt.contains("I am Sam, I study zebras."), "identity is cut last");
+        assert!(prompt.contains(REQUEST));
+        assert!(injected.len() < 3, "memories that did not fit are not reported as injected");
+    }
+
+    #[tokio::test]
+    async fn memories_that_fit_are_all_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = fake_llm(|_| String::new()).await;
+        let brain = Brain::new(config_in(dir.path(), &url)).with_channel("tui");
+        brain.memory.ensure_structure().await.unwrap();
+        oversized(&brain, "knowledge", 3, 300);
+
+        let (prompt, _, injected) = ContextAssembler::build_system_prompt(&brain, REQUEST, false, PromptMode::Full).await;
+        assert_eq!(injected.len(), 3);
+        for memory in &injected {
+            assert!(prompt.contains(&format!("--- File: {} ---", memory.title)));
+        }
+    }
+
+    #[tokio::test]
+    async fn the_slim_prompt_fits_the_window_too() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = fake_llm(|_| String::new()).await;
+        let config = AppConfig {
+            model_context_tokens: Some("test-model=8000".into()),
+            llm_max_tokens: Some(1000),
+            ..config_in(dir.path(), &url)
+        };
+        let brain = Brain::new(config).with_channel("tui");
+        brain.memory.ensure_structure().await.unwrap();
+        oversized(&brain, "preferences", 5, 4000);
+
+        let (prompt, _, _) = ContextAssembler::build_system_prompt(&brain, "hi there", false, PromptMode::Slim).await;
+        let limit = ContextBudget::for_models(&brain.config, &brain.model_chain()).prompt_limit();
+        assert!(count_tokens(&prompt) <= limit, "{} tokens for a {} token budget", count_tokens(&prompt), limit);
+        assert!(prompt.contains("<CONVERSATION_MODE>"));
+    }
+
+    #[tokio::test]
+    async fn the_glossary_excerpt_covers_the_terms_in_the_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = fake_llm(|_| String::new()).await;
+        let brain = Brain::new(config_in(dir.path(), &url)).with_channel("tui");
+        brain.memory.ensure_structure().await.unwrap();
+        std::fs::write(brain.memory.glossary_path(), "| Term | Aliases | Definition |\n|---|---|---|\n| Meridian | MRD | Billing rewrite |\n| Kestrel | | Mobile app |\n").unwrap();
+
+        for mode in [PromptMode::Full, PromptMode::Slim] {
+            let (prompt, _, _) = ContextAssembler::build_system_prompt(&brain, "how is mrd going?", false, mode).await;
+            assert!(prompt.contains("<GLOSSARY>\nThe user's canonical terms. Use these spellings.\n- **Meridian** (also: MRD): Billing rewrite\n</GLOSSARY>"), "{:?}", mode);
+            assert!(!prompt.contains("Kestrel"), "only mentioned terms, and not the whole file");
+
+            let (prompt, _, _) = ContextAssembler::build_system_prompt(&brain, "what's for dinner?", false, mode).await;
+            assert!(!prompt.contains("<GLOSSARY>"));
+        }
     }
 }
@@ -0,0 +1,293 @@
+//! Token budget of the system prompt
+//!
+//! The prompt, the user message and the reply (LLM_MAX_TOKENS) must fit in the
+//! context window of every model the turn may use (MODEL_CONTEXT_TOKENS, else
+//! LLM_CONTEXT_TOKENS). ContextAssembler hands the trimmable sections to fit(),
+//! which drops their items lowest priority first (file tree lines, then
+//! memories, summary lines, the oldest exchanges, preferences, identity) until
+//! the estimate is under the limit. Skills, directives and the request itself
+//! are never cut.
+
+use openspore_core::config::AppConfig;
+use tracing::warn;
+
+/// Share of the window kept free for tool rounds and estimation error
+const MARGIN_RATIO: f64 = 0.05;
+
+/// Tags and separators around a non-empty section
+const SECTION_WRAPPER_TOKENS: usize = 12;
+
+/// Token estimate in the spirit of cl100k_base, erring on the high side:
+/// letter runs cost one token per 5 letters, digit runs one per 3 digits,
+/// punctuation and whitespace runs (other than a single space) one each, and
+/// other scripts one per character.
+pub fn count_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut letters = 0usize;
+    let mut digits = 0usize;
+    let mut spaces = 0usize;
+    let flush = |run: &mut usize, per_token: usize| {
+        let cost = run.div_ceil(per_token);
+        *run = 0;
+        cost
+    };
+    for c in text.chars() {
+        if c.is_ascii_alphabetic() {
+            tokens += flush(&mut digits, 3) + usize::from(spaces > 1);
+            spaces = 0;
+            letters += 1;
+        } else if c.is_ascii_digit() {
+            tokens += flush(&mut letters, 5) + usize::from(spaces > 1);
+            spaces = 0;
+            digits += 1;
+        } else if c.is_whitespace() {
+            tokens += flush(&mut letters, 5) + flush(&mut digits, 3);
+            // " word" is one token; newlines and indentation are not free
+            spaces += if c == ' ' { 1 } else { 2 };
+        } else {
+            tokens += flush(&mut letters, 5) + flush(&mut digits, 3) + usize::from(spaces > 1) + 1;
+            spaces = 0;
+        }
+    }
+    tokens + flush(&mut letters, 5) + flush(&mut digits, 3) + usize::from(spaces > 1)
+}
+
+/// Trimmable parts of the system prompt. Declared least important first:
+/// fit() cuts them in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Section {
+    FileSystem,
+    Knowledge,
+    Summary,
+    RecentTurns,
+    Preferences,
+    Identity,
+}
+
+impl Section {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Section::FileSystem => "file tree lines",
+            Section::Knowledge => "knowledge memories",
+            Section::Summary => "summary lines",
+            Section::RecentTurns => "recent turns",
+            Section::Preferences => "preferences",
+            Section::Identity => "identity notes",
+        }
+    }
+}
+
+/// A section split into the units fit() drops one at a time
+#[derive(Debug, Clone)]
+pub struct BudgetSection {
+    pub section: Section,
+    pub items: Vec<String>,
+    /// Drop from the start (oldest exchanges) instead of the end (lowest-ranked memories)
+    pub drop_oldest: bool,
+    /// Items before fitting
+    pub total: usize,
+}
+
+impl BudgetSection {
+    pub fn new(section: Section, items: Vec<String>, drop_oldest: bool) -> Self {
+        Self { section, total: items.len(), items, drop_oldest }
+    }
+
+    pub fn tokens(&self) -> usize {
+        if self.items.is_empty() {
+            return 0;
+        }
+        SECTION_WRAPPER_TOKENS + self.items.iter().map(|i| count_tokens(i) + 1).sum::<usize>()
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.total - self.items.len()
+    }
+}
+
+/// Outcome of fit()
+#[derive(Debug, Clone, Default)]
+pub struct BudgetReport {
+    /// Estimated prompt tokens after trimming (user message included)
+    pub tokens: usize,
+    pub limit: usize,
+    /// Sections that lost items: (section, dropped, total)
+    pub dropped: Vec<(Section, usize, usize)>,
+}
+
+impl BudgetReport {
+    /// Still over the limit with every trimmable item gone
+    pub fn is_over(&self) -> bool {
+        self.tokens > self.limit
+    }
+
+    pub fn describe_dropped(&self) -> String {
+        self.dropped.iter()
+            .map(|(section, dropped, total)| format!("{} of {} {}", dropped, total, section.label()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Context window shared by the prompt and the reply
+#[derive(Debug, Clone, Copy)]
+pub struct ContextBudget {
+    pub window: usize,
+    /// Tokens left for the reply (LLM_MAX_TOKENS, at most half the window)
+    pub reply: usize,
+}
+
+impl ContextBudget {
+    /// The smallest window among `models` (a fallback may have to take the same prompt)
+    pub fn for_models(config: &AppConfig, models: &[String]) -> Self {
+        let window = models.iter()
+            .map(|m| config.context_tokens(m))
+            .min()
+            .unwrap_or_else(|| config.context_tokens(""));
+        Self { window, reply: config.llm_max_tokens().min(window / 2) }
+    }
+
+    /// Tokens the prompt may use
+    pub fn prompt_limit(&self) -> usize {
+        let margin = (self.window as f64 * MARGIN_RATIO) as usize;
+        self.window.saturating_sub(self.reply).saturating_sub(margin)
+    }
+
+    /// Drop section items, least important section first, until `fixed_tokens`
+    /// (the untrimmable rest of the prompt) plus the sections fit the limit.
+    pub fn fit(&self, fixed_tokens: usize, sections: &mut [BudgetSection]) -> BudgetReport {
+        let limit = self.prompt_limit();
+        let mut tokens = fixed_tokens + sections.iter().map(BudgetSection::tokens).sum::<usize>();
+
+        let mut order: Vec<usize> = (0..sections.len()).collect();
+        order.sort_by_key(|&i| sections[i].section);
+        for i in order {
+            let section = &mut sections[i];
+            while tokens > limit && !section.items.is_empty() {
+                let item = if section.drop_oldest { section.items.remove(0) } else { section.items.pop().unwrap_or_default() };
+                tokens = tokens.saturating_sub(count_tokens(&item) + 1);
+                if section.items.is_empty() {
+                    tokens = tokens.saturating_sub(SECTION_WRAPPER_TOKENS);
+                }
+            }
+        }
+
+        let report = BudgetReport {
+            tokens,
+            limit,
+            dropped: sections.iter()
+                .filter(|s| s.dropped() > 0)
+                .map(|s| (s.section, s.dropped(), s.total))
+                .collect(),
+        };
+        if !report.dropped.is_empty() {
+            warn!("✂️ Context over budget ({} token window, {} kept for the reply): dropped {}", self.window, self.reply, report.describe_dropped());
+        }
+        if report.is_over() {
+            warn!("✂️ Prompt still ~{} tokens over its {} token budget after trimming (skills, directives and the request are never cut)", report.tokens - limit, limit);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(window: usize, reply: usize) -> ContextBudget {
+        ContextBudget { window, reply }
+    }
+
+    /// `count` items of about `tokens` tokens each
+    fn items(count: usize, tokens: usize, tag: &str) -> Vec<String> {
+        (0..count).map(|i| format!("{}{} {}", tag, i, "words ".repeat(tokens))).collect()
+    }
+
+    #[test]
+    fn count_tokens_errs_on_the_high_side() {
+        assert_eq!(count_tokens(""), 0);
+        assert_eq!(count_tokens("hello world"), 2);
+        assert_eq!(count_tokens("2026"), 2);
+        assert_eq!(count_tokens("a\n\n  b"), 3, "a newline and indentation cost one");
+        assert_eq!(count_tokens("日本語"), 3);
+        let prose = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+        assert!(count_tokens(&prose) >= prose.len() / 5, "{}", count_tokens(&prose));
+    }
+
+    #[test]
+    fn the_smallest_window_of_the_chain_counts_and_the_reply_is_kept_free() {
+        let config = AppConfig {
+            model_context_tokens: Some("big=200000, small=32000".into()),
+            llm_context_tokens: Some(64000),
+            llm_max_tokens: Some(50000),
+            ..Default::default()
+        };
+        let chain = ContextBudget::for_models(&config, &["big".into(), "small".into()]);
+        assert_eq!((chain.window, chain.reply), (32000, 16000), "the reply takes at most half");
+        assert_eq!(chain.prompt_limit(), 32000 - 16000 - 1600);
+        assert_eq!(ContextBudget::for_models(&config, &["unlisted".into()]).window, 64000);
+        assert_eq!(ContextBudget::for_models(&config, &[]).window, 64000);
+    }
+
+    #[test]
+    fn fit_drops_the_least_important_sections_first() {
+        let budget = budget(10_000, 1_000);
+        let mut sections = [
+            BudgetSection::new(Section::Identity, items(2, 500, "identity"), false),
+            BudgetSection::new(Section::RecentTurns, items(6, 800, "turn"), true),
+            BudgetSection::new(Section::Knowledge, items(5, 4_000, "memory"), false),
+            BudgetSection::new(Section::FileSystem, items(200, 10, "line"), false),
+        ];
+        let report = budget.fit(1_000, &mut sections);
+
+        assert!(!report.is_over());
+        assert!(report.tokens <= budget.prompt_limit());
+        assert!(sections[3].items.is_empty(), "file tree lines go first");
+        assert!(sections[2].items.is_empty(), "then memories");
+        assert_eq!(sections[1].items.len(), 6, "recent turns fit once memories are gone");
+        assert_eq!(sections[0].items.len(), 2);
+        let dropped: Vec<_> = report.dropped.iter().map(|(s, n, total)| (*s, *n, *total)).collect();
+        assert_eq!(dropped, [(Section::Knowledge, 5, 5), (Section::FileSystem, 200, 200)]);
+        assert_eq!(report.describe_dropped(), "5 of 5 knowledge memories, 200 of 200 file tree lines");
+    }
+
+    #[test]
+    fn fit_keeps_the_newest_turns_and_the_best_memories() {
+        let budget = budget(6_000, 1_000);
+        let mut sections = [
+            BudgetSection::new(Section::RecentTurns, items(8, 1_000, "turn"), true),
+            BudgetSection::new(Section::Knowledge, items(3, 1_000, "memory"), false),
+        ];
+        let report = budget.fit(500, &mut sections);
+
+        assert!(report.tokens <= budget.prompt_limit());
+        assert!(sections[1].items.is_empty());
+        assert!(!sections[0].items.is_empty());
+        assert!(sections[0].items.last().unwrap().starts_with("turn7"), "the oldest turns go");
+        assert!(!sections[0].items.iter().any(|t| t.starts_with("turn0")));
+
+        let mut memories = [BudgetSection::new(Section::Knowledge, items(3, 1_000, "memory"), false)];
+        budget.fit(3_500, &mut memories);
+        assert_eq!(memories[0].items.len(), 1);
+        assert!(memories[0].items[0].starts_with("memory0"), "the lowest-ranked memories go");
+    }
+
+    #[test]
+    fn an_untrimmable_prompt_is_reported_over_budget() {
+        let budget = budget(4_000, 1_000);
+        let mut sections = [BudgetSection::new(Section::Identity, items(1, 100, "identity"), false)];
+        let report = budget.fit(5_000, &mut sections);
+        assert!(report.is_over());
+        assert!(sections[0].items.is_empty());
+        assert_eq!(report.tokens, 5_000);
+    }
+
+    #[test]
+    fn a_prompt_that_fits_is_left_alone() {
+        let mut sections = [BudgetSection::new(Section::Knowledge, items(3, 100, "memory"), false)];
+        let report = budget(128_000, 16_384).fit(2_000, &mut sections);
+        assert!(report.dropped.is_empty());
+        assert_eq!(sections[0].items.len(), 3);
+        assert_eq!(report.tokens, 2_000 + sections[0].tokens());
+    }
+}
@@ -0,0 +1,113 @@
+use openspore_memory::context::WorkingContext;
+
+/// Above this share of the context window the model is asked to keep answers tight
+const HIGH_UTILIZATION: f64 = 0.75;
+
+/// Working-context gauges shown to the model in `<CONTEXT_STATS>`
+#[derive(Debug, Clone)]
+pub struct ContextStats {
+    pub summary_chars: usize,
+    pub recent_exchanges: usize,
+    /// Exchanges kept raw before older ones are compressed into the summary
+    pub compression_threshold: usize,
+    /// Older exchanges waiting for compression
+    pub pending_compression: usize,
+    pub estimated_tokens: usize,
+    pub context_limit: usize,
+    /// Tool rounds available this cycle
+    pub cycle_budget: usize,
+}
+
+impl ContextStats {
+    pub fn new(ctx: &WorkingContext, compression_threshold: usize, estimated_tokens: usize, context_limit: usize, cycle_budget: usize) -> Self {
+        Self {
+            summary_chars: ctx.summary.len(),
+            recent_exchanges: ctx.recent_count,
+            compression_threshold,
+            pending_compression: ctx.older_items.len(),
+            estimated_tokens,
+            context_limit: context_limit.max(1),
+            cycle_budget,
+        }
+    }
+
+    pub fn token_utilization(&self) -> f64 {
+        self.estimated_tokens as f64 / self.context_limit as f64
+    }
+
+    /// High when the prompt fills most of the window or compression is behind
+    pub fn is_high(&self) -> bool {
+        self.token_utilization() >= HIGH_UTILIZATION || self.pending_compression > 0
+    }
+
+    pub fn render(&self) -> String {
+        let guidance = if self.is_high() {
+            "Utilization is HIGH: do not restate earlier answers or recap the history. Refer to prior content briefly (\"as above\", file paths) and keep this reply short."
+        } else {
+            "Utilization is normal. Still prefer references over restating earlier content."
+        };
+        format!(
+            "<CONTEXT_STATS>\nsummary: {} chars\nrecent exchanges: {}/{} before compression{}\nprompt: ~{} / {} tokens ({:.0}%)\ncycle budget: {} tool rounds\n{}\n</CONTEXT_STATS>",
+            self.summary_chars,
+            self.recent_exchanges,
+            self.compression_threshold,
+            if self.pending_compression > 0 { format!(" ({} older pending)", self.pending_compression) } else { String::new() },
+            self.estimated_tokens,
+            self.context_limit,
+            self.token_utilization() * 100.0,
+            self.cycle_budget,
+            guidance
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_memory::MemoryItem;
+
+    fn context(summary_chars: usize, recent: usize, older: usize) -> WorkingContext {
+        WorkingContext {
+            summary: "s".repeat(summary_chars),
+            recent_count: recent,
+            older_items: (0..older).map(|i| MemoryItem::new(format!("exchange{}.md", i), String::new())).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn low_utilization_block() {
+        let stats = ContextStats::new(&context(1200, 3, 0), 10, 20_000, 128_000, 8);
+        assert!(!stats.is_high());
+        assert_eq!(
+            stats.render(),
+            "<CONTEXT_STATS>\nsummary: 1200 chars\nrecent exchanges: 3/10 before compression\nprompt: ~20000 / 128000 tokens (16%)\ncycle budget: 8 tool rounds\n\
+             Utilization is normal. Still prefer references over restating earlier content.\n</CONTEXT_STATS>"
+        );
+    }
+
+    #[test]
+    fn high_utilization_block() {
+        let stats = ContextStats::new(&context(0, 10, 0), 10, 96_000, 128_000, 2);
+        assert!(stats.is_high());
+        let block = stats.render();
+        assert!(block.contains("prompt: ~96000 / 128000 tokens (75%)"));
+        assert!(block.contains("Utilization is HIGH"));
+        assert!(!block.contains("pending"));
+    }
+
+    #[test]
+    fn pending_compression_counts_as_high() {
+        let stats = ContextStats::new(&context(0, 10, 4), 10, 1_000, 128_000, 8);
+        assert!(stats.is_high());
+        assert!(stats.render().contains("recent exchanges: 10/10 before compression (4 older pending)"));
+    }
+
+    #[test]
+    fn a_zero_limit_does_not_divide_by_zero() {
+        let stats = ContextStats::new(&context(0, 0, 0), 10, 500, 0, 8);
+        assert_eq!(stats.context_limit, 1);
+        assert!(stats.is_high());
+        assert!(stats.token_utilization().is_finite());
+    }
+}
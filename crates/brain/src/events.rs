@@ -15,6 +15,18 @@ pub enum BrainEvent {
         output: String,
         success: bool,
     },
+    /// A gated tool waits for Brain::approvals.respond(id, ...) before it runs (APPROVAL_MODE)
+    ApprovalRequest {
+        id: u64,
+        name: String,
+        arg: String,
+    },
+    /// The current turn is private and will not be persisted
+    PrivateTurn,
+    /// Token usage and timing of the finished cycle (sent before FinalAnswer)
+    Stats(crate::usage::CycleStats),
     FinalAnswer(String),
+    /// Up to three follow-up prompts for the answer (sent after FinalAnswer, FOLLOWUP_SUGGESTIONS)
+    Suggestions(Vec<String>),
     Error(String),
 }
@@ -0,0 +1,170 @@
+//! Acknowledgment fast path and the learn() throttle
+//!
+//! An "ok" or "thanks!" turn gets its one completion and nothing else: no
+//! learn(), no exchange file, no compression into the session summary. That
+//! holds only while the turn stays small: once tools ran or the reply grew
+//! long ("yes" to a proposed action), it is saved and learned as usual. Its
+//! journal line is held back, and consecutive acknowledgments go to LOGS.md
+//! as a single line, written before the next real turn's entry (or once
+//! ACK_BATCH_MAX are waiting, or on flush_journal()).
+//!
+//! Apart from acknowledgments, learn() is queued at most once per
+//! LEARN_MIN_INTERVAL_SECS (default 30, 0 disables) across the process, so a
+//! rapid-fire conversation doesn't fan out one extraction call per message.
+//! What a cycle skipped shows in its stats (`FastPathStats`).
+
+use crate::Brain;
+use chrono::{DateTime, Local};
+use openspore_core::fmt_utils::preview;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Acknowledgments held back before the batch is written anyway
+const ACK_BATCH_MAX: usize = 10;
+
+/// Characters kept of each acknowledgment and its reply in the batch line
+const ACK_PREVIEW_CHARS: usize = 60;
+
+/// Longest reply an acknowledgment can get and still take the fast path
+const ACK_REPLY_MAX_CHARS: usize = 300;
+
+pub const DEFAULT_LEARN_MIN_INTERVAL_SECS: u64 = 30;
+
+/// Whether a finished turn takes the fast path: an acknowledgment answered
+/// in one completion, without tool rounds, and briefly
+pub(crate) fn is_fast_path(prompt: &str, reply: &str, tool_rounds: usize) -> bool {
+    tool_rounds == 0
+        && reply.chars().count() <= ACK_REPLY_MAX_CHARS
+        && crate::learning::is_acknowledgment(prompt)
+}
+
+/// When learn() was last queued, for all brains of this process
+static LAST_LEARN: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Acknowledgment turns not yet written to the journal (shared across clones)
+#[derive(Debug, Default)]
+pub struct AckJournal {
+    since: Option<DateTime<Local>>,
+    turns: Vec<String>,
+}
+
+impl AckJournal {
+    /// The batch as one journal line, emptying it
+    fn take_line(&mut self) -> Option<String> {
+        let since = self.since.take()?;
+        let turns = std::mem::take(&mut self.turns);
+        let label = if turns.len() == 1 { "acknowledgment" } else { "acknowledgments" };
+        Some(format!("\n[{}] 💬 {} {}: {}\n", since.format("%Y-%m-%d %H:%M:%S"), turns.len(), label, turns.join(" · ")))
+    }
+}
+
+impl Brain {
+    /// LEARN_MIN_INTERVAL_SECS, 0 when the throttle is off
+    pub fn learn_min_interval(&self) -> Duration {
+        Duration::from_secs(self.config.learn_min_interval_secs.unwrap_or(DEFAULT_LEARN_MIN_INTERVAL_SECS))
+    }
+
+    /// Whether learn() may be queued now; taking the slot starts the next interval
+    pub(crate) fn take_learn_slot(&self) -> bool {
+        let interval = self.learn_min_interval();
+        let mut last = LAST_LEARN.lock().unwrap_or_else(|e| e.into_inner());
+        if last.is_some_and(|at| at.elapsed() < interval) {
+            return false;
+        }
+        *last = Some(Instant::now());
+        true
+    }
+
+    /// Hold back the journal line of an acknowledgment turn
+    pub(crate) async fn journal_acknowledgment(&self, prompt: &str, reply: &str) {
+        let full = {
+            let mut batch = self.ack_journal.lock().unwrap_or_else(|e| e.into_inner());
+            batch.since.get_or_insert_with(Local::now);
+            batch.turns.push(format!("\"{}\" → \"{}\"", preview(prompt.trim(), ACK_PREVIEW_CHARS), preview(reply.trim(), ACK_PREVIEW_CHARS)));
+            batch.turns.len() >= ACK_BATCH_MAX
+        };
+        if full {
+            self.flush_journal().await;
+        }
+    }
+
+    /// Write the held-back acknowledgments to LOGS.md as one line
+    pub async fn flush_journal(&self) {
+        let line = self.ack_journal.lock().unwrap_or_else(|e| e.into_inner()).take_line();
+        if let Some(line) = line
+            && let Err(e) = self.memory.save_journal(&line).await
+        {
+            warn!("❌ Failed to save acknowledgments to LOGS.md: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{brain_in, fake_llm};
+
+    const LEARN_MARKER: &str = "Analyze this conversation for learnable information";
+
+    #[test]
+    fn fast_path_needs_a_small_tool_free_turn() {
+        assert!(is_fast_path("thanks!", "You're welcome.", 0));
+        assert!(!is_fast_path("yes", "Done.", 1));
+        assert!(!is_fast_path("ok", &"a long answer ".repeat(40), 0));
+        assert!(!is_fast_path("rename the file", "Done.", 0));
+    }
+
+    #[tokio::test]
+    async fn acknowledgment_costs_one_completion() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, requests) = fake_llm(|_| "You're welcome!".to_string()).await;
+        let brain = brain_in(dir.path(), &url).await;
+
+        let (reply, stats) = brain.think_with_stats("thanks!").await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        assert_eq!(reply, "You're welcome!");
+        assert_eq!(requests.len(), 1, "no learn or classifier call follows an acknowledgment");
+        assert_eq!(stats.fast_path.exchange_skipped, 1);
+        assert_eq!(stats.fast_path.learn_skipped, 1);
+    }
+
+    #[tokio::test]
+    async fn yes_that_ran_a_tool_is_saved_and_learned() {
+        // Outside the project root, so only read_file can see it
+        let (dir, outside) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let notes = outside.path().join("notes.txt");
+        std::fs::write(&notes, "buy basil").unwrap();
+        let call = format!("[READ_FILE: \"{}\"]", notes.display());
+        let (url, requests) = fake_llm(move |request| {
+            let messages = request["messages"].to_string();
+            let last = request["messages"].as_array().and_then(|m| m.last()).cloned().unwrap_or_default();
+            if messages.contains(LEARN_MARKER) {
+                r#"{"should_save": false}"#.to_string()
+            } else if last["content"] == "yes" {
+                call.clone()
+            } else if messages.contains("buy basil") {
+                "Your notes say: buy basil.".to_string()
+            } else {
+                "I can read your notes file. Shall I?".to_string()
+            }
+        }).await;
+        let brain = brain_in(dir.path(), &url).await;
+
+        brain.think_with_stats("what's in my notes").await;
+        let (reply, stats) = brain.think_with_stats("yes").await;
+        for _ in 0..50 {
+            if requests.mentioning(LEARN_MARKER) > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        assert_eq!(reply, "Your notes say: buy basil.");
+        assert!(stats.layers > 1, "the tool loop ran");
+        assert_eq!(stats.fast_path.exchange_skipped, 0);
+        assert_eq!(stats.fast_path.learn_skipped, 0);
+        assert!(requests.mentioning(LEARN_MARKER) > 0, "learn() ran for the confirmed action");
+    }
+}
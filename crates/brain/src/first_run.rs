@@ -0,0 +1,209 @@
+//! Help for a failing first conversation
+//!
+//! A new user's first message usually fails for a setup reason: no or a bad
+//! API key, a model the endpoint doesn't serve, no network. While the
+//! workspace holds fewer than FIRST_RUN_EXCHANGES saved exchanges (and no
+//! digest of older ones), a think() that cannot reach the model answers with
+//! what went wrong, the setting to fix and the findings of the matching doctor
+//! check, run on the spot as a dry run. Afterwards errors stay one line.
+
+use crate::api::{LlmError, LlmErrorKind};
+use crate::Brain;
+use openspore_doctor::{Check, IssueId, SporeDoctor};
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Saved exchanges after which a workspace is no longer new
+pub const FIRST_RUN_EXCHANGES: usize = 5;
+
+/// Exchange files in `dir`, counting up to `limit`
+fn count_exchanges(dir: &Path, limit: usize) -> usize {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().to_lowercase().starts_with("exchange"))
+        .take(limit)
+        .count()
+}
+
+/// Doctor prescriptions name web pages; the help stays link-free
+fn without_links(line: &str) -> String {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    LINK.get_or_init(|| Regex::new(r"\s*\(https?://[^)]*\)").expect("valid regex"))
+        .replace_all(line, "")
+        .into_owned()
+}
+
+/// Findings of the doctor checks for one kind of failure (blocking: the LLM check calls the endpoint)
+fn doctor_findings(checks: &[Check]) -> Vec<String> {
+    let mut doctor = SporeDoctor::new().with_dry_run(true);
+    // The env check also reports the optional Telegram token, unrelated to the model
+    let issues: Vec<_> = doctor.checks(checks).into_iter()
+        .filter(|issue| issue.id != IssueId::NoTelegramBot)
+        .collect();
+    let mut lines: Vec<String> = doctor.notes().iter()
+        .filter(|note| note.starts_with('✅') || note.starts_with('⚠'))
+        .cloned()
+        .collect();
+    for (issue, treatment) in issues.iter().zip(doctor.treatments(&issues)) {
+        lines.push(issue.line());
+        lines.push(format!("   {}", without_links(&treatment.line())));
+    }
+    lines
+}
+
+impl Brain {
+    /// Fewer than FIRST_RUN_EXCHANGES exchanges in every session together, and no digest yet
+    pub fn is_new_workspace(&self) -> bool {
+        let root = &self.memory.memory_root;
+        let has_digests = std::fs::read_dir(root.join(openspore_memory::DIGESTS_DIR))
+            .is_ok_and(|mut entries| entries.next().is_some());
+        if has_digests {
+            return false;
+        }
+        let context = root.join("context");
+        let mut dirs = vec![context.clone()];
+        dirs.extend(std::fs::read_dir(context.join("sessions")).into_iter().flatten().flatten().map(|e| e.path()));
+        let mut exchanges = 0;
+        for dir in dirs {
+            exchanges += count_exchanges(&dir, FIRST_RUN_EXCHANGES - exchanges);
+            if exchanges >= FIRST_RUN_EXCHANGES {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The reply to a failed first completion of a new workspace: what went
+    /// wrong and how to fix it. None once the workspace matured, for sub-spores
+    /// and for errors the setup doesn't explain.
+    pub(crate) async fn first_run_help(&self, error: &anyhow::Error) -> Option<String> {
+        if self.spore.is_spore || !self.is_new_workspace() {
+            return None;
+        }
+        let config = self.llm_config();
+        let base_url = config.llm_base_url();
+        let raw = error.to_string();
+        let (headline, fix, checks): (String, String, Vec<Check>) = match LlmError::kind_of(error) {
+            LlmErrorKind::Auth => (
+                "The model could not be used: the API key is missing or was rejected".to_string(),
+                "Set OPENROUTER_API_KEY in .env to a valid key, or point OPENSPORE_LLM_BASE_URL at a local server that needs none.".to_string(),
+                vec![Check::Env, Check::LlmEndpoint],
+            ),
+            LlmErrorKind::ModelNotFound => (
+                format!("The endpoint does not serve the model {}", self.get_model()),
+                "Set OPENROUTER_MODEL (and OPENROUTER_MODEL_REASONING_FALLBACKS) in .env to a model the endpoint offers.".to_string(),
+                vec![Check::LlmEndpoint],
+            ),
+            LlmErrorKind::Network => (
+                format!("Nothing answered at {}", base_url),
+                format!(
+                    "Check the internet connection, or OPENSPORE_LLM_BASE_URL in .env for a local server. Slow models may need a higher LLM_TIMEOUT_SECS (now {}s).",
+                    config.llm_timeout().as_secs()
+                ),
+                vec![Check::LlmEndpoint],
+            ),
+            LlmErrorKind::RateLimited => (
+                "The provider is rate limiting requests".to_string(),
+                "Wait a minute and try again. Free models have low limits: credits on the key, or another OPENROUTER_MODEL, raise them.".to_string(),
+                Vec::new(),
+            ),
+            LlmErrorKind::Other => return None,
+        };
+
+        let findings = if checks.is_empty() {
+            Vec::new()
+        } else {
+            tokio::task::spawn_blocking(move || doctor_findings(&checks)).await.unwrap_or_default()
+        };
+        let mut help = format!("{} ({}).\n\n🔧 {}", headline, raw, fix);
+        if !findings.is_empty() {
+            help.push_str("\n\n🩺 Doctor:\n");
+            help.push_str(&findings.join("\n"));
+        }
+        help.push_str("\n\nRun `openspore doctor` for a full check, then send your message again.");
+        Some(help)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{brain_in, config_in};
+
+    fn exchanges(dir: &Path, count: usize) {
+        std::fs::create_dir_all(dir).unwrap();
+        for i in 0..count {
+            std::fs::write(dir.join(format!("exchange_{}.md", i)), "hi").unwrap();
+        }
+    }
+
+    fn rate_limited() -> anyhow::Error {
+        anyhow::Error::new(LlmError { kind: LlmErrorKind::RateLimited, message: "API Error: 429 (after 3 attempts)".to_string() })
+    }
+
+    #[tokio::test]
+    async fn workspace_matures_at_the_exchange_cutoff() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+        let context = brain.memory.memory_root.join("context");
+        assert!(brain.is_new_workspace());
+
+        exchanges(&context, 2);
+        exchanges(&context.join("sessions/telegram"), FIRST_RUN_EXCHANGES - 3);
+        std::fs::write(context.join("notes.md"), "not an exchange").unwrap();
+        assert!(brain.is_new_workspace(), "{} exchanges stay new", FIRST_RUN_EXCHANGES - 1);
+
+        std::fs::write(context.join("sessions/telegram/Exchange_last.md"), "hi").unwrap();
+        assert!(!brain.is_new_workspace(), "sessions count towards the cutoff");
+    }
+
+    #[tokio::test]
+    async fn a_digest_matures_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+        let digests = brain.memory.memory_root.join(openspore_memory::DIGESTS_DIR);
+        std::fs::create_dir_all(&digests).unwrap();
+        assert!(brain.is_new_workspace(), "an empty digest directory doesn't count");
+
+        std::fs::write(digests.join("2026-01-01.md"), "digest").unwrap();
+        assert!(!brain.is_new_workspace());
+        assert_eq!(brain.first_run_help(&rate_limited()).await, None);
+    }
+
+    #[tokio::test]
+    async fn new_workspace_gets_the_fix_for_its_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+
+        let help = brain.first_run_help(&rate_limited()).await.unwrap();
+        assert!(help.starts_with("The provider is rate limiting requests (API Error: 429 (after 3 attempts))."), "{}", help);
+        assert!(help.contains("🔧 Wait a minute"), "{}", help);
+        assert!(!help.contains("🩺 Doctor"), "no check explains a rate limit: {}", help);
+        assert!(help.ends_with("Run `openspore doctor` for a full check, then send your message again."), "{}", help);
+    }
+
+    #[tokio::test]
+    async fn unexplained_errors_and_spores_stay_one_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+        assert_eq!(brain.first_run_help(&anyhow::anyhow!("disk full")).await, None);
+        let other = anyhow::Error::new(LlmError { kind: LlmErrorKind::Other, message: "API Error: 500".to_string() });
+        assert_eq!(brain.first_run_help(&other).await, None);
+
+        let spore = Brain::new_spore(config_in(dir.path(), "http://127.0.0.1:9/v1"), None);
+        assert!(spore.is_new_workspace());
+        assert_eq!(spore.first_run_help(&rate_limited()).await, None);
+    }
+
+    #[test]
+    fn prescriptions_lose_their_links() {
+        assert_eq!(
+            without_links("Get a key (https://openrouter.ai/keys) and set it (http://x.test/a)."),
+            "Get a key and set it."
+        );
+        assert_eq!(without_links("No link (here)"), "No link (here)");
+    }
+}
@@ -0,0 +1,38 @@
+//! `/define` command
+//!
+//! `/define Meridian = meridian project, the meridian project: Q3 analytics platform`
+//! adds or updates a term of the glossary (identity/GLOSSARY.md) without a
+//! model call. `/define` alone lists the terms.
+
+use crate::Brain;
+use openspore_memory::glossary::parse_definition;
+
+pub const DEFINE_COMMAND: &str = "/define";
+
+/// Arguments of a `/define` message, or None for any other message
+pub fn define_args(prompt: &str) -> Option<&str> {
+    let rest = prompt.trim_start().strip_prefix(DEFINE_COMMAND)?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+}
+
+impl Brain {
+    /// Run a `/define` command and return the reply
+    pub(crate) async fn define(&self, args: &str) -> String {
+        if args.is_empty() {
+            let glossary = self.memory.glossary();
+            if glossary.is_empty() {
+                return format!("📖 The glossary is empty. Add a term with `{} Term = alias, alias: definition`.", DEFINE_COMMAND);
+            }
+            let lines: Vec<String> = glossary.entries.iter().map(|e| e.render_short()).collect();
+            return format!("📖 **Glossary** ({} terms)\n{}", lines.len(), lines.join("\n"));
+        }
+        let entry = match parse_definition(args) {
+            Ok(entry) => entry,
+            Err(e) => return format!("⚠️ {}", e),
+        };
+        match self.memory.define_term(entry).await {
+            Ok(stored) => format!("📖 Saved to {}:\n{}", openspore_memory::GLOSSARY_FILE, stored.render_short()),
+            Err(e) => format!("⚠️ Glossary not updated: {}", e),
+        }
+    }
+}
@@ -2,8 +2,10 @@ use crate::{Brain, Message};
 use tracing::{info, warn};
 
 impl Brain {
-    /// Extract and save preferences/knowledge from conversation
-    pub async fn learn(&self, prompt: &str, response: &str) -> bool {
+    /// Extract and save preferences/knowledge from conversation. The memory
+    /// records `cycle`, so a replayed cycle is not learned twice (pending_work).
+    /// Acknowledgment turns are never queued (see fast_path).
+    pub async fn learn(&self, cycle: &str, prompt: &str, response: &str) -> bool {
         let learn_prompt = format!(r#"Analyze this conversation for learnable information:
 
 User: {}
@@ -25,39 +27,39 @@ Return JSON ONLY (no markdown):
 
 If nothing worth saving, set should_save to false."#, prompt, response);
 
-        match self.complete(&[Message{role:"user".into(), content: learn_prompt}]).await {
+        match self.complete(&[Message{role:"user".into(), content: learn_prompt, ..Default::default()}]).await {
             Ok(json_str) => {
                 // Robust JSON extraction
                 let start = json_str.find('{');
                 let end = json_str.rfind('}');
 
-                if let (Some(s), Some(e)) = (start, end) {
-                    if s <= e {
-                        if let Ok(data) = serde_json::from_str::<serde_json::Value>(&json_str[s..=e]) {
-                            if data["should_save"].as_bool().unwrap_or(false) {
-                                let category = data["category"].as_str().unwrap_or("context");
-                                let title = data["title"].as_str().unwrap_or("Learned Info");
-                                let content = data["content"].as_str().unwrap_or("");
-                                let tags: Vec<String> = data["tags"]
-                                    .as_array()
-                                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                                    .unwrap_or_default();
+                if let (Some(s), Some(e)) = (start, end)
+                    && s <= e
+                        && let Ok(data) = serde_json::from_str::<serde_json::Value>(&json_str[s..=e])
+                            && data["should_save"].as_bool().unwrap_or(false)
+                {
+                    let category = data["category"].as_str().unwrap_or("context");
+                    let title = data["title"].as_str().unwrap_or("Learned Info");
+                    let content = data["content"].as_str().unwrap_or("");
+                    let tags: Vec<String> = data["tags"]
+                        .as_array()
+                        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                        .unwrap_or_default();
 
-                                if let Ok(Some(path)) = self.memory.save_memory(category, title, content, tags, Some("learned")).await {
-                                    info!("🧠 Learned: {} -> {:?}", title, path);
+                    let ttl = self.memory.learned_ttl(category);
+                    let metadata = [("cycle", cycle.to_string())];
+                    if let Ok(Some(path)) = self.memory.save_memory_with_metadata(category, title, content, tags, Some("learned"), ttl, &metadata).await {
+                        info!("🧠 Learned: {} -> {:?}", title, path);
 
-                                    // Log to LOGS.md
-                                    let log_entry = format!("\n[{}] 🧠 Learned: {} ({})\n",
-                                        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-                                        title,
-                                        category
-                                    );
-                                    let _ = self.memory.save_journal(&log_entry).await;
+                        // Log to LOGS.md
+                        let log_entry = format!("\n[{}] 🧠 Learned: {} ({})\n",
+                            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                            title,
+                            category
+                        );
+                        let _ = self.memory.save_journal(&log_entry).await;
 
-                                    return true;
-                                }
-                            }
-                        }
+                        return true;
                     }
                 }
             },
@@ -68,3 +70,25 @@ If nothing worth saving, set should_save to false."#, prompt, response);
         false
     }
 }
+
+const ACKNOWLEDGMENTS: [&str; 22] = [
+    "ok", "okay", "k", "kk", "yes", "yep", "yeah", "no", "nope", "sure",
+    "thanks", "thank you", "thx", "ty", "cool", "great", "nice", "perfect",
+    "got it", "sounds good", "good", "done",
+];
+
+/// Lowercased, whitespace-collapsed form without trailing punctuation or emoji
+pub(crate) fn normalize_query(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches(|c: char| !c.is_alphanumeric())
+        .to_string()
+}
+
+/// Cheap local check for "ok" / "thanks!" style replies
+pub(crate) fn is_acknowledgment(prompt: &str) -> bool {
+    let normalized = normalize_query(prompt);
+    normalized.is_empty() || ACKNOWLEDGMENTS.contains(&normalized.as_str())
+}
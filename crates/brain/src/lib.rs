@@ -6,24 +6,111 @@
 //! - thinking: Main think() loop with tool execution
 //! - learning: Knowledge/preference extraction
 //! - compression: Session history compression
+//! - pending_work: Persisted queue of learning/compression after each cycle
 //! - context_assembler: System prompt construction
+//! - context_budget: Token budget that trims the prompt to the model's window
+//! - context_stats: Working-context gauges shown to the model
+//! - privacy: Ephemeral turns that skip all persistence
+//! - usage: Token/cost accounting per cycle
+//! - tool_calling: Native OpenAI-style function calling
+//! - vision: Image descriptions with OPENROUTER_MODEL_VISION
+//! - suggestions: Follow-up suggestions after answers
+//! - conflicts: LLM-proposed merges of memory sync conflicts
+//! - approval: User approval of destructive tool calls
+//! - plan: Dry-run turns that record tool calls instead of running them
+//! - prompt_mode: Slim prompts without tools for general chat
+//! - glossary: The /define command (canonical terms and aliases)
+//! - postmortem: Bundles written when a cycle ends in error
+//! - fast_path: Acknowledgment turns skip learning and persistence; learn() throttle
+//! - status: Health snapshot shown by `openspore status`, `/status` and the TUI header
+//! - first_run: Setup help instead of the raw error when a new workspace's first think fails
 
 mod api;
 mod thinking;
 mod learning;
 mod compression;
+pub mod pending_work;
 mod context_assembler;
+pub mod context_budget;
+pub mod context_stats;
 mod parser;
 pub mod events;
+pub mod privacy;
+pub mod usage;
+pub mod tool_calling;
+mod vision;
+pub mod suggestions;
+mod conflicts;
+pub mod approval;
+pub mod plan;
+pub mod prompt_mode;
+pub mod glossary;
+mod postmortem;
+pub mod fast_path;
+pub mod status;
+pub mod first_run;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+
+pub use api::{LlmError, LlmErrorKind};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Message {
     pub role: String,
     pub content: String,
+    /// Assistant tool calls (native function calling only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<serde_json::Value>,
+    /// Id of the call a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// Whether this brain runs as a delegated sub-spore, and in which role.
+/// Spores get a lean prompt without delegation, skip the session summary and
+/// suggestions, and are recorded under the "spore" channel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SporeContext {
+    pub is_spore: bool,
+    pub role: Option<String>,
+}
+
+impl SporeContext {
+    pub fn spore(role: Option<String>) -> Self {
+        Self { is_spore: true, role: role.filter(|r| !r.trim().is_empty()) }
+    }
+
+    /// Legacy fallback: IS_SPORE and SPORE_ROLE in the environment (read into AppConfig)
+    pub fn from_config(config: &AppConfig) -> Self {
+        if config.is_spore { Self::spore(config.spore_role.clone()) } else { Self::default() }
+    }
+
+    /// The role, "Sub-Agent" when the swarm gave none
+    pub fn role(&self) -> &str {
+        self.role.as_deref().unwrap_or("Sub-Agent")
+    }
 }
 
+/// Per-turn switches of think_with_options()
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThinkOptions {
+    /// Plan only: record tool calls instead of running them (see plan)
+    pub dry_run: bool,
+}
+
+/// Prefix of the reply think() returns when the model could not be reached
+pub const FAILED_REPLY_PREFIX: &str = "Errors: ";
+
+/// The error of a think() reply that never reached the model, if it is one
+pub fn failed_reply_error(reply: &str) -> Option<&str> {
+    reply.strip_prefix(FAILED_REPLY_PREFIX)
+}
+
+/// Start of the note think() appends to a reply when the cycle failed and left a post-mortem
+pub const CYCLE_FAILED_MARKER: &str = "⚠️ This cycle failed (";
+
 use std::sync::Arc;
 use openspore_core::config::AppConfig;
 use openspore_skills::SkillLoader;
@@ -34,39 +121,215 @@ use openspore_io::NativeBridge;
 #[derive(Clone)]
 pub struct Brain {
     pub client: reqwest::Client,
+    /// Configuration at startup; the LLM settings follow reloads (see follow_config)
     pub config: AppConfig,
-    pub skill_loader: Arc<SkillLoader>,
+    /// Latest configuration for models, sampling and the LLM endpoint (shared across clones)
+    live_config: Arc<std::sync::RwLock<Arc<AppConfig>>>,
+    /// Core and plugin skills; `reload_skills` swaps the plugins (shared across clones)
+    pub skill_loader: Arc<std::sync::RwLock<SkillLoader>>,
     pub memory: MemorySystem,
     pub context_manager: openspore_memory::context::ContextManager,
     pub io: Arc<Box<dyn NativeBridge + Send + Sync>>,
+    /// Remaining turns in a `/private N` window (shared across clones)
+    pub private_turns: Arc<std::sync::atomic::AtomicUsize>,
+    /// Pending approvals and "always allow" choices of this session (shared across clones)
+    pub approvals: Arc<approval::ApprovalGate>,
+    /// Model chosen with the TUI's `/model` for this session (shared across clones)
+    pub model_override: Arc<std::sync::RwLock<Option<String>>>,
+    /// Recent prompt-mode classifier runs, for `/inspect` (shared across clones)
+    pub classifier_samples: Arc<std::sync::Mutex<std::collections::VecDeque<prompt_mode::ClassifierSample>>>,
+    /// The last answer of this session: a "yes" to its question gets the full prompt (shared across clones)
+    pub last_answer: Arc<std::sync::Mutex<Option<String>>>,
+    /// Acknowledgment turns waiting for their batched journal line (shared across clones)
+    pub ack_journal: Arc<std::sync::Mutex<fast_path::AckJournal>>,
+    /// Front-end or job this brain serves (tui, telegram, cron...), recorded in the audit log
+    pub channel: &'static str,
+    /// Sub-spore persona (see Brain::new_spore)
+    pub spore: SporeContext,
 }
 
 impl Brain {
     pub fn new(config: AppConfig) -> Self {
         let state = openspore_core::state::AppState::new(config.clone());
         let memory = MemorySystem::new(&state);
+        memory.warm_search_index();
         let context_manager = openspore_memory::context::ContextManager::new(&state);
+        let approvals = Arc::new(approval::ApprovalGate::from_config(&config));
+        let spore = SporeContext::from_config(&config);
 
         Self {
-            client: reqwest::Client::new(),
+            client: reqwest::Client::builder()
+                .timeout(config.llm_timeout())
+                .build()
+                .unwrap_or_default(),
             config: config.clone(),
-            skill_loader: Arc::new(SkillLoader::new(config)),
+            live_config: Arc::new(std::sync::RwLock::new(Arc::new(config.clone()))),
+            skill_loader: Arc::new(std::sync::RwLock::new(SkillLoader::new(config))),
             memory,
             context_manager,
             io: Arc::new(openspore_io::get_bridge()),
+            private_turns: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            approvals,
+            model_override: Arc::new(std::sync::RwLock::new(None)),
+            classifier_samples: Default::default(),
+            last_answer: Default::default(),
+            ack_journal: Default::default(),
+            channel: "cli",
+            spore,
         }
     }
 
+    /// A brain answering as a delegated sub-spore in `role`
+    pub fn new_spore(config: AppConfig, role: Option<String>) -> Self {
+        Self { spore: SporeContext::spore(role), ..Self::new(config) }
+    }
+
     pub fn clone_brain(&self) -> Self {
         self.clone()
     }
 
+    pub fn with_channel(mut self, channel: &'static str) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Apply configuration updates (AppConfig::watch) from the next completion
+    /// on: models, fallbacks, sampling, the provider and its endpoint and key.
+    /// Shared by all clones of this brain.
+    pub fn follow_config(&self, mut updates: tokio::sync::watch::Receiver<Arc<AppConfig>>) {
+        let live = self.live_config.clone();
+        tokio::spawn(async move {
+            while updates.changed().await.is_ok() {
+                let config = updates.borrow_and_update().clone();
+                if let Ok(mut live) = live.write() {
+                    *live = config;
+                }
+            }
+        });
+    }
+
+    /// The skill registry. Keep the guard short: take what you need (`get`
+    /// returns a shared skill) and drop it before awaiting.
+    pub fn skills(&self) -> std::sync::RwLockReadGuard<'_, SkillLoader> {
+        self.skill_loader.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Read the plugin directory again; returns how many plugins were loaded.
+    /// Calls already running finish with the skill they started with.
+    pub fn reload_skills(&self) -> usize {
+        self.skill_loader.write().unwrap_or_else(|e| e.into_inner()).reload_plugins()
+    }
+
+    /// The configuration LLM calls use: the latest one when following updates
+    pub fn llm_config(&self) -> Arc<AppConfig> {
+        self.live_config.read().map(|c| c.clone()).unwrap_or_else(|_| Arc::new(self.config.clone()))
+    }
+
+    /// Skills left out of the prompt and the tool definitions: those
+    /// ALLOWED_SKILLS/DENIED_SKILLS rule out, and delegate for sub-spores (no recursion)
+    pub fn excluded_skills(&self) -> Vec<String> {
+        let mut excluded: Vec<String> = self.skills().summaries().into_iter()
+            .map(|(name, _)| name.to_string())
+            .filter(|name| !self.skill_permitted(name))
+            .collect();
+        if self.spore.is_spore {
+            excluded.push("delegate".to_string());
+        }
+        excluded
+    }
+
+    /// Whether the configuration lets this brain use a skill
+    pub fn skill_permitted(&self, name: &str) -> bool {
+        self.llm_config().skill_permitted(name)
+    }
+
     /// think() with an observer channel for real-time updates
     pub async fn think_with_observer(&self, user_prompt: &str, tx: Option<tokio::sync::mpsc::Sender<events::BrainEvent>>) -> String {
-        self.think_internal(user_prompt, tx).await
+        let suggest = tx.is_some();
+        self.think_internal(user_prompt, tx, suggest, ThinkOptions::default()).await.0
+    }
+
+    /// think_with_observer() with per-turn options (plan mode)
+    pub async fn think_with_options(&self, user_prompt: &str, tx: Option<tokio::sync::mpsc::Sender<events::BrainEvent>>, options: ThinkOptions) -> String {
+        let suggest = tx.is_some();
+        self.think_internal(user_prompt, tx, suggest, options).await.0
     }
 
     pub async fn think(&self, user_prompt: &str) -> String {
-        self.think_internal(user_prompt, None).await
+        self.think_internal(user_prompt, None, false, ThinkOptions::default()).await.0
+    }
+
+    /// think() that also returns token usage and timing of the cycle
+    pub async fn think_with_stats(&self, user_prompt: &str) -> (String, usage::CycleStats) {
+        let (reply, stats, _) = self.think_internal(user_prompt, None, false, ThinkOptions::default()).await;
+        (reply, stats)
+    }
+
+    /// think() for chat front-ends: the reply and its follow-up suggestions (FOLLOWUP_SUGGESTIONS)
+    pub async fn think_with_suggestions(&self, user_prompt: &str) -> (String, Vec<String>) {
+        let (reply, _, suggestions) = self.think_internal(user_prompt, None, true, ThinkOptions::default()).await;
+        (reply, suggestions)
+    }
+
+    /// think_with_observer() that also returns token usage and timing of the cycle
+    pub async fn think_observed_with_stats(&self, user_prompt: &str, tx: Option<tokio::sync::mpsc::Sender<events::BrainEvent>>) -> (String, usage::CycleStats) {
+        let (reply, stats, _) = self.think_internal(user_prompt, tx, false, ThinkOptions::default()).await;
+        (reply, stats)
+    }
+
+    /// think_with_suggestions() with an observer, for chat front-ends that answer approval requests
+    pub async fn think_with_suggestions_observed(&self, user_prompt: &str, tx: tokio::sync::mpsc::Sender<events::BrainEvent>) -> (String, Vec<String>) {
+        let (reply, _, suggestions) = self.think_internal(user_prompt, Some(tx), true, ThinkOptions::default()).await;
+        (reply, suggestions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{config_in, fake_llm};
+
+    #[test]
+    fn spore_context_comes_from_the_caller_or_the_legacy_config() {
+        assert_eq!(SporeContext::spore(Some("  ".to_string())), SporeContext { is_spore: true, role: None });
+        assert_eq!(SporeContext::spore(None).role(), "Sub-Agent");
+        assert_eq!(SporeContext::from_config(&AppConfig::default()), SporeContext::default());
+        let legacy = AppConfig { is_spore: true, spore_role: Some("Critic".to_string()), ..Default::default() };
+        assert_eq!(SporeContext::from_config(&legacy).role(), "Critic");
+    }
+
+    #[tokio::test]
+    async fn a_manager_and_a_spore_think_side_by_side() {
+        let (manager_dir, spore_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (url, requests) = fake_llm(|_| "Done.".to_string()).await;
+        let manager = Brain::new(config_in(manager_dir.path(), &url)).with_channel("tui");
+        let spore = Brain::new_spore(config_in(spore_dir.path(), &url), Some("Researcher".to_string()));
+        manager.memory.ensure_structure().await.unwrap();
+        spore.memory.ensure_structure().await.unwrap();
+
+        assert!(spore.excluded_skills().contains(&"delegate".to_string()));
+        assert!(!manager.excluded_skills().contains(&"delegate".to_string()));
+
+        let task = "Read the tide tables in notes and summarize the week";
+        let (a, b) = tokio::join!(manager.think(task), spore.think(task));
+        assert_eq!((a.as_str(), b.as_str()), ("Done.", "Done."));
+        assert!(std::env::var_os("IS_SPORE").is_none() && std::env::var_os("SPORE_ROLE").is_none(), "nothing went through the environment");
+
+        let systems: Vec<String> = (0..requests.len()).map(|i| requests.message(i, "system")).filter(|s| !s.is_empty()).collect();
+        let spore_prompt = systems.iter().find(|s| s.contains("Role: Researcher")).expect("the spore's prompt");
+        let manager_prompt = systems.iter().find(|s| !s.contains("Sub-Agent")).expect("the manager's prompt");
+        let index = |prompt: &str| prompt.lines().find(|l| l.starts_with("Available Skills")).unwrap_or_default().to_string();
+        assert!(index(spore_prompt).contains("READ_FILE") && !index(spore_prompt).contains("DELEGATE"), "{}", index(spore_prompt));
+        assert!(index(manager_prompt).contains("DELEGATE"));
+
+        let roles = |brain: &Brain| -> Vec<Option<String>> {
+            std::fs::read_to_string(brain.usage_path()).unwrap().lines()
+                .map(|l| serde_json::from_str::<usage::UsageRecord>(l).unwrap())
+                .filter(|r| r.event_type == "think")
+                .map(|r| r.role)
+                .collect()
+        };
+        assert_eq!(roles(&manager), [None]);
+        assert_eq!(roles(&spore), [Some("Researcher".to_string())]);
     }
 }
@@ -70,11 +70,11 @@ impl ToolParser {
                                 }
                             }
                         } else {
-                            if current + 3 <= len && chars[current..current+3].iter().collect::<String>() == "```" {
-                                if !in_quote {
-                                    found_end = true;
-                                    break;
-                                }
+                            if current + 3 <= len && chars[current..current+3].iter().collect::<String>() == "```"
+                                && !in_quote
+                            {
+                                found_end = true;
+                                break;
                             }
                             let c = chars[current];
                             if escape { escape = false; }
@@ -0,0 +1,358 @@
+//! Background work of finished cycles, kept until it is done
+//!
+//! After a cycle, learning from the exchange and compressing older context
+//! items run in the background. Quitting right after an answer used to kill
+//! them halfway, so exchanges were learned twice or context never shrank. Each
+//! task is now written to workspace/autonomy/state/pending_work.json (sealed
+//! with ENCRYPT_MEMORY) before it starts and removed once it ran. On exit the
+//! process waits up to DRAIN_GRACE for running tasks; the rest stays in the
+//! file and the next TUI start runs it again. Both are safe to repeat: learned
+//! memories record their cycle and a cycle is never learned twice, and
+//! compression only takes the items that still exist.
+
+use crate::Brain;
+use crate::usage::FastPathStats;
+use chrono::{DateTime, Local};
+use openspore_memory::MemoryItem;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+/// Location of the queue, relative to the workspace
+pub const PENDING_WORK_FILE: &str = "autonomy/state/pending_work.json";
+
+/// How long an exiting process waits for running background work
+pub const DRAIN_GRACE: Duration = Duration::from_secs(15);
+
+/// Categories learn() writes to
+const LEARNED_CATEGORIES: [&str; 3] = ["preferences", "knowledge", "context"];
+
+/// Read-modify-write of the queue (all brains of a process)
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Supervisors running in this process
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+fn idle() -> &'static Notify {
+    static IDLE: OnceLock<Notify> = OnceLock::new();
+    IDLE.get_or_init(Notify::new)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Work {
+    Learn { prompt: String, response: String },
+    /// Files of the session's context directory to fold into the summary
+    Compress { items: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTask {
+    /// "<cycle>:learn" or "<cycle>:compress"
+    pub id: String,
+    pub cycle: String,
+    /// Process running it
+    pub owner: u32,
+    pub queued: DateTime<Local>,
+    #[serde(flatten)]
+    pub work: Work,
+}
+
+/// Decrements RUNNING when a supervisor ends, even by panic
+struct RunningGuard;
+
+impl RunningGuard {
+    fn enter() -> Self {
+        RUNNING.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        if RUNNING.fetch_sub(1, Ordering::SeqCst) == 1 {
+            idle().notify_waiters();
+        }
+    }
+}
+
+/// Wait up to `grace` for the background work of this process. Returns the
+/// number of supervisors still running (their tasks stay queued for the next start).
+pub async fn drain(grace: Duration) -> usize {
+    let deadline = tokio::time::Instant::now() + grace;
+    loop {
+        let notified = idle().notified();
+        let running = RUNNING.load(Ordering::SeqCst);
+        if running == 0 {
+            return 0;
+        }
+        if tokio::time::timeout_at(deadline, notified).await.is_err() {
+            return RUNNING.load(Ordering::SeqCst);
+        }
+    }
+}
+
+/// Supervisors running in this process
+pub fn running() -> usize {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+impl Brain {
+    pub fn pending_work_path(&self) -> PathBuf {
+        self.memory.memory_root.join(PENDING_WORK_FILE)
+    }
+
+    /// Queued tasks, empty when the file is missing or unreadable
+    pub fn pending_work(&self) -> Vec<PendingTask> {
+        let path = self.pending_work_path();
+        let Ok(text) = self.memory.read_text(&path) else { return Vec::new() };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            warn!("🧺 Ignoring unreadable {}: {}", path.display(), e);
+            Vec::new()
+        })
+    }
+
+    /// Read-modify-write of the queue under the process-wide lock; the file
+    /// is removed once empty
+    fn update_pending_work<T>(&self, change: impl FnOnce(&mut Vec<PendingTask>) -> T) -> anyhow::Result<T> {
+        let _guard = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut tasks = self.pending_work();
+        let result = change(&mut tasks);
+        let path = self.pending_work_path();
+        if tasks.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+        } else {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&path, self.memory.seal_if_enabled(&serde_json::to_string_pretty(&tasks)?)?)?;
+        }
+        Ok(result)
+    }
+
+    /// Queue learning from a finished cycle and compression of its older
+    /// context items, then run them in the background. What the fast path
+    /// leaves out is counted in `fast_path`.
+    pub(crate) fn queue_background_work(&self, cycle: &str, prompt: &str, response: &str, older_items: &[MemoryItem], acknowledgment: bool, fast_path: &mut FastPathStats) {
+        let task = |kind: &str, work: Work| PendingTask {
+            id: format!("{}:{}", cycle, kind),
+            cycle: cycle.to_string(),
+            owner: std::process::id(),
+            queued: Local::now(),
+            work,
+        };
+        // Acknowledgments are never learned and leave the session summary alone: nothing to queue
+        if acknowledgment {
+            fast_path.learn_skipped += 1;
+            if !older_items.is_empty() {
+                fast_path.summary_skipped += 1;
+            }
+            return;
+        }
+        let mut tasks = Vec::new();
+        if self.take_learn_slot() {
+            tasks.push(task("learn", Work::Learn { prompt: prompt.to_string(), response: response.to_string() }));
+        } else {
+            debug!("🧺 Learn throttled for cycle {} (LEARN_MIN_INTERVAL_SECS)", cycle);
+            fast_path.learn_throttled += 1;
+        }
+        if !older_items.is_empty() {
+            tasks.push(task("compress", Work::Compress { items: older_items.iter().map(|m| m.filename.clone()).collect() }));
+        }
+        if tasks.is_empty() {
+            return;
+        }
+        let queued = tasks.clone();
+        if let Err(e) = self.update_pending_work(|pending| pending.extend(queued)) {
+            warn!("🧺 Background work not queued (it still runs): {}", e);
+        }
+        self.supervise(tasks);
+    }
+
+    /// Run `tasks` in order in the background, removing each from the queue once done
+    fn supervise(&self, tasks: Vec<PendingTask>) {
+        let brain = self.clone_brain();
+        let guard = RunningGuard::enter();
+        tokio::spawn(async move {
+            let _guard = guard;
+            for task in tasks {
+                brain.run_pending(&task).await;
+                if let Err(e) = brain.update_pending_work(|pending| pending.retain(|t| t.id != task.id)) {
+                    warn!("🧺 Could not mark {} done: {}", task.id, e);
+                }
+            }
+        });
+    }
+
+    async fn run_pending(&self, task: &PendingTask) {
+        match &task.work {
+            Work::Learn { prompt, response } => {
+                if self.learned_in_cycle(&task.cycle) {
+                    debug!("🧺 Cycle {} already learned, skipped", task.cycle);
+                    return;
+                }
+                self.learn(&task.cycle, prompt, response).await;
+            }
+            Work::Compress { items } => {
+                // Compressed (or deleted) since: only the rest is folded in
+                let remaining: Vec<MemoryItem> = self.memory.get_memories("context")
+                    .into_iter()
+                    .filter(|m| items.contains(&m.filename))
+                    .collect();
+                if remaining.is_empty() {
+                    debug!("🧺 Context items of cycle {} already compressed", task.cycle);
+                    return;
+                }
+                if let Err(e) = self.context_manager.compress_older_items(remaining, self).await {
+                    warn!("Context compression notice (might be parallel task): {}", e);
+                }
+            }
+        }
+    }
+
+    /// Whether a memory learned from `cycle` exists
+    fn learned_in_cycle(&self, cycle: &str) -> bool {
+        LEARNED_CATEGORIES.iter().any(|category| {
+            self.memory.get_memories(category).iter().any(|m| m.meta("type") == Some("learned") && m.meta("cycle") == Some(cycle))
+        })
+    }
+
+    /// Run the tasks left by processes that exited before finishing them.
+    /// Returns how many were resumed.
+    pub fn resume_pending_work(&self) -> usize {
+        let me = std::process::id();
+        let claimed = self.update_pending_work(|pending| {
+            let mut claimed = Vec::new();
+            for task in pending.iter_mut() {
+                if task.owner != me && !openspore_core::process::is_alive(task.owner) {
+                    task.owner = me;
+                    claimed.push(task.clone());
+                }
+            }
+            claimed
+        });
+        let claimed = match claimed {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                warn!("🧺 Pending background work not resumed: {}", e);
+                return 0;
+            }
+        };
+        if claimed.is_empty() {
+            return 0;
+        }
+        info!("🧺 Resuming {} background task(s) left by the last run", claimed.len());
+
+        // One supervisor per cycle, keeping learn before compress
+        let mut cycles: Vec<Vec<PendingTask>> = Vec::new();
+        for task in claimed.iter().cloned() {
+            match cycles.iter_mut().find(|c| c[0].cycle == task.cycle) {
+                Some(cycle) => cycle.push(task),
+                None => cycles.push(vec![task]),
+            }
+        }
+        for tasks in cycles {
+            self.supervise(tasks);
+        }
+        claimed.len()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::{brain_in, fake_llm, Requests};
+
+    const LEARN_REPLY: &str = r#"{"should_save": true, "category": "knowledge", "title": "Harbor tide tables", "content": "The harbor publishes tide tables on Mondays", "tags": ["harbor"]}"#;
+
+    /// Pid of a process that has exited: the run that was killed
+    fn killed_pid() -> u32 {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    fn learn_task(cycle: &str, owner: u32) -> PendingTask {
+        PendingTask {
+            id: format!("{}:learn", cycle),
+            cycle: cycle.to_string(),
+            owner,
+            queued: Local::now(),
+            work: Work::Learn { prompt: "when are the tide tables out".to_string(), response: "Mondays".to_string() },
+        }
+    }
+
+    async fn learning_brain(root: &std::path::Path) -> (Brain, Requests) {
+        let (url, requests) = fake_llm(|_| LEARN_REPLY.to_string()).await;
+        (brain_in(root, &url).await, requests)
+    }
+
+    fn learned(brain: &Brain, cycle: &str) -> usize {
+        brain.memory.get_memories("knowledge").iter().filter(|m| m.meta("cycle") == Some(cycle)).count()
+    }
+
+    #[tokio::test]
+    async fn work_of_a_killed_run_is_resumed_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let (brain, requests) = learning_brain(dir.path()).await;
+        let dead = killed_pid();
+        let compress = PendingTask {
+            id: "c1:compress".to_string(),
+            work: Work::Compress { items: vec!["long_gone.md".to_string()] },
+            ..learn_task("c1", dead)
+        };
+        let mine = learn_task("c3", std::process::id());
+        brain.update_pending_work(|pending| pending.extend([learn_task("c1", dead), compress, mine])).unwrap();
+
+        assert_eq!(brain.resume_pending_work(), 2);
+        assert_eq!(drain(Duration::from_secs(10)).await, 0);
+        assert_eq!(requests.mentioning("Analyze this conversation"), 1);
+        assert_eq!(learned(&brain, "c1"), 1);
+
+        // The claimed tasks are gone; work of a live process is left to it
+        let left: Vec<String> = brain.pending_work().into_iter().map(|t| t.id).collect();
+        assert_eq!(left, ["c3:learn"]);
+        assert_eq!(brain.resume_pending_work(), 0);
+        assert_eq!(requests.mentioning("Analyze this conversation"), 1);
+    }
+
+    #[tokio::test]
+    async fn work_done_just_before_the_kill_is_not_repeated() {
+        let dir = tempfile::tempdir().unwrap();
+        let (brain, requests) = learning_brain(dir.path()).await;
+
+        // The killed run learned from c2 but died before dequeuing the task
+        assert!(brain.learn("c2", "when are the tide tables out", "Mondays").await);
+        brain.update_pending_work(|pending| pending.push(learn_task("c2", killed_pid()))).unwrap();
+        assert!(brain.pending_work_path().exists());
+
+        assert_eq!(brain.resume_pending_work(), 1);
+        assert_eq!(drain(Duration::from_secs(10)).await, 0);
+        assert_eq!(requests.mentioning("Analyze this conversation"), 1, "only the killed run's call");
+        assert_eq!(learned(&brain, "c2"), 1);
+        assert!(!brain.pending_work_path().exists(), "the file goes once the queue is empty");
+    }
+
+    #[tokio::test]
+    async fn a_finished_cycle_leaves_nothing_queued() {
+        let dir = tempfile::tempdir().unwrap();
+        let (brain, requests) = learning_brain(dir.path()).await;
+        let mut fast_path = FastPathStats::default();
+        brain.queue_background_work("c4", "when are the tide tables out", "Mondays", &[], false, &mut fast_path);
+        let queued = brain.pending_work();
+        assert_eq!(queued.len(), 1, "queued before it runs");
+        assert_eq!(queued[0].owner, std::process::id());
+
+        assert_eq!(drain(Duration::from_secs(10)).await, 0);
+        assert_eq!(learned(&brain, "c4"), 1);
+        assert_eq!(requests.mentioning("Analyze this conversation"), 1);
+        assert!(brain.pending_work().is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+//! Plan mode (dry run) of a think() turn
+//!
+//! With ThinkOptions::dry_run the tool loop parses calls as usual but records
+//! them instead of running them, answering each with DRY_RUN_OUTPUT. After
+//! DRY_RUN_ROUNDS rounds the loop stops and the reply ends with the plan.
+//! Read-only skills may still run (DRY_RUN_READ_ONLY) so the plan is grounded.
+
+use openspore_core::fmt_utils::preview;
+
+/// Tool rounds fed back to the model before the plan is final
+pub(crate) const DRY_RUN_ROUNDS: usize = 2;
+
+/// Added to the system prompt of a dry-run turn
+pub(crate) const PLAN_MODE_PROMPT: &str = "<PLAN_MODE>\nThis turn is a dry run. Call the tools you would use, in order, with their real arguments; they are recorded, not executed. Then describe the plan and its expected outcome in your answer.\n</PLAN_MODE>";
+
+/// What the model gets back for a call that was not run
+pub(crate) const DRY_RUN_OUTPUT: &str = "[DRY RUN] not executed. This turn only plans: assume the call would succeed and continue the plan, or finish it.";
+
+/// Characters of an argument shown in the plan
+const PLAN_ARG_CHARS: usize = 300;
+
+/// One tool call of the plan
+#[derive(Debug, Clone)]
+pub struct PlannedCall {
+    pub name: String,
+    pub arg: String,
+    /// A read-only skill that ran anyway (DRY_RUN_READ_ONLY)
+    pub executed: bool,
+}
+
+/// Markdown plan appended to the dry-run answer
+pub(crate) fn render_plan(calls: &[PlannedCall]) -> String {
+    if calls.is_empty() {
+        return "\n\n---\n📋 **Plan (dry run)**: no tools would be used.".to_string();
+    }
+    let steps = calls.iter().enumerate().map(|(i, call)| {
+        let ran = if call.executed { " _(read-only, ran)_" } else { "" };
+        format!("{}. `{}` {}{}", i + 1, call.name.to_uppercase(), preview(&call.arg, PLAN_ARG_CHARS), ran)
+    }).collect::<Vec<_>>().join("\n");
+    format!("\n\n---\n📋 **Plan (dry run, nothing was changed)**\n{}", steps)
+}
@@ -0,0 +1,203 @@
+//! Post-mortems of failed cycles
+//!
+//! think_internal() treats a cycle as failed when no model answers, when the
+//! tool loop hits its depth limit, or when most of at least TOOL_FAILURE_MIN
+//! tool calls fail. postmortem() adds the environment of the run (model chain,
+//! provider, context budget, depth) and writes the bundle to
+//! workspace/debug/failures (see openspore_memory::FailureCapture). The bundle
+//! path goes into the reply and the journal. Private turns write nothing, and
+//! FAILURE_BUNDLES_MAX=0 turns capture off.
+
+use crate::context_budget::ContextBudget;
+use crate::usage::CycleStats;
+use crate::{Brain, Message};
+use openspore_memory::{FailureCapture, FailureKind, FailureRecord, ToolInvocation};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// Tool calls a cycle needs before failing ones make it a failed cycle
+const TOOL_FAILURE_MIN: usize = 4;
+
+/// The error of a cycle whose tool calls mostly failed
+pub(crate) fn tool_failures(calls: usize, errors: usize) -> Option<String> {
+    (calls >= TOOL_FAILURE_MIN && errors * 2 > calls)
+        .then(|| format!("{} of {} tool calls failed", errors, calls))
+}
+
+/// Everything think_internal() knows about the failed cycle
+pub(crate) struct FailedCycle<'a> {
+    pub kind: FailureKind,
+    pub error: &'a str,
+    pub prompt: &'a str,
+    pub context: &'a str,
+    pub messages: &'a [Message],
+    pub tools: &'a [ToolInvocation],
+    pub stats: &'a CycleStats,
+    pub depth: usize,
+    pub dry_run: bool,
+}
+
+impl Brain {
+    /// Write the post-mortem of a failed cycle. Returns the bundle path
+    /// relative to the project root, None when capture is off or failed.
+    pub(crate) fn postmortem(&self, failed: FailedCycle<'_>) -> Option<String> {
+        let keep = self.config.failure_bundles_max();
+        if keep == 0 {
+            return None;
+        }
+
+        let models = self.model_chain();
+        let budget = ContextBudget::for_models(&self.config, &models);
+        let mut environment = BTreeMap::from([
+            ("models".to_string(), models.join(", ")),
+            ("provider".to_string(), self.llm_config().provider().as_str().to_string()),
+            ("base_url".to_string(), self.config.llm_base_url()),
+            ("context_window".to_string(), budget.window.to_string()),
+            ("reply_budget".to_string(), budget.reply.to_string()),
+            ("prompt_limit".to_string(), budget.prompt_limit().to_string()),
+            ("max_depth".to_string(), crate::thinking::MAX_TOOL_DEPTH.to_string()),
+            ("depth".to_string(), failed.depth.to_string()),
+            ("prompt_mode".to_string(), failed.stats.prompt_mode.as_str().to_string()),
+            ("tool_calling".to_string(), if self.native_tool_calling() { "native" } else { "brackets" }.to_string()),
+            ("dry_run".to_string(), failed.dry_run.to_string()),
+            ("tokens".to_string(), format!("{}/{}", failed.stats.total.prompt_tokens, failed.stats.total.completion_tokens)),
+            ("version".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        ]);
+        if let Some(answered) = failed.stats.answer_model() {
+            environment.insert("answer_model".to_string(), answered.to_string());
+        }
+        if self.spore.is_spore {
+            environment.insert("spore".to_string(), "true".to_string());
+        }
+
+        let record = FailureRecord {
+            cycle: failed.stats.cycle_id.clone(),
+            time: chrono::Local::now(),
+            kind: failed.kind,
+            error: failed.error.to_string(),
+            channel: self.channel.to_string(),
+            prompt: failed.prompt.to_string(),
+            tool_calls: failed.tools.len(),
+            tool_errors: failed.tools.iter().filter(|t| !t.ok).count(),
+            environment,
+        };
+        let messages: Vec<(String, String)> = failed.messages.iter()
+            .map(|m| {
+                let content = match &m.tool_calls {
+                    Some(calls) => format!("{}\n\n[tool_calls] {}", m.content, calls),
+                    None => m.content.clone(),
+                };
+                (m.role.clone(), content)
+            })
+            .collect();
+
+        let capture = FailureCapture { record, context: failed.context, messages: &messages, tools: failed.tools };
+        match self.memory.save_failure(capture, keep) {
+            Ok(dir) => {
+                let shown = dir.strip_prefix(&self.config.project_root).unwrap_or(&dir).display().to_string();
+                info!("🩺 Post-mortem of the failed cycle ({}): {}", failed.kind.as_str(), shown);
+                Some(shown)
+            }
+            Err(e) => {
+                warn!("🩺 Post-mortem not written: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{config_in, fake_llm};
+    use openspore_core::config::AppConfig;
+
+    const TASK: &str = "Read the four rollout configs and compare them. Use token=sk-test-1234567890abcdefghijklmnopqrstuv";
+
+    /// A model that asks for four files that do not exist, then gives up
+    async fn brain(root: &std::path::Path, bundles: Option<usize>) -> Brain {
+        let (url, _) = fake_llm(|request| {
+            let last = request["messages"].as_array().and_then(|m| m.last()).cloned().unwrap_or_default();
+            if last["role"] == "user" && last["content"].as_str().is_some_and(|c| c.starts_with("Read the four")) {
+                (1..=4).map(|i| format!("[READ_FILE: missing/rollout_{}.toml]", i)).collect::<Vec<_>>().join("\n")
+            } else {
+                "None of the configs could be read.".to_string()
+            }
+        }).await;
+        let brain = Brain::new(AppConfig { failure_bundles_max: bundles, ..config_in(root, &url) }).with_channel("tui");
+        brain.memory.ensure_structure().await.unwrap();
+        brain
+    }
+
+    #[test]
+    fn most_tool_calls_failing_fails_the_cycle() {
+        assert_eq!(tool_failures(3, 3), None, "too few calls to tell");
+        assert_eq!(tool_failures(4, 2), None);
+        assert_eq!(tool_failures(4, 3).as_deref(), Some("3 of 4 tool calls failed"));
+        assert_eq!(tool_failures(10, 6).as_deref(), Some("6 of 10 tool calls failed"));
+    }
+
+    #[tokio::test]
+    async fn a_failing_cycle_leaves_a_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain(dir.path(), None).await;
+
+        let reply = brain.think(TASK).await;
+        let bundles = brain.memory.list_failures();
+        assert_eq!(bundles.len(), 1);
+        let bundle = &bundles[0];
+        let shown = bundle.path.strip_prefix(dir.path()).unwrap().display().to_string();
+        assert!(reply.contains(&format!("{}tool_failures): 4 of 4 tool calls failed\nPost-mortem: {}", crate::CYCLE_FAILED_MARKER, shown)), "{}", reply);
+
+        let record = &bundle.record;
+        assert_eq!(record.kind, FailureKind::ToolFailures);
+        assert_eq!((record.tool_calls, record.tool_errors), (4, 4));
+        assert_eq!(record.channel, "tui");
+        assert_eq!(record.environment["models"], "test-model");
+        assert_eq!(record.environment["max_depth"], crate::thinking::MAX_TOOL_DEPTH.to_string());
+        assert!(!record.prompt.contains("sk-test-1234567890"), "secrets are redacted: {}", record.prompt);
+
+        let tools = bundle.tools();
+        assert_eq!(tools.len(), 4);
+        assert!(tools.iter().all(|t| !t.ok && t.name == "READ_FILE" && t.arg.starts_with("missing/rollout_")));
+        let messages = std::fs::read_to_string(bundle.path.join("messages.json")).unwrap();
+        assert!(messages.contains("rollout_4.toml") && !messages.contains("sk-test-1234567890"));
+        assert!(std::fs::read_to_string(bundle.path.join("context.md")).unwrap().contains("<USER_REQUEST>"));
+
+        // The journal points at the bundle too
+        let journal = std::fs::read_to_string(brain.memory.context_dir().join("LOGS.md")).unwrap();
+        assert!(journal.contains(&format!("Post-mortem: {}", shown)));
+    }
+
+    #[tokio::test]
+    async fn only_the_newest_bundles_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain(dir.path(), Some(2)).await;
+        let mut cycles = Vec::new();
+        for _ in 0..3 {
+            let (_, stats) = brain.think_with_stats(TASK).await;
+            cycles.push(stats.cycle_id);
+        }
+
+        let kept: Vec<String> = brain.memory.list_failures().iter().map(|b| b.record.cycle.clone()).collect();
+        assert_eq!(kept, [cycles[2].clone(), cycles[1].clone()]);
+        assert!(!brain.memory.failures_dir().join(&cycles[0]).exists());
+        assert_eq!(brain.memory.find_failure("last").unwrap().record.cycle, cycles[2]);
+    }
+
+    #[tokio::test]
+    async fn no_bundle_when_capture_is_off_or_the_turn_is_fine() {
+        let dir = tempfile::tempdir().unwrap();
+        let off = brain(dir.path(), Some(0)).await;
+        let reply = off.think(TASK).await;
+        assert!(!reply.contains("Post-mortem:"));
+        assert!(off.memory.list_failures().is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = fake_llm(|_| "All four configs agree.".to_string()).await;
+        let fine = Brain::new(config_in(dir.path(), &url)).with_channel("tui");
+        fine.memory.ensure_structure().await.unwrap();
+        fine.think(TASK).await;
+        assert!(fine.memory.list_failures().is_empty());
+    }
+}
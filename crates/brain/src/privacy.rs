@@ -0,0 +1,97 @@
+//! Ephemeral (private) turns
+//!
+//! A message starting with `!private` is processed normally but nothing from
+//! that turn is persisted: no journal line, no exchange, no learning.
+//! `/private N` marks the next N turns as private.
+
+use crate::Brain;
+use std::sync::atomic::Ordering;
+
+pub const PRIVATE_MARKER: &str = "!private";
+pub const PRIVATE_COMMAND: &str = "/private";
+
+/// How a raw user message should be treated before reaching the model.
+pub enum PrivacyDirective {
+    /// Regular turn (may still be private if a `/private N` window is open).
+    Normal(String),
+    /// `!private ...` one-shot marker, stripped prompt inside.
+    Private(String),
+    /// `/private N` toggle; no model call needed.
+    Toggle(usize),
+}
+
+pub fn parse_directive(prompt: &str) -> PrivacyDirective {
+    let trimmed = prompt.trim_start();
+
+    if let Some(rest) = trimmed.strip_prefix(PRIVATE_COMMAND)
+        && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+    {
+        let turns = rest.trim().parse::<usize>().unwrap_or(1);
+        return PrivacyDirective::Toggle(turns);
+    }
+
+    if let Some(rest) = trimmed.strip_prefix(PRIVATE_MARKER)
+        && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+    {
+        return PrivacyDirective::Private(rest.trim_start().to_string());
+    }
+
+    PrivacyDirective::Normal(prompt.to_string())
+}
+
+impl Brain {
+    /// Whether the given message would run as a private turn (does not consume the window).
+    pub fn is_private_turn(&self, prompt: &str) -> bool {
+        match parse_directive(prompt) {
+            PrivacyDirective::Private(_) | PrivacyDirective::Toggle(_) => true,
+            PrivacyDirective::Normal(_) => self.private_turns.load(Ordering::SeqCst) > 0,
+        }
+    }
+
+    /// Consume one turn from the `/private N` window, if open.
+    pub(crate) fn take_private_turn(&self) -> bool {
+        self.private_turns
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{brain_in, fake_llm, snapshot};
+
+    #[test]
+    fn directives() {
+        assert!(matches!(parse_directive("!private what's my balance"), PrivacyDirective::Private(p) if p == "what's my balance"));
+        assert!(matches!(parse_directive("/private 3"), PrivacyDirective::Toggle(3)));
+        assert!(matches!(parse_directive("/private"), PrivacyDirective::Toggle(1)));
+        assert!(matches!(parse_directive("!privateer"), PrivacyDirective::Normal(_)));
+    }
+
+    #[tokio::test]
+    async fn private_turn_with_a_read_only_tool_writes_nothing() {
+        let (dir, outside) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let notes = outside.path().join("notes.txt");
+        std::fs::write(&notes, "pin 4242").unwrap();
+        let call = format!("[READ_FILE: \"{}\"]", notes.display());
+        let (url, requests) = fake_llm(move |request| {
+            if request["messages"].to_string().contains("pin 4242") {
+                "The note holds your pin.".to_string()
+            } else {
+                call.clone()
+            }
+        }).await;
+        let brain = brain_in(dir.path(), &url).await;
+        let workspace = dir.path().join("workspace");
+        let before = snapshot(&workspace);
+
+        let (reply, stats) = brain.think_with_stats("!private read my notes file").await;
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+        assert_eq!(reply, "The note holds your pin.");
+        assert!(stats.layers > 1, "the read_file round ran");
+        assert_eq!(requests.len(), 2, "no learn or summary call after a private turn");
+        assert_eq!(snapshot(&workspace), before);
+    }
+}
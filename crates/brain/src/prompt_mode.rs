@@ -0,0 +1,525 @@
+//! Slim prompts for general chat
+//!
+//! "what's a good pasta recipe" needs no skills list, memory search or
+//! delegation strategy. With PROMPT_MODE=auto (the default), interactive
+//! messages that read as plain conversation (no imperative verb, no file,
+//! code or workspace reference) get a slim prompt: identity, preferences and
+//! the recent conversation only, and no tool loop. PROMPT_MODE_LLM_CHECK asks
+//! the Fast model to confirm first. "use your tools" in a message, or
+//! PROMPT_MODE=full, always gets the full agent prompt. So does a bare
+//! confirmation ("yes", "ok go ahead", "sure, do it") and any reply to an
+//! answer that asked a question or offered to act: the approved work needs
+//! the tools.
+//!
+//! The Fast model's reply is capped to a few tokens and read for the first
+//! CHAT or TASK word anywhere in it ("This is a CHAT message." counts). A
+//! reply naming neither gets one stricter retry, then the heuristic stands.
+//! The last replies and what was read from them are kept for `/inspect`.
+
+use crate::api::ReplyLimit;
+use crate::{Brain, Message, ThinkOptions};
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tracing::debug;
+
+/// Phrase that forces the full prompt for one turn
+pub const FORCE_FULL_PHRASE: &str = "use your tools";
+
+/// Longer messages are treated as tasks (pastes, specs, instructions)
+const MAX_CHAT_CHARS: usize = 600;
+
+/// Brain channels a person types into; jobs and watchers always get the full prompt
+const INTERACTIVE_CHANNELS: [&str; 3] = ["tui", "telegram", "cli"];
+
+/// A sentence starting with one of these asks for work, not conversation
+const IMPERATIVE_VERBS: &[&str] = &[
+    "add", "analyse", "analyze", "backup", "browse", "build", "change", "check", "clean", "click",
+    "commit", "compare", "compile", "configure", "copy", "create", "debug", "delegate", "delete",
+    "deploy", "diff", "download", "edit", "execute", "fetch", "find", "fix", "generate", "grep",
+    "implement", "index", "install", "kill", "list", "look", "make", "merge", "move", "navigate",
+    "open", "organize", "patch", "plan", "post", "push", "read", "refactor", "remember", "remind",
+    "remove", "rename", "research", "restart", "review", "run", "save", "scan", "schedule", "search",
+    "send", "set", "show", "spawn", "start", "stop", "store", "summarise", "summarize", "sync",
+    "test", "update", "write",
+];
+
+/// Words that point at the workspace, the machine or earlier knowledge
+const WORKSPACE_WORDS: &[&str] = &[
+    "branch", "code", "command", "crate", "cron", "delegate", "directory", "file", "files", "folder",
+    "journal", "memories", "memory", "note", "notes", "project", "recall", "remember", "repo",
+    "script", "skill", "spore", "task", "terminal", "tool", "tools", "workspace",
+];
+
+/// Words of a bare confirmation ("ok go ahead", "sure, do it", "yes please")
+const CONFIRMATION_WORDS: &[&str] = &[
+    "yes", "yep", "yeah", "yup", "y", "sure", "ok", "okay", "k", "alright", "go", "ahead", "do", "it",
+    "that", "this", "please", "pls", "proceed", "continue", "sounds", "good", "fine", "let", "lets", "s",
+    "approved", "approve", "confirm", "confirmed", "absolutely", "of", "course", "for", "right", "away",
+    "now", "then", "and", "ship",
+];
+
+/// At least one of these makes the words a confirmation, not just filler
+const ASSENT_WORDS: &[&str] = &[
+    "yes", "yep", "yeah", "yup", "y", "sure", "ok", "okay", "k", "alright", "go", "do", "proceed",
+    "continue", "approved", "approve", "confirm", "confirmed", "absolutely", "ship",
+];
+
+/// An answer whose end contains one of these offered to do something
+const OFFER_PHRASES: &[&str] = &[
+    "shall i", "should i", "want me to", "would you like", "do you want", "let me know if",
+    "i can ", "i could ", "if you'd like", "if you want", "ready to",
+];
+
+/// Characters at the end of an answer searched for a question or an offer
+const ANSWER_TAIL_CHARS: usize = 300;
+
+/// Classifier reply budget: one word, a short sentence at most
+const CLASSIFIER_LIMIT: ReplyLimit = ReplyLimit { max_tokens: 8, stop: &["\n"] };
+
+/// Classifier samples kept for `/inspect`
+pub const CLASSIFIER_SAMPLES: usize = 50;
+
+/// Openings dropped before looking for the verb ("please run...", "can you fix...")
+const POLITE_OPENINGS: &[&str] = &["please", "pls", "can you", "could you", "would you", "will you", "now", "then", "also", "and"];
+
+/// Prompt assembled for one turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptMode {
+    /// Skills, memory search, delegation strategy and the tool loop
+    #[default]
+    Full,
+    /// Identity, preferences and recent conversation; no tools
+    Slim,
+}
+
+impl PromptMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptMode::Full => "full",
+            PromptMode::Slim => "slim",
+        }
+    }
+}
+
+/// One run of the Fast-model classifier
+#[derive(Debug, Clone)]
+pub struct ClassifierSample {
+    pub at: DateTime<Local>,
+    /// Start of the classified message
+    pub message: String,
+    /// Replies as received: the first, then the stricter retry if there was one
+    pub replies: Vec<String>,
+    /// Mode read from the replies, None when neither named a category
+    pub verdict: Option<PromptMode>,
+}
+
+impl ClassifierSample {
+    /// The first reply was exactly the category word
+    pub fn is_clean(&self) -> bool {
+        self.replies.first().is_some_and(|r| {
+            let word = r.trim().trim_matches(|c: char| !c.is_alphanumeric());
+            word.eq_ignore_ascii_case("chat") || word.eq_ignore_ascii_case("task")
+        })
+    }
+}
+
+/// The category a classifier reply names: its first CHAT or TASK word,
+/// any case, anywhere in the text (markdown, punctuation and sentences around it ignored)
+pub fn parse_verdict(reply: &str) -> Option<PromptMode> {
+    reply.split(|c: char| !c.is_alphanumeric()).find_map(|word| {
+        if word.eq_ignore_ascii_case("chat") {
+            Some(PromptMode::Slim)
+        } else if word.eq_ignore_ascii_case("task") {
+            Some(PromptMode::Full)
+        } else {
+            None
+        }
+    })
+}
+
+/// The user's own words: Telegram puts `<REPLIED_TO>`-style blocks first
+fn own_text(prompt: &str) -> &str {
+    let trimmed = prompt.trim();
+    if !trimmed.starts_with('<') {
+        return trimmed;
+    }
+    trimmed.rfind("</")
+        .and_then(|start| trimmed[start..].find('>').map(|end| start + end + 1))
+        .map_or(trimmed, |end| trimmed[end..].trim())
+}
+
+fn references_files_or_code(text: &str) -> bool {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)`|https?://|::|~/|\./|\w/\w|\b[\w-]+\.(rs|md|toml|json|jsonl|py|js|ts|tsx|txt|sh|ya?ml|csv|html|css|lock|log|env|sql)\b")
+            .expect("valid regex")
+    });
+    pattern.is_match(text)
+}
+
+fn starts_with_imperative(sentence: &str) -> bool {
+    let mut rest = sentence.trim().trim_start_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+    while let Some(opening) = POLITE_OPENINGS.iter().find(|o| rest.starts_with(&format!("{} ", o))) {
+        rest = rest[opening.len()..].trim_start_matches([' ', ',']).to_string();
+    }
+    rest.split(|c: char| !c.is_alphanumeric())
+        .next()
+        .is_some_and(|word| IMPERATIVE_VERBS.contains(&word))
+}
+
+/// "yes", "ok go ahead", "sure, do it": approval of what was just proposed
+pub fn is_confirmation(prompt: &str) -> bool {
+    let text = own_text(prompt).to_lowercase();
+    let words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    !words.is_empty()
+        && words.len() <= 8
+        && words.iter().all(|w| CONFIRMATION_WORDS.contains(w))
+        && words.iter().any(|w| ASSENT_WORDS.contains(w))
+}
+
+/// The answer ends with a question or offers to act ("Shall I write the fix?")
+pub fn invites_reply(answer: &str) -> bool {
+    let answer = answer.trim_end().trim_end_matches(|c: char| matches!(c, '*' | '_' | '`' | ')' | '"' | '\'') || c.is_whitespace());
+    if answer.ends_with('?') {
+        return true;
+    }
+    let chars = answer.chars().count();
+    let tail: String = answer.chars().skip(chars.saturating_sub(ANSWER_TAIL_CHARS)).collect::<String>().to_lowercase();
+    OFFER_PHRASES.iter().any(|phrase| tail.contains(phrase))
+}
+
+/// Heuristic half of the classifier: plain conversation, not a task
+pub fn looks_like_chat(prompt: &str) -> bool {
+    let text = own_text(prompt);
+    if text.is_empty() || text.chars().count() > MAX_CHAT_CHARS || references_files_or_code(text) {
+        return false;
+    }
+    let lower = text.to_lowercase();
+    if lower.contains(FORCE_FULL_PHRASE) {
+        return false;
+    }
+    let mentions_workspace = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| WORKSPACE_WORDS.contains(&word));
+    !mentions_workspace && !text.split(['.', '!', '?', '\n', ';']).any(starts_with_imperative)
+}
+
+impl Brain {
+    /// PROMPT_MODE=auto (default) or full
+    pub fn slim_prompts_enabled(&self) -> bool {
+        match self.config.prompt_mode.as_deref().map(|s| s.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("auto") => true,
+            Some("full") => false,
+            Some(other) => {
+                tracing::warn!("PROMPT_MODE={} is not auto or full, using auto", other);
+                true
+            }
+        }
+    }
+
+    /// Prompt for this turn. Sub-spores, plan mode and non-interactive
+    /// channels always get the full prompt.
+    pub(crate) async fn prompt_mode(&self, prompt: &str, options: ThinkOptions) -> PromptMode {
+        if options.dry_run
+            || self.spore.is_spore
+            || !INTERACTIVE_CHANNELS.contains(&self.channel)
+            || !self.slim_prompts_enabled()
+            || !looks_like_chat(prompt)
+        {
+            return PromptMode::Full;
+        }
+        // "yes" to "Shall I write the fix?" approves work that needs the tools
+        if is_confirmation(prompt) || self.last_answer().is_some_and(|answer| invites_reply(&answer)) {
+            return PromptMode::Full;
+        }
+        // An acknowledgment is chat without asking (see fast_path)
+        if self.config.prompt_mode_llm_check && !crate::learning::is_acknowledgment(prompt) && !self.confirm_chat(prompt).await {
+            return PromptMode::Full;
+        }
+        PromptMode::Slim
+    }
+
+    /// Fast-model half of the classifier; an unusable reply keeps the heuristic's verdict
+    async fn confirm_chat(&self, prompt: &str) -> bool {
+        let text = own_text(prompt);
+        let request = format!(
+            "Classify this message to an assistant that can run tools, edit files and search saved notes.\n\n{}\n\nReply TASK if answering needs tools, files, notes or actions, or CHAT if it is general conversation or knowledge. Reply with one word.",
+            openspore_core::fmt_utils::truncate_chars(text, 1000)
+        );
+        let mut messages = vec![Message { role: "user".to_string(), content: request, ..Default::default() }];
+        let mut sample = ClassifierSample {
+            at: Local::now(),
+            message: openspore_core::fmt_utils::preview(text, 60),
+            replies: Vec::new(),
+            verdict: None,
+        };
+
+        for attempt in 0..2 {
+            match self.complete_fast_limited(&messages, Some(CLASSIFIER_LIMIT)).await {
+                Ok((reply, usage)) => {
+                    self.record_usage("classifier", &usage);
+                    sample.verdict = parse_verdict(&reply);
+                    sample.replies.push(reply.clone());
+                    if sample.verdict.is_some() || attempt == 1 {
+                        break;
+                    }
+                    messages.push(Message { role: "assistant".to_string(), content: reply, ..Default::default() });
+                    messages.push(Message { role: "user".to_string(), content: "Answer with exactly one word: CHAT or TASK.".to_string(), ..Default::default() });
+                }
+                Err(e) => {
+                    debug!("Prompt mode check skipped: {}", e);
+                    break;
+                }
+            }
+        }
+
+        let verdict = sample.verdict;
+        if !sample.replies.is_empty() {
+            self.record_classifier_sample(sample);
+        }
+        verdict != Some(PromptMode::Full)
+    }
+
+    /// The previous answer of this session, if there was one
+    pub fn last_answer(&self) -> Option<String> {
+        self.last_answer.lock().ok().and_then(|answer| answer.clone())
+    }
+
+    pub(crate) fn set_last_answer(&self, answer: &str) {
+        if let Ok(mut last) = self.last_answer.lock() {
+            *last = Some(answer.to_string());
+        }
+    }
+
+    fn record_classifier_sample(&self, sample: ClassifierSample) {
+        if let Ok(mut samples) = self.classifier_samples.lock() {
+            if samples.len() >= CLASSIFIER_SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(sample);
+        }
+    }
+
+    /// Recent classifier runs of this process, oldest first
+    pub fn classifier_samples(&self) -> Vec<ClassifierSample> {
+        self.classifier_samples.lock().map(|s| s.iter().cloned().collect()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_assembler::ContextAssembler;
+    use crate::test_support::{brain_in, fake_llm};
+
+    #[test]
+    fn bare_confirmations_are_recognized() {
+        for text in ["yes", "ok go ahead", "sure, do it", "Yes please!", "go for it", "sounds good, proceed", "let's do it"] {
+            assert!(is_confirmation(text), "{text}");
+        }
+        for text in ["thanks", "no", "what's a good pasta recipe", "yes but the sauce was too salty", "good"] {
+            assert!(!is_confirmation(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn questions_and_offers_invite_a_reply() {
+        assert!(invites_reply("The test fails on line 12. Shall I write the fix?"));
+        assert!(invites_reply("Done. **Want me to push it?**"));
+        assert!(invites_reply("Here is the diff.\n\nI can also add a regression test if you'd like."));
+        assert!(!invites_reply("Carbonara uses eggs, pecorino and guanciale."));
+    }
+
+    #[test]
+    fn plain_conversation_looks_like_chat() {
+        assert!(looks_like_chat("what's a good pasta recipe"));
+        assert!(!looks_like_chat("fix the failing test in src/main.rs"));
+        assert!(!looks_like_chat("what's in my notes about rust"));
+    }
+
+    #[tokio::test]
+    async fn confirmations_and_replies_to_proposals_get_the_full_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+        let options = ThinkOptions::default();
+
+        assert_eq!(brain.prompt_mode("what's a good pasta recipe", options).await, PromptMode::Slim);
+        for confirmation in ["yes", "ok go ahead", "sure, do it"] {
+            assert_eq!(brain.prompt_mode(confirmation, options).await, PromptMode::Full, "{confirmation}");
+        }
+
+        brain.set_last_answer("Carbonara is a classic.");
+        assert_eq!(brain.prompt_mode("that sounds lovely", options).await, PromptMode::Slim);
+        brain.set_last_answer("The parser drops the last line. Shall I write the fix?");
+        assert_eq!(brain.prompt_mode("that sounds lovely", options).await, PromptMode::Full);
+    }
+
+    #[tokio::test]
+    async fn slim_prompt_omits_the_skills_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+
+        let (full, ..) = ContextAssembler::build_system_prompt(&brain, "what's a good pasta recipe", false, PromptMode::Full).await;
+        let (slim, ..) = ContextAssembler::build_system_prompt(&brain, "what's a good pasta recipe", false, PromptMode::Slim).await;
+
+        assert!(full.contains("Available Skills"));
+        assert!(full.contains("[READ_FILE]"));
+        assert!(!slim.contains("Available Skills"));
+        assert!(!slim.contains("[READ_FILE]"));
+        assert!(!slim.contains("SKILL_HELP"));
+    }
+
+    #[tokio::test]
+    async fn yes_to_a_proposal_runs_with_the_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, requests) = fake_llm(|request| {
+            let last = request["messages"].as_array().and_then(|m| m.last()).cloned().unwrap_or_default();
+            if last["content"] == "yes" { "Done, the fix is written.".to_string() } else { "The parser drops the last line. Shall I write the fix?".to_string() }
+        }).await;
+        let brain = brain_in(dir.path(), &url).await;
+
+        let (_, first) = brain.think_with_stats("why does my pasta timer skip the last minute").await;
+        assert_eq!(first.prompt_mode, PromptMode::Slim);
+
+        let (reply, stats) = brain.think_with_stats("yes").await;
+        assert_eq!(reply, "Done, the fix is written.");
+        assert_eq!(stats.prompt_mode, PromptMode::Full);
+        let call = requests.find_user("yes").expect("the confirmation reached the model");
+        assert!(requests.message(call, "system").contains("Available Skills"));
+    }
+
+    /// Replies seen from small models, and the category each names
+    const CLASSIFIER_REPLIES: &[(&str, Option<PromptMode>)] = &[
+        ("CHAT", Some(PromptMode::Slim)),
+        ("TASK", Some(PromptMode::Full)),
+        ("task\n", Some(PromptMode::Full)),
+        (" Chat.", Some(PromptMode::Slim)),
+        ("**TASK**", Some(PromptMode::Full)),
+        ("`CHAT`", Some(PromptMode::Slim)),
+        ("\"TASK\"", Some(PromptMode::Full)),
+        ("This is a CHAT message.", Some(PromptMode::Slim)),
+        ("Category: TASK", Some(PromptMode::Full)),
+        ("The answer is: chat", Some(PromptMode::Slim)),
+        ("I'd say TASK, since it needs the files", Some(PromptMode::Full)),
+        ("TASK (needs tools)", Some(PromptMode::Full)),
+        ("CHAT/TASK: CHAT", Some(PromptMode::Slim)),
+        ("<answer>TASK</answer>", Some(PromptMode::Full)),
+        ("TASK: CHAT would be wrong here", Some(PromptMode::Full)),
+        ("Sure! Here's my classification", None),
+        ("GENERAL", None),
+        ("chatting about tasks", None),
+        ("multitask", None),
+        ("", None),
+    ];
+
+    #[test]
+    fn the_first_category_word_of_a_reply_is_its_verdict() {
+        for (reply, expected) in CLASSIFIER_REPLIES {
+            assert_eq!(parse_verdict(reply), *expected, "{:?}", reply);
+        }
+    }
+
+    #[test]
+    fn only_a_bare_category_word_is_a_clean_reply() {
+        let sample = |replies: &[&str]| ClassifierSample {
+            at: Local::now(),
+            message: String::new(),
+            replies: replies.iter().map(|r| r.to_string()).collect(),
+            verdict: None,
+        };
+        assert!(sample(&["CHAT"]).is_clean());
+        assert!(sample(&[" task.\n"]).is_clean());
+        assert!(sample(&["**TASK**"]).is_clean());
+        assert!(!sample(&["This is a CHAT message."]).is_clean());
+        assert!(!sample(&["Hmm", "CHAT"]).is_clean(), "a retry is never clean");
+        assert!(!sample(&[]).is_clean());
+    }
+
+    async fn checking_brain(root: &std::path::Path, url: &str) -> Brain {
+        let config = openspore_core::config::AppConfig { prompt_mode_llm_check: true, ..crate::test_support::config_in(root, url) };
+        let brain = Brain::new(config).with_channel("tui");
+        brain.memory.ensure_structure().await.unwrap();
+        brain
+    }
+
+    #[tokio::test]
+    async fn messy_classifier_replies_are_read_and_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, requests) = fake_llm(|request| {
+            let text = request["messages"][0]["content"].as_str().unwrap_or_default();
+            if text.contains("pasta") { "This is a CHAT message.".to_string() } else { "Category: **TASK**".to_string() }
+        }).await;
+        let brain = checking_brain(dir.path(), &url).await;
+        let options = ThinkOptions::default();
+
+        assert_eq!(brain.prompt_mode("what's a good pasta recipe", options).await, PromptMode::Slim);
+        assert_eq!(brain.prompt_mode("who won the league last year", options).await, PromptMode::Full);
+        assert_eq!(requests.len(), 2, "one call each");
+        let call = requests.get(0);
+        assert_eq!(call["max_tokens"], CLASSIFIER_LIMIT.max_tokens);
+        assert_eq!(call["stop"], serde_json::json!(["\n"]));
+
+        let samples = brain.classifier_samples();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].replies, ["This is a CHAT message."]);
+        assert_eq!((samples[0].verdict, samples[0].is_clean()), (Some(PromptMode::Slim), false));
+        assert_eq!(samples[1].verdict, Some(PromptMode::Full));
+        assert!(samples[1].message.starts_with("who won the league"));
+    }
+
+    #[tokio::test]
+    async fn a_reply_naming_no_category_gets_one_stricter_retry() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, requests) = fake_llm(|request| {
+            let messages = request["messages"].as_array().unwrap();
+            match (messages.len(), messages[0]["content"].as_str().unwrap_or_default().contains("pasta")) {
+                (1, _) => "Sure! Here's my classification".to_string(),
+                (_, true) => "TASK".to_string(),
+                (_, false) => "GENERAL".to_string(),
+            }
+        }).await;
+        let brain = checking_brain(dir.path(), &url).await;
+        let options = ThinkOptions::default();
+
+        assert_eq!(brain.prompt_mode("what's a good pasta recipe", options).await, PromptMode::Full, "the retry's TASK stands");
+        let retry = requests.get(1);
+        let messages = retry["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["content"], "Sure! Here's my classification");
+        assert_eq!(messages[2]["content"], "Answer with exactly one word: CHAT or TASK.");
+
+        // Neither reply names a category: the heuristic's chat verdict stands
+        assert_eq!(brain.prompt_mode("tell me a joke about owls", options).await, PromptMode::Slim);
+        assert_eq!(requests.len(), 4, "no third attempt");
+        let samples = brain.classifier_samples();
+        assert_eq!(samples[0].replies, ["Sure! Here's my classification", "TASK"]);
+        assert_eq!(samples[1].replies, ["Sure! Here's my classification", "GENERAL"]);
+        assert_eq!(samples[1].verdict, None);
+    }
+
+    #[tokio::test]
+    async fn acknowledgments_and_an_unreachable_classifier_skip_the_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = checking_brain(dir.path(), "http://127.0.0.1:9/v1").await;
+        let options = ThinkOptions::default();
+        assert_eq!(brain.prompt_mode("thanks!", options).await, PromptMode::Slim);
+        assert_eq!(brain.prompt_mode("what's a good pasta recipe", options).await, PromptMode::Slim);
+        assert!(brain.classifier_samples().is_empty(), "nothing was received");
+    }
+
+    #[tokio::test]
+    async fn the_sample_ring_keeps_the_latest_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+        for i in 0..CLASSIFIER_SAMPLES + 5 {
+            brain.record_classifier_sample(ClassifierSample { at: Local::now(), message: i.to_string(), replies: vec!["CHAT".to_string()], verdict: Some(PromptMode::Slim) });
+        }
+        let samples = brain.classifier_samples();
+        assert_eq!(samples.len(), CLASSIFIER_SAMPLES);
+        assert_eq!(samples[0].message, "5");
+        assert_eq!(samples.last().unwrap().message, (CLASSIFIER_SAMPLES + 4).to_string());
+    }
+}
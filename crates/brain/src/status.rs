@@ -0,0 +1,422 @@
+//! Health snapshot
+//!
+//! `Brain::status()` gathers what `openspore status`, the TUI's `/status` and
+//! the right panel of its header show: configuration, skills, memories, the
+//! last scheduled runs, the swarm, token budgets and the last error. It only
+//! reads local files (no network, no model call) and takes a few
+//! milliseconds. `StatusSnapshot::lines()` is the one rendering of it; the
+//! header shows its first lines, so they come in order of importance.
+
+use crate::Brain;
+use chrono::{DateTime, Local};
+use openspore_core::fmt_utils::{humanize_duration, preview};
+use openspore_swarm::SwarmStatus;
+use serde::Serialize;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Instant;
+
+/// Run history of the scheduled jobs, written by the autonomy scheduler
+const JOB_HISTORY_FILE: &str = "autonomy/job_history.jsonl";
+
+/// State of the autonomy engine (its last run), relative to the workspace
+const AUTONOMY_STATE_FILE: &str = "autonomy/state.json";
+
+/// Tail of the job history read for the last runs
+const JOB_HISTORY_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Characters of the last error shown
+const ERROR_PREVIEW_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSummary {
+    pub provider: String,
+    pub model: String,
+    pub model_fast: String,
+    pub model_vision: Option<String>,
+    pub fallbacks: Vec<String>,
+    /// The LLM endpoint is on this machine (works without internet)
+    pub llm_local: bool,
+    pub autonomy_enabled: bool,
+    pub safe_mode: bool,
+    pub approval_mode: String,
+    pub slim_prompts: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SkillCounts {
+    pub total: usize,
+    pub core: usize,
+    pub plugin: usize,
+    pub agent: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryCount {
+    pub category: String,
+    pub files: usize,
+}
+
+/// Last successful run of each scheduled job
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobTimes {
+    pub heartbeat: Option<DateTime<Local>>,
+    pub journal: Option<DateTime<Local>>,
+    pub autonomy: Option<DateTime<Local>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Budgets {
+    /// Context window of the smallest model in the chain
+    pub context_tokens: usize,
+    pub reply_tokens: usize,
+    pub today_calls: usize,
+    pub today_tokens: u64,
+    pub today_cost: f64,
+    pub month_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LastError {
+    pub at: DateTime<Local>,
+    /// "cycle" for a failed think() cycle, else the scheduled job
+    pub source: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusSnapshot {
+    pub collected_at: DateTime<Local>,
+    pub collect_ms: u64,
+    pub session: String,
+    pub config: ConfigSummary,
+    pub skills: SkillCounts,
+    pub memories: Vec<CategoryCount>,
+    pub jobs: JobTimes,
+    pub swarm: SwarmStatus,
+    pub budgets: Budgets,
+    pub last_error: Option<LastError>,
+}
+
+#[derive(serde::Deserialize)]
+struct HistoryLine {
+    time: String,
+    job: String,
+    status: String,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+fn ago(at: Option<DateTime<Local>>, now: DateTime<Local>) -> String {
+    match at {
+        Some(at) => format!("{} ago", humanize_duration((now - at).to_std().unwrap_or_default())),
+        None => "never".to_string(),
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on { "on" } else { "off" }
+}
+
+/// The last `bytes` of a file, from the first complete line
+fn read_tail(path: &Path, bytes: u64) -> String {
+    let Ok(mut file) = std::fs::File::open(path) else { return String::new() };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(bytes);
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return String::new();
+    }
+    let mut buf = Vec::new();
+    let _ = file.read_to_end(&mut buf);
+    let text = String::from_utf8_lossy(&buf).into_owned();
+    match (start > 0, text.find('\n')) {
+        (true, Some(newline)) => text[newline + 1..].to_string(),
+        _ => text,
+    }
+}
+
+/// .md files directly in a directory
+fn count_markdown(dir: &Path) -> usize {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "md"))
+        .count()
+}
+
+/// Last successful run per job and the last failed one, from the job history
+fn job_history(workspace: &Path) -> (JobTimes, Option<LastError>) {
+    let mut times = JobTimes::default();
+    let mut failure: Option<LastError> = None;
+    for line in read_tail(&workspace.join(JOB_HISTORY_FILE), JOB_HISTORY_TAIL_BYTES).lines() {
+        let Ok(entry) = serde_json::from_str::<HistoryLine>(line) else { continue };
+        let Ok(at) = DateTime::parse_from_rfc3339(&entry.time) else { continue };
+        let at = at.with_timezone(&Local);
+        if entry.status == "succeeded" {
+            let slot = match entry.job.as_str() {
+                "heartbeat" => &mut times.heartbeat,
+                "journal" => &mut times.journal,
+                "engine" => &mut times.autonomy,
+                _ => continue,
+            };
+            *slot = Some(at);
+        } else {
+            failure = Some(LastError {
+                at,
+                source: entry.job,
+                message: entry.error.unwrap_or(entry.status),
+            });
+        }
+    }
+    // Engine runs started by hand or by cron are not in the history
+    if let Some(state) = std::fs::read_to_string(workspace.join(AUTONOMY_STATE_FILE)).ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        && let Some(at) = state["last_processed_log"].as_str().and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+    {
+        let at = at.with_timezone(&Local);
+        if times.autonomy.is_none_or(|known| at > known) {
+            times.autonomy = Some(at);
+        }
+    }
+    (times, failure)
+}
+
+impl Brain {
+    /// Snapshot of this brain's health, from local files only
+    pub fn status(&self) -> StatusSnapshot {
+        let started = Instant::now();
+        let config = self.llm_config();
+        let workspace = &self.memory.memory_root;
+
+        let mut skills = SkillCounts::default();
+        {
+            let loader = self.skills();
+            for (name, _) in loader.summaries() {
+                let Some(skill) = loader.get(name) else { continue };
+                skills.total += 1;
+                match openspore_skills::skill_kind(skill.as_ref()) {
+                    "core" => skills.core += 1,
+                    "agent" => skills.agent += 1,
+                    _ => skills.plugin += 1,
+                }
+            }
+        }
+
+        let memories = self.memory.categories.iter()
+            .map(|c| CategoryCount { category: c.to_string(), files: count_markdown(&self.memory.category_dir(c)) })
+            .collect();
+
+        let (jobs, job_failure) = job_history(workspace);
+        // The newest of a failed scheduled run and a failed think() cycle
+        let cycle_failure = self.memory.list_failures().into_iter().next().map(|bundle| LastError {
+            at: bundle.record.time,
+            source: "cycle".to_string(),
+            message: bundle.record.error.replace('\n', " "),
+        });
+        let last_error = match (job_failure, cycle_failure) {
+            (Some(job), Some(cycle)) => Some(if job.at > cycle.at { job } else { cycle }),
+            (job, cycle) => job.or(cycle),
+        };
+
+        let window = crate::context_budget::ContextBudget::for_models(&config, &self.model_chain());
+        let usage = self.usage_stats();
+
+        StatusSnapshot {
+            collected_at: Local::now(),
+            collect_ms: 0,
+            session: openspore_memory::session::active_session(workspace).unwrap_or_else(|| openspore_memory::session::DEFAULT_SESSION.to_string()),
+            config: ConfigSummary {
+                provider: config.provider().as_str().to_string(),
+                model: self.current_model(),
+                model_fast: self.fast_model(),
+                model_vision: config.model_vision.clone().filter(|m| !m.trim().is_empty()),
+                fallbacks: config.model_fallbacks(),
+                llm_local: openspore_core::config::is_local_url(&config.llm_base_url()),
+                autonomy_enabled: config.autonomy_enabled,
+                safe_mode: config.safe_mode_enabled,
+                approval_mode: self.approvals.mode().as_str().to_string(),
+                slim_prompts: self.slim_prompts_enabled(),
+            },
+            skills,
+            memories,
+            jobs,
+            swarm: openspore_swarm::SwarmManager::from_config(&config).registry_status(),
+            budgets: Budgets {
+                context_tokens: window.window,
+                reply_tokens: window.reply,
+                today_calls: usage.today_calls,
+                today_tokens: usage.today.prompt_tokens + usage.today.completion_tokens,
+                today_cost: usage.today.cost,
+                month_cost: usage.month.cost,
+            },
+            last_error,
+        }
+        .timed(started)
+    }
+}
+
+impl StatusSnapshot {
+    fn timed(mut self, started: Instant) -> Self {
+        self.collect_ms = started.elapsed().as_millis() as u64;
+        self
+    }
+
+    /// The status as text lines, most important first
+    pub fn lines(&self) -> Vec<String> {
+        let now = self.collected_at;
+        let c = &self.config;
+        let local = if c.llm_local { ", local" } else { "" };
+        let mut lines = vec![
+            format!("🧠 {} · fast {} ({}{})", c.model, c.model_fast, c.provider, local),
+            format!(
+                "💓 Heartbeat {} · journal {} · autonomy {}",
+                ago(self.jobs.heartbeat, now), ago(self.jobs.journal, now), ago(self.jobs.autonomy, now)
+            ),
+            format!("🐝 {}", self.swarm),
+        ];
+        lines.push(match &self.last_error {
+            Some(e) => format!("⚠️ Last error {} ({}): {}", ago(Some(e.at), now), e.source, preview(&e.message, ERROR_PREVIEW_CHARS)),
+            None => "✅ No recorded errors".to_string(),
+        });
+        let s = &self.skills;
+        lines.push(format!("🧩 {} skills: {} core, {} plugin, {} agent", s.total, s.core, s.plugin, s.agent));
+        let total: usize = self.memories.iter().map(|m| m.files).sum();
+        let per_category: Vec<String> = self.memories.iter().map(|m| format!("{} {}", m.category, m.files)).collect();
+        lines.push(format!("📚 {} memories: {}", total, per_category.join(", ")));
+        let b = &self.budgets;
+        lines.push(format!(
+            "📊 Today {} calls, {} tokens, ${:.4} (month ${:.4}) · window {} tokens, replies {}",
+            b.today_calls, b.today_tokens, b.today_cost, b.month_cost, b.context_tokens, b.reply_tokens
+        ));
+        lines.push(format!(
+            "⚙️ Session {} · autonomy {} · safe mode {} · approvals {} · slim prompts {}",
+            self.session, on_off(c.autonomy_enabled), on_off(c.safe_mode), c.approval_mode, on_off(c.slim_prompts)
+        ));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{brain_in, fake_llm};
+    use chrono::Duration;
+
+    fn hours_ago(hours: i64) -> DateTime<Local> {
+        Local::now() - Duration::hours(hours)
+    }
+
+    fn history_line(at: DateTime<Local>, job: &str, status: &str, error: Option<&str>) -> String {
+        serde_json::json!({ "time": at.to_rfc3339(), "job": job, "status": status, "error": error }).to_string()
+    }
+
+    /// A workspace with memories, scheduled runs and a failed engine run
+    async fn seeded(llm_url: &str) -> (tempfile::TempDir, Brain) {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), llm_url).await;
+        for (category, name) in [("knowledge", "tides.md"), ("knowledge", "boiler.md"), ("preferences", "editor.md")] {
+            std::fs::write(brain.memory.category_dir(category).join(name), "# note").unwrap();
+        }
+        std::fs::write(brain.memory.category_dir("knowledge").join("scan.png"), "not a memory").unwrap();
+
+        let workspace = &brain.memory.memory_root;
+        std::fs::create_dir_all(workspace.join("autonomy")).unwrap();
+        let history = [
+            history_line(hours_ago(30), "heartbeat", "succeeded", None),
+            "not json".to_string(),
+            history_line(hours_ago(5), "heartbeat", "succeeded", None),
+            history_line(hours_ago(4), "journal", "succeeded", None),
+            history_line(hours_ago(3), "engine", "failed", Some("LLM endpoint unreachable\nretrying")),
+            history_line(hours_ago(2), "nightly_backup", "succeeded", None),
+        ];
+        std::fs::write(workspace.join(JOB_HISTORY_FILE), history.join("\n") + "\n").unwrap();
+        let state = serde_json::json!({ "last_processed_log": hours_ago(1).to_rfc3339() });
+        std::fs::write(workspace.join(AUTONOMY_STATE_FILE), state.to_string()).unwrap();
+        (dir, brain)
+    }
+
+    fn count(snapshot: &StatusSnapshot, category: &str) -> usize {
+        snapshot.memories.iter().find(|m| m.category == category).map(|m| m.files).unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn the_snapshot_reads_the_seeded_workspace() {
+        let (_dir, brain) = seeded("http://127.0.0.1:9/v1").await;
+        let status = brain.status();
+
+        assert_eq!(status.config.model, "test-model");
+        assert!(status.config.llm_local);
+        assert!(status.skills.total > 0 && status.skills.total == status.skills.core + status.skills.plugin + status.skills.agent);
+        assert_eq!((count(&status, "knowledge"), count(&status, "preferences")), (2, 1));
+
+        let near = |at: Option<DateTime<Local>>, hours: i64| at.is_some_and(|at| (at - hours_ago(hours)).num_seconds().abs() < 60);
+        assert!(near(status.jobs.heartbeat, 5), "the latest success: {:?}", status.jobs.heartbeat);
+        assert!(near(status.jobs.journal, 4));
+        assert!(near(status.jobs.autonomy, 1), "a manual engine run counts too");
+
+        let error = status.last_error.as_ref().unwrap();
+        assert_eq!(error.source, "engine");
+        assert!(error.message.starts_with("LLM endpoint unreachable"));
+        assert_eq!(status.budgets.today_calls, 0);
+        assert!(status.collect_ms < 100, "collected in {} ms", status.collect_ms);
+    }
+
+    #[tokio::test]
+    async fn a_newer_failed_cycle_is_the_last_error() {
+        let (url, _) = fake_llm(|request| {
+            let last = request["messages"].as_array().and_then(|m| m.last()).cloned().unwrap_or_default();
+            if last["role"] == "user" && last["content"].as_str().is_some_and(|c| c.starts_with("Read the four")) {
+                (1..=4).map(|i| format!("[READ_FILE: missing/{}.toml]", i)).collect::<Vec<_>>().join("\n")
+            } else {
+                "None of them could be read.".to_string()
+            }
+        }).await;
+        let (_dir, brain) = seeded(&url).await;
+        brain.think("Read the four rollout configs and compare them").await;
+
+        let status = brain.status();
+        let error = status.last_error.as_ref().unwrap();
+        assert_eq!(error.source, "cycle");
+        assert!(error.message.contains("4 of 4 tool calls failed"), "{}", error.message);
+        assert_eq!(status.budgets.today_calls, 1, "one record per cycle");
+        assert!(status.budgets.today_tokens >= 30, "both model calls of the cycle");
+    }
+
+    #[tokio::test]
+    async fn the_lines_lead_with_the_model_runs_and_errors() {
+        let (_dir, brain) = seeded("http://127.0.0.1:9/v1").await;
+        let lines = brain.status().lines();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].starts_with("🧠 test-model · fast ") && lines[0].ends_with(", local)"), "{}", lines[0]);
+        assert!(lines[1].starts_with("💓 Heartbeat 5h") && lines[1].contains("journal 4h") && lines[1].contains("autonomy 1h"), "{}", lines[1]);
+        assert!(lines[2].starts_with("🐝 "));
+        assert!(lines[3].starts_with("⚠️ Last error 3h") && lines[3].contains("(engine): LLM endpoint unreachable retrying"), "{}", lines[3]);
+        assert!(lines[5].starts_with("📚 3 memories:") && lines[5].contains("knowledge 2"), "{}", lines[5]);
+
+        let empty = brain_in(tempfile::tempdir().unwrap().path(), "http://127.0.0.1:9/v1").await.status().lines();
+        assert!(empty[1].contains("Heartbeat never"));
+        assert_eq!(empty[3], "✅ No recorded errors");
+    }
+
+    #[tokio::test]
+    async fn the_snapshot_serializes_for_status_json() {
+        let (_dir, brain) = seeded("http://127.0.0.1:9/v1").await;
+        let json = serde_json::to_value(brain.status()).unwrap();
+        for key in ["collected_at", "session", "config", "skills", "memories", "jobs", "swarm", "budgets", "last_error"] {
+            assert!(json.get(key).is_some(), "{}", key);
+        }
+        assert_eq!(json["last_error"]["source"], "engine");
+        assert_eq!(json["config"]["model"], "test-model");
+    }
+
+    #[test]
+    fn a_long_history_is_read_from_its_first_whole_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        std::fs::write(&path, "first line\nsecond line\nthird\n").unwrap();
+        assert_eq!(read_tail(&path, 14), "third\n");
+        assert_eq!(read_tail(&path, 1000), "first line\nsecond line\nthird\n");
+        assert_eq!(read_tail(&dir.path().join("missing"), 10), "");
+    }
+}
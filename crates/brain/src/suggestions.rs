@@ -0,0 +1,162 @@
+//! Follow-up suggestions after an answer
+//!
+//! FOLLOWUP_SUGGESTIONS=trailer asks the main completion to end with a
+//! `<FOLLOW_UPS>` block, which is stripped from the answer; =model makes one
+//! extra call on the Fast model with the finished exchange. Either way at most
+//! three short prompts come out, sent as BrainEvent::Suggestions.
+
+use crate::{Brain, Message};
+use crate::usage::CycleStats;
+use tracing::debug;
+
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Longer suggestions are dropped: they are meant to be chips, not prompts to read
+const MAX_SUGGESTION_CHARS: usize = 80;
+
+/// Tool rounds that must be left in the cycle for suggestions to be worth it
+const BUDGET_RESERVE: usize = 2;
+
+const TRAILER_OPEN: &str = "<FOLLOW_UPS>";
+const TRAILER_CLOSE: &str = "</FOLLOW_UPS>";
+
+/// Appended to the system prompt in trailer mode
+pub(crate) const TRAILER_INSTRUCTION: &str = "<FOLLOW_UPS_FORMAT>\nAfter your final answer (never in a message that calls tools), add up to 3 short follow-up requests the user is likely to send next, written from the user's point of view, one per line:\n<FOLLOW_UPS>\nRun the tests\nShow the diff\n</FOLLOW_UPS>\nOmit the block when there is no obvious next step.\n</FOLLOW_UPS_FORMAT>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionMode {
+    Off,
+    /// Parsed from a trailer of the main completion
+    Trailer,
+    /// One extra call on the Fast model
+    Model,
+}
+
+/// Answer without its `<FOLLOW_UPS>` trailer, and the suggestions it held
+pub fn split_trailer(content: &str) -> (String, Vec<String>) {
+    let Some(start) = content.rfind(TRAILER_OPEN) else {
+        return (content.to_string(), Vec::new());
+    };
+    let inner_start = start + TRAILER_OPEN.len();
+    let (inner, end) = match content[inner_start..].find(TRAILER_CLOSE) {
+        Some(offset) => (&content[inner_start..inner_start + offset], inner_start + offset + TRAILER_CLOSE.len()),
+        None => (&content[inner_start..], content.len()),
+    };
+
+    let answer = format!("{}{}", content[..start].trim_end(), content[end..].trim_end());
+    (answer, parse_lines(inner))
+}
+
+/// One suggestion per line; bullets, numbering and quotes are removed
+pub fn parse_lines(text: &str) -> Vec<String> {
+    let mut suggestions: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim()
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .trim_start_matches(['-', '*', '•', '.', ')', ' '])
+            .trim()
+            .trim_matches(['"', '\'', '`'])
+            .trim();
+        if line.is_empty() || line.chars().count() > MAX_SUGGESTION_CHARS {
+            continue;
+        }
+        if !suggestions.iter().any(|s| s.eq_ignore_ascii_case(line)) {
+            suggestions.push(line.to_string());
+        }
+        if suggestions.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    suggestions
+}
+
+impl Brain {
+    /// FOLLOWUP_SUGGESTIONS=off|trailer|model (spores never suggest)
+    pub fn suggestion_mode(&self) -> SuggestionMode {
+        if self.spore.is_spore {
+            return SuggestionMode::Off;
+        }
+        match self.config.followup_suggestions.as_deref().map(|s| s.trim().to_lowercase()).as_deref() {
+            Some("trailer") => SuggestionMode::Trailer,
+            Some("model") => SuggestionMode::Model,
+            _ => SuggestionMode::Off,
+        }
+    }
+
+    /// Suggestions for a finished cycle: `trailer` holds what the answer carried
+    pub(crate) async fn followups(&self, prompt: &str, answer: &str, trailer: Vec<String>, stats: &CycleStats) -> Vec<String> {
+        // Replies to acknowledgments don't need next steps
+        if crate::learning::is_acknowledgment(prompt) || stats.layers + BUDGET_RESERVE > crate::thinking::MAX_TOOL_DEPTH {
+            return Vec::new();
+        }
+        match self.suggestion_mode() {
+            SuggestionMode::Off => Vec::new(),
+            SuggestionMode::Trailer => trailer,
+            SuggestionMode::Model => self.suggest_with_model(prompt, answer).await,
+        }
+    }
+
+    async fn suggest_with_model(&self, prompt: &str, answer: &str) -> Vec<String> {
+        let request = format!(
+            "A user asked an assistant:\n{}\n\nThe assistant answered:\n{}\n\nList up to {} short follow-up requests the user is likely to send next, written from the user's point of view (imperative, under 60 characters), one per line, no numbering. Reply with nothing if there is no obvious next step.",
+            openspore_core::fmt_utils::truncate_chars(prompt, 2000),
+            openspore_core::fmt_utils::truncate_chars(answer, 4000),
+            MAX_SUGGESTIONS
+        );
+        let messages = [Message { role: "user".to_string(), content: request, ..Default::default() }];
+        match self.complete_fast(&messages).await {
+            Ok((content, usage)) => {
+                self.record_usage("suggestions", &usage);
+                parse_lines(&content)
+            }
+            Err(e) => {
+                debug!("Follow-up suggestions skipped: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::config_in;
+    use openspore_core::config::AppConfig;
+
+    #[test]
+    fn trailer_is_stripped_from_the_answer() {
+        let (answer, suggestions) = split_trailer("Fixed the parser.\n\n<FOLLOW_UPS>\n1. Run the tests\n- \"Show the diff\"\n</FOLLOW_UPS>");
+        assert_eq!(answer, "Fixed the parser.");
+        assert_eq!(suggestions, ["Run the tests", "Show the diff"]);
+
+        let (answer, suggestions) = split_trailer("Done.\n<FOLLOW_UPS>\nCommit it");
+        assert_eq!(answer, "Done.");
+        assert_eq!(suggestions, ["Commit it"]);
+
+        let (answer, suggestions) = split_trailer("No trailer here.");
+        assert_eq!(answer, "No trailer here.");
+        assert!(suggestions.is_empty());
+    }
+
+    #[test]
+    fn lines_are_capped_deduplicated_and_short() {
+        let long = "x".repeat(MAX_SUGGESTION_CHARS + 1);
+        let text = format!("* Run the tests\nrun the tests\n\n{}\n2) Show the diff\n`Commit`\nPush", long);
+        assert_eq!(parse_lines(&text), ["Run the tests", "Show the diff", "Commit"]);
+    }
+
+    #[tokio::test]
+    async fn acknowledgments_get_no_followups() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig { followup_suggestions: Some("trailer".to_string()), ..config_in(dir.path(), "http://127.0.0.1:9/v1") };
+        let brain = Brain::new(config);
+        let trailer = vec!["Run the tests".to_string()];
+        let stats = CycleStats::default();
+
+        assert_eq!(brain.suggestion_mode(), SuggestionMode::Trailer);
+        assert_eq!(brain.followups("fix the parser", "Fixed.", trailer.clone(), &stats).await, trailer);
+        for ack in ["thanks!", "Got it.", "yes", "👍"] {
+            assert!(brain.followups(ack, "Anytime.", trailer.clone(), &stats).await.is_empty(), "{}", ack);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Test harness: a Brain rooted in a temp directory, talking to a fake
+//! OpenAI-compatible endpoint that answers from a closure and records every
+//! request it gets.
+
+use crate::Brain;
+use openspore_core::config::AppConfig;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+type ReplyFn = dyn Fn(&serde_json::Value) -> String + Send + Sync;
+
+/// Chat completion requests the fake endpoint received, oldest first
+#[derive(Clone, Default)]
+pub struct Requests(Arc<Mutex<Vec<serde_json::Value>>>);
+
+impl Requests {
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Requests whose messages mention `text`
+    pub fn mentioning(&self, text: &str) -> usize {
+        self.0.lock().unwrap().iter().filter(|r| r["messages"].to_string().contains(text)).count()
+    }
+
+    pub fn get(&self, i: usize) -> serde_json::Value {
+        self.0.lock().unwrap()[i].clone()
+    }
+
+    /// The request whose last message is the user saying `text`
+    pub fn find_user(&self, text: &str) -> Option<usize> {
+        self.0.lock().unwrap().iter().position(|r| {
+            let last = r["messages"].as_array().and_then(|m| m.last()).cloned().unwrap_or_default();
+            last["role"] == "user" && last["content"] == text
+        })
+    }
+
+    /// Content of a request's message with `role` (the first one)
+    pub fn message(&self, i: usize, role: &str) -> String {
+        self.get(i)["messages"].as_array().into_iter().flatten()
+            .find(|m| m["role"] == role)
+            .and_then(|m| m["content"].as_str())
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+/// Serve chat completions on 127.0.0.1: `reply` gets the request body and
+/// returns the assistant's content. Returns the base URL and the request log.
+pub async fn fake_llm(reply: impl Fn(&serde_json::Value) -> String + Send + Sync + 'static) -> (String, Requests) {
+    slow_llm(Duration::ZERO, reply).await
+}
+
+/// fake_llm() that waits `delay` before each answer
+pub async fn slow_llm(delay: Duration, reply: impl Fn(&serde_json::Value) -> String + Send + Sync + 'static) -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Requests::default();
+    let reply: Arc<ReplyFn> = Arc::new(reply);
+    let log = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(answer(stream, delay, reply.clone(), log.clone()));
+        }
+    });
+    (url, requests)
+}
+
+async fn answer(mut stream: TcpStream, delay: Duration, reply: Arc<ReplyFn>, log: Requests) {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 8192];
+    let body_start = loop {
+        let Ok(n) = stream.read(&mut buf).await else { return };
+        if n == 0 {
+            return;
+        }
+        data.extend_from_slice(&buf[..n]);
+        if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+    let head = String::from_utf8_lossy(&data[..body_start]).to_lowercase();
+    let length: usize = head.lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0);
+    while data.len() < body_start + length {
+        let Ok(n) = stream.read(&mut buf).await else { return };
+        if n == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..n]);
+    }
+    let request: serde_json::Value = serde_json::from_slice(&data[body_start..]).unwrap_or_default();
+    let content = reply(&request);
+    log.0.lock().unwrap().push(request);
+    tokio::time::sleep(delay).await;
+
+    let body = serde_json::json!({
+        "choices": [{ "message": { "role": "assistant", "content": content } }],
+        "usage": { "prompt_tokens": 10, "completion_tokens": 5 }
+    }).to_string();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(), body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Config of a workspace at `root` using the endpoint at `llm_url`
+pub fn config_in(root: &Path, llm_url: &str) -> AppConfig {
+    AppConfig {
+        project_root: root.to_path_buf(),
+        llm_base_url: Some(llm_url.to_string()),
+        model: Some("test-model".to_string()),
+        learn_min_interval_secs: Some(0),
+        ..Default::default()
+    }
+}
+
+/// An interactive (TUI) brain over a fresh workspace at `root`
+pub async fn brain_in(root: &Path, llm_url: &str) -> Brain {
+    let brain = Brain::new(config_in(root, llm_url)).with_channel("tui");
+    brain.memory.ensure_structure().await.unwrap();
+    brain
+}
+
+/// Every file under `dir` with its length, to compare before and after a turn
+pub fn snapshot(dir: &Path) -> BTreeMap<PathBuf, u64> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.insert(path, entry.metadata().map(|m| m.len()).unwrap_or(0));
+            }
+        }
+    }
+    files
+}
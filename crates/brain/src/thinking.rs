@@ -1,41 +1,149 @@
-use crate::{Brain, Message};
+use crate::{Brain, Message, ThinkOptions};
+use crate::plan::{PlannedCall, DRY_RUN_OUTPUT, DRY_RUN_ROUNDS};
+use crate::postmortem::FailedCycle;
+use crate::prompt_mode::PromptMode;
+use crate::usage::CycleStats;
+use openspore_core::fmt_utils::{humanize_duration, preview};
+use openspore_memory::{AuditEvent, AuditOutcome, FailureKind, ToolInvocation};
 use tracing::{info, warn, error, debug};
 
+/// Tool rounds per cycle before the loop is cut off
+pub(crate) const MAX_TOOL_DEPTH: usize = 24;
+
+/// (call index, tool name, result) - the index keeps <TOOL_OUTPUTS> in call order
+type ToolTask<'a> = std::pin::Pin<Box<dyn futures::Future<Output = (usize, String, Result<String, String>)> + Send + 'a>>;
+
 impl Brain {
     /// The Core Thinking Loop: Minimalist & Robust
-    pub(crate) async fn think_internal(&self, user_prompt: &str, tx: Option<tokio::sync::mpsc::Sender<crate::events::BrainEvent>>) -> String {
+    /// `suggest`: also produce follow-up suggestions (interactive callers only)
+    /// `options.dry_run`: record tool calls instead of running them (plan mode)
+    pub(crate) async fn think_internal(&self, raw_prompt: &str, tx: Option<tokio::sync::mpsc::Sender<crate::events::BrainEvent>>, suggest: bool, options: ThinkOptions) -> (String, CycleStats, Vec<String>) {
         let start_time = std::time::Instant::now();
-        info!("🧠 Thinking: {}", user_prompt);
 
-        // Immediate logging to LOGS.md (Start of turn)
+        // 0. `/define` edits the glossary without a model call
+        if let Some(args) = crate::glossary::define_args(raw_prompt) {
+            let reply = self.define(args).await;
+            if let Some(t) = &tx {
+                let _ = t.send(crate::events::BrainEvent::FinalAnswer(reply.clone())).await;
+            }
+            return (reply, CycleStats::default(), Vec::new());
+        }
+
+        // Privacy markers (`!private ...` / `/private N`)
+        let (prompt, is_private) = match crate::privacy::parse_directive(raw_prompt) {
+            crate::privacy::PrivacyDirective::Toggle(turns) => {
+                self.private_turns.store(turns, std::sync::atomic::Ordering::SeqCst);
+                let reply = format!("🔒 Private mode enabled for the next {} turn(s). Nothing will be saved.", turns);
+                if let Some(t) = &tx {
+                    let _ = t.send(crate::events::BrainEvent::PrivateTurn).await;
+                    let _ = t.send(crate::events::BrainEvent::FinalAnswer(reply.clone())).await;
+                }
+                return (reply, CycleStats::default(), Vec::new());
+            }
+            crate::privacy::PrivacyDirective::Private(p) => (p, true),
+            crate::privacy::PrivacyDirective::Normal(p) => {
+                let private = self.take_private_turn();
+                (p, private)
+            }
+        };
+        let user_prompt = prompt.as_str();
+        // "ok" / "thanks": one completion, nothing learned or saved (see fast_path).
+        // Its start line is held back until the turn turns out to stay small.
+        let acknowledgment = crate::learning::is_acknowledgment(user_prompt);
         let start_log = format!("\n[{}] User: {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), user_prompt);
-        let _ = self.memory.save_journal(&start_log).await;
 
-        // 1. Build Context & System Prompt
-        let (system_prompt, session_ctx) = crate::context_assembler::ContextAssembler::build_system_prompt(self, user_prompt).await;
+        if is_private {
+            info!("🧠 Thinking (private turn)");
+            if let Some(t) = &tx { let _ = t.send(crate::events::BrainEvent::PrivateTurn).await; }
+        } else {
+            info!("🧠 Thinking: {}", preview(user_prompt, 200));
+
+            // Immediate logging to LOGS.md (Start of turn), after the acknowledgments held back before it
+            if !acknowledgment {
+                self.flush_journal().await;
+                let _ = self.memory.save_journal(&start_log).await;
+            }
+        }
+
+        // 1. Build Context & System Prompt (slim for general chat)
+        let mode = self.prompt_mode(user_prompt, options).await;
+        let slim = mode == PromptMode::Slim;
+        if slim {
+            debug!("💬 General chat: slim prompt, no tools");
+        }
+        let (system_prompt, session_ctx, injected) = crate::context_assembler::ContextAssembler::build_system_prompt(self, user_prompt, is_private, mode).await;
+
+        // Native function calling (opt-in): skills are also sent as a `tools` array
+        let native_tools = if !slim && self.native_tool_calling() {
+            let excluded = self.excluded_skills();
+            let excluded: Vec<&str> = excluded.iter().map(String::as_str).collect();
+            Some(self.skills().get_tool_definitions(&excluded))
+        } else {
+            None
+        };
+        let mut system_content = if native_tools.is_some() {
+            format!("{}\n\nNATIVE TOOLS: Function calling is enabled. Prefer calling skills through the tools API; the bracket syntax remains available as a fallback.", system_prompt)
+        } else {
+            system_prompt.clone()
+        };
+        if options.dry_run {
+            system_content.push_str(&format!("\n\n{}", crate::plan::PLAN_MODE_PROMPT));
+        }
 
         let mut messages = vec![
-            Message { role: "system".to_string(), content: system_prompt.clone() },
-            Message { role: "user".to_string(), content: user_prompt.to_string() }
+            Message { role: "system".to_string(), content: system_content, ..Default::default() },
+            Message { role: "user".to_string(), content: user_prompt.to_string(), ..Default::default() }
         ];
 
         // 2. Initial Completion
-        let mut content = match self.complete(&messages).await {
-            Ok(c) => c,
+        let mut stats = CycleStats::start();
+        stats.prompt_mode = mode;
+        let cycle_id = stats.cycle_id.clone();
+        let mut used_native_calls = false;
+        let (mut content, mut pending_calls, mut pending_raw_calls) = match self.complete_turn(&messages, native_tools.as_ref()).await {
+            Ok(c) => { stats.record_call(&c.model, &c.usage); (c.content, c.tool_calls, c.raw_tool_calls) },
             Err(e) => {
-                if let Some(t) = &tx { let _ = t.send(crate::events::BrainEvent::Error(e.to_string())).await; }
-                return format!("Errors: {}", e);
+                // A new workspace gets setup help instead of the bare error
+                let error = self.first_run_help(&e).await.unwrap_or_else(|| e.to_string());
+                let bundle = if is_private { None } else {
+                    self.postmortem(FailedCycle {
+                        kind: FailureKind::Provider, error: &error, prompt: user_prompt, context: &system_prompt,
+                        messages: &messages, tools: &[], stats: &stats, depth: 0, dry_run: options.dry_run,
+                    })
+                };
+                let reply = match &bundle {
+                    Some(path) => format!("{}{}\nPost-mortem: {}", crate::FAILED_REPLY_PREFIX, error, path),
+                    None => format!("{}{}", crate::FAILED_REPLY_PREFIX, error),
+                };
+                if !is_private {
+                    // A failed acknowledgment is journaled in full
+                    if acknowledgment {
+                        self.flush_journal().await;
+                        let _ = self.memory.save_journal(&start_log).await;
+                    }
+                    let log = format!("\nAI [cycle: {}] failed (provider): {}\n", stats.cycle_id, reply);
+                    let _ = self.memory.save_journal(&log).await;
+                }
+                if let Some(t) = &tx { let _ = t.send(crate::events::BrainEvent::Error(reply.clone())).await; }
+                return (reply, stats, Vec::new());
             }
         };
 
-        // 3. Tool Loop
-        let max_depth = 24;
+        // 3. Tool Loop (none for slim prompts)
+        let max_depth = if slim { 0 } else { MAX_TOOL_DEPTH };
         let mut depth = 0;
+        let mut planned: Vec<PlannedCall> = Vec::new();
+        // Calls and outputs of this cycle, and how it failed, for the post-mortem
+        let mut invocations: Vec<ToolInvocation> = Vec::new();
+        let mut failure: Option<(FailureKind, String)> = None;
 
         loop {
             if depth >= max_depth {
-                warn!("⚠️ Depth limit hit ({}). Terminating tool loop to prevent infinite recursion.", max_depth);
-                content.push_str("\n\n[SYSTEM: Maximum thinking depth reached. Please summarize your findings.]");
+                if !slim {
+                    warn!("⚠️ Depth limit hit ({}). Terminating tool loop to prevent infinite recursion.", max_depth);
+                    content.push_str("\n\n[SYSTEM: Maximum thinking depth reached. Please summarize your findings.]");
+                    failure = Some((FailureKind::DepthExhausted, format!("Tool loop hit the depth limit ({} rounds) without a final answer", max_depth)));
+                }
                 break;
             }
 
@@ -53,8 +161,23 @@ impl Brain {
             }
 
 
-            // Robust Parser (State Machine) to handle nested brackets/JSON
-            let tools_to_run = crate::parser::ToolParser::extract_tools(&content, &self.skill_loader);
+            // Structured tool_calls take precedence; otherwise use the Robust Parser (State Machine) for brackets
+            let native_batch = !pending_calls.is_empty();
+            let (tools_to_run, call_ids) = if native_batch {
+                used_native_calls = true;
+                (
+                    pending_calls.iter().map(|c| (c.name.to_uppercase(), c.skill_arg())).collect::<Vec<_>>(),
+                    pending_calls.iter().map(|c| c.id.clone()).collect::<Vec<_>>(),
+                )
+            } else {
+                (crate::parser::ToolParser::extract_tools(&content, &self.skills()), Vec::new())
+            };
+
+            // Plan mode: calls of the last round are only recorded
+            if options.dry_run && depth >= DRY_RUN_ROUNDS {
+                planned.extend(tools_to_run.into_iter().map(|(name, arg)| PlannedCall { name, arg, executed: false }));
+                break;
+            }
 
             // Self-Correction: Check for common hallucinated tool formats (Markdown blocks)
             // We check this BEFORE deciding to break, because if the model tried to run a tool via markdown,
@@ -62,20 +185,25 @@ impl Brain {
             if tools_to_run.is_empty() && (content.contains("```tool_code") || content.contains("```python") || content.contains("```javascript") || content.contains("```bash")) {
                  warn!("⚠️ Detected invalid markdown tool usage. Triggering self-correction.");
 
-                 messages.push(Message { role: "assistant".into(), content: content.clone() });
+                 messages.push(Message { role: "assistant".into(), content: content.clone(), ..Default::default() });
                  messages.push(Message {
                      role: "user".into(),
-                     content: "SYSTEM ERROR: You attempted to use a tool using Markdown code blocks (```). THIS IS INVALID. \n\nREQUIRED SYNTAX: `[TOOL_NAME: argument]`\n\nExample: `[DELEGATE: \"task\"]`\n\nPlease retry immediately with the correct syntax.".into()
+                     content: "SYSTEM ERROR: You attempted to use a tool using Markdown code blocks (```). THIS IS INVALID. \n\nREQUIRED SYNTAX: `[TOOL_NAME: argument]`\n\nExample: `[DELEGATE: \"task\"]`\n\nPlease retry immediately with the correct syntax.".into(),
+                     ..Default::default()
                  });
 
-                 match self.complete(&messages).await {
-                    Ok(new_content) => {
-                        content = new_content;
+                 match self.complete_turn(&messages, native_tools.as_ref()).await {
+                    Ok(c) => {
+                        stats.record_call(&c.model, &c.usage);
+                        content = c.content;
+                        pending_calls = c.tool_calls;
+                        pending_raw_calls = c.raw_tool_calls;
                         depth += 1;
                         continue;
                     },
                     Err(e) => {
                          error!("Re-think error during self-correction: {}", e);
+                         failure = Some((FailureKind::Provider, e.to_string()));
                          break;
                     }
                  }
@@ -84,19 +212,42 @@ impl Brain {
             if tools_to_run.is_empty() {
                 break;
             }
+            stats.tool_calls += tools_to_run.len();
+            let call_args: Vec<String> = tools_to_run.iter().map(|(_, arg)| arg.clone()).collect();
 
             // Execute Tools in Parallel
             use futures::stream::{FuturesUnordered, StreamExt};
-            use std::pin::Pin;
-            use futures::Future;
 
-            let mut tool_tasks: FuturesUnordered<Pin<Box<dyn Future<Output = (String, Result<String, String>)> + Send>>> = FuturesUnordered::new();
+            // Exclusive (stateful) skills are serialized through this lock
+            let exclusive_lock = tokio::sync::Mutex::new(());
+            let mut tool_tasks: FuturesUnordered<ToolTask<'_>> = FuturesUnordered::new();
 
             // State Verification: Track seen files in this turn's history
             let history_so_far = messages.iter().map(|m| m.content.clone()).collect::<Vec<_>>().join("\n");
             let destructive_tools = ["edit_file", "write_file", "diff_patch", "delegate", "exec"];
 
-            for (name, arg) in tools_to_run {
+            for (call_idx, (name, arg)) in tools_to_run.into_iter().enumerate() {
+                if options.dry_run {
+                    let runs = self.config.dry_run_read_only && self.skills().get(&name).is_some_and(|s| s.read_only());
+                    planned.push(PlannedCall { name: name.clone(), arg: arg.clone(), executed: runs });
+                    if !runs {
+                        info!("📋 Dry run: [{} : {}] not executed", name, preview(&arg, 200));
+                        let tx = tx.clone();
+                        tool_tasks.push(Box::pin(async move {
+                            if let Some(t) = &tx {
+                                let _ = t.send(crate::events::BrainEvent::ToolExecution { name: name.clone(), arg }).await;
+                                let _ = t.send(crate::events::BrainEvent::ToolResult {
+                                    name: name.clone(),
+                                    output: DRY_RUN_OUTPUT.to_string(),
+                                    success: true,
+                                }).await;
+                            }
+                            (call_idx, name, Ok(DRY_RUN_OUTPUT.to_string()))
+                        }));
+                        continue;
+                    }
+                }
+
                 // Autonomous Safety Guard: Check if file was read before modification
                 if destructive_tools.contains(&name.to_lowercase().as_str()) {
                     let path_to_verify = if name.to_lowercase() == "delegate" {
@@ -123,7 +274,7 @@ impl Brain {
                         } else {
                             // Fallback: take the first argument (could be quoted)
                             let args = crate::parser::ToolParser::split_arguments(&arg);
-                            let path = args.get(0).cloned().unwrap_or_default();
+                            let path = args.first().cloned().unwrap_or_default();
                             if path.is_empty() { None } else { Some(path) }
                         }
                     };
@@ -142,20 +293,71 @@ impl Brain {
 
                         if !is_internal && file_exists && !history_so_far.contains(&absolute_path) && !system_prompt.contains(&absolute_path) && !history_so_far.contains(&path) && !system_prompt.contains(&path) {
                              warn!("🛑 State Verification Failure: AI tried to modify/delete {} without reading it first.", absolute_path);
+                             if self.skills().get(&name).is_some_and(|s| s.audited(&arg)) {
+                                 self.audit_call(&cycle_id, &name, &arg, AuditOutcome::Denied, is_private);
+                             }
                              tool_tasks.push(Box::pin(async move {
-                                 (name, Err(format!("ERROR: State Verification Refused. You must use `READ_FILE` or `LIST_DIR` on '{}' to verify its current state before attempting to modify or delete it. Blind writes/deletes are forbidden for safety.", absolute_path)))
+                                 (call_idx, name, Err(format!("ERROR: State Verification Refused. You must use `READ_FILE` or `LIST_DIR` on '{}' to verify its current state before attempting to modify or delete it. Blind writes/deletes are forbidden for safety.", absolute_path)))
                              }));
                              continue;
                         }
                     }
                 }
 
-                let skill_loader = &self.skill_loader;
+                let skill = self.skills().get(&name);
+                let config = self.llm_config();
+                let exclusive_lock = &exclusive_lock;
+                let approvals = &self.approvals;
                 let tx = tx.clone();
+                let cycle_id = &cycle_id;
 
                 tool_tasks.push(Box::pin(async move {
-                    if let Some(skill) = skill_loader.get(&name) {
-                        info!("⚙️ Executing: [{} : {}]", name, arg);
+                    if let Some(skill) = skill {
+                        // Left out of the prompt, but the model may still name it
+                        if !self.skill_permitted(&name) {
+                            warn!("🛂 {} refused: not permitted (ALLOWED_SKILLS/DENIED_SKILLS)", name);
+                            let refused = format!("DENIED: the {} skill is disabled in this configuration (ALLOWED_SKILLS/DENIED_SKILLS). Do not retry; use another skill or report what you would have done.", name);
+                            if skill.audited(&arg) {
+                                self.audit_call(cycle_id, &name, &arg, AuditOutcome::Denied, is_private);
+                            }
+                            if let Some(t) = &tx {
+                                let _ = t.send(crate::events::BrainEvent::ToolResult {
+                                    name: name.clone(),
+                                    output: refused.clone(),
+                                    success: false,
+                                }).await;
+                            }
+                            return (call_idx, name, Err(refused));
+                        }
+                        // Malformed arguments go back to the model with the schema, before any approval
+                        if let Err(invalid) = openspore_skills::check_args(skill.as_ref(), &arg) {
+                            warn!("⚠️ Argument error for {}: {}", name, preview(&arg, 200));
+                            if let Some(t) = &tx {
+                                let _ = t.send(crate::events::BrainEvent::ToolResult {
+                                    name: name.clone(),
+                                    output: invalid.clone(),
+                                    success: false,
+                                }).await;
+                            }
+                            return (call_idx, name, Err(invalid));
+                        }
+                        let audited = skill.audited(&arg);
+                        if let Err(denied) = approvals.check(&name, &arg, skill.destructive(), skill.requires_approval(&arg) || config.skill_needs_confirmation(&name), tx.as_ref()).await {
+                            if audited {
+                                self.audit_call(cycle_id, &name, &arg, AuditOutcome::Denied, is_private);
+                            }
+                            if let Some(t) = &tx {
+                                let _ = t.send(crate::events::BrainEvent::ToolResult {
+                                    name: name.clone(),
+                                    output: denied.clone(),
+                                    success: false,
+                                }).await;
+                            }
+                            return (call_idx, name, Err(denied));
+                        }
+                        let _exclusive_guard = if skill.exclusive() { Some(exclusive_lock.lock().await) } else { None };
+                        info!("⚙️ Executing: [{} : {}]", name, preview(&arg, 200));
+                        self.skills().record_use(&name);
 
                         if let Some(t) = &tx {
                             let _ = t.send(crate::events::BrainEvent::ToolExecution {
@@ -164,7 +366,11 @@ impl Brain {
                             }).await;
                         }
 
-                        match skill.execute(&arg).await {
+                        let result = skill.execute(&arg).await;
+                        if audited {
+                            self.audit_call(cycle_id, &name, &arg, audit_outcome(&result), is_private);
+                        }
+                        match result {
                             Ok(output) => {
                                 if let Some(t) = &tx {
                                     let _ = t.send(crate::events::BrainEvent::ToolResult {
@@ -173,7 +379,7 @@ impl Brain {
                                         success: true,
                                     }).await;
                                 }
-                                (name, Ok(output))
+                                (call_idx, name, Ok(output))
                             },
                             Err(e) => {
                                 if let Some(t) = &tx {
@@ -183,86 +389,314 @@ impl Brain {
                                         success: false,
                                     }).await;
                                 }
-                                (name, Err(e))
+                                (call_idx, name, Err(e))
                             }
                         }
                     } else {
-                        (name.clone(), Err(format!("Unknown tool '{}'", name)))
+                        (call_idx, name.clone(), Err(format!("Unknown tool '{}'", name)))
                     }
                 }));
             }
 
+            let mut results = Vec::new();
+            while let Some(res) = tool_tasks.next().await {
+                results.push(res);
+            }
+            results.sort_by_key(|(idx, _, _)| *idx);
+
             let mut tool_outputs = String::from("\n<TOOL_OUTPUTS>\n");
-            while let Some((name, result)) = tool_tasks.next().await {
-                match result {
-                    Ok(output) => {
-                        tool_outputs.push_str(&format!("\n--- Output from {} ---\n{}\n", name, output));
-                    },
+            let mut tool_messages = Vec::new();
+            for (idx, name, result) in results {
+                invocations.push(ToolInvocation {
+                    round: depth,
+                    name: name.clone(),
+                    arg: call_args.get(idx).cloned().unwrap_or_default(),
+                    ok: audit_outcome(&result) == AuditOutcome::Ok,
+                    output: match &result { Ok(output) => output.clone(), Err(e) => e.clone() },
+                });
+                let section = match result {
+                    Ok(output) => format!("\n--- Output from {} ---\n{}\n", name, output),
                     Err(e) => {
                         error!("❌ Error executing {}: {}", name, e);
-                        tool_outputs.push_str(&format!("\n--- Error from {} ---\n{}\n", name, e));
+                        format!("\n--- Error from {} ---\n{}\n", name, e)
                     }
+                };
+                if let Some(id) = call_ids.get(idx) {
+                    tool_messages.push(Message { role: "tool".to_string(), content: section.clone(), tool_call_id: Some(id.clone()), ..Default::default() });
                 }
+                tool_outputs.push_str(&section);
             }
             tool_outputs.push_str("\n</TOOL_OUTPUTS>\n");
 
             // Feedback Loop
-            messages.push(Message { role: "assistant".to_string(), content: content.clone() });
-            messages.push(Message {
-                role: "user".to_string(),
-                content: format!("{}\n\nProcess the results. If more actions needed, use tools. If done, provide final answer.", tool_outputs)
-            });
-
-            match self.complete(&messages).await {
-                Ok(new_content) => content = new_content,
+            if native_batch {
+                messages.push(Message { role: "assistant".to_string(), content: content.clone(), tool_calls: pending_raw_calls.take(), ..Default::default() });
+                messages.extend(tool_messages);
+            } else {
+                messages.push(Message { role: "assistant".to_string(), content: content.clone(), ..Default::default() });
+                messages.push(Message {
+                    role: "user".to_string(),
+                    content: format!("{}\n\nProcess the results. If more actions needed, use tools. If done, provide final answer.", tool_outputs),
+                    ..Default::default()
+                });
+            }
+
+            match self.complete_turn(&messages, native_tools.as_ref()).await {
+                Ok(c) => {
+                    stats.record_call(&c.model, &c.usage);
+                    content = c.content;
+                    pending_calls = c.tool_calls;
+                    pending_raw_calls = c.raw_tool_calls;
+                }
                 Err(e) => {
                     error!("Re-think error: {}", e);
                     if let Some(t) = &tx { let _ = t.send(crate::events::BrainEvent::Error(e.to_string())).await; }
+                    failure = Some((FailureKind::Provider, e.to_string()));
                     break;
                 }
             }
             depth += 1;
         }
 
-        // Final answer notification
+        stats.layers = depth + 1;
+        stats.duration_ms = start_time.elapsed().as_millis() as u64;
+
+        // The <FOLLOW_UPS> trailer never reaches the user or the workspace
+        let (answer, trailer) = crate::suggestions::split_trailer(&content);
+        content = answer;
+        if options.dry_run {
+            content.push_str(&crate::plan::render_plan(&planned));
+        }
+
+        // A failed cycle leaves a post-mortem; the reply (and so the journal) points at it
+        if failure.is_none() {
+            let errors = invocations.iter().filter(|i| !i.ok).count();
+            failure = crate::postmortem::tool_failures(invocations.len(), errors).map(|e| (FailureKind::ToolFailures, e));
+        }
+        if let Some((kind, error)) = &failure
+            && !is_private
+            && let Some(bundle) = self.postmortem(FailedCycle {
+                kind: *kind, error, prompt: user_prompt, context: &system_prompt,
+                messages: &messages, tools: &invocations, stats: &stats, depth, dry_run: options.dry_run,
+            })
+        {
+            content.push_str(&format!("\n\n{}{}): {}\nPost-mortem: {}", crate::CYCLE_FAILED_MARKER, kind.as_str(), preview(error, 300), bundle));
+        }
+
+        self.set_last_answer(&content);
+
+        // Final answer notification (the stats follow once the fast path is counted)
         if let Some(t) = &tx {
             let _ = t.send(crate::events::BrainEvent::FinalAnswer(content.clone())).await;
         }
 
-        // Save interaction for Watchman to analyze
+        // Private turns leave no trace in the workspace
+        if is_private {
+            if let Some(t) = &tx { let _ = t.send(crate::events::BrainEvent::Stats(stats.clone())).await; }
+            info!("🔒 Private cycle finished in {} (nothing persisted)", humanize_duration(start_time.elapsed()));
+            return (content, stats, Vec::new());
+        }
+
+        // A "yes" that ran tools or got a long answer is a real turn after all
+        let held_back = acknowledgment;
+        let acknowledgment = crate::fast_path::is_fast_path(user_prompt, &content, depth);
+        if held_back && !acknowledgment {
+            self.flush_journal().await;
+            let _ = self.memory.save_journal(&start_log).await;
+        }
+
+        let cited: Vec<_> = injected.into_iter().filter(|m| openspore_memory::cites(&content, m)).collect();
+        self.memory.publish_usage(&cited, true);
+
+        // Save interaction for Watchman to analyze (acknowledgments carry nothing to analyze)
         let exchange = format!("**User**: {}\n\n**Assistant**: {}", user_prompt, content);
-        let _ = self.context_manager.save_interaction(
-            &exchange,
-            vec!["conversation".to_string()],
-            Some("exchange")
-        ).await;
-
-        // Learn from interaction (extract preferences/knowledge)
-        tokio::spawn({
-            let brain = self.clone_brain();
-            let prompt = user_prompt.to_string();
-            let resp = content.clone();
-            let older_items = session_ctx.older_items.clone();
-            async move {
-                // 1. Learn from exchange
-                brain.learn(&prompt, &resp).await;
-
-                // 2. Compress context if needed (deferred)
-                if !older_items.is_empty() {
-                    if let Err(e) = brain.context_manager.compress_older_items(older_items, &brain).await {
-                         warn!("Context compression notice (might be parallel task): {}", e);
-                    }
-                }
+        let model = stats.answer_model().map(str::to_string).unwrap_or_else(|| self.get_model());
+        let provider = self.llm_config().provider().as_str();
+        if acknowledgment {
+            stats.fast_path.exchange_skipped += 1;
+        } else {
+            let _ = self.context_manager.save_interaction(
+                &exchange,
+                vec!["conversation".to_string()],
+                Some("exchange"),
+                &[
+                    ("model", model.clone()),
+                    ("provider", provider.to_string()),
+                    ("event", "think".to_string()),
+                    ("cycle", stats.cycle_id.clone()),
+                    ("prompt_tokens", stats.total.prompt_tokens.to_string()),
+                    ("completion_tokens", stats.total.completion_tokens.to_string()),
+                ],
+            ).await;
+        }
+
+        // Learn from interaction and compress older context (queued, see pending_work)
+        self.queue_background_work(&cycle_id, user_prompt, &content, &session_ctx.older_items, acknowledgment, &mut stats.fast_path);
+
+        // Log FULL raw interaction to LOGS.md (Result of turn); acknowledgments go in one batched line
+        let tool_path = if used_native_calls { " [tools: native]" } else { "" };
+        info!("🔧 Tool path: {}", if used_native_calls { "native function calling" } else { "bracket syntax" });
+        if acknowledgment {
+            stats.fast_path.journal_batched += 1;
+            self.journal_acknowledgment(user_prompt, &content).await;
+        } else {
+            let end_log = format!(
+                "\nAI [model: {}, provider: {}, cycle: {}, tokens: {}/{}]{}: {}\n",
+                model, provider, stats.cycle_id, stats.total.prompt_tokens, stats.total.completion_tokens, tool_path, content
+            );
+            if let Err(e) = self.memory.save_journal(&end_log).await {
+                error!("❌ Failed to save journal entry to LOGS.md: {}", e);
             }
-        });
+        }
 
-        // Log FULL raw interaction to LOGS.md (Result of turn)
-        let end_log = format!("\nAI: {}\n", content);
-        if let Err(e) = self.memory.save_journal(&end_log).await {
-            error!("❌ Failed to save journal entry to LOGS.md: {}", e);
+        self.record_cycle(&stats);
+        if let Some(t) = &tx {
+            let _ = t.send(crate::events::BrainEvent::Stats(stats.clone())).await;
         }
 
-        info!("✅ Cycle finished in {:?}", start_time.elapsed());
-        content
+        info!("✅ Cycle finished in {} ({} tokens, ${:.4})", humanize_duration(start_time.elapsed()), stats.total.total_tokens(), stats.total.cost);
+
+        let suggestions = if suggest { self.followups(user_prompt, &content, trailer, &stats).await } else { Vec::new() };
+        if !suggestions.is_empty()
+            && let Some(t) = &tx
+        {
+            let _ = t.send(crate::events::BrainEvent::Suggestions(suggestions.clone())).await;
+        }
+        (content, stats, suggestions)
+    }
+}
+
+impl Brain {
+    /// One skill call outside a think() turn (pipeline steps), through the
+    /// same gates as the tool loop: skill permissions, argument schema,
+    /// approval and the audit log. Refusals are Err with the message a model
+    /// would get; the skill's own output, failed or not, is Ok.
+    pub async fn call_skill(&self, name: &str, arg: &str, cycle: &str, tx: Option<tokio::sync::mpsc::Sender<crate::events::BrainEvent>>) -> Result<String, String> {
+        let Some(skill) = self.skills().get(name) else {
+            return Err(format!("Unknown tool '{}'", name));
+        };
+        let audited = skill.audited(arg);
+        if !self.skill_permitted(name) {
+            if audited {
+                self.audit_call(cycle, name, arg, AuditOutcome::Denied, false);
+            }
+            return Err(format!("DENIED: the {} skill is disabled in this configuration (ALLOWED_SKILLS/DENIED_SKILLS)", name));
+        }
+        openspore_skills::check_args(skill.as_ref(), arg)?;
+        let required = skill.requires_approval(arg) || self.llm_config().skill_needs_confirmation(name);
+        if let Err(denied) = self.approvals.check(name, arg, skill.destructive(), required, tx.as_ref()).await {
+            if audited {
+                self.audit_call(cycle, name, arg, AuditOutcome::Denied, false);
+            }
+            return Err(denied);
+        }
+
+        info!("⚙️ Executing: [{} : {}]", name, preview(arg, 200));
+        self.skills().record_use(name);
+        if let Some(t) = &tx {
+            let _ = t.send(crate::events::BrainEvent::ToolExecution { name: name.to_string(), arg: arg.to_string() }).await;
+        }
+        let result = skill.execute(arg).await;
+        if audited {
+            self.audit_call(cycle, name, arg, audit_outcome(&result), false);
+        }
+        if let Some(t) = &tx {
+            let (output, success) = match &result {
+                Ok(output) => (output.clone(), audit_outcome(&result) == AuditOutcome::Ok),
+                Err(e) => (e.clone(), false),
+            };
+            let _ = t.send(crate::events::BrainEvent::ToolResult { name: name.to_string(), output, success }).await;
+        }
+        result
+    }
+
+    /// Append a call of an audited skill to workspace/audit. Private turns keep
+    /// only the digest of the arguments. A failed write never fails the call.
+    fn audit_call(&self, cycle: &str, skill: &str, arg: &str, outcome: AuditOutcome, private: bool) {
+        let channel = if self.spore.is_spore { "spore" } else { self.channel };
+        let event = AuditEvent { cycle, channel, skill, args: arg, outcome, private };
+        if let Err(e) = self.memory.audit(event) {
+            warn!("📜 Audit log not written: {}", e);
+        }
+    }
+}
+
+/// Skills report most refusals and failures as JSON with `success: false`
+fn audit_outcome(result: &Result<String, String>) -> AuditOutcome {
+    let Ok(output) = result else { return AuditOutcome::Error };
+    match serde_json::from_str::<serde_json::Value>(output) {
+        Ok(json) if json.get("success").and_then(|v| v.as_bool()) == Some(false) => {
+            if json.get("blocked").is_some() { AuditOutcome::Denied } else { AuditOutcome::Error }
+        }
+        _ => AuditOutcome::Ok,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{brain_in, fake_llm};
+    use async_trait::async_trait;
+    use openspore_skills::Skill;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// Sleeps 500ms, then echoes its arguments
+    struct Nap {
+        name: &'static str,
+        exclusive: bool,
+    }
+
+    #[async_trait]
+    impl Skill for Nap {
+        fn name(&self) -> &'static str { self.name }
+        fn description(&self) -> &'static str { "Sleep for half a second" }
+        fn exclusive(&self) -> bool { self.exclusive }
+        async fn execute(&self, args: &str) -> Result<String, String> {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            Ok(format!("{} woke up ({})", self.name, args.trim()))
+        }
+    }
+
+    /// Runs `calls` in one tool round, then answers with the tool outputs it got
+    async fn batch(dir: &std::path::Path, calls: &'static str, skills: [Nap; 2]) -> (String, Duration) {
+        let (url, _) = fake_llm(move |request| {
+            let last = request["messages"].as_array().and_then(|m| m.last()).cloned().unwrap_or_default();
+            let content = last["content"].as_str().unwrap_or_default();
+            match content.find("<TOOL_OUTPUTS>") {
+                Some(at) => content[at..].to_string(),
+                None => calls.to_string(),
+            }
+        }).await;
+        let brain = brain_in(dir, &url).await;
+        for skill in skills {
+            brain.skill_loader.write().unwrap().register(Arc::new(skill));
+        }
+
+        let started = Instant::now();
+        let (reply, _) = brain.think_with_stats("run the nap_a and nap_b skills").await;
+        (reply, started.elapsed())
+    }
+
+    #[tokio::test]
+    async fn independent_skills_run_concurrently() {
+        let dir = tempfile::tempdir().unwrap();
+        let skills = [Nap { name: "nap_a", exclusive: false }, Nap { name: "nap_b", exclusive: false }];
+
+        let (reply, elapsed) = batch(dir.path(), "[NAP_B: second]\n[NAP_A: first]", skills).await;
+
+        assert!(elapsed < Duration::from_millis(900), "took {:?}", elapsed);
+        let (b, a) = (reply.find("nap_b woke up (second)").unwrap(), reply.find("nap_a woke up (first)").unwrap());
+        assert!(b < a, "outputs follow the call order:\n{}", reply);
+    }
+
+    #[tokio::test]
+    async fn exclusive_skills_take_turns() {
+        let dir = tempfile::tempdir().unwrap();
+        let skills = [Nap { name: "nap_a", exclusive: true }, Nap { name: "nap_b", exclusive: true }];
+
+        let (reply, elapsed) = batch(dir.path(), "[NAP_A: first]\n[NAP_B: second]", skills).await;
+
+        assert!(elapsed >= Duration::from_millis(1000), "took {:?}", elapsed);
+        assert!(reply.contains("nap_a woke up (first)") && reply.contains("nap_b woke up (second)"));
     }
 }
@@ -0,0 +1,86 @@
+//! Native (OpenAI-style) Function Calling
+//!
+//! When enabled, skills are sent as a `tools` array and the model answers with
+//! structured `tool_calls` instead of `[TOOL: arg]` text. The bracket parser stays
+//! active as a fallback for models that ignore the tools API.
+
+use crate::Brain;
+
+/// A tool call returned by the model
+#[derive(Debug, Clone)]
+pub struct NativeToolCall {
+    pub id: String,
+    pub name: String,
+    /// Raw JSON arguments as sent by the model
+    pub arguments: String,
+}
+
+impl NativeToolCall {
+    /// Convert the structured arguments into the string a Skill expects.
+    /// The default schema wraps the classic argument in `{"args": "..."}`.
+    pub fn skill_arg(&self) -> String {
+        match serde_json::from_str::<serde_json::Value>(&self.arguments) {
+            Ok(serde_json::Value::Object(map)) if map.len() == 1 && map.get("args").is_some_and(|v| v.is_string()) => {
+                map["args"].as_str().unwrap_or_default().to_string()
+            }
+            _ => self.arguments.clone(),
+        }
+    }
+}
+
+/// Parsed completion (text + optional structured tool calls)
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    pub content: String,
+    pub tool_calls: Vec<NativeToolCall>,
+    /// Original `tool_calls` JSON, echoed back in the assistant message
+    pub raw_tool_calls: Option<serde_json::Value>,
+    pub usage: crate::usage::TokenUsage,
+    pub model: String,
+}
+
+/// Model families known to support the tools API (used by `auto`)
+const NATIVE_CAPABLE_PREFIXES: [&str; 6] = ["openai/", "gpt-", "anthropic/", "google/gemini", "mistralai/", "qwen/"];
+
+impl Brain {
+    /// Whether this brain should send a `tools` array (TOOL_CALLING=native|auto|bracket)
+    pub fn native_tool_calling(&self) -> bool {
+        match self.config.tool_calling.as_deref().map(|s| s.trim().to_lowercase()) {
+            Some(mode) if mode == "native" => true,
+            Some(mode) if mode == "auto" => {
+                let model = self.get_model().to_lowercase();
+                NATIVE_CAPABLE_PREFIXES.iter().any(|p| model.starts_with(p))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Extract tool calls from an OpenAI-style (or Ollama) `message` object
+pub(crate) fn parse_tool_calls(message: &serde_json::Value) -> (Vec<NativeToolCall>, Option<serde_json::Value>) {
+    let Some(raw) = message.get("tool_calls").filter(|v| v.is_array()) else {
+        return (Vec::new(), None);
+    };
+
+    let calls = raw.as_array().map(|arr| {
+        arr.iter().enumerate().filter_map(|(i, call)| {
+            let function = call.get("function")?;
+            let name = function.get("name")?.as_str()?.to_string();
+            // OpenAI sends arguments as a JSON string, Ollama as an object
+            let arguments = match function.get("arguments") {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+                None => "{}".to_string(),
+            };
+            let id = call.get("id").and_then(|v| v.as_str()).map(|s| s.to_string())
+                .unwrap_or_else(|| format!("call_{}", i));
+            Some(NativeToolCall { id, name, arguments })
+        }).collect::<Vec<_>>()
+    }).unwrap_or_default();
+
+    if calls.is_empty() {
+        (Vec::new(), None)
+    } else {
+        (calls, Some(raw.clone()))
+    }
+}
@@ -0,0 +1,333 @@
+//! Token & Cost Accounting
+//!
+//! Every completion reports its `usage` object; think() cycles are summarised
+//! into one line of `workspace/usage.jsonl` (with a per-call breakdown by model)
+//! so spending can be audited later.
+
+use crate::Brain;
+use crate::prompt_mode::PromptMode;
+use chrono::{Datelike, Local};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Token counts reported by the provider for one or more completions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Cost in USD as reported by OpenRouter (0 when unknown)
+    pub cost: f64,
+}
+
+impl TokenUsage {
+    pub fn from_response(json: &serde_json::Value) -> Self {
+        let usage = &json["usage"];
+        Self {
+            prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0),
+            completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0),
+            cost: usage["cost"].as_f64().unwrap_or(0.0),
+        }
+    }
+
+    /// Ollama reports token counts as prompt_eval_count / eval_count (no cost)
+    pub fn from_ollama_response(json: &serde_json::Value) -> Self {
+        Self {
+            prompt_tokens: json["prompt_eval_count"].as_u64().unwrap_or(0),
+            completion_tokens: json["eval_count"].as_u64().unwrap_or(0),
+            cost: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cost += other.cost;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// One persisted line of usage.jsonl
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub timestamp: String,
+    pub model: String,
+    /// "think" for full cycles, "auxiliary" for learning/compression/one-shots
+    pub event_type: String,
+    /// Spore role when the call came from a delegated sub-spore
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    /// Per-call breakdown (think cycles only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallUsage>,
+    /// Links a think cycle to its exchange file and journal line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle_id: Option<String>,
+    /// Prompt of a think cycle (full or slim)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_mode: Option<PromptMode>,
+    /// Wall time of a think cycle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Work a think cycle skipped on the fast path
+    #[serde(default, skip_serializing_if = "FastPathStats::is_empty")]
+    pub fast_path: FastPathStats,
+}
+
+/// Usage of a single completion, attributed to its model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallUsage {
+    pub model: String,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+}
+
+/// Work skipped on the fast path (see fast_path): per cycle, or summed over a month
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FastPathStats {
+    /// learn() not queued for an acknowledgment
+    pub learn_skipped: u32,
+    /// learn() not queued within LEARN_MIN_INTERVAL_SECS of the last one
+    pub learn_throttled: u32,
+    /// Exchange file not written
+    pub exchange_skipped: u32,
+    /// Older context not compressed into the session summary
+    pub summary_skipped: u32,
+    /// Journal line held back for the batched acknowledgment line
+    pub journal_batched: u32,
+}
+
+impl FastPathStats {
+    pub fn add(&mut self, other: &FastPathStats) {
+        self.learn_skipped += other.learn_skipped;
+        self.learn_throttled += other.learn_throttled;
+        self.exchange_skipped += other.exchange_skipped;
+        self.summary_skipped += other.summary_skipped;
+        self.journal_batched += other.journal_batched;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.learn_skipped + self.learn_throttled + self.exchange_skipped + self.summary_skipped + self.journal_batched
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+/// Statistics of one think() cycle, emitted as BrainEvent::Stats
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CycleStats {
+    /// Start timestamp, also written to the exchange frontmatter and journal
+    #[serde(default)]
+    pub cycle_id: String,
+    pub total: TokenUsage,
+    pub calls: Vec<CallUsage>,
+    /// Number of tool executions across all layers
+    pub tool_calls: usize,
+    /// Number of thinking layers (completions in the tool loop)
+    pub layers: usize,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub prompt_mode: PromptMode,
+    #[serde(default)]
+    pub fast_path: FastPathStats,
+}
+
+impl CycleStats {
+    pub fn start() -> Self {
+        Self { cycle_id: Local::now().format("%Y%m%d%H%M%S%3f").to_string(), ..Default::default() }
+    }
+
+    /// Model of the last completion, i.e. the one that wrote the answer
+    pub fn answer_model(&self) -> Option<&str> {
+        self.calls.last().map(|c| c.model.as_str())
+    }
+
+    pub fn record_call(&mut self, model: &str, usage: &TokenUsage) {
+        self.total.add(usage);
+        self.calls.push(CallUsage { model: model.to_string(), usage: usage.clone() });
+    }
+}
+
+/// Aggregated totals for the `usage` command
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageStats {
+    pub today: TokenUsage,
+    pub today_calls: usize,
+    pub month: TokenUsage,
+    pub month_calls: usize,
+    /// This month's think cycles by prompt mode
+    pub month_latency: Vec<ModeLatency>,
+    /// Work this month's think cycles skipped on the fast path
+    pub month_fast_path: FastPathStats,
+}
+
+/// Think cycles of one prompt mode and their total wall time
+#[derive(Debug, Clone, Serialize)]
+pub struct ModeLatency {
+    pub mode: PromptMode,
+    pub cycles: usize,
+    pub total_ms: u64,
+}
+
+impl ModeLatency {
+    pub fn average_ms(&self) -> u64 {
+        self.total_ms / self.cycles.max(1) as u64
+    }
+}
+
+impl Brain {
+    pub(crate) fn usage_path(&self) -> PathBuf {
+        self.config.project_root.join("workspace").join("usage.jsonl")
+    }
+
+    pub(crate) fn record_usage(&self, event_type: &str, usage: &TokenUsage) {
+        self.append_usage_record(event_type, usage, Vec::new(), None);
+    }
+
+    pub(crate) fn record_cycle(&self, stats: &CycleStats) {
+        self.append_usage_record("think", &stats.total, stats.calls.clone(), Some(stats));
+    }
+
+    fn append_usage_record(&self, event_type: &str, usage: &TokenUsage, calls: Vec<CallUsage>, cycle: Option<&CycleStats>) {
+        let role = if self.spore.is_spore {
+            Some(self.spore.role().to_string())
+        } else {
+            None
+        };
+
+        let record = UsageRecord {
+            timestamp: Local::now().to_rfc3339(),
+            model: self.get_model(),
+            event_type: event_type.to_string(),
+            role,
+            usage: usage.clone(),
+            calls,
+            cycle_id: cycle.map(|c| c.cycle_id.clone()),
+            prompt_mode: cycle.map(|c| c.prompt_mode),
+            duration_ms: cycle.map(|c| c.duration_ms),
+            fast_path: cycle.map(|c| c.fast_path).unwrap_or_default(),
+        };
+
+        let path = self.usage_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let line = match serde_json::to_string(&record) {
+            Ok(l) => l,
+            Err(_) => return,
+        };
+
+        let res = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| writeln!(f, "{}", line));
+
+        if let Err(e) = res {
+            warn!("Failed to record usage: {}", e);
+        }
+    }
+
+    /// Totals for today and the current month, read from usage.jsonl
+    pub fn usage_stats(&self) -> UsageStats {
+        let mut stats = UsageStats::default();
+        let content = std::fs::read_to_string(self.usage_path()).unwrap_or_default();
+        let now = Local::now();
+
+        for line in content.lines() {
+            let Ok(record) = serde_json::from_str::<UsageRecord>(line) else { continue };
+            let Ok(ts) = chrono::DateTime::parse_from_rfc3339(&record.timestamp) else { continue };
+            let ts = ts.with_timezone(&Local);
+
+            if ts.year() == now.year() && ts.month() == now.month() {
+                stats.month.add(&record.usage);
+                stats.month_calls += 1;
+                stats.month_fast_path.add(&record.fast_path);
+                if let (Some(mode), Some(ms)) = (record.prompt_mode, record.duration_ms) {
+                    match stats.month_latency.iter_mut().find(|l| l.mode == mode) {
+                        Some(latency) => {
+                            latency.cycles += 1;
+                            latency.total_ms += ms;
+                        }
+                        None => stats.month_latency.push(ModeLatency { mode, cycles: 1, total_ms: ms }),
+                    }
+                }
+
+                if ts.day() == now.day() {
+                    stats.today.add(&record.usage);
+                    stats.today_calls += 1;
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{brain_in, fake_llm};
+
+    const ANSWER: &str = "Use SQLite: it is embedded, durable and needs no server.";
+
+    #[tokio::test]
+    async fn a_cycle_links_usage_exchange_and_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _requests) = fake_llm(|_| ANSWER.to_string()).await;
+        let brain = brain_in(dir.path(), &url).await;
+        brain.think("Which database should the cache use?").await;
+
+        let records: Vec<UsageRecord> = std::fs::read_to_string(brain.usage_path()).unwrap().lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let think = records.iter().find(|r| r.event_type == "think").unwrap();
+        let cycle = think.cycle_id.as_deref().unwrap();
+        assert_eq!(think.model, "test-model");
+        assert!(records.iter().filter(|r| r.event_type != "think").all(|r| r.cycle_id.is_none()));
+
+        // The exchange carries the same values instead of recomputing them
+        let exchange = brain.memory.get_memories("context").into_iter()
+            .find(|m| m.meta("type") == Some("exchange"))
+            .unwrap();
+        assert_eq!(exchange.meta("cycle"), Some(cycle));
+        assert_eq!(exchange.meta("model"), Some("test-model"));
+        assert_eq!(exchange.meta("event"), Some("think"));
+        assert_eq!(exchange.meta("prompt_tokens"), Some(think.usage.prompt_tokens.to_string().as_str()));
+        assert_eq!(exchange.meta("completion_tokens"), Some(think.usage.completion_tokens.to_string().as_str()));
+        assert_eq!(brain.memory.audit_exchanges(Some("test-model"), None, None).len(), 1);
+
+        let journal = std::fs::read_to_string(brain.memory.context_dir().join("LOGS.md")).unwrap();
+        assert!(journal.contains(&format!("cycle: {}, tokens: {}/{}]", cycle, think.usage.prompt_tokens, think.usage.completion_tokens)), "{}", journal);
+    }
+
+    #[tokio::test]
+    async fn usage_stats_read_legacy_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain_in(dir.path(), "http://127.0.0.1:9/v1").await;
+        let now = Local::now().to_rfc3339();
+        let lines = [
+            // Before cycle ids, prompt modes and fast-path counts were recorded
+            format!(r#"{{"timestamp":"{}","model":"old","event_type":"think","prompt_tokens":100,"completion_tokens":10,"cost":0.5}}"#, now),
+            format!(r#"{{"timestamp":"{}","model":"new","event_type":"think","prompt_tokens":50,"completion_tokens":5,"cost":0.0,"cycle_id":"1","prompt_mode":"full","duration_ms":800}}"#, now),
+            "not json".to_string(),
+            r#"{"timestamp":"2001-01-01T00:00:00+00:00","model":"old","event_type":"think","prompt_tokens":7,"completion_tokens":7,"cost":0.0}"#.to_string(),
+        ];
+        std::fs::write(brain.usage_path(), lines.join("\n")).unwrap();
+
+        let stats = brain.usage_stats();
+        assert_eq!(stats.today_calls, 2);
+        assert_eq!((stats.month.prompt_tokens, stats.month.completion_tokens), (150, 15));
+        assert_eq!(stats.month_latency.len(), 1);
+        assert_eq!(stats.month_latency[0].average_ms(), 800);
+    }
+}
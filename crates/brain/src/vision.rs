@@ -0,0 +1,70 @@
+use crate::Brain;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use std::path::Path;
+
+/// Images larger than this are not sent to the vision model
+const MAX_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+fn image_mime(path: &Path) -> &'static str {
+    match path.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref() {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+impl Brain {
+    pub fn vision_enabled(&self) -> bool {
+        self.llm_config().model_vision.as_deref().is_some_and(|m| !m.trim().is_empty())
+    }
+
+    /// Describe an image with OPENROUTER_MODEL_VISION (OpenAI-style image_url content).
+    /// Ok(None) when no vision model is configured.
+    pub async fn describe_image(&self, path: &Path, prompt: &str) -> Result<Option<String>> {
+        let Some(model) = self.llm_config().model_vision.clone().filter(|m| !m.trim().is_empty()) else {
+            return Ok(None);
+        };
+        if std::fs::metadata(path)?.len() > MAX_IMAGE_BYTES {
+            return Err(anyhow!("Image is too large for the vision model"));
+        }
+
+        let data = base64::engine::general_purpose::STANDARD.encode(tokio::fs::read(path).await?);
+        let body = serde_json::json!({
+            "model": model,
+            "messages": [{
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": prompt },
+                    { "type": "image_url", "image_url": { "url": format!("data:{};base64,{}", image_mime(path), data) } }
+                ]
+            }],
+            "max_tokens": 1024,
+            "usage": { "include": true },
+        });
+
+        let mut req = self.client.post(self.config.completions_url());
+        if !self.config.openrouter_api_key.is_empty() {
+            req = req.header("Authorization", format!("Bearer {}", self.config.openrouter_api_key));
+        }
+        let res = req
+            .header("HTTP-Referer", "https://openspore.net")
+            .header("X-Title", "OpenSpore")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| self.map_request_error(e))?;
+
+        let status = res.status();
+        if !status.is_success() {
+            let detail = res.text().await.unwrap_or_default();
+            return Err(anyhow!("Vision API error {}: {}", status, openspore_core::fmt_utils::truncate_chars(&detail, 200)));
+        }
+
+        let json: serde_json::Value = res.json().await?;
+        self.record_usage("vision", &crate::usage::TokenUsage::from_response(&json));
+        let text = json["choices"][0]["message"]["content"].as_str().unwrap_or("").trim().to_string();
+        Ok((!text.is_empty()).then_some(text))
+    }
+}
@@ -0,0 +1,76 @@
+//! Approval prompts (APPROVAL_MODE) as inline keyboards
+//!
+//! While a reply is being thought out, every BrainEvent::ApprovalRequest is
+//! posted to the chat with Approve / Always / Deny buttons. The button's
+//! callback data is `approval:<id>:<choice>`, answered in handle_callback().
+
+use openspore_brain::Brain;
+use openspore_brain::approval::ApprovalDecision;
+use openspore_brain::events::BrainEvent;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use tracing::info;
+
+const CALLBACK_PREFIX: &str = "approval:";
+
+/// think_with_suggestions() that asks the chat before gated tools run
+pub async fn think(bot: &Bot, chat_id: ChatId, brain: &Brain, prompt: &str) -> (String, Vec<String>) {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<BrainEvent>(32);
+    let prompts = {
+        let bot = bot.clone();
+        tokio::spawn(async move {
+            // Ends when the cycle drops its sender
+            while let Some(event) = rx.recv().await {
+                if let BrainEvent::ApprovalRequest { id, name, arg } = event {
+                    let text = format!("🛂 Allow {}?\n\n{}", name, openspore_core::fmt_utils::preview(&arg, 500));
+                    let _ = bot.send_message(chat_id, text).reply_markup(keyboard(id)).await;
+                }
+            }
+        })
+    };
+    let reply = brain.think_with_suggestions_observed(prompt, tx).await;
+    let _ = prompts.await;
+    reply
+}
+
+fn keyboard(id: u64) -> InlineKeyboardMarkup {
+    let button = |label: &str, choice: &str| InlineKeyboardButton::callback(label.to_string(), format!("{}{}:{}", CALLBACK_PREFIX, id, choice));
+    InlineKeyboardMarkup::new(vec![vec![
+        button("✅ Approve", "approve"),
+        button("♾️ Always", "always"),
+        button("🚫 Deny", "deny"),
+    ]])
+}
+
+/// Answer a button press and replace the keyboard with the outcome
+pub async fn handle_callback(bot: Bot, q: CallbackQuery, brain: &Brain, allowed_users: &[String]) -> ResponseResult<()> {
+    let user_id = q.from.id.to_string();
+    if !allowed_users.is_empty() && !allowed_users.contains(&user_id) {
+        bot.answer_callback_query(q.id.clone()).text("⛔ Access Denied.").await?;
+        return Ok(());
+    }
+
+    let Some((id, choice)) = q.data.as_deref()
+        .and_then(|d| d.strip_prefix(CALLBACK_PREFIX))
+        .and_then(|d| d.split_once(':'))
+        .and_then(|(id, choice)| Some((id.parse::<u64>().ok()?, choice)))
+    else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    let (decision, outcome) = match choice {
+        "approve" => (ApprovalDecision::Approve, "✅ Approved"),
+        "always" => (ApprovalDecision::AlwaysAllow, "✅ Always allowed for this session"),
+        _ => (ApprovalDecision::Deny("denied from Telegram".to_string()), "🚫 Denied"),
+    };
+    info!("🛂 [Telegram] {} request {} ({})", outcome, id, user_id);
+    let outcome = if brain.approvals.respond(id, decision) { outcome } else { "⌛ Expired" };
+
+    bot.answer_callback_query(q.id.clone()).text(outcome).await?;
+    if let Some(message) = q.regular_message() {
+        let text = format!("{}\n\n{}", message.text().unwrap_or_default(), outcome);
+        let _ = bot.edit_message_text(message.chat.id, message.id, text).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Photo and document ingestion
+//!
+//! Files sent to the bot land in workspace/inbox/<date>/. Small text documents
+//! are inlined into the prompt; images and other files are referenced by path
+//! (images are also described when OPENROUTER_MODEL_VISION is set).
+
+use anyhow::{anyhow, Result};
+use openspore_brain::Brain;
+use openspore_core::fmt_utils::{humanize_bytes, truncate_chars};
+use std::path::{Path, PathBuf};
+use teloxide::net::Download;
+use teloxide::prelude::*;
+use teloxide::types::FileId;
+
+/// Bot API download limit; bigger files are refused up front
+pub const MAX_FILE_BYTES: u64 = 20 * 1024 * 1024;
+/// Text documents up to this size are read into the prompt
+const MAX_TEXT_BYTES: u64 = 100 * 1024;
+/// Characters of an inlined document kept in the prompt
+const MAX_TEXT_CHARS: usize = 20_000;
+const TEXT_EXTENSIONS: [&str; 3] = ["txt", "md", "csv"];
+
+pub struct Attachment {
+    pub id: FileId,
+    pub file_name: String,
+    pub size: u64,
+    pub is_image: bool,
+}
+
+impl Attachment {
+    pub fn from_message(msg: &Message) -> Option<Self> {
+        if let Some(photo) = msg.photo().and_then(|sizes| sizes.iter().max_by_key(|p| p.width * p.height)) {
+            return Some(Self {
+                id: photo.file.id.clone(),
+                file_name: format!("photo_{}.jpg", photo.file.unique_id),
+                size: photo.file.size as u64,
+                is_image: true,
+            });
+        }
+        let doc = msg.document()?;
+        let file_name = doc.file_name.clone().unwrap_or_else(|| format!("document_{}", doc.file.unique_id));
+        Some(Self {
+            id: doc.file.id.clone(),
+            is_image: doc.mime_type.as_ref().is_some_and(|m| m.type_() == "image"),
+            file_name: sanitize_file_name(&file_name),
+            size: doc.file.size as u64,
+        })
+    }
+
+    pub fn too_large(&self) -> bool {
+        self.size > MAX_FILE_BYTES
+    }
+
+    fn is_text(&self) -> bool {
+        Path::new(&self.file_name)
+            .extension()
+            .is_some_and(|e| TEXT_EXTENSIONS.contains(&e.to_string_lossy().to_lowercase().as_str()))
+    }
+}
+
+/// Keep the name on one path component
+fn sanitize_file_name(name: &str) -> String {
+    let clean: String = name.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    match clean.trim_start_matches('.') {
+        "" => "file".to_string(),
+        rest => rest.to_string(),
+    }
+}
+
+/// Download a Telegram file to `path`
+pub(crate) async fn download(bot: &Bot, id: &FileId, path: &Path) -> Result<()> {
+    let file = bot.get_file(id.clone()).await?;
+    let mut dst = tokio::fs::File::create(path).await?;
+    bot.download_file(&file.path, &mut dst)
+        .await
+        .map_err(|e| anyhow!("Download failed: {}", e))
+}
+
+/// Free path in workspace/inbox/<date>/ (a time prefix avoids overwriting)
+fn inbox_path(brain: &Brain, file_name: &str) -> PathBuf {
+    let now = chrono::Local::now();
+    let dir = brain.config.project_root
+        .join("workspace")
+        .join("inbox")
+        .join(now.format("%Y-%m-%d").to_string());
+    let path = dir.join(file_name);
+    if path.exists() {
+        dir.join(format!("{}_{}", now.format("%H%M%S"), file_name))
+    } else {
+        path
+    }
+}
+
+/// Saved file plus the prompt to hand to brain.think()
+pub struct Ingested {
+    /// Path relative to the project root
+    pub location: String,
+    pub prompt: String,
+}
+
+pub async fn ingest(bot: &Bot, brain: &Brain, attachment: &Attachment, caption: Option<&str>) -> Result<Ingested> {
+    let path = inbox_path(brain, &attachment.file_name);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    if let Err(e) = download(bot, &attachment.id, &path).await {
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(e);
+    }
+    brain.memory.mark_as_internal_write(path.clone()).await;
+
+    let shown = path.strip_prefix(&brain.config.project_root).unwrap_or(&path).display().to_string();
+    let caption = caption.map(str::trim).filter(|c| !c.is_empty());
+
+    let prompt = if attachment.is_image {
+        let request = caption.unwrap_or("I sent you an image. Describe it briefly and note anything worth remembering.");
+        let description = match brain.describe_image(&path, "Describe this image in detail, including any visible text.").await {
+            Ok(Some(d)) => format!("\n\n<IMAGE_DESCRIPTION>\n{}\n</IMAGE_DESCRIPTION>", d),
+            Ok(None) => String::new(),
+            Err(e) => {
+                tracing::warn!("🖼️ [Telegram] Vision model failed: {}", e);
+                String::new()
+            }
+        };
+        format!("{}\n\n[image saved at {}]{}", request, shown, description)
+    } else if attachment.is_text() && attachment.size <= MAX_TEXT_BYTES {
+        let content = tokio::fs::read_to_string(&path).await
+            .map_err(|_| anyhow!("{} is not valid UTF-8 text", attachment.file_name))?;
+        let request = caption.unwrap_or("Summarize this document and remember anything worth keeping.");
+        format!(
+            "{}\n\n<DOCUMENT name=\"{}\" path=\"{}\">\n{}\n</DOCUMENT>",
+            request, attachment.file_name, shown, truncate_chars(&content, MAX_TEXT_CHARS)
+        )
+    } else {
+        let request = caption.unwrap_or("I sent you a file. Acknowledge it and tell me what you can do with it.");
+        format!("{}\n\n[file saved at {} ({})]", request, shown, humanize_bytes(attachment.size))
+    };
+
+    Ok(Ingested { location: shown, prompt })
+}
@@ -2,12 +2,18 @@
 //! Port of opensporejs/src/channels/telegram.js
 
 use teloxide::prelude::*;
-use teloxide::types::ParseMode;
+use teloxide::types::{KeyboardButton, KeyboardMarkup, ParseMode};
 use openspore_core::config::AppConfig;
 use openspore_brain::Brain;
 use tracing::info;
 use std::sync::Arc;
 
+pub mod approvals;
+pub mod attachments;
+pub mod markdown;
+pub mod reply_context;
+pub mod voice;
+
 #[derive(Clone)]
 pub struct TelegramChannel {
     token: String,
@@ -16,16 +22,12 @@ pub struct TelegramChannel {
 
 impl TelegramChannel {
     pub fn new() -> anyhow::Result<Self> {
-        let _config = AppConfig::load()?;
-        let token = std::env::var("TELEGRAM_BOT_TOKEN")
-            .map_err(|_| anyhow::anyhow!("TELEGRAM_BOT_TOKEN not set"))?;
+        let config = AppConfig::load()?;
+        let token = config.telegram_bot_token.clone()
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("TELEGRAM_BOT_TOKEN not set"))?;
 
-        let allowed_users = std::env::var("TELEGRAM_ALLOWED_USERS")
-            .unwrap_or_default()
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
+        let allowed_users = config.telegram_allowed_users();
 
         Ok(Self {
             token,
@@ -50,11 +52,22 @@ impl TelegramChannel {
 
         // Load brain in thread/arc to share
         let config = AppConfig::load()?;
-        let brain = Arc::new(Brain::new(config));
+        let brain = Arc::new(Brain::new(config).with_channel("telegram"));
+        brain.follow_config(AppConfig::watch());
 
         info!("✅ Telegram Gateway Active. Allowed Users: {:?}", allowed_users);
 
-        teloxide::repl(bot, move |bot: Bot, msg: Message| {
+        let on_callback = {
+            let allowed_users = allowed_users.clone();
+            let brain = brain.clone();
+            move |bot: Bot, q: CallbackQuery| {
+                let allowed_users = allowed_users.clone();
+                let brain = brain.clone();
+                async move { approvals::handle_callback(bot, q, &brain, &allowed_users).await }
+            }
+        };
+
+        let on_message = move |bot: Bot, msg: Message| {
             let allowed_users = allowed_users.clone();
             let brain = brain.clone();
             async move {
@@ -76,54 +89,118 @@ impl TelegramChannel {
                     // Spawn a task so thinking doesn't block the next message
                     tokio::spawn(async move {
                         // Think
-                        let response = brain.think(&text).await;
-
-                        // Split and send
-                        for chunk in split_message(&response, 4000) {
-                            // Try sending with MarkdownV2, fallback to plain text if it fails
-                            if let Err(_) = bot.send_message(msg.chat.id, chunk)
-                                .parse_mode(ParseMode::MarkdownV2)
-                                .await
-                            {
-                                let _ = bot.send_message(msg.chat.id, chunk).await;
+                        let prompt = reply_context::with_message_context(&brain, &msg, &text);
+                        let is_private = brain.is_private_turn(&prompt);
+                        let (mut response, suggestions) = approvals::think(&bot, msg.chat.id, &brain, &prompt).await;
+                        if is_private {
+                            response.push_str("\n\n🔒 not saved");
+                        }
+                        reply(&bot, msg.chat.id, &response, &suggestions).await;
+                    });
+                } else if let Some(file) = voice::VoiceFile::from_message(&msg) {
+                    info!("🎙️ [Telegram] Voice message from {}", user_id);
+                    let _ = bot.send_chat_action(msg.chat.id, teloxide::types::ChatAction::Typing).await;
+
+                    tokio::spawn(async move {
+                        let text = match voice::transcribe(&bot, &brain.config, &file).await {
+                            Ok(text) => text,
+                            Err(e) => {
+                                tracing::warn!("🎙️ [Telegram] Transcription failed: {}", e);
+                                let hint = if brain.config.transcriptions_url().is_none() {
+                                    "🎙️ Voice messages are not enabled. Set TRANSCRIPTION_MODEL (e.g. whisper-1) in .env.".to_string()
+                                } else {
+                                    format!("🎙️ Sorry, I couldn't transcribe that voice message ({}).", e)
+                                };
+                                let _ = bot.send_message(msg.chat.id, hint).await;
+                                return;
                             }
+                        };
+                        info!("📩 [Telegram] Transcribed from {}: {}", user_id, text);
+
+                        // The prefix lands in the journal and tells the model the words were spoken
+                        let prompt = format!("[voice] {}", reply_context::with_message_context(&brain, &msg, &text));
+                        let is_private = brain.is_private_turn(&prompt);
+                        let (mut response, suggestions) = approvals::think(&bot, msg.chat.id, &brain, &prompt).await;
+                        if is_private {
+                            response.push_str("\n\n🔒 not saved");
                         }
+                        reply(&bot, msg.chat.id, &response, &suggestions).await;
+                    });
+                } else if let Some(attachment) = attachments::Attachment::from_message(&msg) {
+                    info!("📎 [Telegram] File from {}: {}", user_id, attachment.file_name);
+                    if attachment.too_large() {
+                        let _ = bot.send_message(msg.chat.id, format!(
+                            "📎 Sorry, {} is too large ({}). I can only take files up to {}.",
+                            attachment.file_name,
+                            openspore_core::fmt_utils::humanize_bytes(attachment.size),
+                            openspore_core::fmt_utils::humanize_bytes(attachments::MAX_FILE_BYTES)
+                        )).await;
+                        return Ok(());
+                    }
+                    let _ = bot.send_chat_action(msg.chat.id, teloxide::types::ChatAction::Typing).await;
+
+                    tokio::spawn(async move {
+                        let ingested = match attachments::ingest(&bot, &brain, &attachment, msg.caption()).await {
+                            Ok(ingested) => ingested,
+                            Err(e) => {
+                                tracing::warn!("📎 [Telegram] Ingestion failed: {}", e);
+                                let _ = bot.send_message(msg.chat.id, format!("📎 Sorry, I couldn't save {} ({}).", attachment.file_name, e)).await;
+                                return;
+                            }
+                        };
+
+                        let prompt = reply_context::with_message_context(&brain, &msg, &ingested.prompt);
+                        let is_private = brain.is_private_turn(&prompt);
+                        let (mut response, suggestions) = approvals::think(&bot, msg.chat.id, &brain, &prompt).await;
+                        if is_private {
+                            response.push_str("\n\n🔒 not saved");
+                        }
+                        reply(&bot, msg.chat.id, &format!("📥 Saved to {}\n\n{}", ingested.location, response), &suggestions).await;
                     });
                 }
-                Ok(())
+                respond(())
             }
-        })
-        .await;
+        };
+
+        let handler = dptree::entry()
+            .branch(Update::filter_message().endpoint(on_message))
+            .branch(Update::filter_callback_query().endpoint(on_callback));
+        Dispatcher::builder(bot, handler)
+            .default_handler(|_| async {})
+            .enable_ctrlc_handler()
+            .build()
+            .dispatch()
+            .await;
 
         Ok(())
     }
 
     /// Stateless Send - Send a message without starting a listener (for cron/notifications)
     pub async fn send_stateless(text: &str, target_id: Option<&str>) -> anyhow::Result<()> {
-        let token = std::env::var("TELEGRAM_BOT_TOKEN")
-            .map_err(|_| anyhow::anyhow!("TELEGRAM_BOT_TOKEN not set"))?;
+        let config = AppConfig::current();
+        let token = config.telegram_bot_token.clone()
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("TELEGRAM_BOT_TOKEN not set"))?;
 
         let chat_id = if let Some(id) = target_id {
             id.to_string()
         } else {
-            std::env::var("TELEGRAM_ALLOWED_USERS")
-                .ok()
-                .and_then(|s| s.split(',').next().map(|id| id.trim().to_string()))
+            config.telegram_owner_chat()
                 .ok_or_else(|| anyhow::anyhow!("No target user ID provided and TELEGRAM_ALLOWED_USERS not set"))?
         };
 
         let bot = Bot::new(token);
         info!("📡 Telegram: Sending message to chat_id: {}", chat_id);
 
-        for chunk in split_message(text, 4000) {
-            match bot.send_message(chat_id.clone(), chunk)
+        for (chunk, converted) in markdown::markdown_v2_chunks(text, MESSAGE_LIMIT) {
+            match bot.send_message(chat_id.clone(), converted)
                 .parse_mode(ParseMode::MarkdownV2)
                 .await
             {
                 Ok(_) => info!("✅ Telegram: Message sent successfully."),
                 Err(e) => {
                     info!("⚠️ Telegram: MarkdownV2 failed, retrying with plain text. Error: {}", e);
-                    if let Err(e2) = bot.send_message(chat_id.clone(), chunk).await {
+                    if let Err(e2) = bot.send_message(chat_id.clone(), chunk.clone()).await {
                         info!("❌ Telegram: Final fallback failed. Error: {}", e2);
                         return Err(anyhow::anyhow!("Failed to send Telegram message: {}", e2));
                     } else {
@@ -137,14 +214,36 @@ impl TelegramChannel {
     }
 }
 
-fn split_message(text: &str, max_length: usize) -> Vec<&str> {
-    let mut chunks = Vec::new();
-    let mut current = text;
-    while current.len() > max_length {
-        let (chunk, rest) = current.split_at(max_length);
-        chunks.push(chunk);
-        current = rest;
+/// Telegram's cap on a message, in characters (after MarkdownV2 escaping)
+const MESSAGE_LIMIT: usize = 4096;
+
+/// One-tap reply keyboard with the follow-up suggestions (hidden once used)
+fn suggestion_keyboard(suggestions: &[String]) -> Option<KeyboardMarkup> {
+    (!suggestions.is_empty()).then(|| {
+        KeyboardMarkup::new(suggestions.iter().map(|s| vec![KeyboardButton::new(s.clone())]))
+            .resize_keyboard()
+            .one_time_keyboard()
+    })
+}
+
+/// Split and send as MarkdownV2, falling back to plain text if Telegram rejects a chunk.
+/// Suggestions ride on the last chunk as a reply keyboard.
+async fn reply(bot: &Bot, chat_id: ChatId, text: &str, suggestions: &[String]) {
+    let chunks = markdown::markdown_v2_chunks(text, MESSAGE_LIMIT);
+    let last = chunks.len().saturating_sub(1);
+    for (i, (chunk, converted)) in chunks.into_iter().enumerate() {
+        let keyboard = if i == last { suggestion_keyboard(suggestions) } else { None };
+
+        let mut request = bot.send_message(chat_id, converted).parse_mode(ParseMode::MarkdownV2);
+        if let Some(keyboard) = keyboard.clone() {
+            request = request.reply_markup(keyboard);
+        }
+        if request.await.is_err() {
+            let mut plain = bot.send_message(chat_id, chunk);
+            if let Some(keyboard) = keyboard {
+                plain = plain.reply_markup(keyboard);
+            }
+            let _ = plain.await;
+        }
     }
-    chunks.push(current);
-    chunks
 }
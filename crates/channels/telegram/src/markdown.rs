@@ -0,0 +1,496 @@
+//! Markdown -> Telegram MarkdownV2
+//!
+//! The brain answers in regular markdown. MarkdownV2 rejects any unescaped
+//! reserved character, so the text is converted rather than sent as is:
+//! bold, italics, strikethrough, inline code, fenced code blocks and links
+//! become entities, headers turn bold, bullets become "•", and everything
+//! else is escaped. Unmatched markers are kept as literal characters.
+
+use openspore_core::fmt_utils::truncate_bytes;
+
+/// Characters MarkdownV2 reserves outside entities
+const RESERVED: &str = "_*[]()~`>#+-=|{}.!\\";
+
+fn escape_text(text: &str, out: &mut String) {
+    for c in text.chars() {
+        if RESERVED.contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+/// Inside `code` and ```pre``` only ` and \ are escaped
+fn escape_code(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('`', "\\`")
+}
+
+/// Inside the (...) of a link only ) and \ are escaped
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "\\\\").replace(')', "\\)")
+}
+
+/// Backticks opening a fence ("```rust" -> 3), if the line is one
+fn fence_open(line: &str) -> Option<usize> {
+    let ticks = line.trim_start().chars().take_while(|c| *c == '`').count();
+    (ticks >= 3).then_some(ticks)
+}
+
+/// A closing fence is only backticks, at least as many as the opening one
+fn fence_closes(line: &str, ticks: usize) -> bool {
+    let t = line.trim();
+    t.len() >= ticks && t.chars().all(|c| c == '`')
+}
+
+/// Convert a markdown message to MarkdownV2
+pub fn to_markdown_v2(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + text.len() / 8);
+    // Backtick count of the open code block
+    let mut fence: Option<usize> = None;
+
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+
+        if let Some(ticks) = fence {
+            if fence_closes(line, ticks) {
+                out.push_str("```");
+                fence = None;
+            } else {
+                out.push_str(&escape_code(line));
+            }
+            continue;
+        }
+        if let Some(ticks) = fence_open(line) {
+            let lang: String = line.trim_start()[ticks..].trim().chars()
+                .filter(|c| c.is_alphanumeric() || *c == '+' || *c == '-')
+                .collect();
+            out.push_str("```");
+            out.push_str(&lang);
+            fence = Some(ticks);
+            continue;
+        }
+
+        convert_line(line, &mut out);
+    }
+
+    // Close a fence the model left open
+    if fence.is_some() {
+        out.push_str("\n```");
+    }
+    out
+}
+
+/// Block-level syntax of one line outside code blocks
+fn convert_line(line: &str, out: &mut String) {
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, body) = line.split_at(indent_len);
+
+    // "# Title" -> bold
+    let hashes = body.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && body[hashes..].starts_with(' ') {
+        let title = body[hashes..].trim().trim_end_matches('#').trim();
+        if !title.is_empty() {
+            let mut converted = String::new();
+            convert_inline(title, &mut converted);
+            out.push('*');
+            out.push_str(&without_bold(&converted));
+            out.push('*');
+            return;
+        }
+    }
+
+    // Horizontal rule
+    if body.len() >= 3 && (body.chars().all(|c| c == '-') || body.chars().all(|c| c == '*') || body.chars().all(|c| c == '_')) {
+        out.push_str("──────────");
+        return;
+    }
+
+    // Quotes keep Telegram's own blockquote syntax
+    if let Some(quote) = body.strip_prefix('>') {
+        out.push('>');
+        convert_inline(quote.strip_prefix(' ').unwrap_or(quote), out);
+        return;
+    }
+
+    out.push_str(indent);
+
+    // "- item" / "* item" / "+ item" -> "• item"
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = body.strip_prefix(bullet) {
+            out.push_str("• ");
+            convert_inline(item, out);
+            return;
+        }
+    }
+
+    convert_inline(body, out);
+}
+
+/// A header is bold as a whole, and bold cannot nest: drop the bold markers
+/// of converted text (escaped `*` and those inside code stay)
+fn without_bold(converted: &str) -> String {
+    let mut out = String::with_capacity(converted.len());
+    let mut in_code = false;
+    let mut chars = converted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                out.push(c);
+                out.extend(chars.next());
+            }
+            '`' => {
+                in_code = !in_code;
+                out.push(c);
+            }
+            '*' if !in_code => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Byte index of the closing `marker` in `s` (non-empty span, no space just inside it)
+fn find_closing(s: &str, marker: &str) -> Option<usize> {
+    if s.is_empty() || s.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut from = 0;
+    while let Some(pos) = s[from..].find(marker).map(|p| from + p) {
+        let before_space = s[..pos].ends_with(char::is_whitespace);
+        if pos > 0 && !before_space {
+            return Some(pos);
+        }
+        from = pos + marker.len();
+    }
+    None
+}
+
+/// Index of the `)` ending a link target, allowing balanced parens inside it
+fn closing_paren(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Inline entities within one line
+fn convert_inline(s: &str, out: &mut String) {
+    let mut i = 0;
+    while i < s.len() {
+        let rest = &s[i..];
+        let prev = s[..i].chars().next_back();
+
+        // `code`
+        if let Some(after) = rest.strip_prefix('`')
+            && let Some(end) = after.find('`')
+            && end > 0
+        {
+            out.push('`');
+            out.push_str(&escape_code(&after[..end]));
+            out.push('`');
+            i += end + 2;
+            continue;
+        }
+
+        // [text](url)
+        if let Some(after) = rest.strip_prefix('[')
+            && let Some(close) = after.find("](")
+            && let Some(url_end) = closing_paren(&after[close + 2..])
+        {
+            let label = &after[..close];
+            let url = &after[close + 2..close + 2 + url_end];
+            if !label.is_empty() && !url.is_empty() && !url.contains(char::is_whitespace) {
+                out.push('[');
+                convert_inline(label, out);
+                out.push_str("](");
+                out.push_str(&escape_url(url));
+                out.push(')');
+                i += 1 + close + 2 + url_end + 1;
+                continue;
+            }
+        }
+
+        // **bold** / __bold__ / ~~strike~~ / *italic* / _italic_
+        let spans: [(&str, char); 5] = [("**", '*'), ("__", '*'), ("~~", '~'), ("*", '_'), ("_", '_')];
+        let mut matched = false;
+        for (marker, entity) in spans {
+            let Some(after) = rest.strip_prefix(marker) else { continue };
+            // snake_case is not emphasis
+            if marker.starts_with('_') && prev.is_some_and(|c| c.is_alphanumeric()) {
+                continue;
+            }
+            let Some(end) = find_closing(after, marker) else { continue };
+            let next = after[end + marker.len()..].chars().next();
+            if marker.starts_with('_') && next.is_some_and(|c| c.is_alphanumeric()) {
+                continue;
+            }
+            out.push(entity);
+            convert_inline(&after[..end], out);
+            out.push(entity);
+            i += marker.len() + end + marker.len();
+            matched = true;
+            break;
+        }
+        if matched {
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        escape_text(&rest[..c.len_utf8()], out);
+        i += c.len_utf8();
+    }
+}
+
+/// Split on line boundaries into chunks of at most `max_length` bytes.
+/// A code block that fits in one message is moved whole to the next chunk
+/// rather than cut; a longer one is closed at the end of one chunk and
+/// reopened in the next. Lines longer than a chunk break at the last
+/// whitespace before the limit, or on a character boundary if there is none.
+pub fn split_message(text: &str, max_length: usize) -> Vec<String> {
+    // Leave room to close and reopen a fence around a piece
+    let room = max_length.saturating_sub(32).max(1);
+    let pieces: Vec<&str> = text.split_inclusive('\n')
+        .flat_map(|line| break_line(line, room))
+        .collect();
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    // Opening line and backtick count of the code block we are in, if any
+    let mut fence: Option<(String, usize)> = None;
+
+    for (i, piece) in pieces.iter().copied().enumerate() {
+        if fence.is_none()
+            && !current.is_empty()
+            && let Some(ticks) = fence_open(piece)
+        {
+            let block = fenced_block_len(&pieces[i..], ticks);
+            if block <= max_length && current.len() + block > max_length {
+                chunks.push(std::mem::take(&mut current));
+            }
+        }
+
+        let closing = fence.as_ref().map(|(_, ticks)| ticks + 1).unwrap_or(0);
+        if !current.is_empty() && current.len() + piece.len() + closing > max_length {
+            if let Some((_, ticks)) = &fence {
+                if !current.ends_with('\n') {
+                    current.push('\n');
+                }
+                current.push_str(&"`".repeat(*ticks));
+            }
+            chunks.push(std::mem::take(&mut current));
+            if let Some((open, _)) = &fence {
+                current.push_str(open);
+                current.push('\n');
+            }
+        }
+        current.push_str(piece);
+
+        fence = match fence {
+            Some((_, ticks)) if fence_closes(piece, ticks) => None,
+            Some(open) => Some(open),
+            None => fence_open(piece).map(|ticks| (piece.trim().to_string(), ticks)),
+        };
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Chunks of `text` as (markdown, MarkdownV2) pairs, each converted chunk
+/// at most `max_chars` characters. Escaping can nearly double a chunk full
+/// of reserved characters, so one that grows past the limit is split again
+/// at half the size.
+pub fn markdown_v2_chunks(text: &str, max_chars: usize) -> Vec<(String, String)> {
+    let mut chunks = Vec::new();
+    push_converted(text, max_chars, max_chars, &mut chunks);
+    chunks
+}
+
+fn push_converted(text: &str, size: usize, max_chars: usize, out: &mut Vec<(String, String)>) {
+    for chunk in split_message(text, size) {
+        let converted = to_markdown_v2(&chunk);
+        if converted.chars().count() <= max_chars || size <= 64 {
+            out.push((chunk, converted));
+        } else {
+            push_converted(&chunk, size / 2, max_chars, out);
+        }
+    }
+}
+
+/// Cut a line into pieces of at most `room` bytes, after the last whitespace where possible
+fn break_line(line: &str, room: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = line;
+    while rest.len() > room {
+        let head = truncate_bytes(rest, room);
+        let mut cut = head.char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map(|(pos, c)| pos + c.len_utf8())
+            .unwrap_or(head.len());
+        if cut == 0 {
+            cut = rest.ceil_char_boundary(1);
+        }
+        let (piece, tail) = rest.split_at(cut);
+        parts.push(piece);
+        rest = tail;
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Bytes from an opening fence through its closing one (or to the end, plus the close)
+fn fenced_block_len(pieces: &[&str], ticks: usize) -> usize {
+    let mut len = pieces[0].len();
+    for piece in &pieces[1..] {
+        len += piece.len();
+        if fence_closes(piece, ticks) {
+            return len;
+        }
+    }
+    len + ticks + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_fences_stay_code_until_the_outer_one_closes() {
+        let text = "Example:\n````md\n```rust\nlet x = a_b * 2;\n```\n````\nDone.";
+        assert_eq!(
+            to_markdown_v2(text),
+            "Example:\n```md\n\\`\\`\\`rust\nlet x = a_b * 2;\n\\`\\`\\`\n```\nDone\\."
+        );
+    }
+
+    #[test]
+    fn an_open_fence_is_closed() {
+        assert_eq!(to_markdown_v2("```python\nprint('hi')"), "```python\nprint('hi')\n```");
+        // Language tags keep only what Telegram accepts
+        assert_eq!(to_markdown_v2("```c++ {.numberLines}\nx\n```"), "```c++numberLines\nx\n```");
+    }
+
+    #[test]
+    fn underscores_in_urls_are_not_emphasis() {
+        assert_eq!(
+            to_markdown_v2("[docs](https://ex.com/a_b_c?q=1_2)"),
+            "[docs](https://ex.com/a_b_c?q=1_2)"
+        );
+        assert_eq!(
+            to_markdown_v2("[Rust](https://en.wikipedia.org/wiki/Rust_(programming_language))"),
+            "[Rust](https://en.wikipedia.org/wiki/Rust_(programming_language\\))"
+        );
+        assert_eq!(
+            to_markdown_v2("see https://ex.com/some_path_here."),
+            "see https://ex\\.com/some\\_path\\_here\\."
+        );
+        assert_eq!(to_markdown_v2("_really_ and snake_case_name"), "_really_ and snake\\_case\\_name");
+    }
+
+    #[test]
+    fn emoji_pass_through_entities_untouched() {
+        assert_eq!(to_markdown_v2("# 🚀 Launch **plan**"), "*🚀 Launch plan*");
+        assert_eq!(to_markdown_v2("## **Step 2:** run `a*b` 2*3"), "*Step 2: run `a*b` 2\\*3*");
+        assert_eq!(to_markdown_v2("**🔥 Hot** take 👍🏽!"), "*🔥 Hot* take 👍🏽\\!");
+        assert_eq!(to_markdown_v2("- ✅ done_item\n> 👨‍👩‍👧 family (v2)"), "• ✅ done\\_item\n>👨‍👩‍👧 family \\(v2\\)");
+        assert_eq!(to_markdown_v2("`🦀 {x}` ~~🐛~~"), "`🦀 {x}` ~🐛~");
+    }
+
+    #[test]
+    fn unmatched_markers_are_literal() {
+        assert_eq!(to_markdown_v2("2 * 3 = 6 and a lone ` tick"), "2 \\* 3 \\= 6 and a lone \\` tick");
+        assert_eq!(to_markdown_v2("[not a link] (x)"), "\\[not a link\\] \\(x\\)");
+    }
+
+    const MAX: usize = 3500;
+
+    /// Fence lines in a chunk (an even count means every block is closed)
+    fn fences(chunk: &str) -> usize {
+        chunk.lines().filter(|l| l.trim_start().starts_with("```")).count()
+    }
+
+    fn prose(paragraphs: usize) -> String {
+        (0..paragraphs)
+            .map(|i| format!("Paragraph {} says something about caching, retries and the scheduler. ", i).repeat(6))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    #[test]
+    fn ten_thousand_emoji_split_on_character_boundaries() {
+        let text = "🔥".repeat(10_000);
+        let chunks = split_message(&text, MAX);
+        assert!(chunks.len() >= 12);
+        assert!(chunks.iter().all(|c| c.len() <= MAX && !c.is_empty()));
+        assert!(chunks.iter().all(|c| to_markdown_v2(c).chars().count() <= 4096));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn dense_punctuation_stays_under_the_limit_once_escaped() {
+        let text = "v1.2-rc_3 (see a.b-c_d). ".repeat(800) + "\n```\nfn main() {}\n```\n" + &"x.y_z!".repeat(700);
+        assert!(to_markdown_v2(&split_message(&text, 4096)[0]).chars().count() > 4096, "the plain split would overflow");
+
+        let chunks = markdown_v2_chunks(&text, 4096);
+        assert!(chunks.len() > 5);
+        assert!(chunks.iter().all(|(_, v2)| v2.chars().count() <= 4096 && !v2.is_empty()));
+        assert!(chunks.iter().all(|(source, v2)| to_markdown_v2(source) == *v2));
+        assert_eq!(chunks.iter().map(|(source, _)| source.as_str()).collect::<String>(), text);
+
+        let short = markdown_v2_chunks("Done.", 4096);
+        assert_eq!(short, vec![("Done.".to_string(), "Done\\.".to_string())]);
+    }
+
+    #[test]
+    fn prose_breaks_after_whitespace() {
+        let text = prose(40);
+        let chunks = split_message(&text, MAX);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), text);
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.len() <= MAX);
+            assert!(chunk.ends_with(char::is_whitespace), "{:?}", &chunk[chunk.len() - 20..]);
+        }
+    }
+
+    #[test]
+    fn a_long_code_block_is_closed_and_reopened() {
+        let code: String = (0..300).map(|i| format!("    let value_{:03} = compute({});\n", i, i)).collect();
+        let text = format!("{}\n\n```rust\n{}```\n\n{}", prose(3), code, prose(3));
+        let chunks = split_message(&text, MAX);
+        assert!(chunks.len() >= 4);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert!(chunk.len() <= MAX, "chunk {} is {} bytes", i, chunk.len());
+            assert_eq!(fences(chunk) % 2, 0, "chunk {} leaves a block open", i);
+        }
+        assert!(chunks.iter().filter(|c| c.starts_with("```rust\n    let value_")).count() >= 2, "the block is reopened");
+        // Every line arrives once, in order
+        let joined = chunks.concat();
+        let mut from = 0;
+        for line in code.lines() {
+            let at = joined[from..].find(line).unwrap_or_else(|| panic!("{} missing", line));
+            from += at + line.len();
+        }
+        assert_eq!(joined.matches("let value_").count(), 300);
+    }
+
+    #[test]
+    fn a_short_code_block_moves_whole_to_the_next_chunk() {
+        let block = format!("```sh\n{}```\n", "echo step\n".repeat(40));
+        let text = format!("{}\n{}", "x".repeat(MAX - 100), block);
+        let chunks = split_message(&text, MAX);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1], block);
+        assert_eq!(fences(&chunks[0]), 0);
+    }
+}
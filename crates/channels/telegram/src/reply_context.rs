@@ -0,0 +1,175 @@
+//! Reply and forward context
+//!
+//! Replying "expand point 2" to an earlier message only makes sense with that
+//! message in view, so the quoted text (and, for replies to the bot, the whole
+//! matching exchange) is placed ahead of the user's text.
+
+use openspore_brain::Brain;
+use openspore_brain::privacy::PRIVATE_MARKER;
+use openspore_core::fmt_utils::truncate_chars;
+use teloxide::types::{Message, MessageOrigin};
+
+/// Characters of a quoted or forwarded message kept in the prompt
+const MAX_QUOTE_CHARS: usize = 1500;
+/// Characters of a pinned exchange kept in the prompt
+const MAX_EXCHANGE_CHARS: usize = 4000;
+
+fn message_text(msg: &Message) -> Option<&str> {
+    msg.text().or(msg.caption()).filter(|t| !t.trim().is_empty())
+}
+
+fn origin_name(origin: &MessageOrigin) -> String {
+    match origin {
+        MessageOrigin::User { sender_user, .. } => sender_user.full_name(),
+        MessageOrigin::HiddenUser { sender_user_name, .. } => sender_user_name.clone(),
+        MessageOrigin::Chat { sender_chat, .. } => sender_chat.title().unwrap_or("a chat").to_string(),
+        MessageOrigin::Channel { chat, .. } => chat.title().unwrap_or("a channel").to_string(),
+    }
+}
+
+/// `<REPLIED_TO>` / `<PINNED_EXCHANGE>` / `<FORWARDED>` blocks for this message
+fn context_blocks(brain: &Brain, msg: &Message) -> Vec<String> {
+    let mut blocks = Vec::new();
+
+    if let Some(origin) = msg.forward_origin() {
+        blocks.push(format!(
+            "<FORWARDED from=\"{}\" date=\"{}\">\nThe user forwarded this message; its text follows as the user message.\n</FORWARDED>",
+            origin_name(origin),
+            origin.date().format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+
+    if let Some(replied) = msg.reply_to_message()
+        && let Some(quoted) = message_text(replied)
+    {
+        let from_bot = replied.from.as_ref().is_some_and(|u| u.is_bot);
+        let author = match (from_bot, replied.from.as_ref()) {
+            (true, _) => "OpenSpore".to_string(),
+            (false, Some(user)) => user.full_name(),
+            (false, None) => "unknown".to_string(),
+        };
+        blocks.push(format!(
+            "<REPLIED_TO author=\"{}\">\n{}\n</REPLIED_TO>",
+            author,
+            truncate_chars(quoted, MAX_QUOTE_CHARS)
+        ));
+
+        if from_bot && let Some(exchange) = brain.context_manager.find_exchange(quoted) {
+            // Skip the frontmatter and heading
+            let body = exchange.content.find("**User**").map(|i| &exchange.content[i..]).unwrap_or(&exchange.content);
+            blocks.push(format!(
+                "<PINNED_EXCHANGE file=\"{}\">\n{}\n</PINNED_EXCHANGE>",
+                exchange.filename,
+                truncate_chars(body.trim(), MAX_EXCHANGE_CHARS)
+            ));
+        }
+    }
+
+    blocks
+}
+
+/// Prepend reply/forward context to `text`. A leading `!private` marker stays
+/// in front so the turn is still recognized as private; commands are untouched.
+pub fn with_message_context(brain: &Brain, msg: &Message, text: &str) -> String {
+    if text.trim_start().starts_with('/') {
+        return text.to_string();
+    }
+    let blocks = context_blocks(brain, msg);
+    if blocks.is_empty() {
+        return text.to_string();
+    }
+
+    let (marker, rest) = match text.trim_start().strip_prefix(PRIVATE_MARKER) {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => (format!("{} ", PRIVATE_MARKER), rest.trim_start()),
+        _ => (String::new(), text),
+    };
+    format!("{}{}\n\n{}", marker, blocks.join("\n\n"), rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use serde_json::json;
+
+    const BOT_ANSWER: &str = "Three options:\n1. SQLite\n2. Redis\n3. A flat file";
+
+    fn user(id: u64, name: &str, is_bot: bool) -> serde_json::Value {
+        json!({ "id": id, "is_bot": is_bot, "first_name": name })
+    }
+
+    /// A private-chat message from Ana, as the Bot API would send it
+    fn message(text: &str, extra: serde_json::Value) -> Message {
+        let mut msg = json!({
+            "message_id": 10,
+            "date": 1_760_600_000,
+            "chat": { "id": 7, "type": "private", "first_name": "Ana" },
+            "from": user(7, "Ana", false),
+            "text": text,
+        });
+        msg.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(msg).unwrap()
+    }
+
+    fn reply_to(text: &str, from: serde_json::Value) -> serde_json::Value {
+        json!({ "reply_to_message": {
+            "message_id": 9,
+            "date": 1_760_599_000,
+            "chat": { "id": 7, "type": "private", "first_name": "Ana" },
+            "from": from,
+            "text": text,
+        } })
+    }
+
+    async fn brain(root: &std::path::Path) -> Brain {
+        let brain = Brain::new(AppConfig { project_root: root.to_path_buf(), ..Default::default() });
+        brain.memory.ensure_structure().await.unwrap();
+        brain
+    }
+
+    #[tokio::test]
+    async fn reply_to_the_bot_pins_the_matching_exchange() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain(dir.path()).await;
+        let exchange = format!("**User**: where should the cache live?\n\n**Assistant**: {}", BOT_ANSWER);
+        brain.context_manager.save_interaction(&exchange, vec![], Some("exchange"), &[]).await.unwrap();
+
+        // Telegram strips the markdown of what the bot sent
+        let msg = message("expand point 2", reply_to("Three options: 1. SQLite 2. Redis 3. A flat file", user(99, "OpenSpore", true)));
+        let text = with_message_context(&brain, &msg, "expand point 2");
+
+        assert!(text.starts_with("<REPLIED_TO author=\"OpenSpore\">\nThree options"));
+        let pinned = text.find("<PINNED_EXCHANGE file=\"exchange").expect("exchange pinned");
+        assert!(text[pinned..].contains("**User**: where should the cache live?"));
+        assert!(!text[pinned..].contains("---"), "frontmatter is skipped");
+        assert!(text.ends_with("</PINNED_EXCHANGE>\n\nexpand point 2"));
+    }
+
+    #[tokio::test]
+    async fn reply_to_a_person_quotes_without_an_exchange() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain(dir.path()).await;
+        let msg = message("what do you think?", reply_to(BOT_ANSWER, user(8, "Bo", false)));
+        let text = with_message_context(&brain, &msg, "what do you think?");
+        assert!(text.starts_with("<REPLIED_TO author=\"Bo\">"));
+        assert!(!text.contains("PINNED_EXCHANGE"));
+    }
+
+    #[tokio::test]
+    async fn forwards_keep_the_private_marker_first_and_commands_stay_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let brain = brain(dir.path()).await;
+        let forwarded = json!({ "forward_origin": { "type": "hidden_user", "date": 1_760_000_000, "sender_user_name": "Someone" } });
+
+        let msg = message("!private the forwarded text", forwarded.clone());
+        let text = with_message_context(&brain, &msg, "!private the forwarded text");
+        assert!(text.starts_with(&format!("{} <FORWARDED from=\"Someone\" date=\"2025-10-09 08:53 UTC\">", PRIVATE_MARKER)));
+        assert!(text.ends_with("</FORWARDED>\n\nthe forwarded text"));
+
+        let msg = message("/status", forwarded);
+        assert_eq!(with_message_context(&brain, &msg, "/status"), "/status");
+
+        let plain = message("hello", json!({}));
+        assert_eq!(with_message_context(&brain, &plain, "hello"), "hello");
+    }
+}
@@ -0,0 +1,87 @@
+//! Voice message transcription (Whisper-compatible /audio/transcriptions)
+
+use anyhow::{anyhow, Result};
+use openspore_core::config::AppConfig;
+use teloxide::prelude::*;
+use teloxide::types::FileId;
+
+/// A voice note or audio file attached to a message
+pub struct VoiceFile {
+    pub id: FileId,
+    pub file_name: String,
+    pub mime_type: String,
+}
+
+impl VoiceFile {
+    pub fn from_message(msg: &Message) -> Option<Self> {
+        if let Some(voice) = msg.voice() {
+            return Some(Self {
+                id: voice.file.id.clone(),
+                file_name: format!("voice_{}.ogg", voice.file.unique_id),
+                mime_type: voice.mime_type.as_ref().map(|m| m.to_string()).unwrap_or_else(|| "audio/ogg".to_string()),
+            });
+        }
+        let audio = msg.audio()?;
+        Some(Self {
+            id: audio.file.id.clone(),
+            file_name: audio.file_name.clone().unwrap_or_else(|| format!("audio_{}.mp3", audio.file.unique_id)),
+            mime_type: audio.mime_type.as_ref().map(|m| m.to_string()).unwrap_or_else(|| "audio/mpeg".to_string()),
+        })
+    }
+}
+
+/// Download the file to workspace/tmp/, transcribe it and remove the temp file
+pub async fn transcribe(bot: &Bot, config: &AppConfig, voice: &VoiceFile) -> Result<String> {
+    let url = config.transcriptions_url()
+        .ok_or_else(|| anyhow!("TRANSCRIPTION_MODEL is not set"))?;
+
+    let tmp_dir = config.project_root.join("workspace").join("tmp");
+    tokio::fs::create_dir_all(&tmp_dir).await?;
+    let path = tmp_dir.join(&voice.file_name);
+
+    let result = async {
+        crate::attachments::download(bot, &voice.id, &path).await?;
+        post_audio(config, &url, &path, voice).await
+    }.await;
+    let _ = tokio::fs::remove_file(&path).await;
+
+    let text = result?.trim().to_string();
+    if text.is_empty() {
+        return Err(anyhow!("Transcription was empty"));
+    }
+    Ok(text)
+}
+
+async fn post_audio(config: &AppConfig, url: &str, path: &std::path::Path, voice: &VoiceFile) -> Result<String> {
+    let bytes = tokio::fs::read(path).await?;
+    let part = reqwest::multipart::Part::bytes(bytes)
+        .file_name(voice.file_name.clone())
+        .mime_str(&voice.mime_type)?;
+    let form = reqwest::multipart::Form::new()
+        .text("model", config.transcription_model.clone().unwrap_or_default())
+        .text("response_format", "json")
+        .part("file", part);
+
+    let api_key = config.transcription_api_key.clone()
+        .filter(|k| !k.is_empty())
+        .unwrap_or_else(|| config.openrouter_api_key.clone());
+
+    let client = reqwest::Client::builder().timeout(config.llm_timeout()).build()?;
+    let mut req = client.post(url).multipart(form);
+    if !api_key.is_empty() {
+        req = req.header("Authorization", format!("Bearer {}", api_key));
+    }
+
+    let res = req.send().await?;
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+        return Err(anyhow!("Transcription API error {}: {}", status, openspore_core::fmt_utils::truncate_chars(&body, 200)));
+    }
+
+    let json: serde_json::Value = res.json().await?;
+    json.get("text")
+        .and_then(|t| t.as_str())
+        .map(String::from)
+        .ok_or_else(|| anyhow!("Transcription response has no text"))
+}
@@ -0,0 +1,92 @@
+//! `openspore audit verify|tail`: the hash-chained log of privileged tool calls
+//!
+//! `verify` walks workspace/audit/audit.jsonl and exits non-zero when a line
+//! was edited, removed or the log was truncated. `tail` prints the newest
+//! entries, filtered by skill, channel, outcome and day.
+
+use openspore_core::config::AppConfig;
+use openspore_memory::{AuditEntry, AuditOutcome, MemorySystem};
+
+/// Filters of `openspore audit tail`
+pub struct TailFilter {
+    pub skill: Option<String>,
+    pub channel: Option<String>,
+    pub outcome: Option<String>,
+    pub since: Option<chrono::DateTime<chrono::Local>>,
+    pub limit: usize,
+}
+
+fn memory(config: AppConfig) -> MemorySystem {
+    let state = openspore_core::state::AppState::new(config);
+    MemorySystem::new(&state)
+}
+
+/// Exit code: 0 intact, 1 broken chain, 2 unreadable log
+pub fn verify(config: AppConfig) -> i32 {
+    let memory = memory(config);
+    match memory.verify_audit() {
+        Ok(report) if report.is_intact() => {
+            println!("✅ Audit log intact: {} entr{} chained", report.entries, if report.entries == 1 { "y" } else { "ies" });
+            0
+        }
+        Ok(report) => {
+            println!("❌ Audit log tampered with ({} entries, {} problem(s)):\n", report.entries, report.problems.len());
+            for problem in &report.problems {
+                println!("  - {}", problem);
+            }
+            1
+        }
+        Err(e) => {
+            println!("❌ {}", e);
+            2
+        }
+    }
+}
+
+pub fn tail(config: AppConfig, filter: TailFilter) {
+    let outcome = match filter.outcome.as_deref().map(parse_outcome).transpose() {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
+    let matches = |entry: &AuditEntry| {
+        filter.skill.as_deref().is_none_or(|s| entry.skill.eq_ignore_ascii_case(s))
+            && filter.channel.as_deref().is_none_or(|c| entry.channel.eq_ignore_ascii_case(c))
+            && outcome.is_none_or(|o| entry.outcome == o)
+            && filter.since.is_none_or(|since| entry.timestamp >= since)
+    };
+
+    let entries: Vec<AuditEntry> = memory(config).audit_entries().into_iter().filter(matches).collect();
+    let shown = &entries[entries.len().saturating_sub(filter.limit)..];
+    println!("📜 [Audit Log] {} of {} matching entr{}\n", shown.len(), entries.len(), if entries.len() == 1 { "y" } else { "ies" });
+    for entry in shown {
+        println!(
+            "  {:>5} | {} | {:9} | {:13} | {} | {}",
+            entry.seq,
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            entry.channel,
+            entry.skill,
+            outcome_label(entry.outcome),
+            if entry.args_preview.is_empty() { format!("(private, sha256 {})", &entry.args_digest[..12.min(entry.args_digest.len())]) } else { entry.args_preview.clone() }
+        );
+    }
+}
+
+fn parse_outcome(s: &str) -> Result<AuditOutcome, String> {
+    match s.trim().to_lowercase().as_str() {
+        "ok" => Ok(AuditOutcome::Ok),
+        "error" => Ok(AuditOutcome::Error),
+        "denied" => Ok(AuditOutcome::Denied),
+        other => Err(format!("Unknown outcome \"{}\" (expected ok, error or denied)", other)),
+    }
+}
+
+fn outcome_label(outcome: AuditOutcome) -> &'static str {
+    match outcome {
+        AuditOutcome::Ok => "✅ ok    ",
+        AuditOutcome::Error => "❌ error ",
+        AuditOutcome::Denied => "🚫 denied",
+    }
+}
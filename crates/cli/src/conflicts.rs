@@ -0,0 +1,99 @@
+//! `openspore memory merge-driver|conflicts|resolve`: sync conflicts of memories
+//!
+//! git runs `memory merge-driver %O %A %B %P` for workspace markdown (see the
+//! memory crate's conflicts module). Files it leaves conflicted are listed by
+//! `conflicts` and settled with `resolve <path> --ours|--theirs|--llm`.
+
+use openspore_brain::Brain;
+use openspore_core::config::AppConfig;
+use openspore_core::event_bus::{post_to_inbox, SystemEvent};
+use openspore_memory::{MemorySystem, Resolution};
+use std::io::Write;
+use std::path::Path;
+
+fn memory(config: AppConfig) -> MemorySystem {
+    let state = openspore_core::state::AppState::new(config);
+    MemorySystem::new(&state)
+}
+
+/// Called by git. Exit code 0 tells git the file is merged (conflicted ones
+/// included, they hold both versions); anything else falls back to git's markers.
+pub fn merge_driver(config: AppConfig, args: &[String]) -> i32 {
+    let [base, ours, theirs, path] = args else {
+        eprintln!("Usage: openspore memory merge-driver <base> <ours> <theirs> <path>");
+        return 2;
+    };
+    let memory = memory(config);
+    match memory.run_merge_driver(Path::new(base), Path::new(ours), Path::new(theirs), path) {
+        Ok(false) => 0,
+        Ok(true) => {
+            eprintln!("⚠️  Sync conflict in {}: both versions kept, resolve with 'openspore memory resolve {} --ours|--theirs|--llm'", path, path);
+            let event = SystemEvent::MemoryConflict { path: path.clone() };
+            if let Err(e) = post_to_inbox(&memory.memory_root, &event) {
+                eprintln!("❌ Failed to write inbox: {}", e);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ Memory merge of {} failed: {}", path, e);
+            1
+        }
+    }
+}
+
+pub fn list(config: AppConfig) {
+    let memory = memory(config);
+    let conflicts = memory.conflicts();
+    println!("⚠️  [Sync Conflicts] {} file(s)\n", conflicts.len());
+    for path in &conflicts {
+        println!("  {}", path.strip_prefix(&memory.memory_root).unwrap_or(path).display());
+    }
+    if !conflicts.is_empty() {
+        println!("\nResolve with: openspore memory resolve <path> --ours|--theirs|--llm");
+    }
+}
+
+pub async fn resolve(config: AppConfig, path: Option<&str>, ours: bool, theirs: bool, llm: bool) {
+    let (Some(path), 1) = (path, [ours, theirs, llm].iter().filter(|f| **f).count()) else {
+        println!("Usage: openspore memory resolve <path> --ours|--theirs|--llm");
+        return;
+    };
+    let memory = memory(config.clone());
+    let path = memory.workspace_path(path);
+    let versions = match memory.read_conflict(&path) {
+        Ok(versions) => versions,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
+
+    let resolution = if ours {
+        Resolution::Ours
+    } else if theirs {
+        Resolution::Theirs
+    } else {
+        println!("🧠 Asking the brain to merge both versions...");
+        let merged = match Brain::new(config).merge_conflict(&versions).await {
+            Ok(merged) => merged,
+            Err(e) => {
+                println!("❌ Merge failed: {}", e);
+                return;
+            }
+        };
+        println!("\n--- Changes to the version on this device ---\n{}", diffy::create_patch(&versions.ours, &merged));
+        print!("Write the merged version? [y/N] ");
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err() || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Aborted, the conflict is unchanged.");
+            return;
+        }
+        Resolution::Merged(merged)
+    };
+
+    match memory.resolve_conflict(&path, resolution).await {
+        Ok(()) => println!("✅ Resolved {}", path.display()),
+        Err(e) => println!("❌ {}", e),
+    }
+}
@@ -0,0 +1,79 @@
+//! `openspore failures list|show`: post-mortems of failed cycles
+//!
+//! `list` prints the kept bundles of workspace/debug/failures, newest first,
+//! and the failures that keep recurring. `show <cycle>` prints one bundle: the
+//! error, the environment of the run and its tool calls (a prefix of the cycle
+//! id is enough; without one, the newest bundle).
+
+use openspore_core::config::AppConfig;
+use openspore_core::fmt_utils::preview;
+use openspore_memory::{failure_patterns, MemorySystem};
+
+fn memory(config: AppConfig) -> MemorySystem {
+    let state = openspore_core::state::AppState::new(config);
+    MemorySystem::new(&state)
+}
+
+pub fn list(config: AppConfig, limit: usize) {
+    let bundles = memory(config).list_failures();
+    if bundles.is_empty() {
+        println!("🩺 No failure post-mortems.");
+        return;
+    }
+    println!("🩺 [Failures] {} of {} post-mortem(s)\n", bundles.len().min(limit), bundles.len());
+    for bundle in bundles.iter().take(limit) {
+        let record = &bundle.record;
+        println!(
+            "  {:18} | {} | {:15} | {:9} | {}",
+            record.cycle,
+            record.time.format("%Y-%m-%d %H:%M:%S"),
+            record.kind.as_str(),
+            record.channel,
+            preview(&record.error, 80)
+        );
+    }
+
+    let patterns = failure_patterns(&bundles);
+    if !patterns.is_empty() {
+        println!("\n🔁 Recurring:");
+        for (signature, count) in patterns {
+            println!("  {:>3}x | {}", count, signature);
+        }
+    }
+}
+
+pub fn show(config: AppConfig, id: Option<&str>) {
+    let bundle = match memory(config).find_failure(id.unwrap_or("last")) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
+    let record = &bundle.record;
+    println!("🩺 [Post-mortem] cycle {} ({})", record.cycle, record.kind.as_str());
+    println!("   {}\n", bundle.path.display());
+    println!("  Time:    {}", record.time.format("%Y-%m-%d %H:%M:%S"));
+    println!("  Channel: {}", record.channel);
+    println!("  Prompt:  {}", preview(&record.prompt, 200));
+    println!("  Error:   {}", record.error);
+
+    println!("\n  Environment:");
+    for (key, value) in &record.environment {
+        println!("    {:15} {}", key, value);
+    }
+
+    let tools = bundle.tools();
+    println!("\n  Tool calls: {} ({} failed)", tools.len(), tools.iter().filter(|t| !t.ok).count());
+    for tool in &tools {
+        println!(
+            "    [{:>2}] {} {}: {} -> {}",
+            tool.round,
+            if tool.ok { "✅" } else { "❌" },
+            tool.name,
+            preview(&tool.arg, 60),
+            preview(&tool.output, 100)
+        );
+    }
+    println!("\n  Context and message history: context.md, messages.json");
+}
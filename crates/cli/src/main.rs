@@ -1,9 +1,18 @@
 use clap::{Parser, Subcommand};
 use tracing::error;
 use openspore_core::config::AppConfig;
-use openspore_brain::Brain;
+use openspore_brain::{pending_work, Brain};
 use std::process::Command;
 
+mod audit;
+mod conflicts;
+mod failures;
+mod pipeline;
+mod proposal;
+mod skills;
+mod stop;
+mod storage;
+
 #[derive(Parser, Debug)]
 #[command(author, version, about = "OpenSpore - Autonomous AI Agent", long_about = None)]
 struct Args {
@@ -15,32 +24,220 @@ struct Args {
 enum Commands {
     /// Start the OpenSpore TUI Agent
     Start,
-    /// Stop all running OpenSpore instances
-    Stop,
+    /// Stop running OpenSpore instances, sub-spores and the browser they launched
+    Stop {
+        /// Kill every process matching "openspore" and all Chrome/Chromium/Brave windows
+        #[arg(long)]
+        all: bool,
+    },
     /// Run system diagnostic and self-repair
-    Doctor,
+    Doctor {
+        /// Only report the issues, repairing nothing; exits 1 on critical issues
+        #[arg(long, conflicts_with_all = ["fix", "dry_run"])]
+        check: bool,
+        /// Apply the prescriptions (the default, except with --json)
+        #[arg(long)]
+        fix: bool,
+        /// Print the issues (and, with --fix, the fixes applied) as JSON; exits 1 on critical or warning issues
+        #[arg(long)]
+        json: bool,
+        /// Report what the prescriptions would change without applying them
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the checks that need the network (LLM endpoint, key and models)
+        #[arg(long)]
+        offline: bool,
+    },
     /// Manage system cron jobs (list/install)
     Cron {
         /// Subcommand: list or install
         #[arg(index = 1, default_value = "list")]
         action: String,
     },
-    /// Manually run a workspace job
+    /// Manually run a workspace job (crontab.json), or the built-in "consolidate"
     Job {
-        /// Job name (e.g., heartbeat, cleanup)
+        /// Job name (e.g., heartbeat, cleanup, consolidate)
         #[arg(index = 1)]
         name: String,
+        /// consolidate: list the exchanges that would be digested, without calling the model or deleting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// consolidate: most exchange files to process (default CONSOLIDATE_MAX_FILES)
+        #[arg(long)]
+        limit: Option<usize>,
+        /// consolidate: only exchanges older than this many days (default CONSOLIDATE_AFTER_DAYS)
+        #[arg(long)]
+        days: Option<u32>,
     },
     /// Trigger the autonomy anticipation engine
     Auto,
     /// View recent context logs
     Logs,
-    /// List active sub-spores (swarm activity)
-    Swarm,
+    /// Swarm load (running/queued against OPENSPORE_SWARM_MAX) and active sub-spores
+    Swarm {
+        /// Drop exited spores from the registry and kill those running over an hour
+        #[arg(long)]
+        reap: bool,
+    },
     /// Run system heartbeat and status check
     Heartbeat,
     /// Manually trigger daily journal synthesis
     Journal,
+    /// Workspace memory maintenance (index/reindex/audit/review/deleted/conflicts/resolve)
+    Memory {
+        /// Subcommand: index (INDEX.md), reindex (search index), audit (exchanges by model), review (injected but never cited), deleted (tombstones), conflicts or resolve (sync conflicts)
+        #[arg(index = 1, default_value = "index")]
+        action: String,
+        /// resolve: conflicted file (relative to the workspace); merge-driver: git's %O %A %B %P
+        #[arg(index = 2)]
+        paths: Vec<String>,
+        /// Rescan every category, even unchanged ones
+        #[arg(long)]
+        force: bool,
+        /// audit: model slug to match (substring, case-insensitive)
+        #[arg(long)]
+        model: Option<String>,
+        /// audit: first day to include (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// audit: last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+        /// deleted: bring back a deleted file (path relative to the workspace)
+        #[arg(long)]
+        restore: Option<String>,
+        /// resolve: keep the version on this device
+        #[arg(long)]
+        ours: bool,
+        /// resolve: keep the incoming version
+        #[arg(long)]
+        theirs: bool,
+        /// resolve: let the brain merge both, shown as a diff for approval
+        #[arg(long)]
+        llm: bool,
+    },
+    /// Manage named sessions (new/switch/list)
+    Session {
+        /// Subcommand: new, switch or list
+        #[arg(index = 1, default_value = "list")]
+        action: String,
+        /// Session name
+        #[arg(index = 2)]
+        name: Option<String>,
+    },
+    /// Show token usage and estimated cost (today / this month)
+    Usage,
+    /// Health snapshot: models, skills, memories, last scheduled runs, swarm, budgets, last error
+    Status {
+        /// Print the snapshot as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Log of privileged tool calls (verify/tail)
+    Audit {
+        /// Subcommand: verify (check the hash chain) or tail (recent entries)
+        #[arg(index = 1, default_value = "tail")]
+        action: String,
+        /// tail: only this skill (e.g. exec)
+        #[arg(long)]
+        skill: Option<String>,
+        /// tail: only this channel (tui, telegram, cron, heartbeat, journal, autonomy, watchman, spore, cli)
+        #[arg(long)]
+        channel: Option<String>,
+        /// tail: only this outcome (ok, error, denied)
+        #[arg(long)]
+        outcome: Option<String>,
+        /// tail: first day to include (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// tail: number of entries shown
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Post-mortems of failed cycles (list/show)
+    Failures {
+        /// Subcommand: list (newest first) or show (one bundle)
+        #[arg(index = 1, default_value = "list")]
+        action: String,
+        /// show: cycle id or a prefix of it (default: the newest)
+        #[arg(index = 2)]
+        id: Option<String>,
+        /// list: number of bundles shown
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Disk usage of the workspace and housekeeping of screenshots and downloads (report/clean/pin)
+    Storage {
+        /// Subcommand: report (sizes), clean (apply the retention policies) or pin (keep a file)
+        #[arg(index = 1, default_value = "report")]
+        action: String,
+        /// pin: the file to keep
+        #[arg(index = 2)]
+        path: Option<String>,
+        /// report: number of largest files shown
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+        /// clean: only list what would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Proposals of the autonomy engine (list/show/approve/reject)
+    Proposal {
+        /// Subcommand: list, show, approve (run its Action Plan) or reject
+        #[arg(index = 1, default_value = "list")]
+        action: String,
+        /// Proposal id, or a unique part of it (show without one: the newest pending)
+        #[arg(index = 2)]
+        id: Option<String>,
+        /// reject: why, recorded in the proposal
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Named routines of skill and think steps in workspace/pipelines (list/show/run)
+    Pipeline {
+        /// Subcommand: list, show or run
+        #[arg(index = 1, default_value = "list")]
+        action: String,
+        /// Pipeline name (its file name without .yaml)
+        #[arg(index = 2)]
+        name: Option<String>,
+        /// run: override a variable, as name=value (repeatable)
+        #[arg(long = "var")]
+        vars: Vec<String>,
+        /// run: print the report as JSON and ask nothing (gated steps are refused)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Loaded skills, and a test runner for plugin authors (list/test)
+    Skills {
+        /// Subcommand: list, or test (run one skill and check its result)
+        #[arg(index = 1, default_value = "list")]
+        action: String,
+        /// test: skill name
+        #[arg(index = 2)]
+        name: Option<String>,
+        /// test: the argument, as in [SKILL_NAME: args] (a path, a command or JSON)
+        #[arg(long, default_value = "")]
+        args: String,
+        /// test: seconds before the call is stopped
+        #[arg(long, default_value_t = 60)]
+        timeout: u64,
+        /// test: run again whenever the skill's source files change
+        #[arg(long)]
+        watch: bool,
+        /// test: save the invocation and its result to this JSON file
+        #[arg(long)]
+        record: Option<String>,
+    },
+    /// Post a message to the running TUI (for cron jobs and scripts)
+    Notify {
+        /// Message text
+        #[arg(index = 1)]
+        text: String,
+        /// Sender shown next to the message
+        #[arg(long, default_value = "cli")]
+        source: String,
+    },
     /// One-shot think for swarm/spores
     Think {
         /// The prompt for the AI
@@ -49,9 +246,54 @@ enum Commands {
         /// Optional role for the spore
         #[arg(long)]
         role: Option<String>,
+        /// Plan only: show the tool calls the turn would make without running them
+        #[arg(long)]
+        dry_run: bool,
+        /// Started by the swarm: keep the parent's working directory
+        #[arg(long, hide = true)]
+        spore: bool,
     },
 }
 
+/// Local midnight of a YYYY-MM-DD day, shifted by `offset_days` (1 makes --until inclusive)
+fn parse_day(day: Option<&str>, offset_days: i64) -> Result<Option<chrono::DateTime<chrono::Local>>, String> {
+    use chrono::TimeZone;
+    let Some(day) = day else { return Ok(None) };
+    let date = chrono::NaiveDate::parse_from_str(day.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date \"{}\" (expected YYYY-MM-DD)", day))?;
+    let midnight = (date + chrono::Duration::days(offset_days)).and_hms_opt(0, 0, 0).unwrap_or_default();
+    Ok(chrono::Local.from_local_datetime(&midnight).earliest())
+}
+
+/// Built-in tasks of the TUI's scheduler with their next run where it does not
+/// depend on when the TUI started (cron expressions, the evening journal)
+fn print_builtin_schedule(config: &AppConfig) {
+    use openspore_core::schedule::Schedule;
+    println!("🕒 Built-in schedule (runs while the TUI runs with AUTONOMY_ENABLED):\n");
+    let schedule = match Schedule::from_config(config) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            println!("  ❌ {}\n", e);
+            return;
+        }
+    };
+    let now = chrono::Local::now();
+    for task in &schedule.tasks {
+        let next = match &task.trigger {
+            Some(trigger) if trigger.is_anchored(task.task) => trigger.first_run(task.task, now)
+                .map(|at| format!("next {}", at.format("%a %Y-%m-%d %H:%M")))
+                .unwrap_or_default(),
+            Some(_) => "counted from the TUI's start".to_string(),
+            None => String::new(),
+        };
+        println!("  {:15} | {:28} | {}", task.task, task.describe(), next);
+    }
+    if let Some(quiet) = schedule.quiet_hours {
+        println!("  {:15} | {:28} | no routine Telegram messages", "quiet hours", quiet.to_string());
+    }
+    println!();
+}
+
 fn get_app_dir() -> String {
     openspore_core::path_utils::get_app_root().to_string_lossy().to_string()
 }
@@ -60,8 +302,10 @@ fn get_app_dir() -> String {
 async fn main() {
     let args = Args::parse();
 
-    // 🕵️ Persistence Fix: Reset Virtual CWD if this is a fresh main run (not a sub-spore)
-    if std::env::var("IS_SPORE").is_err() {
+    // 🕵️ Persistence Fix: Reset Virtual CWD if this is a fresh main run (not a sub-spore;
+    // IS_SPORE is what older parents set)
+    let is_spore = matches!(args.command, Some(Commands::Think { spore: true, .. })) || std::env::var("IS_SPORE").is_ok();
+    if !is_spore {
         openspore_skills::utils::reset_virtual_cwd();
     }
 
@@ -79,18 +323,13 @@ async fn main() {
 
     let (non_blocking, _guard) = tracing_appender::non_blocking(file);
 
+    let log_filter = openspore_core::logging::initial_filter();
     if matches!(args.command, Some(Commands::Start) | None) {
         // TUI mode: Only log to file, keep stdout clean for REPL
-        tracing_subscriber::fmt()
-            .with_writer(non_blocking)
-            .with_ansi(false)
-            .init();
+        openspore_core::logging::init(non_blocking, false, &log_filter);
     } else {
         // CLI mode: Log to stdout for feedback
-        tracing_subscriber::fmt()
-            .with_writer(std::io::stderr)
-            .with_ansi(true)
-            .init();
+        openspore_core::logging::init(std::io::stderr, true, &log_filter);
     }
 
     openspore_core::init();
@@ -98,25 +337,26 @@ async fn main() {
     // 1. Commands that DON'T require config
     if let Some(cmd) = &args.command {
         match cmd {
-            Commands::Stop => {
-                println!("🛑 Stopping all OpenSpore instances and browsers...");
-                let _ = Command::new("pkill").args(["-f", "openspore"]).status();
-                let _ = Command::new("pkill").args(["-f", "Google Chrome"]).status();
-                let _ = Command::new("pkill").args(["-f", "Chromium"]).status();
-                let _ = Command::new("pkill").args(["-f", "Brave Browser"]).status();
-
-                let app_dir = openspore_core::path_utils::get_app_root();
-                let session_file = app_dir.join("workspace").join("browser_sessions").join("active_session.json");
-                if session_file.exists() {
-                    let _ = std::fs::remove_file(session_file);
-                }
-
-                println!("✅ All instances and browsers stopped.");
+            Commands::Stop { all } => {
+                stop::run(*all);
                 return;
             }
-            Commands::Doctor => {
-                let mut doctor = openspore_doctor::SporeDoctor::new();
-                doctor.check_all();
+            Commands::Doctor { check, fix, json, dry_run, offline } => {
+                use openspore_doctor::DoctorMode;
+                let mode = match (*json, *check) {
+                    (true, _) => DoctorMode::Json { fix: *fix || *dry_run },
+                    (false, true) => DoctorMode::Check,
+                    (false, false) => DoctorMode::Fix,
+                };
+                let report = openspore_doctor::SporeDoctor::new().with_dry_run(*dry_run).with_offline(*offline).run(mode);
+                let failed = match mode {
+                    DoctorMode::Check => report.has_critical(),
+                    DoctorMode::Json { .. } => !report.ok(),
+                    DoctorMode::Fix => false,
+                };
+                if failed {
+                    std::process::exit(1);
+                }
                 return;
             }
             _ => {} // Continue to config loading for other commands
@@ -137,7 +377,10 @@ async fn main() {
     // 3. Command Dispatch
     match args.command {
         Some(Commands::Start) | None => {
-            // TUI Mode
+            // TUI Mode (recorded in the instance lock for `openspore stop`)
+            let _instance = openspore_core::process::InstanceGuard::acquire(&config.project_root, "tui")
+                .map_err(|e| error!("Failed to write instance lock: {}", e))
+                .ok();
             if let Err(e) = openspore_tui::run().await {
                 eprintln!("TUI Error: {}", e);
             }
@@ -148,15 +391,16 @@ async fn main() {
 
             match action.as_str() {
                 "list" => {
+                    print_builtin_schedule(&config);
                     println!("⏰ Active OpenSpore Cron Jobs:\n");
                     if let Ok(content) = std::fs::read_to_string(&manifest) {
-                        if let Ok(jobs) = serde_json::from_str::<serde_json::Value>(&content) {
-                            if let Some(obj) = jobs.as_object() {
-                                for (name, job) in obj {
-                                    let schedule = job["schedule"].as_str().unwrap_or("?");
-                                    let script = job["script"].as_str().unwrap_or("?");
-                                    println!("  {:15} | {:12} | {}", name, schedule, script);
-                                }
+                        if let Ok(jobs) = serde_json::from_str::<serde_json::Value>(&content)
+                            && let Some(obj) = jobs.as_object()
+                        {
+                            for (name, job) in obj {
+                                let schedule = job["schedule"].as_str().unwrap_or("?");
+                                let script = job["script"].as_str().unwrap_or("?");
+                                println!("  {:15} | {:12} | {}", name, schedule, script);
                             }
                         }
                     } else {
@@ -166,42 +410,42 @@ async fn main() {
                 "install" => {
                     println!("🛠️ Installing cron jobs...");
                     if let Ok(content) = std::fs::read_to_string(&manifest) {
-                        if let Ok(jobs) = serde_json::from_str::<serde_json::Value>(&content) {
-                            if let Some(obj) = jobs.as_object() {
-                                let binary = format!("{}/crates/target/release/openspore", app_dir);
-                                let mut cron_lines = Vec::new();
-
-                                if let Ok(output) = Command::new("crontab").arg("-l").output() {
-                                    let content = String::from_utf8_lossy(&output.stdout);
-                                    for line in content.lines() {
-                                        if !line.contains("openspore job") && !line.trim().is_empty() {
-                                            cron_lines.push(line.to_string());
-                                        }
+                        if let Ok(jobs) = serde_json::from_str::<serde_json::Value>(&content)
+                            && let Some(obj) = jobs.as_object()
+                        {
+                            let binary = format!("{}/crates/target/release/openspore", app_dir);
+                            let mut cron_lines = Vec::new();
+
+                            if let Ok(output) = Command::new("crontab").arg("-l").output() {
+                                let content = String::from_utf8_lossy(&output.stdout);
+                                for line in content.lines() {
+                                    if !line.contains("openspore job") && !line.trim().is_empty() {
+                                        cron_lines.push(line.to_string());
                                     }
                                 }
+                            }
 
-                                for (name, job) in obj {
-                                    let schedule = job["schedule"].as_str().unwrap_or("* * * * *");
-                                    cron_lines.push(format!("{} {} job \"{}\" > /dev/null 2>&1", schedule, binary, name));
-                                }
+                            for (name, job) in obj {
+                                let schedule = job["schedule"].as_str().unwrap_or("* * * * *");
+                                cron_lines.push(format!("{} {} job \"{}\" > /dev/null 2>&1", schedule, binary, name));
+                            }
 
-                                let new_crontab = cron_lines.join("\n") + "\n";
-                                println!("Installing {} cron entries...", cron_lines.len());
+                            let new_crontab = cron_lines.join("\n") + "\n";
+                            println!("Installing {} cron entries...", cron_lines.len());
 
-                                use std::io::Write;
-                                let mut child = Command::new("crontab")
-                                    .stdin(std::process::Stdio::piped())
-                                    .spawn()
-                                    .expect("Failed to spawn crontab command");
+                            use std::io::Write;
+                            let mut child = Command::new("crontab")
+                                .stdin(std::process::Stdio::piped())
+                                .spawn()
+                                .expect("Failed to spawn crontab command");
 
-                                if let Some(mut stdin) = child.stdin.take() {
-                                    stdin.write_all(new_crontab.as_bytes()).expect("Failed to write to crontab stdin");
-                                }
+                            if let Some(mut stdin) = child.stdin.take() {
+                                stdin.write_all(new_crontab.as_bytes()).expect("Failed to write to crontab stdin");
+                            }
 
-                                match child.wait() {
-                                    Ok(status) if status.success() => println!("✅ Cron jobs installed successfully."),
-                                    _ => println!("❌ Failed to install cron jobs."),
-                                }
+                            match child.wait() {
+                                Ok(status) if status.success() => println!("✅ Cron jobs installed successfully."),
+                                _ => println!("❌ Failed to install cron jobs."),
                             }
                         }
                     } else {
@@ -211,7 +455,39 @@ async fn main() {
                 _ => println!("Usage: openspore cron [list|install]"),
             }
         }
-        Some(Commands::Job { name }) => {
+        Some(Commands::Job { name, dry_run, limit, days }) if name == "consolidate" => {
+            let state = openspore_core::state::AppState::new(config.clone());
+            let memory = openspore_memory::MemorySystem::new(&state);
+            let brain = openspore_brain::Brain::new(config.clone()).with_channel("consolidate");
+            let days = days.unwrap_or(config.consolidate_after_days.filter(|d| *d > 0).unwrap_or(openspore_core::config::DEFAULT_CONSOLIDATE_AFTER_DAYS));
+            let older_than = std::time::Duration::from_secs(days as u64 * 24 * 3600);
+            let limit = limit.unwrap_or_else(|| config.consolidate_max_files()).max(1);
+            println!("🗜️ [Consolidate]: exchanges older than {} days, at most {}{}", days, limit, if dry_run { " (dry run)" } else { "" });
+
+            match memory.consolidate(&brain, older_than, limit, dry_run).await {
+                Ok(report) => {
+                    for digest in &report.digests {
+                        let verb = if report.dry_run { "would go to" } else { "->" };
+                        println!("   {}: {} exchanges {} {}", digest.day, digest.exchanges.len(), verb, digest.path.display());
+                    }
+                    for (day, e) in &report.failed {
+                        println!("   ❌ {}: {}", day, e);
+                    }
+                    if report.digests.is_empty() && report.failed.is_empty() {
+                        println!("✅ Nothing to consolidate");
+                    } else if report.dry_run {
+                        println!("📝 Dry run: {} exchanges in {} days, nothing changed", report.exchanges(), report.digests.len());
+                    } else {
+                        println!("✅ {} exchanges folded into {} digests", report.exchanges(), report.digests.len());
+                    }
+                    if report.remaining > 0 {
+                        println!("⏭️  {} older exchanges left for the next run", report.remaining);
+                    }
+                }
+                Err(e) => error!("Consolidation failed: {}", e),
+            }
+        }
+        Some(Commands::Job { name, .. }) => {
             let cron_dir = format!("{}/workspace/cron", app_dir);
             let manifest = format!("{}/crontab.json", cron_dir);
 
@@ -240,27 +516,38 @@ async fn main() {
                 println!("❌ No crontab.json found");
             }
         }
-        Some(Commands::Swarm) => {
-            println!("🐝 [Swarm Status]: Scanning for active sub-spores...");
+        Some(Commands::Swarm { reap }) => {
             let swarm = openspore_swarm::SwarmManager::new();
-            match swarm.discovery().await {
-                Ok(lines) => {
-                    if lines.is_empty() {
-                        println!("📭 No active sub-spores found.");
-                    } else {
-                        println!("✅ Found {} active sub-spores:", lines.len());
-                        for line in lines {
-                            println!("  - {}", line);
-                        }
-                    }
+            if reap {
+                let report = swarm.reap(openspore_swarm::REAP_MAX_AGE);
+                println!("🐝 [Swarm Reap]: {} exited, {} killed, {} still running", report.exited, report.killed.len(), report.running.len());
+                for spore in &report.killed {
+                    println!("  ☠️  {} [pid {}] started {}: {}", spore.role, spore.pid, spore.start_time.format("%H:%M:%S"), openspore_core::fmt_utils::preview(&spore.task, 80));
+                }
+                for (spore, e) in &report.failed {
+                    println!("  ❌ {} [pid {}]: {}", spore.role, spore.pid, e);
+                }
+                return;
+            }
+            println!("🐝 [Swarm Status]: {}", swarm.registry_status());
+            for queued in swarm.registry.queued() {
+                println!("  ⏳ {} [owner pid {}] queued {}: {}", queued.role, queued.owner, queued.since.format("%H:%M:%S"), openspore_core::fmt_utils::preview(&queued.task, 80));
+            }
+            let active = swarm.list_active();
+            if active.is_empty() {
+                println!("📭 No active sub-spores found.");
+            } else {
+                println!("✅ Found {} active sub-spores:", active.len());
+                for spore in &active {
+                    let age = (chrono::Local::now() - spore.start_time).to_std().unwrap_or_default();
+                    println!("  - {} [pid {}] running {}: {}", spore.role, spore.pid, openspore_core::fmt_utils::humanize_duration(age), openspore_core::fmt_utils::preview(&spore.task, 80));
                 }
-                Err(e) => println!("❌ Error: {}", e),
             }
         }
         Some(Commands::Auto) => {
             println!("🧠 [Autonomy Engine]: Analyzing patterns & generating proposal...");
             let state = openspore_core::state::AppState::new(config.clone());
-            let brain = Brain::new(config);
+            let brain = Brain::new(config).with_channel("autonomy");
             let memory = openspore_memory::MemorySystem::new(&state);
 
             match openspore_autonomy::AutonomyEngine::run(&brain, &memory).await {
@@ -270,38 +557,39 @@ async fn main() {
             }
         }
         Some(Commands::Logs) => {
-            let context_dir = format!("{}/workspace/context", app_dir);
+            let context_dir = openspore_memory::session::context_dir(&std::path::Path::new(&app_dir).join("workspace"));
             println!("📜 Recent context files:\n");
-            let output = Command::new("ls").args(["-lt", &context_dir]).output();
+            let output = Command::new("ls").arg("-lt").arg(&context_dir).output();
             if let Ok(out) = output {
                 let content = String::from_utf8_lossy(&out.stdout);
                 for line in content.lines().take(10) { println!("{}", line); }
             }
         }
-        Some(Commands::Think { prompt, role }) => {
-            let brain = Brain::new(config);
-            unsafe {
-                std::env::set_var("IS_SPORE", "true");
-                if let Some(r) = role { std::env::set_var("SPORE_ROLE", r); }
+        Some(Commands::Think { prompt, role, dry_run, .. }) => {
+            let brain = Brain::new_spore(config, role);
+            let response = brain.think_with_options(&prompt, None, openspore_brain::ThinkOptions { dry_run }).await;
+            brain.flush_journal().await;
+            // A parent swarm reads the answer from its result file, not from stdout
+            if let Err(e) = openspore_swarm::write_answer(&response) {
+                error!("{}", e);
             }
-            let response = brain.think(&prompt).await;
             println!("{}", response);
         }
         Some(Commands::Heartbeat) => {
             println!("💓 [System Heartbeat]");
             let state = openspore_core::state::AppState::new(config.clone());
-            let brain = openspore_brain::Brain::new(config);
+            let brain = openspore_brain::Brain::new(config).with_channel("heartbeat");
             let memory = openspore_memory::MemorySystem::new(&state);
             let telegram = openspore_telegram::TelegramChannel::new().ok();
 
-            if let Err(e) = openspore_autonomy::Heartbeat::run(&brain, &memory, telegram.as_ref()).await {
+            if let Err(e) = openspore_autonomy::Heartbeat::run(&brain, &memory, telegram.as_ref(), true).await {
                 error!("Heartbeat failed: {}", e);
             }
         }
         Some(Commands::Journal) => {
             println!("📓 [Daily Journal Synthesis]");
             let state = openspore_core::state::AppState::new(config.clone());
-            let brain = openspore_brain::Brain::new(config);
+            let brain = openspore_brain::Brain::new(config).with_channel("journal");
             let memory = openspore_memory::MemorySystem::new(&state);
 
             match openspore_autonomy::DailyJournal::run(&brain, &memory).await {
@@ -310,6 +598,234 @@ async fn main() {
                 Err(e) => error!("Journal synthesis failed: {}", e),
             }
         }
+        Some(Commands::Memory { action, paths, force, model, since, until, restore, ours, theirs, llm }) => {
+            match action.as_str() {
+                "index" => {
+                    let state = openspore_core::state::AppState::new(config);
+                    let memory = openspore_memory::MemorySystem::new(&state);
+                    match memory.regenerate_index(force).await {
+                        Ok(report) => {
+                            println!("🗂️  Index written: {}", report.path.display());
+                            println!("   Rescanned: {}", if report.rescanned.is_empty() { "-".to_string() } else { report.rescanned.join(", ") });
+                            println!("   Unchanged: {}", if report.skipped.is_empty() { "-".to_string() } else { report.skipped.join(", ") });
+                        }
+                        Err(e) => error!("Index generation failed: {}", e),
+                    }
+                }
+                "reindex" => {
+                    let state = openspore_core::state::AppState::new(config);
+                    let memory = openspore_memory::MemorySystem::new(&state);
+                    let start = std::time::Instant::now();
+                    match memory.rebuild_index().await {
+                        Ok(count) => println!("🔎 Search index rebuilt: {} files in {}", count, openspore_core::fmt_utils::humanize_duration(start.elapsed())),
+                        Err(e) => error!("Search index rebuild failed: {}", e),
+                    }
+                }
+                "audit" => {
+                    let (since, until) = match (parse_day(since.as_deref(), 0), parse_day(until.as_deref(), 1)) {
+                        (Ok(since), Ok(until)) => (since, until),
+                        (Err(e), _) | (_, Err(e)) => {
+                            println!("❌ {}", e);
+                            return;
+                        }
+                    };
+                    let state = openspore_core::state::AppState::new(config);
+                    let memory = openspore_memory::MemorySystem::new(&state);
+                    let exchanges = memory.audit_exchanges(model.as_deref(), since, until);
+                    println!("🔍 [Exchange Audit] {} match(es)\n", exchanges.len());
+                    for (session, item) in exchanges {
+                        println!(
+                            "  {:25} | {:12} | {:30} | {:>7} | {}",
+                            item.meta("created").map(|c| c.chars().take(19).collect::<String>()).unwrap_or_else(|| "-".to_string()),
+                            session,
+                            item.meta("model").unwrap_or("-"),
+                            item.meta("completion_tokens").unwrap_or("-"),
+                            item.filename
+                        );
+                    }
+                }
+                "review" => {
+                    let state = openspore_core::state::AppState::new(config);
+                    let memory = openspore_memory::MemorySystem::new(&state);
+                    let stats = memory.usage_stats();
+                    let entries = stats.review();
+                    println!("📈 [Memory Review] {} memor{} put in context, never-cited first\n", entries.len(), if entries.len() == 1 { "y" } else { "ies" });
+                    println!("  {:>8} | {:>5} | {:>10} | {:10} | File", "Injected", "Cited", "Usefulness", "Last cited");
+                    for (_, usage) in entries.into_iter().take(30) {
+                        println!(
+                            "  {:>8} | {:>5} | {:>10.2} | {:10} | {}",
+                            usage.injected_count,
+                            usage.cited_count,
+                            usage.usefulness(),
+                            usage.last_cited.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "never".to_string()),
+                            usage.path.strip_prefix(&memory.memory_root).unwrap_or(&usage.path).display()
+                        );
+                    }
+                }
+                "deleted" => {
+                    let state = openspore_core::state::AppState::new(config);
+                    let memory = openspore_memory::MemorySystem::new(&state);
+                    if let Some(path) = restore {
+                        match memory.restore_tombstoned(&path).await {
+                            Ok(restored) => println!("✅ Restored {}", restored.display()),
+                            Err(e) => println!("❌ {}", e),
+                        }
+                        return;
+                    }
+                    let tombstones = memory.tombstones();
+                    println!("🪦 [Deleted Memories] {} tombstone(s), kept {} days\n", tombstones.len(), openspore_memory::TOMBSTONE_RETENTION_DAYS);
+                    for t in tombstones.iter().rev().take(30) {
+                        println!("  {} | {:10} | {}", t.deleted_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"), t.reason, t.path);
+                    }
+                    if !tombstones.is_empty() {
+                        println!("\nRestore with: openspore memory deleted --restore <path>");
+                    }
+                }
+                "merge-driver" => std::process::exit(conflicts::merge_driver(config, &paths)),
+                "conflicts" => conflicts::list(config),
+                "resolve" => conflicts::resolve(config, paths.first().map(String::as_str), ours, theirs, llm).await,
+                _ => println!("Usage: openspore memory [index [--force]|reindex|audit [--model <slug>] [--since YYYY-MM-DD] [--until YYYY-MM-DD]|deleted [--restore <path>]|conflicts|resolve <path> --ours|--theirs|--llm]"),
+            }
+        }
+        Some(Commands::Session { action, name }) => {
+            let state = openspore_core::state::AppState::new(config);
+            let memory = openspore_memory::MemorySystem::new(&state);
+            let sessions = openspore_memory::SessionManager::new(&memory);
+
+            match (action.as_str(), name) {
+                ("list", _) => {
+                    println!("🗂️  Sessions:\n");
+                    for s in sessions.list() {
+                        let marker = if s.active { "*" } else { " " };
+                        println!("  {} {:20} | {} exchanges", marker, s.name, s.exchanges);
+                    }
+                }
+                ("new", Some(name)) => match sessions.create(&name).and_then(|_| sessions.switch(&name)) {
+                    Ok(()) => println!("✅ Session '{}' created and active.", name),
+                    Err(e) => println!("❌ {}", e),
+                },
+                ("switch", Some(name)) => match sessions.switch(&name) {
+                    Ok(()) => println!("✅ Switched to session '{}'.", name),
+                    Err(e) => println!("❌ {}", e),
+                },
+                _ => println!("Usage: openspore session [list|new <name>|switch <name>]"),
+            }
+        }
+        Some(Commands::Notify { text, source }) => {
+            let state = openspore_core::state::AppState::new(config);
+            let memory = openspore_memory::MemorySystem::new(&state);
+            let event = openspore_core::event_bus::SystemEvent::InboxMessage { source, text };
+            match openspore_core::event_bus::post_to_inbox(&memory.memory_root, &event) {
+                Ok(()) => println!("📬 Message posted to the inbox."),
+                Err(e) => println!("❌ Failed to write inbox: {}", e),
+            }
+        }
+        Some(Commands::Status { json }) => {
+            let status = Brain::new(config).status();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status).unwrap_or_default());
+            } else {
+                println!("🍄 [OpenSpore Status]\n");
+                for line in status.lines() {
+                    println!("  {}", line);
+                }
+            }
+        }
+        Some(Commands::Usage) => {
+            let brain = Brain::new(config);
+            let stats = brain.usage_stats();
+            println!("📊 [Token Usage]\n");
+            println!("  {:10} | {:>6} | {:>12} | {:>12} | {:>10}", "Period", "Calls", "Prompt", "Completion", "Cost ($)");
+            println!("  {:10} | {:>6} | {:>12} | {:>12} | {:>10.4}", "Today", stats.today_calls, stats.today.prompt_tokens, stats.today.completion_tokens, stats.today.cost);
+            println!("  {:10} | {:>6} | {:>12} | {:>12} | {:>10.4}", "Month", stats.month_calls, stats.month.prompt_tokens, stats.month.completion_tokens, stats.month.cost);
+            if !stats.month_latency.is_empty() {
+                println!("\n⏱️  Think cycles this month by prompt mode:");
+                for latency in &stats.month_latency {
+                    println!(
+                        "  {:10} | {:>6} cycles | {} average",
+                        latency.mode.as_str(),
+                        latency.cycles,
+                        openspore_core::fmt_utils::humanize_duration(std::time::Duration::from_millis(latency.average_ms()))
+                    );
+                }
+            }
+            let fast = stats.month_fast_path;
+            if !fast.is_empty() {
+                println!("\n⚡ Fast path this month (work skipped):");
+                println!("  learn: {} acknowledgments, {} throttled | exchanges: {} | summary compressions: {} | batched journal lines: {}",
+                    fast.learn_skipped, fast.learn_throttled, fast.exchange_skipped, fast.summary_skipped, fast.journal_batched);
+            }
+        }
+        Some(Commands::Audit { action, skill, channel, outcome, since, limit }) => {
+            match action.as_str() {
+                "verify" => std::process::exit(audit::verify(config)),
+                "tail" => match parse_day(since.as_deref(), 0) {
+                    Ok(since) => audit::tail(config, audit::TailFilter { skill, channel, outcome, since, limit }),
+                    Err(e) => println!("❌ {}", e),
+                },
+                other => println!("❌ Unknown audit action: {} (expected verify or tail)", other),
+            }
+        }
+        Some(Commands::Failures { action, id, limit }) => {
+            match action.as_str() {
+                "list" => failures::list(config, limit),
+                "show" => failures::show(config, id.as_deref()),
+                other => println!("❌ Unknown failures action: {} (expected list or show)", other),
+            }
+        }
+        Some(Commands::Proposal { action, id, reason }) => {
+            match action.as_str() {
+                "list" => proposal::list(config),
+                "show" => proposal::show(config, id.as_deref()),
+                "approve" => proposal::approve(config, id.as_deref()).await,
+                "reject" => proposal::reject(config, id.as_deref(), reason.as_deref()),
+                other => println!("❌ Unknown proposal action: {} (expected list, show, approve or reject)", other),
+            }
+        }
+        Some(Commands::Pipeline { action, name, vars, json }) => {
+            match action.as_str() {
+                "list" => pipeline::list(config).await,
+                "show" => pipeline::show(config, name.as_deref()).await,
+                "run" => std::process::exit(pipeline::run(config, name.as_deref(), &vars, json).await),
+                other => println!("❌ Unknown pipeline action: {} (expected list, show or run)", other),
+            }
+        }
+        Some(Commands::Skills { action, name, args, timeout, watch, record }) => {
+            match action.as_str() {
+                "list" => skills::list(config),
+                "test" => {
+                    let options = skills::TestOptions {
+                        args,
+                        timeout: std::time::Duration::from_secs(timeout.max(1)),
+                        watch,
+                        record: record.map(std::path::PathBuf::from),
+                    };
+                    std::process::exit(skills::test(config, name.as_deref(), options).await);
+                }
+                other => {
+                    println!("❌ Unknown skills action: {} (expected list or test)", other);
+                    std::process::exit(skills::EXIT_USAGE);
+                }
+            }
+        }
+        Some(Commands::Storage { action, path, limit, dry_run }) => {
+            match action.as_str() {
+                "report" => storage::report(config, limit),
+                "clean" => storage::clean(config, dry_run),
+                "pin" => storage::pin(config, path.as_deref()),
+                other => println!("❌ Unknown storage action: {} (expected report, clean or pin)", other),
+            }
+        }
         _ => {} // Already handled Stop/Doctor
     }
+
+    // Learning and compression of the last cycles finish before the runtime
+    // stops; whatever outlasts the grace period runs at the next start
+    if pending_work::running() > 0 {
+        eprintln!("⏳ Finishing background learning...");
+        let left = pending_work::drain(pending_work::DRAIN_GRACE).await;
+        if left > 0 {
+            eprintln!("🧺 Background work still running after {}s, resumed at the next start.", pending_work::DRAIN_GRACE.as_secs());
+        }
+    }
 }
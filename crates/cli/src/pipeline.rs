@@ -0,0 +1,176 @@
+//! `openspore pipeline list|show|run`: named routines of workspace/pipelines
+//!
+//! `list` prints the pipelines with their steps and schedule, and the files
+//! that do not parse. `show <name>` prints one with its file. `run <name>
+//! [--var k=v]...` runs it here, printing each step and asking on the
+//! terminal for the calls APPROVAL_MODE gates and for `ask` error policies;
+//! with `--json` nobody is asked and the report is printed as JSON (what the
+//! run_pipeline skill reads). The exit code is 1 when the run was aborted or
+//! could not start.
+
+use openspore_autonomy::pipelines::{self, PipelineStore, RunStatus, StepStatus};
+use openspore_brain::approval::ApprovalDecision;
+use openspore_brain::events::BrainEvent;
+use openspore_brain::Brain;
+use openspore_core::config::AppConfig;
+use openspore_core::fmt_utils::preview;
+use openspore_memory::MemorySystem;
+use std::collections::BTreeMap;
+
+/// The pipelines of the workspace, created with the sample pipeline if missing
+async fn store(config: AppConfig) -> PipelineStore {
+    let state = openspore_core::state::AppState::new(config);
+    let memory = MemorySystem::new(&state);
+    if let Err(e) = memory.ensure_structure().await {
+        println!("⚠️ Could not set up the workspace: {}", e);
+    }
+    PipelineStore::new(&memory.memory_root)
+}
+
+fn step_icon(status: StepStatus) -> &'static str {
+    match status {
+        StepStatus::Ok => "✅",
+        StepStatus::Failed => "❌",
+        StepStatus::Skipped => "⏭️",
+        StepStatus::NotRun => "•",
+    }
+}
+
+pub async fn list(config: AppConfig) {
+    let store = store(config).await;
+    let pipelines = store.list();
+    if pipelines.is_empty() {
+        println!("📭 No pipelines in {}. Each <name>.yaml there is one.", store.dir().display());
+        return;
+    }
+    println!("🧪 [Pipelines] {}\n", store.dir().display());
+    for (name, pipeline) in &pipelines {
+        match pipeline {
+            Ok(p) => println!(
+                "  {:20} | {:2} step(s) | {:16} | {}",
+                name,
+                p.steps.len(),
+                p.schedule.as_deref().unwrap_or("-"),
+                preview(&p.description, 60)
+            ),
+            Err(e) => println!("  ❌ {:17} | {}", name, preview(&e.to_string(), 100)),
+        }
+    }
+    println!("\n  `openspore pipeline show <name>` for its steps, `openspore pipeline run <name>` to run it.");
+}
+
+pub async fn show(config: AppConfig, name: Option<&str>) {
+    let Some(name) = name else {
+        println!("Usage: openspore pipeline show <name>");
+        return;
+    };
+    let pipeline = match store(config).await.find(name) {
+        Ok(pipeline) => pipeline,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
+    println!("🧪 {}: {}\n   {}\n", pipeline.name, pipeline.description, pipeline.path.display());
+    println!("  Schedule: {}", pipeline.schedule.as_deref().unwrap_or("none"));
+    println!("  On error: {}", pipeline.on_error.as_str());
+    for (var, value) in &pipeline.vars {
+        println!("  Var:      {} = {}", var, value);
+    }
+    println!();
+    for (i, step) in pipeline.steps.iter().enumerate() {
+        let detail = match &step.think {
+            Some(prompt) => preview(prompt, 80),
+            None => preview(&step.args.to_string(), 80),
+        };
+        let policy = step.on_error.map(|p| format!(" [on_error: {}]", p.as_str())).unwrap_or_default();
+        println!("  {}. {} ({}){}: {}", i + 1, step.label(i), step.kind(), policy, detail);
+    }
+}
+
+/// `k=v` pairs of --var
+fn parse_vars(vars: &[String]) -> Result<BTreeMap<String, String>, String> {
+    vars.iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+            _ => Err(format!("--var {}: expected name=value", pair)),
+        })
+        .collect()
+}
+
+/// Run a pipeline; the exit code of the command
+pub async fn run(config: AppConfig, name: Option<&str>, vars: &[String], json: bool) -> i32 {
+    let fail = |error: String| {
+        if json {
+            println!("{}", serde_json::json!({ "success": false, "error": error }));
+        } else {
+            println!("❌ {}", error);
+        }
+        1
+    };
+    let Some(name) = name else {
+        return fail("Usage: openspore pipeline run <name> [--var name=value]...".to_string());
+    };
+    let overrides = match parse_vars(vars) {
+        Ok(overrides) => overrides,
+        Err(e) => return fail(e),
+    };
+    let pipeline = match store(config.clone()).await.find(name) {
+        Ok(pipeline) => pipeline,
+        Err(e) => return fail(e.to_string()),
+    };
+
+    let brain = Brain::new(config);
+    let report = if json {
+        pipelines::run(&brain, &pipeline, &overrides, None).await
+    } else {
+        println!("🧪 Running {} ({} step(s))", pipeline.name, pipeline.steps.len());
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<BrainEvent>(32);
+        let running = {
+            let brain = brain.clone_brain();
+            let pipeline = pipeline.clone();
+            tokio::spawn(async move { pipelines::run(&brain, &pipeline, &overrides, Some(tx)).await })
+        };
+        while let Some(event) = rx.recv().await {
+            match event {
+                BrainEvent::ToolExecution { name, arg } => println!("  🔧 {} {}", name, preview(&arg, 100)),
+                BrainEvent::ApprovalRequest { id, name, arg } => {
+                    let decision = tokio::task::spawn_blocking(move || crate::proposal::ask(&name, &arg)).await
+                        .unwrap_or_else(|_| ApprovalDecision::Deny("no answer".to_string()));
+                    if !brain.approvals.respond(id, decision) {
+                        println!("⌛ That approval request had already expired");
+                    }
+                }
+                _ => {}
+            }
+        }
+        match running.await {
+            Ok(report) => report,
+            Err(e) => return fail(format!("The run stopped: {}", e)),
+        }
+    };
+    brain.flush_journal().await;
+
+    let report = match report {
+        Ok(report) => report,
+        Err(e) => return fail(e.to_string()),
+    };
+    let aborted = report.status == RunStatus::Aborted;
+    if json {
+        let mut result = serde_json::to_value(&report).unwrap_or_default();
+        result["success"] = (!aborted).into();
+        result["content"] = report.final_output().unwrap_or_default().into();
+        println!("{}", result);
+        return i32::from(aborted);
+    }
+
+    println!("\n🧪 {} {} (run {})\n", report.pipeline, report.status.as_str(), report.run);
+    for step in &report.steps {
+        let detail = step.error.as_deref().map(|e| format!(": {}", preview(e, 150))).unwrap_or_default();
+        println!("  {} {}. {} ({}){}", step_icon(step.status), step.step, step.label, step.kind, detail);
+    }
+    if let Some(output) = report.final_output() {
+        println!("\n{}", output.trim());
+    }
+    i32::from(aborted)
+}
@@ -0,0 +1,155 @@
+//! `openspore proposal list|show|approve|reject`: review of autonomy proposals
+//!
+//! `list` prints the proposals of workspace/autonomy/proposals, newest first,
+//! with their status. `show <id>` prints one (without an id, the newest
+//! pending). `approve <id>` runs its Action Plan as an Executor Spore cycle
+//! and asks here, on the terminal, for the tool calls APPROVAL_MODE gates.
+//! `reject <id> --reason "..."` closes it. An id may be shortened to any
+//! unique part, such as the time.
+
+use openspore_autonomy::proposals::{self, Proposal, ProposalStore};
+use openspore_brain::approval::ApprovalDecision;
+use openspore_brain::events::BrainEvent;
+use openspore_brain::Brain;
+use openspore_core::config::AppConfig;
+use openspore_core::fmt_utils::preview;
+use std::io::Write;
+
+fn icon(status: &str) -> &'static str {
+    match status {
+        proposals::PENDING => "⏳",
+        proposals::EXECUTING => "▶️",
+        proposals::EXECUTED | "AUTO_APPLIED" => "✅",
+        proposals::FAILED => "❌",
+        proposals::REJECTED => "🚫",
+        _ => "•",
+    }
+}
+
+fn created(proposal: &Proposal) -> String {
+    chrono::DateTime::parse_from_rfc3339(&proposal.created)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| "-".to_string())
+}
+
+pub fn list(config: AppConfig) {
+    let proposals = ProposalStore::new(&config.project_root).list();
+    if proposals.is_empty() {
+        println!("📭 No proposals yet. The autonomy engine writes them to {}.", proposals::PROPOSALS_DIR);
+        return;
+    }
+    let pending = proposals.iter().filter(|p| p.status == proposals::PENDING).count();
+    println!("💡 [Proposals] {} total, {} pending\n", proposals.len(), pending);
+    for proposal in &proposals {
+        println!(
+            "  {} {:26} | {:12} | {:16} | {:7} | {}",
+            icon(&proposal.status),
+            proposal.id,
+            proposal.status,
+            created(proposal),
+            proposal.risk,
+            preview(&proposal.title, 60)
+        );
+    }
+    if pending > 0 {
+        println!("\n  `openspore proposal show <id>` to read one, then approve or reject it.");
+    }
+}
+
+pub fn show(config: AppConfig, id: Option<&str>) {
+    let store = ProposalStore::new(&config.project_root);
+    let found = match id {
+        Some(id) => store.find(id).map_err(|e| e.to_string()),
+        None => store.list().into_iter()
+            .find(|p| p.status == proposals::PENDING)
+            .ok_or_else(|| "No pending proposal".to_string()),
+    };
+    match found {
+        Ok(proposal) => {
+            println!("{} {} ({})\n   {}\n", icon(&proposal.status), proposal.id, proposal.status, proposal.path.display());
+            println!("{}", proposal.content.trim_end());
+        }
+        Err(e) => println!("❌ {}", e),
+    }
+}
+
+pub fn reject(config: AppConfig, id: Option<&str>, reason: Option<&str>) {
+    let Some(id) = id else {
+        println!("Usage: openspore proposal reject <id> --reason \"...\"");
+        return;
+    };
+    match ProposalStore::new(&config.project_root).reject(id, reason.unwrap_or_default()) {
+        Ok(proposal) => println!("🚫 {} rejected: {}", proposal.id, proposal.title),
+        Err(e) => println!("❌ {}", e),
+    }
+}
+
+/// y/yes approves, a/always approves for the rest of the run, anything else denies
+pub(crate) fn ask(name: &str, arg: &str) -> ApprovalDecision {
+    print!("\n🛡️ Allow {} {}? [y/N/a] ", name, preview(arg, 200));
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return ApprovalDecision::Deny("no answer".to_string());
+    }
+    let answer = answer.trim();
+    match answer.to_lowercase().as_str() {
+        "y" | "yes" => ApprovalDecision::Approve,
+        "a" | "always" => ApprovalDecision::AlwaysAllow,
+        _ => {
+            // "n <reason>" or just a reason
+            let reason = match answer.split_once(char::is_whitespace) {
+                Some((first, rest)) if matches!(first.to_lowercase().as_str(), "n" | "no") => rest,
+                _ if matches!(answer.to_lowercase().as_str(), "n" | "no") => "",
+                _ => answer,
+            }.trim();
+            ApprovalDecision::Deny(if reason.is_empty() { "denied on the terminal".to_string() } else { reason.to_string() })
+        }
+    }
+}
+
+pub async fn approve(config: AppConfig, id: Option<&str>) {
+    let Some(id) = id else {
+        println!("Usage: openspore proposal approve <id>");
+        return;
+    };
+    let store = ProposalStore::new(&config.project_root);
+    let proposal = match store.begin(id) {
+        Ok(proposal) => proposal,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
+    println!("▶️ Executing {}: {}", proposal.id, proposal.title);
+
+    let brain = Brain::new(config);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<BrainEvent>(32);
+    let run = {
+        let brain = brain.clone_brain();
+        let store = store.clone();
+        tokio::spawn(async move { store.execute(&brain, &proposal, Some(tx)).await })
+    };
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            BrainEvent::ToolExecution { name, arg } => println!("  🔧 {} {}", name, preview(&arg, 100)),
+            BrainEvent::ApprovalRequest { id, name, arg } => {
+                let decision = tokio::task::spawn_blocking(move || ask(&name, &arg)).await
+                    .unwrap_or_else(|_| ApprovalDecision::Deny("no answer".to_string()));
+                if !brain.approvals.respond(id, decision) {
+                    println!("⌛ That approval request had already expired");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match run.await {
+        Ok(execution) => {
+            println!("\n{} {} (cycle {})\n", icon(execution.status), execution.status, execution.cycle);
+            println!("{}", execution.reply.trim());
+        }
+        Err(e) => println!("❌ Executor stopped: {}", e),
+    }
+}
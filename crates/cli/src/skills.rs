@@ -0,0 +1,357 @@
+//! `openspore skills list|test`: trying skills without a think cycle
+//!
+//! `test <name> --args '...'` runs one skill, plugin or core, with the given
+//! arguments as the tool loop would (arguments that don't match its
+//! `args_schema` are refused the same way), and prints the result as pretty
+//! JSON (or the error). When the skill declares an output schema (`output_schema:` in
+//! SKILL.md) the result is checked against it. Script skills are checked on
+//! the JSON they print. `--timeout` stops a hung script, `--watch` runs it
+//! again whenever its source files change, and `--record fixture.json` saves
+//! the invocation with its result.
+//!
+//! Exit codes: 0 ok, 1 the skill failed (an error or `"success": false`),
+//! 2 the result does not match the schema, 3 timed out, 4 unknown skill or
+//! bad usage.
+
+use openspore_core::config::AppConfig;
+use openspore_core::fmt_utils::preview;
+use openspore_skills::{skill_kind, Skill, SkillLoader};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_INVALID: i32 = 2;
+pub const EXIT_TIMEOUT: i32 = 3;
+pub const EXIT_USAGE: i32 = 4;
+
+/// How often `--watch` looks at the source files
+const WATCH_POLL: Duration = Duration::from_millis(500);
+
+/// Options of `openspore skills test`
+pub struct TestOptions {
+    pub args: String,
+    pub timeout: Duration,
+    pub watch: bool,
+    pub record: Option<PathBuf>,
+}
+
+pub fn list(config: AppConfig) {
+    let loader = SkillLoader::new(config);
+    let mut skills: Vec<Arc<dyn Skill>> = loader.summaries().iter().filter_map(|(name, _)| loader.get(name)).collect();
+    skills.sort_by_key(|s| s.name());
+    println!("🧰 [Skills] {} loaded\n", skills.len());
+    for skill in skills {
+        println!("  {:18} | {:6} | {}", skill.name(), skill_kind(skill.as_ref()), preview(skill.description(), 80));
+    }
+    println!("\n  `openspore skills test <name> --args '...'` runs one.");
+}
+
+
+/// Outcome of one run
+struct Run {
+    /// None when it timed out
+    output: Option<Result<String, String>>,
+    duration: Duration,
+    /// The JSON the schema applies to (a script's printed JSON, else the result)
+    payload: Option<Value>,
+    schema_errors: Vec<String>,
+    code: i32,
+}
+
+impl Run {
+    fn outcome(&self) -> &'static str {
+        match self.code {
+            EXIT_OK => "ok",
+            EXIT_FAILED => "failed",
+            EXIT_INVALID => "invalid",
+            _ => "timeout",
+        }
+    }
+}
+
+/// Result JSON, with a script skill's stdout parsed when it is JSON itself
+fn payload_of(output: &str) -> Option<Value> {
+    let result: Value = serde_json::from_str(output.trim()).ok()?;
+    let printed = result.get("stdout")
+        .and_then(|s| s.as_str())
+        .and_then(|s| serde_json::from_str::<Value>(s.trim()).ok());
+    Some(printed.unwrap_or(result))
+}
+
+async fn run_once(skill: &dyn Skill, options: &TestOptions) -> Run {
+    let started = Instant::now();
+    let output = match openspore_skills::check_args(skill, &options.args) {
+        Ok(()) => tokio::time::timeout(options.timeout, skill.execute(&options.args)).await.ok(),
+        Err(invalid) => Some(Err(invalid)),
+    };
+    let duration = started.elapsed();
+
+    let payload = match &output {
+        Some(Ok(text)) => payload_of(text),
+        _ => None,
+    };
+    let schema_errors = match (skill.output_schema(), &payload, &output) {
+        (Some(schema), Some(payload), _) => openspore_skills::schema::validate(payload, &schema),
+        (Some(_), None, Some(Ok(_))) => vec!["$: the result is not JSON".to_string()],
+        _ => Vec::new(),
+    };
+    // `"success": false` in the result, or in the JSON a script printed
+    let failed = |json: &Value| json.get("success") == Some(&Value::Bool(false));
+    let reported_failure = output.as_ref()
+        .and_then(|o| o.as_ref().ok())
+        .and_then(|text| serde_json::from_str::<Value>(text.trim()).ok())
+        .is_some_and(|json| failed(&json))
+        || payload.as_ref().is_some_and(failed);
+
+    let code = match &output {
+        None => EXIT_TIMEOUT,
+        Some(Err(_)) => EXIT_FAILED,
+        Some(Ok(_)) if reported_failure => EXIT_FAILED,
+        Some(Ok(_)) if !schema_errors.is_empty() => EXIT_INVALID,
+        Some(Ok(_)) => EXIT_OK,
+    };
+    Run { output, duration, payload, schema_errors, code }
+}
+
+fn print_run(skill: &dyn Skill, run: &Run, timeout: Duration) {
+    match &run.output {
+        None => println!("⌛ Timed out after {}s", timeout.as_secs()),
+        Some(Err(e)) => println!("❌ Error: {}", e),
+        Some(Ok(text)) => match serde_json::from_str::<Value>(text.trim()) {
+            Ok(json) => println!("{}", serde_json::to_string_pretty(&json).unwrap_or_else(|_| text.clone())),
+            Err(_) => println!("{}", text),
+        },
+    }
+    if let (Some(payload), Some(Ok(text))) = (&run.payload, &run.output)
+        && serde_json::from_str::<Value>(text.trim()).ok().as_ref() != Some(payload)
+    {
+        println!("\n📤 Printed JSON:\n{}", serde_json::to_string_pretty(payload).unwrap_or_default());
+    }
+
+    if skill.output_schema().is_some() && run.output.as_ref().is_some_and(|o| o.is_ok()) {
+        if run.schema_errors.is_empty() {
+            println!("\n✅ Matches the output schema");
+        } else {
+            println!("\n❌ Does not match the output schema:");
+            for error in &run.schema_errors {
+                println!("  - {}", error);
+            }
+        }
+    }
+    let icon = if run.code == EXIT_OK { "✅" } else { "❌" };
+    println!("\n{} {} in {} ms (exit code {})", icon, run.outcome(), run.duration.as_millis(), run.code);
+}
+
+fn record(path: &Path, skill: &dyn Skill, options: &TestOptions, run: &Run) {
+    let (output, error) = match &run.output {
+        Some(Ok(text)) => (Some(text.clone()), None),
+        Some(Err(e)) => (None, Some(e.clone())),
+        None => (None, Some(format!("timed out after {}s", options.timeout.as_secs()))),
+    };
+    let fixture = serde_json::json!({
+        "skill": skill.name(),
+        "args": options.args,
+        "recorded": chrono::Local::now().to_rfc3339(),
+        "duration_ms": run.duration.as_millis() as u64,
+        "outcome": run.outcome(),
+        "exit_code": run.code,
+        "output": output,
+        "error": error,
+        "result": run.payload,
+        "schema_errors": run.schema_errors,
+    });
+    match std::fs::write(path, serde_json::to_string_pretty(&fixture).unwrap_or_default()) {
+        Ok(()) => println!("💾 Recorded to {}", path.display()),
+        Err(e) => println!("❌ Could not record to {}: {}", path.display(), e),
+    }
+}
+
+fn modified(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths.iter().map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok()).collect()
+}
+
+/// Run `name` once (or, with --watch, again after each change); returns the exit code
+pub async fn test(config: AppConfig, name: Option<&str>, options: TestOptions) -> i32 {
+    let Some(name) = name else {
+        println!("Usage: openspore skills test <name> [--args '...'] [--timeout 60] [--watch] [--record fixture.json]");
+        return EXIT_USAGE;
+    };
+    let mut loader = SkillLoader::new(config.clone());
+    let Some(shared) = loader.get(name) else {
+        println!("❌ No skill named \"{}\". `openspore skills list` shows the loaded ones.", name);
+        return EXIT_USAGE;
+    };
+    let skill = shared.as_ref();
+    let sources = skill.source_files();
+    if options.watch && sources.is_empty() {
+        println!("❌ {} is a core skill: it has no source file to watch", skill.name());
+        return EXIT_USAGE;
+    }
+
+    println!("🧪 {} ({}) with args: {}", skill.name(), skill_kind(skill), if options.args.is_empty() { "(none)" } else { &options.args });
+    if skill.destructive() {
+        println!("⚠️ {} changes files or runs commands: this call is real", skill.name());
+    }
+    println!();
+    let run = run_once(skill, &options).await;
+    print_run(skill, &run, options.timeout);
+    if let Some(path) = &options.record {
+        record(path, skill, &options, &run);
+    }
+    if !options.watch {
+        return run.code;
+    }
+
+    println!("\n👀 Watching {} (Ctrl+C to stop)", sources.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "));
+    let mut seen = modified(&sources);
+    loop {
+        tokio::time::sleep(WATCH_POLL).await;
+        let now = modified(&sources);
+        if now == seen {
+            continue;
+        }
+        seen = now;
+        // Reloaded so an edited SKILL.md (description, schema, entrypoint) counts too
+        loader = SkillLoader::new(config.clone());
+        let Some(shared) = loader.get(name) else {
+            println!("\n❌ {} no longer loads; waiting for the next change", name);
+            continue;
+        };
+        let skill = shared.as_ref();
+        println!("\n🔁 Source changed at {}, running again\n", chrono::Local::now().format("%H:%M:%S"));
+        let run = run_once(skill, &options).await;
+        print_run(skill, &run, options.timeout);
+        if let Some(path) = &options.record {
+            record(path, skill, &options, &run);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    /// A skill answering `output` after `delay`, with optional schemas
+    struct Canned {
+        output: Result<&'static str, &'static str>,
+        delay: Duration,
+        args_schema: Option<Value>,
+        output_schema: Option<Value>,
+    }
+
+    impl Canned {
+        fn new(output: Result<&'static str, &'static str>) -> Self {
+            Self { output, delay: Duration::ZERO, args_schema: None, output_schema: None }
+        }
+
+        fn with_output_schema(self) -> Self {
+            Self { output_schema: Some(json!({ "type": "object", "required": ["count"], "properties": { "count": { "type": "integer", "minimum": 0 } } })), ..self }
+        }
+    }
+
+    #[async_trait]
+    impl Skill for Canned {
+        fn name(&self) -> &'static str { "canned" }
+        fn description(&self) -> &'static str { "Canned answers" }
+        fn args_schema(&self) -> Option<Value> { self.args_schema.clone() }
+        fn output_schema(&self) -> Option<Value> { self.output_schema.clone() }
+
+        async fn execute(&self, _args: &str) -> Result<String, String> {
+            tokio::time::sleep(self.delay).await;
+            self.output.map(str::to_string).map_err(str::to_string)
+        }
+    }
+
+    fn options(args: &str) -> TestOptions {
+        TestOptions { args: args.to_string(), timeout: Duration::from_secs(5), watch: false, record: None }
+    }
+
+    async fn code(skill: Canned) -> i32 {
+        run_once(&skill, &options("{}")).await.code
+    }
+
+    #[tokio::test]
+    async fn each_outcome_has_its_exit_code() {
+        assert_eq!(code(Canned::new(Ok(r#"{"success": true}"#))).await, EXIT_OK);
+        assert_eq!(code(Canned::new(Ok("plain text"))).await, EXIT_OK, "no schema, nothing to check");
+        assert_eq!(code(Canned::new(Err("boom"))).await, EXIT_FAILED);
+        assert_eq!(code(Canned::new(Ok(r#"{"success": false, "error": "no"}"#))).await, EXIT_FAILED);
+        assert_eq!(code(Canned::new(Ok(r#"{"count": -1}"#)).with_output_schema()).await, EXIT_INVALID);
+        assert_eq!(code(Canned::new(Ok(r#"{"count": 3}"#)).with_output_schema()).await, EXIT_OK);
+
+        let hung = Canned { delay: Duration::from_secs(30), ..Canned::new(Ok("{}")) };
+        let run = run_once(&hung, &TestOptions { timeout: Duration::from_millis(50), ..options("") }).await;
+        assert_eq!((run.code, run.outcome()), (EXIT_TIMEOUT, "timeout"));
+        assert!(run.output.is_none());
+    }
+
+    #[tokio::test]
+    async fn a_failure_wins_over_a_schema_mismatch() {
+        let run = run_once(&Canned::new(Ok(r#"{"success": false}"#)).with_output_schema(), &options("")).await;
+        assert_eq!(run.code, EXIT_FAILED);
+        assert_eq!(run.schema_errors, ["$: missing required \"count\""]);
+    }
+
+    #[tokio::test]
+    async fn results_that_are_not_json_fail_an_output_schema() {
+        let run = run_once(&Canned::new(Ok("3 files")).with_output_schema(), &options("")).await;
+        assert_eq!(run.code, EXIT_INVALID);
+        assert_eq!(run.schema_errors, ["$: the result is not JSON"]);
+    }
+
+    #[tokio::test]
+    async fn a_script_is_checked_on_the_json_it_prints() {
+        let script = Canned::new(Ok(r#"{"success": true, "stdout": "{\"count\": \"two\"}\n", "exit_code": 0}"#)).with_output_schema();
+        let run = run_once(&script, &options("")).await;
+        assert_eq!(run.payload, Some(json!({ "count": "two" })));
+        assert_eq!(run.schema_errors, ["$.count: expected integer, got string"]);
+        assert_eq!(run.code, EXIT_INVALID);
+
+        let failed = Canned::new(Ok(r#"{"success": true, "stdout": "{\"success\": false}"}"#));
+        assert_eq!(run_once(&failed, &options("")).await.code, EXIT_FAILED, "the printed JSON reports the failure");
+        assert_eq!(payload_of(r#"{"stdout": "not json"}"#), Some(json!({ "stdout": "not json" })));
+    }
+
+    #[tokio::test]
+    async fn arguments_off_the_schema_are_refused_before_running() {
+        let skill = Canned {
+            args_schema: Some(json!({ "type": "object", "required": ["x"], "properties": { "x": { "type": "integer" } }, "additionalProperties": false })),
+            ..Canned::new(Err("should not run"))
+        };
+        let run = run_once(&skill, &options(r#"{"x": "1", "y": 2}"#)).await;
+        assert_eq!(run.code, EXIT_FAILED);
+        let Some(Err(refusal)) = &run.output else { panic!("refused: {:?}", run.output) };
+        let refusal: Value = serde_json::from_str(refusal).unwrap();
+        assert_eq!(refusal["problems"], json!(["$.x: expected integer, got string", "$: unexpected \"y\""]));
+    }
+
+    #[tokio::test]
+    async fn a_run_is_recorded_with_its_outcome() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixture.json");
+        let skill = Canned::new(Ok(r#"{"count": -2}"#)).with_output_schema();
+        let options = TestOptions { record: Some(path.clone()), ..options(r#"{"x": 1}"#) };
+        let run = run_once(&skill, &options).await;
+        record(&path, &skill, &options, &run);
+
+        let fixture: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(fixture["skill"], "canned");
+        assert_eq!(fixture["args"], r#"{"x": 1}"#);
+        assert_eq!((fixture["outcome"].as_str(), fixture["exit_code"].as_i64()), (Some("invalid"), Some(EXIT_INVALID as i64)));
+        assert_eq!(fixture["result"], json!({ "count": -2 }));
+        assert_eq!(fixture["schema_errors"], json!(["$.count: -2 is below the minimum 0"]));
+    }
+
+    #[tokio::test]
+    async fn unknown_skills_and_a_missing_name_are_usage_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig { project_root: dir.path().to_path_buf(), ..Default::default() };
+        assert_eq!(test(config.clone(), None, options("")).await, EXIT_USAGE);
+        assert_eq!(test(config, Some("no_such_skill"), options("")).await, EXIT_USAGE);
+    }
+}
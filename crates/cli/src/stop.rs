@@ -0,0 +1,208 @@
+//! `openspore stop`: terminate the processes this installation started
+//!
+//! Only recorded pids are touched: instances from the instance lock, sub-spores
+//! from the swarm registry and the browser launched with our profile. Each
+//! gets SIGTERM (taskkill on Windows) and is forced after a grace period.
+
+use openspore_core::process::{self, PidFile, Termination};
+use openspore_swarm::SporeRegistry;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+const GRACE: Duration = Duration::from_secs(5);
+
+struct Target {
+    pid: u32,
+    what: String,
+}
+
+/// Recorded pids still running with a command line that matches
+fn recorded(file: &PidFile, matches: impl Fn(&str) -> bool, what: &str) -> Vec<Target> {
+    let own = std::process::id();
+    file.read()
+        .into_iter()
+        .filter(|r| r.pid != own)
+        .filter(|r| process::command_line(r.pid).is_some_and(|cmd| matches(&cmd)))
+        .map(|r| Target { pid: r.pid, what: format!("{} ({})", what, r.label) })
+        .collect()
+}
+
+/// Registered sub-spores still running `think`
+fn spore_targets(registry: &SporeRegistry) -> Vec<Target> {
+    registry.read()
+        .into_iter()
+        .filter(|s| s.pid != std::process::id())
+        .filter(|s| process::command_line(s.pid).is_some_and(|cmd| cmd.contains("think")))
+        .map(|s| Target { pid: s.pid, what: format!("sub-spore (spore:{})", s.role) })
+        .collect()
+}
+
+/// Main browser process started with our profile (helpers carry --type= and exit with it)
+fn browser_targets(root: &Path) -> Vec<Target> {
+    let session_file = root.join("workspace").join("browser_sessions").join("active_session.json");
+    let session: serde_json::Value = std::fs::read_to_string(&session_file)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    let profile = session["user_data_dir"].as_str()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| root.join("workspace").join("browser_profile"));
+    let flag = format!("--user-data-dir={}", profile.display());
+    let ours = |cmd: &str| cmd.contains(&flag) && !cmd.contains("--type=");
+
+    let mut pids: Vec<u32> = session["pid"].as_u64()
+        .map(|p| p as u32)
+        .filter(|p| process::command_line(*p).is_some_and(|cmd| ours(&cmd)))
+        .into_iter()
+        .collect();
+    for (pid, cmd) in process::list_processes() {
+        if ours(&cmd) && !pids.contains(&pid) {
+            pids.push(pid);
+        }
+    }
+
+    pids.into_iter().map(|pid| Target { pid, what: "browser (OpenSpore profile)".to_string() }).collect()
+}
+
+pub fn run(all: bool) {
+    if all {
+        stop_everything();
+        return;
+    }
+
+    let root = openspore_core::path_utils::get_app_root();
+    let instances = PidFile::instances(&root);
+    let spores = SporeRegistry::new(&root);
+
+    println!("🛑 Stopping OpenSpore processes...");
+    let mut targets = recorded(&instances, |cmd| cmd.contains("openspore"), "instance");
+    targets.extend(spore_targets(&spores));
+    targets.extend(browser_targets(&root));
+
+    let mut stopped = 0;
+    for target in &targets {
+        match process::terminate(target.pid, GRACE) {
+            Termination::Terminated => {
+                stopped += 1;
+                println!("  ✅ Stopped {} [pid {}]", target.what, target.pid);
+            }
+            Termination::Killed => {
+                stopped += 1;
+                println!("  ✅ Killed {} [pid {}] (did not exit within {}s)", target.what, target.pid, GRACE.as_secs());
+            }
+            Termination::NotRunning => println!("  · {} [pid {}] had already exited", target.what, target.pid),
+            Termination::Failed(e) => println!("  ❌ Could not stop {} [pid {}]: {}", target.what, target.pid, e),
+        }
+    }
+
+    // Whatever is left in the records is stale now
+    let _ = instances.clear();
+    let _ = spores.clear();
+    let _ = std::fs::remove_file(root.join("workspace").join("browser_sessions").join("active_session.json"));
+
+    if targets.is_empty() {
+        println!("📭 Nothing was running.");
+    } else {
+        println!("✅ Stopped {} of {} process(es).", stopped, targets.len());
+    }
+}
+
+/// The old behaviour: every process mentioning openspore, and every Chrome/Chromium/Brave
+fn stop_everything() {
+    print!("⚠️  --all kills EVERY process whose command line contains \"openspore\" (editors with the repo open included) and ALL Chrome, Chromium and Brave windows. Continue? [y/N] ");
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+        println!("Aborted.");
+        return;
+    }
+
+    println!("🛑 Stopping all OpenSpore instances and browsers...");
+    let _ = Command::new("pkill").args(["-f", "openspore"]).status();
+    let _ = Command::new("pkill").args(["-f", "Google Chrome"]).status();
+    let _ = Command::new("pkill").args(["-f", "Chromium"]).status();
+    let _ = Command::new("pkill").args(["-f", "Brave Browser"]).status();
+
+    let root = openspore_core::path_utils::get_app_root();
+    let _ = PidFile::instances(&root).clear();
+    let _ = SporeRegistry::new(&root).clear();
+    let _ = std::fs::remove_file(root.join("workspace").join("browser_sessions").join("active_session.json"));
+
+    println!("✅ All instances and browsers stopped.");
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use openspore_core::process::ProcessRecord;
+    use openspore_swarm::SporeInfo;
+    use std::process::Stdio;
+
+    /// A dummy `sh` process whose command line ends with `args` (reaped by a thread)
+    fn dummy(args: &[&str]) -> u32 {
+        let mut child = Command::new("sh").args(["-c", "sleep 30; true", "sh"]).args(args)
+            .stdin(Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        std::thread::spawn(move || child.wait());
+        pid
+    }
+
+    fn pids(targets: &[Target]) -> Vec<u32> {
+        targets.iter().map(|t| t.pid).collect()
+    }
+
+    #[test]
+    fn only_recorded_pids_with_a_matching_command_line_are_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PidFile::instances(dir.path());
+        let ours = dummy(&["openspore", "tui"]);
+        let reused = dummy(&["something-else"]);
+        for (pid, label) in [(ours, "tui"), (reused, "tui"), (std::process::id(), "self")] {
+            file.add(ProcessRecord::new(pid, label)).unwrap();
+        }
+
+        let targets = recorded(&file, |cmd| cmd.contains("openspore"), "instance");
+        assert_eq!(pids(&targets), [ours]);
+        assert_eq!(targets[0].what, "instance (tui)");
+        process::kill_all(&[ours, reused]);
+    }
+
+    #[test]
+    fn spores_must_still_be_thinking() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = SporeRegistry::new(dir.path());
+        let thinking = dummy(&["think", "--role", "coder"]);
+        let other = dummy(&["idle"]);
+        for (pid, role) in [(thinking, "coder"), (other, "writer")] {
+            registry.add(SporeInfo { pid, role: role.into(), task: "t".into(), start_time: chrono::Local::now() }).unwrap();
+        }
+
+        let targets = spore_targets(&registry);
+        assert_eq!(pids(&targets), [thinking]);
+        assert_eq!(targets[0].what, "sub-spore (spore:coder)");
+        process::kill_all(&[thinking, other]);
+    }
+
+    #[test]
+    fn browser_is_found_by_profile_without_its_helpers() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = dir.path().join("profile");
+        let flag = format!("--user-data-dir={}", profile.display());
+        let main = dummy(&[&flag]);
+        let helper = dummy(&[&flag, "--type=renderer"]);
+        let elsewhere = dummy(&["--user-data-dir=/some/other/profile"]);
+
+        let sessions = dir.path().join("workspace").join("browser_sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        let session = serde_json::json!({ "pid": main, "user_data_dir": profile });
+        std::fs::write(sessions.join("active_session.json"), session.to_string()).unwrap();
+
+        assert_eq!(pids(&browser_targets(dir.path())), [main]);
+        process::kill_all(&[main, helper, elsewhere]);
+    }
+}
@@ -0,0 +1,87 @@
+//! `openspore storage report|clean|pin`: disk usage of the workspace
+//!
+//! `report` prints the size of each workspace directory and the largest files.
+//! `clean` applies the screenshot and download retention policies (and drops
+//! abandoned chunked writes) right away (the daily maintenance job does the same); `--dry-run` only lists what would
+//! go. `pin <file>` keeps a screenshot or download through housekeeping.
+
+use openspore_core::config::AppConfig;
+use openspore_core::fmt_utils::humanize_bytes;
+use openspore_memory::{HousekeepingReport, MemorySystem, PARTIAL_WRITES_DIR, PARTIAL_WRITES_MAX_AGE};
+use std::path::Path;
+
+fn memory(config: &AppConfig) -> MemorySystem {
+    let state = openspore_core::state::AppState::new(config.clone());
+    MemorySystem::new(&state)
+}
+
+fn shown<'a>(memory: &MemorySystem, path: &'a Path) -> std::borrow::Cow<'a, str> {
+    path.strip_prefix(&memory.memory_root).unwrap_or(path).to_string_lossy()
+}
+
+pub fn report(config: AppConfig, limit: usize) {
+    let memory = memory(&config);
+    let report = memory.storage_report(limit);
+    println!("💾 [Storage] workspace: {}\n", humanize_bytes(report.total));
+    for dir in &report.dirs {
+        println!("  {:>10} | {:>6} file(s) | {}", humanize_bytes(dir.bytes), dir.files, dir.name);
+    }
+    if !report.largest.is_empty() {
+        println!("\n📦 Largest files:");
+        for file in &report.largest {
+            println!(
+                "  {:>10} | {} | {}",
+                humanize_bytes(file.size),
+                chrono::DateTime::<chrono::Local>::from(file.modified).format("%Y-%m-%d"),
+                shown(&memory, &file.path)
+            );
+        }
+    }
+
+    println!("\n🧹 Retention:");
+    for (dir, policy) in [("screenshots", config.screenshots_retention()), ("downloads", config.downloads_retention())] {
+        let size = policy.max_bytes.map(humanize_bytes).unwrap_or_else(|| "unlimited".to_string());
+        let age = policy.max_age.map(|a| format!("{} day(s)", a.as_secs() / 86_400)).unwrap_or_else(|| "unlimited".to_string());
+        println!("  {:14} max {} | max age {}", dir, size, age);
+    }
+    println!("  {:14} abandoned chunked writes after {} day(s)", PARTIAL_WRITES_DIR, PARTIAL_WRITES_MAX_AGE.as_secs() / 86_400);
+}
+
+fn print_housekeeping(memory: &MemorySystem, report: &HousekeepingReport) {
+    let verb = if report.dry_run { "would delete" } else { "deleted" };
+    println!(
+        "  {}: {} {} file(s), {} -> {}",
+        shown(memory, &report.dir),
+        verb,
+        report.deleted.len(),
+        humanize_bytes(report.bytes_before),
+        humanize_bytes(report.bytes_after)
+    );
+    for file in &report.deleted {
+        println!("    - {} ({})", shown(memory, &file.path), humanize_bytes(file.size));
+    }
+    if report.kept_pinned + report.kept_referenced > 0 {
+        println!("    kept: {} pinned, {} referenced by an export", report.kept_pinned, report.kept_referenced);
+    }
+}
+
+pub fn clean(config: AppConfig, dry_run: bool) {
+    let memory = memory(&config);
+    let reports = memory.housekeep_media(config.screenshots_retention(), config.downloads_retention(), dry_run);
+    println!("🧹 [Housekeeping]{}\n", if dry_run { " dry run" } else { "" });
+    for report in &reports {
+        print_housekeeping(&memory, report);
+    }
+}
+
+pub fn pin(config: AppConfig, path: Option<&str>) {
+    let Some(path) = path else {
+        println!("❌ Usage: openspore storage pin <file>");
+        return;
+    };
+    let memory = memory(&config);
+    match memory.pin_file(&openspore_core::path_utils::get_path(path)) {
+        Ok(path) => println!("📌 Pinned {}", shown(&memory, &path)),
+        Err(e) => println!("❌ {}", e),
+    }
+}
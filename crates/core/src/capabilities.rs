@@ -0,0 +1,71 @@
+//! External tools OpenSpore can do without, probed once per process
+//!
+//! git versions the workspace (snapshots before consolidation, restoring
+//! deleted files, the sync merge driver). Without it the memory system keeps
+//! tarball snapshots in workspace/.snapshots instead.
+
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// How to get git, for errors and the doctor
+pub const GIT_INSTALL_HINT: &str =
+    "Install git (sudo apt install -y git, brew install git, or https://git-scm.com/downloads) and restart OpenSpore";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `git --version` runs
+    pub git: bool,
+}
+
+impl Capabilities {
+    fn probe() -> Self {
+        Self {
+            git: Command::new("git").arg("--version").output().is_ok_and(|o| o.status.success()),
+        }
+    }
+}
+
+/// The capabilities of this process (probed on first use)
+pub fn capabilities() -> Capabilities {
+    static PROBED: OnceLock<Capabilities> = OnceLock::new();
+    *PROBED.get_or_init(Capabilities::probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Set in the child process of without_git()
+    const NO_GIT: &str = "OPENSPORE_TEST_NO_GIT";
+
+    /// Run the test `name` of this binary again, with the directories holding
+    /// git left out of PATH, and check that it passed there
+    fn assert_passes_without_git(name: &str) {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let stripped = std::env::join_paths(std::env::split_paths(&path).filter(|dir| !dir.join("git").exists())).unwrap();
+        let child = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", name])
+            .env("PATH", stripped)
+            .env(NO_GIT, "1")
+            .output()
+            .unwrap();
+        let report = String::from_utf8_lossy(&child.stdout);
+        assert!(child.status.success() && report.contains("1 passed"), "{}{}", report, String::from_utf8_lossy(&child.stderr));
+    }
+
+    #[test]
+    fn git_is_missing_from_a_path_without_it() {
+        if std::env::var_os(NO_GIT).is_some() {
+            assert!(!capabilities().git);
+            assert!(!Capabilities::probe().git);
+            return;
+        }
+        assert_passes_without_git("capabilities::tests::git_is_missing_from_a_path_without_it");
+    }
+
+    #[test]
+    fn the_probe_is_cached() {
+        assert_eq!(capabilities(), capabilities());
+        assert_eq!(crate::state::AppState::new(Default::default()).capabilities, capabilities());
+    }
+}
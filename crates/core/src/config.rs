@@ -1,7 +1,144 @@
-use config::{Config, ConfigError, Environment, File};
-use serde::Deserialize;
+use config::builder::DefaultState;
+use config::{Config, ConfigBuilder, ConfigError, Environment, File};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::OnceLock;
 
-#[derive(Debug, Deserialize, Clone)]
+/// Default OpenAI-compatible endpoint (OpenRouter)
+pub const DEFAULT_LLM_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// Default local Ollama server
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Sub-spores one process runs at once unless OPENSPORE_SWARM_MAX says otherwise
+pub const DEFAULT_SWARM_MAX: usize = 6;
+
+/// Larger OPENSPORE_SWARM_MAX values are treated as typos
+pub const MAX_SWARM_MAX: usize = 64;
+
+/// Seconds a sub-spore may run unless SPORE_TIMEOUT_SECS says otherwise
+pub const DEFAULT_SPORE_TIMEOUT_SECS: u64 = 180;
+
+/// Longest accepted SPORE_TIMEOUT_SECS, well below the swarm's reap age so a
+/// spore that is still within its timeout is never reaped as an orphan
+pub const MAX_SPORE_TIMEOUT_SECS: u64 = 1800;
+
+/// Share of a search score that memory usefulness may add or take away
+pub const DEFAULT_USEFULNESS_WEIGHT: f64 = 0.3;
+
+/// Age in days at which exchanges are consolidated unless CONSOLIDATE_AFTER_DAYS says otherwise
+pub const DEFAULT_CONSOLIDATE_AFTER_DAYS: u32 = 7;
+
+/// Exchange files per consolidation run unless CONSOLIDATE_MAX_FILES says otherwise
+pub const DEFAULT_CONSOLIDATE_MAX_FILES: usize = 50;
+
+/// Failure post-mortems kept in workspace/debug/failures unless FAILURE_BUNDLES_MAX says otherwise
+pub const DEFAULT_FAILURE_BUNDLES_MAX: usize = 20;
+
+/// Size (MB) workspace/screenshots may reach unless SCREENSHOTS_MAX_MB says otherwise
+pub const DEFAULT_SCREENSHOTS_MAX_MB: u64 = 200;
+
+/// Size (MB) workspace/downloads may reach unless DOWNLOADS_MAX_MB says otherwise
+pub const DEFAULT_DOWNLOADS_MAX_MB: u64 = 1000;
+
+/// Age in days at which screenshots and downloads are deleted unless
+/// SCREENSHOTS_MAX_AGE_DAYS / DOWNLOADS_MAX_AGE_DAYS say otherwise
+pub const DEFAULT_MEDIA_MAX_AGE_DAYS: u32 = 30;
+
+/// Minutes between heartbeats unless `[autonomy]` or HEARTBEAT_INTERVAL_MINUTES says otherwise
+pub const DEFAULT_HEARTBEAT_MINUTES: u64 = 120;
+
+/// Minutes between journal syntheses (a day: every evening at 22:00)
+pub const DEFAULT_JOURNAL_MINUTES: u64 = 1440;
+
+/// Minutes between autonomy engine (Tree of Thought) runs when AUTONOMY_ENABLED
+pub const DEFAULT_ENGINE_MINUTES: u64 = 120;
+
+/// Context window assumed for models missing from MODEL_CONTEXT_TOKENS when
+/// LLM_CONTEXT_TOKENS is not set
+pub const DEFAULT_CONTEXT_TOKENS: usize = 128_000;
+
+/// Reply length requested from the model unless LLM_MAX_TOKENS says otherwise
+pub const DEFAULT_LLM_MAX_TOKENS: usize = 16_384;
+
+/// Which backend Brain::complete talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LlmProvider {
+    #[default]
+    OpenRouter,
+    Ollama,
+}
+
+impl std::str::FromStr for LlmProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "openrouter" | "openai" | "" => Ok(LlmProvider::OpenRouter),
+            "ollama" => Ok(LlmProvider::Ollama),
+            other => Err(format!("Unknown LLM provider: {}", other)),
+        }
+    }
+}
+
+impl LlmProvider {
+    /// Lowercase name, as recorded in exchange frontmatter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmProvider::OpenRouter => "openrouter",
+            LlmProvider::Ollama => "ollama",
+        }
+    }
+}
+
+/// A web search backend of the web_search skill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchProvider {
+    /// Brave Search API (BRAVE_SEARCH_API_KEY)
+    Brave,
+    /// SerpAPI's Google results (SERPAPI_API_KEY)
+    SerpApi,
+    /// A SearXNG instance with the JSON format enabled (SEARXNG_URL)
+    SearxNg,
+    /// DuckDuckGo's HTML page: no key, but scraped and sometimes answered with a captcha
+    DuckDuckGo,
+}
+
+impl std::str::FromStr for SearchProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "brave" => Ok(SearchProvider::Brave),
+            "serpapi" => Ok(SearchProvider::SerpApi),
+            "searxng" | "searx" => Ok(SearchProvider::SearxNg),
+            "duckduckgo" | "ddg" => Ok(SearchProvider::DuckDuckGo),
+            other => Err(format!("Unknown search provider: {}", other)),
+        }
+    }
+}
+
+impl SearchProvider {
+    pub const ALL: [SearchProvider; 4] = [SearchProvider::Brave, SearchProvider::SerpApi, SearchProvider::SearxNg, SearchProvider::DuckDuckGo];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchProvider::Brave => "brave",
+            SearchProvider::SerpApi => "serpapi",
+            SearchProvider::SearxNg => "searxng",
+            SearchProvider::DuckDuckGo => "duckduckgo",
+        }
+    }
+}
+
+/// A key left at its .env.example placeholder
+fn is_placeholder(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value.starts_with("your_")
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct AppConfig {
     #[serde(alias = "OPENROUTER_API_KEY")]
     pub openrouter_api_key: String,
@@ -9,48 +146,900 @@ pub struct AppConfig {
     #[serde(alias = "TELEGRAM_BOT_TOKEN")]
     pub telegram_bot_token: Option<String>,
 
+    /// Comma-separated chat ids; the first is the owner's (see telegram_allowed_users())
     #[serde(alias = "TELEGRAM_ALLOWED_USERS")]
     pub telegram_allowed_users: Option<String>,
 
+    /// Legacy: a sub-spore process started with IS_SPORE in its environment
+    /// (the swarm now passes `think --spore`; see Brain's SporeContext)
+    #[serde(default, alias = "IS_SPORE")]
+    pub is_spore: bool,
+
+    /// Legacy: the role of such a process (SPORE_ROLE)
+    #[serde(alias = "SPORE_ROLE")]
+    pub spore_role: Option<String>,
+
     #[serde(alias = "AUTONOMY_ENABLED")]
     pub autonomy_enabled: bool,
 
-    #[serde(alias = "OPENROUTER_MODEL")]
+    /// Apply trivial, allowlisted autonomy proposals without waiting for approval
+    #[serde(alias = "AUTONOMY_AUTO_APPLY")]
+    pub autonomy_auto_apply: bool,
+
+    /// Days before learned memories expire (default 90, 0 keeps them forever)
+    #[serde(alias = "LEARNED_TTL_DAYS")]
+    pub learned_ttl_days: Option<u32>,
+
+    /// Days before exchanges are folded into daily digests by the nightly job (default 7, 0 disables the job)
+    #[serde(alias = "CONSOLIDATE_AFTER_DAYS")]
+    pub consolidate_after_days: Option<u32>,
+
+    /// Exchange files one consolidation run reads at most (default 50)
+    #[serde(alias = "CONSOLIDATE_MAX_FILES")]
+    pub consolidate_max_files: Option<usize>,
+
+    /// Post-mortems of failed cycles kept in workspace/debug/failures (default 20, 0 disables them)
+    #[serde(alias = "FAILURE_BUNDLES_MAX")]
+    pub failure_bundles_max: Option<usize>,
+
+    /// Size of workspace/screenshots in MB before the oldest are deleted (default 200, 0 for no limit)
+    #[serde(alias = "SCREENSHOTS_MAX_MB")]
+    pub screenshots_max_mb: Option<u64>,
+
+    /// Days screenshots are kept (default 30, 0 keeps them until the size limit)
+    #[serde(alias = "SCREENSHOTS_MAX_AGE_DAYS")]
+    pub screenshots_max_age_days: Option<u32>,
+
+    /// Size of workspace/downloads in MB before the oldest are deleted (default 1000, 0 for no limit)
+    #[serde(alias = "DOWNLOADS_MAX_MB")]
+    pub downloads_max_mb: Option<u64>,
+
+    /// Days downloads are kept (default 30, 0 keeps them until the size limit)
+    #[serde(alias = "DOWNLOADS_MAX_AGE_DAYS")]
+    pub downloads_max_age_days: Option<u32>,
+
+    /// Weight of usefulness (cited / injected) in search ranking, 0-1 (default 0.3, 0 disables)
+    #[serde(alias = "USEFULNESS_WEIGHT")]
+    pub usefulness_weight: Option<f64>,
+
+    /// Encrypt memory files at rest with a key derived from OPENSPORE_MEM_KEY
+    #[serde(alias = "ENCRYPT_MEMORY")]
+    pub encrypt_memory: Option<bool>,
+
+    /// Passphrase for encrypted memory (also needed to read it back)
+    #[serde(alias = "OPENSPORE_MEM_KEY")]
+    pub mem_key: Option<String>,
+
+    /// Retries of a failed daily journal (default 3)
+    #[serde(alias = "JOURNAL_RETRIES")]
+    pub journal_retries: Option<u32>,
+
+    /// Minutes between journal retries, doubled after each attempt (default 30)
+    #[serde(alias = "JOURNAL_RETRY_BACKOFF_MINUTES")]
+    pub journal_retry_backoff_minutes: Option<u64>,
+
+    /// Retries of a failed heartbeat (default 0: the next heartbeat is 2 hours away)
+    #[serde(alias = "HEARTBEAT_RETRIES")]
+    pub heartbeat_retries: Option<u32>,
+
+    /// Minutes between heartbeat retries, doubled after each attempt (default 15)
+    #[serde(alias = "HEARTBEAT_RETRY_BACKOFF_MINUTES")]
+    pub heartbeat_retry_backoff_minutes: Option<u64>,
+
+    #[serde(alias = "OPENROUTER_MODEL", alias = "openrouter_model")]
     pub model: Option<String>,
 
+    /// Vision-capable model used to describe images sent over Telegram (optional)
+    #[serde(alias = "OPENROUTER_MODEL_VISION", alias = "openrouter_model_vision")]
+    pub model_vision: Option<String>,
+
+    /// Cheap model for auxiliary calls such as follow-up suggestions (defaults to OPENROUTER_MODEL)
+    #[serde(alias = "OPENROUTER_MODEL_FAST", alias = "openrouter_model_fast")]
+    pub model_fast: Option<String>,
+
+    /// Comma-separated models tried in order when the primary model is unavailable
+    #[serde(alias = "OPENROUTER_MODEL_REASONING_FALLBACKS", alias = "openrouter_model_reasoning_fallbacks")]
+    pub model_fallbacks: Option<String>,
+
+    /// OpenAI-compatible base URL (e.g. http://localhost:11434/v1 for Ollama)
+    #[serde(alias = "OPENSPORE_LLM_BASE_URL", alias = "openspore_llm_base_url")]
+    pub llm_base_url: Option<String>,
+
+    /// "openrouter" (default) or "ollama"
+    #[serde(alias = "LLM_PROVIDER")]
+    pub llm_provider: Option<String>,
+
+    #[serde(alias = "OLLAMA_URL")]
+    pub ollama_url: Option<String>,
+
+    #[serde(alias = "OLLAMA_MODEL_FAST")]
+    pub ollama_model_fast: Option<String>,
+
+    #[serde(alias = "OLLAMA_MODEL_REASONING")]
+    pub ollama_model_reasoning: Option<String>,
+
+    #[serde(alias = "OLLAMA_MODEL_REASONING_FALLBACKS")]
+    pub ollama_model_reasoning_fallbacks: Option<String>,
+
+    /// Per-request timeout for LLM calls in seconds (default 120)
+    #[serde(alias = "LLM_TIMEOUT_SECS")]
+    pub llm_timeout_secs: Option<u64>,
+
+    /// Sampling temperature sent with every completion (default 0.2)
+    #[serde(alias = "LLM_TEMPERATURE")]
+    pub llm_temperature: Option<f64>,
+
+    /// Nucleus sampling; omitted from requests when unset
+    #[serde(alias = "LLM_TOP_P")]
+    pub llm_top_p: Option<f64>,
+
+    /// Embedding model for semantic memory search (disabled when unset)
+    #[serde(alias = "EMBEDDING_MODEL")]
+    pub embedding_model: Option<String>,
+
+    /// OpenAI-compatible endpoint for embeddings (defaults to the LLM base URL)
+    #[serde(alias = "EMBEDDING_BASE_URL")]
+    pub embedding_base_url: Option<String>,
+
+    /// Key for the embeddings endpoint (defaults to OPENROUTER_API_KEY)
+    #[serde(alias = "EMBEDDING_API_KEY")]
+    pub embedding_api_key: Option<String>,
+
+    /// Speech-to-text model for Telegram voice messages, e.g. "whisper-1" (disabled when unset)
+    #[serde(alias = "TRANSCRIPTION_MODEL")]
+    pub transcription_model: Option<String>,
+
+    /// OpenAI-compatible endpoint for /audio/transcriptions (defaults to the LLM base URL)
+    #[serde(alias = "TRANSCRIPTION_BASE_URL")]
+    pub transcription_base_url: Option<String>,
+
+    /// Key for the transcription endpoint (defaults to OPENROUTER_API_KEY)
+    #[serde(alias = "TRANSCRIPTION_API_KEY")]
+    pub transcription_api_key: Option<String>,
+
+    /// Tracing filter, e.g. "info,openspore_brain=debug" (RUST_LOG takes precedence)
+    #[serde(alias = "LOG_FILTER")]
+    pub log_filter: Option<String>,
+
     #[serde(alias = "SAFE_MODE_ENABLED")]
     pub safe_mode_enabled: bool,
 
+    /// Comma-separated commands the exec skill may run (all when unset):
+    /// command prefixes like `git status`, or `re:<regex>` matched against each command
+    #[serde(alias = "EXEC_ALLOWLIST")]
+    pub exec_allowlist: Option<String>,
+
+    /// Comma-separated commands the exec skill refuses, on top of the built-in
+    /// rules (sudo, shutdown, rm -rf /, curl | sh...); same syntax as EXEC_ALLOWLIST
+    #[serde(alias = "EXEC_DENYLIST")]
+    pub exec_denylist: Option<String>,
+
+    /// Plan mode (`--dry-run`, `/plan`) still runs read-only skills (read_file, list_dir, search...)
+    #[serde(default, alias = "DRY_RUN_READ_ONLY")]
+    pub dry_run_read_only: bool,
+
+    /// Refuse exec commands that reference paths outside the project root
+    #[serde(default, alias = "EXEC_CONFINE_TO_WORKSPACE")]
+    pub exec_confine_to_workspace: bool,
+
+    /// Comma-separated variables from .env (or secret-looking ones) still passed to exec commands
+    #[serde(alias = "EXEC_ENV_PASSTHROUGH")]
+    pub exec_env_passthrough: Option<String>,
+
     #[serde(alias = "BROWSER_TYPE")]
     pub browser_type: Option<String>,
 
+    /// Comma-separated web search providers tried in order (brave, serpapi, searxng,
+    /// duckduckgo); default: the configured ones in that order, then duckduckgo
+    #[serde(alias = "SEARCH_PROVIDER")]
+    pub search_provider: Option<String>,
+
+    #[serde(alias = "BRAVE_SEARCH_API_KEY")]
+    pub brave_search_api_key: Option<String>,
+
+    #[serde(alias = "SERPAPI_API_KEY")]
+    pub serpapi_api_key: Option<String>,
+
+    /// Base URL of a SearXNG instance, e.g. http://localhost:8888
+    #[serde(alias = "SEARXNG_URL")]
+    pub searxng_url: Option<String>,
+
+    /// Tool call format: "bracket" (default), "native" (OpenAI tools API) or "auto" (per model)
+    #[serde(alias = "TOOL_CALLING")]
+    pub tool_calling: Option<String>,
+
+    /// Context window of the model in tokens, for models missing from MODEL_CONTEXT_TOKENS (default 128000)
+    #[serde(alias = "LLM_CONTEXT_TOKENS")]
+    pub llm_context_tokens: Option<usize>,
+
+    /// Comma-separated `model=tokens` context windows, e.g. `openai/gpt-4o=128000,anthropic/claude-3.5-sonnet=200000`
+    #[serde(alias = "MODEL_CONTEXT_TOKENS")]
+    pub model_context_tokens: Option<String>,
+
+    /// Longest reply requested from the model; the system prompt leaves room for it (default 16384)
+    #[serde(alias = "LLM_MAX_TOKENS")]
+    pub llm_max_tokens: Option<usize>,
+
+    /// Show <CONTEXT_STATS> in the system prompt (default true)
+    #[serde(alias = "CONTEXT_STATS_ENABLED")]
+    pub context_stats_enabled: Option<bool>,
+
+    /// Seconds between two learn() extractions across the process; turns in between are not learned (default 30, 0 disables)
+    #[serde(alias = "LEARN_MIN_INTERVAL_SECS")]
+    pub learn_min_interval_secs: Option<u64>,
+
+    /// Word overlap (0-1) above which a new exchange repeating a recent one is not saved (default 0.9, 0 disables)
+    #[serde(alias = "CONTEXT_DEDUP_THRESHOLD")]
+    pub context_dedup_threshold: Option<f32>,
+
+    /// Quiet time before a changed file is analyzed; repeated events for it are coalesced (default 2000)
+    #[serde(alias = "WATCHMAN_DEBOUNCE_MS")]
+    pub watchman_debounce_ms: Option<u64>,
+
+    /// Files first changed within this window of each other are analyzed in one prompt (default 5000)
+    #[serde(alias = "WATCHMAN_BATCH_WINDOW_MS")]
+    pub watchman_batch_window_ms: Option<u64>,
+
+    /// Most files per analysis prompt (default 8)
+    #[serde(alias = "WATCHMAN_MAX_BATCH")]
+    pub watchman_max_batch: Option<usize>,
+
+    /// Follow-up suggestions after answers: "off" (default), "trailer" (asked of the main completion) or "model" (extra Fast-model call)
+    #[serde(alias = "FOLLOWUP_SUGGESTIONS")]
+    pub followup_suggestions: Option<String>,
+
+    /// "auto" (default): general chat gets a slim prompt without skills, memory search or tools; "full" never slims
+    #[serde(alias = "PROMPT_MODE")]
+    pub prompt_mode: Option<String>,
+
+    /// Ask the Fast model to confirm that a message is general chat before slimming its prompt
+    #[serde(default, alias = "PROMPT_MODE_LLM_CHECK")]
+    pub prompt_mode_llm_check: bool,
+
+    /// Character budget for detailed skill descriptions in the system prompt
+    #[serde(alias = "SKILL_PROMPT_BUDGET")]
+    pub skill_prompt_budget: Option<usize>,
+
+    /// Instructions a `.wasm` plugin skill may run per call (default 1000000000)
+    #[serde(alias = "WASM_SKILL_FUEL")]
+    pub wasm_skill_fuel: Option<u64>,
+
+    /// Linear memory a `.wasm` plugin skill may grow to, in MB (default 64)
+    #[serde(alias = "WASM_SKILL_MEMORY_MB")]
+    pub wasm_skill_memory_mb: Option<u64>,
+
+    /// Ask before running tools: "off" (default), "destructive" (exec, file writes, purge) or "all"
+    #[serde(alias = "APPROVAL_MODE")]
+    pub approval_mode: Option<String>,
+
+    /// Comma-separated gated tools that still run when nobody can approve
+    /// (cron, autonomy, sub-spores): `write_file` or `exec:git status` (command prefix)
+    #[serde(alias = "APPROVAL_ALLOW")]
+    pub approval_allow: Option<String>,
+
+    /// Comma-separated skills the agent may use; the others are left out of
+    /// its prompt and refused (all when unset)
+    #[serde(alias = "ALLOWED_SKILLS")]
+    pub allowed_skills: Option<String>,
+
+    /// Comma-separated skills left out of the prompt and refused, whatever ALLOWED_SKILLS says
+    #[serde(alias = "DENIED_SKILLS")]
+    pub denied_skills: Option<String>,
+
+    /// Comma-separated skills whose every call waits for the user's approval, whatever APPROVAL_MODE says
+    #[serde(alias = "CONFIRM_SKILLS")]
+    pub confirm_skills: Option<String>,
+
+    /// Sub-spores one process runs at once; further delegations queue (default 6)
+    #[serde(alias = "OPENSPORE_SWARM_MAX", alias = "openspore_swarm_max")]
+    pub swarm_max_concurrent: Option<usize>,
+
+    /// Seconds a sub-spore may run before it is killed (default 180)
+    #[serde(alias = "SPORE_TIMEOUT_SECS")]
+    pub spore_timeout_secs: Option<u64>,
+
+    /// `[tui]` section of openspore.toml
+    #[serde(default)]
+    pub tui: TuiConfig,
+
+    /// `[autonomy]` section of openspore.toml
+    #[serde(default)]
+    pub autonomy: AutonomySchedule,
+
+    /// `[watchman]` section of openspore.toml
+    #[serde(default)]
+    pub watchman: WatchmanConfig,
+
+    /// Overrides `[autonomy] heartbeat_minutes`
+    #[serde(alias = "HEARTBEAT_INTERVAL_MINUTES")]
+    pub heartbeat_interval_minutes: Option<u64>,
+
+    /// Overrides `[autonomy] journal_minutes`
+    #[serde(alias = "JOURNAL_INTERVAL_MINUTES")]
+    pub journal_interval_minutes: Option<u64>,
+
+    /// Overrides `[autonomy] engine_minutes`
+    #[serde(alias = "AUTONOMY_ENGINE_INTERVAL_MINUTES")]
+    pub autonomy_engine_interval_minutes: Option<u64>,
+
     #[serde(skip)]
     pub project_root: std::path::PathBuf,
 }
 
-impl AppConfig {
-    pub fn load() -> Result<Self, ConfigError> {
-        // 1. Try standard dotenv discovery from current dir
-        if let Err(_) = dotenvy::dotenv() {
-            // 2. Fallback: Try explicitly from resolved OPENSPORE_ROOT
-            let root = crate::path_utils::get_app_root();
-            let path = root.join(".env");
-            if path.exists() {
-                 let _ = dotenvy::from_path(&path);
-            }
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TuiConfig {
+    #[serde(default)]
+    pub attention: AttentionConfig,
+}
+
+/// `[tui.attention]`: event kind -> "bell" | "osc9" | "title" | "none"
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct AttentionConfig {
+    pub answer_ready: Option<String>,
+    pub approval_needed: Option<String>,
+    pub error: Option<String>,
+    /// Minimum delay between two signals (default 3000ms)
+    pub min_interval_ms: Option<u64>,
+}
+
+/// `[autonomy]`: minutes between scheduled runs. Defaults are set by load();
+/// 0 (or a key missing from a hand-built config) disables the task. See
+/// crate::schedule for cron expressions, switches and quiet hours.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct AutonomySchedule {
+    #[serde(default)]
+    pub heartbeat_minutes: u64,
+    #[serde(default)]
+    pub journal_minutes: u64,
+    /// Autonomy engine (Tree of Thought proposals), only with AUTONOMY_ENABLED
+    #[serde(default)]
+    pub engine_minutes: u64,
+    /// Cron expressions ("0 */2 * * *"); set, they replace the minutes
+    pub heartbeat_cron: Option<String>,
+    pub journal_cron: Option<String>,
+    pub engine_cron: Option<String>,
+    /// Per-task switches (on unless false)
+    pub heartbeat_enabled: Option<bool>,
+    pub journal_enabled: Option<bool>,
+    pub engine_enabled: Option<bool>,
+    /// "23:00-08:00": scheduled runs send no routine Telegram messages
+    pub quiet_hours: Option<String>,
+}
+
+/// `[watchman]`: directories watched besides the project root and the
+/// hourly analysis budget. See crate::watch for how entries are resolved.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct WatchmanConfig {
+    /// LLM analyses per hour across all watched directories (unlimited when missing or 0)
+    pub analyses_per_hour: Option<u32>,
+    /// `[[watchman.watch]]` entries
+    #[serde(default)]
+    pub watch: Vec<WatchEntryConfig>,
+}
+
+/// One `[[watchman.watch]]` entry
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct WatchEntryConfig {
+    /// Directory; `~` expands, relative paths start at the app root
+    pub path: String,
+    /// Label in logs and the heartbeat (default: the directory name)
+    pub name: Option<String>,
+    /// Extensions of the files analyzed, e.g. [".md", "rs"] (default .md, .txt, .json)
+    pub extensions: Option<Vec<String>>,
+    /// Ignore globs on top of the built-in ones, .watchmanignore and .gitignore
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// "learn" (default), "journal" or "off"
+    pub learning: Option<String>,
+    /// Largest share (0-1) of `analyses_per_hour` this directory may use
+    pub budget_share: Option<f64>,
+}
+
+/// Limits of a housekept directory (screenshots, downloads); None is unlimited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_bytes: Option<u64>,
+    pub max_age: Option<std::time::Duration>,
+}
+
+impl RetentionPolicy {
+    fn new(max_mb: u64, max_age_days: u32) -> Self {
+        Self {
+            max_bytes: (max_mb > 0).then(|| max_mb.saturating_mul(1024 * 1024)),
+            max_age: (max_age_days > 0).then(|| std::time::Duration::from_secs(max_age_days as u64 * 24 * 3600)),
         }
+    }
 
-        let builder = Config::builder()
-            .set_default("autonomy_enabled", false)?
-            .set_default("safe_mode_enabled", false)?
-            .add_source(File::with_name("openspore").required(false))
-            .add_source(Environment::default());
+    pub fn is_unlimited(&self) -> bool {
+        self.max_bytes.is_none() && self.max_age.is_none()
+    }
+}
 
-        let mut config: Self = builder.build()?.try_deserialize()?;
+/// Longer schedule intervals (a year) are treated as typos and capped
+const MAX_SCHEDULE_MINUTES: u64 = 525_600;
+
+fn minutes(value: u64) -> Option<std::time::Duration> {
+    (value > 0).then(|| std::time::Duration::from_secs(value.min(MAX_SCHEDULE_MINUTES) * 60))
+}
+
+/// Lowercase skill names of a comma-separated list
+fn skill_names(list: &Option<String>) -> Vec<String> {
+    list.as_deref().unwrap_or_default()
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// A configuration value that doesn't parse or is out of range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// The variable, e.g. LLM_TIMEOUT_SECS
+    pub key: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// The .env file in effect: the first one in the current directory or its
+/// parents, else the one in the app root
+pub fn env_file_path() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok();
+    cwd.iter()
+        .flat_map(|dir| dir.ancestors())
+        .map(|dir| dir.join(".env"))
+        .find(|p| p.is_file())
+        .or_else(|| Some(crate::path_utils::get_app_root().join(".env")).filter(|p| p.is_file()))
+}
+
+/// Variables the configuration is read from: the process environment, with
+/// the .env file filling in what the real environment doesn't set. The file
+/// is read again on each call so a reload sees its edits; only the first call
+/// copies it into the process environment (for code reading variables directly).
+fn environment() -> config::Map<String, String> {
+    /// Keys the first call copied from the file, stale in the process environment since
+    static FROM_FILE: OnceLock<HashSet<String>> = OnceLock::new();
+
+    let file: Vec<(String, String)> = env_file_path()
+        .and_then(|path| dotenvy::from_path_iter(path).ok())
+        .map(|vars| vars.filter_map(Result::ok).collect())
+        .unwrap_or_default();
+    let from_file = FROM_FILE.get_or_init(|| {
+        let copied = file.iter().filter(|(key, _)| std::env::var_os(key).is_none()).map(|(key, _)| key.clone()).collect();
+        if let Some(path) = env_file_path() {
+            let _ = dotenvy::from_path(path);
+        }
+        copied
+    });
+
+    let mut vars: config::Map<String, String> = std::env::vars().filter(|(key, _)| !from_file.contains(key)).collect();
+    for (key, value) in file {
+        vars.entry(key).or_insert(value);
+    }
+    vars
+}
+
+fn builder(vars: config::Map<String, String>) -> Result<ConfigBuilder<DefaultState>, ConfigError> {
+    Ok(Config::builder()
+        .set_default("openrouter_api_key", "")?
+        .set_default("autonomy_enabled", false)?
+        .set_default("autonomy_auto_apply", false)?
+        .set_default("safe_mode_enabled", false)?
+        .set_default("autonomy.heartbeat_minutes", DEFAULT_HEARTBEAT_MINUTES)?
+        .set_default("autonomy.journal_minutes", DEFAULT_JOURNAL_MINUTES)?
+        .set_default("autonomy.engine_minutes", DEFAULT_ENGINE_MINUTES)?
+        .add_source(File::with_name("openspore").required(false))
+        .add_source(Environment::default().source(Some(vars))))
+}
+
+/// Accepted values of the free-text switches
+const CHOICES: [(&str, &[&str]); 4] = [
+    ("TOOL_CALLING", &["bracket", "native", "auto"]),
+    ("PROMPT_MODE", &["auto", "full"]),
+    ("FOLLOWUP_SUGGESTIONS", &["off", "trailer", "model"]),
+    ("APPROVAL_MODE", &["off", "destructive", "all"]),
+];
+
+impl AppConfig {
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_vars(environment())
+    }
+
+    fn from_vars(vars: config::Map<String, String>) -> Result<Self, ConfigError> {
+        let mut config: Self = builder(vars)?.build()?.try_deserialize()?;
 
         // Set project_root
         config.project_root = crate::path_utils::get_app_root();
 
         Ok(config)
     }
+
+    /// load() that leaves out variables whose value doesn't parse (their
+    /// default applies) and reports them, along with what validate() finds.
+    /// Fails only when the configuration can't be read at all (a malformed
+    /// openspore.toml).
+    pub fn load_with_problems() -> Result<(Self, Vec<ConfigProblem>), ConfigError> {
+        let mut vars = environment();
+        let mut problems = Vec::new();
+        loop {
+            match Self::from_vars(vars.clone()) {
+                Ok(config) => {
+                    problems.extend(config.validate());
+                    return Ok((config, problems));
+                }
+                Err(ConfigError::Type { key: Some(key), unexpected, expected, origin }) => {
+                    // Variables are matched case-insensitively; a key from openspore.toml can't be left out
+                    let Some(var) = vars.keys().find(|v| v.eq_ignore_ascii_case(&key)).cloned() else {
+                        return Err(ConfigError::Type { key: Some(key), unexpected, expected, origin });
+                    };
+                    vars.remove(&var);
+                    problems.push(ConfigProblem { key: var, message: format!("{} is not {}, ignored", unexpected, expected) });
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Values that parse but can't be right: unknown switches, out-of-range
+    /// numbers, malformed lists and URLs. The schedule is checked by
+    /// Schedule::from_config and encryption by the doctor.
+    pub fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        let mut problem = |key: &str, message: String| problems.push(ConfigProblem { key: key.to_string(), message });
+
+        if let Some(provider) = self.llm_provider.as_deref()
+            && provider.parse::<LlmProvider>().is_err()
+        {
+            problem("LLM_PROVIDER", format!("\"{}\" is not openrouter or ollama", provider));
+        }
+
+        for name in self.search_provider.as_deref().unwrap_or_default().split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if name.parse::<SearchProvider>().is_err() {
+                problem("SEARCH_PROVIDER", format!("\"{}\" is not brave, serpapi, searxng or duckduckgo", name));
+            }
+        }
+
+        let switches = [&self.tool_calling, &self.prompt_mode, &self.followup_suggestions, &self.approval_mode];
+        for ((key, allowed), value) in CHOICES.iter().zip(switches) {
+            if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+                && !allowed.contains(&value.to_lowercase().as_str())
+            {
+                problem(key, format!("\"{}\" is not one of {}", value, allowed.join(", ")));
+            }
+        }
+
+        let urls = [
+            ("OPENSPORE_LLM_BASE_URL", &self.llm_base_url),
+            ("OLLAMA_URL", &self.ollama_url),
+            ("EMBEDDING_BASE_URL", &self.embedding_base_url),
+            ("TRANSCRIPTION_BASE_URL", &self.transcription_base_url),
+            ("SEARXNG_URL", &self.searxng_url),
+        ];
+        for (key, url) in urls {
+            if let Some(url) = url.as_deref().map(str::trim).filter(|u| !u.is_empty())
+                && !url.starts_with("http://") && !url.starts_with("https://")
+            {
+                problem(key, format!("\"{}\" is not an http(s) URL", url));
+            }
+        }
+
+        if let Some(max) = self.swarm_max_concurrent.filter(|m| !(1..=MAX_SWARM_MAX).contains(m)) {
+            problem("OPENSPORE_SWARM_MAX", format!("{} is out of range (1-{})", max, MAX_SWARM_MAX));
+        }
+        if let Some(secs) = self.spore_timeout_secs.filter(|s| !(1..=MAX_SPORE_TIMEOUT_SECS).contains(s)) {
+            problem("SPORE_TIMEOUT_SECS", format!("{} is out of range (1-{})", secs, MAX_SPORE_TIMEOUT_SECS));
+        }
+        if self.llm_timeout_secs == Some(0) {
+            problem("LLM_TIMEOUT_SECS", "0 would time out every request".to_string());
+        }
+        let fractions = [
+            ("USEFULNESS_WEIGHT", self.usefulness_weight, 1.0),
+            ("CONTEXT_DEDUP_THRESHOLD", self.context_dedup_threshold.map(f64::from), 1.0),
+            ("LLM_TOP_P", self.llm_top_p, 1.0),
+            ("LLM_TEMPERATURE", self.llm_temperature, 2.0),
+        ];
+        for (key, value, max) in fractions {
+            if let Some(value) = value.filter(|v| !(0.0..=max).contains(v)) {
+                problem(key, format!("{} is out of range (0-{})", value, max));
+            }
+        }
+
+        for id in self.telegram_allowed_users() {
+            if id.parse::<i64>().is_err() {
+                problem("TELEGRAM_ALLOWED_USERS", format!("\"{}\" is not a numeric chat id", id));
+            }
+        }
+        for entry in self.model_context_tokens.as_deref().unwrap_or_default().split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let valid = entry.rsplit_once('=').is_some_and(|(model, tokens)| !model.trim().is_empty() && tokens.trim().parse::<usize>().is_ok_and(|t| t > 0));
+            if !valid {
+                problem("MODEL_CONTEXT_TOKENS", format!("\"{}\" is not model=tokens", entry));
+            }
+        }
+        problems
+    }
+
+    /// Whether ALLOWED_SKILLS and DENIED_SKILLS let the agent use a skill.
+    /// skill_help only reads descriptions and stays available.
+    pub fn skill_permitted(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        if name == "skill_help" {
+            return true;
+        }
+        let allowed = skill_names(&self.allowed_skills);
+        !skill_names(&self.denied_skills).contains(&name) && (allowed.is_empty() || allowed.contains(&name))
+    }
+
+    /// CONFIRM_SKILLS lists the skill
+    pub fn skill_needs_confirmation(&self, name: &str) -> bool {
+        skill_names(&self.confirm_skills).contains(&name.to_lowercase())
+    }
+
+    /// TELEGRAM_ALLOWED_USERS as a list, the owner's chat first
+    pub fn telegram_allowed_users(&self) -> Vec<String> {
+        self.telegram_allowed_users.as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect()
+    }
+
+    /// The owner's chat: the first TELEGRAM_ALLOWED_USERS entry
+    pub fn telegram_owner_chat(&self) -> Option<String> {
+        self.telegram_allowed_users().into_iter().next()
+    }
+
+    /// Base URL of the LLM endpoint, without trailing slash
+    pub fn llm_base_url(&self) -> String {
+        self.llm_base_url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+            .unwrap_or(DEFAULT_LLM_BASE_URL)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Full chat completions URL for the configured endpoint
+    pub fn completions_url(&self) -> String {
+        format!("{}/chat/completions", self.llm_base_url())
+    }
+
+    pub fn provider(&self) -> LlmProvider {
+        self.llm_provider.as_deref()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Native Ollama chat endpoint
+    pub fn ollama_chat_url(&self) -> String {
+        let base = self.ollama_url.as_deref()
+            .filter(|u| !u.trim().is_empty())
+            .unwrap_or(DEFAULT_OLLAMA_URL)
+            .trim_end_matches('/');
+        format!("{}/api/chat", base)
+    }
+
+    /// Key of a search provider (the instance URL for SearXNG, empty for
+    /// DuckDuckGo), None when unset or left at its placeholder
+    pub fn search_api_key(&self, provider: SearchProvider) -> Option<&str> {
+        let value = match provider {
+            SearchProvider::Brave => &self.brave_search_api_key,
+            SearchProvider::SerpApi => &self.serpapi_api_key,
+            SearchProvider::SearxNg => &self.searxng_url,
+            SearchProvider::DuckDuckGo => return Some(""),
+        };
+        value.as_deref().map(str::trim).filter(|v| !is_placeholder(v))
+    }
+
+    /// Web search providers in the order they are tried: SEARCH_PROVIDER
+    /// (unknown names skipped), else every configured one, then DuckDuckGo
+    pub fn search_providers(&self) -> Vec<SearchProvider> {
+        let chosen: Vec<SearchProvider> = self.search_provider.as_deref().unwrap_or_default()
+            .split(',')
+            .filter_map(|name| name.parse().ok())
+            .fold(Vec::new(), |mut list, p| {
+                if !list.contains(&p) {
+                    list.push(p);
+                }
+                list
+            });
+        if !chosen.is_empty() {
+            return chosen;
+        }
+        SearchProvider::ALL.into_iter().filter(|p| self.search_api_key(*p).is_some()).collect()
+    }
+
+    /// Concurrency limit of the swarm. 0 and values over MAX_SWARM_MAX fall back to the default.
+    pub fn swarm_max_concurrent(&self) -> usize {
+        match self.swarm_max_concurrent {
+            Some(max @ 1..=MAX_SWARM_MAX) => max,
+            Some(max) => {
+                tracing::warn!("OPENSPORE_SWARM_MAX={} is out of range (1-{}), using {}", max, MAX_SWARM_MAX, DEFAULT_SWARM_MAX);
+                DEFAULT_SWARM_MAX
+            }
+            None => DEFAULT_SWARM_MAX,
+        }
+    }
+
+    /// Time a sub-spore may run. 0 and values over MAX_SPORE_TIMEOUT_SECS fall back to the default.
+    pub fn spore_timeout(&self) -> std::time::Duration {
+        let secs = match self.spore_timeout_secs {
+            Some(secs @ 1..=MAX_SPORE_TIMEOUT_SECS) => secs,
+            Some(secs) => {
+                tracing::warn!("SPORE_TIMEOUT_SECS={} is out of range (1-{}), using {}", secs, MAX_SPORE_TIMEOUT_SECS, DEFAULT_SPORE_TIMEOUT_SECS);
+                DEFAULT_SPORE_TIMEOUT_SECS
+            }
+            None => DEFAULT_SPORE_TIMEOUT_SECS,
+        };
+        std::time::Duration::from_secs(secs)
+    }
+
+    /// USEFULNESS_WEIGHT clamped to 0-1
+    pub fn usefulness_weight(&self) -> f64 {
+        match self.usefulness_weight {
+            Some(w) if (0.0..=1.0).contains(&w) => w,
+            Some(w) => {
+                let used = if w.is_nan() { DEFAULT_USEFULNESS_WEIGHT } else { w.clamp(0.0, 1.0) };
+                tracing::warn!("USEFULNESS_WEIGHT={} is out of range (0-1), using {}", w, used);
+                used
+            }
+            None => DEFAULT_USEFULNESS_WEIGHT,
+        }
+    }
+
+    /// Age at which the nightly job consolidates exchanges, or None when CONSOLIDATE_AFTER_DAYS=0
+    pub fn consolidate_after(&self) -> Option<std::time::Duration> {
+        let days = self.consolidate_after_days.unwrap_or(DEFAULT_CONSOLIDATE_AFTER_DAYS);
+        (days > 0).then(|| std::time::Duration::from_secs(days as u64 * 24 * 3600))
+    }
+
+    /// CONSOLIDATE_MAX_FILES, at least 1
+    pub fn consolidate_max_files(&self) -> usize {
+        self.consolidate_max_files.unwrap_or(DEFAULT_CONSOLIDATE_MAX_FILES).max(1)
+    }
+
+    /// FAILURE_BUNDLES_MAX; 0 means no post-mortems are written
+    pub fn failure_bundles_max(&self) -> usize {
+        self.failure_bundles_max.unwrap_or(DEFAULT_FAILURE_BUNDLES_MAX)
+    }
+
+    /// SCREENSHOTS_MAX_MB and SCREENSHOTS_MAX_AGE_DAYS
+    pub fn screenshots_retention(&self) -> RetentionPolicy {
+        RetentionPolicy::new(
+            self.screenshots_max_mb.unwrap_or(DEFAULT_SCREENSHOTS_MAX_MB),
+            self.screenshots_max_age_days.unwrap_or(DEFAULT_MEDIA_MAX_AGE_DAYS),
+        )
+    }
+
+    /// DOWNLOADS_MAX_MB and DOWNLOADS_MAX_AGE_DAYS
+    pub fn downloads_retention(&self) -> RetentionPolicy {
+        RetentionPolicy::new(
+            self.downloads_max_mb.unwrap_or(DEFAULT_DOWNLOADS_MAX_MB),
+            self.downloads_max_age_days.unwrap_or(DEFAULT_MEDIA_MAX_AGE_DAYS),
+        )
+    }
+
+    /// Time between heartbeats, None when disabled
+    pub fn heartbeat_interval(&self) -> Option<std::time::Duration> {
+        minutes(self.heartbeat_interval_minutes.unwrap_or(self.autonomy.heartbeat_minutes))
+    }
+
+    /// Time between journal syntheses, None when disabled
+    pub fn journal_interval(&self) -> Option<std::time::Duration> {
+        minutes(self.journal_interval_minutes.unwrap_or(self.autonomy.journal_minutes))
+    }
+
+    /// Time between autonomy engine runs, None when disabled
+    pub fn engine_interval(&self) -> Option<std::time::Duration> {
+        minutes(self.autonomy_engine_interval_minutes.unwrap_or(self.autonomy.engine_minutes))
+    }
+
+    pub fn llm_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.llm_timeout_secs.unwrap_or(120))
+    }
+
+    /// Embeddings URL, or None when semantic search is not configured
+    pub fn embeddings_url(&self) -> Option<String> {
+        self.embedding_model.as_deref().filter(|m| !m.trim().is_empty())?;
+        let base = self.embedding_base_url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+            .map(|u| u.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| self.llm_base_url());
+        Some(format!("{}/embeddings", base))
+    }
+
+    /// Transcriptions URL, or None when voice transcription is not configured
+    pub fn transcriptions_url(&self) -> Option<String> {
+        self.transcription_model.as_deref().filter(|m| !m.trim().is_empty())?;
+        let base = self.transcription_base_url
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+            .map(|u| u.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| self.llm_base_url());
+        Some(format!("{}/audio/transcriptions", base))
+    }
+
+    /// Context window of `model`: its MODEL_CONTEXT_TOKENS entry, else LLM_CONTEXT_TOKENS
+    pub fn context_tokens(&self, model: &str) -> usize {
+        for entry in self.model_context_tokens.as_deref().unwrap_or_default().split(',').filter(|e| !e.trim().is_empty()) {
+            let Some((name, tokens)) = entry.rsplit_once('=') else {
+                tracing::warn!("MODEL_CONTEXT_TOKENS entry \"{}\" is not model=tokens, ignored", entry.trim());
+                continue;
+            };
+            if !name.trim().eq_ignore_ascii_case(model.trim()) {
+                continue;
+            }
+            match tokens.trim().parse::<usize>() {
+                Ok(tokens) if tokens > 0 => return tokens,
+                _ => tracing::warn!("MODEL_CONTEXT_TOKENS entry \"{}\" has no valid token count, ignored", entry.trim()),
+            }
+        }
+        self.llm_context_tokens.filter(|t| *t > 0).unwrap_or(DEFAULT_CONTEXT_TOKENS)
+    }
+
+    /// Reply length requested from the model (LLM_MAX_TOKENS, at least 256)
+    pub fn llm_max_tokens(&self) -> usize {
+        self.llm_max_tokens.unwrap_or(DEFAULT_LLM_MAX_TOKENS).max(256)
+    }
+
+    /// Ordered fallback models for the active provider (empty when unset)
+    pub fn model_fallbacks(&self) -> Vec<String> {
+        let raw = match self.provider() {
+            LlmProvider::Ollama => self.ollama_model_reasoning_fallbacks.as_deref(),
+            LlmProvider::OpenRouter => self.model_fallbacks.as_deref(),
+        };
+        raw.unwrap_or_default()
+            .split(',')
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect()
+    }
+
+    /// True when the endpoint points at this machine (local llama.cpp/ollama/vLLM)
+    pub fn is_local_llm(&self) -> bool {
+        is_local_url(&self.llm_base_url())
+    }
+}
+
+/// Names of the variables set in the app's .env file
+pub fn env_file_keys() -> Vec<String> {
+    let path = crate::path_utils::get_app_root().join(".env");
+    dotenvy::from_path_iter(path)
+        .map(|vars| vars.filter_map(|v| v.ok()).map(|(key, _)| key).collect())
+        .unwrap_or_default()
+}
+
+/// Set (or remove, with None) a KEY=value line in the app's .env file
+pub fn set_env_value(key: &str, value: Option<&str>) -> std::io::Result<()> {
+    let path = crate::path_utils::get_app_root().join(".env");
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    let prefix = format!("{}=", key);
+
+    let mut lines: Vec<String> = content.lines()
+        .filter(|l| !l.trim_start().starts_with(&prefix))
+        .map(String::from)
+        .collect();
+    if let Some(value) = value {
+        lines.push(format!("{}{}", prefix, value));
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    std::fs::write(path, out)
+}
+
+pub fn is_local_url(url: &str) -> bool {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = without_scheme.split('/').next().unwrap_or("");
+    let host = if authority.starts_with('[') {
+        authority.split(']').next().map(|h| h.trim_start_matches('[')).unwrap_or("")
+    } else {
+        authority.split(':').next().unwrap_or("")
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "::1")
 }
@@ -0,0 +1,174 @@
+//! Live configuration
+//!
+//! `AppConfig::watch()` starts, once per process, a watcher on the .env file
+//! and openspore.toml. After an edit settles, the configuration is read again
+//! and, when it changed, published on a tokio watch channel; Brain and the
+//! scheduler subscribe to it, so models and schedules apply without a
+//! restart. A reload with problems (see AppConfig::load_with_problems) is not
+//! applied: the problems are reported and the current configuration stays.
+//! Settings only read at startup (the Telegram token, the keys of the
+//! embedding and transcription endpoints, memory encryption...) are reported
+//! as needing a restart.
+//!
+//! `AppConfig::current()` is the latest configuration, for code that has no
+//! Brain to ask (skills, Telegram notifications).
+
+use crate::config::{env_file_path, AppConfig};
+use crate::event_bus::{self, SystemEvent};
+use notify::{RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Once, OnceLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+/// Quiet time after the last change before the files are read
+const RELOAD_QUIET: Duration = Duration::from_millis(500);
+
+/// Settings read once at startup: a change takes a restart
+const RESTART_KEYS: &[&str] = &[
+    "telegram_bot_token", "telegram_allowed_users", "embedding_api_key", "embedding_model", "transcription_api_key",
+    "encrypt_memory", "mem_key", "llm_timeout_secs", "log_filter", "watchman",
+];
+
+/// Variable names of the fields whose alias differs from their name
+const VARIABLE_NAMES: &[(&str, &str)] = &[
+    ("model", "OPENROUTER_MODEL"),
+    ("model_vision", "OPENROUTER_MODEL_VISION"),
+    ("model_fast", "OPENROUTER_MODEL_FAST"),
+    ("model_fallbacks", "OPENROUTER_MODEL_REASONING_FALLBACKS"),
+    ("llm_base_url", "OPENSPORE_LLM_BASE_URL"),
+    ("mem_key", "OPENSPORE_MEM_KEY"),
+    ("swarm_max_concurrent", "OPENSPORE_SWARM_MAX"),
+    ("tui", "[tui]"),
+    ("autonomy", "[autonomy]"),
+    ("watchman", "[watchman]"),
+];
+
+static LIVE: OnceLock<watch::Sender<Arc<AppConfig>>> = OnceLock::new();
+
+/// Keeps the file watcher alive
+static WATCHER: Mutex<Option<notify::RecommendedWatcher>> = Mutex::new(None);
+
+fn live() -> &'static watch::Sender<Arc<AppConfig>> {
+    LIVE.get_or_init(|| {
+        let config = match AppConfig::load_with_problems() {
+            Ok((config, problems)) => {
+                for problem in problems {
+                    warn!("⚙️ {}", problem);
+                }
+                config
+            }
+            Err(e) => {
+                error!("⚙️ Configuration not loaded, using defaults: {}", e);
+                AppConfig::default()
+            }
+        };
+        watch::Sender::new(Arc::new(config))
+    })
+}
+
+impl AppConfig {
+    /// The latest configuration of this process
+    pub fn current() -> Arc<AppConfig> {
+        live().borrow().clone()
+    }
+
+    /// Updates of the configuration; the first call starts watching the files
+    pub fn watch() -> watch::Receiver<Arc<AppConfig>> {
+        static STARTED: Once = Once::new();
+        STARTED.call_once(start_watching);
+        live().subscribe()
+    }
+}
+
+/// Variable name of a top-level field, e.g. OPENROUTER_MODEL for `model`
+fn variable_name(field: &str) -> String {
+    VARIABLE_NAMES.iter()
+        .find(|(name, _)| *name == field)
+        .map(|(_, var)| var.to_string())
+        .unwrap_or_else(|| field.to_uppercase())
+}
+
+/// Fields that differ between two configurations
+fn changed_fields(old: &AppConfig, new: &AppConfig) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return Vec::new();
+    };
+    new.into_iter().filter(|(field, value)| old.get(field) != Some(value)).map(|(field, _)| field).collect()
+}
+
+/// The .env file (or where one would be created) and openspore.toml
+fn watched_files() -> Vec<PathBuf> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let env = env_file_path().unwrap_or_else(|| crate::path_utils::get_app_root().join(".env"));
+    vec![cwd.join(env), cwd.join("openspore.toml")]
+}
+
+fn start_watching() {
+    let files = watched_files();
+    let (tx, rx) = std::sync::mpsc::channel::<()>();
+    let watched = files.clone();
+    let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res
+            && event.paths.iter().any(|p| watched.contains(p))
+        {
+            let _ = tx.send(());
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(w) => w,
+        Err(e) => {
+            warn!("⚙️ Configuration changes need a restart: cannot watch files ({})", e);
+            return;
+        }
+    };
+    // Editors replace files rather than write them: watch the directories
+    let mut dirs: Vec<PathBuf> = files.iter().filter_map(|f| f.parent().map(PathBuf::from)).collect();
+    dirs.dedup();
+    for dir in &dirs {
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            warn!("⚙️ Cannot watch {} for configuration changes: {}", dir.display(), e);
+        }
+    }
+    if let Ok(mut slot) = WATCHER.lock() {
+        *slot = Some(watcher);
+    }
+    info!("⚙️ Watching {} for configuration changes", files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(" and "));
+
+    std::thread::spawn(move || {
+        while rx.recv().is_ok() {
+            // One reload for a burst of events
+            while rx.recv_timeout(RELOAD_QUIET).is_ok() {}
+            reload();
+        }
+    });
+}
+
+/// Read the configuration again and publish it if it changed
+fn reload() {
+    let sender = live();
+    let problems: Vec<String> = match AppConfig::load_with_problems() {
+        Ok((config, problems)) if problems.is_empty() => return apply(sender, config),
+        Ok((_, problems)) => problems.iter().map(|p| p.to_string()).collect(),
+        Err(e) => vec![e.to_string()],
+    };
+    warn!("⚙️ Configuration change not applied: {}", problems.join("; "));
+    event_bus::publish(SystemEvent::ConfigRejected { problems });
+}
+
+fn apply(sender: &watch::Sender<Arc<AppConfig>>, config: AppConfig) {
+    let current = sender.borrow().clone();
+    let changed = changed_fields(&current, &config);
+    if changed.is_empty() {
+        return;
+    }
+    let restart_required: Vec<String> = changed.iter().filter(|f| RESTART_KEYS.contains(&f.as_str())).map(|f| variable_name(f)).collect();
+    let changed: Vec<String> = changed.iter().map(|f| variable_name(f)).collect();
+    info!("⚙️ Configuration reloaded: {} changed", changed.join(", "));
+    if !restart_required.is_empty() {
+        warn!("⚙️ {} changed: restart OpenSpore to apply it", restart_required.join(", "));
+    }
+    sender.send_replace(Arc::new(config));
+    event_bus::publish(SystemEvent::ConfigReloaded { changed, restart_required });
+}
@@ -0,0 +1,234 @@
+//! In-process Event Bus
+//!
+//! Subsystems publish typed `SystemEvent`s on a tokio broadcast channel instead
+//! of each consumer polling state files. Publishing never blocks: a subscriber
+//! that falls behind loses the oldest events and the loss is counted.
+//!
+//! Other processes (cron jobs, sub-spores) cannot reach the bus directly; they
+//! append to `workspace/inbox.jsonl`, which the running daemon bridges onto it.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Events buffered per subscriber before the oldest are dropped
+const BUS_CAPACITY: usize = 256;
+const INBOX_FILE: &str = "inbox.jsonl";
+const INBOX_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum SystemEvent {
+    MemorySaved { category: String, title: String, path: PathBuf },
+    ProposalCreated { title: String, path: PathBuf, auto_applied: bool },
+    InboxMessage { source: String, text: String },
+    /// The configuration was read again (see config_watch); `changed` lists
+    /// variables, `restart_required` those that only apply after a restart
+    ConfigReloaded {
+        #[serde(default)]
+        changed: Vec<String>,
+        #[serde(default)]
+        restart_required: Vec<String>,
+    },
+    /// An edited configuration was not applied because of these problems
+    ConfigRejected { problems: Vec<String> },
+    HeartbeatCompleted { status: String, report: String },
+    SkillReloaded { count: usize },
+    /// A sync merge left both versions of a memory in the file
+    MemoryConflict { path: String },
+    /// A memory was put in the model's context, or cited by its answer
+    MemoryUsed { id: String, path: PathBuf, cited: bool },
+}
+
+pub struct EventBus {
+    tx: broadcast::Sender<SystemEvent>,
+    dropped: AtomicU64,
+}
+
+static BUS: OnceLock<EventBus> = OnceLock::new();
+
+/// Process-wide bus
+pub fn bus() -> &'static EventBus {
+    BUS.get_or_init(|| EventBus::new(BUS_CAPACITY))
+}
+
+/// Shorthand for `bus().publish(event)`
+pub fn publish(event: SystemEvent) {
+    bus().publish(event);
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx, dropped: AtomicU64::new(0) }
+    }
+
+    /// Non-blocking; events published with no subscriber are simply discarded
+    pub fn publish(&self, event: SystemEvent) {
+        let _ = self.tx.send(event);
+    }
+
+    pub fn subscribe(&self) -> Subscriber<'_> {
+        Subscriber { rx: self.tx.subscribe(), bus: self }
+    }
+
+    /// Total events lost by lagging subscribers
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Subscriber<'a> {
+    rx: broadcast::Receiver<SystemEvent>,
+    bus: &'a EventBus,
+}
+
+impl Subscriber<'_> {
+    fn record_lag(&self, n: u64) {
+        self.bus.dropped.fetch_add(n, Ordering::Relaxed);
+        warn!("📭 Event bus: subscriber lagged, {} events dropped", n);
+    }
+
+    /// Next event, or None once the bus is closed
+    pub async fn recv(&mut self) -> Option<SystemEvent> {
+        loop {
+            match self.rx.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(n)) => self.record_lag(n),
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Next pending event without waiting (for draw loops)
+    pub fn try_recv(&mut self) -> Option<SystemEvent> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::TryRecvError::Lagged(n)) => self.record_lag(n),
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+pub fn inbox_path(workspace: &Path) -> PathBuf {
+    workspace.join(INBOX_FILE)
+}
+
+/// Cross-process publish: append the event to the workspace inbox
+pub fn post_to_inbox(workspace: &Path, event: &SystemEvent) -> std::io::Result<()> {
+    let line = serde_json::to_string(event)?;
+    std::fs::create_dir_all(workspace)?;
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(inbox_path(workspace))?;
+    writeln!(file, "{}", line)
+}
+
+/// Bridge the inbox onto the in-process bus. Lines already present at start are
+/// skipped; the file is truncated once fully consumed so it cannot grow forever.
+pub fn spawn_inbox_bridge(workspace: PathBuf) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let path = inbox_path(&workspace);
+        let mut offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+        loop {
+            tokio::time::sleep(INBOX_POLL_INTERVAL).await;
+            let Ok(mut file) = std::fs::File::open(&path) else { continue };
+            let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+            if len < offset {
+                offset = 0; // Truncated by someone else
+            }
+            if len == offset {
+                continue;
+            }
+
+            let mut buf = String::new();
+            if file.seek(SeekFrom::Start(offset)).is_err() || file.read_to_string(&mut buf).is_err() {
+                continue;
+            }
+            // Only consume complete lines
+            let Some(end) = buf.rfind('\n') else { continue };
+            for line in buf[..end].lines().filter(|l| !l.trim().is_empty()) {
+                match serde_json::from_str::<SystemEvent>(line) {
+                    Ok(event) => publish(event),
+                    Err(e) => warn!("📭 Inbox: skipping malformed event: {}", e),
+                }
+            }
+            offset += end as u64 + 1;
+
+            if offset == len && std::fs::OpenOptions::new().write(true).truncate(true).open(&path).is_ok() {
+                offset = 0;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inbox(n: usize) -> SystemEvent {
+        SystemEvent::InboxMessage { source: "test".into(), text: n.to_string() }
+    }
+
+    #[tokio::test]
+    async fn every_subscriber_gets_every_event() {
+        let bus = EventBus::new(16);
+        let mut a = bus.subscribe();
+        let mut b = bus.subscribe();
+        for n in 0..3 {
+            bus.publish(inbox(n));
+        }
+        for n in 0..3 {
+            assert_eq!(a.recv().await, Some(inbox(n)));
+            assert_eq!(b.try_recv(), Some(inbox(n)));
+        }
+        assert_eq!(a.try_recv(), None);
+        assert_eq!(b.try_recv(), None);
+        assert_eq!(bus.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn lagged_subscriber_loses_the_oldest_and_the_loss_is_counted() {
+        let bus = EventBus::new(4);
+        let mut slow = bus.subscribe();
+        let mut fast = bus.subscribe();
+        for n in 0..10 {
+            bus.publish(inbox(n));
+            assert_eq!(fast.try_recv(), Some(inbox(n)));
+        }
+
+        // The slow one resumes at the oldest event still buffered
+        assert_eq!(slow.recv().await, Some(inbox(6)));
+        assert_eq!(bus.dropped(), 6);
+        for n in 7..10 {
+            assert_eq!(slow.try_recv(), Some(inbox(n)));
+        }
+        assert_eq!(slow.try_recv(), None);
+    }
+
+    #[test]
+    fn publishing_with_no_subscriber_is_a_no_op() {
+        let bus = EventBus::new(4);
+        bus.publish(inbox(0));
+        let mut late = bus.subscribe();
+        assert_eq!(late.try_recv(), None);
+        assert_eq!(bus.dropped(), 0);
+    }
+
+    #[test]
+    fn inbox_lines_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let events = [inbox(1), SystemEvent::SkillReloaded { count: 3 }];
+        for event in &events {
+            post_to_inbox(dir.path(), event).unwrap();
+        }
+        let text = std::fs::read_to_string(inbox_path(dir.path())).unwrap();
+        let read: Vec<SystemEvent> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(read, events);
+    }
+}
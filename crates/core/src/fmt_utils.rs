@@ -0,0 +1,221 @@
+//! Formatting utilities shared by all crates
+//!
+//! UTF-8 safe truncation (never slices inside a character or, for graphemes,
+//! inside an emoji/combining sequence) and human-readable sizes and durations.
+
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const ELLIPSIS: &str = "…";
+
+/// Keep at most `max` characters (ellipsis included when truncated)
+pub fn truncate_chars(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut out: String = s.chars().take(max - 1).collect();
+    out.push_str(ELLIPSIS);
+    out
+}
+
+/// Keep at most `max` grapheme clusters (ellipsis included when truncated).
+/// Use this for user-visible text: emoji and accented letters stay intact.
+pub fn truncate_graphemes(s: &str, max: usize) -> String {
+    if s.graphemes(true).count() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut out: String = s.graphemes(true).take(max - 1).collect();
+    out.push_str(ELLIPSIS);
+    out
+}
+
+/// Longest prefix of at most `max_bytes` bytes that ends on a character boundary
+pub fn truncate_bytes(s: &str, max_bytes: usize) -> &str {
+    &s[..s.floor_char_boundary(max_bytes)]
+}
+
+/// Single-line preview: whitespace (including newlines) collapsed, then truncated to `n` graphemes
+pub fn preview(text: &str, n: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate_graphemes(&collapsed, n)
+}
+
+/// 512 B, 1.5 KB, 3.2 MB, ...
+pub fn humanize_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// 850ms, 4.2s, 3m 05s, 1h 02m, 2d 03h
+pub fn humanize_duration(d: Duration) -> String {
+    let ms = d.as_millis();
+    if ms < 1000 {
+        return format!("{}ms", ms);
+    }
+    let secs = d.as_secs();
+    if secs < 60 {
+        return format!("{:.1}s", d.as_secs_f64());
+    }
+    let (mins, secs) = (secs / 60, secs % 60);
+    if mins < 60 {
+        return format!("{}m {:02}s", mins, secs);
+    }
+    let (hours, mins) = (mins / 60, mins % 60);
+    if hours < 24 {
+        return format!("{}h {:02}m", hours, mins);
+    }
+    format!("{}d {:02}h", hours / 24, hours % 24)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Emoji (with skin tones and ZWJ families), flags, combining marks, CJK and ASCII
+    const SAMPLES: &[&str] = &[
+        "",
+        "plain ascii text",
+        "héllo wörld",
+        "e\u{301}e\u{301}e\u{301} combining",
+        "👋🏽 hi 👨‍👩‍👧‍👦 family 🇫🇷🇯🇵",
+        "日本語のテキストと中文",
+        "mixed: a👍b\u{308}c🏳️‍🌈d 한국어",
+        "🔥🔥🔥🔥🔥🔥🔥🔥🔥🔥",
+    ];
+
+    #[test]
+    fn truncate_chars_stays_within_limit_and_keeps_a_prefix() {
+        for sample in SAMPLES {
+            let len = sample.chars().count();
+            for max in 0..=len + 2 {
+                let out = truncate_chars(sample, max);
+                assert!(out.chars().count() <= max, "{:?} at {}", sample, max);
+                if max >= len {
+                    assert_eq!(&out, sample);
+                } else if max > 0 {
+                    let kept = out.strip_suffix(ELLIPSIS).expect("truncated output ends with an ellipsis");
+                    assert!(sample.starts_with(kept));
+                    assert_eq!(kept.chars().count(), max - 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn truncate_graphemes_never_splits_a_cluster() {
+        for sample in SAMPLES {
+            let clusters: Vec<&str> = sample.graphemes(true).collect();
+            for max in 0..=clusters.len() + 2 {
+                let out = truncate_graphemes(sample, max);
+                assert!(out.graphemes(true).count() <= max, "{:?} at {}", sample, max);
+                if max >= clusters.len() {
+                    assert_eq!(&out, sample);
+                } else if max > 0 {
+                    let kept = out.strip_suffix(ELLIPSIS).unwrap();
+                    assert_eq!(kept, clusters[..max - 1].concat());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn truncate_bytes_ends_on_a_char_boundary() {
+        for sample in SAMPLES {
+            for max in 0..=sample.len() + 2 {
+                let out = truncate_bytes(sample, max);
+                assert!(out.len() <= max);
+                assert!(sample.starts_with(out));
+                // The next character would not have fit
+                if let Some(next) = sample[out.len()..].chars().next() {
+                    assert!(out.len() + next.len_utf8() > max);
+                }
+            }
+        }
+    }
+
+    /// Random text weighted towards what breaks naive truncation: arbitrary
+    /// characters mixed with combining marks, ZWJ, skin tones, variation
+    /// selectors, regional indicators and whitespace
+    fn unicode_text() -> impl Strategy<Value = String> {
+        let piece = prop_oneof![
+            3 => any::<char>().prop_map(String::from),
+            2 => "[a-z ]{1,4}",
+            1 => prop::sample::select(vec!["\u{301}", "\u{308}", "\u{200d}", "\u{1f3fd}", "\u{fe0f}", "🇫", "🇷", "👨", "🔥", "日", "\n", "\t", "\r\n"])
+                .prop_map(String::from),
+        ];
+        prop::collection::vec(piece, 0..40).prop_map(|pieces| pieces.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn random_text_truncates_within_every_limit(text in unicode_text(), max in 0usize..48) {
+            let chars = truncate_chars(&text, max);
+            prop_assert!(chars.chars().count() <= max);
+            if text.chars().count() <= max {
+                prop_assert_eq!(&chars, &text);
+            } else if max > 0 {
+                prop_assert!(text.starts_with(chars.strip_suffix(ELLIPSIS).unwrap()));
+            }
+
+            let clusters: Vec<&str> = text.graphemes(true).collect();
+            let graphemes = truncate_graphemes(&text, max);
+            prop_assert!(graphemes.graphemes(true).count() <= max);
+            if clusters.len() <= max {
+                prop_assert_eq!(&graphemes, &text);
+            } else if max > 0 {
+                prop_assert_eq!(graphemes.strip_suffix(ELLIPSIS).unwrap(), clusters[..max - 1].concat());
+            }
+
+            let one_line = preview(&text, max);
+            prop_assert!(one_line.graphemes(true).count() <= max);
+            prop_assert!(!one_line.contains(['\n', '\r', '\t']));
+        }
+
+        #[test]
+        fn random_text_truncates_bytes_on_a_char_boundary(text in unicode_text(), max in 0usize..160) {
+            let out = truncate_bytes(&text, max);
+            prop_assert!(out.len() <= max);
+            prop_assert!(text.is_char_boundary(out.len()));
+            prop_assert!(text.starts_with(out));
+            if let Some(next) = text[out.len()..].chars().next() {
+                prop_assert!(out.len() + next.len_utf8() > max);
+            }
+        }
+    }
+
+    #[test]
+    fn preview_is_one_line_and_keeps_emoji_whole() {
+        let text = "first line\n\n  👨‍👩‍👧‍👦   second\tline 🇫🇷";
+        assert_eq!(preview(text, 100), "first line 👨‍👩‍👧‍👦 second line 🇫🇷");
+        assert_eq!(preview(text, 13), "first line 👨‍👩‍👧‍👦…");
+        assert!(!preview(text, 5).contains('\n'));
+    }
+
+    #[test]
+    fn humanize() {
+        assert_eq!(humanize_bytes(512), "512 B");
+        assert_eq!(humanize_bytes(1536), "1.5 KB");
+        assert_eq!(humanize_bytes(3 * 1024 * 1024 + 200 * 1024), "3.2 MB");
+        assert_eq!(humanize_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(humanize_duration(Duration::from_millis(4200)), "4.2s");
+        assert_eq!(humanize_duration(Duration::from_secs(185)), "3m 05s");
+        assert_eq!(humanize_duration(Duration::from_secs(3720)), "1h 02m");
+        assert_eq!(humanize_duration(Duration::from_secs(2 * 86400 + 3 * 3600)), "2d 03h");
+    }
+}
@@ -1,6 +1,14 @@
+pub mod capabilities;
 pub mod config;
+pub mod config_watch;
 pub mod state;
 pub mod path_utils;
+pub mod fmt_utils;
+pub mod event_bus;
+pub mod logging;
+pub mod process;
+pub mod schedule;
+pub mod watch;
 
 use tracing::{info};
 
@@ -0,0 +1,186 @@
+//! Runtime Log Filter
+//!
+//! The tracing subscriber is installed with a reloadable `EnvFilter`, so the
+//! level of any target can be changed without restarting (and losing the
+//! session). The filter is taken from RUST_LOG, then LOG_FILTER in .env, then
+//! `DEFAULT_FILTER`; `set_filter` persists the new one back to LOG_FILTER.
+
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+pub const DEFAULT_FILTER: &str = "info";
+const ENV_KEY: &str = "LOG_FILTER";
+
+#[derive(Debug, thiserror::Error)]
+pub enum LogFilterError {
+    #[error("invalid filter \"{0}\": {1}")]
+    Invalid(String, String),
+    #[error("logging is not initialized")]
+    NotInitialized,
+    #[error("filter could not be applied: {0}")]
+    Reload(String),
+    #[error("filter applied but not saved: {0}")]
+    Persist(#[from] std::io::Error),
+}
+
+struct LogControl {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: Mutex<String>,
+}
+
+static CONTROL: OnceLock<LogControl> = OnceLock::new();
+
+/// Filter to start with: RUST_LOG, then the configured LOG_FILTER, then the default
+pub fn initial_filter() -> String {
+    std::env::var("RUST_LOG").ok()
+        .or_else(|| crate::config::AppConfig::load().ok().and_then(|c| c.log_filter))
+        .filter(|f| EnvFilter::try_new(f).is_ok())
+        .unwrap_or_else(|| DEFAULT_FILTER.to_string())
+}
+
+/// Install the global subscriber with a reloadable filter
+pub fn init<W>(writer: W, ansi: bool, filter: &str)
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let env_filter = EnvFilter::try_new(filter).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let (filter_layer, handle) = reload::Layer::new(env_filter);
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(ansi))
+        .init();
+
+    let _ = CONTROL.set(LogControl { handle, current: Mutex::new(filter.to_string()) });
+}
+
+/// Active filter string (the startup choice when logging is not initialized)
+pub fn current_filter() -> String {
+    match CONTROL.get() {
+        Some(control) => control.current.lock().map(|c| c.clone()).unwrap_or_default(),
+        None => initial_filter(),
+    }
+}
+
+/// Apply `directives` on top of the active filter and persist the result.
+/// "openspore_brain=debug" overrides only that target; a bare level ("warn")
+/// replaces the default level. Returns the new filter string.
+pub fn set_filter(directives: &str) -> Result<String, LogFilterError> {
+    let merged = merge_directives(&current_filter(), directives);
+    apply(&merged)?;
+    crate::config::set_env_value(ENV_KEY, Some(&merged))?;
+    Ok(merged)
+}
+
+/// Return to `DEFAULT_FILTER` and drop the persisted filter
+pub fn reset_filter() -> Result<String, LogFilterError> {
+    apply(DEFAULT_FILTER)?;
+    crate::config::set_env_value(ENV_KEY, None)?;
+    Ok(DEFAULT_FILTER.to_string())
+}
+
+fn apply(filter: &str) -> Result<(), LogFilterError> {
+    let env_filter = EnvFilter::try_new(filter)
+        .map_err(|e| LogFilterError::Invalid(filter.to_string(), e.to_string()))?;
+    let control = CONTROL.get().ok_or(LogFilterError::NotInitialized)?;
+    control.handle.reload(env_filter).map_err(|e| LogFilterError::Reload(e.to_string()))?;
+    if let Ok(mut current) = control.current.lock() {
+        *current = filter.to_string();
+    }
+    Ok(())
+}
+
+/// Merge comma-separated directives, replacing any existing one for the same target
+fn merge_directives(current: &str, new: &str) -> String {
+    // A bare level has no target; a bare target enables everything for it
+    let target = |d: &str| match d.split_once('=') {
+        Some((t, _)) => t.trim().to_string(),
+        None if d.parse::<tracing_subscriber::filter::LevelFilter>().is_ok() => String::new(),
+        None => d.to_string(),
+    };
+
+    let mut directives: Vec<String> = current.split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(String::from)
+        .collect();
+
+    for directive in new.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let t = target(directive);
+        directives.retain(|d| target(d) != t);
+        directives.push(directive.to_string());
+    }
+    directives.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Log output kept in memory
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Captured {
+        fn take(&self) -> String {
+            String::from_utf8(std::mem::take(&mut *self.0.lock().unwrap())).unwrap()
+        }
+    }
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn merging_replaces_directives_for_the_same_target() {
+        assert_eq!(merge_directives("info", "openspore_brain=debug"), "info,openspore_brain=debug");
+        assert_eq!(merge_directives("info,openspore_brain=debug", "openspore_brain=trace"), "info,openspore_brain=trace");
+        assert_eq!(merge_directives("info,openspore_brain=debug", "warn"), "openspore_brain=debug,warn");
+        assert_eq!(merge_directives("info", " hyper , "), "info,hyper");
+    }
+
+    // The subscriber is process-wide, so the whole reload story is one test
+    #[test]
+    fn reload_handle_changes_the_level_without_reinstalling() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(crate::path_utils::set_app_root(root.path().to_path_buf()));
+        assert!(matches!(apply("debug"), Err(LogFilterError::NotInitialized)));
+
+        let out = Captured::default();
+        let writer = out.clone();
+        init(move || writer.clone(), false, "warn");
+        assert_eq!(current_filter(), "warn");
+
+        tracing::info!(target: "reload_test", "hidden");
+        tracing::warn!(target: "reload_test", "shown");
+        let logged = out.take();
+        assert!(logged.contains("shown") && !logged.contains("hidden"));
+
+        assert_eq!(set_filter("reload_test=info").unwrap(), "warn,reload_test=info");
+        tracing::info!(target: "reload_test", "now visible");
+        tracing::info!(target: "other", "still hidden");
+        let logged = out.take();
+        assert!(logged.contains("now visible") && !logged.contains("still hidden"));
+        let env = std::fs::read_to_string(root.path().join(".env")).unwrap();
+        assert!(env.contains("LOG_FILTER=warn,reload_test=info"));
+
+        // A bad filter is refused and the active one is kept
+        assert!(matches!(set_filter("reload_test=loud"), Err(LogFilterError::Invalid(..))));
+        assert_eq!(current_filter(), "warn,reload_test=info");
+
+        assert_eq!(reset_filter().unwrap(), DEFAULT_FILTER);
+        tracing::info!(target: "other", "back to info");
+        assert!(out.take().contains("back to info"));
+        assert!(!std::fs::read_to_string(root.path().join(".env")).unwrap().contains("LOG_FILTER"));
+    }
+}
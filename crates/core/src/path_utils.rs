@@ -3,6 +3,10 @@
 //! Handles tilde expansion and path normalization.
 
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// App root fixed by set_app_root()
+static APP_ROOT: OnceLock<PathBuf> = OnceLock::new();
 
 /// Expands tilde (~) in paths to the user's home directory.
 /// Examples:
@@ -29,9 +33,19 @@ pub fn get_path(path: &str) -> PathBuf {
     PathBuf::from(expand_tilde(path))
 }
 
+/// Fix the app root of this process ahead of OPENSPORE_ROOT, for tests and
+/// embedders that must not change the environment. The first call wins;
+/// returns false when the root was already set.
+pub fn set_app_root(root: PathBuf) -> bool {
+    APP_ROOT.set(root).is_ok()
+}
+
 /// Robustly resolves the OpenSpore project root using OPENSPORE_ROOT env var.
 /// Handles absolute paths, tilde expansion, and relative names.
 pub fn get_app_root() -> PathBuf {
+    if let Some(root) = APP_ROOT.get() {
+        return root.clone();
+    }
     let root_name = std::env::var("OPENSPORE_ROOT").unwrap_or_else(|_| ".openspore".to_string());
 
     if root_name.starts_with('/') {
@@ -95,7 +109,7 @@ fn build_tree_string(path: &std::path::Path, depth: usize, max_depth: usize, out
         } else {
             out.push_str("📄 ");
             out.push_str(&name);
-            out.push_str("\n");
+            out.push('\n');
         }
     }
     Ok(())
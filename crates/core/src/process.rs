@@ -0,0 +1,393 @@
+//! Process bookkeeping for `openspore stop`
+//!
+//! Running instances record their pids under workspace/.runtime/ (sub-spores
+//! have their own registry in the swarm crate), so stop can terminate exactly
+//! those processes instead of everything whose command line mentions
+//! "openspore". Before a recorded pid is signalled its command line is checked
+//! again (pids get reused).
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub fn runtime_dir(root: &Path) -> PathBuf {
+    root.join("workspace").join(".runtime")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessRecord {
+    pub pid: u32,
+    /// What the process is ("tui", "spore:<role>"...)
+    pub label: String,
+    /// Unix seconds
+    pub started: u64,
+}
+
+impl ProcessRecord {
+    pub fn current(label: &str) -> Self {
+        Self::new(std::process::id(), label)
+    }
+
+    pub fn new(pid: u32, label: &str) -> Self {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        Self { pid, label: label.to_string(), started }
+    }
+}
+
+/// JSON list of process records
+#[derive(Debug, Clone)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Running OpenSpore instances (TUI)
+    pub fn instances(root: &Path) -> Self {
+        Self { path: runtime_dir(root).join("instance.lock") }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn read(&self) -> Vec<ProcessRecord> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, records: &[ProcessRecord]) -> std::io::Result<()> {
+        if records.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(records)?)
+    }
+
+    /// Add a record (records of exited processes are dropped on the way)
+    pub fn add(&self, record: ProcessRecord) -> std::io::Result<()> {
+        let mut records: Vec<_> = self.read().into_iter()
+            .filter(|r| r.pid != record.pid && is_alive(r.pid))
+            .collect();
+        records.push(record);
+        self.write(&records)
+    }
+
+    pub fn remove(&self, pid: u32) -> std::io::Result<()> {
+        let records: Vec<_> = self.read().into_iter().filter(|r| r.pid != pid).collect();
+        self.write(&records)
+    }
+
+    pub fn clear(&self) -> std::io::Result<()> {
+        self.write(&[])
+    }
+}
+
+/// Records this process in the instance lock until dropped
+pub struct InstanceGuard {
+    file: PidFile,
+}
+
+impl InstanceGuard {
+    pub fn acquire(root: &Path, label: &str) -> std::io::Result<Self> {
+        let file = PidFile::instances(root);
+        file.add(ProcessRecord::current(label))?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        let _ = self.file.remove(std::process::id());
+    }
+}
+
+/// How a process ended up after `terminate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Termination {
+    /// Exited after the polite signal
+    Terminated,
+    /// Needed the forceful kill
+    Killed,
+    NotRunning,
+    Failed(String),
+}
+
+fn quiet(cmd: &mut Command) -> &mut Command {
+    cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null())
+}
+
+pub fn is_alive(pid: u32) -> bool {
+    if pid == 0 {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        quiet(Command::new("kill").args(["-0", &pid.to_string()]))
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+    #[cfg(windows)]
+    {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{}\"", pid)))
+    }
+}
+
+/// Full command line of a process, if it is running and readable
+pub fn command_line(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let raw = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+        let line = String::from_utf8_lossy(&raw).replace('\0', " ").trim().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let out = Command::new("ps").args(["-o", "command=", "-p", &pid.to_string()]).output().ok()?;
+        let line = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+    #[cfg(windows)]
+    {
+        let query = format!("(Get-CimInstance Win32_Process -Filter 'ProcessId={}').CommandLine", pid);
+        let out = Command::new("powershell").args(["-NoProfile", "-Command", &query]).output().ok()?;
+        let line = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// (pid, command line) of every process we can see
+pub fn list_processes() -> Vec<(u32, String)> {
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(entries) = std::fs::read_dir("/proc") else { return vec![] };
+        entries.flatten()
+            .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| Some((pid, command_line(pid)?)))
+            .collect()
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let Ok(out) = Command::new("ps").args(["-axo", "pid=,command="]).output() else { return vec![] };
+        parse_pid_lines(&String::from_utf8_lossy(&out.stdout))
+    }
+    #[cfg(windows)]
+    {
+        let query = "Get-CimInstance Win32_Process | ForEach-Object { \"$($_.ProcessId) $($_.CommandLine)\" }";
+        let Ok(out) = Command::new("powershell").args(["-NoProfile", "-Command", query]).output() else { return vec![] };
+        parse_pid_lines(&String::from_utf8_lossy(&out.stdout))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn parse_pid_lines(text: &str) -> Vec<(u32, String)> {
+    text.lines()
+        .filter_map(|l| {
+            let (pid, cmd) = l.trim().split_once(' ')?;
+            Some((pid.parse().ok()?, cmd.trim().to_string()))
+        })
+        .filter(|(_, cmd)| !cmd.is_empty())
+        .collect()
+}
+
+/// Children, grandchildren... of a process (unix; empty on Windows, where
+/// kill_tree() lets taskkill walk the tree). Read before the parent dies:
+/// orphans are re-parented and can no longer be traced back.
+pub fn descendants(pid: u32) -> Vec<u32> {
+    let parents = parent_pids();
+    let mut found = Vec::new();
+    let mut frontier = vec![pid];
+    while let Some(parent) = frontier.pop() {
+        for &(child, ppid) in &parents {
+            if ppid == parent && child != pid && !found.contains(&child) {
+                found.push(child);
+                frontier.push(child);
+            }
+        }
+    }
+    found
+}
+
+/// (pid, parent pid) of every visible process
+fn parent_pids() -> Vec<(u32, u32)> {
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(entries) = std::fs::read_dir("/proc") else { return vec![] };
+        entries.flatten()
+            .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| {
+                // "pid (comm) state ppid ...": comm may contain spaces and parentheses
+                let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+                let ppid = stat.rsplit_once(')')?.1.split_whitespace().nth(1)?.parse().ok()?;
+                Some((pid, ppid))
+            })
+            .collect()
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let Ok(out) = Command::new("ps").args(["-axo", "pid=,ppid="]).output() else { return vec![] };
+        String::from_utf8_lossy(&out.stdout).lines()
+            .filter_map(|l| {
+                let mut fields = l.split_whitespace();
+                Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+            })
+            .collect()
+    }
+    #[cfg(windows)]
+    {
+        vec![]
+    }
+}
+
+/// Force-kill processes (on Windows with their trees) without waiting; gone ones are skipped
+pub fn kill_all(pids: &[u32]) {
+    let alive: Vec<String> = pids.iter().filter(|p| is_alive(**p)).map(u32::to_string).collect();
+    if alive.is_empty() {
+        return;
+    }
+    #[cfg(unix)]
+    let _ = quiet(Command::new("kill").arg("-KILL").args(&alive)).status();
+    #[cfg(windows)]
+    for pid in &alive {
+        let _ = quiet(Command::new("taskkill").args(["/F", "/T", "/PID", pid])).status();
+    }
+}
+
+fn wait_for_exit(pid: u32, limit: Duration) -> bool {
+    let deadline = Instant::now() + limit;
+    while Instant::now() < deadline {
+        if !is_alive(pid) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    !is_alive(pid)
+}
+
+/// Ask a process to exit (SIGTERM / taskkill), then force it after `grace`
+pub fn terminate(pid: u32, grace: Duration) -> Termination {
+    if !is_alive(pid) {
+        return Termination::NotRunning;
+    }
+
+    #[cfg(unix)]
+    let (polite, force) = (
+        vec!["kill".to_string(), "-TERM".to_string(), pid.to_string()],
+        vec!["kill".to_string(), "-KILL".to_string(), pid.to_string()],
+    );
+    #[cfg(windows)]
+    let (polite, force) = (
+        vec!["taskkill".to_string(), "/PID".to_string(), pid.to_string()],
+        vec!["taskkill".to_string(), "/F".to_string(), "/PID".to_string(), pid.to_string()],
+    );
+
+    let _ = quiet(Command::new(&polite[0]).args(&polite[1..])).status();
+    if wait_for_exit(pid, grace) {
+        return Termination::Terminated;
+    }
+
+    if let Err(e) = quiet(Command::new(&force[0]).args(&force[1..])).status() {
+        return Termination::Failed(e.to_string());
+    }
+    if wait_for_exit(pid, Duration::from_secs(2)) {
+        Termination::Killed
+    } else {
+        Termination::Failed("still running after a forced kill".to_string())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// A dummy process running `script`; a thread reaps it so it does not
+    /// linger as a zombie (which `kill -0` still reports alive)
+    fn dummy(script: &str) -> u32 {
+        let mut child = Command::new("sh").args(["-c", script]).stdin(Stdio::null()).spawn().unwrap();
+        let pid = child.id();
+        std::thread::spawn(move || child.wait());
+        pid
+    }
+
+    /// A pid no process has (one that just exited)
+    fn dead_pid() -> u32 {
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn pid_file_drops_exited_processes_when_adding() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PidFile::instances(dir.path());
+        assert!(file.read().is_empty());
+
+        let sleeper = dummy("sleep 30; true");
+        file.add(ProcessRecord::new(dead_pid(), "gone")).unwrap();
+        file.add(ProcessRecord::new(sleeper, "tui")).unwrap();
+        file.add(ProcessRecord::new(sleeper, "tui again")).unwrap();
+        let records = file.read();
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].pid, records[0].label.as_str()), (sleeper, "tui again"));
+
+        file.remove(sleeper).unwrap();
+        assert!(!file.path().exists(), "an empty list removes the file");
+        kill_all(&[sleeper]);
+    }
+
+    #[test]
+    fn instance_guard_records_this_process_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let guard = InstanceGuard::acquire(dir.path(), "test").unwrap();
+        let records = PidFile::instances(dir.path()).read();
+        assert!(records.iter().any(|r| r.pid == std::process::id() && r.label == "test"));
+        drop(guard);
+        assert!(PidFile::instances(dir.path()).read().is_empty());
+    }
+
+    #[test]
+    fn command_line_and_descendants_of_a_dummy_tree() {
+        let parent = dummy("sleep 30 & sleep 31 & wait");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut children = descendants(parent);
+        while children.len() < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            children = descendants(parent);
+        }
+        assert_eq!(children.len(), 2);
+        assert!(children.iter().all(|c| command_line(*c).is_some_and(|cmd| cmd.starts_with("sleep 3"))));
+        assert!(command_line(parent).unwrap().contains("wait"));
+        assert!(list_processes().iter().any(|(pid, _)| *pid == parent));
+
+        // The shell exits on its own once its children are gone: stop it first
+        assert_eq!(terminate(parent, Duration::from_secs(5)), Termination::Terminated);
+        kill_all(&children);
+        assert!(command_line(dead_pid()).is_none());
+    }
+
+    #[test]
+    fn terminate_is_polite_first_then_forceful() {
+        assert_eq!(terminate(dead_pid(), Duration::from_millis(100)), Termination::NotRunning);
+        assert!(!is_alive(0));
+
+        let polite = dummy("sleep 30; true");
+        assert_eq!(terminate(polite, Duration::from_secs(5)), Termination::Terminated);
+        assert!(!is_alive(polite));
+
+        let stubborn = dummy("trap '' TERM; while true; do sleep 0.1; done");
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(terminate(stubborn, Duration::from_millis(500)), Termination::Killed);
+        assert!(!is_alive(stubborn));
+    }
+}
@@ -0,0 +1,183 @@
+//! Schedule of the built-in background tasks
+//!
+//! The heartbeat, the daily journal and the autonomy engine each run every N
+//! minutes (`[autonomy] heartbeat_minutes`, overridden from .env) or on a
+//! cron expression (`heartbeat_cron = "0 9-18/3 * * 1-5"`, standard five
+//! fields, optional leading seconds), which wins over the interval. A task is
+//! off with `heartbeat_enabled = false` or 0 minutes. `quiet_hours =
+//! "23:00-08:00"` holds back the routine Telegram messages of scheduled runs.
+//! Schedule::from_config validates all of it for the scheduler, `openspore
+//! cron list` and the doctor.
+
+use crate::config::AppConfig;
+use chrono::{DateTime, Local, NaiveTime};
+use std::time::Duration;
+
+/// Hour of the journal when it runs on an interval of a day or more
+pub const JOURNAL_HOUR: u32 = 22;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("[autonomy] {key} = \"{value}\" is not a valid cron expression: {reason}")]
+    Cron { key: String, value: String, reason: String },
+    #[error("[autonomy] quiet_hours = \"{0}\" is not a range like \"23:00-08:00\"")]
+    QuietHours(String),
+}
+
+/// When a task runs
+#[derive(Debug, Clone)]
+pub enum Trigger {
+    Every(Duration),
+    Cron { expression: String, cron: Box<croner::Cron> },
+}
+
+/// One built-in task: "heartbeat", "journal" or "engine"
+#[derive(Debug, Clone)]
+pub struct TaskSchedule {
+    pub task: &'static str,
+    /// None when the task is off
+    pub trigger: Option<Trigger>,
+    /// Why the task is off
+    pub off_reason: Option<&'static str>,
+}
+
+/// Daily window without routine Telegram messages (may span midnight)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    pub tasks: Vec<TaskSchedule>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// A cron expression of five fields (minute first) or six (seconds first)
+pub fn parse_cron(expression: &str) -> Result<croner::Cron, String> {
+    croner::Cron::new(expression.trim()).with_seconds_optional().parse().map_err(|e| e.to_string())
+}
+
+impl Trigger {
+    /// Whether the runs fall at set times (a cron expression, the evening
+    /// journal) rather than counting from the scheduler's start
+    pub fn is_anchored(&self, task: &str) -> bool {
+        match self {
+            Trigger::Cron { .. } => true,
+            Trigger::Every(period) => task == "journal" && *period >= Duration::from_secs(24 * 3600),
+        }
+    }
+
+    /// First run after a start at `now`: one interval away, except a daily (or
+    /// rarer) journal, which runs at JOURNAL_HOUR
+    pub fn first_run(&self, task: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Trigger::Every(_) if self.is_anchored(task) => {
+                let tonight = now.date_naive().and_hms_opt(JOURNAL_HOUR, 0, 0)?.and_local_timezone(Local).earliest()?;
+                Some(if tonight > now { tonight } else { tonight + chrono::Duration::days(1) })
+            }
+            _ => self.next_run(now),
+        }
+    }
+
+    /// Run following one at `after`
+    pub fn next_run(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Trigger::Every(period) => Some(after + chrono::Duration::from_std(*period).ok()?),
+            Trigger::Cron { cron, .. } => cron.find_next_occurrence(&after, false).ok(),
+        }
+    }
+
+    /// "every 2h", "every 1d at 22:00" or "cron 0 9 * * 1-5"
+    pub fn describe(&self, task: &str) -> String {
+        match self {
+            Trigger::Cron { expression, .. } => format!("cron {}", expression),
+            Trigger::Every(period) => {
+                let minutes = period.as_secs() / 60;
+                let every = match minutes {
+                    m if m % 1440 == 0 => format!("every {}d", m / 1440),
+                    m if m % 60 == 0 => format!("every {}h", m / 60),
+                    m => format!("every {}m", m),
+                };
+                if task == "journal" && minutes >= 1440 { format!("{} at {}:00", every, JOURNAL_HOUR) } else { every }
+            }
+        }
+    }
+}
+
+impl TaskSchedule {
+    /// Trigger description, or "off (reason)"
+    pub fn describe(&self) -> String {
+        match &self.trigger {
+            Some(trigger) => trigger.describe(self.task),
+            None => format!("off ({})", self.off_reason.unwrap_or("disabled")),
+        }
+    }
+}
+
+impl QuietHours {
+    pub fn parse(text: &str) -> Option<Self> {
+        let (start, end) = text.split_once('-')?;
+        let time = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok();
+        Some(Self { start: time(start)?, end: time(end)? })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl std::fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start.format("%H:%M"), self.end.format("%H:%M"))
+    }
+}
+
+impl Schedule {
+    /// The effective schedule of `config`, or the first misconfigured key
+    pub fn from_config(config: &AppConfig) -> Result<Self, ScheduleError> {
+        let autonomy = &config.autonomy;
+        let tasks = [
+            ("heartbeat", config.heartbeat_interval(), &autonomy.heartbeat_cron, autonomy.heartbeat_enabled),
+            ("journal", config.journal_interval(), &autonomy.journal_cron, autonomy.journal_enabled),
+            ("engine", config.engine_interval(), &autonomy.engine_cron, autonomy.engine_enabled),
+        ];
+
+        let mut schedule = Schedule::default();
+        for (task, interval, cron, enabled) in tasks {
+            let cron = cron.as_deref().map(str::trim).filter(|c| !c.is_empty());
+            // Validated even when the task is off, so a typo shows before it is switched on
+            let cron = match cron {
+                Some(expression) => Some(parse_cron(expression).map(|cron| Trigger::Cron { expression: expression.to_string(), cron: Box::new(cron) }).map_err(|reason| {
+                    ScheduleError::Cron { key: format!("{}_cron", task), value: expression.to_string(), reason }
+                })?),
+                None => None,
+            };
+            let off_reason = if enabled == Some(false) {
+                Some("disabled")
+            } else if task == "engine" && !config.autonomy_enabled {
+                Some("AUTONOMY_ENABLED=false")
+            } else if cron.is_none() && interval.is_none() {
+                Some("0 minutes")
+            } else {
+                None
+            };
+            let trigger = match off_reason {
+                Some(_) => None,
+                None => cron.or(interval.map(Trigger::Every)),
+            };
+            schedule.tasks.push(TaskSchedule { task, trigger, off_reason });
+        }
+
+        schedule.quiet_hours = match autonomy.quiet_hours.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            Some(text) => Some(QuietHours::parse(text).ok_or_else(|| ScheduleError::QuietHours(text.to_string()))?),
+            None => None,
+        };
+        Ok(schedule)
+    }
+}
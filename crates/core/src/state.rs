@@ -1,3 +1,4 @@
+use crate::capabilities::Capabilities;
 use crate::config::AppConfig;
 
 #[derive(Debug, Clone)]
@@ -5,6 +6,8 @@ pub struct AppState {
     pub config: AppConfig,
     // Placeholder for future memory and autonomy state
     pub memory_path: String,
+    /// Optional external tools (git), probed once per process
+    pub capabilities: Capabilities,
 }
 
 impl AppState {
@@ -18,6 +21,7 @@ impl AppState {
         Self {
             config,
             memory_path,
+            capabilities: crate::capabilities::capabilities(),
         }
     }
 }
@@ -0,0 +1,369 @@
+//! Directories the Watchman observes
+//!
+//! The app root is always watched: its changes keep the search index current
+//! and, by default, are analyzed for knowledge. `[[watchman.watch]]` entries
+//! add directories, each with its own extensions, ignore globs, learning
+//! policy and share of `[watchman] analyses_per_hour`. An entry naming the app
+//! root replaces its defaults. A file belongs to the deepest entry containing
+//! it. The running Watchman keeps per-entry counts in WATCH_STATS_FILE for the
+//! heartbeat.
+
+use crate::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Per-entry counts of the running Watchman, relative to the app root
+pub const WATCH_STATS_FILE: &str = "workspace/autonomy/state/watchman.json";
+
+/// Extensions analyzed when an entry lists none
+pub const DEFAULT_EXTENSIONS: [&str; 3] = [".md", ".txt", ".json"];
+
+/// Name of the app root's entry unless `[[watchman.watch]]` names it
+pub const ROOT_ENTRY: &str = "openspore";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WatchError {
+    #[error("[[watchman.watch]] entry {0} has no path")]
+    EmptyPath(usize),
+    #[error("[[watchman.watch]] \"{entry}\": learning = \"{value}\" is not learn, journal or off")]
+    Learning { entry: String, value: String },
+    #[error("[[watchman.watch]] \"{entry}\": budget_share = {value} is not between 0 and 1")]
+    BudgetShare { entry: String, value: f64 },
+    #[error("[[watchman.watch]] lists {0} twice")]
+    Duplicate(String),
+}
+
+/// What happens to the settled changes of an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LearningPolicy {
+    /// Analyzed by the model, which saves what is worth keeping
+    #[default]
+    Learn,
+    /// Listed in the daily journal, no model call
+    Journal,
+    /// Only counted (the app root still keeps its search index current)
+    Off,
+}
+
+impl LearningPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LearningPolicy::Learn => "learn",
+            LearningPolicy::Journal => "journal",
+            LearningPolicy::Off => "off",
+        }
+    }
+}
+
+impl FromStr for LearningPolicy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "learn" | "" => Ok(LearningPolicy::Learn),
+            "journal" => Ok(LearningPolicy::Journal),
+            "off" | "none" => Ok(LearningPolicy::Off),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A watched directory with its rules
+#[derive(Debug, Clone)]
+pub struct WatchEntry {
+    pub name: String,
+    pub path: PathBuf,
+    /// Lowercase, with the dot: ".md"
+    pub extensions: HashSet<String>,
+    /// Ignore globs of this entry, on top of the Watchman's built-in ones
+    pub ignore: Vec<String>,
+    pub learning: LearningPolicy,
+    pub budget_share: Option<f64>,
+}
+
+impl WatchEntry {
+    fn root(project_root: &Path) -> Self {
+        Self {
+            name: ROOT_ENTRY.to_string(),
+            path: project_root.to_path_buf(),
+            extensions: DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            ignore: Vec::new(),
+            learning: LearningPolicy::Learn,
+            budget_share: None,
+        }
+    }
+
+    /// Whether the extension of `path` is one this entry analyzes
+    pub fn accepts(&self, path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| self.extensions.contains(&format!(".{}", ext.to_string_lossy().to_lowercase())))
+    }
+
+    /// Analyses per hour this entry may use; None when unlimited
+    pub fn hourly_allowance(&self, analyses_per_hour: Option<u32>) -> Option<usize> {
+        let total = analyses_per_hour.filter(|n| *n > 0)? as f64;
+        Some((total * self.budget_share.unwrap_or(1.0)).floor() as usize)
+    }
+}
+
+fn normalize_extension(ext: &str) -> String {
+    format!(".{}", ext.trim().trim_start_matches('.').to_lowercase())
+}
+
+/// `~` expanded, relative paths from the app root, no trailing separator
+fn resolve_path(project_root: &Path, path: &str) -> PathBuf {
+    let path = crate::path_utils::get_path(path.trim());
+    let path = if path.is_absolute() { path } else { project_root.join(path) };
+    path.components().collect()
+}
+
+/// The app root's entry first, then the `[[watchman.watch]]` entries
+pub fn watch_entries(config: &AppConfig) -> Result<Vec<WatchEntry>, WatchError> {
+    let root: PathBuf = config.project_root.components().collect();
+    let mut entries = vec![WatchEntry::root(&root)];
+
+    for (i, raw) in config.watchman.watch.iter().enumerate() {
+        if raw.path.trim().is_empty() {
+            return Err(WatchError::EmptyPath(i + 1));
+        }
+        let path = resolve_path(&root, &raw.path);
+        let is_root = path == root;
+        let name = raw.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_string)
+            .or_else(|| is_root.then(|| ROOT_ENTRY.to_string()))
+            .unwrap_or_else(|| path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string()));
+
+        let learning = match raw.learning.as_deref() {
+            Some(value) => value.parse().map_err(|_| WatchError::Learning { entry: name.clone(), value: value.to_string() })?,
+            None => LearningPolicy::Learn,
+        };
+        if let Some(share) = raw.budget_share
+            && !(0.0..=1.0).contains(&share)
+        {
+            return Err(WatchError::BudgetShare { entry: name, value: share });
+        }
+        let extensions = match &raw.extensions {
+            Some(list) => list.iter().filter(|e| !e.trim().is_empty()).map(|e| normalize_extension(e)).collect(),
+            None => DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+        };
+        let entry = WatchEntry { name, path, extensions, ignore: raw.ignore.clone(), learning, budget_share: raw.budget_share };
+
+        if is_root && !config.watchman.watch[..i].iter().any(|e| resolve_path(&root, &e.path) == root) {
+            entries[0] = entry;
+            continue;
+        }
+        if let Some(existing) = entries.iter().find(|e| e.path == entry.path || e.name == entry.name) {
+            let what = if existing.path == entry.path { entry.path.display().to_string() } else { format!("the name \"{}\"", entry.name) };
+            return Err(WatchError::Duplicate(what));
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Index of the deepest entry containing `path`
+pub fn entry_for<'a>(entries: impl IntoIterator<Item = &'a WatchEntry>, path: &Path) -> Option<usize> {
+    entries.into_iter().enumerate()
+        .filter(|(_, e)| path.starts_with(&e.path))
+        .max_by_key(|(_, e)| e.path.components().count())
+        .map(|(i, _)| i)
+}
+
+/// Counts of one entry since the Watchman started
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryStats {
+    pub name: String,
+    pub path: PathBuf,
+    pub learning: String,
+    /// Files queued after a change
+    pub detected: u64,
+    /// Files sent to the model
+    pub analyzed: u64,
+    /// Memories saved from them
+    pub learned: u64,
+    /// Files listed in the journal
+    pub journaled: u64,
+    /// Files left unanalyzed because the hourly budget was spent
+    pub over_budget: u64,
+}
+
+/// Contents of WATCH_STATS_FILE
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchStats {
+    /// Process of the Watchman
+    pub pid: u32,
+    /// RFC 3339
+    pub started: String,
+    pub updated: String,
+    pub entries: Vec<EntryStats>,
+}
+
+impl WatchStats {
+    pub fn new(entries: &[WatchEntry]) -> Self {
+        let now = chrono::Local::now().to_rfc3339();
+        Self {
+            pid: std::process::id(),
+            started: now.clone(),
+            updated: now,
+            entries: entries.iter().map(|e| EntryStats {
+                name: e.name.clone(),
+                path: e.path.clone(),
+                learning: e.learning.as_str().to_string(),
+                ..Default::default()
+            }).collect(),
+        }
+    }
+
+    pub fn load(project_root: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(project_root.join(WATCH_STATS_FILE)).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    pub fn save(&mut self, project_root: &Path) -> std::io::Result<()> {
+        self.updated = chrono::Local::now().to_rfc3339();
+        let path = project_root.join(WATCH_STATS_FILE);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// Heartbeat line: "👀 Watchman (since 09:12): notes 4 changed, 2 analyzed, 1 learned · ..."
+    pub fn summary(&self) -> String {
+        let since = chrono::DateTime::parse_from_rfc3339(&self.started)
+            .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+            .unwrap_or_else(|_| "-".to_string());
+        if !crate::process::is_alive(self.pid) {
+            return format!("👀 Watchman: not running (last run started {})", since);
+        }
+        let entries: Vec<String> = self.entries.iter().map(|e| {
+            let mut parts = vec![format!("{} changed", e.detected)];
+            if e.analyzed > 0 {
+                parts.push(format!("{} analyzed", e.analyzed));
+            }
+            if e.learned > 0 {
+                parts.push(format!("{} learned", e.learned));
+            }
+            if e.journaled > 0 {
+                parts.push(format!("{} journaled", e.journaled));
+            }
+            if e.over_budget > 0 {
+                parts.push(format!("{} over budget", e.over_budget));
+            }
+            format!("{} {}", e.name, parts.join(", "))
+        }).collect();
+        format!("👀 Watchman (since {}): {}", since, entries.join(" · "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WatchEntryConfig;
+
+    fn config(root: &Path, watch: Vec<WatchEntryConfig>) -> AppConfig {
+        let mut config = AppConfig { project_root: root.to_path_buf(), ..Default::default() };
+        config.watchman.watch = watch;
+        config
+    }
+
+    fn watch(path: &Path) -> WatchEntryConfig {
+        WatchEntryConfig { path: path.display().to_string(), ..Default::default() }
+    }
+
+    fn extensions(entry: &WatchEntry) -> Vec<&str> {
+        let mut list: Vec<&str> = entry.extensions.iter().map(String::as_str).collect();
+        list.sort();
+        list
+    }
+
+    #[test]
+    fn two_directories_keep_their_own_rules() {
+        let root = tempfile::tempdir().unwrap();
+        let (client, notes) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let entries = watch_entries(&config(root.path(), vec![
+            WatchEntryConfig { name: Some("client-a".into()), extensions: Some(vec!["RS".into(), ".toml".into(), " ".into()]), budget_share: Some(0.5), ..watch(client.path()) },
+            WatchEntryConfig { ignore: vec!["drafts/**".into()], learning: Some("Journal".into()), budget_share: Some(0.25), ..watch(notes.path()) },
+        ])).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!((entries[0].name.as_str(), entries[0].path.as_path()), (ROOT_ENTRY, root.path()));
+        assert_eq!(extensions(&entries[0]), [".json", ".md", ".txt"]);
+
+        let (a, b) = (&entries[1], &entries[2]);
+        assert_eq!(a.name, "client-a");
+        assert_eq!(extensions(a), [".rs", ".toml"]);
+        assert!(a.accepts(&client.path().join("src/Main.RS")) && !a.accepts(&client.path().join("README.md")));
+        assert_eq!(b.name, notes.path().file_name().unwrap().to_string_lossy());
+        assert_eq!(extensions(b), [".json", ".md", ".txt"]);
+        assert!(b.accepts(&notes.path().join("todo.md")) && !b.accepts(&notes.path().join("build.rs")));
+        assert_eq!((a.learning, b.learning), (LearningPolicy::Learn, LearningPolicy::Journal));
+        assert_eq!(b.ignore, ["drafts/**"]);
+
+        assert_eq!((a.hourly_allowance(Some(10)), b.hourly_allowance(Some(10))), (Some(5), Some(2)));
+        assert_eq!(entries[0].hourly_allowance(Some(10)), Some(10), "no share: the whole budget");
+        assert_eq!(a.hourly_allowance(None), None);
+        assert_eq!(a.hourly_allowance(Some(0)), None);
+    }
+
+    #[test]
+    fn a_file_belongs_to_the_deepest_entry_containing_it() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let entries = watch_entries(&config(root.path(), vec![
+            WatchEntryConfig { path: "projects/app".into(), ..Default::default() },
+            watch(outside.path()),
+        ])).unwrap();
+        assert_eq!(entries[1].path, root.path().join("projects/app"));
+
+        assert_eq!(entry_for(&entries, &root.path().join("projects/app/src/lib.rs")), Some(1));
+        assert_eq!(entry_for(&entries, &root.path().join("projects/other.md")), Some(0));
+        assert_eq!(entry_for(&entries, &outside.path().join("a.md")), Some(2));
+        assert_eq!(entry_for(&entries, Path::new("/somewhere/else.md")), None);
+    }
+
+    #[test]
+    fn an_entry_naming_the_app_root_replaces_its_defaults() {
+        let root = tempfile::tempdir().unwrap();
+        let entries = watch_entries(&config(root.path(), vec![
+            WatchEntryConfig { path: format!("{}/", root.path().display()), extensions: Some(vec!["org".into()]), learning: Some("off".into()), ..Default::default() },
+        ])).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, ROOT_ENTRY);
+        assert_eq!(extensions(&entries[0]), [".org"]);
+        assert_eq!(entries[0].learning, LearningPolicy::Off);
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let error = |watch: Vec<WatchEntryConfig>| watch_entries(&config(root.path(), watch)).unwrap_err().to_string();
+
+        assert_eq!(error(vec![watch(other.path()), WatchEntryConfig { path: " ".into(), ..Default::default() }]), "[[watchman.watch]] entry 2 has no path");
+        assert!(error(vec![WatchEntryConfig { learning: Some("sometimes".into()), ..watch(other.path()) }]).contains("learning = \"sometimes\""));
+        assert!(error(vec![WatchEntryConfig { budget_share: Some(1.5), ..watch(other.path()) }]).contains("budget_share = 1.5"));
+        assert!(error(vec![watch(other.path()), watch(other.path())]).contains("lists"));
+        assert!(error(vec![WatchEntryConfig { name: Some(ROOT_ENTRY.into()), ..watch(other.path()) }]).contains("the name \"openspore\""));
+    }
+
+    #[test]
+    fn stats_start_at_zero_per_entry_and_round_trip() {
+        let root = tempfile::tempdir().unwrap();
+        let notes = tempfile::tempdir().unwrap();
+        let entries = watch_entries(&config(root.path(), vec![WatchEntryConfig { name: Some("notes".into()), ..watch(notes.path()) }])).unwrap();
+        let mut stats = WatchStats::new(&entries);
+        stats.entries[1].detected = 4;
+        stats.entries[1].analyzed = 2;
+        stats.entries[1].over_budget = 2;
+        stats.save(root.path()).unwrap();
+
+        let loaded = WatchStats::load(root.path()).unwrap();
+        assert_eq!(loaded.pid, std::process::id());
+        let summary = loaded.summary();
+        assert!(summary.contains("openspore 0 changed · notes 4 changed, 2 analyzed, 2 over budget"), "{}", summary);
+    }
+}
@@ -1,27 +1,261 @@
 //! Consolidates integrity checks, permission fixes, and engine recovery.
 //! Exact port of opensporejs/src/utils/doctor.js
+//!
+//! check_all() is the diagnosis alone: it returns the issues and prints
+//! nothing. run() reports them in one of three modes: Check lists them
+//! (`openspore doctor --check`, and the TUI at startup), Fix also applies the
+//! prescriptions (`openspore doctor`, or only reports them with `--dry-run`)
+//! and Json prints the issues and fixes as JSON for scripts and probes
+//! (`openspore doctor --json`, repairing with `--fix`).
 
+use serde::Serialize;
 use std::path::PathBuf;
 use std::process::Command;
 use tracing::info;
 
+/// Marker of a sealed line in encrypted memory files (see openspore-memory crypto)
+const SEALED_PREFIX: &str = "openspore-enc:v1:";
+
+/// Seconds the LLM endpoint gets per request (the OpenRouter model list is large)
+const LLM_CHECK_TIMEOUT_SECS: &str = "10";
+
+/// Alternatives offered for an unknown model
+const MODEL_SUGGESTIONS: usize = 3;
+
 /// Issue severity levels
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Critical,
     Warning,
     Info,
 }
 
+impl Severity {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Severity::Critical => "🔴",
+            Severity::Warning => "🟡",
+            Severity::Info => "🔵",
+        }
+    }
+}
+
+/// What an issue is. The serialized names (MISSING_ENV, ...) are stable:
+/// scripts match on them in `openspore doctor --json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum IssueId {
+    /// MISSING_ENV: no .env in the app root (copied from .env.example by Fix)
+    MissingEnv,
+    /// NO_API_KEY: OPENROUTER_API_KEY missing or left at its placeholder, with a remote LLM
+    NoApiKey,
+    /// NO_TELEGRAM_BOT: TELEGRAM_BOT_TOKEN not configured
+    NoTelegramBot,
+    /// LLM_UNREACHABLE: GET /models of the LLM base URL failed: network error, timeout or HTTP error (meta: the URL)
+    LlmUnreachable,
+    /// LLM_AUTH_FAILED: the LLM endpoint rejected OPENROUTER_API_KEY (HTTP 401/403)
+    LlmAuthFailed,
+    /// MODEL_UNKNOWN: a configured model is not in the endpoint's /models list (meta: the model)
+    ModelUnknown,
+    /// MISSING_DIR: a workspace directory is missing (meta: its path, created by Fix)
+    MissingDir,
+    /// MISSING_SOUL_FILE: an identity or log file is missing (meta: its path, restored by Fix)
+    MissingSoulFile,
+    /// PERM_ERR: the release binary is not executable (meta: its path, chmod +x by Fix)
+    PermErr,
+    /// CRON_NOT_INSTALLED: no OpenSpore job in the crontab
+    CronNotInstalled,
+    /// NO_BINARY: no compiled binary in crates/target
+    NoBinary,
+    /// SEARCH_INDEX_MISSING: workspace/.index not built yet
+    SearchIndexMissing,
+    /// SEARCH_INDEX_STALE: the workspace changed well after the index was written
+    SearchIndexStale,
+    /// IDENTITY_ENCRYPTED: identity files are sealed and unreadable by the prompt
+    IdentityEncrypted,
+    /// MEM_KEY_MISSING: ENCRYPT_MEMORY is on without OPENSPORE_MEM_KEY
+    MemKeyMissing,
+    /// MEMORY_CONFLICT: memories left with both sides of a sync merge (meta: the first)
+    MemoryConflict,
+    /// SCHEDULE_INVALID: `[autonomy]` has a bad cron expression or quiet hours
+    ScheduleInvalid,
+    /// WATCH_CONFIG_INVALID: a `[[watchman.watch]]` entry is invalid
+    WatchConfigInvalid,
+    /// WATCH_PATH_INVALID: a watched directory is missing or unreadable (meta: its path)
+    WatchPathInvalid,
+    /// GIT_MISSING: git is not installed, so the workspace has no version history
+    /// (tarball snapshots in workspace/.snapshots instead)
+    GitMissing,
+    /// CONFIG_INVALID: a setting in .env or openspore.toml has a bad value (meta: the variable)
+    ConfigInvalid,
+}
+
+impl IssueId {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueId::MissingEnv => "MISSING_ENV",
+            IssueId::NoApiKey => "NO_API_KEY",
+            IssueId::NoTelegramBot => "NO_TELEGRAM_BOT",
+            IssueId::LlmUnreachable => "LLM_UNREACHABLE",
+            IssueId::LlmAuthFailed => "LLM_AUTH_FAILED",
+            IssueId::ModelUnknown => "MODEL_UNKNOWN",
+            IssueId::MissingDir => "MISSING_DIR",
+            IssueId::MissingSoulFile => "MISSING_SOUL_FILE",
+            IssueId::PermErr => "PERM_ERR",
+            IssueId::CronNotInstalled => "CRON_NOT_INSTALLED",
+            IssueId::NoBinary => "NO_BINARY",
+            IssueId::SearchIndexMissing => "SEARCH_INDEX_MISSING",
+            IssueId::SearchIndexStale => "SEARCH_INDEX_STALE",
+            IssueId::IdentityEncrypted => "IDENTITY_ENCRYPTED",
+            IssueId::MemKeyMissing => "MEM_KEY_MISSING",
+            IssueId::MemoryConflict => "MEMORY_CONFLICT",
+            IssueId::ScheduleInvalid => "SCHEDULE_INVALID",
+            IssueId::WatchConfigInvalid => "WATCH_CONFIG_INVALID",
+            IssueId::WatchPathInvalid => "WATCH_PATH_INVALID",
+            IssueId::GitMissing => "GIT_MISSING",
+            IssueId::ConfigInvalid => "CONFIG_INVALID",
+        }
+    }
+}
+
+impl std::fmt::Display for IssueId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// A detected issue
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Issue {
-    pub id: String,
+    pub id: IssueId,
     pub label: String,
     pub severity: Severity,
     pub meta: Option<String>,
 }
 
+impl Issue {
+    /// "🔴 NO_API_KEY: OpenRouter API Key is missing or default"
+    pub fn line(&self) -> String {
+        format!("{} {}: {}", self.severity.icon(), self.id, self.label)
+    }
+}
+
+/// What a prescription did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Applied,
+    /// Dry run: what it would have done
+    WouldApply,
+    Failed,
+    /// No automatic repair; the detail says what to do
+    Manual,
+}
+
+/// The prescription of one issue
+#[derive(Debug, Clone, Serialize)]
+pub struct Treatment {
+    pub id: IssueId,
+    pub outcome: Outcome,
+    pub detail: String,
+}
+
+impl Treatment {
+    fn new(id: IssueId, outcome: Outcome, detail: impl Into<String>) -> Self {
+        Self { id, outcome, detail: detail.into() }
+    }
+
+    pub fn line(&self) -> String {
+        let icon = match self.outcome {
+            Outcome::Applied => "✓",
+            Outcome::WouldApply => "WOULD",
+            Outcome::Failed => "❌",
+            Outcome::Manual => "👉",
+        };
+        format!("{} {}", icon, self.detail)
+    }
+}
+
+/// One diagnostic of check_all(), for callers that need a single answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Env,
+    Config,
+    LlmEndpoint,
+    Structure,
+    Permissions,
+    Cron,
+    Engine,
+    SearchIndex,
+    Encryption,
+    Conflicts,
+    Schedule,
+    Watch,
+    Git,
+}
+
+impl Check {
+    /// Every check, in the order check_all() runs them
+    pub const ALL: [Check; 13] = [
+        Check::Env, Check::Config, Check::LlmEndpoint, Check::Structure, Check::Permissions, Check::Cron, Check::Engine,
+        Check::SearchIndex, Check::Encryption, Check::Conflicts, Check::Schedule, Check::Watch, Check::Git,
+    ];
+}
+
+/// How run() reports and whether it repairs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoctorMode {
+    /// List the issues, change nothing
+    Check,
+    /// List the issues and apply their prescriptions (or only report them in a dry run)
+    Fix,
+    /// Print the issues (and, with `fix`, the prescriptions applied) as JSON on stdout
+    Json { fix: bool },
+}
+
+impl DoctorMode {
+    fn fixes(&self) -> bool {
+        matches!(self, DoctorMode::Fix | DoctorMode::Json { fix: true })
+    }
+}
+
+/// Result of run()
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub issues: Vec<Issue>,
+    /// Empty unless the mode fixes
+    pub treatments: Vec<Treatment>,
+}
+
+impl Report {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues.iter().filter(|i| i.severity == severity).count()
+    }
+
+    pub fn has_critical(&self) -> bool {
+        self.count(Severity::Critical) > 0
+    }
+
+    /// No critical or warning issue; info issues are advice
+    pub fn ok(&self) -> bool {
+        !self.has_critical() && self.count(Severity::Warning) == 0
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "ok": self.ok(),
+            "counts": {
+                "critical": self.count(Severity::Critical),
+                "warning": self.count(Severity::Warning),
+                "info": self.count(Severity::Info),
+            },
+            "issues": self.issues,
+            "fixes": self.treatments,
+        })
+    }
+}
+
 /// Colored terminal output
 /// Log wrapper (now using tracing)
 fn log(msg: &str, _color: &str) {
@@ -31,9 +265,16 @@ fn log(msg: &str, _color: &str) {
 /// SporeDoctor - diagnostic and repair utility
 pub struct SporeDoctor {
     root: PathBuf,
-    #[allow(dead_code)]
     workspace: PathBuf,
     issues: Vec<Issue>,
+    /// Passed checks and remarks of the last diagnosis, logged by run()
+    notes: Vec<String>,
+    /// Report prescriptions instead of applying them
+    dry_run: bool,
+    /// Skip the checks that need the network (LLM key and models)
+    offline: bool,
+    /// Model ids the LLM endpoint listed, for the MODEL_UNKNOWN prescription
+    available_models: Vec<String>,
 }
 
 impl SporeDoctor {
@@ -45,27 +286,109 @@ impl SporeDoctor {
             root,
             workspace,
             issues: Vec::new(),
+            notes: Vec::new(),
+            dry_run: false,
+            offline: false,
+            available_models: Vec::new(),
         }
     }
 
-    /// Run all diagnostics
-    pub fn check_all(&mut self) -> bool {
-        log("\n🩺 --- OpenSpore System Diagnostic ---", "blue");
+    /// A doctor whose Fix mode leaves the filesystem untouched
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// A doctor that works without network: the LLM checks are skipped
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Run all diagnostics; prints and repairs nothing
+    pub fn check_all(&mut self) -> Vec<Issue> {
+        self.checks(&Check::ALL)
+    }
 
-        self.check_env();
-        self.check_structure();
-        self.check_permissions();
-        self.check_cron();
-        self.check_engine();
+    /// Run only these diagnostics; prints and repairs nothing
+    pub fn checks(&mut self, checks: &[Check]) -> Vec<Issue> {
+        self.issues.clear();
+        self.notes.clear();
+        for check in checks {
+            match check {
+                Check::Env => self.check_env(),
+                Check::Config => self.check_config(),
+                Check::LlmEndpoint => self.check_llm_endpoint(),
+                Check::Structure => self.check_structure(),
+                Check::Permissions => self.check_permissions(),
+                Check::Cron => self.check_cron(),
+                Check::Engine => self.check_engine(),
+                Check::SearchIndex => self.check_search_index(),
+                Check::Encryption => self.check_encryption(),
+                Check::Conflicts => self.check_conflicts(),
+                Check::Schedule => self.check_schedule(),
+                Check::Watch => self.check_watch(),
+                Check::Git => self.check_git(),
+            }
+        }
+        self.issues.clone()
+    }
+
+    /// Passed checks and remarks of the last diagnosis
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// What the prescriptions of `issues` do (or, in a dry run, would do)
+    pub fn treatments(&self, issues: &[Issue]) -> Vec<Treatment> {
+        self.prescribe(issues)
+    }
 
-        if self.issues.is_empty() {
+    /// Diagnose, then report (and in Fix modes repair) as `mode` says
+    pub fn run(&mut self, mode: DoctorMode) -> Report {
+        let issues = self.check_all();
+        let treatments = if mode.fixes() { self.prescribe(&issues) } else { Vec::new() };
+        let report = Report { issues, treatments };
+
+        if let DoctorMode::Json { .. } = mode {
+            println!("{}", serde_json::to_string_pretty(&report.to_json()).unwrap_or_default());
+            return report;
+        }
+
+        log("\n🩺 --- OpenSpore System Diagnostic ---", "blue");
+        for note in &self.notes {
+            log(note, "green");
+        }
+        if report.issues.is_empty() {
             log("\n✅ SYSTEM OPTIMAL: No issues detected.", "green");
-            true
+            return report;
+        }
+
+        if mode == DoctorMode::Check {
+            log(&format!("\n🔴 Found {} issues ({} critical):\n", report.issues.len(), report.count(Severity::Critical)), "red");
+            for issue in &report.issues {
+                log(&format!("   {}", issue.line()), "yellow");
+            }
+            log("\n👉 Run 'openspore doctor --fix' to apply the prescriptions.\n", "cyan");
+            return report;
+        }
+
+        let action = if self.dry_run { "Prescriptions (dry run)" } else { "Running prescriptions..." };
+        log(&format!("\n🔴 Found {} issues. {}\n", report.issues.len(), action), "red");
+        for (issue, treatment) in report.issues.iter().zip(&report.treatments) {
+            log(&format!("💊 Treatment for {}: {}...", issue.id, issue.label), "yellow");
+            log(&format!("   {}", treatment.line()), "gray");
+        }
+        if self.dry_run {
+            log("\n🔍 Dry run: nothing was changed. Run 'openspore doctor' to apply the prescriptions.\n", "cyan");
         } else {
-            log(&format!("\n🔴 Found {} issues. Running prescriptions...\n", self.issues.len()), "red");
-            self.prescribe();
-            false
+            log("\n✨ System has been patched. Run 'openspore stop && openspore start' if issues were critical.\n", "cyan");
         }
+        report
+    }
+
+    fn note(&mut self, line: &str) {
+        self.notes.push(line.to_string());
     }
 
     // --- DIAGNOSTICS ---
@@ -75,7 +398,7 @@ impl SporeDoctor {
 
         if !env_path.exists() {
             self.issues.push(Issue {
-                id: "MISSING_ENV".to_string(),
+                id: IssueId::MissingEnv,
                 label: ".env file not found".to_string(),
                 severity: Severity::Critical,
                 meta: None,
@@ -84,9 +407,10 @@ impl SporeDoctor {
         }
 
         if let Ok(content) = std::fs::read_to_string(&env_path) {
-            if !content.contains("OPENROUTER_API_KEY") || content.contains("YOUR_KEY_HERE") {
+            let is_local = openspore_core::config::is_local_url(&self.llm_base_url());
+            if !is_local && (!content.contains("OPENROUTER_API_KEY") || content.contains("YOUR_KEY_HERE")) {
                 self.issues.push(Issue {
-                    id: "NO_API_KEY".to_string(),
+                    id: IssueId::NoApiKey,
                     label: "OpenRouter API Key is missing or default".to_string(),
                     severity: Severity::Critical,
                     meta: None,
@@ -94,7 +418,7 @@ impl SporeDoctor {
             }
             if !content.contains("TELEGRAM_BOT_TOKEN") {
                 self.issues.push(Issue {
-                    id: "NO_TELEGRAM_BOT".to_string(),
+                    id: IssueId::NoTelegramBot,
                     label: "Telegram Bot Token not configured".to_string(),
                     severity: Severity::Warning,
                     meta: None,
@@ -102,7 +426,197 @@ impl SporeDoctor {
             }
         }
 
-        log("✅ .env file found", "green");
+        self.note("✅ .env file found");
+        self.note(&format!("📜 Log filter: {}", openspore_core::logging::current_filter()));
+    }
+
+    /// The configuration, with bad values left at their defaults (check_config reports them)
+    fn config(&self) -> Option<openspore_core::config::AppConfig> {
+        openspore_core::config::AppConfig::load_with_problems().ok().map(|(config, _)| config)
+    }
+
+    /// Values the configuration rejects or ignores (AppConfig::load_with_problems)
+    fn check_config(&mut self) {
+        match openspore_core::config::AppConfig::load_with_problems() {
+            Ok((_, problems)) if problems.is_empty() => self.note("✅ Configuration values are valid"),
+            Ok((_, problems)) => {
+                for problem in problems {
+                    self.issues.push(Issue {
+                        id: IssueId::ConfigInvalid,
+                        label: problem.to_string(),
+                        severity: Severity::Warning,
+                        meta: Some(problem.key),
+                    });
+                }
+            }
+            Err(e) => self.issues.push(Issue {
+                id: IssueId::ConfigInvalid,
+                label: format!("Configuration does not load: {}", e),
+                severity: Severity::Critical,
+                meta: None,
+            }),
+        }
+    }
+
+    /// A setting from the process env first, then .env
+    fn env_value(&self, key: &str) -> Option<String> {
+        let prefix = format!("{}=", key);
+        let from_env_file = || {
+            std::fs::read_to_string(self.root.join(".env")).ok().and_then(|content| {
+                content.lines()
+                    .filter_map(|l| l.trim().strip_prefix(prefix.as_str()))
+                    .map(|v| v.trim().trim_matches('"').to_string())
+                    .next_back()
+            })
+        };
+        std::env::var(key).ok().or_else(from_env_file)
+    }
+
+    /// Resolve the configured LLM base URL (process env first, then .env)
+    fn llm_base_url(&self) -> String {
+        self.env_value("OPENSPORE_LLM_BASE_URL")
+            .filter(|u| !u.trim().is_empty())
+            .unwrap_or_else(|| openspore_core::config::DEFAULT_LLM_BASE_URL.to_string())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// GET with the API key (sent on stdin, not on the command line); the
+    /// HTTP status (0 when nothing answered) and body, None without curl
+    fn http_get(&self, url: &str, key: &str) -> Option<(u16, String)> {
+        use std::io::Write;
+        let mut child = Command::new("curl")
+            .args(["-s", "--max-time", LLM_CHECK_TIMEOUT_SECS, "-H", "@-", "-w", "\n%{http_code}", url])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .ok()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let header = if key.is_empty() { String::new() } else { format!("Authorization: Bearer {}\n", key) };
+            let _ = stdin.write_all(header.as_bytes());
+        }
+        let output = child.wait_with_output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let (body, code) = text.rsplit_once('\n').unwrap_or(("", &text));
+        Some((code.trim().parse().unwrap_or(0), body.to_string()))
+    }
+
+    /// Models the configuration names, with the setting of each (primary,
+    /// fast, vision, fallbacks); empty with the native Ollama provider
+    fn configured_models(&self) -> Vec<(String, &'static str)> {
+        use openspore_core::config::LlmProvider;
+        let mut models = Vec::new();
+        let mut add = |model: Option<String>, setting: &'static str| {
+            if let Some(model) = model.map(|m| m.trim().to_string()).filter(|m| !m.is_empty())
+                && !models.iter().any(|(m, _)| *m == model)
+            {
+                models.push((model, setting));
+            }
+        };
+        match self.config() {
+            Some(config) if config.provider() == LlmProvider::Ollama => {}
+            Some(config) => {
+                add(config.model.clone(), "OPENROUTER_MODEL");
+                add(config.model_fast.clone(), "OPENROUTER_MODEL_FAST");
+                add(config.model_vision.clone(), "OPENROUTER_MODEL_VISION");
+                for model in config.model_fallbacks() {
+                    add(Some(model), "OPENROUTER_MODEL_REASONING_FALLBACKS");
+                }
+            }
+            None => {
+                for setting in ["OPENROUTER_MODEL", "OPENROUTER_MODEL_FAST", "OPENROUTER_MODEL_VISION"] {
+                    add(self.env_value(setting), setting);
+                }
+            }
+        }
+        models
+    }
+
+    /// The endpoint answers, accepts the key and offers the configured models
+    fn check_llm_endpoint(&mut self) {
+        if self.offline {
+            self.note("⏭️ Offline: LLM endpoint, key and model checks skipped");
+            return;
+        }
+        let base = self.llm_base_url();
+        let models_url = format!("{}/models", base);
+        let key = self.config()
+            .map(|c| c.openrouter_api_key)
+            .or_else(|| self.env_value("OPENROUTER_API_KEY"))
+            .unwrap_or_default();
+        let key = if key.contains("YOUR_KEY_HERE") { String::new() } else { key.trim().to_string() };
+
+        let Some((code, body)) = self.http_get(&models_url, &key) else {
+            self.note("⚠️ curl not available, skipping LLM endpoint check");
+            return;
+        };
+        match code {
+            200..=299 => self.note(&format!("✅ LLM endpoint reachable ({})", models_url)),
+            401 | 403 => {
+                self.push_auth_failed(code);
+                return;
+            }
+            0 => {
+                self.issues.push(Issue {
+                    id: IssueId::LlmUnreachable,
+                    label: "LLM endpoint unreachable (network error or timeout on GET /models)".to_string(),
+                    severity: Severity::Warning,
+                    meta: Some(models_url),
+                });
+                return;
+            }
+            _ => {
+                self.issues.push(Issue {
+                    id: IssueId::LlmUnreachable,
+                    label: format!("LLM endpoint did not respond to GET /models (HTTP {})", code),
+                    severity: Severity::Warning,
+                    meta: Some(models_url),
+                });
+                return;
+            }
+        }
+
+        // OpenRouter lists its models to anyone: its /key endpoint checks the key
+        if !key.is_empty() && base.contains("openrouter.ai")
+            && let Some((code @ (401 | 403), _)) = self.http_get(&format!("{}/key", base), &key)
+        {
+            self.push_auth_failed(code);
+        }
+
+        self.available_models = serde_json::from_str::<serde_json::Value>(&body).ok()
+            .and_then(|json| json.get("data").and_then(|d| d.as_array()).cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|m| m.get("id").and_then(|id| id.as_str()).map(str::to_string))
+            .collect();
+        if self.available_models.is_empty() {
+            return;
+        }
+        let mut unknown = 0;
+        for (model, setting) in self.configured_models() {
+            if !self.available_models.contains(&model) {
+                unknown += 1;
+                self.issues.push(Issue {
+                    id: IssueId::ModelUnknown,
+                    label: format!("{} = {} is not offered by {}", setting, model, base),
+                    severity: Severity::Warning,
+                    meta: Some(model),
+                });
+            }
+        }
+        if unknown == 0 {
+            self.note(&format!("✅ Configured models available ({} offered)", self.available_models.len()));
+        }
+    }
+
+    fn push_auth_failed(&mut self, code: u16) {
+        self.issues.push(Issue {
+            id: IssueId::LlmAuthFailed,
+            label: format!("The LLM endpoint rejected OPENROUTER_API_KEY (HTTP {})", code),
+            severity: Severity::Critical,
+            meta: None,
+        });
     }
 
     fn check_structure(&mut self) {
@@ -121,10 +635,10 @@ impl SporeDoctor {
         for d in dirs {
             let path = self.root.join(d);
             if path.exists() {
-                log(&format!("✅ {} exists", d), "green");
+                self.note(&format!("✅ {} exists", d));
             } else {
                 self.issues.push(Issue {
-                    id: "MISSING_DIR".to_string(),
+                    id: IssueId::MissingDir,
                     label: format!("Directory missing: {}", d),
                     severity: Severity::Warning,
                     meta: Some(d.to_string()),
@@ -143,10 +657,10 @@ impl SporeDoctor {
         for f in soul_files {
             let path = self.root.join(f);
             if path.exists() {
-                log(&format!("✅ {} exists", f), "green");
+                self.note(&format!("✅ {} exists", f));
             } else {
                 self.issues.push(Issue {
-                    id: "MISSING_SOUL_FILE".to_string(),
+                    id: IssueId::MissingSoulFile,
                     label: format!("Critical file missing: {}", f),
                     severity: Severity::Warning,
                     meta: Some(f.to_string()),
@@ -161,19 +675,19 @@ impl SporeDoctor {
             use std::os::unix::fs::PermissionsExt;
 
             let binary = self.root.join("crates/target/release/openspore");
-            if binary.exists() {
-                if let Ok(meta) = std::fs::metadata(&binary) {
-                    let mode = meta.permissions().mode();
-                    if mode & 0o111 != 0 {
-                        log("✅ Binary is executable", "green");
-                    } else {
-                        self.issues.push(Issue {
-                            id: "PERM_ERR".to_string(),
-                            label: "Binary not executable".to_string(),
-                            severity: Severity::Warning,
-                            meta: Some(binary.to_string_lossy().to_string()),
-                        });
-                    }
+            if binary.exists()
+                && let Ok(meta) = std::fs::metadata(&binary)
+            {
+                let mode = meta.permissions().mode();
+                if mode & 0o111 != 0 {
+                    self.note("✅ Binary is executable");
+                } else {
+                    self.issues.push(Issue {
+                        id: IssueId::PermErr,
+                        label: "Binary not executable".to_string(),
+                        severity: Severity::Warning,
+                        meta: Some(binary.to_string_lossy().to_string()),
+                    });
                 }
             }
         }
@@ -188,10 +702,10 @@ impl SporeDoctor {
             Ok(out) => {
                 let content = String::from_utf8_lossy(&out.stdout);
                 if content.contains("openspore") {
-                    log("✅ OpenSpore jobs found in crontab", "green");
+                    self.note("✅ OpenSpore jobs found in crontab");
                 } else {
                     self.issues.push(Issue {
-                        id: "CRON_NOT_INSTALLED".to_string(),
+                        id: IssueId::CronNotInstalled,
                         label: "OpenSpore jobs not found in crontab".to_string(),
                         severity: Severity::Info,
                         meta: None,
@@ -200,7 +714,7 @@ impl SporeDoctor {
             }
             Err(_) => {
                 self.issues.push(Issue {
-                    id: "CRON_NOT_INSTALLED".to_string(),
+                    id: IssueId::CronNotInstalled,
                     label: "System crontab appears empty/inactive".to_string(),
                     severity: Severity::Info,
                     meta: None,
@@ -213,14 +727,14 @@ impl SporeDoctor {
         // Check if Rust binary exists
         let binary = self.root.join("crates/target/release/openspore");
         if binary.exists() {
-            log("✅ Rust binary found (release)", "green");
+            self.note("✅ Rust binary found (release)");
         } else {
             let debug_binary = self.root.join("crates/target/debug/openspore");
             if debug_binary.exists() {
-                log("⚠️ Only debug binary found (run: cargo build --release)", "yellow");
+                self.note("⚠️ Only debug binary found (run: cargo build --release)");
             } else {
                 self.issues.push(Issue {
-                    id: "NO_BINARY".to_string(),
+                    id: IssueId::NoBinary,
                     label: "No compiled binary found".to_string(),
                     severity: Severity::Critical,
                     meta: None,
@@ -234,92 +748,318 @@ impl SporeDoctor {
             let count = std::fs::read_dir(&skills_path)
                 .map(|r| r.filter(|e| e.is_ok()).count())
                 .unwrap_or(0);
-            log(&format!("✅ skills/ directory ({} plugins)", count), "green");
+            self.note(&format!("✅ skills/ directory ({} plugins)", count));
+        }
+    }
+
+    fn check_search_index(&mut self) {
+        let index_path = self.workspace.join(".index").join("search.bin");
+        let Ok(index_modified) = std::fs::metadata(&index_path).and_then(|m| m.modified()) else {
+            self.issues.push(Issue {
+                id: IssueId::SearchIndexMissing,
+                label: "Search index not built yet (first search will be slow)".to_string(),
+                severity: Severity::Info,
+                meta: None,
+            });
+            return;
+        };
+
+        // Stale: workspace files changed well after the index was last written
+        let grace = std::time::Duration::from_secs(24 * 3600);
+        let stale = walkdir::WalkDir::new(&self.workspace)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git" && e.file_name() != ".index" && e.file_name() != "audit" && e.file_name() != "debug")
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.metadata().ok()?.modified().ok())
+            .any(|m| m.duration_since(index_modified).is_ok_and(|d| d > grace));
+
+        if stale {
+            self.issues.push(Issue {
+                id: IssueId::SearchIndexStale,
+                label: "Search index is older than recent workspace changes".to_string(),
+                severity: Severity::Warning,
+                meta: None,
+            });
+        } else {
+            self.note("✅ Search index is up to date");
+        }
+    }
+
+    /// Encrypted memory needs its key, and identity files must stay readable
+    fn check_encryption(&mut self) {
+        let enabled = self.env_value("ENCRYPT_MEMORY").is_some_and(|v| matches!(v.trim(), "true" | "1"));
+        let has_key = self.env_value("OPENSPORE_MEM_KEY").is_some_and(|k| !k.trim().is_empty());
+        let is_sealed = |path: &std::path::Path| {
+            std::fs::read_to_string(path).is_ok_and(|c| c.starts_with(SEALED_PREFIX) || c.contains(&format!("\n{}", SEALED_PREFIX)))
+        };
+
+        let sealed_identity: Vec<String> = std::fs::read_dir(self.workspace.join("identity"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| is_sealed(p))
+            .map(|p| p.file_name().unwrap_or_default().to_string_lossy().to_string())
+            .collect();
+        if !sealed_identity.is_empty() {
+            self.issues.push(Issue {
+                id: IssueId::IdentityEncrypted,
+                label: format!("Identity files are encrypted and unreadable by the prompt: {}", sealed_identity.join(", ")),
+                severity: Severity::Critical,
+                meta: None,
+            });
+        }
+
+        if !enabled && !has_key {
+            return;
+        }
+        if !has_key {
+            self.issues.push(Issue {
+                id: IssueId::MemKeyMissing,
+                label: "ENCRYPT_MEMORY is on but OPENSPORE_MEM_KEY is not set (memories cannot be saved or read)".to_string(),
+                severity: Severity::Critical,
+                meta: None,
+            });
+        } else if enabled {
+            self.note("🔒 Memory encryption is on");
+        }
+    }
+
+    /// Memories left with both versions by a sync merge. Encrypted files are
+    /// not readable here; `openspore memory conflicts` lists those too.
+    fn check_conflicts(&mut self) {
+        let conflicted: Vec<String> = walkdir::WalkDir::new(&self.workspace)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git" && e.file_name() != ".index")
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+            .filter(|e| {
+                std::fs::read_to_string(e.path()).is_ok_and(|c| {
+                    c.strip_prefix("---\n")
+                        .and_then(|rest| rest.find("\n---").map(|end| rest[..end].lines().any(|l| l.trim() == "conflict: true")))
+                        .unwrap_or(false)
+                })
+            })
+            .map(|e| e.path().strip_prefix(&self.workspace).unwrap_or(e.path()).display().to_string())
+            .collect();
+
+        if conflicted.is_empty() {
+            self.note("✅ No unresolved sync conflicts");
+            return;
+        }
+        self.issues.push(Issue {
+            id: IssueId::MemoryConflict,
+            label: format!("Unresolved sync conflicts: {}", conflicted.join(", ")),
+            severity: Severity::Warning,
+            meta: conflicted.first().cloned(),
+        });
+    }
+
+    /// Cron expressions and quiet hours of `[autonomy]` (skipped when the
+    /// configuration does not load; check_config reports that)
+    fn check_schedule(&mut self) {
+        let Some(config) = self.config() else { return };
+        match openspore_core::schedule::Schedule::from_config(&config) {
+            Ok(_) => self.note("✅ Autonomy schedule is valid"),
+            Err(e) => self.issues.push(Issue {
+                id: IssueId::ScheduleInvalid,
+                label: e.to_string(),
+                severity: Severity::Warning,
+                meta: None,
+            }),
+        }
+    }
+
+    /// `[[watchman.watch]]` entries: valid, and each path a readable directory
+    fn check_watch(&mut self) {
+        let Some(config) = self.config() else { return };
+        let entries = match openspore_core::watch::watch_entries(&config) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.issues.push(Issue {
+                    id: IssueId::WatchConfigInvalid,
+                    label: e.to_string(),
+                    severity: Severity::Warning,
+                    meta: None,
+                });
+                return;
+            }
+        };
+        let mut ok = true;
+        for entry in &entries {
+            let problem = if !entry.path.is_dir() {
+                Some("does not exist or is not a directory")
+            } else if std::fs::read_dir(&entry.path).is_err() {
+                Some("is not readable")
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                ok = false;
+                self.issues.push(Issue {
+                    id: IssueId::WatchPathInvalid,
+                    label: format!("Watched directory \"{}\" ({}) {}", entry.name, entry.path.display(), problem),
+                    severity: Severity::Warning,
+                    meta: Some(entry.path.display().to_string()),
+                });
+            }
+        }
+        if ok {
+            self.note(&format!("✅ Watchman: {} watched director{} readable", entries.len(), if entries.len() == 1 { "y" } else { "ies" }));
+        }
+    }
+
+    /// git versions the workspace; without it only snapshots are kept
+    fn check_git(&mut self) {
+        if !openspore_core::capabilities::capabilities().git {
+            self.issues.push(Issue {
+                id: IssueId::GitMissing,
+                label: "git is not installed: no workspace history, sync or merge driver (tarball snapshots in workspace/.snapshots instead)".to_string(),
+                severity: Severity::Warning,
+                meta: None,
+            });
+        } else if self.workspace.join(".git").exists() {
+            self.note("✅ Workspace is versioned with git");
+        } else {
+            self.note("⚠️ git is installed but the workspace has no repository yet (created at the next start)");
         }
     }
 
     // --- PRESCRIPTIONS (Fixes) ---
 
-    fn prescribe(&mut self) {
-        for issue in self.issues.clone() {
-            log(&format!("💊 Treatment for {}: {}...", issue.id, issue.label), "yellow");
+    /// One treatment per issue, in order; applied unless this is a dry run
+    fn prescribe(&self, issues: &[Issue]) -> Vec<Treatment> {
+        issues.iter().map(|issue| self.treat(issue)).collect()
+    }
+
+    fn treat(&self, issue: &Issue) -> Treatment {
+        let id = issue.id;
+        let manual = |detail: String| Treatment::new(id, Outcome::Manual, detail);
+        let meta = issue.meta.as_deref().unwrap_or_default();
 
-            match issue.id.as_str() {
-                "MISSING_DIR" => {
-                    if let Some(dir) = &issue.meta {
-                        let path = self.root.join(dir);
-                        if std::fs::create_dir_all(&path).is_ok() {
-                            log(&format!("   ✓ Created directory: {}", dir), "gray");
-                        }
-                    }
+        match id {
+            IssueId::MissingDir => {
+                if self.dry_run {
+                    return Treatment::new(id, Outcome::WouldApply, format!("create directory: {}", meta));
                 }
-                "MISSING_SOUL_FILE" => {
-                    if let Some(file) = &issue.meta {
-                        let path = self.root.join(file);
-
-                        // Try to find template in crates/core/identity template
-                        let filename = path.file_name().and_then(|f| f.to_str()).unwrap_or("");
-                        let template_path = self.root.join("crates/core/identity template").join(filename);
-
-                        let content = if template_path.exists() {
-                            std::fs::read_to_string(&template_path).unwrap_or_else(|_| {
-                                format!("# {}\nRestoration failed, source template unreadable.", filename)
-                            })
-                        } else if file.contains("QUEUE.md") {
-                            "# Proactive Queue\n\n## Pending\n\n## Completed\n".to_string()
-                        } else if file.contains("LOGS.md") {
-                            "# System Logs\n\nInitialized by Doctor.\n".to_string()
-                        } else {
-                            format!("# {}\nInitial engine established by Doctor.", filename)
-                        };
-
-                        // Create parent directory if needed
-                        if let Some(parent) = path.parent() {
-                            std::fs::create_dir_all(parent).ok();
-                        }
-
-                        if std::fs::write(&path, content).is_ok() {
-                            log(&format!("   ✓ Restored identity file from template: {}", file), "gray");
-                        }
-                    }
+                match std::fs::create_dir_all(self.root.join(meta)) {
+                    Ok(()) => Treatment::new(id, Outcome::Applied, format!("Created directory: {}", meta)),
+                    Err(e) => Treatment::new(id, Outcome::Failed, format!("Could not create {}: {}", meta, e)),
                 }
-                "PERM_ERR" => {
-                    if let Some(path) = &issue.meta {
-                        let _ = Command::new("chmod")
-                            .args(["+x", path])
-                            .status();
-                        log("   ✓ Fixed permissions", "gray");
-                    }
+            }
+            IssueId::MissingSoulFile => {
+                let path = self.root.join(meta);
+
+                // Try to find template in crates/core/identity template
+                let filename = path.file_name().and_then(|f| f.to_str()).unwrap_or("");
+                let template_path = self.root.join("crates/core/identity template").join(filename);
+                if self.dry_run {
+                    let source = if template_path.exists() { "from its template" } else { "as a stub" };
+                    return Treatment::new(id, Outcome::WouldApply, format!("write {} {}", meta, source));
                 }
-                "CRON_NOT_INSTALLED" => {
-                    log("   👉 Run 'openspore cron install' to set up cron jobs", "magenta");
+
+                let content = if template_path.exists() {
+                    std::fs::read_to_string(&template_path).unwrap_or_else(|_| {
+                        format!("# {}\nRestoration failed, source template unreadable.", filename)
+                    })
+                } else if meta.contains("QUEUE.md") {
+                    "# Proactive Queue\n\n## Pending\n\n## Completed\n".to_string()
+                } else if meta.contains("LOGS.md") {
+                    "# System Logs\n\nInitialized by Doctor.\n".to_string()
+                } else {
+                    format!("# {}\nInitial engine established by Doctor.", filename)
+                };
+
+                // Create parent directory if needed
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).ok();
                 }
-                "NO_BINARY" => {
-                    log("   👉 Run 'cargo build --release' in crates/", "magenta");
+
+                match std::fs::write(&path, content) {
+                    Ok(()) => Treatment::new(id, Outcome::Applied, format!("Restored identity file from template: {}", meta)),
+                    Err(e) => Treatment::new(id, Outcome::Failed, format!("Could not write {}: {}", meta, e)),
                 }
-                "MISSING_ENV" => {
-                    // Create .env from example if possible
-                    let example = self.root.join(".env.example");
-                    let target = self.root.join(".env");
-                    if example.exists() {
-                        if std::fs::copy(&example, &target).is_ok() {
-                            log("   ✓ Created .env from .env.example", "gray");
-                            log("   👉 Edit .env and add your API keys", "magenta");
-                        }
-                    } else {
-                        log("   👉 Create .env file with OPENROUTER_API_KEY", "magenta");
-                    }
+            }
+            IssueId::PermErr => {
+                if self.dry_run {
+                    return Treatment::new(id, Outcome::WouldApply, format!("run chmod +x {}", meta));
                 }
-                _ => {
-                    log(&format!("   ❌ Auto-repair not available for {}. Manual intervention required.", issue.id), "red");
+                match Command::new("chmod").args(["+x", meta]).status() {
+                    Ok(status) if status.success() => Treatment::new(id, Outcome::Applied, "Fixed permissions"),
+                    _ => Treatment::new(id, Outcome::Failed, format!("chmod +x {} failed", meta)),
                 }
             }
+            IssueId::MissingEnv => {
+                // Create .env from example if possible
+                let example = self.root.join(".env.example");
+                if !example.exists() {
+                    return manual("Create .env file with OPENROUTER_API_KEY".to_string());
+                }
+                if self.dry_run {
+                    return Treatment::new(id, Outcome::WouldApply, "create .env from .env.example");
+                }
+                match std::fs::copy(&example, self.root.join(".env")) {
+                    Ok(_) => Treatment::new(id, Outcome::Applied, "Created .env from .env.example; edit it and add your API keys"),
+                    Err(e) => Treatment::new(id, Outcome::Failed, format!("Could not copy .env.example: {}", e)),
+                }
+            }
+            IssueId::CronNotInstalled => manual("Run 'openspore cron install' to set up cron jobs".to_string()),
+            IssueId::LlmUnreachable => manual(format!("Check that the server behind {} is running, or fix OPENSPORE_LLM_BASE_URL in .env", meta)),
+            IssueId::LlmAuthFailed => manual("Replace OPENROUTER_API_KEY in .env with a valid key (https://openrouter.ai/keys), or 'openspore doctor --offline' to skip this check".to_string()),
+            IssueId::ModelUnknown => {
+                let similar = similar_models(meta, &self.available_models);
+                if similar.is_empty() {
+                    manual(format!("Pick a model listed by {}/models", self.llm_base_url()))
+                } else {
+                    manual(format!("Use a listed model instead of {}, such as {}", meta, similar.join(", ")))
+                }
+            }
+            IssueId::SearchIndexMissing | IssueId::SearchIndexStale => {
+                manual("Run 'openspore memory reindex' to rebuild the search index".to_string())
+            }
+            IssueId::MemKeyMissing => manual("Set OPENSPORE_MEM_KEY in .env (the passphrase the memories were encrypted with)".to_string()),
+            IssueId::IdentityEncrypted => manual("Restore the identity files from git history (workspace/.git), a snapshot (workspace/.snapshots) or the identity templates".to_string()),
+            IssueId::MemoryConflict => {
+                let example = issue.meta.as_deref().unwrap_or("<path>");
+                manual(format!("Run 'openspore memory resolve {} --ours|--theirs|--llm' for each file", example))
+            }
+            IssueId::ScheduleInvalid => manual("Fix [autonomy] in openspore.toml, then /reload in the TUI. Scheduled tasks stay off until then.".to_string()),
+            IssueId::WatchConfigInvalid => manual("Fix [[watchman.watch]] in openspore.toml. Until then the Watchman only watches the app root.".to_string()),
+            IssueId::WatchPathInvalid => manual("Create the directory, fix its permissions or remove its [[watchman.watch]] entry. The Watchman skips it meanwhile.".to_string()),
+            IssueId::GitMissing => manual(openspore_core::capabilities::GIT_INSTALL_HINT.to_string()),
+            IssueId::ConfigInvalid => {
+                let setting = if meta.is_empty() { "the reported setting" } else { meta };
+                manual(format!("Fix {} in .env or openspore.toml; a running TUI picks the change up", setting))
+            }
+            IssueId::NoBinary => manual("Run 'cargo build --release' in crates/".to_string()),
+            IssueId::NoApiKey => manual("Add OPENROUTER_API_KEY to .env, or point OPENSPORE_LLM_BASE_URL at a local server".to_string()),
+            IssueId::NoTelegramBot => manual("Add TELEGRAM_BOT_TOKEN to .env to chat over Telegram (optional)".to_string()),
         }
-
-        log("\n✨ System has been patched. Run 'openspore stop && openspore start' if issues were critical.\n", "cyan");
     }
 }
 
+/// The listed models closest to `model`: sharing most of its name parts
+/// ("anthropic/claude-3.5-sonet" → "anthropic/claude-3.5-sonnet")
+fn similar_models<'a>(model: &str, available: &'a [String]) -> Vec<&'a str> {
+    let parts = |name: &str| -> Vec<String> {
+        name.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|p| !p.is_empty()).map(str::to_string).collect()
+    };
+    let wanted = parts(model);
+    let provider = model.split_once('/').map(|(p, _)| p.to_lowercase());
+    let mut scored: Vec<(usize, &str)> = available.iter()
+        .map(|id| {
+            let theirs = parts(id);
+            let shared = wanted.iter().filter(|p| theirs.contains(p)).count();
+            let same_provider = provider.as_deref().is_some_and(|p| id.to_lowercase().starts_with(&format!("{}/", p)));
+            (shared * 2 + usize::from(same_provider), id.as_str())
+        })
+        .filter(|(score, _)| *score > 1)
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.len().cmp(&b.1.len())));
+    scored.into_iter().take(MODEL_SUGGESTIONS).map(|(_, id)| id).collect()
+}
+
 impl Default for SporeDoctor {
     fn default() -> Self {
         Self::new()
@@ -1,4 +1,6 @@
 use async_trait::async_trait;
+use std::path::Path;
+use std::str::FromStr;
 use thiserror::Error;
 
 pub mod shell;
@@ -9,12 +11,21 @@ pub mod macos;
 #[cfg(target_os = "linux")]
 pub mod linux;
 
+#[cfg(target_os = "windows")]
+pub mod windows;
+
 #[derive(Error, Debug)]
 pub enum IoError {
     #[error("Command failed: {0}")]
     CommandError(String),
     #[error("Platform not supported")]
     UnsupportedPlatform,
+    #[error("No display to capture: {0}")]
+    NoDisplay(String),
+    #[error("{0} is not running")]
+    NotRunning(&'static str),
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
     #[error("IO Error: {0}")]
     StdIo(#[from] std::io::Error),
 }
@@ -35,6 +46,63 @@ pub trait NativeBridge {
 
     /// Get spotify current track info
     async fn get_spotify_status(&self) -> Result<String, IoError>;
+
+    /// Control Spotify playback (see PlaybackCommand) and describe the state
+    /// afterwards: "Playing: Artist - Track (volume 60%)". NotRunning when
+    /// Spotify is closed.
+    async fn spotify_control(&self, command: &str) -> Result<String, IoError>;
+
+    /// Text on the system clipboard
+    async fn get_clipboard(&self) -> Result<String, IoError>;
+
+    /// Replace the system clipboard with text
+    async fn set_clipboard(&self, text: &str) -> Result<(), IoError>;
+
+    /// Save a PNG of the whole screen (every monitor) to out_path
+    async fn capture_screen(&self, out_path: &Path) -> Result<(), IoError>;
+}
+
+/// A command of spotify_control: play, pause, toggle, next, previous, volume <0-100>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackCommand {
+    Play,
+    Pause,
+    Toggle,
+    Next,
+    Previous,
+    /// Percent
+    Volume(u8),
+}
+
+pub const PLAYBACK_USAGE: &str = "play, pause, toggle, next, previous or volume <0-100>";
+
+impl FromStr for PlaybackCommand {
+    type Err = IoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let (verb, rest) = s.split_once(char::is_whitespace).map(|(v, r)| (v, r.trim())).unwrap_or((s.as_str(), ""));
+        let invalid = || IoError::InvalidCommand(format!("\"{}\": use {}", s, PLAYBACK_USAGE));
+        match (verb, rest) {
+            ("play" | "resume", "") => Ok(Self::Play),
+            ("pause" | "stop", "") => Ok(Self::Pause),
+            ("toggle" | "playpause", "") => Ok(Self::Toggle),
+            ("next" | "skip", "") => Ok(Self::Next),
+            ("previous" | "prev" | "back", "") => Ok(Self::Previous),
+            ("volume", level) => level.trim_end_matches('%').parse::<u8>().ok()
+                .filter(|v| *v <= 100)
+                .map(Self::Volume)
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl PlaybackCommand {
+    /// Whether the track changes, so the state is read after a short delay
+    pub fn changes_track(&self) -> bool {
+        matches!(self, Self::Next | Self::Previous)
+    }
 }
 
 // Factory function to get the platform-specific bridge
@@ -49,7 +117,12 @@ pub fn get_bridge() -> Box<dyn NativeBridge + Send + Sync> {
         Box::new(linux::LinuxBridge::new())
     }
 
-    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(windows::WindowsBridge::new())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
     {
         panic!("Unsupported OS")
     }
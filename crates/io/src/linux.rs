@@ -1,9 +1,95 @@
-use super::{NativeBridge, IoError};
+use super::{IoError, NativeBridge, PlaybackCommand};
 use async_trait::async_trait;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 
+const NO_CLIPBOARD_TOOL: &str = "No clipboard tool found: install wl-clipboard (Wayland) or xclip (X11)";
+
+const NO_SCREENSHOT_TOOL: &str = "No screenshot tool found: install grim (Wayland) or scrot (X11)";
+
+const NO_PLAYERCTL: &str = "playerctl not found: install playerctl to control Spotify";
+
+/// `playerctl --player=spotify <args>`: stdout, or the error of a failed call
+async fn playerctl(args: &[&str]) -> Result<String, IoError> {
+    let output = Command::new("playerctl").arg("--player=spotify").args(args).output().await?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(IoError::CommandError(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+    }
+}
+
+/// Command line tools that read and write the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClipboardTool {
+    /// wl-copy / wl-paste
+    Wayland,
+    Xclip,
+}
+
+impl ClipboardTool {
+    /// The tool of the running session (Wayland when WAYLAND_DISPLAY is set),
+    /// else whichever is installed
+    fn detect() -> Option<Self> {
+        let wayland = installed("wl-copy") && installed("wl-paste");
+        let xclip = installed("xclip");
+        let on_wayland = session_var("WAYLAND_DISPLAY");
+        match (wayland, xclip) {
+            (true, true) => Some(if on_wayland { Self::Wayland } else { Self::Xclip }),
+            (true, false) => Some(Self::Wayland),
+            (false, true) => Some(Self::Xclip),
+            (false, false) => None,
+        }
+    }
+
+    fn paste(self) -> Command {
+        match self {
+            Self::Wayland => {
+                let mut cmd = Command::new("wl-paste");
+                cmd.arg("--no-newline");
+                cmd
+            }
+            Self::Xclip => {
+                let mut cmd = Command::new("xclip");
+                cmd.args(["-selection", "clipboard", "-o"]);
+                cmd
+            }
+        }
+    }
+
+    fn copy(self) -> Command {
+        match self {
+            Self::Wayland => Command::new("wl-copy"),
+            Self::Xclip => {
+                let mut cmd = Command::new("xclip");
+                cmd.args(["-selection", "clipboard"]);
+                cmd
+            }
+        }
+    }
+}
+
+/// Whether a display variable (WAYLAND_DISPLAY, DISPLAY) is set
+fn session_var(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|d| !d.is_empty())
+}
+
+/// Whether `program` is an executable file on PATH
+fn installed(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
 pub struct LinuxBridge;
 
+impl Default for LinuxBridge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LinuxBridge {
     pub fn new() -> Self { Self }
 }
@@ -53,4 +139,93 @@ impl NativeBridge for LinuxBridge {
     async fn get_spotify_status(&self) -> Result<String, IoError> {
         Ok("".to_string())
     }
+
+    async fn spotify_control(&self, command: &str) -> Result<String, IoError> {
+        let command = command.parse::<PlaybackCommand>()?;
+        if !installed("playerctl") {
+            return Err(IoError::CommandError(NO_PLAYERCTL.to_string()));
+        }
+        // "No players found" when Spotify is not running (its MPRIS name is spotify)
+        if playerctl(&["status"]).await.is_err() {
+            return Err(IoError::NotRunning("Spotify"));
+        }
+        let volume;
+        let args: &[&str] = match command {
+            PlaybackCommand::Play => &["play"],
+            PlaybackCommand::Pause => &["pause"],
+            PlaybackCommand::Toggle => &["play-pause"],
+            PlaybackCommand::Next => &["next"],
+            PlaybackCommand::Previous => &["previous"],
+            PlaybackCommand::Volume(level) => {
+                volume = format!("{:.2}", f64::from(level) / 100.0);
+                &["volume", &volume]
+            }
+        };
+        playerctl(args).await?;
+        if command.changes_track() {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+
+        let state = playerctl(&["status"]).await.unwrap_or_default();
+        let track = playerctl(&["metadata", "--format", "{{artist}} - {{title}}"]).await.unwrap_or_default();
+        let volume = playerctl(&["volume"]).await.ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|v| format!(" (volume {:.0}%)", v * 100.0))
+            .unwrap_or_default();
+        Ok(format!("{}: {}{}", if state.is_empty() { "Stopped" } else { &state }, track, volume))
+    }
+
+    async fn get_clipboard(&self) -> Result<String, IoError> {
+        let tool = ClipboardTool::detect().ok_or_else(|| IoError::CommandError(NO_CLIPBOARD_TOOL.to_string()))?;
+        let output = tool.paste().output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(IoError::CommandError(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+        }
+    }
+
+    async fn set_clipboard(&self, text: &str) -> Result<(), IoError> {
+        let tool = ClipboardTool::detect().ok_or_else(|| IoError::CommandError(NO_CLIPBOARD_TOOL.to_string()))?;
+        // Both tools fork a process that keeps serving the selection: leaving
+        // stdout and stderr unpiped keeps it from holding up the wait
+        let mut child = tool.copy()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        let status = child.wait().await?;
+        if status.success() {
+            Ok(())
+        } else {
+            let program = if tool == ClipboardTool::Wayland { "wl-copy" } else { "xclip" };
+            Err(IoError::CommandError(format!("{} exited with {}", program, status)))
+        }
+    }
+
+    async fn capture_screen(&self, out_path: &Path) -> Result<(), IoError> {
+        let (wayland, x11) = (session_var("WAYLAND_DISPLAY"), session_var("DISPLAY"));
+        if !wayland && !x11 {
+            return Err(IoError::NoDisplay("neither WAYLAND_DISPLAY nor DISPLAY is set (headless session)".to_string()));
+        }
+        // grim only works on wlroots compositors, scrot only on X11 (or XWayland)
+        let program = if wayland && installed("grim") {
+            "grim"
+        } else if x11 && installed("scrot") {
+            "scrot"
+        } else {
+            return Err(IoError::CommandError(NO_SCREENSHOT_TOOL.to_string()));
+        };
+        let output = Command::new(program).arg(out_path).output().await?;
+        if !output.status.success() {
+            return Err(IoError::CommandError(format!("{}: {}", program, String::from_utf8_lossy(&output.stderr).trim())));
+        }
+        if !out_path.is_file() {
+            return Err(IoError::CommandError(format!("{} wrote no image", program)));
+        }
+        Ok(())
+    }
 }
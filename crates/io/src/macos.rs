@@ -1,5 +1,8 @@
-use super::{NativeBridge, IoError};
+use super::{IoError, NativeBridge, PlaybackCommand};
 use async_trait::async_trait;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 
 pub struct MacBridge;
@@ -62,4 +65,70 @@ impl NativeBridge for MacBridge {
         "#;
         self.tell("Spotify", script).await.or(Ok("".to_string()))
     }
+
+    async fn spotify_control(&self, command: &str) -> Result<String, IoError> {
+        let action = match command.parse::<PlaybackCommand>()? {
+            PlaybackCommand::Play => "play".to_string(),
+            PlaybackCommand::Pause => "pause".to_string(),
+            PlaybackCommand::Toggle => "playpause".to_string(),
+            PlaybackCommand::Next => "next track\n                delay 0.5".to_string(),
+            PlaybackCommand::Previous => "previous track\n                delay 0.5".to_string(),
+            PlaybackCommand::Volume(level) => format!("set sound volume to {}", level),
+        };
+        // `is running` does not launch Spotify, unlike a bare tell
+        let script = format!(r#"
+            if application "Spotify" is running then
+                tell application "Spotify"
+                    {}
+                    return (player state as string) & "|" & (get artist of current track) & " - " & (get name of current track) & "|" & (sound volume as string)
+                end tell
+            else
+                return "NOT_RUNNING"
+            end if
+        "#, action);
+        let out = self.tell("System Events", &script).await?;
+        if out == "NOT_RUNNING" {
+            return Err(IoError::NotRunning("Spotify"));
+        }
+        let mut parts = out.splitn(3, '|');
+        let (state, track, volume) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+        let state = if state == "playing" { "Playing" } else { "Paused" };
+        Ok(format!("{}: {} (volume {}%)", state, track, volume))
+    }
+
+    async fn get_clipboard(&self) -> Result<String, IoError> {
+        let output = Command::new("pbpaste").output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(IoError::CommandError(String::from_utf8_lossy(&output.stderr).to_string()))
+        }
+    }
+
+    async fn set_clipboard(&self, text: &str) -> Result<(), IoError> {
+        let mut child = Command::new("pbcopy").stdin(Stdio::piped()).spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        let status = child.wait().await?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(IoError::CommandError(format!("pbcopy exited with {}", status)))
+        }
+    }
+
+    async fn capture_screen(&self, out_path: &Path) -> Result<(), IoError> {
+        // -x: no shutter sound. Without the Screen Recording permission the
+        // capture only shows the desktop background.
+        let output = Command::new("screencapture").args(["-x", "-t", "png"]).arg(out_path).output().await?;
+        if !output.status.success() {
+            return Err(IoError::CommandError(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        if !out_path.is_file() {
+            // Over SSH, without a logged-in session, screencapture exits 0 and writes nothing
+            return Err(IoError::NoDisplay("screencapture wrote no image (no logged-in session?)".to_string()));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,127 @@
+use super::{NativeBridge, IoError};
+use async_trait::async_trait;
+use std::path::Path;
+use tokio::process::Command;
+
+/// Keeps PowerShell from flashing a console window
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// AppUserModelID of Windows PowerShell, which may show toasts without
+/// registering one of our own
+const TOAST_APP_ID: &str = r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+const SAY_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Speech
+$voice = New-Object System.Speech.Synthesis.SpeechSynthesizer
+$voice.Speak($env:OPENSPORE_TEXT)
+"#;
+
+const NOTIFY_SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+$template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02)
+$texts = $template.GetElementsByTagName('text')
+$texts.Item(0).AppendChild($template.CreateTextNode($env:OPENSPORE_TITLE)) | Out-Null
+$texts.Item(1).AppendChild($template.CreateTextNode($env:OPENSPORE_TEXT)) | Out-Null
+$toast = [Windows.UI.Notifications.ToastNotification]::new($template)
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:OPENSPORE_APP_ID).Show($toast)
+"#;
+
+const ACTIVE_WINDOW_SCRIPT: &str = r#"
+Add-Type @"
+using System;
+using System.Runtime.InteropServices;
+using System.Text;
+public static class SporeWindow {
+    [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+    [DllImport("user32.dll", CharSet = CharSet.Unicode)] public static extern int GetWindowText(IntPtr hWnd, StringBuilder text, int count);
+}
+"@
+$title = New-Object System.Text.StringBuilder 512
+[void][SporeWindow]::GetWindowText([SporeWindow]::GetForegroundWindow(), $title, 512)
+$title.ToString()
+"#;
+
+/// Every monitor (the virtual screen) to a PNG at $env:OPENSPORE_PATH
+const CAPTURE_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Windows.Forms, System.Drawing
+$bounds = [System.Windows.Forms.SystemInformation]::VirtualScreen
+$bitmap = New-Object System.Drawing.Bitmap $bounds.Width, $bounds.Height
+$graphics = [System.Drawing.Graphics]::FromImage($bitmap)
+$graphics.CopyFromScreen($bounds.Location, [System.Drawing.Point]::Empty, $bounds.Size)
+$bitmap.Save($env:OPENSPORE_PATH, [System.Drawing.Imaging.ImageFormat]::Png)
+$graphics.Dispose()
+$bitmap.Dispose()
+"#;
+
+pub struct WindowsBridge;
+
+impl Default for WindowsBridge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WindowsBridge {
+    pub fn new() -> Self { Self }
+
+    /// Run a PowerShell script. Text goes in through environment variables,
+    /// never spliced into the script, so quotes in it cannot break out.
+    async fn powershell(&self, script: &str, env: &[(&str, &str)]) -> Result<String, IoError> {
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-Command", script])
+            .envs(env.iter().copied())
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .await?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(IoError::CommandError(String::from_utf8_lossy(&output.stderr).to_string()))
+        }
+    }
+}
+
+#[async_trait]
+impl NativeBridge for WindowsBridge {
+    async fn say(&self, text: &str) -> Result<(), IoError> {
+        self.powershell(SAY_SCRIPT, &[("OPENSPORE_TEXT", text)]).await.map(|_| ())
+    }
+
+    async fn notify(&self, title: &str, message: &str) -> Result<(), IoError> {
+        let env = [("OPENSPORE_TITLE", title), ("OPENSPORE_TEXT", message), ("OPENSPORE_APP_ID", TOAST_APP_ID)];
+        self.powershell(NOTIFY_SCRIPT, &env).await.map(|_| ())
+    }
+
+    async fn tell(&self, _app: &str, _command: &str) -> Result<String, IoError> {
+        // No AppleScript equivalent that works across applications
+        Err(IoError::UnsupportedPlatform)
+    }
+
+    async fn get_active_app(&self) -> Result<String, IoError> {
+        // Title of the foreground window ("README.md - Visual Studio Code")
+        self.powershell(ACTIVE_WINDOW_SCRIPT, &[]).await
+    }
+
+    async fn get_spotify_status(&self) -> Result<String, IoError> {
+        Err(IoError::UnsupportedPlatform)
+    }
+
+    async fn spotify_control(&self, _command: &str) -> Result<String, IoError> {
+        // Spotify for Windows has no scripting interface
+        Err(IoError::UnsupportedPlatform)
+    }
+
+    async fn get_clipboard(&self) -> Result<String, IoError> {
+        self.powershell("Get-Clipboard -Raw", &[]).await
+    }
+
+    async fn set_clipboard(&self, text: &str) -> Result<(), IoError> {
+        self.powershell("Set-Clipboard -Value $env:OPENSPORE_TEXT", &[("OPENSPORE_TEXT", text)]).await.map(|_| ())
+    }
+
+    async fn capture_screen(&self, out_path: &Path) -> Result<(), IoError> {
+        let path = out_path.to_string_lossy();
+        self.powershell(CAPTURE_SCRIPT, &[("OPENSPORE_PATH", path.as_ref())]).await.map(|_| ())
+    }
+}
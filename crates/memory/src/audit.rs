@@ -0,0 +1,358 @@
+//! Audit trail of privileged tool calls
+//!
+//! Every call of a skill that acts on the machine or the outside world (exec,
+//! file writes, purge, cron, browser actions, telegram) appends one JSON line
+//! to workspace/audit/audit.jsonl. Each line carries `prev`, the SHA-256 of the
+//! line before it, and audit.head records the number of lines and the hash of
+//! the last one, so edited, removed or truncated lines show in verify_audit().
+//! A missing head is never rebuilt quietly: the next append first writes a
+//! HEAD_MISSING_SKILL entry, which verify_audit() always reports, since the
+//! lines before it could have been rewritten along with the head's removal.
+//! Arguments are stored as a digest plus a short preview with secrets masked.
+//! The directory is device-local (not synced) and never searched, watched or
+//! put in the LLM's context.
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Directory under the workspace
+pub const AUDIT_DIR: &str = "audit";
+const AUDIT_FILE: &str = "audit.jsonl";
+const HEAD_FILE: &str = "audit.head";
+/// `prev` of the first line
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// `skill` of the entry written when audit.head was gone but the log was not
+pub const HEAD_MISSING_SKILL: &str = "audit:head-missing";
+/// Characters of the masked argument preview
+const PREVIEW_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Ok,
+    Error,
+    /// Refused before running (approval, policy)
+    Denied,
+}
+
+/// What the caller knows about one tool call
+#[derive(Debug, Clone)]
+pub struct AuditEvent<'a> {
+    pub cycle: &'a str,
+    /// Front-end or job the cycle runs for (tui, telegram, cron, spore...)
+    pub channel: &'a str,
+    pub skill: &'a str,
+    pub args: &'a str,
+    pub outcome: AuditOutcome,
+    /// Leave the preview empty (private turns)
+    pub private: bool,
+}
+
+/// One line of audit.jsonl
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// 0-based position in the log
+    pub seq: u64,
+    pub timestamp: DateTime<Local>,
+    pub cycle: String,
+    pub channel: String,
+    pub skill: String,
+    /// SHA-256 of the full arguments
+    pub args_digest: String,
+    /// Start of the arguments with secrets masked
+    pub args_preview: String,
+    pub outcome: AuditOutcome,
+    /// SHA-256 of the previous line
+    pub prev: String,
+}
+
+/// Lines in the log and hash of the last one, written with every append
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AuditHead {
+    entries: u64,
+    last: String,
+}
+
+/// Result of verify_audit()
+#[derive(Debug, Clone, Default)]
+pub struct AuditVerification {
+    pub entries: usize,
+    /// Human-readable breaks of the chain, in file order
+    pub problems: Vec<String>,
+}
+
+impl AuditVerification {
+    pub fn is_intact(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, data)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Arguments with credentials masked (KEY=..., bearer tokens, long opaque strings)
+pub fn redact(text: &str) -> String {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        [
+            (r#"(?i)((?:api[_-]?key|token|secret|password|passwd|authorization)["']?\s*[=:]\s*["']?(?:bearer\s+)?)[^\s"',;&]+"#, "${1}***"),
+            (r"(?i)(bearer\s+)\S+", "${1}***"),
+            (r"[A-Za-z0-9_\-]{32,}", "***"),
+        ]
+        .into_iter()
+        .filter_map(|(p, r)| Regex::new(p).ok().map(|re| (re, r)))
+        .collect()
+    });
+    patterns.iter().fold(text.to_string(), |acc, (re, replacement)| re.replace_all(&acc, *replacement).to_string())
+}
+
+impl MemorySystem {
+    pub fn audit_dir(&self) -> PathBuf {
+        self.memory_root.join(AUDIT_DIR)
+    }
+
+    /// Append an entry. Processes share the log; the append and the head update
+    /// happen under an exclusive lock on audit.jsonl.
+    pub fn audit(&self, event: AuditEvent) -> Result<()> {
+        let dir = self.audit_dir();
+        std::fs::create_dir_all(&dir)?;
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            // Each device keeps its own chain; merging two would break both
+            std::fs::write(&gitignore, "*\n")?;
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(AUDIT_FILE))
+            .context("Cannot open the audit log")?;
+        file.lock().context("Cannot lock the audit log")?;
+
+        let mut head = match self.audit_head() {
+            Some(head) => head,
+            None => {
+                let found = self.scan_audit_log();
+                if found.entries > 0 {
+                    // Re-anchoring on what is there now would vouch for a rewritten log
+                    tracing::warn!("⚠️ {} is missing with {} entries in the audit log; recording the break", HEAD_FILE, found.entries);
+                    let note = format!("{} was missing; {} earlier entries could not be checked", HEAD_FILE, found.entries);
+                    Self::append_entry(&mut file, &found, "audit", "system", HEAD_MISSING_SKILL, &note, &note, AuditOutcome::Error)?
+                } else {
+                    found
+                }
+            }
+        };
+        let preview = if event.private { String::new() } else { openspore_core::fmt_utils::preview(&redact(event.args), PREVIEW_CHARS) };
+        head = Self::append_entry(&mut file, &head, event.cycle, event.channel, &event.skill.to_lowercase(), event.args, &preview, event.outcome)?;
+
+        let tmp = dir.join(format!("{}.tmp", HEAD_FILE));
+        std::fs::write(&tmp, serde_json::to_string(&head)?)?;
+        std::fs::rename(&tmp, dir.join(HEAD_FILE))?;
+        Ok(())
+    }
+
+    /// Write one line chained to `head`; returns the head after it
+    #[allow(clippy::too_many_arguments)]
+    fn append_entry(file: &mut std::fs::File, head: &AuditHead, cycle: &str, channel: &str, skill: &str, args: &str, preview: &str, outcome: AuditOutcome) -> Result<AuditHead> {
+        let entry = AuditEntry {
+            seq: head.entries,
+            timestamp: Local::now(),
+            cycle: cycle.to_string(),
+            channel: channel.to_string(),
+            skill: skill.to_string(),
+            args_digest: sha256_hex(args.as_bytes()),
+            args_preview: preview.to_string(),
+            outcome,
+            prev: if head.entries == 0 { GENESIS.to_string() } else { head.last.clone() },
+        };
+        let line = serde_json::to_string(&entry)?;
+        writeln!(file, "{}", line)?;
+        file.flush()?;
+        Ok(AuditHead { entries: entry.seq + 1, last: sha256_hex(line.as_bytes()) })
+    }
+
+    /// Every entry, oldest first (lines that do not parse are skipped)
+    pub fn audit_entries(&self) -> Vec<AuditEntry> {
+        std::fs::read_to_string(self.audit_dir().join(AUDIT_FILE))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect()
+    }
+
+    /// Walk the chain and compare its end with audit.head
+    pub fn verify_audit(&self) -> Result<AuditVerification> {
+        let path = self.audit_dir().join(AUDIT_FILE);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(anyhow!("Cannot read {}: {}", path.display(), e)),
+        };
+
+        let mut report = AuditVerification::default();
+        let mut expected_prev = GENESIS.to_string();
+        for (index, line) in content.lines().enumerate() {
+            let number = index + 1;
+            match serde_json::from_str::<AuditEntry>(line) {
+                Ok(entry) => {
+                    if entry.prev != expected_prev {
+                        report.problems.push(format!("line {}: chain broken after line {} (that line was edited, or lines were inserted or removed)", number, index));
+                    }
+                    if entry.seq != index as u64 {
+                        report.problems.push(format!("line {}: sequence number {} instead of {}", number, entry.seq, index));
+                    }
+                    if entry.skill == HEAD_MISSING_SKILL {
+                        report.problems.push(format!("line {}: {} was missing when this entry was written; lines 1-{} may have been rewritten", number, HEAD_FILE, index));
+                    }
+                }
+                Err(e) => report.problems.push(format!("line {}: not an audit entry ({})", number, e)),
+            }
+            expected_prev = sha256_hex(line.as_bytes());
+            report.entries = number;
+        }
+
+        match self.audit_head() {
+            Some(head) if head.entries != report.entries as u64 => report.problems.push(format!(
+                "the log has {} entries but {} were written (truncated or appended to by hand)", report.entries, head.entries
+            )),
+            Some(head) if report.entries > 0 && head.last != expected_prev => {
+                report.problems.push(format!("line {}: the last entry was changed after it was written", report.entries))
+            }
+            None if report.entries > 0 => report.problems.push(format!("{} is missing", HEAD_FILE)),
+            _ => {}
+        }
+        Ok(report)
+    }
+
+    fn audit_head(&self) -> Option<AuditHead> {
+        let content = std::fs::read_to_string(self.audit_dir().join(HEAD_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Length and last hash of the log as it is now (only trusted when it is empty)
+    fn scan_audit_log(&self) -> AuditHead {
+        let content = std::fs::read_to_string(self.audit_dir().join(AUDIT_FILE)).unwrap_or_default();
+        let lines: Vec<&str> = content.lines().collect();
+        AuditHead {
+            entries: lines.len() as u64,
+            last: lines.last().map(|l| sha256_hex(l.as_bytes())).unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    fn memory_in(root: &std::path::Path) -> MemorySystem {
+        let config = AppConfig { project_root: root.to_path_buf(), ..Default::default() };
+        MemorySystem::new(&AppState::new(config))
+    }
+
+    fn record(memory: &MemorySystem, args: &str) {
+        memory.audit(AuditEvent { cycle: "c1", channel: "tui", skill: "exec", args, outcome: AuditOutcome::Ok, private: false }).unwrap();
+    }
+
+    fn log_lines(memory: &MemorySystem) -> Vec<String> {
+        std::fs::read_to_string(memory.audit_dir().join(AUDIT_FILE)).unwrap().lines().map(str::to_string).collect()
+    }
+
+    fn write_log(memory: &MemorySystem, lines: &[String]) {
+        std::fs::write(memory.audit_dir().join(AUDIT_FILE), lines.iter().map(|l| format!("{}\n", l)).collect::<String>()).unwrap();
+    }
+
+    #[test]
+    fn an_untouched_chain_verifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory_in(dir.path());
+        for args in ["ls", "git status", "cargo build"] {
+            record(&memory, args);
+        }
+        let report = memory.verify_audit().unwrap();
+        assert_eq!(report.entries, 3);
+        assert!(report.is_intact(), "{:?}", report.problems);
+    }
+
+    #[test]
+    fn a_modified_middle_line_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory_in(dir.path());
+        for args in ["ls", "rm -rf build", "cargo build"] {
+            record(&memory, args);
+        }
+        let mut lines = log_lines(&memory);
+        lines[1] = lines[1].replace("rm -rf build", "ls -la build");
+        write_log(&memory, &lines);
+
+        let report = memory.verify_audit().unwrap();
+        assert!(!report.is_intact());
+        assert!(report.problems.iter().any(|p| p.starts_with("line 3: chain broken")), "{:?}", report.problems);
+    }
+
+    #[test]
+    fn a_truncated_log_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory_in(dir.path());
+        for args in ["ls", "git status", "rm -rf build"] {
+            record(&memory, args);
+        }
+        let lines = log_lines(&memory);
+        write_log(&memory, &lines[..2]);
+
+        let report = memory.verify_audit().unwrap();
+        assert!(!report.is_intact());
+        assert!(report.problems.iter().any(|p| p.contains("has 2 entries but 3 were written")), "{:?}", report.problems);
+    }
+
+    #[test]
+    fn a_deleted_head_is_not_reanchored() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory_in(dir.path());
+        for args in ["ls", "git status", "rm -rf build"] {
+            record(&memory, args);
+        }
+        // Truncate the log, delete the head, and let the agent append again
+        let lines = log_lines(&memory);
+        write_log(&memory, &lines[..2]);
+        std::fs::remove_file(memory.audit_dir().join(HEAD_FILE)).unwrap();
+        record(&memory, "cargo test");
+
+        let entries = memory.audit_entries();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[2].skill, HEAD_MISSING_SKILL);
+        let report = memory.verify_audit().unwrap();
+        assert!(!report.is_intact());
+        assert!(report.problems.iter().any(|p| p.contains("audit.head was missing")), "{:?}", report.problems);
+    }
+
+    #[test]
+    fn a_first_entry_needs_no_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory_in(dir.path());
+        record(&memory, "ls");
+        assert_eq!(memory.audit_entries()[0].prev, GENESIS);
+        assert!(memory.verify_audit().unwrap().is_intact());
+    }
+
+    #[test]
+    fn secrets_are_masked_in_previews() {
+        let header = redact("curl -H 'Authorization: Bearer abc123'");
+        assert!(!header.contains("abc123"), "{header}");
+        assert!(header.starts_with("curl -H 'Authorization: Bearer ***"), "{header}");
+        assert_eq!(redact("API_KEY=sk-live-1 make deploy"), "API_KEY=*** make deploy");
+    }
+}
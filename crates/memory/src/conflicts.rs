@@ -0,0 +1,263 @@
+//! Sync conflicts of markdown memories
+//!
+//! The workspace syncs between devices through git. Markdown files go through
+//! the `openspore-memory` merge driver (see git.rs): edits to different parts
+//! of a file merge as usual, but when both sides changed the same lines the
+//! file keeps both complete versions under labeled sections, with
+//! `conflict: true` in its frontmatter, until `openspore memory resolve` picks
+//! one (or a merged text). Readers can tell with is_conflicted().
+
+use crate::crypto::is_sealed;
+use crate::{parse_frontmatter, MemorySystem};
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// Name of the git merge driver registered for `*.md`
+pub const MERGE_DRIVER: &str = "openspore-memory";
+
+const OURS_MARKER: &str = "<!-- openspore-conflict: ours -->";
+const THEIRS_MARKER: &str = "<!-- openspore-conflict: theirs -->";
+const END_MARKER: &str = "<!-- openspore-conflict: end -->";
+const OURS_HEADING: &str = "## Version on this device";
+const THEIRS_HEADING: &str = "## Incoming version";
+
+/// The two sides of a conflicted file, as they were before the merge
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictVersions {
+    pub ours: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// A merged text (e.g. proposed by the LLM and approved)
+    Merged(String),
+}
+
+/// True when `content` is a conflict document written by the merge driver
+pub fn is_conflicted(content: &str) -> bool {
+    parse_frontmatter(content).get("conflict").is_some_and(|v| v == "true")
+}
+
+/// Three-way merge of plain texts with `git merge-file`.
+/// Some(merged) when the edits do not overlap, None on conflicting hunks.
+pub fn merge_texts(base: &str, ours: &str, theirs: &str) -> Result<Option<String>> {
+    if !openspore_core::capabilities::capabilities().git {
+        return Err(crate::VersioningError::NotAvailable("Merging synced memories").into());
+    }
+    let dir = tempfile::tempdir()?;
+    let write = |name: &str, text: &str| -> Result<PathBuf> {
+        let path = dir.path().join(name);
+        std::fs::write(&path, text)?;
+        Ok(path)
+    };
+    let (ours_path, base_path, theirs_path) = (write("ours", ours)?, write("base", base)?, write("theirs", theirs)?);
+
+    let output = Command::new("git")
+        .arg("merge-file")
+        .arg("-p")
+        .args([&ours_path, &base_path, &theirs_path])
+        .output()
+        .context("Cannot run git merge-file")?;
+    // Exit code: number of conflicts, negative (>127) on error
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8_lossy(&output.stdout).to_string())),
+        Some(1..=127) => Ok(None),
+        _ => Err(anyhow!("git merge-file failed: {}", String::from_utf8_lossy(&output.stderr).trim())),
+    }
+}
+
+/// A file holding both versions. Its frontmatter keeps `type` and `tags` of our
+/// side so category listings still place it.
+pub fn conflict_document(ours: &str, theirs: &str, rel_path: &str) -> String {
+    let meta = parse_frontmatter(ours);
+    let mut frontmatter = String::new();
+    for key in ["type", "tags"] {
+        if let Some(value) = meta.get(key) {
+            frontmatter.push_str(&format!("{}: {}\n", key, value));
+        }
+    }
+    format!(
+        "---\nconflict: true\nconflict_detected: {}\n{}---\n\n# Sync conflict: {}\n\n\
+         This memory was changed on two devices and the edits overlap. Both versions are kept below; \
+         resolve with `openspore memory resolve {} --ours|--theirs|--llm`.\n\n\
+         {}\n{}\n\n{}\n{}\n{}\n\n{}\n{}\n",
+        chrono::Utc::now().to_rfc3339(),
+        frontmatter,
+        rel_path,
+        rel_path,
+        OURS_MARKER, OURS_HEADING, ours,
+        THEIRS_MARKER, THEIRS_HEADING, theirs,
+        END_MARKER
+    )
+}
+
+/// Both versions of a conflict document (None for any other content)
+pub fn conflict_versions(content: &str) -> Option<ConflictVersions> {
+    if !is_conflicted(content) {
+        return None;
+    }
+    let section = |marker: &str, heading: &str, next: &str| -> Option<String> {
+        let start = content.find(&format!("{}\n{}\n\n", marker, heading))? + marker.len() + heading.len() + 3;
+        let len = content[start..].find(&format!("\n{}\n", next))?;
+        Some(content[start..start + len].to_string())
+    };
+    Some(ConflictVersions {
+        ours: section(OURS_MARKER, OURS_HEADING, THEIRS_MARKER)?,
+        theirs: section(THEIRS_MARKER, THEIRS_HEADING, END_MARKER)?,
+    })
+}
+
+impl MemorySystem {
+    /// The merge driver: merge `base`/`ours`/`theirs` (git's %O %A %B) into
+    /// `ours`. Overlapping edits produce a conflict document instead of
+    /// conflict markers, so the merge itself always completes.
+    /// Returns true when the file was left conflicted.
+    pub fn run_merge_driver(&self, base: &Path, ours: &Path, theirs: &Path, rel_path: &str) -> Result<bool> {
+        let raw_ours = std::fs::read_to_string(ours)?;
+        let raw_theirs = std::fs::read_to_string(theirs)?;
+        let sealed = is_sealed(&raw_ours) || is_sealed(&raw_theirs);
+        let ours_text = self.open_text(raw_ours)?;
+        let theirs_text = self.open_text(raw_theirs)?;
+        // Added on both sides: no common ancestor
+        let base_text = self.read_text(base).unwrap_or_default();
+
+        let (merged, conflicted) = match merge_texts(&base_text, &ours_text, &theirs_text)? {
+            Some(merged) => (merged, false),
+            None => (conflict_document(&ours_text, &theirs_text, rel_path), true),
+        };
+        let merged = if sealed { self.seal(merged.as_bytes())? + "\n" } else { merged };
+        std::fs::write(ours, merged)?;
+        Ok(conflicted)
+    }
+
+    /// Workspace files currently holding an unresolved conflict
+    pub fn conflicts(&self) -> Vec<PathBuf> {
+        WalkDir::new(&self.memory_root)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git" && e.file_name() != crate::SEARCH_INDEX_DIR)
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|x| x == "md"))
+            .filter(|e| self.read_text(e.path()).is_ok_and(|c| is_conflicted(&c)))
+            .map(|e| e.into_path())
+            .collect()
+    }
+
+    /// A workspace-relative path (or absolute path inside the workspace) as a file path
+    pub fn workspace_path(&self, path: &str) -> PathBuf {
+        let path = Path::new(path.trim());
+        if path.is_absolute() {
+            return path.to_path_buf();
+        }
+        self.memory_root.join(path.strip_prefix("workspace").unwrap_or(path))
+    }
+
+    /// Both sides of the conflicted file at `path`
+    pub fn read_conflict(&self, path: &Path) -> Result<ConflictVersions> {
+        let content = self.read_text(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        conflict_versions(&content).ok_or_else(|| anyhow!("{} has no unresolved conflict", path.display()))
+    }
+
+    /// Replace a conflict document with the chosen version and commit it
+    pub async fn resolve_conflict(&self, path: &Path, resolution: Resolution) -> Result<()> {
+        let raw = std::fs::read_to_string(path)?;
+        let sealed = is_sealed(&raw);
+        let versions = conflict_versions(&self.open_text(raw)?)
+            .ok_or_else(|| anyhow!("{} has no unresolved conflict", path.display()))?;
+        let text = match resolution {
+            Resolution::Ours => versions.ours,
+            Resolution::Theirs => versions.theirs,
+            Resolution::Merged(text) => text,
+        };
+        let text = if sealed { self.seal(text.as_bytes())? + "\n" } else { text };
+
+        self.mark_as_internal_write(path.to_path_buf()).await;
+        tokio::fs::write(path, text).await?;
+        self.index_written(path).await;
+        let rel = path.strip_prefix(&self.memory_root).unwrap_or(path);
+        self.commit(&format!("Resolve conflict: {}", rel.display()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    const BASE: &str = "---\ntype: preferences\ntags: [editor]\n---\n\n# Editor setup\n\nTheme: light\nFont: Iosevka 12\nKeymap: default\n";
+    const OURS: &str = "---\ntype: preferences\ntags: [editor]\n---\n\n# Editor setup\n\nTheme: solarized dark\nFont: Iosevka 12\nKeymap: default\n";
+    const THEIRS: &str = "---\ntype: preferences\ntags: [editor, laptop]\n---\n\n# Editor setup\n\nTheme: gruvbox\nFont: Iosevka 12\nKeymap: default\n";
+
+    async fn memory(root: &Path) -> MemorySystem {
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: root.to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        memory
+    }
+
+    /// preferences/editor_setup.md after a sync where both devices changed
+    /// the theme line: runs the merge driver the way git does
+    async fn conflicted(memory: &MemorySystem) -> PathBuf {
+        let scratch = tempfile::tempdir().unwrap();
+        let (base, theirs) = (scratch.path().join("base"), scratch.path().join("theirs"));
+        std::fs::write(&base, BASE).unwrap();
+        std::fs::write(&theirs, THEIRS).unwrap();
+        let path = memory.category_dir("preferences").join("editor_setup.md");
+        std::fs::write(&path, OURS).unwrap();
+
+        assert!(memory.run_merge_driver(&base, &path, &theirs, "preferences/editor_setup.md").unwrap());
+        path
+    }
+
+    #[test]
+    fn merge_texts_merges_separate_edits_and_refuses_overlapping_ones() {
+        let keymap = BASE.replace("Keymap: default", "Keymap: vim");
+        let merged = merge_texts(BASE, OURS, &keymap).unwrap().unwrap();
+        assert!(merged.contains("Theme: solarized dark") && merged.contains("Keymap: vim"));
+        assert_eq!(merge_texts(BASE, OURS, THEIRS).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn overlapping_edits_keep_both_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        let path = conflicted(&memory).await;
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(is_conflicted(&content));
+        assert!(!content.contains("<<<<<<<"), "no git conflict markers");
+        assert!(content.contains("type: preferences"), "still listed in its category");
+        assert_eq!(memory.read_conflict(&path).unwrap(), ConflictVersions { ours: OURS.to_string(), theirs: THEIRS.to_string() });
+        assert_eq!(memory.conflicts(), vec![path.clone()]);
+        assert_eq!(memory.workspace_path("workspace/preferences/editor_setup.md"), path);
+
+        // Search results carry the flag, so neither side is quoted as current
+        let results = memory.search("gruvbox", 5).await.unwrap();
+        assert!(results.iter().any(|r| r.path == path && r.is_conflicted()));
+    }
+
+    #[tokio::test]
+    async fn each_resolution_replaces_the_conflict_document() {
+        let merged = OURS.replace("Theme: solarized dark", "Theme: gruvbox (laptop), solarized dark (desktop)");
+        for (resolution, expected) in [
+            (Resolution::Ours, OURS.to_string()),
+            (Resolution::Theirs, THEIRS.to_string()),
+            (Resolution::Merged(merged.clone()), merged.clone()),
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let memory = memory(dir.path()).await;
+            let path = conflicted(&memory).await;
+
+            memory.resolve_conflict(&path, resolution.clone()).await.unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), expected, "{:?}", resolution);
+            assert!(memory.conflicts().is_empty());
+            assert!(memory.read_conflict(&path).is_err());
+            assert!(memory.resolve_conflict(&path, resolution).await.is_err(), "nothing left to resolve");
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Consolidation of old exchanges into daily digests
+//!
+//! Every turn leaves an Exchange file in the working context, and nothing else
+//! removes them once the journal has missed them. consolidate() takes the
+//! exchanges older than a cutoff (all sessions), oldest first and at most
+//! `max_files` per run to bound token spend, groups them by day and has a
+//! DigestWriter (the Brain) summarize each day into
+//! workspace/memory/digests/YYYY-MM-DD.md. The workspace is committed before
+//! the originals are deleted (with tombstones), so they stay in git history.
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use openspore_core::fmt_utils::truncate_chars;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Digests live here, one file per day
+pub const DIGESTS_DIR: &str = "memory/digests";
+
+/// Characters of one exchange passed to the digest writer
+const EXCHANGE_INPUT_CHARS: usize = 4000;
+
+/// Writes the digest of one day from its exchanges (implemented by the Brain)
+pub trait DigestWriter: Send + Sync {
+    fn digest<'a>(&'a self, day: &'a str, exchanges: &'a str) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+}
+
+/// Exchanges of one day and the digest they go to
+#[derive(Debug, Clone)]
+pub struct DayDigest {
+    pub day: NaiveDate,
+    pub exchanges: Vec<PathBuf>,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Default)]
+pub struct ConsolidationReport {
+    /// Days digested (or, in a dry run, that would be)
+    pub digests: Vec<DayDigest>,
+    /// Days whose digest failed; their exchanges are kept for the next run
+    pub failed: Vec<(NaiveDate, String)>,
+    /// Old exchanges left over by the per-run cap
+    pub remaining: usize,
+    pub dry_run: bool,
+}
+
+impl ConsolidationReport {
+    pub fn exchanges(&self) -> usize {
+        self.digests.iter().map(|d| d.exchanges.len()).sum()
+    }
+}
+
+/// When an exchange was saved: its name (exchangeYYYYMMDDHHMMSS.md, as
+/// save_memory sanitizes "Exchange_<ts>"), else its `created` field, else mtime
+fn exchange_time(memory: &MemorySystem, path: &Path) -> Option<NaiveDateTime> {
+    let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+    let digits: String = stem.strip_prefix("exchange")?.chars().filter(|c| c.is_ascii_digit()).collect();
+    if let Ok(time) = NaiveDateTime::parse_from_str(digits.get(..14).unwrap_or_default(), "%Y%m%d%H%M%S") {
+        return Some(time);
+    }
+    let created = memory.read_text(path).ok()
+        .and_then(|content| crate::parse_frontmatter(&content).remove("created"))
+        .and_then(|c| DateTime::parse_from_rfc3339(c.trim()).ok());
+    if let Some(created) = created {
+        return Some(created.with_timezone(&Local).naive_local());
+    }
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(DateTime::<Local>::from(modified).naive_local())
+}
+
+impl MemorySystem {
+    /// Context directories holding exchanges: the flat layout and every named session
+    fn exchange_dirs(&self) -> Vec<PathBuf> {
+        let base = self.memory_root.join("context");
+        let mut dirs = vec![base.clone()];
+        if let Ok(entries) = std::fs::read_dir(base.join("sessions")) {
+            let mut sessions: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+            sessions.sort();
+            dirs.extend(sessions);
+        }
+        dirs
+    }
+
+    /// Exchange files saved before `older_than` ago, oldest first
+    pub fn old_exchanges(&self, older_than: Duration) -> Vec<(NaiveDateTime, PathBuf)> {
+        let Some(cutoff) = chrono::Duration::from_std(older_than).ok()
+            .and_then(|age| Local::now().naive_local().checked_sub_signed(age)) else { return Vec::new() };
+        let mut exchanges: Vec<(NaiveDateTime, PathBuf)> = self.exchange_dirs().iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten().map(|e| e.path()))
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "md"))
+            .filter_map(|p| exchange_time(self, &p).map(|t| (t, p)))
+            .filter(|(time, _)| *time < cutoff)
+            .collect();
+        exchanges.sort();
+        exchanges
+    }
+
+    pub fn digest_path(&self, day: NaiveDate) -> PathBuf {
+        self.memory_root.join(DIGESTS_DIR).join(format!("{}.md", day.format("%Y-%m-%d")))
+    }
+
+    /// Fold exchanges older than `older_than` into daily digests, at most
+    /// `max_files` of them. A dry run only reports what would be digested
+    /// (no LLM call, nothing written).
+    pub async fn consolidate(&self, writer: &dyn DigestWriter, older_than: Duration, max_files: usize, dry_run: bool) -> Result<ConsolidationReport> {
+        let mut old = self.old_exchanges(older_than);
+        let mut report = ConsolidationReport { dry_run, ..Default::default() };
+        report.remaining = old.len().saturating_sub(max_files);
+        old.truncate(max_files);
+
+        let mut days: BTreeMap<NaiveDate, Vec<PathBuf>> = BTreeMap::new();
+        for (time, path) in old {
+            days.entry(time.date()).or_default().push(path);
+        }
+        let days: Vec<DayDigest> = days.into_iter()
+            .map(|(day, exchanges)| DayDigest { day, path: self.digest_path(day), exchanges })
+            .collect();
+
+        if dry_run || days.is_empty() {
+            report.digests = days;
+            return Ok(report);
+        }
+
+        let total: usize = days.iter().map(|d| d.exchanges.len()).sum();
+        self.commit(&format!("Consolidate: snapshot before digesting {} exchanges", total));
+
+        for day in days {
+            let label = day.day.format("%Y-%m-%d").to_string();
+            match self.digest_day(writer, &label, &day).await {
+                Ok(()) => report.digests.push(day),
+                Err(e) => {
+                    warn!("🗜️ Digest of {} failed, its exchanges are kept: {}", label, e);
+                    report.failed.push((day.day, e.to_string()));
+                }
+            }
+        }
+
+        if !report.digests.is_empty() {
+            info!("🗜️ Consolidated {} exchanges into {} daily digests", report.exchanges(), report.digests.len());
+            self.commit(&format!("Consolidate: {} exchanges into {} daily digests", report.exchanges(), report.digests.len()));
+        }
+        Ok(report)
+    }
+
+    /// Write (or extend) the digest of one day, then delete its exchanges
+    async fn digest_day(&self, writer: &dyn DigestWriter, label: &str, day: &DayDigest) -> Result<()> {
+        let input = day.exchanges.iter()
+            .filter_map(|path| self.read_text(path).ok())
+            .map(|content| truncate_chars(content.trim(), EXCHANGE_INPUT_CHARS))
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n");
+        let digest = writer.digest(label, &input).await?;
+        if digest.trim().is_empty() {
+            return Err(anyhow!("empty digest"));
+        }
+
+        if let Some(dir) = day.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        self.mark_as_internal_write(day.path.clone()).await;
+        if day.path.exists() {
+            // An earlier run (capped, or a day that got more exchanges) wrote part of it
+            let update = format!("\n\n---\n\n## 🔄 Update [{}] ({} exchanges)\n\n{}\n", Local::now().format("%Y-%m-%d %H:%M"), day.exchanges.len(), digest.trim());
+            let update = if self.encrypt_memory { format!("\n{}", self.seal_if_enabled(&update)?) } else { update };
+            use std::io::Write;
+            let mut file = std::fs::OpenOptions::new().append(true).open(&day.path)?;
+            write!(file, "{}", update)?;
+        } else {
+            let content = format!(
+                "---\ntype: digest\ncreated: {}\nexchanges: {}\ntags: digest, {}\n---\n\n# Digest {}\n\n{}\n",
+                chrono::Utc::now().to_rfc3339(), day.exchanges.len(), label, label, digest.trim()
+            );
+            tokio::fs::write(&day.path, self.seal_if_enabled(&content)?).await?;
+        }
+        self.index_written(&day.path).await;
+
+        for path in &day.exchanges {
+            self.forget(path, "consolidated").await?;
+        }
+        Ok(())
+    }
+}
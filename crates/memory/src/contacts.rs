@@ -0,0 +1,408 @@
+//! Contact book for outbound messages
+//!
+//! `workspace/identity/CONTACTS.md` holds one `---` block of `key: value`
+//! lines per person (name, aliases, telegram, email, channel, notes, owner),
+//! edited by hand or through the contacts skill. Skills that send messages
+//! take a contact instead of a raw address and resolve it here: names and
+//! aliases match ignoring case, then whole words of the name ("anna" for Anna
+//! Schmidt). A query matching several contacts resolves to none; the caller
+//! gets the candidates and must ask. `owner: true` marks the user, the only
+//! contact messaged without approval.
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+
+/// Location of the contact book, relative to the workspace
+pub const CONTACTS_FILE: &str = "identity/CONTACTS.md";
+
+const NEW_CONTACTS: &str = "# Contacts\n\nPeople the agent may message. One block per contact; `owner: true` marks you, everyone else needs approval before a message goes out.\n";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Contact {
+    pub name: String,
+    pub aliases: Vec<String>,
+    /// Telegram chat id
+    pub telegram: Option<String>,
+    pub email: Option<String>,
+    /// Preferred channel ("telegram", "email")
+    pub channel: Option<String>,
+    pub notes: String,
+    pub owner: bool,
+}
+
+impl Contact {
+    /// Block as written to CONTACTS.md
+    fn block(&self) -> String {
+        let mut lines = vec!["---".to_string(), format!("name: {}", one_line(&self.name))];
+        if !self.aliases.is_empty() {
+            lines.push(format!("aliases: {}", one_line(&self.aliases.join(", "))));
+        }
+        for (key, value) in [("telegram", &self.telegram), ("email", &self.email), ("channel", &self.channel)] {
+            if let Some(value) = value {
+                lines.push(format!("{}: {}", key, one_line(value)));
+            }
+        }
+        if !self.notes.is_empty() {
+            lines.push(format!("notes: {}", one_line(&self.notes)));
+        }
+        if self.owner {
+            lines.push("owner: true".to_string());
+        }
+        lines.push("---".to_string());
+        lines.join("\n")
+    }
+
+    /// The contact as JSON for skill results
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "aliases": self.aliases,
+            "telegram": self.telegram,
+            "email": self.email,
+            "channel": self.channel,
+            "notes": self.notes,
+            "owner": self.owner,
+        })
+    }
+
+    /// Whether `query` (normalized) is this contact's name, an alias or an address
+    fn is_exactly(&self, query: &str) -> bool {
+        std::iter::once(&self.name)
+            .chain(&self.aliases)
+            .chain(&self.telegram)
+            .chain(&self.email)
+            .any(|form| normalize(form) == query)
+    }
+
+    /// Whether every word of `query` (normalized) is a word of the name or of an alias
+    fn has_words(&self, query: &str) -> bool {
+        std::iter::once(&self.name).chain(&self.aliases).any(|form| {
+            let form = normalize(form);
+            let words: Vec<&str> = form.split(' ').collect();
+            query.split(' ').all(|q| words.contains(&q))
+        })
+    }
+
+    /// Fill the fields `other` leaves empty with this contact's, merging aliases
+    fn merged_into(&self, mut other: Contact) -> Contact {
+        let mut aliases = self.aliases.clone();
+        aliases.extend(other.aliases.into_iter().filter(|a| !self.aliases.iter().any(|x| x.eq_ignore_ascii_case(a))));
+        other.aliases = aliases;
+        other.telegram = other.telegram.or_else(|| self.telegram.clone());
+        other.email = other.email.or_else(|| self.email.clone());
+        other.channel = other.channel.or_else(|| self.channel.clone());
+        if other.notes.is_empty() {
+            other.notes = self.notes.clone();
+        }
+        other.owner |= self.owner;
+        other
+    }
+}
+
+/// Outcome of a lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContactMatch<'a> {
+    Found(&'a Contact),
+    /// Several contacts match; none is chosen
+    Ambiguous(Vec<&'a Contact>),
+    NotFound,
+}
+
+/// Parsed contact book
+#[derive(Debug, Clone, Default)]
+pub struct ContactBook {
+    pub contacts: Vec<Contact>,
+}
+
+fn one_line(text: &str) -> String {
+    text.replace('\n', " ").trim().to_string()
+}
+
+/// Lowercase, single spaces, no leading `@`
+fn normalize(text: &str) -> String {
+    text.trim().trim_start_matches('@').split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn split_aliases(text: &str) -> Vec<String> {
+    text.split(',').map(|a| a.trim().trim_matches('"').trim().to_string()).filter(|a| !a.is_empty()).collect()
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Contact of one block's lines (None without a name)
+fn parse_block(lines: &[&str]) -> Option<Contact> {
+    let mut contact = Contact::default();
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "name" => contact.name = value.to_string(),
+            "aliases" | "alias" => contact.aliases = split_aliases(value),
+            "telegram" | "telegram_id" => contact.telegram = non_empty(value),
+            "email" => contact.email = non_empty(value),
+            "channel" | "preferred_channel" => contact.channel = non_empty(&value.to_lowercase()),
+            "notes" => contact.notes = value.to_string(),
+            "owner" => contact.owner = matches!(value.to_lowercase().as_str(), "true" | "yes"),
+            _ => {}
+        }
+    }
+    (!contact.name.is_empty()).then_some(contact)
+}
+
+/// Line ranges of the `---` blocks of `content` (between the fences)
+fn blocks(content: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut open: Option<usize> = None;
+    for (i, line) in content.lines().enumerate() {
+        if line.trim() != "---" {
+            continue;
+        }
+        match open.take() {
+            Some(start) => ranges.push((start, i)),
+            None => open = Some(i + 1),
+        }
+    }
+    ranges
+}
+
+impl ContactBook {
+    pub fn parse(content: &str) -> Self {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut contacts: Vec<Contact> = Vec::new();
+        for (start, end) in blocks(content) {
+            if let Some(contact) = parse_block(&lines[start..end]) {
+                // A name listed twice: the later block wins
+                contacts.retain(|c| !c.name.eq_ignore_ascii_case(&contact.name));
+                contacts.push(contact);
+            }
+        }
+        Self { contacts }
+    }
+
+    /// The contact book of `workspace` (identity files are never encrypted),
+    /// empty when the file is missing
+    pub fn load(workspace: &Path) -> Self {
+        std::fs::read_to_string(workspace.join(CONTACTS_FILE)).map(|c| Self::parse(&c)).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+
+    pub fn owner(&self) -> Option<&Contact> {
+        self.contacts.iter().find(|c| c.owner)
+    }
+
+    /// The contact `query` names: an exact name, alias, telegram id or email
+    /// first, then contacts having all of the query's words in their name
+    pub fn resolve(&self, query: &str) -> ContactMatch<'_> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return ContactMatch::NotFound;
+        }
+        let exact: Vec<&Contact> = self.contacts.iter().filter(|c| c.is_exactly(&query)).collect();
+        let matches = if exact.is_empty() { self.contacts.iter().filter(|c| c.has_words(&query)).collect() } else { exact };
+        match matches.len() {
+            0 => ContactMatch::NotFound,
+            1 => ContactMatch::Found(matches[0]),
+            _ => ContactMatch::Ambiguous(matches),
+        }
+    }
+
+    /// Contacts whose name, aliases or notes contain `query` (all of them for an empty one)
+    pub fn search(&self, query: &str) -> Vec<&Contact> {
+        let query = normalize(query);
+        self.contacts.iter()
+            .filter(|c| {
+                query.is_empty()
+                    || std::iter::once(&c.name).chain(&c.aliases).chain(std::iter::once(&c.notes)).any(|f| normalize(f).contains(&query))
+            })
+            .collect()
+    }
+}
+
+/// `content` with `contact`'s block replaced (same name) or appended.
+/// Everything else in the file is kept as written.
+fn upsert_block(content: &str, contact: &Contact) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let existing = blocks(content).into_iter()
+        .find(|(start, end)| parse_block(&lines[*start..*end]).is_some_and(|c| c.name.eq_ignore_ascii_case(&contact.name)));
+    match existing {
+        Some((start, end)) => {
+            let mut text: Vec<String> = lines[..start - 1].iter().map(|l| l.to_string()).collect();
+            text.push(contact.block());
+            text.extend(lines[end + 1..].iter().map(|l| l.to_string()));
+            text.join("\n") + "\n"
+        }
+        None => {
+            let head = if content.trim().is_empty() { NEW_CONTACTS.to_string() } else { content.trim_end().to_string() + "\n" };
+            format!("{}\n{}\n", head, contact.block())
+        }
+    }
+}
+
+impl MemorySystem {
+    pub fn contacts_path(&self) -> PathBuf {
+        self.memory_root.join(CONTACTS_FILE)
+    }
+
+    /// The contact book, empty when the file is missing
+    pub fn contacts(&self) -> ContactBook {
+        self.read_text(&self.contacts_path()).map(|c| ContactBook::parse(&c)).unwrap_or_default()
+    }
+
+    /// Add a contact, or update the one of the same name: new aliases are
+    /// merged and empty fields keep their current value. Returns the stored contact.
+    pub async fn add_contact(&self, contact: Contact) -> Result<Contact> {
+        if contact.name.trim().is_empty() {
+            return Err(anyhow!("A contact needs a name"));
+        }
+        let path = self.contacts_path();
+        let content = self.read_text(&path).unwrap_or_default();
+        let book = ContactBook::parse(&content);
+
+        let name = normalize(&contact.name);
+        if let Some(other) = book.contacts.iter().find(|c| !c.name.eq_ignore_ascii_case(&contact.name) && c.is_exactly(&name)) {
+            return Err(anyhow!("\"{}\" is already an alias of {}", contact.name, other.name));
+        }
+        let stored = match book.contacts.iter().find(|c| c.name.eq_ignore_ascii_case(&contact.name)) {
+            Some(existing) => existing.merged_into(contact),
+            None => contact,
+        };
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        self.mark_as_internal_write(path.clone()).await;
+        tokio::fs::write(&path, upsert_block(&content, &stored)).await?;
+        self.index_written(&path).await;
+        self.commit(&format!("Contact: {}", stored.name));
+        Ok(stored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    const BOOK: &str = "# Contacts\n\nHand-written intro.\n\n---\nname: Anna Schmidt\naliases: Anna, \"Schmidti\"\ntelegram: 1001\nemail: anna@example.com\nchannel: Telegram\n---\n\n---\nname: Anna Berg\ntelegram: 1002\n---\n\n---\nname: Bob Stone\naliases: Bobby\nemail: bob@example.com\nnotes: plumber, weekdays only\n---\n\n---\nname: Me\ntelegram: 42\nowner: yes\n---\n\n---\nno name here\n---\n";
+
+    fn found(book: &ContactBook, query: &str) -> Option<String> {
+        match book.resolve(query) {
+            ContactMatch::Found(contact) => Some(contact.name.clone()),
+            _ => None,
+        }
+    }
+
+    fn names(contacts: &[&Contact]) -> Vec<String> {
+        contacts.iter().map(|c| c.name.clone()).collect()
+    }
+
+    #[test]
+    fn parses_blocks_and_skips_nameless_ones() {
+        let book = ContactBook::parse(BOOK);
+        assert_eq!(names(&book.contacts.iter().collect::<Vec<_>>()), ["Anna Schmidt", "Anna Berg", "Bob Stone", "Me"]);
+        let anna = &book.contacts[0];
+        assert_eq!(anna.aliases, ["Anna", "Schmidti"]);
+        assert_eq!((anna.telegram.as_deref(), anna.channel.as_deref()), (Some("1001"), Some("telegram")));
+        assert_eq!(book.owner().map(|c| c.name.as_str()), Some("Me"));
+
+        let twice = ContactBook::parse("---\nname: Bob\nemail: old@example.com\n---\n---\nname: bob\nemail: new@example.com\n---\n");
+        assert_eq!(twice.contacts.len(), 1, "the later block wins");
+        assert_eq!(twice.contacts[0].email.as_deref(), Some("new@example.com"));
+    }
+
+    #[test]
+    fn names_aliases_and_addresses_resolve_ignoring_case() {
+        let book = ContactBook::parse(BOOK);
+        for query in ["anna schmidt", "  ANNA   SCHMIDT ", "schmidti", "@1001", "Anna@Example.com"] {
+            assert_eq!(found(&book, query).as_deref(), Some("Anna Schmidt"), "{}", query);
+        }
+        assert_eq!(found(&book, "bobby").as_deref(), Some("Bob Stone"));
+        assert_eq!(found(&book, "stone").as_deref(), Some("Bob Stone"), "a whole word of the name");
+        assert_eq!(found(&book, "berg").as_deref(), Some("Anna Berg"));
+        assert_eq!(book.resolve("sto"), ContactMatch::NotFound, "no partial words");
+        assert_eq!(book.resolve("Carol"), ContactMatch::NotFound);
+        assert_eq!(book.resolve("  "), ContactMatch::NotFound);
+    }
+
+    #[test]
+    fn an_exact_alias_beats_word_matches() {
+        // "Anna" is an alias of Anna Schmidt, though it is also a word of Anna Berg's name
+        let book = ContactBook::parse(BOOK);
+        assert_eq!(found(&book, "Anna").as_deref(), Some("Anna Schmidt"));
+    }
+
+    #[test]
+    fn a_query_matching_several_contacts_resolves_to_none() {
+        let book = ContactBook::parse(&BOOK.replace("aliases: Anna, \"Schmidti\"", "aliases: Schmidti"));
+        match book.resolve("anna") {
+            ContactMatch::Ambiguous(candidates) => assert_eq!(names(&candidates), ["Anna Schmidt", "Anna Berg"]),
+            other => panic!("expected ambiguity, got {:?}", other),
+        }
+
+        let shared = ContactBook::parse("---\nname: Sam Lee\naliases: Sam\n---\n---\nname: Samantha Ruiz\naliases: sam\n---\n");
+        assert!(matches!(shared.resolve("SAM"), ContactMatch::Ambiguous(c) if c.len() == 2), "an alias used twice");
+    }
+
+    #[test]
+    fn search_covers_names_aliases_and_notes() {
+        let book = ContactBook::parse(BOOK);
+        assert_eq!(names(&book.search("plumber")), ["Bob Stone"]);
+        assert_eq!(names(&book.search("anna")), ["Anna Schmidt", "Anna Berg"]);
+        assert_eq!(book.search("").len(), 4);
+    }
+
+    #[tokio::test]
+    async fn adding_merges_into_the_contact_of_the_same_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: dir.path().to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        std::fs::create_dir_all(memory.contacts_path().parent().unwrap()).unwrap();
+        std::fs::write(memory.contacts_path(), BOOK).unwrap();
+
+        let stored = memory.add_contact(Contact {
+            name: "bob stone".to_string(),
+            aliases: vec!["BOBBY".to_string(), "Stoney".to_string()],
+            telegram: Some("1003".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(stored.aliases, ["Bobby", "Stoney"]);
+        assert_eq!(stored.email.as_deref(), Some("bob@example.com"), "empty fields keep their value");
+        assert_eq!(stored.notes, "plumber, weekdays only");
+
+        let content = std::fs::read_to_string(memory.contacts_path()).unwrap();
+        assert!(content.starts_with("# Contacts\n\nHand-written intro.\n"), "{}", content);
+        let book = memory.contacts();
+        assert_eq!(book.contacts.len(), 4);
+        assert_eq!(found(&book, "stoney").as_deref(), Some("bob stone"));
+        assert_eq!(found(&book, "@1003").as_deref(), Some("bob stone"));
+
+        memory.add_contact(Contact { name: "Carol Diaz".to_string(), ..Default::default() }).await.unwrap();
+        assert_eq!(found(&memory.contacts(), "carol").as_deref(), Some("Carol Diaz"));
+
+        let clash = memory.add_contact(Contact { name: "Schmidti".to_string(), ..Default::default() }).await.unwrap_err();
+        assert!(clash.to_string().contains("already an alias of Anna Schmidt"), "{}", clash);
+        assert!(memory.add_contact(Contact::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn the_first_contact_starts_a_new_book() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: dir.path().to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        assert!(memory.contacts().is_empty());
+
+        memory.add_contact(Contact { name: "Anna".to_string(), telegram: Some("7".to_string()), owner: true, ..Default::default() }).await.unwrap();
+        let content = std::fs::read_to_string(memory.contacts_path()).unwrap();
+        assert!(content.starts_with(NEW_CONTACTS));
+        assert!(content.ends_with("---\nname: Anna\ntelegram: 7\nowner: true\n---\n"), "{}", content);
+        assert_eq!(ContactBook::load(&memory.memory_root).owner().map(|c| c.name.as_str()), Some("Anna"));
+    }
+}
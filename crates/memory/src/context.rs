@@ -14,15 +14,25 @@ pub trait ContextCompressor: Send + Sync {
 #[derive(Clone)]
 pub struct ContextManager {
     pub memory: MemorySystem,
-    pub summary_path: PathBuf,
     pub max_raw_items: usize,
+    /// Word overlap at which a new exchange counts as a repeat (0 disables)
+    pub dedup_threshold: f32,
 }
 
+/// Exchanges a new one is compared against before saving
+const DEDUP_WINDOW: usize = 5;
+
+const DEFAULT_DEDUP_THRESHOLD: f32 = 0.9;
+
 #[derive(Debug, Clone)]
 pub struct WorkingContext {
     pub timestamp: chrono::DateTime<chrono::Local>,
     pub summary: String,
     pub recent: String,
+    /// The exchanges joined in `recent`, oldest first
+    pub recent_exchanges: Vec<String>,
+    /// Exchanges in the raw window (before redundancy filtering)
+    pub recent_count: usize,
     pub older_items: Vec<MemoryItem>,
 }
 
@@ -32,6 +42,8 @@ impl Default for WorkingContext {
             timestamp: chrono::Local::now(),
             summary: String::new(),
             recent: String::new(),
+            recent_exchanges: Vec::new(),
+            recent_count: 0,
             older_items: Vec::new(),
         }
     }
@@ -40,15 +52,19 @@ impl Default for WorkingContext {
 impl ContextManager {
     pub fn new(state: &AppState) -> Self {
         let memory = MemorySystem::new(state);
-        let summary_path = memory.memory_root.join("context").join("session_summary.md");
 
         Self {
             memory,
-            summary_path,
             max_raw_items: 12,
+            dedup_threshold: state.config.context_dedup_threshold.unwrap_or(DEFAULT_DEDUP_THRESHOLD).clamp(0.0, 1.0),
         }
     }
 
+    /// Summary of the active session (resolved on each call so session switches apply)
+    pub fn summary_path(&self) -> PathBuf {
+        self.memory.context_dir().join("session_summary.md")
+    }
+
     pub fn clone_manager(&self) -> Self {
         self.clone()
     }
@@ -61,16 +77,18 @@ impl ContextManager {
             .collect();
 
         let len = raw_items.len();
-        let split_point = if len > self.max_raw_items { len - self.max_raw_items } else { 0 };
+        let split_point = len.saturating_sub(self.max_raw_items);
 
         let older_items: Vec<MemoryItem> = raw_items[..split_point].to_vec();
         let recent_items: Vec<MemoryItem> = raw_items[split_point..].to_vec();
+        let recent_count = recent_items.len();
 
+        let summary_path = self.summary_path();
         let mut summary = "No session summary available.".to_string();
-        if self.summary_path.exists() {
-            if let Ok(content) = fs::read_to_string(&self.summary_path).await {
-                summary = content;
-            }
+        if summary_path.exists()
+            && let Ok(content) = fs::read_to_string(&summary_path).await
+        {
+            summary = content;
         }
 
         // Filter recent items: if their key content is already in the summary, omit them
@@ -95,6 +113,8 @@ impl ContextManager {
             timestamp: chrono::Local::now(),
             summary,
             recent: filtered_recent.join("\n\n"),
+            recent_exchanges: filtered_recent,
+            recent_count,
             older_items,
         })
     }
@@ -105,25 +125,27 @@ impl ContextManager {
             return Ok(());
         }
 
+        let summary_path = self.summary_path();
         let mut summary = "No session summary available.".to_string();
-        if self.summary_path.exists() {
-            if let Ok(content) = fs::read_to_string(&self.summary_path).await {
-                summary = content;
-            }
+        if summary_path.exists()
+            && let Ok(content) = fs::read_to_string(&summary_path).await
+        {
+            summary = content;
         }
 
         let items_content = items.iter().map(|m| m.content.clone()).collect::<Vec<_>>().join("\n---\n");
         match compressor.compress(&summary, &items_content).await {
             Ok(new_summary) => {
-                self.memory.mark_as_internal_write(self.summary_path.clone()).await;
-                if let Err(e) = fs::write(&self.summary_path, &new_summary).await {
+                self.memory.mark_as_internal_write(summary_path.clone()).await;
+                if let Err(e) = fs::write(&summary_path, &new_summary).await {
                     tracing::error!("Failed to write session summary: {}", e);
                 } else {
                     // Cleanup older items
                     for item in items {
-                        let path = self.memory.memory_root.join("context").join(&item.filename);
-                        self.memory.mark_as_internal_write(path.clone()).await;
-                        let _ = fs::remove_file(path).await;
+                        let path = self.memory.context_dir().join(&item.filename);
+                        if let Err(e) = self.memory.forget(&path, "compressed").await {
+                            tracing::warn!("Failed to remove compressed {}: {}", item.filename, e);
+                        }
                     }
                 }
                 Ok(())
@@ -135,8 +157,92 @@ impl ContextManager {
         }
     }
 
-    /// Save interaction helper (missing in previous port)
-    pub async fn save_interaction(&self, content: &str, tags: Vec<String>, memory_type: Option<&str>) -> Result<Option<PathBuf>> {
-        self.memory.save_memory("context", &format!("Exchange_{}", chrono::Local::now().format("%Y%m%d_%H%M%S")), content, tags, memory_type).await
+    /// Exchange of the current session whose assistant reply is `quoted` (a bot
+    /// message the user replied to). Matched by a hash of the normalized reply;
+    /// long replies reach Telegram in chunks, so containment is the fallback.
+    pub fn find_exchange(&self, quoted: &str) -> Option<MemoryItem> {
+        let wanted = normalize_for_match(quoted);
+        if wanted.is_empty() {
+            return None;
+        }
+        let wanted_hash = content_hash(&wanted);
+
+        let exchanges: Vec<(MemoryItem, String)> = self.memory.get_memories("context")
+            .into_iter()
+            // save_memory sanitizes "Exchange_<ts>" to "exchange<ts>.md"
+            .filter(|m| m.filename.to_lowercase().starts_with("exchange"))
+            .filter_map(|m| {
+                let reply = m.content.split_once("**Assistant**:")?.1;
+                let reply = normalize_for_match(reply);
+                Some((m, reply))
+            })
+            .collect();
+
+        // Newest first: the same answer may have been given more than once
+        exchanges.iter().rev()
+            .find(|(_, reply)| content_hash(reply) == wanted_hash)
+            .or_else(|| exchanges.iter().rev().find(|(_, reply)| reply.contains(&wanted)))
+            .map(|(m, _)| m.clone())
+    }
+
+    /// Save interaction helper (missing in previous port).
+    /// `metadata` (model, provider, cycle...) lands in the frontmatter.
+    /// Returns None without writing when it repeats one of the last few exchanges.
+    pub async fn save_interaction(&self, content: &str, tags: Vec<String>, memory_type: Option<&str>, metadata: &[(&str, String)]) -> Result<Option<PathBuf>> {
+        if let Some(previous) = self.repeated_exchange(content) {
+            tracing::debug!("♻️ Exchange repeats {}, not saved", previous);
+            return Ok(None);
+        }
+        self.memory.save_memory_with_metadata("context", &format!("Exchange_{}", chrono::Local::now().format("%Y%m%d_%H%M%S")), content, tags, memory_type, None, metadata).await
+    }
+
+    /// Filename of a recent exchange that `content` essentially repeats:
+    /// same normalized text, or word overlap at or above the threshold
+    fn repeated_exchange(&self, content: &str) -> Option<String> {
+        if self.dedup_threshold <= 0.0 {
+            return None;
+        }
+        let normalized = normalize_for_match(content);
+        let words = word_set(content);
+
+        self.memory.get_memories("context")
+            .into_iter()
+            .filter(|m| m.filename.to_lowercase().starts_with("exchange"))
+            .rev()
+            .take(DEDUP_WINDOW)
+            .find(|m| {
+                // Compare the exchange itself, not the frontmatter or timestamped title
+                let body = m.content.find("**User**:").map_or(m.content.as_str(), |i| &m.content[i..]);
+                normalize_for_match(body) == normalized || jaccard(&words, &word_set(body)) >= self.dedup_threshold
+            })
+            .map(|m| m.filename)
     }
 }
+
+fn word_set(text: &str) -> std::collections::HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Shared words over all words of both sets
+fn jaccard(a: &std::collections::HashSet<String>, b: &std::collections::HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// Lowercase alphanumerics only, so Telegram's stripped markdown still matches
+fn normalize_for_match(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+fn content_hash(text: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
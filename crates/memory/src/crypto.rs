@@ -0,0 +1,268 @@
+//! Encryption of memory files at rest (ENCRYPT_MEMORY)
+//!
+//! Content is sealed with XChaCha20-Poly1305 under a key derived from
+//! OPENSPORE_MEM_KEY with Argon2id. A sealed block is one text line:
+//! `openspore-enc:v1:` + base64(salt | nonce | ciphertext). A memory file is
+//! a single block; LOGS.md gets one block per appended entry. Lines without
+//! the marker are plain text, so a workspace can hold both during migration.
+//! Identity files stay plain (Doctor and the prompt templates read them).
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Context, Result};
+use argon2::Argon2;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+/// Marks a sealed line
+pub const SEALED_PREFIX: &str = "openspore-enc:v1:";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// Workspace salt shared by new blocks (each block also carries its own)
+const SALT_FILE: &str = ".encryption/salt";
+
+/// Derived keys by (passphrase, salt), shared by every MemorySystem in the
+/// process: Argon2 is deliberately slow
+static KEYS: LazyLock<Mutex<HashMap<KeyId, Key>>> = LazyLock::new(Default::default);
+
+/// (passphrase, salt)
+type KeyId = (String, [u8; SALT_LEN]);
+
+pub struct MemoryCipher {
+    passphrase: String,
+    salt: [u8; SALT_LEN],
+}
+
+impl MemoryCipher {
+    /// Cipher for the workspace at `memory_root`, creating its salt on first use
+    pub fn load(memory_root: &Path, passphrase: &str) -> Result<Self> {
+        let salt_path = memory_root.join(SALT_FILE);
+        let salt = match std::fs::read(&salt_path) {
+            Ok(bytes) => bytes.as_slice().try_into().map_err(|_| anyhow!("Corrupt salt file {}", salt_path.display()))?,
+            Err(_) => {
+                let salt: [u8; SALT_LEN] = rand_bytes();
+                if let Some(dir) = salt_path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&salt_path, salt).context("Failed to write encryption salt")?;
+                salt
+            }
+        };
+        Ok(Self { passphrase: passphrase.to_string(), salt })
+    }
+
+    fn key(&self, salt: &[u8; SALT_LEN]) -> Result<Key> {
+        let mut keys = KEYS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(key) = keys.get(&(self.passphrase.clone(), *salt)) {
+            return Ok(*key);
+        }
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+        keys.insert((self.passphrase.clone(), *salt), key);
+        Ok(key)
+    }
+
+    /// Encrypt `plain` into a single sealed line (no trailing newline)
+    pub fn seal(&self, plain: &[u8]) -> Result<String> {
+        let cipher = XChaCha20Poly1305::new(&self.key(&self.salt)?);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = cipher.encrypt(&nonce, plain).map_err(|_| anyhow!("Encryption failed"))?;
+
+        let mut blob = Vec::with_capacity(SALT_LEN + NONCE_LEN + sealed.len());
+        blob.extend_from_slice(&self.salt);
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&sealed);
+        Ok(format!("{}{}", SEALED_PREFIX, STANDARD.encode(blob)))
+    }
+
+    /// Decrypt one sealed line
+    pub fn open(&self, line: &str) -> Result<Vec<u8>> {
+        let encoded = line.trim().strip_prefix(SEALED_PREFIX).ok_or_else(|| anyhow!("Not a sealed block"))?;
+        let blob = STANDARD.decode(encoded).context("Sealed block is not valid base64")?;
+        if blob.len() < SALT_LEN + NONCE_LEN {
+            return Err(anyhow!("Sealed block is truncated"));
+        }
+        let (salt, rest) = blob.split_at(SALT_LEN);
+        let (nonce, sealed) = rest.split_at(NONCE_LEN);
+
+        let key = self.key(salt.try_into()?)?;
+        XChaCha20Poly1305::new(&key)
+            .decrypt(XNonce::from_slice(nonce), sealed)
+            .map_err(|_| anyhow!("Decryption failed (wrong OPENSPORE_MEM_KEY?)"))
+    }
+
+    /// Text with every sealed line replaced by its plaintext
+    pub fn open_text(&self, content: &str) -> Result<String> {
+        let mut out = String::with_capacity(content.len());
+        for line in content.split_inclusive('\n') {
+            if line.starts_with(SEALED_PREFIX) {
+                out.push_str(&String::from_utf8(self.open(line)?)?);
+            } else {
+                out.push_str(line);
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn rand_bytes<const N: usize>() -> [u8; N] {
+    use chacha20poly1305::aead::rand_core::RngCore;
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+pub fn is_sealed(content: &str) -> bool {
+    content.starts_with(SEALED_PREFIX) || content.contains(&format!("\n{}", SEALED_PREFIX))
+}
+
+impl MemorySystem {
+    /// Whether new memories in `category` are written encrypted
+    pub fn encrypts(&self, category: &str) -> bool {
+        self.encrypt_memory && category != "identity"
+    }
+
+    /// Seal data for writing, or fail when encryption is on without a key
+    pub(crate) fn seal(&self, plain: &[u8]) -> Result<String> {
+        match &self.cipher {
+            Some(cipher) => cipher.seal(plain),
+            None => Err(anyhow!("ENCRYPT_MEMORY is on but OPENSPORE_MEM_KEY is not set")),
+        }
+    }
+
+    /// `text` as it should be written: a sealed line when ENCRYPT_MEMORY is on.
+    /// Sealed output can be appended to a file that already holds sealed lines.
+    pub fn seal_if_enabled(&self, text: &str) -> Result<String> {
+        if !self.encrypt_memory {
+            return Ok(text.to_string());
+        }
+        Ok(self.seal(text.as_bytes())? + "\n")
+    }
+
+    /// Plaintext of a file read from `content`, decrypting any sealed lines
+    pub fn open_text(&self, content: String) -> std::io::Result<String> {
+        if !is_sealed(&content) {
+            return Ok(content);
+        }
+        let cipher = self.cipher.as_ref().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::PermissionDenied, "encrypted memory and OPENSPORE_MEM_KEY is not set")
+        })?;
+        cipher.open_text(&content).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    /// Read a workspace file as text, decrypting it if needed.
+    /// Use this instead of fs::read_to_string for memory files.
+    pub fn read_text(&self, path: &Path) -> std::io::Result<String> {
+        self.open_text(std::fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    async fn workspace(root: &Path, encrypt: bool, key: Option<&str>) -> MemorySystem {
+        let config = AppConfig {
+            project_root: root.to_path_buf(),
+            encrypt_memory: Some(encrypt),
+            mem_key: key.map(String::from),
+            ..Default::default()
+        };
+        let memory = MemorySystem::new(&AppState::new(config));
+        memory.ensure_structure().await.unwrap();
+        memory
+    }
+
+    #[test]
+    fn sealed_lines_open_to_the_plaintext() {
+        let dir = tempfile::tempdir().unwrap();
+        let cipher = MemoryCipher::load(dir.path(), "correct horse").unwrap();
+        let plain = "line one\nline two: ünïcode 🔒\n";
+
+        let sealed = cipher.seal(plain.as_bytes()).unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX) && !sealed.contains('\n') && !sealed.contains("line one"));
+        assert_ne!(cipher.seal(plain.as_bytes()).unwrap(), sealed, "every block gets its own nonce");
+        assert_eq!(cipher.open(&sealed).unwrap(), plain.as_bytes());
+
+        // The salt is kept, so the same passphrase opens it after a restart
+        let reloaded = MemoryCipher::load(dir.path(), "correct horse").unwrap();
+        assert_eq!(reloaded.open(&format!("{}\n", sealed)).unwrap(), plain.as_bytes());
+
+        let mut tampered = sealed.clone();
+        tampered.replace_range(sealed.len() - 4.., "AAAA");
+        assert!(cipher.open(&tampered).is_err());
+        assert!(cipher.open("plain text").is_err());
+        assert!(cipher.open(&format!("{}AAAA", SEALED_PREFIX)).is_err());
+    }
+
+    #[tokio::test]
+    async fn logs_mix_plain_and_sealed_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let before = workspace(dir.path(), false, None).await;
+        before.save_journal("[2026-01-01 10:00:00] plain entry\n").await.unwrap();
+
+        let after = workspace(dir.path(), true, Some("correct horse")).await;
+        after.save_journal("[2026-01-01 10:05:00] sealed entry\n").await.unwrap();
+        after.save_journal("[2026-01-01 10:10:00] another sealed entry\n").await.unwrap();
+
+        let logs = after.context_dir().join("LOGS.md");
+        let raw = std::fs::read_to_string(&logs).unwrap();
+        let lines: Vec<&str> = raw.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "[2026-01-01 10:00:00] plain entry");
+        assert!(lines[1..].iter().all(|line| line.starts_with(SEALED_PREFIX)));
+        assert!(!raw.contains("sealed entry"));
+
+        assert_eq!(
+            after.read_text(&logs).unwrap(),
+            "[2026-01-01 10:00:00] plain entry\n[2026-01-01 10:05:00] sealed entry\n[2026-01-01 10:10:00] another sealed entry\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_wrong_or_missing_key_skips_sealed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = workspace(dir.path(), false, None).await;
+        plain.save_memory("knowledge", "Plain note", "Written before encryption.", vec![], None, None).await.unwrap();
+        let sealed = workspace(dir.path(), true, Some("correct horse")).await;
+        let secret = sealed.save_memory("knowledge", "Secret note", "The safe code is 1234.", vec![], None, None).await.unwrap().unwrap();
+        assert!(std::fs::read_to_string(&secret).unwrap().starts_with(SEALED_PREFIX));
+
+        let titles = |memory: &MemorySystem| memory.get_memories("knowledge").into_iter().map(|m| m.filename).collect::<Vec<_>>();
+        assert_eq!(titles(&sealed), ["plain_note.md", "secret_note.md"]);
+        assert!(sealed.get_memories("knowledge").iter().any(|m| m.content.contains("The safe code is 1234.")));
+
+        let wrong = workspace(dir.path(), true, Some("battery staple")).await;
+        assert_eq!(titles(&wrong), ["plain_note.md"]);
+        assert_eq!(wrong.read_text(&secret).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        let missing = workspace(dir.path(), false, None).await;
+        assert_eq!(titles(&missing), ["plain_note.md"]);
+        assert_eq!(missing.read_text(&secret).unwrap_err().kind(), std::io::ErrorKind::PermissionDenied);
+
+        // Without a key nothing can be saved sealed
+        let keyless = workspace(dir.path(), true, None).await;
+        assert!(keyless.save_memory("knowledge", "Lost note", "Never written.", vec![], None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn identity_files_stay_plain() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = workspace(dir.path(), true, Some("correct horse")).await;
+        assert!(!memory.encrypts("identity") && memory.encrypts("knowledge"));
+
+        let profile = memory.save_memory("identity", "Profile", "Prefers metric units.", vec![], None, None).await.unwrap().unwrap();
+        let raw = std::fs::read_to_string(&profile).unwrap();
+        assert!(!is_sealed(&raw));
+        assert!(raw.contains("Prefers metric units."));
+    }
+}
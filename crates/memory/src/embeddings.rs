@@ -0,0 +1,331 @@
+use crate::{MemorySystem, SearchResult, SEARCH_INDEX_DIR};
+use anyhow::{anyhow, Result};
+use openspore_core::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+use tokio::sync::Mutex;
+
+const EMBEDDINGS_FILE: &str = "embeddings.bin";
+const EMBEDDINGS_VERSION: u32 = 1;
+/// Inputs per embeddings request
+const BATCH_SIZE: usize = 32;
+/// Characters of a memory sent for embedding
+const MAX_INPUT_CHARS: usize = 8000;
+/// Results below this cosine similarity are noise
+const MIN_SIMILARITY: f32 = 0.3;
+
+/// Files of the working context that are transcripts, not memories
+const SKIPPED_FILES: [&str; 2] = ["LOGS.md", "session_summary.md"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmbeddedFile {
+    /// mtime in seconds since the epoch
+    modified: u64,
+    vector: Vec<f32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EmbeddingStore {
+    version: u32,
+    /// Vectors of different models are not comparable: a model change drops the store
+    model: String,
+    files: HashMap<PathBuf, EmbeddedFile>,
+}
+
+/// Embedding-based retrieval over memory files (enabled by EMBEDDING_MODEL)
+pub struct Embeddings {
+    client: reqwest::Client,
+    url: String,
+    model: String,
+    api_key: String,
+    store: Mutex<Option<EmbeddingStore>>,
+    refreshing: AtomicBool,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl Embeddings {
+    /// None when no embedding model is configured (search stays keyword-only)
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let url = config.embeddings_url()?;
+        let api_key = config.embedding_api_key.clone()
+            .filter(|k| !k.is_empty())
+            .unwrap_or_else(|| config.openrouter_api_key.clone());
+
+        Some(Self {
+            client: reqwest::Client::builder()
+                .timeout(config.llm_timeout())
+                .build()
+                .unwrap_or_default(),
+            url,
+            model: config.embedding_model.clone().unwrap_or_default(),
+            api_key,
+            store: Mutex::new(None),
+            refreshing: AtomicBool::new(false),
+        })
+    }
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut req = self.client.post(&self.url).json(&serde_json::json!({
+            "model": self.model,
+            "input": inputs,
+        }));
+        if !self.api_key.is_empty() {
+            req = req.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+
+        let res = req.send().await?;
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            return Err(anyhow!("Embeddings API error {}: {}", status, openspore_core::fmt_utils::truncate_chars(&body, 200)));
+        }
+
+        let mut data = res.json::<EmbeddingResponse>().await?.data;
+        if data.len() != inputs.len() {
+            return Err(anyhow!("Embeddings API returned {} vectors for {} inputs", data.len(), inputs.len()));
+        }
+        data.sort_by_key(|d| d.index);
+        Ok(data.into_iter().map(|d| normalize(d.embedding)).collect())
+    }
+}
+
+/// Scale to unit length so cosine similarity is a dot product
+fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    v
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok()?
+        .modified().ok()?
+        .duration_since(UNIX_EPOCH).ok()
+        .map(|d| d.as_secs())
+}
+
+fn title_of(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+impl MemorySystem {
+    fn embeddings_path(&self) -> PathBuf {
+        self.memory_root.join(SEARCH_INDEX_DIR).join(EMBEDDINGS_FILE)
+    }
+
+    pub fn embeddings_enabled(&self) -> bool {
+        self.embeddings.is_some()
+    }
+
+    /// Memory files are the markdown notes of each category (transcripts excluded)
+    fn is_embeddable(&self, path: &Path) -> bool {
+        let name = title_of(path);
+        path.extension().is_some_and(|e| e == "md")
+            && !SKIPPED_FILES.contains(&name.as_str())
+            && !name.to_lowercase().starts_with("exchange_")
+            && self.categories.iter().any(|c| path.parent() == Some(self.category_dir(c).as_path()))
+    }
+
+    fn memory_files(&self) -> Vec<PathBuf> {
+        self.categories.iter()
+            .flat_map(|c| std::fs::read_dir(self.category_dir(c)).into_iter().flatten().flatten())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && self.is_embeddable(p))
+            .collect()
+    }
+
+    fn load_embeddings(&self, model: &str) -> EmbeddingStore {
+        std::fs::read(self.embeddings_path())
+            .ok()
+            .and_then(|bytes| bincode::deserialize::<EmbeddingStore>(&bytes).ok())
+            .filter(|s| s.version == EMBEDDINGS_VERSION && s.model == model)
+            .unwrap_or_else(|| EmbeddingStore { version: EMBEDDINGS_VERSION, model: model.to_string(), ..Default::default() })
+    }
+
+    fn save_embeddings(&self, store: &EmbeddingStore) -> Result<()> {
+        let path = self.ensure_index_dir()?.join(EMBEDDINGS_FILE);
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bincode::serialize(store)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Forget the vectors of `path` and everything below it
+    pub(crate) async fn unembed(&self, path: &Path) -> usize {
+        let Some(emb) = self.embeddings.as_ref() else { return 0 };
+        let mut guard = emb.store.lock().await;
+        let store = guard.get_or_insert_with(|| self.load_embeddings(&emb.model));
+        let before = store.files.len();
+        store.files.retain(|p, _| !p.starts_with(path));
+        let removed = before - store.files.len();
+        if removed > 0
+            && let Err(e) = self.save_embeddings(store)
+        {
+            tracing::warn!("🧭 Could not save embeddings: {}", e);
+        }
+        removed
+    }
+
+    /// Embed new or changed files among `paths` and forget deleted ones.
+    /// The store is not locked while the embeddings API is called.
+    pub async fn embed_paths(&self, paths: &[PathBuf]) -> Result<usize> {
+        let Some(emb) = self.embeddings.as_ref() else { return Ok(0) };
+        if paths.is_empty() {
+            return Ok(0);
+        }
+
+        let mut pending = Vec::new();
+        {
+            let mut guard = emb.store.lock().await;
+            let store = guard.get_or_insert_with(|| self.load_embeddings(&emb.model));
+            let mut removed = false;
+            for path in paths {
+                if !self.is_embeddable(path) && !store.files.contains_key(path) {
+                    continue;
+                }
+                match modified_secs(path).filter(|_| self.is_embeddable(path)) {
+                    Some(modified) => {
+                        if store.files.get(path).is_none_or(|f| f.modified != modified) {
+                            pending.push((path.clone(), modified));
+                        }
+                    }
+                    None => removed |= store.files.remove(path).is_some(),
+                }
+            }
+            if removed && pending.is_empty() {
+                self.save_embeddings(store)?;
+            }
+        }
+
+        let mut embedded = 0;
+        for batch in pending.chunks(BATCH_SIZE) {
+            let inputs: Vec<String> = batch.iter()
+                .map(|(p, _)| {
+                    let content = self.read_text(p).unwrap_or_default();
+                    openspore_core::fmt_utils::truncate_chars(&format!("{}\n{}", title_of(p), content), MAX_INPUT_CHARS)
+                })
+                .collect();
+            let vectors = emb.embed(&inputs).await?;
+
+            let mut guard = emb.store.lock().await;
+            let store = guard.get_or_insert_with(|| self.load_embeddings(&emb.model));
+            for ((path, modified), vector) in batch.iter().zip(vectors) {
+                store.files.insert(path.clone(), EmbeddedFile { modified: *modified, vector });
+            }
+            self.save_embeddings(store)?;
+            embedded += batch.len();
+        }
+        Ok(embedded)
+    }
+
+    /// Bring the store in line with every memory file (incremental)
+    pub async fn refresh_embeddings(&self) -> Result<usize> {
+        let Some(emb) = self.embeddings.as_ref() else { return Ok(0) };
+
+        let mut paths = self.memory_files();
+        {
+            let mut guard = emb.store.lock().await;
+            let store = guard.get_or_insert_with(|| self.load_embeddings(&emb.model));
+            // Include stored files so deleted ones are pruned
+            paths.extend(store.files.keys().filter(|p| !p.exists()).cloned());
+        }
+        self.embed_paths(&paths).await
+    }
+
+    /// Refresh in the background unless a refresh is already running
+    fn spawn_embedding_refresh(&self) {
+        let Some(emb) = self.embeddings.as_ref() else { return };
+        if emb.refreshing.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let memory = self.clone();
+        tokio::spawn(async move {
+            match memory.refresh_embeddings().await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("🧭 Embedded {} memory files", n),
+                Err(e) => tracing::warn!("🧭 Embedding refresh failed: {}", e),
+            }
+            if let Some(emb) = memory.embeddings.as_ref() {
+                emb.refreshing.store(false, Ordering::SeqCst);
+            }
+        });
+    }
+
+    /// Memories ranked by cosine similarity to the query (score = similarity x 100).
+    /// Empty when embeddings are not configured; the store is refreshed in the
+    /// background, so files saved moments ago may only match on the next query.
+    pub async fn search_semantic(&self, query: &str, k: usize) -> Result<Vec<SearchResult>> {
+        let Some(emb) = self.embeddings.as_ref() else { return Ok(vec![]) };
+        if query.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        self.spawn_embedding_refresh();
+
+        // No index yet (first run): don't pay for a query embedding
+        {
+            let mut guard = emb.store.lock().await;
+            if guard.get_or_insert_with(|| self.load_embeddings(&emb.model)).files.is_empty() {
+                return Ok(vec![]);
+            }
+        }
+
+        let query_vector = emb.embed(&[query.to_string()]).await?.pop().unwrap_or_default();
+
+        let mut ranked: Vec<(PathBuf, f32)> = {
+            let mut guard = emb.store.lock().await;
+            let store = guard.get_or_insert_with(|| self.load_embeddings(&emb.model));
+            store.files.iter()
+                .map(|(path, f)| (path.clone(), dot(&query_vector, &f.vector)))
+                .filter(|(_, sim)| *sim >= MIN_SIMILARITY)
+                .collect()
+        };
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let tombstones = self.tombstone_set();
+        Ok(ranked.into_iter()
+            .filter_map(|(path, sim)| {
+                let content = self.read_text(&path).ok().filter(|c| !tombstones.buries(&path, c))?;
+                Some(SearchResult { title: title_of(&path), content, score: (sim * 100.0) as usize, path })
+            })
+            .take(k)
+            .collect())
+    }
+}
+
+/// Interleave semantic and keyword hits (semantic first), dropping duplicate paths
+pub(crate) fn merge_results(semantic: Vec<SearchResult>, keyword: Vec<SearchResult>, limit: usize) -> Vec<SearchResult> {
+    let mut merged: Vec<SearchResult> = Vec::new();
+    let mut semantic = semantic.into_iter();
+    let mut keyword = keyword.into_iter();
+
+    while merged.len() < limit {
+        let next = [semantic.next(), keyword.next()];
+        if next.iter().all(Option::is_none) {
+            break;
+        }
+        for result in next.into_iter().flatten() {
+            if merged.len() < limit && !merged.iter().any(|m| m.path == result.path) {
+                merged.push(result);
+            }
+        }
+    }
+    merged
+}
@@ -0,0 +1,95 @@
+use crate::MemorySystem;
+use crate::storage::CORE_FILES;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::path::{Path, PathBuf};
+
+/// Lifetime of learned memories unless LEARNED_TTL_DAYS says otherwise
+pub(crate) const DEFAULT_LEARNED_TTL_DAYS: u32 = 90;
+
+/// Expired files are moved here (out of search) rather than deleted
+const EXPIRED_ARCHIVE_DIR: &str = ".archive/expired";
+
+/// Categories whose files never expire
+const PERMANENT_CATEGORIES: [&str; 2] = ["identity", "preferences"];
+
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    /// (original path, archived path)
+    pub archived: Vec<(PathBuf, PathBuf)>,
+    /// Files that carry an `expires` field but are protected
+    pub protected: Vec<PathBuf>,
+}
+
+/// `expires:` from the leading frontmatter block (RFC 3339 or YYYY-MM-DD)
+fn expiry_of(content: &str) -> Option<DateTime<Utc>> {
+    let rest = content.strip_prefix("---\n")?;
+    let end = rest.find("\n---")?;
+    let value = rest[..end].lines().find_map(|l| l.strip_prefix("expires:"))?.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.with_timezone(&Utc))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0).map(|d| d.and_utc()))
+}
+
+fn is_core_file(path: &Path) -> bool {
+    path.file_stem().is_some_and(|s| CORE_FILES.contains(&s.to_string_lossy().to_uppercase().as_str()))
+}
+
+impl MemorySystem {
+    /// TTL for a memory learned into `category` (identity and preferences are kept)
+    pub fn learned_ttl(&self, category: &str) -> Option<u32> {
+        if PERMANENT_CATEGORIES.contains(&category.trim().to_lowercase().as_str()) {
+            return None;
+        }
+        self.learned_ttl_days
+    }
+
+    /// Archive memory files whose `expires` date has passed into
+    /// workspace/.archive/expired/<category>/. Core identity files (SOUL, USER...)
+    /// and the identity category are never touched, whatever their frontmatter says.
+    pub async fn prune_expired(&self) -> Result<PruneReport> {
+        let now = Utc::now();
+        let mut report = PruneReport::default();
+
+        for category in &self.categories {
+            let dir = self.category_dir(category);
+            let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+
+            for path in entries.flatten().map(|e| e.path()) {
+                if !path.is_file() || path.extension().is_none_or(|e| e != "md") {
+                    continue;
+                }
+                let Ok(content) = self.read_text(&path) else { continue };
+                let Some(expires) = expiry_of(&content) else { continue };
+                if expires > now {
+                    continue;
+                }
+                if *category == "identity" || is_core_file(&path) {
+                    tracing::warn!("🛡️ Ignoring expiry on protected file {}", path.display());
+                    report.protected.push(path);
+                    continue;
+                }
+
+                let archive_dir = self.memory_root.join(EXPIRED_ARCHIVE_DIR).join(category);
+                tokio::fs::create_dir_all(&archive_dir).await?;
+                let mut dest = archive_dir.join(path.file_name().unwrap_or_default());
+                if dest.exists() {
+                    dest = archive_dir.join(format!("{}_{}", now.format("%Y%m%d%H%M%S"), path.file_name().unwrap_or_default().to_string_lossy()));
+                }
+
+                self.record_tombstone(&path, &content, "expired", Some(&dest))?;
+                self.mark_as_internal_write(path.clone()).await;
+                tokio::fs::rename(&path, &dest).await?;
+                self.index_written(&path).await;
+                report.archived.push((path, dest));
+            }
+        }
+
+        if !report.archived.is_empty() {
+            tracing::info!("🗑️ Archived {} expired memories", report.archived.len());
+            self.commit(&format!("Prune: {} expired memories", report.archived.len()));
+        }
+        Ok(report)
+    }
+}
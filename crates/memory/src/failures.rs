@@ -0,0 +1,234 @@
+//! Post-mortems of failed cycles
+//!
+//! A think() cycle that ends in error leaves a bundle in
+//! workspace/debug/failures/<cycle_id>/:
+//! - failure.json: kind, error and environment (models, provider, budgets)
+//! - context.md: the system prompt of the cycle
+//! - messages.json: the message history
+//! - tools.json: tool invocations with their outputs
+//!
+//! Secrets are masked with redact() before anything is written. Only the
+//! newest bundles are kept. The directory is device-local (not synced) and
+//! never searched or watched; the autonomy engine reads the summaries so that
+//! recurring failures become evidence for its proposals.
+
+use crate::{redact, MemorySystem};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use openspore_core::fmt_utils::truncate_chars;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Directory under the workspace
+pub const FAILURES_DIR: &str = "debug/failures";
+const RECORD_FILE: &str = "failure.json";
+const CONTEXT_FILE: &str = "context.md";
+const MESSAGES_FILE: &str = "messages.json";
+const TOOLS_FILE: &str = "tools.json";
+
+/// Characters of one tool output kept in tools.json
+const TOOL_OUTPUT_CHARS: usize = 4000;
+
+/// Characters of the error that make up a failure's signature
+const SIGNATURE_CHARS: usize = 100;
+
+/// How the cycle ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// No model of the chain answered
+    Provider,
+    /// The tool loop hit its depth limit without a final answer
+    DepthExhausted,
+    /// Most tool calls of the cycle failed
+    ToolFailures,
+}
+
+impl FailureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureKind::Provider => "provider",
+            FailureKind::DepthExhausted => "depth_exhausted",
+            FailureKind::ToolFailures => "tool_failures",
+        }
+    }
+}
+
+/// failure.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureRecord {
+    pub cycle: String,
+    pub time: DateTime<Local>,
+    pub kind: FailureKind,
+    pub error: String,
+    pub channel: String,
+    pub prompt: String,
+    pub tool_calls: usize,
+    pub tool_errors: usize,
+    /// Model chain, provider, context budget and other facts of the run
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
+}
+
+impl FailureRecord {
+    /// One line for listings and the autonomy engine
+    pub fn summary(&self) -> String {
+        format!(
+            "[{}] {} via {}: {} ({} tool calls, {} failed)",
+            self.time.format("%Y-%m-%d %H:%M"), self.kind.as_str(), self.channel,
+            truncate_chars(&self.error.replace('\n', " "), 200), self.tool_calls, self.tool_errors
+        )
+    }
+
+    /// Kind and error with digits blanked, so repeats of one problem group together
+    pub fn signature(&self) -> String {
+        let error: String = self.error.chars()
+            .map(|c| if c.is_ascii_digit() { '#' } else { c })
+            .take(SIGNATURE_CHARS)
+            .collect();
+        format!("{}: {}", self.kind.as_str(), error.replace('\n', " ").trim())
+    }
+}
+
+/// One entry of tools.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInvocation {
+    /// Tool loop round (0 for the calls of the first reply)
+    pub round: usize,
+    pub name: String,
+    pub arg: String,
+    pub ok: bool,
+    pub output: String,
+}
+
+/// Everything a post-mortem is written from
+pub struct FailureCapture<'a> {
+    pub record: FailureRecord,
+    pub context: &'a str,
+    /// (role, content) of each message
+    pub messages: &'a [(String, String)],
+    pub tools: &'a [ToolInvocation],
+}
+
+/// A bundle on disk
+#[derive(Debug, Clone)]
+pub struct FailureBundle {
+    pub path: PathBuf,
+    pub record: FailureRecord,
+}
+
+impl FailureBundle {
+    /// Tool invocations of tools.json
+    pub fn tools(&self) -> Vec<ToolInvocation> {
+        std::fs::read_to_string(self.path.join(TOOLS_FILE)).ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Repeated signatures among `bundles`, most frequent first
+pub fn failure_patterns(bundles: &[FailureBundle]) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for bundle in bundles {
+        *counts.entry(bundle.record.signature()).or_default() += 1;
+    }
+    let mut patterns: Vec<(String, usize)> = counts.into_iter().filter(|(_, n)| *n > 1).collect();
+    patterns.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    patterns
+}
+
+impl MemorySystem {
+    pub fn failures_dir(&self) -> PathBuf {
+        self.memory_root.join(FAILURES_DIR)
+    }
+
+    /// Write a post-mortem, then drop the oldest bundles beyond `keep`.
+    /// Returns the bundle directory.
+    pub fn save_failure(&self, capture: FailureCapture<'_>, keep: usize) -> Result<PathBuf> {
+        let root = self.failures_dir();
+        std::fs::create_dir_all(&root).with_context(|| format!("Failed to create {}", root.display()))?;
+        if let Some(debug_dir) = root.parent() {
+            let gitignore = debug_dir.join(".gitignore");
+            if !gitignore.exists() {
+                // Prompts and tool outputs of this device only
+                std::fs::write(&gitignore, "*\n")?;
+            }
+        }
+
+        let mut record = capture.record;
+        record.error = redact(&record.error);
+        record.prompt = redact(&record.prompt);
+        let name = if record.cycle.is_empty() { record.time.format("%Y%m%d%H%M%S%3f").to_string() } else { record.cycle.clone() };
+        let dir = root.join(&name);
+        std::fs::create_dir_all(&dir)?;
+
+        let messages: Vec<serde_json::Value> = capture.messages.iter()
+            .map(|(role, content)| serde_json::json!({ "role": role, "content": redact(content) }))
+            .collect();
+        let tools: Vec<ToolInvocation> = capture.tools.iter()
+            .map(|t| ToolInvocation {
+                arg: redact(&t.arg),
+                output: redact(&truncate_chars(&t.output, TOOL_OUTPUT_CHARS)),
+                ..t.clone()
+            })
+            .collect();
+
+        std::fs::write(dir.join(RECORD_FILE), serde_json::to_string_pretty(&record)?)?;
+        std::fs::write(dir.join(CONTEXT_FILE), redact(capture.context))?;
+        std::fs::write(dir.join(MESSAGES_FILE), serde_json::to_string_pretty(&messages)?)?;
+        std::fs::write(dir.join(TOOLS_FILE), serde_json::to_string_pretty(&tools)?)?;
+
+        self.prune_failures(keep);
+        Ok(dir)
+    }
+
+    /// Bundles on disk, newest first (unreadable ones are skipped)
+    pub fn list_failures(&self) -> Vec<FailureBundle> {
+        let Ok(entries) = std::fs::read_dir(self.failures_dir()) else { return Vec::new() };
+        let mut bundles: Vec<FailureBundle> = entries.flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .filter_map(|path| {
+                let record = serde_json::from_str(&std::fs::read_to_string(path.join(RECORD_FILE)).ok()?).ok()?;
+                Some(FailureBundle { path, record })
+            })
+            .collect();
+        bundles.sort_by(|a, b| b.record.time.cmp(&a.record.time).then_with(|| b.path.cmp(&a.path)));
+        bundles
+    }
+
+    /// Bundles written since `cutoff`, newest first
+    pub fn failures_since(&self, cutoff: DateTime<Local>) -> Vec<FailureBundle> {
+        self.list_failures().into_iter().filter(|b| b.record.time >= cutoff).collect()
+    }
+
+    /// The bundle of cycle `id` (a unique prefix is enough; "last" is the newest)
+    pub fn find_failure(&self, id: &str) -> Result<FailureBundle> {
+        let bundles = self.list_failures();
+        let id = id.trim();
+        if id.is_empty() || id == "last" || id == "latest" {
+            return bundles.into_iter().next().ok_or_else(|| anyhow!("No failure post-mortems"));
+        }
+        let mut matches: Vec<FailureBundle> = bundles.into_iter()
+            .filter(|b| b.path.file_name().is_some_and(|n| n.to_string_lossy().starts_with(id)))
+            .collect();
+        match matches.len() {
+            0 => Err(anyhow!("No post-mortem matches '{}'", id)),
+            1 => Ok(matches.remove(0)),
+            n => Err(anyhow!("'{}' matches {} post-mortems, give more of the cycle id", id, n)),
+        }
+    }
+
+    /// Delete the oldest bundles beyond `keep`. Returns how many went.
+    pub fn prune_failures(&self, keep: usize) -> usize {
+        let mut removed = 0;
+        for bundle in self.list_failures().into_iter().skip(keep) {
+            match std::fs::remove_dir_all(&bundle.path) {
+                Ok(()) => removed += 1,
+                Err(e) => tracing::warn!("🩺 Could not prune {}: {}", bundle.path.display(), e),
+            }
+        }
+        removed
+    }
+}
@@ -1,8 +1,52 @@
+//! Versioning of the workspace: git when it is installed, otherwise gzipped
+//! tarball snapshots in workspace/.snapshots (the newest MAX_SNAPSHOTS kept),
+//! taken wherever a commit would be and once a day by the scheduler.
+
 use crate::MemorySystem;
+use anyhow::{anyhow, Result};
+use openspore_core::capabilities::GIT_INSTALL_HINT;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Once;
+use walkdir::WalkDir;
+
+/// Snapshots of an unversioned workspace, relative to the memory root
+pub const SNAPSHOTS_DIR: &str = ".snapshots";
+
+/// Snapshots kept; older ones are deleted
+pub const MAX_SNAPSHOTS: usize = 20;
+
+/// Messages of the snapshots, one "<file>  <message>" line each
+const SNAPSHOT_LOG: &str = "LOG";
+
+/// Left out of snapshots: bulky media, caches and runtime records
+const SNAPSHOT_SKIP: [&str; 8] = [".git", SNAPSHOTS_DIR, crate::SEARCH_INDEX_DIR, ".runtime", "screenshots", "downloads", "exports", "debug"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum VersioningError {
+    /// git is missing and the operation has no snapshot fallback
+    #[error("{0} needs git, which is not installed. {hint}", hint = GIT_INSTALL_HINT)]
+    NotAvailable(&'static str),
+}
+
+/// The "no git" warning, once per process however many MemorySystems start
+fn warn_unversioned() {
+    static WARNED: Once = Once::new();
+    WARNED.call_once(|| {
+        tracing::warn!(
+            "⚠️ git is not installed: the workspace has no version history. Snapshots go to workspace/{} (newest {} kept) instead. {}",
+            SNAPSHOTS_DIR, MAX_SNAPSHOTS, GIT_INSTALL_HINT
+        );
+    });
+}
 
 impl MemorySystem {
     pub fn init_git(&self) {
+        if !self.versioned {
+            warn_unversioned();
+            return;
+        }
+        self.ensure_merge_attributes();
         if !self.memory_root.join(".git").exists() {
             let _ = Command::new("git")
                 .arg("init")
@@ -17,9 +61,59 @@ impl MemorySystem {
                 .current_dir(&self.memory_root)
                 .output();
         }
+        self.configure_merge_driver();
+    }
+
+    /// Merge rules of the workspace: markdown goes through the memory merge
+    /// driver (conflicts.rs), while the append-only logs and tombstones keep
+    /// the lines of both replicas. Later rules win, so union comes last.
+    fn ensure_merge_attributes(&self) {
+        let rules = [
+            format!("*.md merge={}", crate::MERGE_DRIVER),
+            "LOGS.md merge=union".to_string(),
+            format!("{} merge=union", crate::TOMBSTONES_FILE),
+        ];
+        let path = self.memory_root.join(".gitattributes");
+        let mut current = std::fs::read_to_string(&path).unwrap_or_default();
+        let missing: Vec<&String> = rules.iter().filter(|r| !current.lines().any(|l| l.trim() == r.as_str())).collect();
+        if !self.memory_root.exists() || missing.is_empty() {
+            return;
+        }
+        if !current.is_empty() && !current.ends_with('\n') {
+            current.push('\n');
+        }
+        for rule in missing {
+            current.push_str(rule);
+            current.push('\n');
+        }
+        let _ = std::fs::write(&path, current);
     }
 
+    /// Register the merge driver in the repository config (not synced, so each
+    /// device does this itself). Only the openspore binary can act as driver;
+    /// without it git falls back to its normal text merge.
+    fn configure_merge_driver(&self) {
+        let Ok(exe) = std::env::current_exe() else { return };
+        if !exe.file_stem().is_some_and(|s| s.to_string_lossy().starts_with("openspore")) {
+            return;
+        }
+        let driver = format!("'{}' memory merge-driver %O %A %B %P", exe.display());
+        for (key, value) in [("name", "OpenSpore memory merge"), ("driver", driver.as_str())] {
+            let _ = Command::new("git")
+                .args(["config", &format!("merge.{}.{}", crate::MERGE_DRIVER, key), value])
+                .current_dir(&self.memory_root)
+                .output();
+        }
+    }
+
+    /// Commit the whole workspace (a snapshot when git is not installed)
     pub fn commit(&self, message: &str) {
+        if !self.versioned {
+            if let Err(e) = self.snapshot(message) {
+                tracing::warn!("📦 Workspace snapshot failed: {}", e);
+            }
+            return;
+        }
         let _ = Command::new("git")
             .args(["add", "."])
             .current_dir(&self.memory_root)
@@ -29,4 +123,184 @@ impl MemorySystem {
             .current_dir(&self.memory_root)
             .output();
     }
+
+    fn snapshots_dir(&self) -> PathBuf {
+        self.memory_root.join(SNAPSHOTS_DIR)
+    }
+
+    /// Snapshot archives, oldest first (their names are timestamps, with a
+    /// zero-padded counter for several in one millisecond)
+    pub fn snapshots(&self) -> Vec<PathBuf> {
+        let mut archives: Vec<PathBuf> = std::fs::read_dir(self.snapshots_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.to_string_lossy().ends_with(".tar.gz"))
+            .collect();
+        archives.sort();
+        archives
+    }
+
+    /// Archive the workspace into SNAPSHOTS_DIR and drop the oldest snapshots
+    /// beyond MAX_SNAPSHOTS
+    pub fn snapshot(&self, message: &str) -> Result<PathBuf> {
+        use flate2::{write::GzEncoder, Compression};
+
+        let dir = self.snapshots_dir();
+        std::fs::create_dir_all(&dir)?;
+        let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+        let mut path = dir.join(format!("{}.tar.gz", stamp));
+        for n in 2.. {
+            if !path.exists() {
+                break;
+            }
+            path = dir.join(format!("{}_{:03}.tar.gz", stamp, n));
+        }
+
+        let tmp = path.with_extension("tmp");
+        let mut archive = tar::Builder::new(GzEncoder::new(std::fs::File::create(&tmp)?, Compression::default()));
+        let files = WalkDir::new(&self.memory_root)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !SNAPSHOT_SKIP.iter().any(|s| e.file_name() == *s))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file());
+        for entry in files {
+            let rel = entry.path().strip_prefix(&self.memory_root).unwrap_or(entry.path());
+            archive.append_path_with_name(entry.path(), rel)?;
+        }
+        archive.into_inner()?.finish()?;
+        std::fs::rename(&tmp, &path)?;
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let log_line = format!("{}  {}\n", name, message.replace('\n', " "));
+        use std::io::Write;
+        std::fs::OpenOptions::new().create(true).append(true).open(dir.join(SNAPSHOT_LOG))?.write_all(log_line.as_bytes())?;
+
+        let archives = self.snapshots();
+        for old in archives.iter().take(archives.len().saturating_sub(MAX_SNAPSHOTS)) {
+            let _ = std::fs::remove_file(old);
+        }
+        Ok(path)
+    }
+
+    /// Extract `rel_path` from the newest snapshot that holds it
+    pub(crate) fn restore_from_snapshots(&self, rel_path: &str) -> Result<PathBuf> {
+        let archives = self.snapshots();
+        for archive in archives.iter().rev() {
+            if extract_from(archive, rel_path, &self.memory_root.join(rel_path))? {
+                return Ok(archive.clone());
+            }
+        }
+        Err(anyhow!(
+            "{} is in none of the {} snapshots in workspace/{} (git, which keeps the full history, is not installed)",
+            rel_path, archives.len(), SNAPSHOTS_DIR
+        ))
+    }
+}
+
+/// Unpack the entry `rel_path` of `archive` to `dest`; false when it has none
+fn extract_from(archive: &Path, rel_path: &str, dest: &Path) -> Result<bool> {
+    let file = std::fs::File::open(archive)?;
+    let mut entries = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    for entry in entries.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.as_ref() == Path::new(rel_path) {
+            entry.unpack(dest)?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    /// Set in the child process of without_git()
+    const NO_GIT: &str = "OPENSPORE_TEST_NO_GIT";
+
+    /// Run the test `name` of this binary again, with the directories holding
+    /// git left out of PATH, and check that it passed there
+    fn assert_passes_without_git(name: &str) {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let stripped = std::env::join_paths(std::env::split_paths(&path).filter(|dir| !dir.join("git").exists())).unwrap();
+        let child = Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", name])
+            .env("PATH", stripped)
+            .env(NO_GIT, "1")
+            .output()
+            .unwrap();
+        let report = String::from_utf8_lossy(&child.stdout);
+        assert!(child.status.success() && report.contains("1 passed"), "{}{}", report, String::from_utf8_lossy(&child.stderr));
+    }
+
+    async fn memory(root: &Path) -> MemorySystem {
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: root.to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        memory
+    }
+
+    #[tokio::test]
+    async fn without_git_the_workspace_falls_back_to_snapshots() {
+        if std::env::var_os(NO_GIT).is_none() {
+            assert_passes_without_git("git::tests::without_git_the_workspace_falls_back_to_snapshots");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        assert!(!memory.versioned);
+        assert!(!memory.memory_root.join(".git").exists());
+        assert!(!memory.memory_root.join(".gitattributes").exists());
+
+        let path = memory.save_memory("knowledge", "Boiler service", "The boiler was serviced in May.", vec![], None, None).await.unwrap().unwrap();
+        let snapshots = memory.snapshots();
+        assert!(!snapshots.is_empty(), "the save was snapshotted instead of committed");
+        let log = std::fs::read_to_string(memory.snapshots_dir().join(SNAPSHOT_LOG)).unwrap();
+        assert!(log.lines().last().unwrap().starts_with(&snapshots.last().unwrap().file_name().unwrap().to_string_lossy().to_string()));
+
+        // Deleted files come back from the newest snapshot holding them
+        memory.forget(&path, "test").await.unwrap();
+        assert!(!path.exists());
+        let rel = path.strip_prefix(&memory.memory_root).unwrap().to_string_lossy().to_string();
+        memory.restore_tombstoned(&rel).await.unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("The boiler was serviced in May."));
+
+        // Operations that need git say so and how to get it
+        let error = crate::merge_texts("a\n", "b\n", "c\n").unwrap_err();
+        assert!(matches!(error.downcast_ref::<VersioningError>(), Some(VersioningError::NotAvailable(_))));
+        assert!(error.to_string().contains(GIT_INSTALL_HINT));
+    }
+
+    #[tokio::test]
+    async fn snapshots_skip_bulky_directories_and_keep_the_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut memory = memory(dir.path()).await;
+        memory.versioned = false;
+        std::fs::write(memory.memory_root.join("knowledge/keep.md"), "kept").unwrap();
+        std::fs::create_dir_all(memory.memory_root.join("screenshots")).unwrap();
+        std::fs::write(memory.memory_root.join("screenshots/shot.png"), "png").unwrap();
+
+        let first = memory.snapshot("first").unwrap();
+        let names: Vec<String> = tar::Archive::new(flate2::read::GzDecoder::new(std::fs::File::open(&first).unwrap()))
+            .entries().unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert!(names.contains(&"knowledge/keep.md".to_string()));
+        assert!(!names.iter().any(|n| n.starts_with("screenshots") || n.starts_with(".git/") || n.starts_with(SNAPSHOTS_DIR)), "{:?}", names);
+
+        for i in 0..MAX_SNAPSHOTS + 2 {
+            memory.snapshot(&format!("snapshot {}", i)).unwrap();
+        }
+        let kept = memory.snapshots();
+        assert_eq!(kept.len(), MAX_SNAPSHOTS);
+        assert!(!first.exists(), "the oldest go first");
+
+        std::fs::remove_file(memory.memory_root.join("knowledge/keep.md")).unwrap();
+        memory.restore_from_snapshots("knowledge/keep.md").unwrap();
+        assert_eq!(std::fs::read_to_string(memory.memory_root.join("knowledge/keep.md")).unwrap(), "kept");
+        assert!(memory.restore_from_snapshots("knowledge/never.md").unwrap_err().to_string().contains("none of the 20 snapshots"));
+    }
 }
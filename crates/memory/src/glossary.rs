@@ -0,0 +1,400 @@
+//! Glossary of canonical terms
+//!
+//! `workspace/identity/GLOSSARY.md` holds a markdown table of terms, their
+//! aliases and a definition, edited by hand or through `/define`. Aliases are
+//! matched case-insensitively on word boundaries ("the meridian project",
+//! "meridian" for **Meridian**). save_memory rewrites them to the term in
+//! titles and tags (never in the body), search adds a matched entry's term and
+//! one-word aliases to the query, and the prompt gets the entries whose terms
+//! appear in the user's message.
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+
+/// Location of the glossary, relative to the workspace
+pub const GLOSSARY_FILE: &str = "identity/GLOSSARY.md";
+
+/// Entries shown in the prompt at most
+pub const MAX_EXCERPT_ENTRIES: usize = 8;
+
+const TABLE_HEADER: &str = "| Term | Aliases | Definition |\n|------|---------|------------|";
+
+const NEW_GLOSSARY: &str = "# Glossary\n\nCanonical spellings of project names, people and jargon. Aliases (comma-separated) are rewritten to the term in memory titles and tags.\n\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryEntry {
+    pub term: String,
+    pub aliases: Vec<String>,
+    pub definition: String,
+}
+
+impl GlossaryEntry {
+    /// The term and its aliases, longest first
+    fn forms(&self) -> Vec<&str> {
+        let mut forms: Vec<&str> = std::iter::once(self.term.as_str())
+            .chain(self.aliases.iter().map(String::as_str))
+            .filter(|f| !f.trim().is_empty())
+            .collect();
+        forms.sort_by_key(|f| std::cmp::Reverse(f.len()));
+        forms
+    }
+
+    fn row(&self) -> String {
+        format!("| {} | {} | {} |", escape_cell(&self.term), escape_cell(&self.aliases.join(", ")), escape_cell(&self.definition))
+    }
+
+    /// Compact line for the prompt
+    pub fn render_short(&self) -> String {
+        let aliases = if self.aliases.is_empty() { String::new() } else { format!(" (also: {})", self.aliases.join(", ")) };
+        let definition = if self.definition.is_empty() { String::new() } else { format!(": {}", self.definition) };
+        format!("- **{}**{}{}", self.term, aliases, definition)
+    }
+}
+
+/// Parsed glossary
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    pub entries: Vec<GlossaryEntry>,
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('\n', " ").replace('|', "\\|").trim().to_string()
+}
+
+/// Cells of a table row, honouring `\|`
+fn split_row(line: &str) -> Option<Vec<String>> {
+    let inner = line.trim().strip_prefix('|')?;
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    let mut cells = vec![String::new()];
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut()?.push('|');
+                chars.next();
+            }
+            '|' => cells.push(String::new()),
+            _ => cells.last_mut()?.push(c),
+        }
+    }
+    Some(cells.into_iter().map(|c| c.trim().to_string()).collect())
+}
+
+/// Entry of a table row (header and separator rows give None)
+fn parse_row(line: &str) -> Option<GlossaryEntry> {
+    let cells = split_row(line)?;
+    let term = cells.first()?.trim_matches('*').trim().to_string();
+    if term.is_empty() || term.eq_ignore_ascii_case("term") || term.chars().all(|c| c == '-' || c == ':') {
+        return None;
+    }
+    Some(GlossaryEntry {
+        aliases: cells.get(1).map(|a| split_aliases(a)).unwrap_or_default()
+            .into_iter()
+            .filter(|a| !a.eq_ignore_ascii_case(&term))
+            .collect(),
+        definition: cells.get(2).cloned().unwrap_or_default(),
+        term,
+    })
+}
+
+fn split_aliases(text: &str) -> Vec<String> {
+    text.split(',').map(|a| a.trim().trim_matches('"').trim().to_string()).filter(|a| !a.is_empty()).collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte ranges where `form` occurs in `text` as a whole word, ignoring case
+fn find_word(text: &str, form: &str) -> Vec<(usize, usize)> {
+    let Ok(pattern) = regex::RegexBuilder::new(&regex::escape(form.trim())).case_insensitive(true).build() else {
+        return Vec::new();
+    };
+    pattern.find_iter(text)
+        .filter(|m| {
+            !text[..m.start()].chars().next_back().is_some_and(is_word_char)
+                && !text[m.end()..].chars().next().is_some_and(is_word_char)
+        })
+        .map(|m| (m.start(), m.end()))
+        .collect()
+}
+
+impl Glossary {
+    pub fn parse(content: &str) -> Self {
+        let mut entries: Vec<GlossaryEntry> = Vec::new();
+        for entry in content.lines().filter_map(parse_row) {
+            // A term listed twice: the later row wins
+            entries.retain(|e| !e.term.eq_ignore_ascii_case(&entry.term));
+            entries.push(entry);
+        }
+        Self { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entry whose term or alias is `name`
+    pub fn lookup(&self, name: &str) -> Option<&GlossaryEntry> {
+        let name = name.trim();
+        self.entries.iter().find(|e| e.forms().iter().any(|f| f.eq_ignore_ascii_case(name)))
+    }
+
+    /// Entries mentioned in `text`, in glossary order
+    pub fn mentioned_in(&self, text: &str) -> Vec<&GlossaryEntry> {
+        self.entries.iter().filter(|e| e.forms().iter().any(|f| !find_word(text, f).is_empty())).collect()
+    }
+
+    /// `text` with every alias (and differently cased term) replaced by the
+    /// canonical term. Longer aliases win over the shorter ones they contain.
+    pub fn normalize(&self, text: &str) -> String {
+        let mut forms: Vec<(&str, &str)> = self.entries.iter()
+            .flat_map(|e| e.forms().into_iter().map(move |f| (f, e.term.as_str())))
+            .collect();
+        forms.sort_by_key(|(form, _)| std::cmp::Reverse(form.len()));
+
+        let mut replacements: Vec<(usize, usize, &str)> = Vec::new();
+        for (form, term) in forms {
+            for (start, end) in find_word(text, form) {
+                if !replacements.iter().any(|(s, e, _)| start < *e && *s < end) {
+                    replacements.push((start, end, term));
+                }
+            }
+        }
+        if replacements.is_empty() {
+            return text.to_string();
+        }
+        replacements.sort_by_key(|(start, _, _)| *start);
+        let mut normalized = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end, term) in replacements {
+            normalized.push_str(&text[last..start]);
+            normalized.push_str(term);
+            last = end;
+        }
+        normalized.push_str(&text[last..]);
+        normalized
+    }
+
+    /// Words to add to a search for `query`: the term and one-word aliases of
+    /// each entry it mentions (longer aliases would only add common words)
+    pub fn query_expansions(&self, query: &str) -> Vec<String> {
+        self.mentioned_in(query).into_iter()
+            .flat_map(|e| std::iter::once(e.term.clone()).chain(e.aliases.iter().filter(|a| !a.contains(char::is_whitespace)).cloned()))
+            .collect()
+    }
+
+    /// Compact lines for the entries mentioned in `prompt`, at most `max`
+    pub fn excerpt(&self, prompt: &str, max: usize) -> Vec<String> {
+        self.mentioned_in(prompt).into_iter().take(max).map(GlossaryEntry::render_short).collect()
+    }
+}
+
+/// `/define` arguments: `Term = alias, alias: definition`; the aliases and the
+/// definition are both optional
+pub fn parse_definition(args: &str) -> Result<GlossaryEntry> {
+    let (head, definition) = args.split_once(':').unwrap_or((args, ""));
+    let (term, aliases) = head.split_once('=').unwrap_or((head, ""));
+    let term = term.trim().trim_matches('*').trim();
+    if term.is_empty() {
+        return Err(anyhow!("Usage: /define Term = alias, alias: definition"));
+    }
+    Ok(GlossaryEntry {
+        term: term.to_string(),
+        aliases: split_aliases(aliases).into_iter().filter(|a| !a.eq_ignore_ascii_case(term)).collect(),
+        definition: definition.trim().to_string(),
+    })
+}
+
+/// `content` with `entry`'s row replaced (same term) or added after the last
+/// row of the table. Everything else in the file is kept as written.
+fn upsert_row(content: &str, entry: &GlossaryEntry) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    if let Some(i) = lines.iter().position(|l| parse_row(l).is_some_and(|e| e.term.eq_ignore_ascii_case(&entry.term))) {
+        lines[i] = entry.row();
+    } else if let Some(i) = lines.iter().rposition(|l| split_row(l).is_some()) {
+        lines.insert(i + 1, entry.row());
+    } else {
+        let mut text = if content.trim().is_empty() { NEW_GLOSSARY.to_string() } else { format!("{}\n\n", content.trim_end()) };
+        text.push_str(&format!("{}\n{}\n", TABLE_HEADER, entry.row()));
+        return text;
+    }
+    lines.join("\n") + "\n"
+}
+
+impl MemorySystem {
+    pub fn glossary_path(&self) -> PathBuf {
+        self.memory_root.join(GLOSSARY_FILE)
+    }
+
+    /// The glossary, empty when the file is missing
+    pub fn glossary(&self) -> Glossary {
+        self.read_text(&self.glossary_path()).map(|c| Glossary::parse(&c)).unwrap_or_default()
+    }
+
+    /// Add or update a term. New aliases are merged into the existing ones and
+    /// an empty definition keeps the current one. Returns the stored entry.
+    pub async fn define_term(&self, entry: GlossaryEntry) -> Result<GlossaryEntry> {
+        let path = self.glossary_path();
+        let content = self.read_text(&path).unwrap_or_default();
+        let glossary = Glossary::parse(&content);
+
+        let mut stored = entry;
+        if let Some(other) = glossary.entries.iter().find(|e| !e.term.eq_ignore_ascii_case(&stored.term) && e.forms().iter().any(|f| f.eq_ignore_ascii_case(&stored.term))) {
+            return Err(anyhow!("\"{}\" is already an alias of {}", stored.term, other.term));
+        }
+        if let Some(existing) = glossary.entries.iter().find(|e| e.term.eq_ignore_ascii_case(&stored.term)) {
+            let mut aliases = existing.aliases.clone();
+            aliases.extend(stored.aliases.into_iter().filter(|a| !existing.aliases.iter().any(|x| x.eq_ignore_ascii_case(a))));
+            stored.aliases = aliases;
+            if stored.definition.is_empty() {
+                stored.definition = existing.definition.clone();
+            }
+        }
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        self.mark_as_internal_write(path.clone()).await;
+        tokio::fs::write(&path, upsert_row(&content, &stored)).await?;
+        self.index_written(&path).await;
+        self.commit(&format!("Glossary: {}", stored.term));
+        Ok(stored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    const GLOSSARY: &str = r#"# Glossary
+
+| Term | Aliases | Definition |
+|------|---------|------------|
+| **Meridian** | meridian project, the meridian project, MRD | Billing rewrite, ships Q3 |
+| Sam Okafor | Sam, "S. Okafor" | Tech lead of Meridian |
+| Pipe \| Filter | p&f | Architecture style |
+| Old | legacy | Replaced below |
+| old | Legacy | The previous stack |
+"#;
+
+    fn glossary() -> Glossary {
+        Glossary::parse(GLOSSARY)
+    }
+
+    #[test]
+    fn parse_reads_the_table() {
+        let glossary = glossary();
+        let terms: Vec<_> = glossary.entries.iter().map(|e| e.term.as_str()).collect();
+        assert_eq!(terms, ["Meridian", "Sam Okafor", "Pipe | Filter", "old"], "a repeated term keeps the later row");
+        assert_eq!(glossary.entries[0].aliases, ["meridian project", "the meridian project", "MRD"]);
+        assert_eq!(glossary.entries[1].aliases, ["Sam", "S. Okafor"]);
+        assert_eq!(glossary.lookup("mrd").map(|e| e.term.as_str()), Some("Meridian"));
+        assert!(glossary.lookup("meridians").is_none());
+    }
+
+    #[test]
+    fn normalize_rewrites_whole_word_aliases_to_the_term() {
+        let glossary = glossary();
+        assert_eq!(glossary.normalize("Status of the meridian project"), "Status of Meridian");
+        assert_eq!(glossary.normalize("MERIDIAN launch"), "Meridian launch");
+        assert_eq!(glossary.normalize("mrd notes from sam"), "Meridian notes from Sam Okafor");
+        assert_eq!(glossary.normalize("S. Okafor on MRD"), "Sam Okafor on Meridian");
+        // Word boundaries: no partial words, and the term's own casing is kept
+        assert_eq!(glossary.normalize("meridians and samples"), "meridians and samples");
+        assert_eq!(glossary.normalize("Sam Okafor"), "Sam Okafor");
+        assert_eq!(glossary.normalize("café meridian"), "café Meridian");
+        assert_eq!(Glossary::default().normalize("the meridian project"), "the meridian project");
+    }
+
+    #[test]
+    fn queries_expand_with_the_term_and_one_word_aliases() {
+        let glossary = glossary();
+        assert_eq!(glossary.query_expansions("where is the meridian project at?"), ["Meridian", "MRD"]);
+        assert_eq!(glossary.query_expansions("ask sam"), ["Sam Okafor", "Sam"]);
+        assert!(glossary.query_expansions("meridians of longitude").is_empty());
+    }
+
+    #[test]
+    fn the_excerpt_lists_mentioned_entries_only() {
+        let glossary = glossary();
+        assert_eq!(
+            glossary.excerpt("Did Sam sign off on MRD?", MAX_EXCERPT_ENTRIES),
+            [
+                "- **Meridian** (also: meridian project, the meridian project, MRD): Billing rewrite, ships Q3",
+                "- **Sam Okafor** (also: Sam, S. Okafor): Tech lead of Meridian",
+            ]
+        );
+        assert_eq!(glossary.excerpt("Did Sam sign off on MRD?", 1).len(), 1);
+        assert!(glossary.excerpt("What's the weather?", MAX_EXCERPT_ENTRIES).is_empty());
+
+        let many = Glossary { entries: (0..20).map(|i| GlossaryEntry { term: format!("T{}", i), aliases: vec![], definition: String::new() }).collect() };
+        let prompt: String = (0..20).map(|i| format!("T{} ", i)).collect();
+        assert_eq!(many.excerpt(&prompt, MAX_EXCERPT_ENTRIES).len(), MAX_EXCERPT_ENTRIES);
+        assert_eq!(many.excerpt("T3", MAX_EXCERPT_ENTRIES), ["- **T3**"]);
+    }
+
+    #[test]
+    fn define_arguments_parse() {
+        let entry = parse_definition("Meridian = MRD, the meridian project: Billing rewrite").unwrap();
+        assert_eq!(entry, GlossaryEntry {
+            term: "Meridian".into(),
+            aliases: vec!["MRD".into(), "the meridian project".into()],
+            definition: "Billing rewrite".into(),
+        });
+        assert_eq!(parse_definition("**Kestrel**").unwrap().aliases, Vec::<String>::new());
+        assert!(parse_definition(" = x: y").is_err());
+    }
+
+    async fn memory(root: &std::path::Path) -> MemorySystem {
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: root.to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        memory
+    }
+
+    #[tokio::test]
+    async fn define_term_merges_into_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        let path = memory.glossary_path();
+        std::fs::write(&path, "# My terms\n\nKept as written.\n").unwrap();
+
+        memory.define_term(parse_definition("Meridian = MRD: Billing rewrite").unwrap()).await.unwrap();
+        let stored = memory.define_term(parse_definition("meridian = the meridian project, mrd").unwrap()).await.unwrap();
+        assert_eq!(stored.aliases, ["MRD", "the meridian project"]);
+        assert_eq!(stored.definition, "Billing rewrite", "an empty definition keeps the old one");
+        assert!(memory.define_term(parse_definition("MRD").unwrap()).await.is_err(), "already an alias");
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# My terms\n\nKept as written.\n"));
+        assert_eq!(content.matches("| meridian |").count() + content.matches("| Meridian |").count(), 1);
+        assert_eq!(memory.glossary().entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn saved_memories_get_canonical_titles_and_tags_but_keep_their_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        std::fs::write(memory.glossary_path(), GLOSSARY).unwrap();
+
+        let path = memory
+            .save_memory("knowledge", "the meridian project timeline", "mrd ships when sam says so", vec!["mrd".into(), "billing".into()], None, None)
+            .await
+            .unwrap()
+            .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(path.file_name().unwrap(), "meridian_timeline.md");
+        assert!(content.contains("\ntags: Meridian, billing\n"), "{}", content);
+        assert!(content.contains("\n# Meridian timeline\n"), "{}", content);
+        assert!(content.contains("mrd ships when sam says so"), "the body is left alone");
+
+        // Searching for the term finds notes that only use an alias
+        let note = memory.category_dir("knowledge").join("standup.md");
+        std::fs::write(&note, "MRD invoices are late again.").unwrap();
+        let found = memory.search_in_path("the meridian project", &memory.category_dir("knowledge"), 10).await.unwrap();
+        assert!(found.iter().any(|r| r.path == note));
+    }
+}
@@ -0,0 +1,349 @@
+//! Housekeeping of screenshots and downloads
+//!
+//! Browser screenshots land in workspace/screenshots and downloads in
+//! workspace/downloads, and nothing else ever removes them. housekeep() holds
+//! such a directory to a RetentionPolicy: files past the age limit go first,
+//! then the oldest ones until the directory fits its size limit. Files listed
+//! in the directory's `.pinned` file, or named by a conversation export written
+//! within the age window, are never deleted. Chunked writes abandoned before
+//! their final chunk (workspace/partial_writes) go after a day. storage_report()
+//! backs `openspore storage report`.
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Result};
+use openspore_core::config::RetentionPolicy;
+use openspore_core::fmt_utils::humanize_bytes;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// Housekept directories, relative to the workspace
+pub const SCREENSHOTS_DIR: &str = "screenshots";
+pub const DOWNLOADS_DIR: &str = "downloads";
+
+/// Staged chunks of write_file calls, relative to the workspace
+pub const PARTIAL_WRITES_DIR: &str = "partial_writes";
+
+/// Age at which a staged chunked write counts as abandoned
+pub const PARTIAL_WRITES_MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// Conversations exported from the TUI, relative to the workspace
+pub const EXPORTS_DIR: &str = "exports";
+
+/// Per-directory list of kept files, one path (relative to the directory) per line
+pub const PINS_FILE: &str = ".pinned";
+
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+impl StoredFile {
+    pub fn age(&self) -> Duration {
+        SystemTime::now().duration_since(self.modified).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HousekeepingReport {
+    pub dir: PathBuf,
+    /// Deleted (or, in a dry run, to be deleted), oldest first
+    pub deleted: Vec<StoredFile>,
+    /// Over a limit but pinned
+    pub kept_pinned: usize,
+    /// Over a limit but named by a recent export
+    pub kept_referenced: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub dry_run: bool,
+}
+
+impl HousekeepingReport {
+    pub fn freed(&self) -> u64 {
+        self.deleted.iter().map(|f| f.size).sum()
+    }
+}
+
+/// Size and file count of one top-level workspace directory
+#[derive(Debug, Clone)]
+pub struct DirUsage {
+    pub name: String,
+    pub bytes: u64,
+    pub files: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct StorageReport {
+    /// Largest first
+    pub dirs: Vec<DirUsage>,
+    pub largest: Vec<StoredFile>,
+    pub total: u64,
+}
+
+/// Files under `dir` (recursively), dotfiles and `.git` excluded
+fn stored_files(dir: &Path) -> Vec<StoredFile> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some(StoredFile { path: e.into_path(), size: meta.len(), modified: meta.modified().ok()? })
+        })
+        .collect()
+}
+
+/// Entries of `dir/.pinned`
+fn pins(dir: &Path) -> HashSet<String> {
+    std::fs::read_to_string(dir.join(PINS_FILE))
+        .map(|text| text.lines().map(|l| l.trim().trim_start_matches("./").to_string()).filter(|l| !l.is_empty() && !l.starts_with('#')).collect())
+        .unwrap_or_default()
+}
+
+fn is_pinned(pins: &HashSet<String>, dir: &Path, file: &Path) -> bool {
+    let relative = file.strip_prefix(dir).unwrap_or(file).to_string_lossy().to_string();
+    let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    pins.contains(&relative) || pins.contains(&name)
+}
+
+impl MemorySystem {
+    /// Text of the exports written within `window` (all of them when None)
+    fn recent_exports(&self, window: Option<Duration>) -> String {
+        stored_files(&self.memory_root.join(EXPORTS_DIR)).into_iter()
+            .filter(|f| window.is_none_or(|w| f.age() <= w))
+            .filter_map(|f| std::fs::read_to_string(&f.path).ok())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Apply `policy` to workspace/`dir_name`. A dry run only reports.
+    pub fn housekeep(&self, dir_name: &str, policy: RetentionPolicy, dry_run: bool) -> Result<HousekeepingReport> {
+        let dir = self.memory_root.join(dir_name);
+        let mut report = HousekeepingReport { dir: dir.clone(), dry_run, ..Default::default() };
+        let mut files = stored_files(&dir);
+        report.bytes_before = files.iter().map(|f| f.size).sum();
+        report.bytes_after = report.bytes_before;
+        if files.is_empty() || policy.is_unlimited() {
+            return Ok(report);
+        }
+        files.sort_by_key(|f| f.modified);
+
+        let pins = pins(&dir);
+        let exports = self.recent_exports(policy.max_age);
+        let mut total = report.bytes_before;
+        for file in files {
+            let too_old = policy.max_age.is_some_and(|max| file.age() > max);
+            let over_size = policy.max_bytes.is_some_and(|max| total > max);
+            if !too_old && !over_size {
+                continue;
+            }
+            if is_pinned(&pins, &dir, &file.path) {
+                report.kept_pinned += 1;
+                continue;
+            }
+            let name = file.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if !name.is_empty() && exports.contains(&name) {
+                report.kept_referenced += 1;
+                continue;
+            }
+            if !dry_run && let Err(e) = std::fs::remove_file(&file.path) {
+                warn!("🧹 Could not delete {}: {}", file.path.display(), e);
+                continue;
+            }
+            total = total.saturating_sub(file.size);
+            report.deleted.push(file);
+        }
+        report.bytes_after = total;
+
+        if !dry_run && !report.deleted.is_empty() {
+            info!("🧹 {}: deleted {} file(s), {} freed", dir_name, report.deleted.len(), humanize_bytes(report.freed()));
+        }
+        Ok(report)
+    }
+
+    /// Housekeeping of screenshots, downloads and abandoned chunked writes (the daily maintenance job)
+    pub fn housekeep_media(&self, screenshots: RetentionPolicy, downloads: RetentionPolicy, dry_run: bool) -> Vec<HousekeepingReport> {
+        let partial_writes = RetentionPolicy { max_bytes: None, max_age: Some(PARTIAL_WRITES_MAX_AGE) };
+        [(SCREENSHOTS_DIR, screenshots), (DOWNLOADS_DIR, downloads), (PARTIAL_WRITES_DIR, partial_writes)].into_iter()
+            .filter_map(|(dir, policy)| match self.housekeep(dir, policy, dry_run) {
+                Ok(report) => Some(report),
+                Err(e) => {
+                    warn!("🧹 Housekeeping of {} failed: {}", dir, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Keep `path` (a file in screenshots or downloads) through housekeeping
+    pub fn pin_file(&self, path: &Path) -> Result<PathBuf> {
+        let path = if path.is_absolute() { path.to_path_buf() } else { std::env::current_dir()?.join(path) };
+        if !path.is_file() {
+            return Err(anyhow!("{} is not a file", path.display()));
+        }
+        let dir = [SCREENSHOTS_DIR, DOWNLOADS_DIR].iter()
+            .map(|d| self.memory_root.join(d))
+            .find(|d| path.starts_with(d))
+            .ok_or_else(|| anyhow!("Only files in workspace/{} or workspace/{} are housekept", SCREENSHOTS_DIR, DOWNLOADS_DIR))?;
+        let relative = path.strip_prefix(&dir)?.to_string_lossy().to_string();
+        if !pins(&dir).contains(&relative) {
+            use std::io::Write;
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(dir.join(PINS_FILE))?;
+            writeln!(file, "{}", relative)?;
+        }
+        Ok(path)
+    }
+
+    /// Sizes of the workspace's top-level directories and its `largest` files
+    pub fn storage_report(&self, largest: usize) -> StorageReport {
+        let mut report = StorageReport::default();
+        let Ok(entries) = std::fs::read_dir(&self.memory_root) else { return report };
+        let mut all: Vec<StoredFile> = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let files = if path.is_dir() { stored_files(&path) } else {
+                entry.metadata().ok()
+                    .and_then(|m| Some(StoredFile { size: m.len(), modified: m.modified().ok()?, path: path.clone() }))
+                    .into_iter().collect()
+            };
+            let bytes = files.iter().map(|f| f.size).sum();
+            report.total += bytes;
+            report.dirs.push(DirUsage { name, bytes, files: files.len() });
+            all.extend(files);
+        }
+        report.dirs.sort_by_key(|d| std::cmp::Reverse(d.bytes));
+        all.sort_by_key(|f| std::cmp::Reverse(f.size));
+        all.truncate(largest);
+        report.largest = all;
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    const DAY: Duration = Duration::from_secs(24 * 3600);
+
+    async fn memory(root: &Path) -> MemorySystem {
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: root.to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        memory
+    }
+
+    /// A `size`-byte file in workspace/`dir`, last modified `age` ago
+    fn stored(memory: &MemorySystem, dir: &str, name: &str, size: usize, age: Duration) -> PathBuf {
+        let path = memory.memory_root.join(dir).join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, vec![b'x'; size]).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+        path
+    }
+
+    fn names(report: &HousekeepingReport) -> Vec<String> {
+        report.deleted.iter().map(|f| f.path.file_name().unwrap().to_string_lossy().to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn oldest_files_go_first_until_the_directory_fits() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        for (name, days) in [("d.png", 1), ("a.png", 4), ("c.png", 2), ("b.png", 3)] {
+            stored(&memory, SCREENSHOTS_DIR, name, 100, DAY * days);
+        }
+        let policy = RetentionPolicy { max_bytes: Some(250), max_age: None };
+
+        let dry = memory.housekeep(SCREENSHOTS_DIR, policy, true).unwrap();
+        assert_eq!(names(&dry), ["a.png", "b.png"]);
+        assert_eq!((dry.bytes_before, dry.bytes_after, dry.freed()), (400, 200, 200));
+        assert!(memory.memory_root.join(SCREENSHOTS_DIR).join("a.png").exists(), "a dry run deletes nothing");
+
+        let report = memory.housekeep(SCREENSHOTS_DIR, policy, false).unwrap();
+        assert_eq!(names(&report), ["a.png", "b.png"]);
+        let left: HashSet<String> = stored_files(&memory.memory_root.join(SCREENSHOTS_DIR)).iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(left, HashSet::from(["c.png".to_string(), "d.png".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn files_past_the_age_limit_go_whatever_the_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        stored(&memory, DOWNLOADS_DIR, "old.zip", 10, DAY * 40);
+        stored(&memory, DOWNLOADS_DIR, "nested/older.pdf", 10, DAY * 50);
+        stored(&memory, DOWNLOADS_DIR, "new.zip", 10_000, DAY);
+        let policy = RetentionPolicy { max_bytes: Some(1_000_000), max_age: Some(DAY * 30) };
+
+        let report = memory.housekeep(DOWNLOADS_DIR, policy, false).unwrap();
+        assert_eq!(names(&report), ["older.pdf", "old.zip"]);
+        assert!(memory.memory_root.join(DOWNLOADS_DIR).join("new.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn pinned_files_survive_and_the_next_oldest_goes_instead() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        let oldest = stored(&memory, SCREENSHOTS_DIR, "receipt.png", 100, DAY * 90);
+        stored(&memory, SCREENSHOTS_DIR, "flows/login.png", 100, DAY * 80);
+        stored(&memory, SCREENSHOTS_DIR, "b.png", 100, DAY * 3);
+        stored(&memory, SCREENSHOTS_DIR, "c.png", 100, DAY * 2);
+        memory.pin_file(&oldest).unwrap();
+        memory.pin_file(&oldest).unwrap();
+        let pins_file = memory.memory_root.join(SCREENSHOTS_DIR).join(PINS_FILE);
+        assert_eq!(std::fs::read_to_string(&pins_file).unwrap(), "receipt.png\n", "pinned once");
+        std::fs::write(&pins_file, "# keep\nreceipt.png\n./flows/login.png\n").unwrap();
+
+        let report = memory.housekeep(SCREENSHOTS_DIR, RetentionPolicy { max_bytes: Some(350), max_age: Some(DAY * 30) }, false).unwrap();
+        assert_eq!(names(&report), ["b.png"], "pinned files are skipped, not counted as freed");
+        assert_eq!(report.kept_pinned, 2);
+        assert_eq!(report.bytes_after, 300);
+        assert!(oldest.exists() && pins_file.exists());
+        assert!(memory.memory_root.join(SCREENSHOTS_DIR).join("flows/login.png").exists());
+    }
+
+    #[tokio::test]
+    async fn files_named_by_a_recent_export_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        stored(&memory, DOWNLOADS_DIR, "invoice_0412.pdf", 10, DAY * 40);
+        stored(&memory, DOWNLOADS_DIR, "unused.pdf", 10, DAY * 40);
+        stored(&memory, DOWNLOADS_DIR, "quote.pdf", 10, DAY * 40);
+        std::fs::create_dir_all(memory.memory_root.join(EXPORTS_DIR)).unwrap();
+        std::fs::write(memory.memory_root.join(EXPORTS_DIR).join("chat.md"), "Saved to downloads/invoice_0412.pdf").unwrap();
+        let stale = stored(&memory, EXPORTS_DIR, "stale.md", 0, DAY * 60);
+        std::fs::write(&stale, "quote.pdf").unwrap();
+        std::fs::File::options().write(true).open(&stale).unwrap().set_modified(SystemTime::now() - DAY * 60).unwrap();
+
+        let report = memory.housekeep(DOWNLOADS_DIR, RetentionPolicy { max_bytes: None, max_age: Some(DAY * 30) }, false).unwrap();
+        let mut deleted = names(&report);
+        deleted.sort();
+        assert_eq!(deleted, ["quote.pdf", "unused.pdf"], "an export older than the window does not keep a file");
+        assert_eq!(report.kept_referenced, 1);
+    }
+
+    #[tokio::test]
+    async fn unlimited_policies_and_foreign_pins_change_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        stored(&memory, SCREENSHOTS_DIR, "a.png", 100, DAY * 400);
+        let unlimited = RetentionPolicy { max_bytes: None, max_age: None };
+        let report = memory.housekeep(SCREENSHOTS_DIR, unlimited, false).unwrap();
+        assert!(report.deleted.is_empty());
+        assert_eq!(report.bytes_after, 100);
+
+        let elsewhere = stored(&memory, "notes", "a.md", 1, DAY);
+        assert!(memory.pin_file(&elsewhere).is_err());
+        let partial = stored(&memory, PARTIAL_WRITES_DIR, "abc/0001", 1, DAY * 2);
+        let reports = memory.housekeep_media(unlimited, unlimited, false);
+        assert_eq!(reports.len(), 3);
+        assert!(!partial.exists(), "abandoned chunked writes go after a day");
+    }
+}
@@ -0,0 +1,395 @@
+use crate::MemorySystem;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Human-readable table of contents at the workspace root
+pub const INDEX_FILE: &str = "INDEX.md";
+
+/// Rows listed per category before the table is truncated
+const MAX_ROWS_PER_CATEGORY: usize = 50;
+const SUMMARY_MAX_CHARS: usize = 100;
+
+/// Cached section of a category, keyed by its git tree hash
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CategorySnapshot {
+    tree: String,
+    count: usize,
+    section: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct IndexState {
+    categories: HashMap<String, CategorySnapshot>,
+}
+
+/// Outcome of an index regeneration
+#[derive(Debug, Clone, Default)]
+pub struct IndexReport {
+    pub path: PathBuf,
+    pub rescanned: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+struct IndexEntry {
+    file: String,
+    title: String,
+    date: String,
+    tags: String,
+    summary: String,
+}
+
+impl MemorySystem {
+    pub fn index_path(&self) -> PathBuf {
+        self.memory_root.join(INDEX_FILE)
+    }
+
+    fn index_state_path(&self) -> PathBuf {
+        self.memory_root.join("autonomy").join("index_state.json")
+    }
+
+    /// Regenerate workspace/INDEX.md.
+    /// Only categories whose git tree hash changed since the last run are rescanned
+    /// (pass `force` to rescan everything).
+    pub async fn regenerate_index(&self, force: bool) -> Result<IndexReport> {
+        self.ensure_structure().await?;
+
+        let state_path = self.index_state_path();
+        let mut state: IndexState = std::fs::read_to_string(&state_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
+        let trees = self.category_trees();
+        let mut report = IndexReport { path: self.index_path(), ..Default::default() };
+
+        for cat in &self.categories {
+            let tree = trees.get(*cat).cloned();
+            let cached = state.categories.get(*cat);
+
+            let unchanged = !force
+                && tree.is_some()
+                && cached.is_some_and(|c| Some(&c.tree) == tree.as_ref());
+
+            if unchanged {
+                report.skipped.push(cat.to_string());
+                continue;
+            }
+
+            let entries = self.scan_category(cat);
+            let rel_dir = self.category_dir(cat)
+                .strip_prefix(&self.memory_root)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| cat.to_string());
+            let snapshot = CategorySnapshot {
+                tree: tree.unwrap_or_default(),
+                count: entries.len(),
+                section: render_section(cat, &rel_dir, &entries),
+            };
+            state.categories.insert(cat.to_string(), snapshot);
+            report.rescanned.push(cat.to_string());
+        }
+
+        let content = self.render_index(&state);
+        let index_path = self.index_path();
+        self.mark_as_internal_write(index_path.clone()).await;
+        tokio::fs::write(&index_path, content).await?;
+
+        if let Some(parent) = state_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&state_path, serde_json::to_string_pretty(&state)?)?;
+
+        Ok(report)
+    }
+
+    /// Tree hash of every category in the working copy (including uncommitted files).
+    /// Uses a private index file so the user's staging area is untouched.
+    fn category_trees(&self) -> HashMap<String, String> {
+        let mut trees = HashMap::new();
+        // Without git every category counts as changed
+        if !self.versioned || !self.memory_root.join(".git").exists() {
+            return trees;
+        }
+
+        let index_file = self.memory_root.join(".git").join("openspore-index");
+        let git = |args: &[&str]| -> Option<String> {
+            let out = Command::new("git")
+                .args(args)
+                .env("GIT_INDEX_FILE", &index_file)
+                .current_dir(&self.memory_root)
+                .output()
+                .ok()?;
+            out.status.success().then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        };
+
+        let mut add_args = vec!["add", "-A", "--"];
+        add_args.extend(self.categories.iter().filter(|c| self.memory_root.join(c).exists()));
+        if git(&add_args).is_none() {
+            return trees;
+        }
+        let Some(root) = git(&["write-tree"]) else { return trees };
+
+        for cat in &self.categories {
+            // An empty category has no tree object
+            let mut hash = git(&["rev-parse", &format!("{}:{}", root, cat)]).unwrap_or_else(|| "empty".to_string());
+            // The context section lists the active session, so a switch invalidates it
+            if *cat == "context" {
+                hash = format!("{}@{}", hash, crate::session::active_session(&self.memory_root).unwrap_or_default());
+            }
+            trees.insert(cat.to_string(), hash);
+        }
+        trees
+    }
+
+    fn scan_category(&self, category: &str) -> Vec<IndexEntry> {
+        let dir = self.category_dir(category);
+        let mut entries: Vec<IndexEntry> = std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "md"))
+            .filter_map(|p| parse_entry(&p))
+            .collect();
+
+        // Newest first
+        entries.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.file.cmp(&b.file)));
+        entries
+    }
+
+    fn render_index(&self, state: &IndexState) -> String {
+        let mut out = String::from("# 🍄 OpenSpore Workspace Index\n\n");
+        out.push_str(&format!(
+            "_Generated {} — do not edit, this file is rewritten automatically._\n\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        ));
+
+        out.push_str("## Overview\n\n| Category | Files |\n|---|---|\n");
+        for cat in &self.categories {
+            let count = state.categories.get(*cat).map(|c| c.count).unwrap_or(0);
+            out.push_str(&format!("| [{}](#{}) | {} |\n", cat, cat, count));
+        }
+        out.push('\n');
+
+        out.push_str("## Quick Links\n\n");
+        for (label, link) in self.quick_links() {
+            out.push_str(&format!("- **{}**: {}\n", label, link));
+        }
+        out.push('\n');
+
+        for cat in &self.categories {
+            if let Some(snapshot) = state.categories.get(*cat) {
+                out.push_str(&snapshot.section);
+            }
+        }
+        out
+    }
+
+    fn quick_links(&self) -> Vec<(&'static str, String)> {
+        let link_or_none = |rel: Option<String>| match rel {
+            Some(r) => format!("[{}]({})", r, r),
+            None => "_none_".to_string(),
+        };
+
+        // Daily journals are named YYYY-MM-DD.md in memory/
+        let newest_journal = std::fs::read_dir(self.memory_root.join("memory"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+            .filter(|n| n.len() == 13 && n.ends_with(".md") && chrono::NaiveDate::parse_from_str(&n[..10], "%Y-%m-%d").is_ok())
+            .max()
+            .map(|n| format!("memory/{}", n));
+
+        let existing = |rel: &str| self.memory_root.join(rel).exists().then(|| rel.to_string());
+        let handoff = existing("context/HANDOFF.md").or_else(|| existing("HANDOFF.md"));
+        let session_summary = self.context_dir().join("session_summary.md")
+            .strip_prefix(&self.memory_root).ok()
+            .map(|p| p.to_string_lossy().to_string())
+            .and_then(|rel| existing(&rel));
+
+        let mut pending: Vec<String> = std::fs::read_dir(self.memory_root.join("autonomy/proposals"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                std::fs::read_to_string(p).is_ok_and(|c| c.lines().any(|l| l.trim() == "status: PENDING"))
+            })
+            .filter_map(|p| p.file_name().map(|n| format!("autonomy/proposals/{}", n.to_string_lossy())))
+            .collect();
+        pending.sort();
+
+        let proposals = if pending.is_empty() {
+            "_none_".to_string()
+        } else {
+            let links: Vec<String> = pending.iter().map(|p| format!("[{}]({})", file_stem(p), p)).collect();
+            format!("{} pending — {}", pending.len(), links.join(", "))
+        };
+
+        vec![
+            ("Newest journal", link_or_none(newest_journal)),
+            ("Session summary", link_or_none(session_summary)),
+            ("Pending proposals", proposals),
+            ("Handoff note", link_or_none(handoff)),
+        ]
+    }
+}
+
+fn file_stem(path: &str) -> String {
+    Path::new(path).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn parse_entry(path: &Path) -> Option<IndexEntry> {
+    let file = path.file_name()?.to_string_lossy().to_string();
+    let content = std::fs::read_to_string(path).ok()?;
+    // INDEX.md is plain text: an encrypted memory is listed by file name only
+    if content.starts_with(crate::SEALED_PREFIX) {
+        return Some(IndexEntry {
+            title: file.trim_end_matches(".md").replace('_', " "),
+            file,
+            date: modified_date(path),
+            tags: String::new(),
+            summary: "🔒 encrypted".to_string(),
+        });
+    }
+
+    let mut date = String::new();
+    let mut tags = String::new();
+    let mut body = content.as_str();
+
+    // YAML frontmatter (as written by save_memory)
+    if let Some(rest) = content.strip_prefix("---\n")
+        && let Some(end) = rest.find("\n---")
+    {
+        for line in rest[..end].lines() {
+            if let Some(v) = line.strip_prefix("created:") {
+                date = v.trim().chars().take(10).collect();
+            } else if let Some(v) = line.strip_prefix("tags:") {
+                tags = v.trim().to_string();
+            }
+        }
+        body = rest[end + 4..].trim_start_matches('-');
+    }
+
+    if date.is_empty() {
+        date = modified_date(path);
+    }
+
+    let mut title = None;
+    let mut summary = String::new();
+    for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if title.is_none() && let Some(h) = line.strip_prefix("# ") {
+            title = Some(h.trim().to_string());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        summary = line.to_string();
+        break;
+    }
+
+    Some(IndexEntry {
+        title: title.unwrap_or_else(|| file.trim_end_matches(".md").replace('_', " ")),
+        file,
+        date,
+        tags,
+        summary: openspore_core::fmt_utils::truncate_graphemes(&summary, SUMMARY_MAX_CHARS),
+    })
+}
+
+fn modified_date(path: &Path) -> String {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| chrono::DateTime::<chrono::Local>::from(t).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Escape characters that would break a markdown table cell
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+fn render_section(category: &str, rel_dir: &str, entries: &[IndexEntry]) -> String {
+    let mut out = format!("## {}\n\n", category);
+    if entries.is_empty() {
+        out.push_str("_empty_\n\n");
+        return out;
+    }
+
+    out.push_str("| Title | Date | Tags | Summary |\n|---|---|---|---|\n");
+    for e in entries.iter().take(MAX_ROWS_PER_CATEGORY) {
+        out.push_str(&format!(
+            "| [{}]({}/{}) | {} | {} | {} |\n",
+            cell(&e.title), rel_dir, e.file, e.date, cell(&e.tags), cell(&e.summary)
+        ));
+    }
+    if entries.len() > MAX_ROWS_PER_CATEGORY {
+        out.push_str(&format!("\n_…and {} more_\n", entries.len() - MAX_ROWS_PER_CATEGORY));
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    async fn seeded(root: &Path) -> MemorySystem {
+        let config = AppConfig { project_root: root.to_path_buf(), ..Default::default() };
+        let memory = MemorySystem::new(&AppState::new(config));
+        memory.ensure_structure().await.unwrap();
+        memory.init_git();
+        std::fs::write(
+            memory.memory_root.join("knowledge/rust_tips.md"),
+            "---\ncreated: 2026-03-01T10:00:00Z\ntags: rust, tips\n---\n# Rust tips\n\nPrefer iterators | avoid indexing.\n",
+        ).unwrap();
+        std::fs::write(memory.memory_root.join("preferences/editor.md"), "Uses helix with a light theme.\n").unwrap();
+        memory
+    }
+
+    fn sorted(mut names: Vec<String>) -> Vec<String> {
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn index_lists_every_category() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = seeded(dir.path()).await;
+
+        let report = memory.regenerate_index(false).await.unwrap();
+        let index = std::fs::read_to_string(&report.path).unwrap();
+
+        assert_eq!(report.rescanned.len(), memory.categories.len());
+        assert!(index.contains("| [knowledge](#knowledge) | 1 |"));
+        assert!(index.contains("| [Rust tips](knowledge/rust_tips.md) | 2026-03-01 | rust, tips | Prefer iterators \\| avoid indexing. |"));
+        assert!(index.contains("| [editor](preferences/editor.md) |"), "untitled notes are listed by file name");
+        assert!(index.contains("## identity\n\n_empty_"));
+    }
+
+    #[tokio::test]
+    async fn unchanged_categories_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = seeded(dir.path()).await;
+        assert!(memory.versioned, "git is needed for the tree hashes");
+
+        memory.regenerate_index(false).await.unwrap();
+        let again = memory.regenerate_index(false).await.unwrap();
+        assert!(again.rescanned.is_empty(), "rescanned {:?}", again.rescanned);
+
+        std::fs::write(memory.memory_root.join("knowledge/borrowing.md"), "# Borrowing\n\nOne writer or many readers.\n").unwrap();
+        let after_edit = memory.regenerate_index(false).await.unwrap();
+        assert_eq!(after_edit.rescanned, ["knowledge"]);
+        assert!(std::fs::read_to_string(&after_edit.path).unwrap().contains("[Borrowing](knowledge/borrowing.md)"));
+
+        let forced = memory.regenerate_index(true).await.unwrap();
+        assert_eq!(sorted(forced.rescanned), sorted(memory.categories.iter().map(|c| c.to_string()).collect()));
+    }
+}
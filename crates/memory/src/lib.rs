@@ -2,27 +2,78 @@
 //!
 //! This module is organized into:
 //! - types: Core data structures (MemoryItem, SearchResult)
-//! - git: Version control operations
-//! - structure: Directory initialization
+//! - git: Version control operations (tarball snapshots when git is missing)
+//! - structure: Directory initialization (and the sample pipeline)
 //! - storage: Saving memories and journal entries
 //! - retrieval: Searching and retrieving memories
+//! - search_index: Persistent inverted keyword index behind search()
+//! - embeddings: Optional semantic search over memory files
+//! - index: Human-readable workspace/INDEX.md generation
 //! - context: Context management (already modular)
+//! - session: Named sessions with isolated working context
+//! - templates: Note templates (decision records, meeting notes...)
+//! - glossary: Canonical terms and their aliases (identity/GLOSSARY.md)
+//! - contacts: People the agent may message (identity/CONTACTS.md)
+//! - expiry: TTL of learned memories and pruning of expired files
+//! - crypto: Optional encryption of memory files at rest
+//! - tombstones: Deletion records that keep removed files from resurrecting
+//! - conflicts: Sync conflicts kept as two labeled versions until resolved
+//! - audit: Hash-chained log of privileged tool calls
+//! - usefulness: Injected/cited counters of memories, folded into search ranking
+//! - consolidation: Old exchanges folded into daily digests
+//! - failures: Post-mortem bundles of failed cycles
+//! - housekeeping: Size and age limits of screenshots, downloads and partial writes
 
 mod types;
 mod git;
 mod structure;
 mod storage;
 mod retrieval;
+mod search_index;
+mod embeddings;
+mod index;
+mod expiry;
+mod crypto;
+mod tombstones;
+mod conflicts;
+mod audit;
+mod usefulness;
+mod consolidation;
+mod failures;
+mod housekeeping;
 
 pub mod context;
+pub mod session;
+pub mod templates;
+pub mod glossary;
+pub mod contacts;
 
 // Re-export public types
-pub use types::{MemoryItem, SearchResult};
+pub use types::{parse_frontmatter, MemoryItem, SearchResult};
+pub use index::{IndexReport, INDEX_FILE};
+pub use session::{SessionInfo, SessionManager};
+pub use search_index::{SearchIndex, SEARCH_INDEX_DIR};
+pub use embeddings::Embeddings;
+pub use templates::{Template, TemplateNote};
+pub use glossary::{Glossary, GlossaryEntry, GLOSSARY_FILE};
+pub use contacts::{Contact, ContactBook, ContactMatch, CONTACTS_FILE};
+pub use expiry::PruneReport;
+pub use crypto::{MemoryCipher, SEALED_PREFIX};
+pub use conflicts::{conflict_document, conflict_versions, is_conflicted, merge_texts, ConflictVersions, Resolution, MERGE_DRIVER};
+pub use audit::{redact, sha256_hex, AuditEntry, AuditEvent, AuditOutcome, AuditVerification, AUDIT_DIR};
+pub use failures::{failure_patterns, FailureBundle, FailureCapture, FailureKind, FailureRecord, ToolInvocation, FAILURES_DIR};
+pub use housekeeping::{DirUsage, HousekeepingReport, StorageReport, StoredFile, DOWNLOADS_DIR, EXPORTS_DIR, PARTIAL_WRITES_DIR, PARTIAL_WRITES_MAX_AGE, PINS_FILE, SCREENSHOTS_DIR};
+pub use structure::PIPELINES_DIR;
+pub use consolidation::{ConsolidationReport, DayDigest, DigestWriter, DIGESTS_DIR};
+pub use usefulness::{cites, memory_id, MemoryUsage, UsageHit, UsageStats};
+pub use git::{VersioningError, MAX_SNAPSHOTS, SNAPSHOTS_DIR};
+pub use tombstones::{Tombstone, TombstoneSet, TOMBSTONES_FILE, TOMBSTONE_RETENTION_DAYS};
 
 use openspore_core::state::AppState;
 use std::path::{Path, PathBuf};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use tokio::sync::Mutex;
 
 /// Exact port of opensporejs/src/memory.js MemorySystem class
@@ -34,6 +85,22 @@ pub struct MemorySystem {
     pub allowed_extensions: Vec<&'static str>,
     pub ignore_dirs: HashSet<&'static str>,
     pub recently_written: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Lazily loaded keyword index (shared by clones)
+    pub search_index: Arc<Mutex<Option<SearchIndex>>>,
+    /// Set while a background load/build of the index is running
+    index_warming: Arc<AtomicBool>,
+    /// Semantic retrieval, None unless EMBEDDING_MODEL is configured
+    pub embeddings: Option<Arc<Embeddings>>,
+    /// Lifetime of learned memories (None: kept forever)
+    pub learned_ttl_days: Option<u32>,
+    /// New memories are written encrypted (ENCRYPT_MEMORY)
+    pub encrypt_memory: bool,
+    /// Present whenever OPENSPORE_MEM_KEY is set, so sealed files stay readable
+    pub cipher: Option<Arc<MemoryCipher>>,
+    /// Weight of usefulness in search ranking (USEFULNESS_WEIGHT, 0 disables)
+    pub usefulness_weight: f64,
+    /// git is installed: commits version the workspace (otherwise snapshots do)
+    pub versioned: bool,
 }
 
 impl MemorySystem {
@@ -42,7 +109,7 @@ impl MemorySystem {
         let memory_root = root.join("workspace");
         let categories = vec!["preferences", "identity", "knowledge", "context", "memory"];
 
-        let mem = Self {
+        let mut mem = Self {
             project_root: root,
             memory_root,
             categories,
@@ -51,10 +118,38 @@ impl MemorySystem {
                 ".js", ".ts", ".py", ".rs", ".go", ".c", ".cpp", ".h", ".sh"
             ],
             ignore_dirs: HashSet::from([
-                "node_modules", "target", ".git", "dist", "build", "coverage", "__pycache__", ".next", "bin", "lib"
+                "node_modules", "target", ".git", "dist", "build", "coverage", "__pycache__", ".next", "bin", "lib",
+                // Archived exchanges and expired memories stay out of search
+                ".archive",
+                // Pid records of running processes
+                ".runtime",
+                // Audit trail of tool calls (never searched or fed to the model)
+                "audit",
+                // Failure post-mortems (prompts and tool outputs of failed cycles)
+                "debug",
+                // Conversations exported from the TUI (copies of saved exchanges)
+                "exports",
+                // Workspace snapshots when git is not installed
+                SNAPSHOTS_DIR
             ]),
             recently_written: Arc::new(Mutex::new(HashSet::new())),
+            search_index: Arc::new(Mutex::new(None)),
+            index_warming: Arc::new(AtomicBool::new(false)),
+            embeddings: Embeddings::from_config(&state.config).map(Arc::new),
+            learned_ttl_days: Some(state.config.learned_ttl_days.unwrap_or(expiry::DEFAULT_LEARNED_TTL_DAYS)).filter(|d| *d > 0),
+            encrypt_memory: state.config.encrypt_memory.unwrap_or(false),
+            cipher: None,
+            usefulness_weight: state.config.usefulness_weight(),
+            versioned: state.capabilities.git,
         };
+        if let Some(key) = state.config.mem_key.as_deref().filter(|k| !k.is_empty()) {
+            match MemoryCipher::load(&mem.memory_root, key) {
+                Ok(cipher) => mem.cipher = Some(Arc::new(cipher)),
+                Err(e) => tracing::error!("🔒 Memory encryption unavailable: {}", e),
+            }
+        } else if mem.encrypt_memory {
+            tracing::error!("🔒 ENCRYPT_MEMORY is on but OPENSPORE_MEM_KEY is not set: memories will not be saved");
+        }
         mem.init_git();
         mem
     }
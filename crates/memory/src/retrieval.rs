@@ -3,33 +3,40 @@ use walkdir::WalkDir;
 use anyhow::{Result, Context};
 use std::path::Path;
 use chrono::{DateTime, Local, Duration, NaiveDateTime, TimeZone};
-use std::fs;
 
 impl MemorySystem {
     /// Get all memories from a category (lines 211-228 in JS)
     pub fn get_memories(&self, category: &str) -> Vec<MemoryItem> {
-        let dir = self.memory_root.join(category);
+        self.read_memory_dir(&self.category_dir(category))
+    }
 
+    /// Memory files of one directory, sorted by filename
+    fn read_memory_dir(&self, dir: &Path) -> Vec<MemoryItem> {
         if !dir.exists() {
             return vec![];
         }
 
+        let tombstones = self.tombstone_set();
         let mut memories = vec![];
-        if let Ok(entries) = std::fs::read_dir(&dir) {
+        if let Ok(entries) = std::fs::read_dir(dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
                 // Filter out special files
                 let filename = path.file_name().unwrap_or_default().to_string_lossy();
-                if filename == "LOGS.md" || filename == "session_summary.md" {
+                // The glossary reaches the prompt as an excerpt (ContextAssembler),
+                // contacts only through the contacts skill
+                if filename == "LOGS.md" || filename == "session_summary.md" || filename == "GLOSSARY.md" || filename == "CONTACTS.md" {
                     continue;
                 }
 
                 if path.extension().map(|e| e == "md").unwrap_or(false) {
-                    if let Ok(content) = std::fs::read_to_string(&path) {
-                        memories.push(MemoryItem {
-                            filename: filename.to_string(),
-                            content,
-                        });
+                    match self.read_text(&path) {
+                        Ok(content) if tombstones.buries(&path, &content) => {}
+                        Ok(content) => memories.push(MemoryItem::new(filename.to_string(), content)),
+                        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                            tracing::warn!("Skipping {}: {}", path.display(), e);
+                        }
+                        Err(_) => {}
                     }
                 }
             }
@@ -40,6 +47,67 @@ impl MemorySystem {
         memories
     }
 
+    /// Exchanges of every session produced by a model (substring of the `model`
+    /// slug, case-insensitive) and/or created in [since, until). Exchanges saved
+    /// before models were recorded only show up without a model filter.
+    pub fn audit_exchanges(&self, model: Option<&str>, since: Option<DateTime<Local>>, until: Option<DateTime<Local>>) -> Vec<(String, MemoryItem)> {
+        let context = self.memory_root.join("context");
+        let mut dirs = vec![(crate::session::DEFAULT_SESSION.to_string(), context.clone())];
+        if let Ok(entries) = std::fs::read_dir(context.join("sessions")) {
+            let mut sessions: Vec<_> = entries.flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+                .collect();
+            sessions.sort();
+            dirs.extend(sessions);
+        }
+
+        let model = model.map(str::to_lowercase);
+        let mut found = Vec::new();
+        for (session, dir) in dirs {
+            for item in self.read_memory_dir(&dir) {
+                if item.meta("type") != Some("exchange") && !item.filename.to_lowercase().starts_with("exchange") {
+                    continue;
+                }
+                if let Some(model) = &model
+                    && !item.meta("model").is_some_and(|m| m.to_lowercase().contains(model.as_str()))
+                {
+                    continue;
+                }
+                if since.is_some() || until.is_some() {
+                    let Some(created) = item.meta("created").and_then(|c| DateTime::parse_from_rfc3339(c).ok()) else { continue };
+                    let created = created.with_timezone(&Local);
+                    if since.is_some_and(|s| created < s) || until.is_some_and(|u| created >= u) {
+                        continue;
+                    }
+                }
+                found.push((session.clone(), item));
+            }
+        }
+        found
+    }
+
+    /// Memories of a category carrying the given frontmatter tags:
+    /// all of them when `match_all`, any of them otherwise (case-insensitive)
+    pub fn get_memories_by_tags(&self, category: &str, tags: &[&str], match_all: bool) -> Vec<MemoryItem> {
+        let wanted: Vec<String> = tags.iter()
+            .map(|t| normalize_tag(t))
+            .filter(|t| !t.is_empty())
+            .collect();
+        if wanted.is_empty() {
+            return vec![];
+        }
+
+        self.get_memories(category)
+            .into_iter()
+            .filter(|item| {
+                let have = item.meta("tags").map(split_tags).unwrap_or_default();
+                let has = |t: &String| have.contains(t);
+                if match_all { wanted.iter().all(has) } else { wanted.iter().any(has) }
+            })
+            .collect()
+    }
+
     /// Retrieve raw logs from the last N hours
     pub fn get_recent_logs(&self, hours: i64) -> Result<String> {
         let cutoff = Local::now() - Duration::hours(hours);
@@ -48,12 +116,12 @@ impl MemorySystem {
 
     /// Retrieve raw logs since a specific timestamp
     pub fn get_logs_since(&self, cutoff: DateTime<Local>) -> Result<String> {
-        let logs_path = self.memory_root.join("context").join("LOGS.md");
+        let logs_path = self.context_dir().join("LOGS.md");
         if !logs_path.exists() {
             return Ok(String::from("No log file found."));
         }
 
-        let content = fs::read_to_string(&logs_path)
+        let content = self.read_text(&logs_path)
             .context("Failed to read LOGS.md")?;
 
         // Regex to match [YYYY-MM-DD HH:MM:SS]
@@ -65,13 +133,13 @@ impl MemorySystem {
         let mut is_recent = false;
 
         for line in lines {
-            if let Some(caps) = re.captures(line) {
-                if let Ok(ts_naive) = NaiveDateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S") {
-                    let ts: DateTime<Local> = Local.from_local_datetime(&ts_naive).single()
-                        .unwrap_or_else(|| Local::now()); // Fallback on ambiguity
+            if let Some(caps) = re.captures(line)
+                && let Ok(ts_naive) = NaiveDateTime::parse_from_str(&caps[1], "%Y-%m-%d %H:%M:%S")
+            {
+                let ts: DateTime<Local> = Local.from_local_datetime(&ts_naive).single()
+                    .unwrap_or_else(Local::now); // Fallback on ambiguity
 
-                    is_recent = ts >= cutoff;
-                }
+                is_recent = ts >= cutoff;
             }
 
             if is_recent {
@@ -82,7 +150,7 @@ impl MemorySystem {
         Ok(relevant_lines.join("\n"))
     }
 
-    /// Wrapper for semantic search (placeholdered by keyword search for now)
+    /// Wrapper for search() that swallows errors (context assembly)
     pub async fn search_memories(&self, query: &str, limit: usize) -> Vec<SearchResult> {
         self.search(query, limit).await.unwrap_or_default()
     }
@@ -93,27 +161,35 @@ impl MemorySystem {
             return Ok(vec![]);
         }
 
-        let keywords: Vec<String> = query
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
-            .collect::<String>()
-            .split_whitespace()
-            .filter(|w| w.len() > 2)
-            .map(|s| s.to_string())
-            .collect();
-
+        let mut keywords = query_keywords(query);
+        // "the meridian project" also finds notes that say "Meridian" or "MRD"
+        for word in query_keywords(&self.glossary().query_expansions(query).join(" ")) {
+            if !keywords.contains(&word) {
+                keywords.push(word);
+            }
+        }
         if keywords.is_empty() {
             return Ok(vec![]);
         }
 
-        let mut results: Vec<SearchResult> = vec![];
-
         // Ensure base_path exists
         if !base_path.exists() {
              return Err(anyhow::anyhow!("Search path does not exist: {}", base_path.display()));
         }
 
+        // Inside the project the persistent index answers; other paths are walked
+        if base_path.starts_with(&self.project_root) {
+            return self.search_indexed(&keywords, base_path, limit).await;
+        }
+
+        self.search_walk(&keywords, base_path, limit)
+    }
+
+    /// Full scan of a directory tree (outside the project root, or while the index is cold)
+    pub(crate) fn search_walk(&self, keywords: &[String], base_path: &Path, limit: usize) -> Result<Vec<SearchResult>> {
+        let tombstones = self.tombstone_set();
+        let mut results: Vec<SearchResult> = vec![];
+
         for entry in WalkDir::new(base_path)
             .into_iter()
             .filter_map(|e| e.ok())
@@ -121,18 +197,22 @@ impl MemorySystem {
             let path = entry.path();
 
             // Skip ignored directories (only check leaf name)
-            if path.is_dir() {
-                if let Some(name) = path.file_name() {
-                    if self.ignore_dirs.contains(name.to_string_lossy().as_ref()) {
-                        continue;
-                    }
-                }
+            if path.is_dir()
+                && let Some(name) = path.file_name()
+                    && self.ignore_dirs.contains(name.to_string_lossy().as_ref())
+            {
+                continue;
             }
 
             if !path.is_file() {
                 continue;
             }
 
+            // The generated index only repeats other files
+            if path == self.index_path() {
+                continue;
+            }
+
             // Check extension
             let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy().to_lowercase())).unwrap_or_default();
             if !self.allowed_extensions.contains(&ext.as_str()) {
@@ -140,18 +220,21 @@ impl MemorySystem {
             }
 
             // Skip large files (100KB)
-            if let Ok(meta) = std::fs::metadata(path) {
-                if meta.len() > 100 * 1024 {
-                    continue;
-                }
+            if let Ok(meta) = std::fs::metadata(path)
+                && meta.len() > 100 * 1024
+            {
+                continue;
             }
 
-            if let Ok(content) = std::fs::read_to_string(path) {
+            if let Ok(content) = self.read_text(path) {
+                if tombstones.buries(path, &content) {
+                    continue;
+                }
                 let lower_content = content.to_lowercase();
                 let filename = path.file_name().unwrap().to_string_lossy().to_lowercase();
 
                 let mut score = 0;
-                for kw in &keywords {
+                for kw in keywords {
                     if filename.contains(kw) {
                         score += 50; // Boost filename matches
                     }
@@ -159,15 +242,15 @@ impl MemorySystem {
                 }
 
                 // Recency Boost (Add weight to more recent events)
-                if let Ok(meta) = std::fs::metadata(path) {
-                    if let Ok(modified) = meta.modified() {
-                        let elapsed = modified.elapsed().unwrap_or_default().as_secs();
-                        let hours_since = elapsed / 3600;
-                        if hours_since < 2 {
-                            score += 50; // Last 2 hours: Strong boost
-                        } else if hours_since < 24 {
-                            score += 20; // Last 24 hours: Moderate boost
-                        }
+                if let Ok(meta) = std::fs::metadata(path)
+                    && let Ok(modified) = meta.modified()
+                {
+                    let elapsed = modified.elapsed().unwrap_or_default().as_secs();
+                    let hours_since = elapsed / 3600;
+                    if hours_since < 2 {
+                        score += 50; // Last 2 hours: Strong boost
+                    } else if hours_since < 24 {
+                        score += 20; // Last 24 hours: Moderate boost
                     }
                 }
 
@@ -182,12 +265,169 @@ impl MemorySystem {
             }
         }
 
-        results.sort_by(|a, b| b.score.cmp(&a.score));
+        results.sort_by_key(|r| std::cmp::Reverse(r.score));
         Ok(results.into_iter().take(limit).collect())
     }
 
-    /// Search across workspace (lines 114-152 in JS)
+    /// Search across workspace (lines 114-152 in JS).
+    /// With an embedding index, memories ranked by cosine similarity come first and
+    /// keyword hits fill in; otherwise (or if the embeddings API fails) keywords only.
+    /// Each ranking is then adjusted by how often its memories get cited.
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-        self.search_in_path(query, &self.project_root, limit).await
+        let stats = (self.usefulness_weight > 0.0).then(|| self.usage_stats()).filter(|s| !s.memories.is_empty());
+        let pool = if stats.is_some() { limit * crate::usefulness::RERANK_POOL } else { limit };
+        let rerank = |mut results: Vec<SearchResult>| {
+            if let Some(stats) = &stats {
+                stats.rerank(&mut results, self.usefulness_weight);
+            }
+            results.truncate(limit);
+            results
+        };
+
+        if !self.embeddings_enabled() {
+            return self.search_in_path(query, &self.project_root, pool).await.map(rerank);
+        }
+
+        let (keyword, semantic) = tokio::join!(
+            self.search_in_path(query, &self.project_root, pool),
+            self.search_semantic(query, pool)
+        );
+        let semantic = semantic.unwrap_or_else(|e| {
+            tracing::warn!("🧭 Semantic search unavailable, using keywords only: {}", e);
+            vec![]
+        });
+        Ok(crate::embeddings::merge_results(rerank(semantic), rerank(keyword?), limit))
+    }
+}
+
+/// Lowercased alphanumeric words longer than 2 characters
+fn query_keywords(query: &str) -> Vec<String> {
+    query
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .filter(|w| w.len() > 2)
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// A `tags:` value: "a, b", "[a, b]" or quoted entries
+fn split_tags(value: &str) -> Vec<String> {
+    value.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(normalize_tag)
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_matches(|c| c == '"' || c == '\'' || c == '#').trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    async fn memory(root: &Path) -> MemorySystem {
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: root.to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        memory
+    }
+
+    /// An exchange written by hand, the way older versions (or other sessions) left it
+    fn exchange(dir: &Path, name: &str, frontmatter: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(name), format!("---\n{}---\n\n# Exchange\n\n**User**: hi\n\n**Assistant**: hello\n", frontmatter)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn metadata_round_trips_through_the_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        let metadata = [
+            ("model", "anthropic/claude-sonnet".to_string()),
+            ("provider", "openrouter".to_string()),
+            ("cycle", "20261016104836123".to_string()),
+            ("prompt_tokens", "1200".to_string()),
+            ("note", "two\nlines ".to_string()),
+            ("empty", "  ".to_string()),
+        ];
+        let path = memory
+            .save_memory_with_metadata("context", "Exchange_20261016_104836", "**User**: q\n\n**Assistant**: a", vec!["conversation".into()], Some("exchange"), None, &metadata)
+            .await.unwrap().unwrap();
+
+        let item = memory.get_memories("context").into_iter().find(|m| path.ends_with(&m.filename)).unwrap();
+        assert_eq!(item.meta("type"), Some("exchange"));
+        assert_eq!(item.meta("model"), Some("anthropic/claude-sonnet"));
+        assert_eq!(item.meta("provider"), Some("openrouter"));
+        assert_eq!(item.meta("cycle"), Some("20261016104836123"));
+        assert_eq!(item.meta("prompt_tokens"), Some("1200"));
+        assert_eq!(item.meta("note"), Some("two lines"), "values stay on one line");
+        assert_eq!(item.meta("empty"), None);
+        assert_eq!(item.meta("tags"), Some("conversation"));
+        assert!(item.meta("created").is_some_and(|c| DateTime::parse_from_rfc3339(c).is_ok()));
+        assert!(item.content.contains("**Assistant**: a"));
+    }
+
+    #[tokio::test]
+    async fn audit_filters_by_model_and_date_across_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = memory(dir.path()).await;
+        let context = memory.memory_root.join("context");
+        exchange(&context, "exchange_a.md", "type: exchange\ncreated: 2026-10-01T09:00:00+00:00\nmodel: anthropic/Claude-Sonnet\n");
+        exchange(&context, "exchange_b.md", "type: exchange\ncreated: 2026-10-10T09:00:00+00:00\nmodel: openai/gpt-5\n");
+        exchange(&context, "exchange_legacy.md", "type: exchange\ncreated: 2026-09-01T09:00:00+00:00\ntags: conversation\n");
+        exchange(&context, "notes.md", "type: knowledge\ncreated: 2026-10-05T09:00:00+00:00\nmodel: openai/gpt-5\n");
+        exchange(&context.join("sessions").join("work"), "exchange_c.md", "type: exchange\ncreated: 2026-10-12T09:00:00+00:00\nmodel: anthropic/claude-opus\n");
+
+        let names = |found: Vec<(String, MemoryItem)>| -> Vec<String> {
+            found.into_iter().map(|(session, m)| format!("{}/{}", session, m.filename)).collect()
+        };
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Local);
+
+        assert_eq!(
+            names(memory.audit_exchanges(None, None, None)),
+            ["default/exchange_a.md", "default/exchange_b.md", "default/exchange_legacy.md", "work/exchange_c.md"]
+        );
+        assert_eq!(names(memory.audit_exchanges(Some("CLAUDE"), None, None)), ["default/exchange_a.md", "work/exchange_c.md"]);
+        assert_eq!(
+            names(memory.audit_exchanges(None, Some(at("2026-10-01T09:00:00+00:00")), Some(at("2026-10-12T09:00:00+00:00")))),
+            ["default/exchange_a.md", "default/exchange_b.md"],
+            "since is inclusive, until exclusive"
+        );
+        assert_eq!(names(memory.audit_exchanges(Some("claude"), Some(at("2026-10-05T00:00:00+00:00")), None)), ["work/exchange_c.md"]);
+        assert!(memory.audit_exchanges(Some("gemini"), None, None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_ranks_cited_memories_above_ignored_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig { project_root: dir.path().to_path_buf(), usefulness_weight: Some(0.5), ..Default::default() };
+        let memory = MemorySystem::new(&AppState::new(config));
+        memory.ensure_structure().await.unwrap();
+        let knowledge = memory.category_dir("knowledge");
+        // The first matches the query twice as often, the second is the one answers cite
+        let (ignored, cited) = (knowledge.join("kayak_noise.md"), knowledge.join("kayak_routes.md"));
+        std::fs::write(&ignored, "---\nid: noise\n---\nkayak kayak kayak kayak").unwrap();
+        std::fs::write(&cited, "---\nid: routes\n---\nkayak kayak").unwrap();
+        let order = |results: Vec<SearchResult>| results.into_iter().map(|r| r.path).collect::<Vec<_>>();
+
+        assert_eq!(order(memory.search("kayak", 2).await.unwrap()), vec![ignored.clone(), cited.clone()]);
+
+        let now = chrono::Local::now();
+        let mut hits = Vec::new();
+        for _ in 0..20 {
+            hits.push(crate::UsageHit { id: "noise".into(), path: ignored.clone(), cited: false, at: now });
+            hits.push(crate::UsageHit { id: "routes".into(), path: cited.clone(), cited: false, at: now });
+            hits.push(crate::UsageHit { id: "routes".into(), path: cited.clone(), cited: true, at: now });
+        }
+        memory.record_usage(&hits).unwrap();
+        assert_eq!(order(memory.search("kayak", 2).await.unwrap()), vec![cited, ignored]);
     }
 }
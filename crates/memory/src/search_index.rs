@@ -0,0 +1,598 @@
+use crate::MemorySystem;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+/// Directory (inside the workspace) holding the persisted search index
+pub const SEARCH_INDEX_DIR: &str = ".index";
+const SEARCH_INDEX_FILE: &str = "search.bin";
+const SEARCH_INDEX_VERSION: u32 = 1;
+
+/// Incremental updates are flushed to disk at most this often
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_FILE_SIZE: u64 = 100 * 1024;
+const MAX_TERM_LEN: usize = 40;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    path: PathBuf,
+    /// mtime in seconds since the epoch
+    modified: u64,
+    /// Lowercased file name (filename matches are boosted)
+    name: String,
+    /// (term id, occurrences) sorted by term id; counts saturate since scores cap at 20
+    terms: Vec<(u32, u8)>,
+}
+
+impl IndexedFile {
+    fn count(&self, term: u32) -> u32 {
+        self.terms.binary_search_by_key(&term, |(t, _)| *t).map(|i| self.terms[i].1 as u32).unwrap_or(0)
+    }
+}
+
+/// Inverted keyword index over the project root
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    /// Term id -> term
+    vocab: Vec<String>,
+    /// File id -> file (None once removed; ids are reused)
+    slots: Vec<Option<IndexedFile>>,
+    #[serde(skip)]
+    term_ids: HashMap<String, u32>,
+    #[serde(skip)]
+    by_path: HashMap<PathBuf, u32>,
+    /// Term id -> file ids. Rebuilt on load; entries of removed or re-indexed
+    /// files may linger and are ignored at query time.
+    #[serde(skip)]
+    postings: Vec<Vec<u32>>,
+    /// Empty slots available for reuse
+    #[serde(skip)]
+    free: Vec<u32>,
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    last_saved: Option<Instant>,
+}
+
+fn modified_secs(meta: &std::fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn tokenize(content: &str) -> HashMap<String, u32> {
+    let mut terms = HashMap::new();
+    for token in content.split(|c: char| !c.is_alphanumeric()) {
+        if token.len() <= 2 || token.len() > MAX_TERM_LEN {
+            continue;
+        }
+        *terms.entry(token.to_lowercase()).or_insert(0) += 1;
+    }
+    terms
+}
+
+/// Tokenized file, not yet attached to an index
+struct ScannedFile {
+    path: PathBuf,
+    modified: u64,
+    name: String,
+    terms: HashMap<String, u32>,
+}
+
+impl SearchIndex {
+    pub fn len(&self) -> usize {
+        self.by_path.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_path.is_empty()
+    }
+
+    fn rebuild_lookups(&mut self) {
+        self.term_ids = self.vocab.iter().enumerate().map(|(i, t)| (t.clone(), i as u32)).collect();
+        self.by_path.clear();
+        self.postings = vec![Vec::new(); self.vocab.len()];
+        self.free.clear();
+        for (id, slot) in self.slots.iter().enumerate() {
+            let Some(file) = slot else {
+                self.free.push(id as u32);
+                continue;
+            };
+            self.by_path.insert(file.path.clone(), id as u32);
+            for (term, _) in &file.terms {
+                self.postings[*term as usize].push(id as u32);
+            }
+        }
+    }
+
+    fn term_id(&mut self, term: String) -> u32 {
+        if let Some(id) = self.term_ids.get(&term) {
+            return *id;
+        }
+        let id = self.vocab.len() as u32;
+        self.vocab.push(term.clone());
+        self.term_ids.insert(term, id);
+        self.postings.push(Vec::new());
+        id
+    }
+
+    fn modified_of(&self, path: &Path) -> Option<u64> {
+        let id = self.by_path.get(path)?;
+        self.slots[*id as usize].as_ref().map(|f| f.modified)
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(id) = self.by_path.remove(path) {
+            self.slots[id as usize] = None;
+            self.free.push(id);
+            self.dirty = true;
+        }
+    }
+
+    /// Remove `path` and, for a directory, every file below it
+    fn remove_under(&mut self, path: &Path) -> usize {
+        let gone: Vec<PathBuf> = self.by_path.keys().filter(|p| p.starts_with(path)).cloned().collect();
+        for p in &gone {
+            self.remove(p);
+        }
+        gone.len()
+    }
+
+    fn insert(&mut self, scanned: ScannedFile) {
+        let id = match self.by_path.get(&scanned.path) {
+            Some(id) => *id,
+            None => match self.free.pop() {
+                Some(free) => free,
+                None => {
+                    self.slots.push(None);
+                    (self.slots.len() - 1) as u32
+                }
+            },
+        };
+
+        let mut terms: Vec<(u32, u8)> = scanned.terms.into_iter()
+            .map(|(term, count)| (self.term_id(term), count.min(u8::MAX as u32) as u8))
+            .collect();
+        terms.sort_unstable_by_key(|(t, _)| *t);
+        for (term, _) in &terms {
+            let list = &mut self.postings[*term as usize];
+            if list.last() != Some(&id) {
+                list.push(id);
+            }
+        }
+
+        self.by_path.insert(scanned.path.clone(), id);
+        self.slots[id as usize] = Some(IndexedFile {
+            path: scanned.path,
+            modified: scanned.modified,
+            name: scanned.name,
+            terms,
+        });
+        self.dirty = true;
+    }
+
+    /// Score files for the given keywords (same weights as the walking search)
+    fn query(&self, keywords: &[String], base_path: &Path) -> Vec<(PathBuf, usize)> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+        // Substring semantics: a keyword matches every term that contains it
+        let matching_terms: Vec<Vec<u32>> = keywords.iter()
+            .map(|kw| {
+                self.vocab.iter().enumerate()
+                    .filter(|(_, t)| t.contains(kw.as_str()))
+                    .map(|(i, _)| i as u32)
+                    .collect()
+            })
+            .collect();
+
+        let mut candidates: HashSet<u32> = HashSet::new();
+        for terms in &matching_terms {
+            for term in terms {
+                candidates.extend(self.postings[*term as usize].iter().copied());
+            }
+        }
+        for (id, slot) in self.slots.iter().enumerate() {
+            if let Some(file) = slot
+                && keywords.iter().any(|kw| file.name.contains(kw.as_str()))
+            {
+                candidates.insert(id as u32);
+            }
+        }
+
+        let mut scored: Vec<(PathBuf, usize)> = candidates.into_iter()
+            .filter_map(|id| self.slots[id as usize].as_ref())
+            .filter(|file| file.path.starts_with(base_path))
+            .filter_map(|file| {
+                let mut score = 0;
+                for (kw, terms) in keywords.iter().zip(&matching_terms) {
+                    if file.name.contains(kw.as_str()) {
+                        score += 50; // Boost filename matches
+                    }
+                    let hits: u32 = terms.iter().map(|t| file.count(*t)).sum();
+                    score += (hits as usize).min(20);
+                }
+
+                // Recency Boost (Add weight to more recent events)
+                let hours_since = now.saturating_sub(file.modified) / 3600;
+                if hours_since < 2 {
+                    score += 50;
+                } else if hours_since < 24 {
+                    score += 20;
+                }
+
+                (score > 0).then(|| (file.path.clone(), score))
+            })
+            .collect();
+
+        scored.sort_by_key(|(_, s)| std::cmp::Reverse(*s));
+        scored
+    }
+}
+
+impl MemorySystem {
+    pub fn search_index_path(&self) -> PathBuf {
+        self.memory_root.join(SEARCH_INDEX_DIR).join(SEARCH_INDEX_FILE)
+    }
+
+    /// Same filters as the walking search: allowed extension and < 100KB
+    fn index_entry_for(&self, path: &Path) -> Option<ScannedFile> {
+        if path == self.index_path() {
+            return None;
+        }
+        let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy().to_lowercase())).unwrap_or_default();
+        if !self.allowed_extensions.contains(&ext.as_str()) {
+            return None;
+        }
+        let meta = std::fs::metadata(path).ok()?;
+        if !meta.is_file() || meta.len() > MAX_FILE_SIZE {
+            return None;
+        }
+        let content = self.read_text(path).ok()?;
+        Some(ScannedFile {
+            path: path.to_path_buf(),
+            modified: modified_secs(&meta),
+            name: path.file_name()?.to_string_lossy().to_lowercase(),
+            terms: tokenize(&content),
+        })
+    }
+
+    fn walk_indexable<'a>(&'a self, root: &Path) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
+        WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| {
+                !(e.file_type().is_dir()
+                    && e.depth() > 0
+                    && (self.ignore_dirs.contains(e.file_name().to_string_lossy().as_ref())
+                        || e.file_name() == SEARCH_INDEX_DIR))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+    }
+
+    fn build_search_index(&self) -> SearchIndex {
+        let mut index = SearchIndex { version: SEARCH_INDEX_VERSION, ..Default::default() };
+        for entry in self.walk_indexable(&self.project_root) {
+            if let Some(file) = self.index_entry_for(entry.path()) {
+                index.insert(file);
+            }
+        }
+        index
+    }
+
+    fn load_search_index(&self) -> Option<SearchIndex> {
+        let mut bytes = std::fs::read(self.search_index_path()).ok()?;
+        // Sealed when memory encryption is on: the terms would leak content
+        if bytes.starts_with(crate::SEALED_PREFIX.as_bytes()) {
+            bytes = self.cipher.as_ref()?.open(std::str::from_utf8(&bytes).ok()?).ok()?;
+        }
+        let mut index: SearchIndex = bincode::deserialize(&bytes).ok()?;
+        if index.version != SEARCH_INDEX_VERSION {
+            return None;
+        }
+        index.rebuild_lookups();
+        Some(index)
+    }
+
+    /// Create the index directory, kept out of the workspace git history
+    pub(crate) fn ensure_index_dir(&self) -> Result<PathBuf> {
+        let dir = self.memory_root.join(SEARCH_INDEX_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(&gitignore, "*\n")?;
+        }
+        Ok(dir)
+    }
+
+    fn save_search_index(&self, index: &mut SearchIndex) -> Result<()> {
+        let path = self.search_index_path();
+        self.ensure_index_dir()?;
+
+        let tmp = path.with_extension("tmp");
+        let mut bytes = bincode::serialize(index)?;
+        if self.encrypt_memory {
+            bytes = self.seal(&bytes)?.into_bytes();
+        }
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, &path)?;
+        index.dirty = false;
+        index.last_saved = Some(Instant::now());
+        Ok(())
+    }
+
+    fn flush_if_due(&self, index: &mut SearchIndex, force: bool) {
+        let due = index.last_saved.is_none_or(|t| t.elapsed() >= SAVE_INTERVAL);
+        if index.dirty && (force || due)
+            && let Err(e) = self.save_search_index(index)
+        {
+            tracing::warn!("Failed to save search index: {}", e);
+        }
+    }
+
+    /// Re-stat the workspace (small, written by OpenSpore itself) and pick up changes
+    fn refresh_workspace_entries(&self, index: &mut SearchIndex) {
+        let mut seen = HashSet::new();
+        for entry in self.walk_indexable(&self.memory_root) {
+            let path = entry.path().to_path_buf();
+            let modified = entry.metadata().map(|m| modified_secs(&m)).unwrap_or(0);
+            if index.modified_of(&path) != Some(modified) {
+                match self.index_entry_for(&path) {
+                    Some(file) => index.insert(file),
+                    None => index.remove(&path),
+                }
+            }
+            seen.insert(path);
+        }
+
+        let gone: Vec<PathBuf> = index.by_path.keys()
+            .filter(|p| p.starts_with(&self.memory_root) && !seen.contains(*p))
+            .cloned()
+            .collect();
+        for path in gone {
+            index.remove(&path);
+        }
+    }
+
+    /// Drop the current index and rebuild it from the project root
+    pub async fn rebuild_index(&self) -> Result<usize> {
+        let this = self.clone();
+        let mut index = tokio::task::spawn_blocking(move || this.build_search_index()).await?;
+        self.save_search_index(&mut index)?;
+        let count = index.len();
+        *self.search_index.lock().await = Some(index);
+        Ok(count)
+    }
+
+    /// Re-index changed paths (fed by the Watchman). Deleted paths are removed.
+    pub async fn index_paths(&self, paths: &[PathBuf]) {
+        let mut guard = self.search_index.lock().await;
+        if guard.is_none() {
+            // Nothing to update until the index has been built once
+            *guard = self.load_search_index();
+        }
+        let Some(index) = guard.as_mut() else { return };
+
+        for path in paths {
+            if path.components().any(|c| self.ignore_dirs.contains(c.as_os_str().to_string_lossy().as_ref())) {
+                continue;
+            }
+            match self.index_entry_for(path) {
+                Some(file) => index.insert(file),
+                None => index.remove(path),
+            }
+        }
+        self.flush_if_due(index, false);
+    }
+
+    /// Drop a deleted file or directory from the search index and the embedding
+    /// store without touching the disk (the path is gone). Returns the entries removed.
+    pub async fn unindex(&self, path: &Path) -> usize {
+        let mut removed = 0;
+        {
+            let mut guard = self.search_index.lock().await;
+            if let Some(index) = guard.as_mut() {
+                removed += index.remove_under(path);
+                self.flush_if_due(index, false);
+            }
+        }
+        removed + self.unembed(path).await
+    }
+
+    /// Load (or build) the index in the background so the first search doesn't
+    /// pay for it. No-op outside a Tokio runtime or while already warming.
+    pub fn warm_search_index(&self) {
+        if tokio::runtime::Handle::try_current().is_err() || self.index_warming.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let memory = self.clone();
+        tokio::spawn(async move {
+            if memory.search_index.lock().await.is_none() {
+                let loader = memory.clone();
+                let loaded = tokio::task::spawn_blocking(move || loader.load_search_index()).await.ok().flatten();
+                match loaded {
+                    Some(index) => {
+                        memory.search_index.lock().await.get_or_insert(index);
+                    }
+                    None => {
+                        tracing::info!("🔎 Building search index in the background...");
+                        match memory.rebuild_index().await {
+                            Ok(n) => tracing::info!("🔎 Search index ready ({} files)", n),
+                            Err(e) => tracing::warn!("Failed to build search index: {}", e),
+                        }
+                    }
+                }
+            }
+            memory.index_warming.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Update the index after OpenSpore itself wrote `path` (only when loaded:
+    /// a cold index picks the change up when it is built or refreshed)
+    pub(crate) async fn index_written(&self, path: &Path) {
+        let mut guard = self.search_index.lock().await;
+        let Some(index) = guard.as_mut() else { return };
+        match self.index_entry_for(path) {
+            Some(file) => index.insert(file),
+            None => index.remove(path),
+        }
+        self.flush_if_due(index, false);
+    }
+
+    /// Indexed search: score from the index, then only read the top candidates.
+    /// While the index is cold (first run, still building) the tree is walked.
+    pub(crate) async fn search_indexed(&self, keywords: &[String], base_path: &Path, limit: usize) -> Result<Vec<crate::SearchResult>> {
+        let mut guard = self.search_index.lock().await;
+        if guard.is_none() {
+            *guard = self.load_search_index();
+        }
+        if guard.is_none() {
+            drop(guard);
+            self.warm_search_index();
+            return self.search_walk(keywords, base_path, limit);
+        }
+        let Some(index) = guard.as_mut() else { return Ok(vec![]) };
+
+        self.refresh_workspace_entries(index);
+
+        let tombstones = self.tombstone_set();
+        let mut results = Vec::new();
+        for (path, score) in index.query(keywords, base_path) {
+            if results.len() >= limit {
+                break;
+            }
+            match self.read_text(&path) {
+                Ok(content) if tombstones.buries(&path, &content) => {}
+                Ok(content) => {
+                    // Stale entry: refresh it for next time, keep the current score
+                    if let Ok(meta) = std::fs::metadata(&path)
+                        && index.modified_of(&path).is_some_and(|m| m != modified_secs(&meta))
+                        && let Some(file) = self.index_entry_for(&path)
+                    {
+                        index.insert(file);
+                    }
+                    results.push(crate::SearchResult {
+                        title: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                        content,
+                        score,
+                        path,
+                    });
+                }
+                Err(_) => index.remove(&path),
+            }
+        }
+
+        self.flush_if_due(index, false);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+    use crate::MemorySystem;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Embeddings endpoint on 127.0.0.1: inputs mentioning orchids point one
+    /// way, everything else the other. Returns the base URL.
+    async fn fake_embeddings() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut data = Vec::new();
+                    let mut buf = [0u8; 8192];
+                    let request = loop {
+                        let Ok(n) = stream.read(&mut buf).await else { return };
+                        if n == 0 {
+                            return;
+                        }
+                        data.extend_from_slice(&buf[..n]);
+                        let text = String::from_utf8_lossy(&data).to_string();
+                        if let Some((head, body)) = text.split_once("\r\n\r\n")
+                            && let Some(length) = head.to_lowercase().lines()
+                                .find_map(|l| l.strip_prefix("content-length:").and_then(|v| v.trim().parse::<usize>().ok()))
+                            && body.len() >= length
+                        {
+                            break serde_json::from_str::<serde_json::Value>(body).unwrap_or_default();
+                        }
+                    };
+                    let data: Vec<_> = request["input"].as_array().into_iter().flatten().enumerate()
+                        .map(|(index, input)| {
+                            let orchids = input.as_str().unwrap_or_default().to_lowercase().contains("orchid");
+                            serde_json::json!({ "index": index, "embedding": if orchids { [1.0, 0.0] } else { [0.0, 1.0] } })
+                        })
+                        .collect();
+                    let body = serde_json::json!({ "data": data }).to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(), body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn a_created_then_deleted_file_leaves_the_index_and_the_embeddings() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig {
+            project_root: dir.path().to_path_buf(),
+            embedding_model: Some("test-embed".to_string()),
+            embedding_base_url: Some(fake_embeddings().await),
+            ..Default::default()
+        };
+        let memory = MemorySystem::new(&AppState::new(config));
+        memory.ensure_structure().await.unwrap();
+        memory.rebuild_index().await.unwrap();
+
+        // Created: the Watchman indexes and embeds it
+        let path = memory.category_dir("knowledge").join("orchids.md");
+        std::fs::write(&path, "Zanzibar orchids want morning light.").unwrap();
+        memory.index_paths(std::slice::from_ref(&path)).await;
+        assert_eq!(memory.embed_paths(std::slice::from_ref(&path)).await.unwrap(), 1);
+        let found = memory.search("zanzibar", 5).await.unwrap();
+        assert!(found.iter().any(|r| r.path == path));
+        let similar = memory.search_semantic("orchid care", 5).await.unwrap();
+        assert!(similar.iter().any(|r| r.path == path));
+
+        // Deleted: one index entry and one vector go
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(memory.unindex(&path).await, 2);
+        assert_eq!(memory.unindex(&path).await, 0);
+        assert!(memory.search("zanzibar", 5).await.unwrap().is_empty());
+        assert!(memory.search_semantic("orchid care", 5).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_deleted_directory_takes_its_files_along() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: dir.path().to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        memory.rebuild_index().await.unwrap();
+
+        let notes = dir.path().join("notes");
+        std::fs::create_dir_all(notes.join("trips")).unwrap();
+        let files = [notes.join("zanzibar.md"), notes.join("trips").join("zanzibar-2026.md"), dir.path().join("notes.md")];
+        for file in &files {
+            std::fs::write(file, "Zanzibar in March.").unwrap();
+        }
+        memory.index_paths(&files).await;
+        assert_eq!(memory.search("zanzibar", 10).await.unwrap().len(), 3);
+
+        std::fs::remove_dir_all(&notes).unwrap();
+        assert_eq!(memory.unindex(&notes).await, 2, "a sibling sharing the name prefix stays");
+        let left: Vec<_> = memory.search("zanzibar", 10).await.unwrap().into_iter().map(|r| r.path).collect();
+        assert_eq!(left, vec![files[2].clone()]);
+    }
+}
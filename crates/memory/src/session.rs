@@ -0,0 +1,165 @@
+//! Named Sessions
+//!
+//! Each session isolates its working context (LOGS.md, session_summary.md and
+//! Exchange files) under `workspace/context/sessions/<name>/`. The active session
+//! is tracked in `workspace/context/session.json`; without one, the flat
+//! `workspace/context/` layout is used as before.
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name of the implicit session backed by the flat context directory
+pub const DEFAULT_SESSION: &str = "default";
+
+const STATE_FILE: &str = "session.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionState {
+    active: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub name: String,
+    pub active: bool,
+    /// Number of Exchange files in the session
+    pub exchanges: usize,
+}
+
+fn state_path(memory_root: &Path) -> PathBuf {
+    memory_root.join("context").join(STATE_FILE)
+}
+
+/// Active session name, or None for the default (flat) layout
+pub fn active_session(memory_root: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(state_path(memory_root)).ok()?;
+    let state: SessionState = serde_json::from_str(&content).ok()?;
+    state.active.filter(|n| n != DEFAULT_SESSION && validate_name(n).is_ok())
+}
+
+/// Context directory of the active session
+pub fn context_dir(memory_root: &Path) -> PathBuf {
+    let base = memory_root.join("context");
+    match active_session(memory_root) {
+        Some(name) => base.join("sessions").join(name),
+        None => base,
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 64 {
+        return Err(anyhow!("Session name must be 1-64 characters"));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(anyhow!("Session name may only contain letters, digits, '-' and '_'"));
+    }
+    Ok(())
+}
+
+pub struct SessionManager {
+    memory_root: PathBuf,
+}
+
+impl SessionManager {
+    pub fn new(memory: &MemorySystem) -> Self {
+        Self { memory_root: memory.memory_root.clone() }
+    }
+
+    fn sessions_dir(&self) -> PathBuf {
+        self.memory_root.join("context").join("sessions")
+    }
+
+    /// Active session name ("default" for the flat layout)
+    pub fn active(&self) -> String {
+        active_session(&self.memory_root).unwrap_or_else(|| DEFAULT_SESSION.to_string())
+    }
+
+    /// Create a session directory without switching to it
+    pub fn create(&self, name: &str) -> Result<PathBuf> {
+        validate_name(name)?;
+        if name == DEFAULT_SESSION {
+            return Err(anyhow!("\"{}\" is reserved for the shared context", DEFAULT_SESSION));
+        }
+        let dir = self.sessions_dir().join(name);
+        if dir.exists() {
+            return Err(anyhow!("Session \"{}\" already exists", name));
+        }
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// Make a session active. "default" returns to the flat layout.
+    pub fn switch(&self, name: &str) -> Result<()> {
+        validate_name(name)?;
+        let state = if name == DEFAULT_SESSION {
+            SessionState { active: None }
+        } else {
+            if !self.sessions_dir().join(name).is_dir() {
+                return Err(anyhow!("Session \"{}\" does not exist (create it with `openspore session new {}`)", name, name));
+            }
+            SessionState { active: Some(name.to_string()) }
+        };
+
+        let path = state_path(&self.memory_root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&state)?)?;
+        Ok(())
+    }
+
+    /// All sessions, starting with the default one
+    pub fn list(&self) -> Vec<SessionInfo> {
+        let active = self.active();
+        let count_exchanges = |dir: &Path| {
+            std::fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().to_lowercase().starts_with("exchange_"))
+                .count()
+        };
+
+        let mut sessions = vec![SessionInfo {
+            name: DEFAULT_SESSION.to_string(),
+            active: active == DEFAULT_SESSION,
+            exchanges: count_exchanges(&self.memory_root.join("context")),
+        }];
+
+        let mut named: Vec<SessionInfo> = std::fs::read_dir(self.sessions_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                SessionInfo {
+                    active: name == active,
+                    exchanges: count_exchanges(&e.path()),
+                    name,
+                }
+            })
+            .collect();
+        named.sort_by(|a, b| a.name.cmp(&b.name));
+        sessions.extend(named);
+        sessions
+    }
+}
+
+impl MemorySystem {
+    /// Working context directory of the active session
+    pub fn context_dir(&self) -> PathBuf {
+        context_dir(&self.memory_root)
+    }
+
+    /// Directory backing a category (the "context" category follows the active session)
+    pub fn category_dir(&self, category: &str) -> PathBuf {
+        if category == "context" {
+            self.context_dir()
+        } else {
+            self.memory_root.join(category)
+        }
+    }
+}
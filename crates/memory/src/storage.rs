@@ -4,10 +4,15 @@ use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use chrono::Utc;
 use anyhow::Result;
+use openspore_core::event_bus::SystemEvent;
+
+/// Core identity and operational files: never written by save_memory, never expired
+pub(crate) const CORE_FILES: [&str; 8] = ["USER", "SOUL", "AGENTS", "SKILLS", "LOGS", "SESSION_SUMMARY", "GLOSSARY", "CONTACTS"];
 
 impl MemorySystem {
     /// Save memory with YAML frontmatter (lines 154-189 in JS)
-    /// Exact replication of saveMemory(category, title, content, metadata)
+    /// Exact replication of saveMemory(category, title, content, metadata).
+    /// With `ttl_days`, an `expires` date is added and prune_expired() removes it later.
     pub async fn save_memory(
         &self,
         category: &str,
@@ -15,6 +20,23 @@ impl MemorySystem {
         content: &str,
         tags: Vec<String>,
         memory_type: Option<&str>,
+        ttl_days: Option<u32>,
+    ) -> Result<Option<PathBuf>> {
+        self.save_memory_with_metadata(category, title, content, tags, memory_type, ttl_days, &[]).await
+    }
+
+    /// save_memory with extra frontmatter fields (e.g. the model behind an exchange),
+    /// written between `created` and `tags`. Empty values are left out.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn save_memory_with_metadata(
+        &self,
+        category: &str,
+        title: &str,
+        content: &str,
+        tags: Vec<String>,
+        memory_type: Option<&str>,
+        ttl_days: Option<u32>,
+        metadata: &[(&str, String)],
     ) -> Result<Option<PathBuf>> {
         self.ensure_structure().await?;
 
@@ -28,21 +50,35 @@ impl MemorySystem {
             "context".to_string()
         };
 
-        let dir = self.memory_root.join(&target_category);
+        let dir = self.category_dir(&target_category);
         if !dir.exists() {
             fs::create_dir_all(&dir).await?;
         }
 
+        // Canonical spellings from the glossary (titles and tags only, never the body)
+        let glossary = self.glossary();
+        let (title, tags) = if glossary.is_empty() {
+            (title.to_string(), tags)
+        } else {
+            (glossary.normalize(title), tags.iter().map(|t| glossary.normalize(t)).collect())
+        };
+        let title = title.as_str();
+
         // v3.5: Protect core identity and operational log files
-        let core_identity_files = ["USER", "SOUL", "AGENTS", "SKILLS", "LOGS", "SESSION_SUMMARY"];
         let normalized_title = title.to_uppercase().trim().to_string();
         if (target_category == "identity" || target_category == "context")
-            && core_identity_files.contains(&normalized_title.as_str())
+            && CORE_FILES.contains(&normalized_title.as_str())
         {
             tracing::warn!("🛡️ Save Blocked: Attempt to clobber protected core file \"{}.md\" via save_memory.", normalized_title);
             return Ok(None);
         }
 
+        // The generated workspace index is never written through save_memory
+        if normalized_title == "INDEX" {
+            tracing::warn!("🛡️ Save Blocked: \"{}\" is regenerated automatically.", crate::INDEX_FILE);
+            return Ok(None);
+        }
+
         // Sanitize filename
         let filename = format!(
             "{}.md",
@@ -53,35 +89,75 @@ impl MemorySystem {
         // Build frontmatter (exact format from JS line 177)
         let tags_str = tags.join(", ");
         let mem_type = memory_type.unwrap_or("memory");
-        let created = Utc::now().to_rfc3339();
-
-        let file_content = format!(
-            "---\ntype: {}\ncreated: {}\ntags: {}\n---\n\n# {}\n\n{}\n",
-            mem_type, created, tags_str, title, content
+        let now = Utc::now();
+        let created = now.to_rfc3339();
+        let expires = ttl_days
+            .filter(|d| *d > 0)
+            .map(|d| format!("expires: {}\n", (now + chrono::Duration::days(d as i64)).to_rfc3339()))
+            .unwrap_or_default();
+        // Usage stats follow the memory by this id (a rewrite keeps it)
+        let id = self.read_text(&file_path).ok()
+            .and_then(|existing| crate::parse_frontmatter(&existing).remove("id"))
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| crate::sha256_hex(format!("{}\n{}\n{}", title, content, created).as_bytes())[..16].to_string());
+        let extra: String = metadata.iter()
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(key, value)| format!("{}: {}\n", key, value.replace('\n', " ").trim()))
+            .collect();
+
+        let mut file_content = format!(
+            "---\nid: {}\ntype: {}\ncreated: {}\n{}{}tags: {}\n---\n\n# {}\n\n{}\n",
+            id, mem_type, created, expires, extra, tags_str, title, content
         );
+        if self.encrypts(&target_category) {
+            file_content = self.seal(file_content.as_bytes())? + "\n";
+        }
 
         self.mark_as_internal_write(file_path.clone()).await;
         fs::write(&file_path, &file_content).await?;
+        self.index_written(&file_path).await;
+        if let Err(e) = self.clear_tombstones(&file_path) {
+            tracing::warn!("🪦 Could not clear tombstones of {}: {}", file_path.display(), e);
+        }
 
         // Versioning: Commit important changes
         if ["preferences", "identity", "knowledge", "memory"].contains(&target_category.as_str()) {
             self.commit(&format!("Auto-save: {}/{}", target_category, title));
         }
 
+        if self.embeddings_enabled() {
+            let memory = self.clone();
+            let path = file_path.clone();
+            tokio::spawn(async move {
+                if let Err(e) = memory.embed_paths(&[path]).await {
+                    tracing::warn!("🧭 Embedding failed: {}", e);
+                }
+            });
+        }
+
+        openspore_core::event_bus::publish(SystemEvent::MemorySaved {
+            category: target_category,
+            title: title.to_string(),
+            path: file_path.clone(),
+        });
+
         Ok(Some(file_path))
     }
 
     /// Append to LOGS.md (non-blocking journal, lines 192-209 in JS)
     pub async fn save_journal(&self, entry: &str) -> Result<()> {
-        let path = self.memory_root.join("context").join("LOGS.md");
+        let path = self.context_dir().join("LOGS.md");
 
         // Ensure parent directory exists
-        if let Some(parent) = path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent).await?;
-            }
+        if let Some(parent) = path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent).await?;
         }
 
+        // One sealed line per entry, so the log stays appendable
+        let entry = self.seal_if_enabled(entry)?;
+
         self.mark_as_internal_write(path.clone()).await;
 
         let mut file = tokio::fs::OpenOptions::new()
@@ -91,6 +167,11 @@ impl MemorySystem {
             .await?;
 
         file.write_all(entry.as_bytes()).await?;
+        // tokio finishes unflushed writes in the background; readers right
+        // after the turn must see this entry
+        file.flush().await?;
+        drop(file);
+        self.index_written(&path).await;
         Ok(())
     }
 }
@@ -2,6 +2,44 @@ use crate::MemorySystem;
 use tokio::fs;
 use anyhow::Result;
 
+/// Pipelines of skill and think steps (`openspore pipeline`), relative to the workspace
+pub const PIPELINES_DIR: &str = "pipelines";
+
+/// Written when the pipelines directory is first created (deleting it sticks)
+const SAMPLE_PIPELINE: (&str, &str) = ("weekly-review.yaml", r##"# Sample pipeline: openspore pipeline run weekly-review --var topic=rust
+#
+# Steps run in order. Each one calls a skill with fixed arguments or thinks
+# about a prompt. {{vars.<name>}}, {{steps.<n or name>.output}}, {{date}},
+# {{time}}, {{workspace}} and {{pipeline}} are filled in before a step runs.
+# on_error (for the pipeline or one step): abort, continue or ask.
+description: Gather the notes on a topic, summarize the week and file the summary
+# schedule: "0 18 * * 5"   # Fridays at 18:00, while `openspore start` runs
+on_error: abort
+vars:
+  topic: projects
+steps:
+  - name: notes
+    skill: search
+    args: "{{vars.topic}}"
+  - name: summary
+    think: |
+      These workspace notes match "{{vars.topic}}" (search results as JSON):
+      {{steps.notes.output}}
+
+      Read the most relevant ones and write a short weekly summary: progress,
+      open questions and next steps. Reply with the summary only.
+  - name: file
+    skill: write_file
+    args:
+      path: "{{workspace}}/knowledge/weekly-{{vars.topic}}-{{date}}.md"
+      content: "# Weekly review: {{vars.topic}} ({{date}})\n\n{{steps.summary.output}}\n"
+  - name: notify
+    skill: telegram_send
+    args:
+      message: "📓 Weekly review of {{vars.topic}} filed"
+    on_error: continue
+"##);
+
 impl MemorySystem {
     /// Ensure the workspace directory structure exists (lines 30-62 in JS)
     pub async fn ensure_structure(&self) -> Result<()> {
@@ -27,6 +65,14 @@ impl MemorySystem {
             }
         }
 
+        self.ensure_default_templates().await?;
+
+        let pipelines = self.memory_root.join(PIPELINES_DIR);
+        if !pipelines.exists() {
+            fs::create_dir_all(&pipelines).await?;
+            fs::write(pipelines.join(SAMPLE_PIPELINE.0), SAMPLE_PIPELINE.1).await?;
+        }
+
         Ok(())
     }
 }
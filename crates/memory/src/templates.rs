@@ -0,0 +1,425 @@
+//! Memory Templates
+//!
+//! `workspace/identity/templates/*.md` define reusable note formats. The
+//! frontmatter declares the template name, target category, optional aliases
+//! and the placeholder fields; the body uses `{{field}}` or `{{field|default}}`.
+//! `date`, `time`, `week`, `title` and `slug` are filled automatically.
+
+use crate::MemorySystem;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Placeholder text for fields that were not provided and have no default
+pub const MISSING_FIELD_MARKER: &str = "_TODO_";
+
+const AUTO_FIELDS: [&str; 5] = ["date", "time", "week", "title", "slug"];
+
+/// Written when the templates directory is first created (deleting one sticks)
+const DEFAULT_TEMPLATES: [(&str, &str); 3] = [
+    ("decision-record.md", "---
+name: decision-record
+description: Decision record (context, decision, alternatives, consequences)
+category: knowledge
+aliases: decision, adr
+fields: status, context, decision, alternatives, consequences
+---
+**Date:** {{date}}
+**Status:** {{status|Accepted}}
+
+## Context
+{{context}}
+
+## Decision
+{{decision}}
+
+## Alternatives Considered
+{{alternatives|None recorded}}
+
+## Consequences
+{{consequences}}
+"),
+    ("meeting-note.md", "---
+name: meeting-note
+description: Meeting notes with attendees, discussion and action items
+category: memory
+aliases: meeting notes, meeting
+fields: attendees, agenda, notes, action_items
+---
+**Date:** {{date}} {{time}}
+**Attendees:** {{attendees}}
+
+## Agenda
+{{agenda}}
+
+## Notes
+{{notes}}
+
+## Action Items
+{{action_items|- [ ] None}}
+"),
+    ("weekly-review.md", "---
+name: weekly-review
+description: End-of-week review (wins, challenges, lessons, next week)
+category: memory
+aliases: weekly review, week review
+fields: wins, challenges, lessons, next_week
+---
+**Week:** {{week}} (written {{date}})
+
+## Wins
+{{wins}}
+
+## Challenges
+{{challenges}}
+
+## Lessons
+{{lessons}}
+
+## Next Week
+{{next_week}}
+"),
+];
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    pub aliases: Vec<String>,
+    /// Placeholder fields in order of appearance (auto fields excluded)
+    pub fields: Vec<String>,
+    pub body: String,
+    pub path: PathBuf,
+}
+
+/// Outcome of `new_from_template`
+#[derive(Debug, Clone)]
+pub struct TemplateNote {
+    pub path: PathBuf,
+    pub template: String,
+    /// Fields left as `MISSING_FIELD_MARKER`
+    pub missing: Vec<String>,
+}
+
+/// "Decision Record" / "decision_record" -> "decision-record"
+fn normalize_name(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// Every `{{...}}` in the body as (full match range, field, default)
+fn placeholders(body: &str) -> Vec<(std::ops::Range<usize>, String, Option<String>)> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = body[pos..].find("{{").map(|i| pos + i) {
+        let Some(end) = body[start + 2..].find("}}").map(|i| start + 2 + i) else { break };
+        let inner = &body[start + 2..end];
+        let (field, default) = match inner.split_once('|') {
+            Some((f, d)) => (f.trim(), Some(d.trim().to_string())),
+            None => (inner.trim(), None),
+        };
+        // `{{ not a field }}` is left alone
+        if !field.is_empty() && field.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            found.push((start..end + 2, field.to_string(), default));
+        }
+        pos = end + 2;
+    }
+    found
+}
+
+impl Template {
+    pub fn parse(content: &str, path: PathBuf) -> Option<Self> {
+        let rest = content.strip_prefix("---\n")?;
+        let end = rest.find("\n---")?;
+        let mut meta: HashMap<&str, &str> = HashMap::new();
+        for line in rest[..end].lines() {
+            if let Some((k, v)) = line.split_once(':') {
+                meta.insert(k.trim(), v.trim());
+            }
+        }
+        let body = rest[end + 4..].trim_start_matches('-').trim_start_matches('\n').to_string();
+
+        let name = meta.get("name").map(|n| normalize_name(n))
+            .or_else(|| path.file_stem().map(|s| normalize_name(&s.to_string_lossy())))?;
+
+        let mut fields = meta.get("fields").map(|f| split_list(f)).unwrap_or_default();
+        for (_, field, _) in placeholders(&body) {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        fields.retain(|f| !AUTO_FIELDS.contains(&f.as_str()));
+
+        Some(Self {
+            name,
+            description: meta.get("description").unwrap_or(&"").to_string(),
+            category: meta.get("category").unwrap_or(&"knowledge").to_lowercase(),
+            aliases: meta.get("aliases").map(|a| split_list(a)).unwrap_or_default(),
+            fields,
+            body,
+            path,
+        })
+    }
+
+    /// Fill placeholders: provided values first, then auto fields, then `{{f|default}}`,
+    /// otherwise `MISSING_FIELD_MARKER`. Returns the text and the missing fields.
+    pub fn render(&self, title: &str, values: &HashMap<String, String>) -> (String, Vec<String>) {
+        let now = chrono::Local::now();
+        let auto = |field: &str| -> Option<String> {
+            match field {
+                "date" => Some(now.format("%Y-%m-%d").to_string()),
+                "time" => Some(now.format("%H:%M").to_string()),
+                "week" => Some(now.format("%G-W%V").to_string()),
+                "title" => Some(title.to_string()),
+                "slug" => Some(normalize_name(title)),
+                _ => None,
+            }
+        };
+
+        let mut out = String::with_capacity(self.body.len());
+        let mut missing = Vec::new();
+        let mut last = 0;
+        for (range, field, default) in placeholders(&self.body) {
+            out.push_str(&self.body[last..range.start]);
+            let provided = values.get(&field).filter(|v| !v.trim().is_empty()).cloned();
+            let value = provided.or_else(|| auto(&field)).or(default).unwrap_or_else(|| {
+                if !missing.contains(&field) {
+                    missing.push(field.clone());
+                }
+                MISSING_FIELD_MARKER.to_string()
+            });
+            out.push_str(&value);
+            last = range.end;
+        }
+        out.push_str(&self.body[last..]);
+        (out, missing)
+    }
+
+    /// Phrases that name this template in chat ("decision record", aliases...)
+    fn phrases(&self) -> Vec<String> {
+        let mut phrases = vec![self.name.replace('-', " ")];
+        phrases.extend(self.aliases.iter().map(|a| a.to_lowercase()));
+        // Longest first so "meeting notes" wins over "meeting"
+        phrases.sort_by_key(|p| std::cmp::Reverse(p.len()));
+        phrases
+    }
+}
+
+/// Recognize "new decision record: use SQLite for caching" or
+/// "create a meeting note about the launch". Returns (template name, title).
+pub fn detect_template_request(prompt: &str, templates: &[Template]) -> Option<(String, String)> {
+    // ASCII lowercasing keeps byte offsets valid for slicing the original prompt
+    let lower = prompt.trim().to_ascii_lowercase();
+    let original = prompt.trim();
+
+    let mut rest_start = None;
+    for verb in ["new ", "create ", "add ", "start ", "write "] {
+        if let Some(after) = lower.strip_prefix(verb) {
+            let mut offset = verb.len();
+            for article in ["a new ", "an ", "a ", "new "] {
+                if after.starts_with(article) {
+                    offset += article.len();
+                    break;
+                }
+            }
+            rest_start = Some(offset);
+            break;
+        }
+    }
+    let rest_start = rest_start?;
+    let rest = &lower[rest_start..];
+
+    for template in templates {
+        for phrase in template.phrases() {
+            let Some(after) = rest.strip_prefix(phrase.as_str()) else { continue };
+            // Whole words only ("adrift" is not "adr")
+            if after.chars().next().is_some_and(|c| c.is_alphanumeric()) {
+                continue;
+            }
+            let mut title_start = rest_start + phrase.len();
+            let tail = &lower[title_start..];
+            let trimmed = tail.trim_start_matches(|c: char| c == ':' || c == '-' || c.is_whitespace());
+            title_start += tail.len() - trimmed.len();
+            for connector in ["about ", "on ", "for ", "regarding "] {
+                if lower[title_start..].starts_with(connector) {
+                    title_start += connector.len();
+                    break;
+                }
+            }
+            let title = original[title_start..].trim().trim_end_matches('.').to_string();
+            return Some((template.name.clone(), title));
+        }
+    }
+    None
+}
+
+impl MemorySystem {
+    pub fn templates_dir(&self) -> PathBuf {
+        self.memory_root.join("identity").join("templates")
+    }
+
+    /// Seed the default templates into a new templates directory
+    pub(crate) async fn ensure_default_templates(&self) -> Result<()> {
+        let dir = self.templates_dir();
+        if dir.exists() {
+            return Ok(());
+        }
+        tokio::fs::create_dir_all(&dir).await?;
+        for (file, content) in DEFAULT_TEMPLATES {
+            let path = dir.join(file);
+            if !path.exists() {
+                tokio::fs::write(&path, content).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// All parseable templates, sorted by name
+    pub fn list_templates(&self) -> Vec<Template> {
+        let mut templates: Vec<Template> = std::fs::read_dir(self.templates_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "md"))
+            .filter_map(|p| Template::parse(&std::fs::read_to_string(&p).ok()?, p))
+            .collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+
+    /// Look up a template by name or alias
+    pub fn find_template(&self, name: &str) -> Option<Template> {
+        let wanted = normalize_name(name);
+        self.list_templates().into_iter().find(|t| {
+            t.name == wanted || t.aliases.iter().any(|a| normalize_name(a) == wanted)
+        })
+    }
+
+    /// Render a template and save it with save_memory in the template's category
+    pub async fn new_from_template(&self, name: &str, title: &str, values: &HashMap<String, String>) -> Result<TemplateNote> {
+        self.ensure_structure().await?;
+        let template = self.find_template(name).ok_or_else(|| {
+            let known: Vec<String> = self.list_templates().into_iter().map(|t| t.name).collect();
+            anyhow!("Unknown template \"{}\" (available: {})", name, known.join(", "))
+        })?;
+        // "new weekly review" -> "weekly review 2026-10-16"
+        let title = match title.trim() {
+            "" => format!("{} {}", template.name.replace('-', " "), chrono::Local::now().format("%Y-%m-%d")),
+            t => t.to_string(),
+        };
+
+        let (content, missing) = template.render(&title, values);
+        let path = self
+            .save_memory(&template.category, &title, &content, vec![template.name.clone()], Some(&template.name), None)
+            .await?
+            .ok_or_else(|| anyhow!("Title \"{}\" is reserved", title))?;
+
+        Ok(TemplateNote { path, template: template.name, missing })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    fn template(body: &str) -> Template {
+        let content = format!("---\nname: Test Note\ncategory: Memory\nfields: owner\n---\n{}", body);
+        Template::parse(&content, PathBuf::from("test.md")).unwrap()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn parse_collects_fields_but_not_auto_ones() {
+        let t = template("{{date}} {{title}} {{status|Open}} {{ owner }} {{status}}\n");
+        assert_eq!(t.name, "test-note");
+        assert_eq!(t.category, "memory");
+        assert_eq!(t.fields, ["owner", "status"]);
+    }
+
+    #[test]
+    fn render_prefers_values_then_auto_fields_then_defaults() {
+        let t = template("# {{title}} ({{slug}})\nStatus: {{status|Open}}\nOwner: {{owner}}\nDue: {{due}}\n");
+        let (text, missing) = t.render("Ship The Beta", &values(&[("owner", "Ana"), ("status", "  ")]));
+        assert_eq!(text, format!("# Ship The Beta (ship-the-beta)\nStatus: Open\nOwner: Ana\nDue: {}\n", MISSING_FIELD_MARKER));
+        assert_eq!(missing, ["due"]);
+
+        let (text, _) = t.render("x", &values(&[("status", "Closed"), ("title", "Overridden")]));
+        assert!(text.starts_with("# Overridden (x)\nStatus: Closed\n"));
+    }
+
+    #[test]
+    fn render_leaves_non_fields_and_unclosed_braces_alone() {
+        let t = template("{{ not a field }} {{owner}} {{}} {{date");
+        let (text, missing) = t.render("t", &HashMap::new());
+        assert_eq!(text, format!("{{{{ not a field }}}} {} {{{{}}}} {{{{date", MISSING_FIELD_MARKER));
+        assert_eq!(missing, ["owner"]);
+    }
+
+    #[test]
+    fn render_fills_dates_and_reports_a_field_once() {
+        let t = template("{{date}} {{week}} {{owner}} {{owner}}");
+        let (text, missing) = t.render("t", &HashMap::new());
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert!(text.starts_with(&today));
+        assert!(!text.contains("{{"));
+        assert_eq!(missing, ["owner"]);
+    }
+
+    #[test]
+    fn detects_template_requests() {
+        let templates: Vec<Template> = DEFAULT_TEMPLATES.iter()
+            .filter_map(|(file, content)| Template::parse(content, PathBuf::from(file)))
+            .collect();
+        assert_eq!(
+            detect_template_request("New decision record: Use SQLite for caching.", &templates),
+            Some(("decision-record".into(), "Use SQLite for caching".into()))
+        );
+        assert_eq!(
+            detect_template_request("create a meeting note about the Launch", &templates),
+            Some(("meeting-note".into(), "the Launch".into()))
+        );
+        assert_eq!(
+            detect_template_request("start a weekly review", &templates),
+            Some(("weekly-review".into(), String::new()))
+        );
+        assert_eq!(detect_template_request("new meetings tracker", &templates), None);
+        assert_eq!(detect_template_request("what was the decision record?", &templates), None);
+    }
+
+    #[tokio::test]
+    async fn new_from_template_saves_the_rendered_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig { project_root: dir.path().to_path_buf(), ..Default::default() };
+        let memory = MemorySystem::new(&AppState::new(config));
+
+        let fields = values(&[("context", "Reads are slow"), ("decision", "Cache in SQLite")]);
+        let note = memory.new_from_template("ADR", "Use SQLite", &fields).await.unwrap();
+        assert_eq!(note.template, "decision-record");
+        assert_eq!(note.missing, ["consequences"]);
+        assert!(note.path.starts_with(memory.memory_root.join("knowledge")));
+
+        let saved = std::fs::read_to_string(&note.path).unwrap();
+        assert!(saved.contains("**Status:** Accepted"));
+        assert!(saved.contains("## Decision\nCache in SQLite"));
+        assert!(saved.contains("## Alternatives Considered\nNone recorded"));
+        assert!(saved.contains(&format!("## Consequences\n{}", MISSING_FIELD_MARKER)));
+
+        let err = memory.new_from_template("retro", "x", &HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("available: decision-record, meeting-note, weekly-review"));
+    }
+}
@@ -0,0 +1,273 @@
+//! Tombstones: deletions that survive syncs and snapshot restores
+//!
+//! Removing a memory file appends a record (path, content hash, date, reason) to
+//! workspace/.tombstones.jsonl. A file whose path and content match a tombstone
+//! is treated as deleted wherever it comes back from (a git checkout of an old
+//! snapshot, the other side of a merge), so readers skip it. Saving new content
+//! under the same path is a deliberate write and clears the tombstone.
+
+use crate::{sha256_hex, MemorySystem};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const TOMBSTONES_FILE: &str = ".tombstones.jsonl";
+
+/// Tombstones older than this are dropped for good by purge_tombstones()
+pub const TOMBSTONE_RETENTION_DAYS: i64 = 180;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tombstone {
+    /// Relative to the workspace, `/`-separated
+    pub path: String,
+    /// SHA-256 (hex) of the plaintext content at deletion time
+    pub hash: String,
+    pub deleted_at: DateTime<Utc>,
+    /// expired, compressed, purged...
+    pub reason: String,
+    /// Where the file was moved, for deletions that archive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<String>,
+}
+
+/// (path, hash) pairs of all tombstones, for filtering reads
+#[derive(Debug, Default)]
+pub struct TombstoneSet {
+    buried: HashSet<(String, String)>,
+    memory_root: PathBuf,
+}
+
+impl TombstoneSet {
+    /// True when `path` holding `content` was deleted and has come back
+    pub fn buries(&self, path: &Path, content: &str) -> bool {
+        if self.buried.is_empty() {
+            return false;
+        }
+        relative_key(&self.memory_root, path)
+            .is_some_and(|key| self.buried.contains(&(key, sha256_hex(content.as_bytes()))))
+    }
+}
+
+/// Workspace-relative key of `path` (None outside the workspace)
+fn relative_key(memory_root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(memory_root).ok()?;
+    Some(rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+}
+
+impl MemorySystem {
+    fn tombstones_path(&self) -> PathBuf {
+        self.memory_root.join(TOMBSTONES_FILE)
+    }
+
+    /// All tombstones, oldest first (unreadable lines are skipped)
+    pub fn tombstones(&self) -> Vec<Tombstone> {
+        read_tombstones(&self.tombstones_path())
+    }
+
+    pub fn tombstone_set(&self) -> TombstoneSet {
+        TombstoneSet {
+            buried: self.tombstones().into_iter().map(|t| (t.path, t.hash)).collect(),
+            memory_root: self.memory_root.clone(),
+        }
+    }
+
+    /// Append a tombstone for `path`, whose last content was `content`
+    pub(crate) fn record_tombstone(&self, path: &Path, content: &str, reason: &str, archived: Option<&Path>) -> Result<Tombstone> {
+        let key = relative_key(&self.memory_root, path)
+            .ok_or_else(|| anyhow!("{} is outside the workspace", path.display()))?;
+        let tombstone = Tombstone {
+            path: key,
+            hash: sha256_hex(content.as_bytes()),
+            deleted_at: Utc::now(),
+            reason: reason.to_string(),
+            archived: archived.and_then(|a| relative_key(&self.memory_root, a)),
+        };
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.tombstones_path())?;
+        writeln!(file, "{}", serde_json::to_string(&tombstone)?)?;
+        Ok(tombstone)
+    }
+
+    /// Delete a memory file, leaving a tombstone so it cannot resurrect
+    pub async fn forget(&self, path: &Path, reason: &str) -> Result<Tombstone> {
+        let content = self.read_text(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        let tombstone = self.record_tombstone(path, &content, reason, None)?;
+
+        self.mark_as_internal_write(path.to_path_buf()).await;
+        tokio::fs::remove_file(path).await?;
+        self.index_written(path).await;
+        Ok(tombstone)
+    }
+
+    /// Drop the tombstones of `path` (it was written again on purpose).
+    /// Returns how many were removed.
+    pub(crate) fn clear_tombstones(&self, path: &Path) -> Result<usize> {
+        let Some(key) = relative_key(&self.memory_root, path) else { return Ok(0) };
+        let all = self.tombstones();
+        let kept: Vec<Tombstone> = all.iter().filter(|t| t.path != key).cloned().collect();
+        if kept.len() != all.len() {
+            write_tombstones(&self.tombstones_path(), &kept)?;
+        }
+        Ok(all.len() - kept.len())
+    }
+
+    /// Permanently drop tombstones past the retention horizon. Returns the count.
+    pub fn purge_tombstones(&self) -> Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::days(TOMBSTONE_RETENTION_DAYS);
+        let all = self.tombstones();
+        let kept: Vec<Tombstone> = all.iter().filter(|t| t.deleted_at >= cutoff).cloned().collect();
+        if kept.len() != all.len() {
+            write_tombstones(&self.tombstones_path(), &kept)?;
+            tracing::info!("🪦 Dropped {} tombstones older than {} days", all.len() - kept.len(), TOMBSTONE_RETENTION_DAYS);
+        }
+        Ok(all.len() - kept.len())
+    }
+
+    /// Bring back a deleted file: from its archive copy when it has one,
+    /// otherwise from the last git commit (or, without git, the last tarball
+    /// snapshot) that contained it. Clears its tombstones.
+    pub async fn restore_tombstoned(&self, rel_path: &str) -> Result<PathBuf> {
+        let rel_path = rel_path.trim().trim_start_matches("workspace/");
+        let tombstone = self.tombstones().into_iter().rev().find(|t| t.path == rel_path)
+            .ok_or_else(|| anyhow!("No tombstone for {}", rel_path))?;
+        let dest = self.memory_root.join(&tombstone.path);
+        if dest.exists() {
+            return Err(anyhow!("{} already exists", tombstone.path));
+        }
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        self.mark_as_internal_write(dest.clone()).await;
+        let archived = tombstone.archived.as_ref().map(|a| self.memory_root.join(a)).filter(|a| a.exists());
+        match archived {
+            Some(archive) => tokio::fs::rename(&archive, &dest).await?,
+            None if !self.versioned => {
+                self.restore_from_snapshots(&tombstone.path)?;
+            }
+            None => self.checkout_from_history(&tombstone.path)?,
+        }
+
+        self.clear_tombstones(&dest)?;
+        self.index_written(&dest).await;
+        self.commit(&format!("Restore: {}", tombstone.path));
+        Ok(dest)
+    }
+
+    /// `git checkout` of the newest commit holding `rel_path` (the last commit
+    /// touching it may be the deletion itself, then its parent has the file)
+    fn checkout_from_history(&self, rel_path: &str) -> Result<()> {
+        let git = |args: &[&str]| Command::new("git").args(args).current_dir(&self.memory_root).output();
+
+        let log = git(&["log", "-n", "1", "--format=%H", "--", rel_path])?;
+        let commit = String::from_utf8_lossy(&log.stdout).trim().to_string();
+        if commit.is_empty() {
+            return Err(anyhow!("{} is not in the workspace git history", rel_path));
+        }
+        for rev in [commit.clone(), format!("{}^", commit)] {
+            if git(&["checkout", &rev, "--", rel_path])?.status.success() {
+                return Ok(());
+            }
+        }
+        Err(anyhow!("git could not restore {} from {}", rel_path, &commit[..commit.len().min(8)]))
+    }
+}
+
+fn read_tombstones(path: &Path) -> Vec<Tombstone> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+fn write_tombstones(path: &Path, tombstones: &[Tombstone]) -> Result<()> {
+    let mut out = String::new();
+    for t in tombstones {
+        out.push_str(&serde_json::to_string(t)?);
+        out.push('\n');
+    }
+    std::fs::write(path, out)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    #[tokio::test]
+    async fn tombstone_buries_only_the_deleted_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig { project_root: dir.path().to_path_buf(), ..Default::default() };
+        let memory = MemorySystem::new(&AppState::new(config));
+        memory.ensure_structure().await.unwrap();
+        let path = memory.memory_root.join("knowledge/old.md");
+
+        let tombstone = memory.record_tombstone(&path, "stale fact", "expired", None).unwrap();
+        assert_eq!(tombstone.path, "knowledge/old.md");
+        assert_eq!(tombstone.hash, sha256_hex(b"stale fact"));
+
+        let set = memory.tombstone_set();
+        assert!(set.buries(&path, "stale fact"));
+        assert!(!set.buries(&path, "rewritten fact"));
+        assert!(!set.buries(&memory.memory_root.join("knowledge/other.md"), "stale fact"));
+    }
+
+    async fn replica(root: &Path) -> MemorySystem {
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: root.to_path_buf(), ..Default::default() }));
+        memory.ensure_structure().await.unwrap();
+        memory
+    }
+
+    #[tokio::test]
+    async fn deletions_of_both_replicas_survive_a_sync() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (a, b) = (replica(dir_a.path()).await, replica(dir_b.path()).await);
+        let boiler = a.save_memory("knowledge", "Boiler pressure", "The boiler pressure should read 1.5 bar.", vec![], None, None).await.unwrap().unwrap();
+        let manual = a.save_memory("knowledge", "Boiler manual", "The boiler manual is in the garage.", vec![], None, None).await.unwrap().unwrap();
+        let car = a.save_memory("knowledge", "Old car", "The old car needs a boiler flush.", vec![], None, None).await.unwrap().unwrap();
+        let (boiler_text, car_text) = (std::fs::read_to_string(&boiler).unwrap(), std::fs::read_to_string(&car).unwrap());
+        let on_b = |path: &Path| b.memory_root.join(path.strip_prefix(&a.memory_root).unwrap());
+        for path in [&boiler, &manual, &car] {
+            std::fs::copy(path, on_b(path)).unwrap();
+        }
+
+        // Each side deletes a different note, then they sync
+        a.forget(&boiler, "purged").await.unwrap();
+        b.forget(&on_b(&car), "purged").await.unwrap();
+        let base = dir_a.path().join("base.jsonl");
+        std::fs::write(&base, "").unwrap();
+        let merged = Command::new("git")
+            .args(["merge-file", "-p", "--union"])
+            .args([&a.memory_root.join(TOMBSTONES_FILE), &base, &b.memory_root.join(TOMBSTONES_FILE)])
+            .output()
+            .unwrap();
+        std::fs::write(a.memory_root.join(TOMBSTONES_FILE), &merged.stdout).unwrap();
+        assert_eq!(a.tombstones().len(), 2, "{}", String::from_utf8_lossy(&merged.stdout));
+
+        // The stale side of the sync brings both notes back unchanged
+        std::fs::write(&boiler, &boiler_text).unwrap();
+        std::fs::write(&car, &car_text).unwrap();
+
+        let names: Vec<String> = a.get_memories("knowledge").into_iter().map(|m| m.filename).collect();
+        assert_eq!(names, vec![manual.file_name().unwrap().to_string_lossy().to_string()]);
+        let found: Vec<PathBuf> = a.search("boiler", 10).await.unwrap().into_iter()
+            .map(|r| r.path)
+            .filter(|p| p.starts_with(a.category_dir("knowledge")))
+            .collect();
+        assert_eq!(found, vec![manual.clone()]);
+
+        // Saving new content under a buried path is a deliberate write
+        std::fs::write(&boiler, "The boiler pressure should read 1.2 bar.").unwrap();
+        assert_eq!(a.get_memories("knowledge").len(), 2);
+    }
+}
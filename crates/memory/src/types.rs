@@ -1,9 +1,24 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct MemoryItem {
     pub filename: String,
     pub content: String,
+    /// `key: value` lines of the leading frontmatter block (empty without one)
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl MemoryItem {
+    pub fn new(filename: String, content: String) -> Self {
+        let metadata = parse_frontmatter(&content);
+        Self { filename, content, metadata }
+    }
+
+    /// A frontmatter field, None when absent or empty (older files lack most fields)
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str).filter(|v| !v.is_empty())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -13,3 +28,25 @@ pub struct SearchResult {
     pub score: usize,
     pub path: PathBuf,
 }
+
+impl SearchResult {
+    /// The file holds two unresolved versions from a sync conflict
+    pub fn is_conflicted(&self) -> bool {
+        crate::is_conflicted(&self.content)
+    }
+}
+
+/// Fields of a leading `---` block, as written by save_memory. Values are kept
+/// raw (trimmed); lines without a colon are ignored.
+pub fn parse_frontmatter(content: &str) -> BTreeMap<String, String> {
+    let Some(rest) = content.strip_prefix("---\n") else { return BTreeMap::new() };
+    let Some(end) = rest.find("\n---") else { return BTreeMap::new() };
+
+    rest[..end].lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let key = key.trim();
+            (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,353 @@
+//! Usefulness of memories in context
+//!
+//! Every memory put in the model's context is counted as injected, and as cited
+//! when the answer refers to it. Both arrive as SystemEvent::MemoryUsed on the
+//! event bus and are folded into .index/memory_stats.json in batches by the
+//! tracker, off the thinking loop. Stats are keyed by the memory's `id`
+//! (frontmatter, set by save_memory) so they follow renames; files without one
+//! fall back to a hash of their content. search() scales scores by
+//! usefulness_factor(), and `openspore memory review` lists the memories that
+//! are injected often but never cited.
+
+use crate::{MemorySystem, SearchResult, SEARCH_INDEX_DIR};
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use openspore_core::event_bus::{self, SystemEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::warn;
+
+const STATS_FILE: &str = "memory_stats.json";
+/// Pending events are written at least this often...
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+/// ...or as soon as this many are waiting
+const FLUSH_BATCH: usize = 64;
+/// Candidates fetched per result so re-ranking can promote lower hits
+pub(crate) const RERANK_POOL: usize = 3;
+/// Shortest title or file name that counts as a citation
+const MIN_CITATION_CHARS: usize = 4;
+
+/// Counters of one memory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub injected_count: u64,
+    pub cited_count: u64,
+    pub last_cited: Option<DateTime<Local>>,
+    /// Where the memory was last seen
+    pub path: PathBuf,
+}
+
+impl MemoryUsage {
+    /// Cited share of injections, smoothed towards 0.5 so a memory seen once or
+    /// twice is neither buried nor promoted: (cited + 1) / (injected + 2)
+    pub fn usefulness(&self) -> f64 {
+        ((self.cited_count as f64 + 1.0) / (self.injected_count as f64 + 2.0)).min(1.0)
+    }
+}
+
+/// Contents of memory_stats.json, by memory id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    pub memories: HashMap<String, MemoryUsage>,
+}
+
+/// One MemoryUsed event waiting to be written
+#[derive(Debug, Clone)]
+pub struct UsageHit {
+    pub id: String,
+    pub path: PathBuf,
+    pub cited: bool,
+    pub at: DateTime<Local>,
+}
+
+impl UsageStats {
+    /// Add a batch of hits (the tracker's only write)
+    pub fn apply(&mut self, hits: &[UsageHit]) {
+        for hit in hits {
+            let usage = self.memories.entry(hit.id.clone()).or_default();
+            usage.path = hit.path.clone();
+            if hit.cited {
+                usage.cited_count += 1;
+                usage.last_cited = Some(usage.last_cited.map_or(hit.at, |last| last.max(hit.at)));
+            } else {
+                usage.injected_count += 1;
+            }
+        }
+    }
+
+    /// Score multiplier between 1 - weight (never cited) and 1 + weight (always
+    /// cited); 1 for memories without stats
+    pub fn factor(&self, id: &str, weight: f64) -> f64 {
+        self.memories.get(id).map_or(1.0, |u| 1.0 + weight * (2.0 * u.usefulness() - 1.0))
+    }
+
+    /// Scale scores by usefulness and re-sort, highest first (stable for ties)
+    pub fn rerank(&self, results: &mut [SearchResult], weight: f64) {
+        if weight <= 0.0 || self.memories.is_empty() {
+            return;
+        }
+        for result in results.iter_mut() {
+            let factor = self.factor(&memory_id(&result.content), weight);
+            result.score = (result.score as f64 * factor).round() as usize;
+        }
+        results.sort_by_key(|r| std::cmp::Reverse(r.score));
+    }
+
+    /// Review order: never cited first, then most injected, then least useful
+    pub fn review(&self) -> Vec<(&String, &MemoryUsage)> {
+        let mut entries: Vec<_> = self.memories.iter().filter(|(_, u)| u.injected_count > 0).collect();
+        entries.sort_by(|(_, a), (_, b)| {
+            (a.cited_count > 0).cmp(&(b.cited_count > 0))
+                .then(b.injected_count.cmp(&a.injected_count))
+                .then(a.usefulness().total_cmp(&b.usefulness()))
+        });
+        entries
+    }
+}
+
+/// Stable id of a memory: its frontmatter `id`, else a hash of the content
+pub fn memory_id(content: &str) -> String {
+    crate::parse_frontmatter(content)
+        .remove("id")
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| crate::sha256_hex(content.as_bytes())[..16].to_string())
+}
+
+/// Whether an answer refers to a memory by its file name (`rust_tips`,
+/// `rust tips`) or its `# Title`, case-insensitive
+pub fn cites(answer: &str, memory: &SearchResult) -> bool {
+    let answer = answer.to_lowercase();
+    let stem = memory.path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase();
+    let heading = memory.content.lines()
+        .find_map(|l| l.strip_prefix("# "))
+        .map(|t| t.trim().to_lowercase())
+        .unwrap_or_default();
+    [stem.clone(), stem.replace(['_', '-'], " "), heading]
+        .iter()
+        .any(|name| name.chars().count() >= MIN_CITATION_CHARS && answer.contains(name.as_str()))
+}
+
+impl MemorySystem {
+    fn usage_stats_path(&self) -> PathBuf {
+        self.memory_root.join(SEARCH_INDEX_DIR).join(STATS_FILE)
+    }
+
+    pub fn usage_stats(&self) -> UsageStats {
+        std::fs::read_to_string(self.usage_stats_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Fold a batch into memory_stats.json (written atomically)
+    pub fn record_usage(&self, hits: &[UsageHit]) -> Result<()> {
+        if hits.is_empty() {
+            return Ok(());
+        }
+        let mut stats = self.usage_stats();
+        stats.apply(hits);
+        let path = self.usage_stats_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(&stats)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Announce memories that were injected (or cited) for the tracker
+    pub fn publish_usage(&self, memories: &[SearchResult], cited: bool) {
+        for memory in memories {
+            event_bus::publish(SystemEvent::MemoryUsed { id: memory_id(&memory.content), path: memory.path.clone(), cited });
+        }
+    }
+
+    /// Batch MemoryUsed events from the bus into memory_stats.json. Events
+    /// published by processes without a tracker are not counted.
+    pub fn spawn_usage_tracker(&self) -> tokio::task::JoinHandle<()> {
+        let memory = self.clone();
+        tokio::spawn(async move {
+            let mut events = event_bus::bus().subscribe();
+            let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+            let mut pending: Vec<UsageHit> = Vec::new();
+            loop {
+                let closed = tokio::select! {
+                    event = events.recv() => match event {
+                        Some(SystemEvent::MemoryUsed { id, path, cited }) => {
+                            pending.push(UsageHit { id, path, cited, at: Local::now() });
+                            if pending.len() < FLUSH_BATCH {
+                                continue;
+                            }
+                            false
+                        }
+                        Some(_) => continue,
+                        None => true,
+                    },
+                    _ = flush.tick() => false,
+                };
+                if let Err(e) = memory.record_usage(&pending) {
+                    warn!("📈 Memory usage stats not written: {}", e);
+                }
+                pending.clear();
+                if closed {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+    use openspore_core::state::AppState;
+
+    fn usage(injected: u64, cited: u64) -> MemoryUsage {
+        MemoryUsage { injected_count: injected, cited_count: cited, ..Default::default() }
+    }
+
+    fn hit(id: &str, path: &str, cited: bool, at: DateTime<Local>) -> UsageHit {
+        UsageHit { id: id.to_string(), path: PathBuf::from(path), cited, at }
+    }
+
+    fn result(name: &str, content: &str, score: usize) -> SearchResult {
+        SearchResult { path: PathBuf::from(name), title: name.to_string(), content: content.to_string(), score }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn usefulness_is_the_smoothed_cited_share() {
+        assert!(close(usage(0, 0).usefulness(), 0.5), "unseen memories are neutral");
+        assert!(close(usage(10, 0).usefulness(), 1.0 / 12.0));
+        assert!(close(usage(10, 10).usefulness(), 11.0 / 12.0));
+        assert!(close(usage(2, 1).usefulness(), 0.5));
+        // Citations without a recorded injection (another process) stay capped
+        assert!(close(usage(0, 5).usefulness(), 1.0));
+    }
+
+    #[test]
+    fn factor_stays_within_one_plus_minus_the_weight() {
+        let mut stats = UsageStats::default();
+        stats.memories.insert("noise".into(), usage(1000, 0));
+        stats.memories.insert("gem".into(), usage(1000, 1000));
+        stats.memories.insert("fresh".into(), usage(0, 0));
+
+        assert!(close(stats.factor("unknown", 0.4), 1.0));
+        assert!(close(stats.factor("fresh", 0.4), 1.0));
+        let (noise, gem) = (stats.factor("noise", 0.4), stats.factor("gem", 0.4));
+        assert!(noise > 0.6 && noise < 0.61, "{}", noise);
+        assert!(gem > 1.39 && gem < 1.4, "{}", gem);
+        assert!(close(stats.factor("noise", 0.0), 1.0));
+    }
+
+    #[test]
+    fn rerank_promotes_cited_memories_over_noise() {
+        let (noise, gem, plain) = ("---\nid: noise\n---\nA", "---\nid: gem\n---\nB", "---\nid: plain\n---\nC");
+        let mut stats = UsageStats::default();
+        stats.memories.insert("noise".into(), usage(20, 0));
+        stats.memories.insert("gem".into(), usage(20, 18));
+        let ranked = || vec![result("noise.md", noise, 100), result("plain.md", plain, 90), result("gem.md", gem, 80)];
+
+        let mut results = ranked();
+        stats.rerank(&mut results, 0.5);
+        let order: Vec<_> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(order, ["gem.md", "plain.md", "noise.md"]);
+        assert_eq!(results[1].score, 90, "memories without stats keep their score");
+
+        let mut unchanged = ranked();
+        stats.rerank(&mut unchanged, 0.0);
+        assert_eq!(unchanged.iter().map(|r| r.score).collect::<Vec<_>>(), [100, 90, 80]);
+    }
+
+    #[test]
+    fn apply_counts_hits_and_follows_renames() {
+        let now = Local::now();
+        let mut stats = UsageStats::default();
+        stats.apply(&[
+            hit("a", "knowledge/old_name.md", false, now),
+            hit("a", "knowledge/old_name.md", false, now),
+            hit("a", "knowledge/new_name.md", true, now),
+            // Batches are not ordered in time: last_cited keeps the latest
+            hit("a", "knowledge/new_name.md", true, now - chrono::Duration::hours(1)),
+            hit("b", "knowledge/b.md", false, now),
+        ]);
+        let a = &stats.memories["a"];
+        assert_eq!((a.injected_count, a.cited_count), (2, 2));
+        assert_eq!(a.last_cited, Some(now));
+        assert_eq!(a.path, PathBuf::from("knowledge/new_name.md"));
+        assert_eq!(stats.memories["b"].last_cited, None);
+    }
+
+    #[test]
+    fn review_lists_never_cited_memories_first() {
+        let mut stats = UsageStats::default();
+        stats.memories.insert("cited".into(), usage(50, 1));
+        stats.memories.insert("rarely_injected".into(), usage(3, 0));
+        stats.memories.insert("often_injected".into(), usage(40, 0));
+        stats.memories.insert("only_cited".into(), usage(0, 2));
+        let order: Vec<_> = stats.review().into_iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(order, ["often_injected", "rarely_injected", "cited"]);
+    }
+
+    #[test]
+    fn ids_come_from_the_frontmatter_or_the_content() {
+        assert_eq!(memory_id("---\nid: 3f2a\ntype: knowledge\n---\n# Tips"), "3f2a");
+        let hashed = memory_id("# Tips\n\nNo frontmatter");
+        assert_eq!(hashed.len(), 16);
+        assert_eq!(hashed, memory_id("# Tips\n\nNo frontmatter"));
+        assert_ne!(hashed, memory_id("# Tips\n\nEdited"));
+        assert_ne!(memory_id("---\nid:\n---\n# A"), "");
+    }
+
+    #[test]
+    fn citations_match_file_names_and_titles() {
+        let memory = result("knowledge/rust_tips.md", "---\nid: x\n---\n# Borrow checker notes\n\nBody", 1);
+        assert!(cites("As rust_tips says, clone less.", &memory));
+        assert!(cites("From my RUST TIPS: clone less.", &memory));
+        assert!(cites("Per your borrow checker notes, ...", &memory));
+        assert!(!cites("Clone less.", &memory));
+        assert!(!cites("Go to the UI", &result("knowledge/ui.md", "# UI", 1)), "short names are too common");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn the_tracker_writes_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemorySystem::new(&AppState::new(AppConfig { project_root: dir.path().to_path_buf(), ..Default::default() }));
+        let tracker = memory.spawn_usage_tracker();
+        // Let it subscribe
+        for _ in 0..3 {
+            tokio::task::yield_now().await;
+        }
+        let path = PathBuf::from("knowledge/tracked.md");
+        let publish = |cited: bool| event_bus::publish(SystemEvent::MemoryUsed { id: "tracker-test".into(), path: path.clone(), cited });
+        let counts = || memory.usage_stats().memories.get("tracker-test").map(|u| (u.injected_count, u.cited_count));
+
+        for _ in 0..FLUSH_BATCH - 1 {
+            publish(false);
+        }
+        tokio::task::yield_now().await;
+        assert_eq!(counts(), None, "below a batch, nothing is written yet");
+
+        publish(false);
+        for _ in 0..3 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(counts(), Some((FLUSH_BATCH as u64, 0)), "a full batch is written at once");
+
+        publish(true);
+        publish(true);
+        tokio::task::yield_now().await;
+        assert_eq!(counts(), Some((FLUSH_BATCH as u64, 0)));
+        tokio::time::sleep(FLUSH_INTERVAL + Duration::from_secs(1)).await;
+        assert_eq!(counts(), Some((FLUSH_BATCH as u64, 2)), "the rest is written on the next tick");
+        assert!(memory.usage_stats().memories["tracker-test"].last_cited.is_some());
+        tracker.abort();
+    }
+}
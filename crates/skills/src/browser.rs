@@ -37,7 +37,8 @@ pub enum BrowserAction {
     Hover { selector: String },
     Wait { selector: Option<String>, ms: Option<u64> },
     Snapshot,
-    Screenshot,
+    /// `max_width` scales wider pages down; `quality` (1-100) saves a JPEG instead of a PNG
+    Screenshot { quality: Option<u8>, max_width: Option<u32> },
     Url,
     Title,
     Evaluate { expr: String },
@@ -45,6 +46,22 @@ pub enum BrowserAction {
     Reset,
 }
 
+/// A page rendered in the shared browser session, as readable markdown
+#[derive(Deserialize, Debug)]
+pub struct RenderedPage {
+    pub title: String,
+    /// Where the page ended up after redirects
+    pub url: String,
+    pub canonical: Option<String>,
+    pub markdown: String,
+}
+
+/// Longest wait for a rendered page to stop changing
+const RENDER_SETTLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Interval between two looks at a rendering page
+const RENDER_POLL: std::time::Duration = std::time::Duration::from_millis(500);
+
 impl BrowserSkill {
     pub fn new(preferred_browser: Option<BrowserType>) -> Self {
         Self {
@@ -56,12 +73,11 @@ impl BrowserSkill {
 
     async fn get_page(&self) -> anyhow::Result<Page> {
         let mut page_lock = self.current_page.lock().await;
-        if let Some(page) = &*page_lock {
-            if let Ok(info) = page.evaluate("1+1").await {
-                if info.value().and_then(|v| v.as_u64()) == Some(2) {
-                    return Ok(page.clone());
-                }
-            }
+        if let Some(page) = &*page_lock
+            && let Ok(info) = page.evaluate("1+1").await
+                && info.value().and_then(|v| v.as_u64()) == Some(2)
+        {
+            return Ok(page.clone());
         }
 
         info!("Starting new browser session...");
@@ -81,6 +97,48 @@ impl BrowserSkill {
         Ok(page)
     }
 
+    /// Load `url` in a new tab of the shared session (the running browser,
+    /// launched if needed), wait until the page stops changing and extract its
+    /// readable text. The tab is closed afterwards; the agent's page is untouched.
+    pub async fn render_readable(&self, url: &str) -> anyhow::Result<RenderedPage> {
+        let (browser, mut handler) = {
+            // Held like get_page() does, so two callers never launch two browsers
+            let _launch = self.current_page.lock().await;
+            self.session_manager.get_or_create_session().await?
+        };
+        tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let page = browser.new_page(url).await?;
+        let _ = tokio::time::timeout(RENDER_SETTLE_TIMEOUT, page.wait_for_navigation()).await;
+
+        // Network idle, approximated: loaded, and the text stopped growing
+        let started = std::time::Instant::now();
+        let mut last_length = None;
+        while started.elapsed() < RENDER_SETTLE_TIMEOUT {
+            let state = page.evaluate("document.readyState + '|' + (document.body ? document.body.innerText.length : 0)").await
+                .ok()
+                .and_then(|r| r.value().and_then(|v| v.as_str()).map(String::from))
+                .unwrap_or_default();
+            let (ready, length) = state.split_once('|').unwrap_or(("", "0"));
+            if ready == "complete" && length != "0" && last_length.as_deref() == Some(length) {
+                break;
+            }
+            last_length = Some(length.to_string());
+            tokio::time::sleep(RENDER_POLL).await;
+        }
+
+        let extracted = page.evaluate(resolver::readable_text_js()).await;
+        let _ = page.close().await;
+        let value = extracted?.value().cloned().ok_or_else(|| anyhow::anyhow!("The page returned no text"))?;
+        Ok(serde_json::from_value(value)?)
+    }
+
     async fn eval_all_frames(&self, page: &Page, js: &str) -> Vec<(String, Option<serde_json::Value>)> {
         let mut results = Vec::new();
         // Always try main context first
@@ -90,19 +148,19 @@ impl BrowserSkill {
 
         if let Ok(frames) = page.frames().await {
             for frame_id in frames {
-                if let Ok(ctx_id) = page.frame_execution_context(frame_id.clone()).await {
-                    if let Some(id) = ctx_id {
-                        let eval = chromiumoxide::cdp::js_protocol::runtime::EvaluateParams::builder()
-                            .expression(js)
-                            .context_id(id)
-                            .await_promise(true)
-                            .return_by_value(true)
-                            .build()
-                            .unwrap();
-
-                        if let Ok(res) = page.evaluate(eval).await {
-                            results.push((format!("{:?}", frame_id), res.value().cloned()));
-                        }
+                if let Ok(ctx_id) = page.frame_execution_context(frame_id.clone()).await
+                    && let Some(id) = ctx_id
+                {
+                    let eval = chromiumoxide::cdp::js_protocol::runtime::EvaluateParams::builder()
+                        .expression(js)
+                        .context_id(id)
+                        .await_promise(true)
+                        .return_by_value(true)
+                        .build()
+                        .unwrap();
+
+                    if let Ok(res) = page.evaluate(eval).await {
+                        results.push((format!("{:?}", frame_id), res.value().cloned()));
                     }
                 }
             }
@@ -143,13 +201,13 @@ impl BrowserSkill {
 
         for (frame_id, val) in frames_res {
             if let Some(obj) = val.and_then(|v| v.as_object().cloned()) {
-                if let Some(snap_str) = obj.get("snapshot").and_then(|s| s.as_str()) {
-                    if !snap_str.is_empty() {
-                        if frame_id != "main" {
-                            all_snapshot_lines.push(format!("--- Frame: {} ---", frame_id));
-                        }
-                        all_snapshot_lines.push(snap_str.to_string());
+                if let Some(snap_str) = obj.get("snapshot").and_then(|s| s.as_str())
+                    && !snap_str.is_empty()
+                {
+                    if frame_id != "main" {
+                        all_snapshot_lines.push(format!("--- Frame: {} ---", frame_id));
                     }
+                    all_snapshot_lines.push(snap_str.to_string());
                 }
                 if let Some(elements) = obj.get("elements").and_then(|e| e.as_object()) {
                     for (k, v) in elements {
@@ -179,6 +237,38 @@ impl Skill for BrowserSkill {
         "Interact with a web browser. Actions: navigate, click, type, fill, scroll, hover, wait, snapshot, screenshot, url, title, close, reset."
     }
 
+    fn exclusive(&self) -> bool { true }
+
+    /// Page loads and clicks act on the web; reading the page does not
+    fn audited(&self, args: &str) -> bool {
+        serde_json::from_str::<serde_json::Value>(args)
+            .ok()
+            .and_then(|v| v.get("action")?.as_str().map(str::to_lowercase))
+            .is_some_and(|action| action == "navigate" || action == "click")
+    }
+
+    fn args_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["navigate", "click", "type", "fill", "scroll", "hover", "wait", "snapshot", "screenshot", "url", "title", "evaluate", "close", "reset"]
+                },
+                "url": { "type": "string" },
+                "selector": { "type": "string", "description": "CSS selector or snapshot ref (e.g. e12)" },
+                "text": { "type": "string" },
+                "x": { "type": "integer" },
+                "y": { "type": "integer" },
+                "ms": { "type": "integer" },
+                "expr": { "type": "string" },
+                "quality": { "type": "integer", "description": "screenshot: JPEG quality 1-100 (PNG when omitted)", "minimum": 1, "maximum": 100 },
+                "max_width": { "type": "integer", "description": "screenshot: scale down to this width in pixels", "minimum": 1 }
+            },
+            "required": ["action"]
+        }))
+    }
+
     async fn execute(&self, args: &str) -> Result<String, String> {
         let action: BrowserAction = serde_json::from_str(args).map_err(|e| {
             let valid = "navigate, click, type, fill, scroll, hover, wait, snapshot, screenshot, url, title, close, reset";
@@ -219,20 +309,21 @@ impl Skill for BrowserSkill {
                         while start.elapsed() < std::time::Duration::from_secs(5) {
                             let frames_res = self.eval_all_frames(&page, &js).await;
                             for (_frame_id, val) in frames_res {
-                                        if let Some(res) = val {
-                                            if !res.is_null() {
-                                                let x = res.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                                                let y = res.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                                                let needs_js_click = res.get("js_click").and_then(|v| v.as_bool()).unwrap_or(false);
-                                                let tag_name = res.get("tag_name").and_then(|v| v.as_str()).unwrap_or("UNKNOWN").to_string();
-                                                let preview = res.get("preview").and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                                                info!("Found element <{}>: '{}' at ({}, {})", tag_name, preview, x, y);
-
-                                                if needs_js_click {
-                                                    info!("Subframe detected or robust click needed for <{}>. Injecting aligned JS click...", tag_name);
-                                                    let click_js = format!(
-                                                        "(function() {{
+                                        if let Some(res) = val
+                                            && !res.is_null()
+                                        {
+                                            let x = res.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                                            let y = res.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                                            let needs_js_click = res.get("js_click").and_then(|v| v.as_bool()).unwrap_or(false);
+                                            let tag_name = res.get("tag_name").and_then(|v| v.as_str()).unwrap_or("UNKNOWN").to_string();
+                                            let preview = res.get("preview").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+                                            info!("Found element <{}>: '{}' at ({}, {})", tag_name, preview, x, y);
+
+                                            if needs_js_click {
+                                                info!("Subframe detected or robust click needed for <{}>. Injecting aligned JS click...", tag_name);
+                                                let click_js = format!(
+                                                    "(function() {{
 {}
  const el = {};
  if (el) {{
@@ -259,25 +350,24 @@ impl Skill for BrowserSkill {
  }}
  return false;
 }})()",
-                                                        resolver::find_element_js(),
-                                                        find_js
-                                                    );
-                                                    let click_res = self.eval_all_frames(&page, &click_js).await;
-                                                    for (_, c_val) in click_res {
-                                                        if c_val.and_then(|v| v.as_bool()).unwrap_or(false) {
-                                                            match tag_name.as_str() {
-                                                                "A" | "BUTTON" | "INPUT" => return Ok(format!("Clicked <{}> '{}' (JS-injected)", tag_name, preview)),
-                                                                _ => return Ok(format!("Clicked element <{}> (JS-injected)", tag_name))
-                                                            }
+                                                    resolver::find_element_js(),
+                                                    find_js
+                                                );
+                                                let click_res = self.eval_all_frames(&page, &click_js).await;
+                                                for (_, c_val) in click_res {
+                                                    if c_val.and_then(|v| v.as_bool()).unwrap_or(false) {
+                                                        match tag_name.as_str() {
+                                                            "A" | "BUTTON" | "INPUT" => return Ok(format!("Clicked <{}> '{}' (JS-injected)", tag_name, preview)),
+                                                            _ => return Ok(format!("Clicked element <{}> (JS-injected)", tag_name))
                                                         }
                                                     }
-                                                } else {
-                                                    info!("Main frame target. Using CDP hardware click at ({}, {}) for <{}>", x, y, tag_name);
-                                                    let point = Point::new(x, y);
-                                                    page.move_mouse(point.clone()).await.map_err(|e| e.to_string())?;
-                                                    page.click(point).await.map_err(|e| e.to_string())?;
-                                                    return Ok(format!("Clicked <{}> '{}' (CDP hardware click)", tag_name, preview));
                                                 }
+                                            } else {
+                                                info!("Main frame target. Using CDP hardware click at ({}, {}) for <{}>", x, y, tag_name);
+                                                let point = Point::new(x, y);
+                                                page.move_mouse(point).await.map_err(|e| e.to_string())?;
+                                                page.click(point).await.map_err(|e| e.to_string())?;
+                                                return Ok(format!("Clicked <{}> '{}' (CDP hardware click)", tag_name, preview));
                                             }
                                         }
                             }
@@ -285,9 +375,8 @@ impl Skill for BrowserSkill {
                         }
 
                         let snapshot = self.generate_snapshot(&page).await;
-                        let truncated_snapshot: String = snapshot.chars().take(8000).collect();
-                        let hint = if snapshot.len() > 8000 { "... (truncated)" } else { "" };
-                        Err(format!("Element '{}' not found in any frame after 5s waiting.\n\nHere is a partial snapshot of the current page to help you correct the selector:\n\n{}{}", selector, truncated_snapshot, hint))
+                        let truncated_snapshot = openspore_core::fmt_utils::truncate_chars(&snapshot, 8000);
+                        Err(format!("Element '{}' not found in any frame after 5s waiting.\n\nHere is a partial snapshot of the current page to help you correct the selector:\n\n{}", selector, truncated_snapshot))
                     }
                     BrowserAction::Type { selector, text } => {
                         let find_js = self.resolve_selector_js(&selector).await;
@@ -371,13 +460,13 @@ impl Skill for BrowserSkill {
                         while start.elapsed() < std::time::Duration::from_secs(5) {
                             let frames_res = self.eval_all_frames(&page, &js).await;
                             for (_, val) in frames_res {
-                                if let Some(coords) = val {
-                                    if !coords.is_null() {
-                                        let x = coords.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                                        let y = coords.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                                        page.move_mouse(Point::new(x, y)).await.map_err(|e| e.to_string())?;
-                                        return Ok(format!("Hovered '{}'", selector));
-                                    }
+                                if let Some(coords) = val
+                                    && !coords.is_null()
+                                {
+                                    let x = coords.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                                    let y = coords.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                                    page.move_mouse(Point::new(x, y)).await.map_err(|e| e.to_string())?;
+                                    return Ok(format!("Hovered '{}'", selector));
                                 }
                             }
                             tokio::time::sleep(std::time::Duration::from_millis(500)).await;
@@ -407,11 +496,12 @@ impl Skill for BrowserSkill {
                     BrowserAction::Snapshot => {
                         Ok(self.generate_snapshot(&page).await)
                     }
-                    BrowserAction::Screenshot => {
+                    BrowserAction::Screenshot { quality, max_width } => {
                         let screenshot = page.screenshot(chromiumoxide::page::ScreenshotParams::builder().full_page(true).build()).await.map_err(|e| e.to_string())?;
-                        let path = openspore_core::path_utils::get_app_root().join("workspace").join("screenshots");
+                        let (screenshot, extension) = shrink_screenshot(screenshot, quality, max_width)?;
+                        let path = openspore_core::path_utils::get_app_root().join("workspace").join(openspore_memory::SCREENSHOTS_DIR);
                         std::fs::create_dir_all(&path).ok();
-                        let file_name = format!("screenshot_{}.png", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+                        let file_name = format!("screenshot_{}.{}", chrono::Local::now().format("%Y%m%d_%H%M%S"), extension);
                         let full_path = path.join(&file_name);
                         std::fs::write(&full_path, screenshot).map_err(|e| e.to_string())?;
                         Ok(format!("Screenshot saved to: {}", full_path.display()))
@@ -431,3 +521,29 @@ impl Skill for BrowserSkill {
         }
     }
 }
+
+/// Scale a PNG screenshot down to `max_width` and/or re-encode it as a JPEG of
+/// `quality`. Returns the bytes and their file extension.
+fn shrink_screenshot(png: Vec<u8>, quality: Option<u8>, max_width: Option<u32>) -> Result<(Vec<u8>, &'static str), String> {
+    use image::codecs::jpeg::JpegEncoder;
+    if quality.is_none() && max_width.is_none() {
+        return Ok((png, "png"));
+    }
+    let mut img = image::load_from_memory(&png).map_err(|e| format!("Screenshot could not be decoded: {}", e))?;
+    if let Some(width) = max_width.filter(|w| *w > 0 && *w < img.width()) {
+        img = img.resize(width, u32::MAX, image::imageops::FilterType::Triangle);
+    }
+    let mut out = std::io::Cursor::new(Vec::new());
+    match quality {
+        Some(q) => {
+            JpegEncoder::new_with_quality(&mut out, q.clamp(1, 100))
+                .encode_image(&img.to_rgb8())
+                .map_err(|e| format!("Screenshot could not be encoded: {}", e))?;
+            Ok((out.into_inner(), "jpg"))
+        }
+        None => {
+            img.write_to(&mut out, image::ImageFormat::Png).map_err(|e| format!("Screenshot could not be encoded: {}", e))?;
+            Ok((out.into_inner(), "png"))
+        }
+    }
+}
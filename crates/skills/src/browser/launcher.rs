@@ -274,12 +274,12 @@ impl BrowserLauncher {
 
         for _ in 0..20 {
             sleep(Duration::from_millis(500)).await;
-            if let Ok(resp) = client.get(&url).send().await {
-                if resp.status().is_success() {
-                    let json: Value = resp.json().await?;
-                    if let Some(ws_url) = json.get("webSocketDebuggerUrl").and_then(|v| v.as_str()) {
-                        return Ok(ws_url.to_string());
-                    }
+            if let Ok(resp) = client.get(&url).send().await
+                && resp.status().is_success()
+            {
+                let json: Value = resp.json().await?;
+                if let Some(ws_url) = json.get("webSocketDebuggerUrl").and_then(|v| v.as_str()) {
+                    return Ok(ws_url.to_string());
                 }
             }
         }
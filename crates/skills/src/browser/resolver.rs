@@ -247,3 +247,49 @@ pub fn find_element_js() -> &'static str {
     }
     "#
 }
+
+/// Readable text of the page as markdown: the main content (article/main when
+/// it holds most of the text), headings, paragraphs, list items, table rows and
+/// code blocks, without navigation, headers, footers, forms and hidden elements.
+/// Returns { title, url, canonical, markdown }.
+pub fn readable_text_js() -> &'static str {
+    r#"
+    (function() {
+        const SKIP = new Set(['SCRIPT','STYLE','NOSCRIPT','TEMPLATE','SVG','NAV','HEADER','FOOTER','ASIDE','FORM','BUTTON','IFRAME','SELECT','DIALOG']);
+        const BLOCK = /^(block|flex|grid|list-item|table|table-row|table-row-group|flow-root)$/;
+        const text = el => (el.innerText || '').replace(/[ \t]+/g, ' ').replace(/\s*\n\s*/g, '\n').trim();
+        const inline = t => t.replace(/\s*\n\s*/g, ' ');
+        const hidden = el => { const s = window.getComputedStyle(el); return s.display === 'none' || s.visibility === 'hidden'; };
+
+        const body = document.body;
+        let root = body;
+        const bodyLength = body ? text(body).length : 0;
+        for (const selector of ['article', 'main', '[role="main"]', '#content', '#main']) {
+            const candidate = document.querySelector(selector);
+            if (candidate && text(candidate).length >= bodyLength * 0.4) { root = candidate; break; }
+        }
+
+        const lines = [];
+        const push = line => { if (line && lines[lines.length - 1] !== line) lines.push(line); };
+        const walk = node => {
+            for (const el of node.children) {
+                if (SKIP.has(el.tagName) || hidden(el)) continue;
+                const tag = el.tagName;
+                if (/^H[1-6]$/.test(tag)) { const t = inline(text(el)); if (t) push('#'.repeat(+tag[1]) + ' ' + t); }
+                else if (tag === 'LI') { const t = inline(text(el)); if (t) push('- ' + t); }
+                else if (tag === 'PRE') { const t = (el.innerText || '').replace(/\n+$/, ''); if (t.trim()) push('```\n' + t + '\n```'); }
+                else if (tag === 'TR') { const cells = Array.from(el.children).map(c => inline(text(c))).filter(Boolean); if (cells.length) push('| ' + cells.join(' | ') + ' |'); }
+                else if (tag === 'BLOCKQUOTE') { const t = text(el); if (t) push(t.split('\n').map(l => '> ' + l).join('\n')); }
+                else if (Array.from(el.children).some(c => !SKIP.has(c.tagName) && BLOCK.test(window.getComputedStyle(c).display))) walk(el);
+                else { const t = text(el); if (t) push(t); }
+            }
+        };
+        if (root) walk(root);
+
+        let markdown = lines.join('\n\n');
+        if (!markdown && root) markdown = text(root);
+        const canonical = document.querySelector('link[rel="canonical"]');
+        return { title: document.title || '', url: location.href, canonical: canonical ? canonical.href : null, markdown: markdown };
+    })()
+    "#
+}
@@ -10,6 +10,11 @@ use super::launcher::{BrowserLauncher, BrowserType};
 pub struct SessionState {
     pub cdp_port: u16,
     pub cdp_url: String,
+    /// Browser we launched, so `openspore stop` can close only that one
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[serde(default)]
+    pub user_data_dir: Option<PathBuf>,
 }
 
 pub struct SessionManager {
@@ -53,20 +58,22 @@ impl SessionManager {
 
     pub async fn get_or_create_session(&self) -> Result<(Browser, Handler)> {
         if let Some(state) = self.load_session_state() {
-            if self.is_alive(&state).await {
-                if let Ok((browser, handler)) = Browser::connect(&state.cdp_url).await {
-                    return Ok((browser, handler));
-                }
+            if self.is_alive(&state).await
+                && let Ok((browser, handler)) = Browser::connect(&state.cdp_url).await
+            {
+                return Ok((browser, handler));
             }
             self.remove_session_state();
         }
 
         let launcher = BrowserLauncher::new(self.preferred_browser)?;
-        let (_child, cdp_url) = launcher.launch_and_wait().await?;
+        let (child, cdp_url) = launcher.launch_and_wait().await?;
 
         let state = SessionState {
             cdp_port: launcher.cdp_port,
             cdp_url: cdp_url.clone(),
+            pid: Some(child.id()),
+            user_data_dir: Some(launcher.user_data_dir.clone()),
         };
         self.save_session_state(&state)?;
 
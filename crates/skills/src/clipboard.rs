@@ -0,0 +1,81 @@
+//! Clipboard Skill (Core)
+//! Reads or replaces the system clipboard through the platform's NativeBridge:
+//! pbpaste/pbcopy on macOS, wl-clipboard or xclip on Linux, PowerShell on Windows.
+
+use super::Skill;
+use async_trait::async_trait;
+use openspore_core::fmt_utils::{humanize_bytes, truncate_bytes};
+
+/// Longest clipboard text handed to the model
+const MAX_CONTENT_BYTES: usize = 10000;
+
+pub struct ClipboardSkill;
+
+/// None reads the clipboard, Some(text) replaces it: `get`, `{"set": "..."}`
+fn parse_args(args: &str) -> Result<Option<String>, String> {
+    let args = args.trim();
+    match crate::utils::try_parse_json(args) {
+        Some(json) if json.is_object() => match json.get("set") {
+            Some(serde_json::Value::String(text)) => Ok(Some(text.clone())),
+            Some(_) => Err("\"set\" must be a string".to_string()),
+            None => match crate::utils::get_str_field(&json, "action").as_deref() {
+                None | Some("get") => Ok(None),
+                Some(other) => Err(format!("Unknown action \"{}\": use get or {{\"set\": \"...\"}}", other)),
+            },
+        },
+        _ => match args.trim_matches('"').trim().to_lowercase().as_str() {
+            "" | "get" | "read" | "paste" => Ok(None),
+            _ => Err("Usage: [CLIPBOARD: get] or [CLIPBOARD: {\"set\": \"text\"}]".to_string()),
+        },
+    }
+}
+
+#[async_trait]
+impl Skill for ClipboardSkill {
+    fn name(&self) -> &'static str { "clipboard" }
+
+    fn audited(&self, _args: &str) -> bool { true }
+
+    fn description(&self) -> &'static str {
+        "Read or replace the system clipboard. Returns JSON with success and, for get, content. \
+         Usage: [CLIPBOARD: get] reads it; [CLIPBOARD: {\"set\": \"text\"}] copies text to it."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "set": { "type": "string", "description": "Text to copy to the clipboard; omit to read the clipboard" }
+            }
+        })
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let set = match parse_args(args) {
+            Ok(set) => set,
+            Err(e) => return Ok(serde_json::json!({ "success": false, "error": e }).to_string()),
+        };
+        let bridge = openspore_io::get_bridge();
+
+        let res = match set {
+            Some(text) => match bridge.set_clipboard(&text).await {
+                Ok(()) => serde_json::json!({ "success": true, "action": "set", "length": text.len() }),
+                Err(e) => serde_json::json!({ "success": false, "action": "set", "error": e.to_string() }),
+            },
+            None => match bridge.get_clipboard().await {
+                Ok(content) => {
+                    let full_length = content.len();
+                    let content = if full_length > MAX_CONTENT_BYTES {
+                        let head = truncate_bytes(&content, MAX_CONTENT_BYTES);
+                        format!("{}...\n[Truncated {}]", head, humanize_bytes((full_length - head.len()) as u64))
+                    } else {
+                        content
+                    };
+                    serde_json::json!({ "success": true, "action": "get", "content": content, "full_length": full_length })
+                }
+                Err(e) => serde_json::json!({ "success": false, "action": "get", "error": e.to_string() }),
+            },
+        };
+        Ok(res.to_string())
+    }
+}
@@ -0,0 +1,203 @@
+//! Contacts Skill (Core)
+//! Looks up people in workspace/identity/CONTACTS.md and, with the user's
+//! approval, adds or updates them. `owner` is only set by hand.
+
+use super::Skill;
+use async_trait::async_trait;
+use openspore_memory::{Contact, ContactMatch};
+
+pub struct ContactsSkill;
+
+/// (action, arguments): JSON with `action`, or the bracket form `list`, `list <query>` or `<name>`
+fn parse_args(args: &str) -> (String, serde_json::Value) {
+    let args = args.trim();
+    match crate::utils::try_parse_json(args) {
+        Some(json) if json.is_object() => {
+            let action = crate::utils::get_str_field(&json, "action").unwrap_or_else(|| "lookup".to_string());
+            (action.to_lowercase(), json)
+        }
+        _ => {
+            let raw = args.trim_matches('"');
+            match raw.split_once(char::is_whitespace).unwrap_or((raw, "")) {
+                ("list", query) | ("search", query) => ("list".to_string(), serde_json::json!({ "query": query.trim() })),
+                _ if raw.is_empty() => ("list".to_string(), serde_json::json!({})),
+                _ => ("lookup".to_string(), serde_json::json!({ "name": raw })),
+            }
+        }
+    }
+}
+
+fn field(json: &serde_json::Value, key: &str) -> Option<String> {
+    crate::utils::get_str_field(json, key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Contact of an `add` call
+fn contact_of(json: &serde_json::Value) -> Contact {
+    let aliases = match json.get("aliases") {
+        Some(serde_json::Value::Array(items)) => items.iter().filter_map(|a| a.as_str()).map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect(),
+        Some(serde_json::Value::String(text)) => text.split(',').map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect(),
+        _ => Vec::new(),
+    };
+    Contact {
+        name: field(json, "name").unwrap_or_default(),
+        aliases,
+        telegram: field(json, "telegram").or_else(|| json.get("telegram").and_then(|v| v.as_i64()).map(|id| id.to_string())),
+        email: field(json, "email"),
+        channel: field(json, "channel").map(|c| c.to_lowercase()),
+        notes: field(json, "notes").unwrap_or_default(),
+        owner: false,
+    }
+}
+
+fn memory() -> Result<openspore_memory::MemorySystem, String> {
+    let config = openspore_core::config::AppConfig::load().map_err(|e| format!("Config error: {}", e))?;
+    let state = openspore_core::state::AppState::new(config);
+    Ok(openspore_memory::MemorySystem::new(&state))
+}
+
+#[async_trait]
+impl Skill for ContactsSkill {
+    fn name(&self) -> &'static str { "contacts" }
+
+    fn description(&self) -> &'static str {
+        "Look up people the user may want to message (names, aliases, telegram id, email, preferred channel). \
+         Usage: [CONTACTS: Anna] resolves a name or alias; [CONTACTS: list] or [CONTACTS: list <query>] lists contacts; \
+         [CONTACTS: {\"action\": \"add\", \"name\": \"Anna Schmidt\", \"aliases\": [\"Anna\"], \"telegram\": \"123\", \"email\": \"anna@example.com\", \"channel\": \"telegram\", \"notes\": \"...\"}] \
+         adds or updates one after the user approves. When a name matches several contacts, ask the user which one."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": { "type": "string", "enum": ["lookup", "list", "add"], "description": "lookup (default), list, or add (needs approval)" },
+                "name": { "type": "string", "description": "lookup/add: name or alias" },
+                "query": { "type": "string", "description": "list: filter on names, aliases and notes" },
+                "aliases": { "type": "array", "items": { "type": "string" } },
+                "telegram": { "type": "string", "description": "add: Telegram chat id" },
+                "email": { "type": "string" },
+                "channel": { "type": "string", "description": "add: preferred channel (telegram, email)" },
+                "notes": { "type": "string" }
+            }
+        })
+    }
+
+    fn requires_approval(&self, args: &str) -> bool {
+        parse_args(args).0 == "add"
+    }
+
+    fn audited(&self, args: &str) -> bool {
+        self.requires_approval(args)
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let (action, json) = parse_args(args);
+        match action.as_str() {
+            "lookup" => {
+                let name = field(&json, "name").unwrap_or_default();
+                let memory = memory()?;
+                let book = memory.contacts();
+                let res = match book.resolve(&name) {
+                    ContactMatch::Found(contact) => serde_json::json!({ "success": true, "contact": contact.to_json() }),
+                    ContactMatch::Ambiguous(candidates) => serde_json::json!({
+                        "success": false,
+                        "error": format!("\"{}\" matches several contacts; ask the user which one", name),
+                        "candidates": candidates.iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+                    }),
+                    ContactMatch::NotFound => serde_json::json!({
+                        "success": false,
+                        "error": format!("No contact \"{}\" in {}", name, openspore_memory::CONTACTS_FILE),
+                    }),
+                };
+                Ok(res.to_string())
+            }
+            "list" => {
+                let query = field(&json, "query").unwrap_or_default();
+                let memory = memory()?;
+                let book = memory.contacts();
+                let contacts: Vec<_> = book.search(&query).into_iter().map(Contact::to_json).collect();
+                Ok(serde_json::json!({ "success": true, "count": contacts.len(), "contacts": contacts }).to_string())
+            }
+            "add" => {
+                let contact = contact_of(&json);
+                if contact.name.is_empty() {
+                    return Ok(serde_json::json!({ "success": false, "error": "add needs a name" }).to_string());
+                }
+                let memory = memory()?;
+                match memory.add_contact(contact).await {
+                    Ok(stored) => Ok(serde_json::json!({ "success": true, "contact": stored.to_json() }).to_string()),
+                    Err(e) => Ok(serde_json::json!({ "success": false, "error": e.to_string() }).to_string()),
+                }
+            }
+            other => Ok(serde_json::json!({ "success": false, "error": format!("Unknown action \"{}\" (lookup, list or add)", other) }).to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::contact_book;
+
+    async fn run(args: &str) -> serde_json::Value {
+        serde_json::from_str(&ContactsSkill.execute(args).await.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn only_adding_needs_approval() {
+        assert!(ContactsSkill.requires_approval(r#"{"action": "add", "name": "Carol"}"#));
+        assert!(ContactsSkill.requires_approval(r#"{"action": "ADD", "name": "Carol"}"#));
+        for args in ["Anna", "list", "list plumber", "", r#"{"name": "Anna"}"#, r#"{"action": "list"}"#] {
+            assert!(!ContactsSkill.requires_approval(args), "{}", args);
+        }
+    }
+
+    #[tokio::test]
+    async fn lookup_resolves_or_lists_the_candidates() {
+        let _book = contact_book().await;
+        let anna = run("anna").await;
+        assert_eq!(anna["success"], true);
+        assert_eq!(anna["contact"]["name"], "Anna Schmidt");
+        assert_eq!(anna["contact"]["telegram"], "1001");
+        assert_eq!(run(r#"{"name": "BEN@example.com"}"#).await["contact"]["name"], "Ben Ortiz");
+
+        let full_name = run(r#"{"name": "Anna Schmidt"}"#).await;
+        assert_eq!(full_name["contact"]["name"], "Anna Schmidt");
+        let both = run(r#"{"name": "schmidt berg"}"#).await;
+        assert_eq!(both["success"], false);
+
+        assert_eq!(run("list anna").await["count"], 2);
+        let missing = run("Carol").await;
+        assert_eq!(missing["success"], false);
+        assert!(missing["error"].as_str().unwrap().contains("No contact \"Carol\""));
+    }
+
+    #[tokio::test]
+    async fn an_ambiguous_name_returns_every_candidate() {
+        let _book = contact_book().await;
+        let path = crate::test_support::app_root().join("workspace").join(openspore_memory::CONTACTS_FILE);
+        std::fs::write(&path, crate::test_support::CONTACTS.replace("aliases: Anna\n", "")).unwrap();
+
+        let res = run("Anna").await;
+        assert_eq!(res["success"], false);
+        assert!(res["error"].as_str().unwrap().contains("ask the user which one"));
+        let candidates: Vec<&str> = res["candidates"].as_array().unwrap().iter().map(|c| c["name"].as_str().unwrap()).collect();
+        assert_eq!(candidates, ["Anna Schmidt", "Anna Berg"]);
+    }
+
+    #[tokio::test]
+    async fn add_stores_the_contact_but_never_the_owner_flag() {
+        let _book = contact_book().await;
+        let res = run(r#"{"action": "add", "name": "Carol Diaz", "aliases": "Caro, CD", "telegram": 1003, "channel": "Telegram", "owner": true}"#).await;
+        assert_eq!(res["success"], true, "{}", res);
+        assert_eq!(res["contact"]["owner"], false);
+
+        let carol = run("caro").await;
+        assert_eq!(carol["contact"]["name"], "Carol Diaz");
+        assert_eq!(carol["contact"]["telegram"], "1003");
+        assert_eq!(carol["contact"]["channel"], "telegram");
+
+        assert_eq!(run(r#"{"action": "add", "aliases": ["x"]}"#).await["success"], false);
+        assert_eq!(run(r#"{"action": "forget", "name": "Carol"}"#).await["success"], false);
+    }
+}
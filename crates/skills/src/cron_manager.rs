@@ -23,10 +23,27 @@ struct CronParams {
 impl Skill for CronManagerSkill {
     fn name(&self) -> &'static str { "cron_manager" }
 
+    fn audited(&self, _args: &str) -> bool { true }
+
     fn description(&self) -> &'static str {
         "Manage OpenSpore automation jobs. Actions: list, add, remove. Returns JSON with success and results. Usage: [CRON_MANAGER: {\"action\": \"list\"}]"
     }
 
+    fn args_schema(&self) -> Option<Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "action": { "type": "string", "enum": ["list", "add", "remove"] },
+                "name": { "type": "string", "description": "add, remove: the job's name" },
+                "schedule": { "type": "string", "description": "add: cron expression, e.g. \"0 9 * * *\"" },
+                "script_content": { "type": "string", "description": "add: the job's Node.js script" },
+                "description": { "type": "string" }
+            },
+            "required": ["action"],
+            "additionalProperties": false
+        }))
+    }
+
     async fn execute(&self, args: &str) -> Result<String, String> {
         let mut sanitized = args.trim();
         if sanitized.starts_with('"') && sanitized.ends_with('"') && sanitized.contains('{') {
@@ -132,11 +149,11 @@ impl Skill for CronManagerSkill {
                     return Ok(res.to_string());
                 }
 
-                if let Some(job) = manifest.get(&name) {
-                    if let Some(script) = job.get("script").and_then(|s| s.as_str()) {
-                        let target = cron_dir.join(script);
-                        let _ = fs::remove_file(target).await;
-                    }
+                if let Some(job) = manifest.get(&name)
+                    && let Some(script) = job.get("script").and_then(|s| s.as_str())
+                {
+                    let target = cron_dir.join(script);
+                    let _ = fs::remove_file(target).await;
                 }
 
                 manifest.as_object_mut().unwrap().remove(&name);
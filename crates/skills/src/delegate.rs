@@ -1,42 +1,158 @@
 //! Delegate Skill (Core) - Spawn sub-spores
+//!
+//! `[DELEGATE: "task" --role="X"]` runs one sub-spore. A JSON object
+//! `{"tasks": [{"task": "...", "role": "..."}], "aggregate": "merge"|"list"}`
+//! fans out to several spores at once (still limited by the swarm)
+//! and reports every subtask, so partial failures stay visible.
+//! All delegations of one skill loader share a limit of OPENSPORE_SWARM_MAX
+//! concurrent spores; the rest wait for a permit.
 
 use super::Skill;
 use async_trait::async_trait;
+use openspore_swarm::{SporeResult, SwarmManager};
+use serde::Deserialize;
+use std::time::Duration;
 
-pub struct DelegateSkill;
+/// Most subtasks in one fan-out
+const MAX_FAN_OUT: usize = 12;
+
+const DEFAULT_ROLE: &str = "GeneralExpert";
+
+#[derive(Deserialize)]
+struct FanOut {
+    tasks: Vec<SubTask>,
+    #[serde(default)]
+    aggregate: Aggregate,
+    /// Default per-task timeout
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SubTask {
+    task: String,
+    role: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum Aggregate {
+    /// One markdown document with a section per successful subtask
+    Merge,
+    /// Outputs of successful subtasks as an array
+    #[default]
+    List,
+}
+
+pub struct DelegateSkill {
+    swarm: SwarmManager,
+}
+
+impl DelegateSkill {
+    pub fn new(swarm: SwarmManager) -> Self {
+        Self { swarm }
+    }
+}
 
 #[async_trait]
 impl Skill for DelegateSkill {
     fn name(&self) -> &'static str { "delegate" }
 
     fn description(&self) -> &'static str {
-        "Spawn a specialized sub-spore for parallel task execution. Returns JSON with success and result. Usage: [DELEGATE: \"task description\" --role=\"ExpertRole\"]"
+        "Spawn a specialized sub-spore for parallel task execution. Returns JSON with success, status, duration and result. Usage: [DELEGATE: \"task description\" --role=\"ExpertRole\"]. Fan-out: [DELEGATE: {\"tasks\": [{\"task\": \"...\", \"role\": \"...\"}, ...], \"aggregate\": \"merge\"|\"list\"}] runs all tasks concurrently and reports each one (optional \"timeout_secs\" per task or overall)."
     }
 
     async fn execute(&self, args: &str) -> Result<String, String> {
+        let swarm = &self.swarm;
+
+        if args.trim_start().starts_with('{') {
+            let fan_out: FanOut = serde_json::from_str(args.trim()).map_err(|e| format!("Invalid fan-out JSON: {}", e))?;
+            return Ok(run_fan_out(swarm, fan_out).await.to_string());
+        }
+
         let parts: Vec<&str> = args.splitn(2, "--role=").collect();
         let task = parts[0].trim().trim_matches('"').trim_matches('\'').trim();
-        let role = parts.get(1).map(|r| r.trim().trim_matches('"').trim_matches('\'')).unwrap_or("GeneralExpert");
-
-        let swarm = openspore_swarm::SwarmManager::new();
-
-        match swarm.spawn(task, role).await {
-            Ok(execution_result) => {
-                let res = serde_json::json!({
-                    "success": true,
-                    "role": role,
-                    "result": execution_result
-                });
-                Ok(res.to_string())
-            }
-            Err(e) => {
-                let res = serde_json::json!({
-                    "success": false,
-                    "error": format!("Delegation Failed: {}", e),
-                    "role": role
-                });
-                Ok(res.to_string())
-            }
-        }
+        let role = parts.get(1).map(|r| r.trim().trim_matches('"').trim_matches('\'')).unwrap_or(DEFAULT_ROLE);
+
+        let res = match swarm.spawn(task, role).await {
+            Ok(result) if result.success() => serde_json::json!({
+                "success": true,
+                "role": role,
+                "duration_ms": result.duration_ms,
+                "result": result.output
+            }),
+            Ok(result) => serde_json::json!({
+                "success": false,
+                "error": format!("Delegation Failed ({:?}): {}", result.status, result.stderr),
+                "role": role,
+                "status": result.status,
+                "exit_code": result.exit_code,
+                "duration_ms": result.duration_ms,
+                "output": result.output
+            }),
+            Err(e) => serde_json::json!({
+                "success": false,
+                "error": format!("Delegation Failed: {}", e),
+                "role": role
+            }),
+        };
+        Ok(res.to_string())
+    }
+}
+
+/// A requested timeout, or `default` when it is missing, 0 or over MAX_SPORE_TIMEOUT_SECS
+fn task_timeout(secs: Option<u64>, default: Duration) -> Duration {
+    secs.filter(|s| (1..=openspore_core::config::MAX_SPORE_TIMEOUT_SECS).contains(s))
+        .map(Duration::from_secs)
+        .unwrap_or(default)
+}
+
+async fn run_fan_out(swarm: &SwarmManager, fan_out: FanOut) -> serde_json::Value {
+    if fan_out.tasks.is_empty() || fan_out.tasks.len() > MAX_FAN_OUT {
+        return serde_json::json!({
+            "success": false,
+            "error": format!("Fan-out needs between 1 and {} tasks (got {})", MAX_FAN_OUT, fan_out.tasks.len())
+        });
+    }
+
+    let default_timeout = task_timeout(fan_out.timeout_secs, swarm.timeout());
+    let runs = fan_out.tasks.into_iter()
+        .map(|t| (
+            t.task,
+            t.role.filter(|r| !r.trim().is_empty()).unwrap_or_else(|| DEFAULT_ROLE.to_string()),
+            task_timeout(t.timeout_secs, default_timeout),
+        ))
+        .collect();
+    let results = swarm.fan_out(runs).await;
+
+    let succeeded = results.iter().filter(|r| r.success()).count();
+    let tasks: Vec<serde_json::Value> = results.iter().enumerate()
+        .map(|(index, r)| {
+            let mut entry = serde_json::to_value(r).unwrap_or_default();
+            entry["index"] = index.into();
+            entry
+        })
+        .collect();
+
+    serde_json::json!({
+        "success": succeeded == results.len(),
+        "succeeded": succeeded,
+        "failed": results.len() - succeeded,
+        "tasks": tasks,
+        "aggregate": aggregate(&results, fan_out.aggregate)
+    })
+}
+
+fn aggregate(results: &[SporeResult], mode: Aggregate) -> serde_json::Value {
+    let done = results.iter().enumerate().filter(|(_, r)| r.success());
+    match mode {
+        Aggregate::List => done
+            .map(|(index, r)| serde_json::json!({ "index": index, "role": r.role, "output": r.output }))
+            .collect(),
+        Aggregate::Merge => done
+            .map(|(index, r)| format!("## [{}] {}: {}\n\n{}", index, r.role, openspore_core::fmt_utils::preview(&r.task, 80), r.output))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+            .into(),
     }
 }
@@ -13,6 +13,8 @@ pub struct DiffPatchSkill;
 impl Skill for DiffPatchSkill {
     fn name(&self) -> &'static str { "diff_patch" }
 
+    fn destructive(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
         "Apply a GNU-style diff/patch to a file. Returns JSON with success, message, and path. Usage: [DIFF_PATCH: \"/path/to/file|||patch_text\"]"
     }
@@ -11,10 +11,24 @@ pub struct EditFileSkill;
 impl Skill for EditFileSkill {
     fn name(&self) -> &'static str { "edit_file" }
 
+    fn destructive(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
         "Replace targeted text in a file. Returns JSON with success, message, and path. Usage: [EDIT_FILE: \"/path\" --target=\"old text\" --replacement=\"new text\"]"
     }
 
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "target": { "type": "string", "description": "Exact text to replace (must match the file)" },
+                "replacement": { "type": "string" }
+            },
+            "required": ["path", "target", "replacement"]
+        })
+    }
+
     async fn execute(&self, args: &str) -> Result<String, String> {
         let (path_raw, target, replacement) = if let Some(json_args) = crate::utils::try_parse_json(args) {
             let p = crate::utils::get_str_field(&json_args, "path").ok_or("JSON missing 'path'")?;
@@ -1,18 +1,29 @@
 //! Exec Skill - Run shell commands (Core)
 
 use super::Skill;
+use crate::exec_policy::ExecPolicy;
 use async_trait::async_trait;
 use tokio::process::Command;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use std::process::Stdio;
 use tokio::time::Duration;
 
-pub struct ExecSkill;
+pub struct ExecSkill {
+    policy: ExecPolicy,
+}
+
+impl ExecSkill {
+    pub fn new(config: &openspore_core::config::AppConfig) -> Self {
+        Self { policy: ExecPolicy::from_config(config) }
+    }
+}
 
 #[async_trait]
 impl Skill for ExecSkill {
     fn name(&self) -> &'static str { "exec" }
 
+    fn destructive(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
         "Execute a shell command with virtual statefulness and optional pattern waiting. Usage: [EXEC: \"command\", \"optional_pattern\", \"optional_timeout_sec\"]"
     }
@@ -25,7 +36,7 @@ impl Skill for ExecSkill {
 
         // 2. Argument Parsing
         let args_list = crate::utils::parse_smart_args(args);
-        let raw_cmd = args_list.get(0).cloned().unwrap_or_default();
+        let raw_cmd = args_list.first().cloned().unwrap_or_default();
         let wait_for = args_list.get(1).cloned();
         let timeout_secs = args_list.get(2).and_then(|s| s.parse::<u64>().ok()).unwrap_or(30);
 
@@ -33,10 +44,16 @@ impl Skill for ExecSkill {
              return Ok(serde_json::json!({ "success": true, "stdout": "", "stderr": "" }).to_string());
         }
 
-        // 3. Handle 'cd' (Virtual Statefulness update)
+        // 3. Policy: denied commands, confinement (see exec_policy)
+        if let Err(refusal) = self.policy.check(&raw_cmd, &current_cwd) {
+            tracing::warn!("🛑 Exec policy refused [{}]: {}", raw_cmd, refusal.reason);
+            return Ok(refusal.to_json().to_string());
+        }
+
+        // 4. Handle 'cd' (Virtual Statefulness update)
         let trimmed_cmd = raw_cmd.trim();
-        if trimmed_cmd.starts_with("cd ") {
-            let target_path_str = trimmed_cmd[3..].trim().trim_matches('"').trim_matches('\'');
+        if let Some(cd_target) = trimmed_cmd.strip_prefix("cd ") {
+            let target_path_str = cd_target.trim().trim_matches('"').trim_matches('\'');
             let target_path = openspore_core::path_utils::ensure_absolute(target_path_str);
 
             if target_path.exists() && target_path.is_dir() {
@@ -52,7 +69,7 @@ impl Skill for ExecSkill {
             }
         }
 
-        // 4. Intelligent Binary & PATH setup
+        // 5. Intelligent Binary & PATH setup
         let engine_bin = project_root.join("crates/target/release");
         let path = std::env::var("PATH").unwrap_or_default();
 
@@ -61,7 +78,7 @@ impl Skill for ExecSkill {
         let expanded_extra = openspore_core::path_utils::expand_tilde(extra_paths);
         let new_path = format!("{}:{}:{}", engine_bin.to_string_lossy(), expanded_extra, path);
 
-        // 5. SAFE MODE CHECK
+        // 6. SAFE MODE CHECK
         if crate::utils::is_safe_mode_active() {
             let dangerous_keywords = ["rm ", "mv ", "sed ", "cargo build", "git checkout", "git reset", "git clean", "chmod ", "chown "];
             let lower_cmd = raw_cmd.to_lowercase();
@@ -77,10 +94,11 @@ impl Skill for ExecSkill {
             }
         }
 
-        // 6. Execution Path
+        // 7. Execution Path
         let mut cmd = Command::new("sh");
         cmd.arg("-c").arg(&raw_cmd)
-           .envs(std::env::vars())
+           .env_clear()
+           .envs(self.policy.child_env(std::env::vars()))
            .env("PATH", new_path)
            .current_dir(&current_cwd)
            .stdout(Stdio::piped())
@@ -107,9 +125,8 @@ impl Skill for ExecSkill {
             tokio::select! {
                 line = stdout_reader.next_line() => {
                     if let Ok(Some(l)) = line {
-                        if let Some(ref p) = wait_for {
-                            if l.contains(p) { pattern_found = true; }
-                        }
+                        if let Some(ref p) = wait_for
+                            && l.contains(p) { pattern_found = true; }
                         captured_stdout.push(l);
                         if pattern_found { break; }
                     } else {
@@ -118,9 +135,8 @@ impl Skill for ExecSkill {
                 }
                 line = stderr_reader.next_line() => {
                     if let Ok(Some(l)) = line {
-                         if let Some(ref p) = wait_for {
-                            if l.contains(p) { pattern_found = true; }
-                        }
+                         if let Some(ref p) = wait_for
+                            && l.contains(p) { pattern_found = true; }
                         captured_stderr.push(l);
                         if pattern_found { break; }
                     }
@@ -144,3 +160,24 @@ impl Skill for ExecSkill {
         }).to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+
+    #[tokio::test]
+    async fn sudo_shutdown_is_refused_and_ls_workspace_runs() {
+        let root = crate::test_support::app_root();
+        std::fs::create_dir_all(root.join("workspace/knowledge")).unwrap();
+        let skill = ExecSkill::new(&AppConfig { project_root: root.clone(), exec_confine_to_workspace: true, ..Default::default() });
+
+        let refused: serde_json::Value = serde_json::from_str(&skill.execute(r#""sudo shutdown -h now""#).await.unwrap()).unwrap();
+        assert_eq!(refused["success"], false);
+        assert_eq!(refused["blocked"]["rule"], "builtin:privilege");
+
+        let listed: serde_json::Value = serde_json::from_str(&skill.execute(r#""ls workspace""#).await.unwrap()).unwrap();
+        assert_eq!(listed["success"], true, "{}", listed);
+        assert!(listed["stdout"].as_str().unwrap().contains("knowledge"));
+    }
+}
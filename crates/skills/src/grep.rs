@@ -11,6 +11,8 @@ pub struct GrepSkill;
 impl Skill for GrepSkill {
     fn name(&self) -> &'static str { "grep" }
 
+    fn read_only(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
         "Search for text patterns in files recursively. Returns matching lines with file paths and line numbers. Usage: [GREP: \"pattern\" --path=\"/search/dir\"] or [GREP: \"pattern\"] (searches from CWD). Supports --include=\"*.ext\" for filtering."
     }
@@ -19,7 +21,6 @@ impl Skill for GrepSkill {
         let args_str = args.trim();
 
         // Parse arguments
-        let pattern;
         let mut search_path = crate::utils::get_virtual_cwd().to_string_lossy().to_string();
         let mut include_glob = String::new();
 
@@ -45,7 +46,7 @@ impl Skill for GrepSkill {
         }
 
         // Remaining text is the pattern
-        pattern = remaining.trim().trim_matches('"').trim_matches('\'').to_string();
+        let pattern = remaining.trim().trim_matches('"').trim_matches('\'').to_string();
 
         if pattern.is_empty() {
             return Ok(serde_json::json!({
@@ -3,17 +3,20 @@
 //! Hybrid architecture:
 //! 1. Core skills (hardcoded in Rust) - fast, reliable
 //! 2. Plugin skills (JS/shell in ~/.openspore/skills/) - dynamic, extensible
+//! 3. WASM plugin skills (.wasm in ~/.openspore/skills/) - sandboxed, metered
 
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
 use tokio::process::Command;
 use tracing::info;
 use openspore_core::config::AppConfig;
 
 // Core skill modules (hardcoded in Rust)
 pub mod exec;
+pub mod exec_policy;
 pub mod grep;
 pub mod read_file;
 pub mod write_file;
@@ -21,14 +24,26 @@ pub mod edit_file;
 pub mod list_dir;
 pub mod purge;
 pub mod web_fetch;
+pub mod web_search;
 pub mod search;
 pub mod delegate;
 pub mod telegram_send;
+pub mod contacts;
+pub mod clipboard;
+pub mod screenshot;
+pub mod music;
 pub mod diff_patch;
 pub mod cron_manager;
 pub mod submit_skill;
 pub mod browser;
+pub mod skill_help;
+pub mod new_from_template;
+pub mod run_pipeline;
+pub mod wasm_plugin;
+pub mod schema;
 pub mod utils;
+#[cfg(test)]
+mod test_support;
 
 use crate::browser::launcher::BrowserType;
 
@@ -43,6 +58,57 @@ pub trait Skill: Send + Sync {
 
     /// Execute the skill with given arguments
     async fn execute(&self, args: &str) -> Result<String, String>;
+
+    /// JSON Schema the arguments must match. The tool loop checks them before
+    /// `execute` and answers a mismatch with an argument error carrying the
+    /// schema, so the model can correct the call. None (the default) passes
+    /// the arguments through unchecked.
+    fn args_schema(&self) -> Option<serde_json::Value> { None }
+
+    /// JSON Schema of the arguments for native (OpenAI-style) function calling.
+    /// The default is `args_schema()`, else the classic bracket argument
+    /// wrapped in a single `args` string.
+    fn parameters_schema(&self) -> serde_json::Value {
+        if let Some(schema) = self.args_schema() {
+            return schema;
+        }
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "args": {
+                    "type": "string",
+                    "description": "The argument exactly as it would appear in [SKILL_NAME: args] (a path, a command, or a JSON string)."
+                }
+            },
+            "required": ["args"]
+        })
+    }
+
+    /// Stateful skills (e.g. the shared browser) must not run concurrently with themselves.
+    /// The tool loop serializes every call of an exclusive skill within a batch.
+    fn exclusive(&self) -> bool { false }
+
+    /// Skills that change or delete files, run commands or wipe state.
+    /// With APPROVAL_MODE=destructive only these wait for the user's approval.
+    fn destructive(&self) -> bool { false }
+
+    /// Calls that wait for the user's approval whatever APPROVAL_MODE says,
+    /// such as messages to anyone but the owner.
+    fn requires_approval(&self, _args: &str) -> bool { false }
+
+    /// Calls recorded in the audit log (workspace/audit). Destructive skills
+    /// always are; others opt in, possibly only for some arguments.
+    fn audited(&self, _args: &str) -> bool { self.destructive() }
+
+    /// Skills that only look (files, search, web pages). Plan mode may still
+    /// run them when DRY_RUN_READ_ONLY is set.
+    fn read_only(&self) -> bool { false }
+
+    /// Files a plugin is loaded from (watched by `openspore skills test --watch`); none for core skills
+    fn source_files(&self) -> Vec<PathBuf> { Vec::new() }
+
+    /// JSON Schema of the result, when the skill declares one
+    fn output_schema(&self) -> Option<serde_json::Value> { None }
 }
 
 /// Plugin skill - loads single-file JS/shell scripts from ~/.openspore/skills/
@@ -63,6 +129,10 @@ impl Skill for PluginSkill {
         Box::leak(self.description.clone().into_boxed_str())
     }
 
+    fn source_files(&self) -> Vec<PathBuf> {
+        vec![self.script_path.clone()]
+    }
+
     async fn execute(&self, args: &str) -> Result<String, String> {
         let ext = self.script_path.extension()
             .and_then(|e| e.to_str())
@@ -99,6 +169,12 @@ pub struct AgentSkill {
     pub description: String,
     pub instructions: String,
     pub script_path: Option<PathBuf>,
+    /// The skill's directory (holding SKILL.md)
+    pub dir: PathBuf,
+    /// Read from the file SKILL.md names in `output_schema:`
+    pub output_schema: Option<serde_json::Value>,
+    /// Read from the file SKILL.md names in `args_schema:`
+    pub args_schema: Option<serde_json::Value>,
 }
 
 #[async_trait]
@@ -113,6 +189,18 @@ impl Skill for AgentSkill {
         Box::leak(combined.into_boxed_str())
     }
 
+    fn source_files(&self) -> Vec<PathBuf> {
+        std::iter::once(self.dir.join("SKILL.md")).chain(self.script_path.clone()).collect()
+    }
+
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        self.output_schema.clone()
+    }
+
+    fn args_schema(&self) -> Option<serde_json::Value> {
+        self.args_schema.clone()
+    }
+
     async fn execute(&self, args: &str) -> Result<String, String> {
         if let Some(ref script_path) = self.script_path {
             let ext = script_path.extension().and_then(|e| e.to_str()).unwrap_or("");
@@ -149,6 +237,23 @@ impl Skill for AgentSkill {
     }
 }
 
+/// Kills a script and everything it started when its call is given up on
+/// (a timeout drops the future before the script ends)
+struct ScriptGuard {
+    pid: Option<u32>,
+    finished: bool,
+}
+
+impl Drop for ScriptGuard {
+    fn drop(&mut self) {
+        if let (false, Some(pid)) = (self.finished, self.pid) {
+            let mut tree = openspore_core::process::descendants(pid);
+            tree.push(pid);
+            openspore_core::process::kill_all(&tree);
+        }
+    }
+}
+
 /// Helper to execute a process and return JSON result
 async fn execute_process(program: &str, args: &[String]) -> Result<String, String> {
     let root = openspore_core::path_utils::get_app_root();
@@ -162,13 +267,23 @@ async fn execute_process(program: &str, args: &[String]) -> Result<String, Strin
     let expanded_extra = openspore_core::path_utils::expand_tilde(extra_paths);
     let new_path = format!("{}:{}:{}", engine_bin.to_string_lossy(), expanded_extra, path);
 
-    let output_res = Command::new(program)
+    let child = Command::new(program)
         .args(args)
         .envs(std::env::vars())
         .env("PATH", new_path)
         .current_dir(&current_cwd)
-        .output()
-        .await;
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn();
+    let output_res = match child {
+        Ok(child) => {
+            let mut guard = ScriptGuard { pid: child.id(), finished: false };
+            let output = child.wait_with_output().await;
+            guard.finished = true;
+            output
+        }
+        Err(e) => Err(e),
+    };
 
     match output_res {
         Ok(output) => {
@@ -195,15 +310,57 @@ async fn execute_process(program: &str, args: &[String]) -> Result<String, Strin
     }
 }
 
+/// "core", "agent" (an AgentSkill directory) or "plugin" (a single script)
+pub fn skill_kind(skill: &dyn Skill) -> &'static str {
+    let sources = skill.source_files();
+    if sources.is_empty() {
+        "core"
+    } else if sources.iter().any(|p| p.ends_with("SKILL.md")) {
+        "agent"
+    } else {
+        "plugin"
+    }
+}
+
+/// Arguments checked against the skill's `args_schema()`. The error is the
+/// skill-style JSON result the model gets instead of running the skill: what
+/// is wrong, and the schema to retry with.
+pub fn check_args(skill: &dyn Skill, args: &str) -> Result<(), String> {
+    let Some(schema) = skill.args_schema() else { return Ok(()) };
+    let problems = schema::validate_args(args, &schema);
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(serde_json::json!({
+        "success": false,
+        "error": format!("argument error: {}", problems.join("; ")),
+        "problems": problems,
+        "schema": schema,
+        "hint": format!("Call {} again with JSON arguments matching the schema.", skill.name().to_uppercase()),
+    }).to_string())
+}
+
+/// Default character budget for the detailed part of the skills block
+const DEFAULT_SKILL_PROMPT_BUDGET: usize = 3000;
+
+/// Skills that always get their full description (basic file/shell work)
+const ESSENTIAL_SKILLS: [&str; 4] = ["read_file", "write_file", "edit_file", "list_dir"];
+
 /// Skill Registry - loads core skills + plugin skills
 pub struct SkillLoader {
-    skills: HashMap<String, Box<dyn Skill>>,
+    skills: HashMap<String, Arc<dyn Skill>>,
+    /// Core skills, restored on reload (a plugin may have replaced one)
+    core: HashMap<String, Arc<dyn Skill>>,
     plugin_dir: PathBuf,
+    docs: skill_help::SkillDocs,
+    usage_counts: std::sync::Mutex<HashMap<String, usize>>,
+    prompt_budget: usize,
+    wasm_limits: wasm_plugin::WasmLimits,
 }
 
 impl SkillLoader {
     pub fn new(config: AppConfig) -> Self {
-        let mut skills: HashMap<String, Box<dyn Skill>> = HashMap::new();
+        let mut skills: HashMap<String, Arc<dyn Skill>> = HashMap::new();
 
         let preferred_browser = config.browser_type.as_deref()
             .and_then(|s| {
@@ -215,25 +372,38 @@ impl SkillLoader {
             tracing::error!("Failed to parse browser type: {:?}", config.browser_type);
         }
 
+        // One browser session, shared by the browser skill and web_fetch's rendering
+        let browser = Arc::new(browser::BrowserSkill::new(preferred_browser));
+
         // Register core skills (hardcoded in Rust)
-        let core_skills: Vec<Box<dyn Skill>> = vec![
-            Box::new(exec::ExecSkill),
-            Box::new(read_file::ReadFileSkill),
-            Box::new(write_file::WriteFileSkill),
-            Box::new(edit_file::EditFileSkill),
-            Box::new(list_dir::ListDirSkill),
-            Box::new(purge::PurgeSkill),
-            Box::new(web_fetch::WebFetchSkill),
-            Box::new(search::SearchSkill),
-            Box::new(delegate::DelegateSkill),
-            Box::new(telegram_send::TelegramSendSkill),
-            Box::new(diff_patch::DiffPatchSkill),
-            Box::new(cron_manager::CronManagerSkill),
-            Box::new(submit_skill::SubmitSkill),
-            Box::new(browser::BrowserSkill::new(preferred_browser)),
-            Box::new(grep::GrepSkill),
+        let core_skills: Vec<Arc<dyn Skill>> = vec![
+            Arc::new(exec::ExecSkill::new(&config)),
+            Arc::new(read_file::ReadFileSkill),
+            Arc::new(write_file::WriteFileSkill),
+            Arc::new(edit_file::EditFileSkill),
+            Arc::new(list_dir::ListDirSkill),
+            Arc::new(purge::PurgeSkill),
+            Arc::new(web_fetch::WebFetchSkill::new(browser.clone())),
+            Arc::new(web_search::WebSearchSkill),
+            Arc::new(search::SearchSkill),
+            Arc::new(delegate::DelegateSkill::new(openspore_swarm::SwarmManager::from_config(&config))),
+            Arc::new(telegram_send::TelegramSendSkill),
+            Arc::new(contacts::ContactsSkill),
+            Arc::new(clipboard::ClipboardSkill),
+            Arc::new(screenshot::ScreenshotSkill),
+            Arc::new(music::MusicSkill),
+            Arc::new(diff_patch::DiffPatchSkill),
+            Arc::new(cron_manager::CronManagerSkill),
+            Arc::new(submit_skill::SubmitSkill),
+            browser,
+            Arc::new(grep::GrepSkill),
+            Arc::new(new_from_template::NewFromTemplateSkill),
+            Arc::new(run_pipeline::RunPipelineSkill),
         ];
 
+        let docs = skill_help::SkillDocs::default();
+        skills.insert("skill_help".to_string(), Arc::new(skill_help::SkillHelpSkill { docs: docs.clone() }));
+
         for skill in core_skills {
             skills.insert(skill.name().to_lowercase(), skill);
         }
@@ -242,20 +412,40 @@ impl SkillLoader {
         let root = openspore_core::path_utils::get_app_root();
         let plugin_dir = root.join("skills");
 
-        let mut loader = Self { skills, plugin_dir };
+        let mut loader = Self {
+            core: skills.clone(),
+            skills,
+            plugin_dir,
+            docs,
+            usage_counts: std::sync::Mutex::new(HashMap::new()),
+            prompt_budget: config.skill_prompt_budget.unwrap_or(DEFAULT_SKILL_PROMPT_BUDGET),
+            wasm_limits: wasm_plugin::WasmLimits::from_config(&config),
+        };
         loader.load_plugins();
+        loader.refresh_docs();
         loader
     }
 
-    /// Load plugin skills from ~/.openspore/skills/
-    fn load_plugins(&mut self) {
+    /// Sync the SKILL_HELP catalog with the currently registered skills
+    fn refresh_docs(&self) {
+        if let Ok(mut docs) = self.docs.write() {
+            docs.clear();
+            for (name, skill) in &self.skills {
+                docs.insert(name.clone(), skill.description().to_string());
+            }
+        }
+    }
+
+    /// Load plugin skills from ~/.openspore/skills/; returns how many were loaded
+    fn load_plugins(&mut self) -> usize {
+        let mut loaded = 0;
         if !self.plugin_dir.exists() {
-            return;
+            return loaded;
         }
 
         let entries = match std::fs::read_dir(&self.plugin_dir) {
             Ok(e) => e,
-            Err(_) => return,
+            Err(_) => return loaded,
         };
 
         for entry in entries.flatten() {
@@ -263,6 +453,17 @@ impl SkillLoader {
 
             if path.is_file() {
                 let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                if ext == "wasm" {
+                    match wasm_plugin::WasmSkill::load(&path, self.wasm_limits) {
+                        Ok(skill) => {
+                            info!("🔌 Loaded WASM plugin skill: {} from {:?}", skill.name, path);
+                            self.skills.insert(skill.name.to_lowercase(), Arc::new(skill));
+                            loaded += 1;
+                        }
+                        Err(e) => tracing::warn!("Skipping WASM plugin {:?}: {:#}", path, e),
+                    }
+                    continue;
+                }
                 if !["js", "sh", "py"].contains(&ext) {
                     continue;
                 }
@@ -291,42 +492,51 @@ impl SkillLoader {
                     script_path: path,
                 };
 
-                self.skills.insert(name.to_lowercase(), Box::new(plugin));
+                self.skills.insert(name.to_lowercase(), Arc::new(plugin));
+                loaded += 1;
             } else if path.is_dir() {
                 // AgentSkill (Directory Compatibility)
                 let skill_md_path = path.join("SKILL.md");
-                if skill_md_path.exists() {
-                    if let Ok(content) = std::fs::read_to_string(&skill_md_path) {
-                        let (metadata, instructions) = parse_skill_md(&content);
-
-                        let name = metadata.get("name")
-                            .cloned()
-                            .unwrap_or_else(|| path.file_name().and_then(|s| s.to_str()).unwrap_or("unknown").to_string());
+                if skill_md_path.exists()
+                    && let Ok(content) = std::fs::read_to_string(&skill_md_path)
+                {
+                    let (metadata, instructions) = parse_skill_md(&content);
 
-                        let description = metadata.get("description")
-                            .cloned()
-                            .unwrap_or_else(|| format!("AgentSkill: {}", name));
+                    let name = metadata.get("name")
+                        .cloned()
+                        .unwrap_or_else(|| path.file_name().and_then(|s| s.to_str()).unwrap_or("unknown").to_string());
 
-                        // Find entrypoint script
-                        let script_path = find_skill_script(&path, &name);
+                    let description = metadata.get("description")
+                        .cloned()
+                        .unwrap_or_else(|| format!("AgentSkill: {}", name));
 
-                        info!("📂 Loaded AgentSkill: {} from {:?}", name, path);
-                        if let Some(ref sp) = script_path {
-                            info!("   -> Entrypoint: {:?}", sp);
-                        }
-
-                        let skill = AgentSkill {
-                            name: name.clone(),
-                            description,
-                            instructions,
-                            script_path,
-                        };
+                    // Find entrypoint script
+                    let script_path = find_skill_script(&path, &name);
 
-                        self.skills.insert(name.to_lowercase(), Box::new(skill));
+                    info!("📂 Loaded AgentSkill: {} from {:?}", name, path);
+                    if let Some(ref sp) = script_path {
+                        info!("   -> Entrypoint: {:?}", sp);
                     }
+
+                    let output_schema = metadata.get("output_schema").and_then(|file| load_schema(&path.join(file)));
+                    let args_schema = metadata.get("args_schema").and_then(|file| load_schema(&path.join(file)));
+
+                    let skill = AgentSkill {
+                        name: name.clone(),
+                        description,
+                        instructions,
+                        script_path,
+                        dir: path.clone(),
+                        output_schema,
+                        args_schema,
+                    };
+
+                    self.skills.insert(name.to_lowercase(), Arc::new(skill));
+                    loaded += 1;
                 }
             }
         }
+        loaded
     }
 }
 
@@ -360,6 +570,24 @@ fn parse_skill_md(content: &str) -> (HashMap<String, String>, String) {
     (metadata, instructions)
 }
 
+/// JSON Schema file of an AgentSkill (None, with a warning, when unreadable)
+fn load_schema(path: &std::path::Path) -> Option<serde_json::Value> {
+    let parsed = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).map_err(|e| e.to_string()));
+    match parsed {
+        Ok(schema) if schema.is_object() => Some(schema),
+        Ok(_) => {
+            tracing::warn!("Output schema {:?} is not a JSON object, ignored", path);
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Output schema {:?} unreadable, ignored: {}", path, e);
+            None
+        }
+    }
+}
+
 /// Find a script within an AgentSkill folder
 fn find_skill_script(dir: &std::path::Path, skill_name: &str) -> Option<PathBuf> {
     let extensions = ["js", "sh", "py"];
@@ -393,9 +621,16 @@ fn find_skill_script(dir: &std::path::Path, skill_name: &str) -> Option<PathBuf>
 }
 
 impl SkillLoader {
-    /// Get a skill by name (case-insensitive)
-    pub fn get(&self, name: &str) -> Option<&dyn Skill> {
-        self.skills.get(&name.to_lowercase()).map(|s| s.as_ref())
+    /// Get a skill by name (case-insensitive); it stays usable after a reload
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Skill>> {
+        self.skills.get(&name.to_lowercase()).cloned()
+    }
+
+    /// Name and description of every skill, sorted by name
+    pub fn summaries(&self) -> Vec<(&'static str, &'static str)> {
+        let mut summaries: Vec<(&'static str, &'static str)> = self.skills.values().map(|s| (s.name(), s.description())).collect();
+        summaries.sort();
+        summaries
     }
 
     /// Generate system prompt listing available skills, optionally validating against an exclusion list.
@@ -411,14 +646,180 @@ impl SkillLoader {
         prompt
     }
 
-    /// Reload plugin skills (hot reload)
-    pub fn reload_plugins(&mut self) {
-        // Remove existing plugins (keep core skills)
-        let core_names: Vec<String> = ["exec", "read_file", "write_file", "edit_file", "list_dir", "purge",
-                                        "web_fetch", "search", "delegate", "telegram_send", "diff_patch", "cron_manager", "submit_skill", "browser", "grep"]
-            .iter().map(|s| s.to_string()).collect();
+    /// `tools` array for native function calling (OpenAI format)
+    pub fn get_tool_definitions(&self, excluded_skills: &[&str]) -> serde_json::Value {
+        let mut names: Vec<&String> = self.skills.keys().collect();
+        names.sort();
+
+        let tools: Vec<serde_json::Value> = names.into_iter()
+            .filter_map(|key| self.skills.get(key))
+            .filter(|skill| !excluded_skills.contains(&skill.name()))
+            // Function names must match ^[a-zA-Z0-9_-]{1,64}$
+            .filter(|skill| {
+                let n = skill.name();
+                !n.is_empty() && n.len() <= 64 && n.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            })
+            .map(|skill| serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": skill.name().to_lowercase(),
+                    "description": skill.description(),
+                    "parameters": skill.parameters_schema(),
+                }
+            }))
+            .collect();
+
+        serde_json::Value::Array(tools)
+    }
+
+    /// Record that a skill was executed (feeds the relevance ranking)
+    pub fn record_use(&self, name: &str) {
+        if let Ok(mut counts) = self.usage_counts.lock() {
+            *counts.entry(name.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    /// Tiered skills block: a one-line index of every skill, plus full descriptions
+    /// only for the most relevant ones (within the configured character budget).
+    pub fn get_tiered_system_prompt(&self, excluded_skills: &[&str], user_prompt: &str) -> String {
+        let mut names: Vec<&str> = self.skills.values()
+            .map(|s| s.name())
+            .filter(|n| !excluded_skills.contains(n))
+            .collect();
+        names.sort();
+
+        let ranked = self.rank_skills(&names, user_prompt);
+
+        let mut detailed = String::new();
+        let mut detailed_names = Vec::new();
+        for name in ranked {
+            let Some(skill) = self.get(name) else { continue };
+            let line = format!("- [{}]: {}\n", name.to_uppercase(), skill.description());
+            let essential = ESSENTIAL_SKILLS.contains(&name.to_lowercase().as_str());
+            if !essential && detailed.len() + line.len() > self.prompt_budget {
+                continue;
+            }
+            detailed.push_str(&line);
+            detailed_names.push(name);
+        }
+
+        let index = names.iter().map(|n| n.to_uppercase()).collect::<Vec<_>>().join(", ");
+        let mut prompt = format!("Available Skills (index): {}\n\nSkill Details:\n{}", index, detailed);
+
+        if detailed_names.len() < names.len() {
+            prompt.push_str("\nSkills without details above are still available. Use [SKILL_HELP: name] to get their full description before using them.\n");
+        }
+
+        prompt
+    }
+
+    /// Rank skills by keyword overlap with the prompt and by recent usage
+    fn rank_skills<'a>(&self, names: &[&'a str], user_prompt: &str) -> Vec<&'a str> {
+        let prompt_words: std::collections::HashSet<String> = user_prompt
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 3)
+            .map(|w| w.to_string())
+            .collect();
+
+        let counts = self.usage_counts.lock().map(|c| c.clone()).unwrap_or_default();
+
+        let mut scored: Vec<(&'a str, f64)> = names.iter().map(|&name| {
+            let lower = name.to_lowercase();
+            let description = self.get(name).map(|s| s.description().to_lowercase()).unwrap_or_default();
+
+            let mut score = 0.0;
+            if ESSENTIAL_SKILLS.contains(&lower.as_str()) {
+                score += 100.0;
+            }
+            if lower.split('_').any(|part| prompt_words.contains(part)) {
+                score += 10.0;
+            }
+            score += description
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| prompt_words.contains(*w))
+                .collect::<std::collections::HashSet<_>>()
+                .len() as f64 * 2.0;
+            score += (*counts.get(&lower).unwrap_or(&0) as f64).ln_1p();
+
+            (name, score)
+        }).collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(b.0)));
+        scored.into_iter().map(|(n, _)| n).collect()
+    }
+
+    /// Add a skill that is neither core nor a plugin file (embedders, tests).
+    /// It is dropped again by reload_plugins().
+    pub fn register(&mut self, skill: Arc<dyn Skill>) {
+        self.skills.insert(skill.name().to_lowercase(), skill);
+    }
+
+    /// Reload plugin skills (hot reload): core skills stay, plugins are read
+    /// again from the plugin directory. Returns how many plugins were loaded.
+    pub fn reload_plugins(&mut self) -> usize {
+        self.skills = self.core.clone();
+        let count = self.load_plugins();
+        self.refresh_docs();
+
+        openspore_core::event_bus::publish(openspore_core::event_bus::SystemEvent::SkillReloaded { count });
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loader(budget: usize) -> SkillLoader {
+        crate::test_support::app_root();
+        SkillLoader::new(AppConfig { skill_prompt_budget: Some(budget), ..Default::default() })
+    }
+
+    fn position(ranked: &[&str], name: &str) -> usize {
+        ranked.iter().position(|n| *n == name).unwrap()
+    }
+
+    #[test]
+    fn essentials_lead_then_skills_the_prompt_names() {
+        let loader = loader(DEFAULT_SKILL_PROMPT_BUDGET);
+        let names: Vec<&str> = loader.summaries().into_iter().map(|(n, _)| n).collect();
+
+        let ranked = loader.rank_skills(&names, "take a screenshot of my desktop");
+        let mut leading = ranked[..4].to_vec();
+        leading.sort();
+        assert_eq!(leading, ["edit_file", "list_dir", "read_file", "write_file"]);
+        assert_eq!(ranked[4], "screenshot");
+        assert_eq!(ranked.len(), names.len());
+    }
+
+    #[test]
+    fn usage_breaks_ties() {
+        let loader = loader(DEFAULT_SKILL_PROMPT_BUDGET);
+        let names = ["clipboard", "music"];
+        assert_eq!(loader.rank_skills(&names, "hello there"), ["clipboard", "music"]);
+
+        loader.record_use("MUSIC");
+        assert_eq!(loader.rank_skills(&names, "hello there"), ["music", "clipboard"]);
+
+        // A description match outweighs a few uses
+        let ranked = loader.rank_skills(&names, "copy this to the clipboard");
+        assert!(position(&ranked, "clipboard") < position(&ranked, "music"));
+    }
+
+    #[test]
+    fn tiered_prompt_keeps_to_the_budget() {
+        let prompt = loader(1).get_tiered_system_prompt(&["music"], "play something");
+
+        let (index, details) = prompt.split_once("Skill Details:").unwrap();
+        assert!(index.contains("SCREENSHOT") && !index.contains("MUSIC"));
+        for essential in ESSENTIAL_SKILLS {
+            assert!(details.contains(&format!("[{}]", essential.to_uppercase())), "{}", essential);
+        }
+        assert!(!details.contains("[SCREENSHOT]"), "over budget: index only");
+        assert!(details.contains("[SKILL_HELP: name]"));
 
-        self.skills.retain(|name, _| core_names.contains(name));
-        self.load_plugins();
+        let roomy = loader(usize::MAX / 2).get_tiered_system_prompt(&[], "play something");
+        assert!(roomy.contains("[SCREENSHOT]") && !roomy.contains("[SKILL_HELP: name]"));
     }
 }
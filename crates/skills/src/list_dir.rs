@@ -10,6 +10,8 @@ pub struct ListDirSkill;
 impl Skill for ListDirSkill {
     fn name(&self) -> &'static str { "list_dir" }
 
+    fn read_only(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
         "List contents of a directory. Returns JSON with success, path, and items (name/type). Usage: [LIST_DIR: \"/path/to/dir\"]"
     }
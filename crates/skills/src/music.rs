@@ -0,0 +1,47 @@
+//! Music Skill (Core)
+//! Controls Spotify playback through the platform's NativeBridge: AppleScript
+//! on macOS, playerctl on Linux.
+
+use super::Skill;
+use async_trait::async_trait;
+use openspore_io::PLAYBACK_USAGE;
+
+pub struct MusicSkill;
+
+/// `next`, `"volume 40"` or `{"command": "next"}`
+fn parse_args(args: &str) -> String {
+    let args = args.trim();
+    match crate::utils::try_parse_json(args) {
+        Some(json) if json.is_object() => crate::utils::get_str_field(&json, "command").unwrap_or_default(),
+        _ => args.trim_matches('"').trim().to_string(),
+    }
+}
+
+#[async_trait]
+impl Skill for MusicSkill {
+    fn name(&self) -> &'static str { "music" }
+
+    fn description(&self) -> &'static str {
+        "Control Spotify playback: play, pause, toggle, next, previous, volume <0-100>. \
+         Returns JSON with success and state (what plays now). Usage: [MUSIC: next] or [MUSIC: volume 40]"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "command": { "type": "string", "description": format!("One of: {}", PLAYBACK_USAGE) }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let command = parse_args(args);
+        let res = match openspore_io::get_bridge().spotify_control(&command).await {
+            Ok(state) => serde_json::json!({ "success": true, "command": command, "state": state }),
+            Err(e) => serde_json::json!({ "success": false, "command": command, "error": e.to_string() }),
+        };
+        Ok(res.to_string())
+    }
+}
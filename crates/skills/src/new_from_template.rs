@@ -0,0 +1,133 @@
+//! New From Template Skill (Core)
+//! Creates a workspace note from a template in workspace/identity/templates/.
+
+use super::Skill;
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+pub struct NewFromTemplateSkill;
+
+#[async_trait]
+impl Skill for NewFromTemplateSkill {
+    fn name(&self) -> &'static str { "new_from_template" }
+
+    fn description(&self) -> &'static str {
+        "Create a note from a workspace template (decision-record, meeting-note, weekly-review...) so it keeps the template's structure. \
+         Usage: [NEW_FROM_TEMPLATE: {\"template\": \"decision-record\", \"title\": \"Use SQLite for caching\", \"fields\": {\"context\": \"...\", \"decision\": \"...\"}}]. \
+         [NEW_FROM_TEMPLATE: list] shows the templates and their fields. Unfilled fields are reported as missing."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "template": { "type": "string", "description": "Template name or alias (use \"list\" to see them)" },
+                "title": { "type": "string", "description": "Title of the new note" },
+                "fields": {
+                    "type": "object",
+                    "description": "Placeholder values by field name",
+                    "additionalProperties": { "type": "string" }
+                }
+            },
+            "required": ["template"]
+        })
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let config = openspore_core::config::AppConfig::load()
+            .map_err(|e| format!("Config error: {}", e))?;
+        let state = openspore_core::state::AppState::new(config);
+        let memory = openspore_memory::MemorySystem::new(&state);
+        memory.ensure_structure().await.map_err(|e| e.to_string())?;
+
+        let args = args.trim();
+        let (template, title, fields) = match crate::utils::try_parse_json(args) {
+            Some(json) => {
+                let fields: HashMap<String, String> = json.get("fields")
+                    .and_then(|f| f.as_object())
+                    .map(|o| o.iter()
+                        .map(|(k, v)| (k.clone(), v.as_str().map(String::from).unwrap_or_else(|| v.to_string())))
+                        .collect())
+                    .unwrap_or_default();
+                (
+                    crate::utils::get_str_field(&json, "template").unwrap_or_default(),
+                    crate::utils::get_str_field(&json, "title").unwrap_or_default(),
+                    fields,
+                )
+            }
+            // Bracket form: "decision-record: Use SQLite for caching"
+            None => {
+                let raw = args.trim_matches('"');
+                let (t, title) = raw.split_once(':').unwrap_or((raw, ""));
+                (t.trim().to_string(), title.trim().to_string(), HashMap::new())
+            }
+        };
+
+        if template.is_empty() || template == "list" {
+            let items: Vec<_> = memory.list_templates().into_iter().map(|t| serde_json::json!({
+                "name": t.name,
+                "description": t.description,
+                "category": t.category,
+                "fields": t.fields,
+            })).collect();
+            return Ok(serde_json::json!({ "success": true, "templates": items }).to_string());
+        }
+
+        match memory.new_from_template(&template, &title, &fields).await {
+            Ok(note) => {
+                let mut res = serde_json::json!({
+                    "success": true,
+                    "template": note.template,
+                    "path": note.path.to_string_lossy(),
+                    "missing_fields": note.missing,
+                });
+                if !note.missing.is_empty() {
+                    res["hint"] = serde_json::json!(format!(
+                        "Fields marked {} were not provided. Ask the user or fill them with EDIT_FILE.",
+                        openspore_memory::templates::MISSING_FIELD_MARKER
+                    ));
+                }
+                Ok(res.to_string())
+            }
+            Err(e) => Ok(serde_json::json!({ "success": false, "error": e.to_string() }).to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run(args: &str) -> serde_json::Value {
+        serde_json::from_str(&NewFromTemplateSkill.execute(args).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn fills_fields_from_json_and_reports_the_rest() {
+        crate::test_support::app_root();
+        let res = run(r#"{"template": "meeting notes", "title": "Template skill sync", "fields": {"attendees": "Ana, Bo", "notes": 42}}"#).await;
+        assert_eq!(res["success"], true);
+        assert_eq!(res["template"], "meeting-note");
+        assert_eq!(res["missing_fields"], serde_json::json!(["agenda"]));
+        assert!(res["hint"].as_str().unwrap().contains(openspore_memory::templates::MISSING_FIELD_MARKER));
+
+        let saved = std::fs::read_to_string(res["path"].as_str().unwrap()).unwrap();
+        assert!(saved.contains("**Attendees:** Ana, Bo"));
+        assert!(saved.contains("## Notes\n42"));
+        assert!(saved.contains("## Action Items\n- [ ] None"));
+    }
+
+    #[tokio::test]
+    async fn bracket_form_and_listing() {
+        crate::test_support::app_root();
+        let res = run("adr: Template skill bracket form").await;
+        assert_eq!(res["template"], "decision-record");
+        assert_eq!(res["missing_fields"], serde_json::json!(["context", "decision", "consequences"]));
+
+        let listed = run("list").await;
+        let names: Vec<&str> = listed["templates"].as_array().unwrap().iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(names, ["decision-record", "meeting-note", "weekly-review"]);
+
+        assert_eq!(run(r#"{"template": "nope"}"#).await["success"], false);
+    }
+}
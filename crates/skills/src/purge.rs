@@ -1,5 +1,6 @@
 //! Purge Skill (Core)
 //! Cleans up old logs and raw context to keep the engine slim.
+//! Deleted files leave tombstones (see `openspore memory deleted`).
 
 use super::Skill;
 use async_trait::async_trait;
@@ -12,6 +13,8 @@ pub struct PurgeSkill;
 impl Skill for PurgeSkill {
     fn name(&self) -> &'static str { "purge" }
 
+    fn destructive(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
         "Clean up old context logs. Returns JSON with success and deleted_count. Usage: [PURGE: \"days\"]"
     }
@@ -21,9 +24,13 @@ impl Skill for PurgeSkill {
         let days: i64 = sanitized.parse().unwrap_or(7);
         let cutoff = Utc::now() - Duration::days(days);
 
-        // Get context directory
-        let root = openspore_core::path_utils::get_app_root();
-        let context_dir = root.join("workspace/context");
+        let config = match openspore_core::config::AppConfig::load() {
+            Ok(c) => c,
+            Err(e) => return Ok(serde_json::json!({ "success": false, "error": format!("Config error: {}", e) }).to_string()),
+        };
+        let state = openspore_core::state::AppState::new(config);
+        let memory = openspore_memory::MemorySystem::new(&state);
+        let context_dir = memory.context_dir();
 
         if !context_dir.exists() {
             let res = serde_json::json!({ "success": true, "deleted_count": 0, "message": "No context directory found to purge." });
@@ -47,13 +54,13 @@ impl Skill for PurgeSkill {
                     continue;
                 }
 
-                if let Ok(metadata) = entry.metadata().await {
-                    if let Ok(modified) = metadata.modified() {
-                        let dt: DateTime<Utc> = modified.into();
-                        if dt < cutoff {
-                            let _ = fs::remove_file(path).await;
-                            deleted_count += 1;
-                        }
+                if let Ok(metadata) = entry.metadata().await
+                    && let Ok(modified) = metadata.modified()
+                {
+                    let dt: DateTime<Utc> = modified.into();
+                    // Tombstoned, so a restored snapshot doesn't bring them back
+                    if dt < cutoff && memory.forget(&path, "purged").await.is_ok() {
+                        deleted_count += 1;
                     }
                 }
             }
@@ -1,96 +1,370 @@
 //! Read File Skill (Core)
 //! Supports optional line-range reading to save context tokens.
+//!
+//! Lines come back numbered (`12: text`) under a header with the file's line
+//! count and whether the output was cut, so follow-up edits can point at
+//! exact lines. Without a range at most DEFAULT_MAX_LINES lines are shown, and
+//! every read stops at `max_bytes`. Files that look binary or are over
+//! MAX_WHOLE_FILE_BYTES are refused with their size and kind unless a range
+//! is asked for. The file is streamed, so a range of a huge file is cheap on
+//! memory.
 
 use super::Skill;
 use async_trait::async_trait;
+use openspore_core::fmt_utils::{humanize_bytes, truncate_bytes};
 use tokio::fs;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader};
+
+/// Lines shown when the call gives no end line
+const DEFAULT_MAX_LINES: usize = 500;
+
+/// Bytes of numbered content returned when the call doesn't say
+const DEFAULT_MAX_BYTES: usize = 100_000;
+
+/// Larger files are only read by range
+const MAX_WHOLE_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Bytes sniffed for binary content
+const SNIFF_BYTES: usize = 8192;
+
+/// Signatures of common binary formats, for the refusal's hint
+const MAGIC: &[(&[u8], &str)] = &[
+    (b"\x89PNG", "image/png"),
+    (b"\xFF\xD8\xFF", "image/jpeg"),
+    (b"GIF8", "image/gif"),
+    (b"%PDF", "application/pdf"),
+    (b"PK\x03\x04", "application/zip (also docx, xlsx, jar)"),
+    (b"\x1F\x8B", "application/gzip"),
+    (b"\x7FELF", "application/x-executable (ELF)"),
+    (b"SQLite format 3", "application/x-sqlite3"),
+    (b"ID3", "audio/mpeg"),
+];
 
 pub struct ReadFileSkill;
 
+#[derive(Debug, Default)]
+struct ReadArgs {
+    path: String,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    max_bytes: Option<usize>,
+}
+
+impl ReadArgs {
+    fn has_range(&self) -> bool {
+        self.start_line.is_some() || self.end_line.is_some()
+    }
+}
+
+/// `"/path"`, `"/path" --lines=50-80` or `{"path": "...", "start_line": 50, "end_line": 80, "max_bytes": 20000}`
+fn parse_args(args: &str) -> ReadArgs {
+    let args = args.trim();
+    if args.starts_with('{')
+        && let Some(json) = crate::utils::try_parse_json(args).filter(|j| j.is_object())
+    {
+        let number = |key: &str| json.get(key).and_then(|v| v.as_u64().or_else(|| v.as_str()?.trim().parse().ok())).map(|n| n as usize);
+        return ReadArgs {
+            path: crate::utils::get_str_field(&json, "path").or_else(|| crate::utils::get_str_field(&json, "file")).unwrap_or_default(),
+            start_line: number("start_line"),
+            end_line: number("end_line"),
+            max_bytes: number("max_bytes"),
+        };
+    }
+
+    // Extract --lines=START-END if present
+    let lines_marker = "--lines=";
+    let Some(idx) = args.find(lines_marker) else {
+        return ReadArgs { path: args.to_string(), ..Default::default() };
+    };
+    let range = args[idx + lines_marker.len()..].trim().trim_matches('"').trim_matches('\'');
+    let (start_line, end_line) = match range.split_once('-') {
+        Some((start, end)) => (Some(start.trim().parse().unwrap_or(1)), end.trim().parse().ok()),
+        None => match range.parse() {
+            Ok(single) => (Some(single), Some(single)),
+            Err(_) => (None, None),
+        },
+    };
+    ReadArgs { path: args[..idx].to_string(), start_line, end_line, max_bytes: None }
+}
+
+/// The kind of a binary file's first bytes, None when they look like text
+fn binary_kind(head: &[u8]) -> Option<&'static str> {
+    if let Some((_, kind)) = MAGIC.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return Some(kind);
+    }
+    if head.len() >= 12 && &head[..4] == b"RIFF" && &head[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    // A character cut off at the end of the sniffed bytes is not an error
+    let invalid_utf8 = std::str::from_utf8(head).is_err_and(|e| e.error_len().is_some());
+    (head.contains(&0) || invalid_utf8).then_some("application/octet-stream")
+}
+
+fn failure(path: &str, error: String) -> String {
+    serde_json::json!({ "success": false, "error": error, "path": path }).to_string()
+}
+
 #[async_trait]
 impl Skill for ReadFileSkill {
     fn name(&self) -> &'static str { "read_file" }
 
+    fn read_only(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
-        "Read contents of a file. Supports optional line range to save context. Usage:\n\
+        "Read a text file. Lines come back numbered (`12: text`, the number is not part of the file) under a header with the total line count. \
+         Without a range at most 500 lines are shown; binary files and files over 1 MB are refused unless you ask for a range. Usage:\n\
          - Full: [READ_FILE: \"/path/to/file\"]\n\
-         - Range: [READ_FILE: \"/path/to/file\" --lines=50-80]\n\
-         Returns JSON with success, content, path, total_lines, and shown range."
+         - Range: [READ_FILE: {\"path\": \"/path/to/file\", \"start_line\": 50, \"end_line\": 80}] (or [READ_FILE: \"/path/to/file\" --lines=50-80])\n\
+         - Optional \"max_bytes\" caps the output (default 100000).\n\
+         Returns JSON with success, content, path, total_lines, shown_range and truncated."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "Absolute, ~ or relative path of the file" },
+                "start_line": { "type": "integer", "minimum": 1, "description": "First line to show (from 1)" },
+                "end_line": { "type": "integer", "minimum": 1, "description": "Last line to show (default: 500 lines from start_line)" },
+                "max_bytes": { "type": "integer", "minimum": 1, "description": "Bytes of output at most (default 100000)" }
+            },
+            "required": ["path"]
+        })
     }
 
     async fn execute(&self, args: &str) -> Result<String, String> {
-        let args_str = args.trim();
-
-        // Extract --lines=START-END if present
-        let lines_marker = "--lines=";
-        let (path_raw, line_range) = if let Some(idx) = args_str.find(lines_marker) {
-            let path_part = &args_str[..idx];
-            let range_part = &args_str[idx + lines_marker.len()..];
-            let range_str = range_part.trim().trim_matches('"').trim_matches('\'');
-
-            let range = if let Some(dash) = range_str.find('-') {
-                let start = range_str[..dash].parse::<usize>().unwrap_or(1);
-                let end = range_str[dash + 1..].parse::<usize>().unwrap_or(usize::MAX);
-                Some((start, end))
-            } else if let Ok(single) = range_str.parse::<usize>() {
-                Some((single, single))
-            } else {
-                None
-            };
-            (path_part, range)
-        } else {
-            (args_str, None)
+        let args = parse_args(args);
+        let path = crate::utils::sanitize_path(&args.path);
+        if path.is_empty() {
+            return Ok(failure(&path, "No path given".to_string()));
+        }
+        let metadata = match fs::metadata(&path).await {
+            Ok(metadata) => metadata,
+            Err(e) => return Ok(failure(&path, format!("Failed to read {}: {}", path, e))),
+        };
+        if metadata.is_dir() {
+            return Ok(failure(&path, format!("{} is a directory: use list_dir", path)));
+        }
+        let size = metadata.len();
+        let mut file = match fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) => return Ok(failure(&path, format!("Failed to read {}: {}", path, e))),
         };
 
-        let path = crate::utils::sanitize_path(path_raw);
-
-        match fs::read_to_string(&path).await {
-            Ok(content) => {
-                let all_lines: Vec<&str> = content.lines().collect();
-                let total_lines = all_lines.len();
-
-                let (output_content, shown_start, shown_end) = if let Some((start, end)) = line_range {
-                    let s = start.saturating_sub(1).min(total_lines); // 1-indexed to 0-indexed
-                    let e = end.min(total_lines);
-                    let selected: Vec<String> = all_lines[s..e]
-                        .iter()
-                        .enumerate()
-                        .map(|(i, line)| format!("{}: {}", s + i + 1, line))
-                        .collect();
-                    (selected.join("\n"), s + 1, e)
-                } else {
-                    // Cap output at 500 lines to prevent context flooding
-                    if total_lines > 500 {
-                        let selected: Vec<String> = all_lines[..500]
-                            .iter()
-                            .enumerate()
-                            .map(|(i, line)| format!("{}: {}", i + 1, line))
-                            .collect();
-                        let mut result = selected.join("\n");
-                        result.push_str(&format!("\n\n[... {} more lines. Use --lines= to view specific ranges]", total_lines - 500));
-                        (result, 1, 500)
-                    } else {
-                        (content.clone(), 1, total_lines)
-                    }
-                };
-
-                let result = serde_json::json!({
-                    "success": true,
-                    "content": output_content,
+        if !args.has_range() {
+            let mut head = Vec::with_capacity(SNIFF_BYTES);
+            if let Err(e) = (&mut file).take(SNIFF_BYTES as u64).read_to_end(&mut head).await {
+                return Ok(failure(&path, format!("Failed to read {}: {}", path, e)));
+            }
+            if let Some(kind) = binary_kind(&head) {
+                let res = serde_json::json!({
+                    "success": false,
+                    "error": format!(
+                        "{} looks like a binary file ({}, {}): read_file only shows text. Use a tool for that format, or ask for a range (start_line/end_line) to read it as text anyway",
+                        path, kind, humanize_bytes(size)
+                    ),
                     "path": path,
-                    "total_lines": total_lines,
-                    "shown_range": format!("{}-{}", shown_start, shown_end)
+                    "size_bytes": size,
+                    "mime_hint": kind
                 });
-                Ok(result.to_string())
-            },
-            Err(e) => {
-                let result = serde_json::json!({
+                return Ok(res.to_string());
+            }
+            if size > MAX_WHOLE_FILE_BYTES {
+                let res = serde_json::json!({
                     "success": false,
-                    "error": format!("Failed to read {}: {}", path, e),
-                    "path": path
+                    "error": format!(
+                        "{} is {}, over the {} read_file reads whole. Ask for a range, e.g. {{\"path\": \"{}\", \"start_line\": 1, \"end_line\": 200}}, or use grep to find the lines first",
+                        path, humanize_bytes(size), humanize_bytes(MAX_WHOLE_FILE_BYTES), path
+                    ),
+                    "path": path,
+                    "size_bytes": size,
+                    "mime_hint": "text/plain"
                 });
-                Ok(result.to_string())
+                return Ok(res.to_string());
+            }
+            if let Err(e) = file.rewind().await {
+                return Ok(failure(&path, format!("Failed to read {}: {}", path, e)));
+            }
+        }
+
+        let start = args.start_line.unwrap_or(1).max(1);
+        let last = args.end_line.unwrap_or_else(|| start.saturating_add(DEFAULT_MAX_LINES - 1));
+        if last < start {
+            return Ok(failure(&path, format!("end_line {} is before start_line {}", last, start)));
+        }
+        let max_bytes = args.max_bytes.unwrap_or(DEFAULT_MAX_BYTES).max(1);
+
+        // Numbered lines of the range, up to max_bytes; the whole file is read to count its lines
+        let mut reader = BufReader::new(file);
+        let mut line = Vec::new();
+        let mut content = String::new();
+        let (mut total_lines, mut shown_end, mut bytes_cut) = (0, 0, false);
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line).await {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => return Ok(failure(&path, format!("Failed to read {}: {}", path, e))),
+            }
+            total_lines += 1;
+            if total_lines < start || total_lines > last || bytes_cut {
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line);
+            let numbered = format!("{}: {}\n", total_lines, text.trim_end_matches(['\n', '\r']));
+            if content.len() + numbered.len() > max_bytes {
+                bytes_cut = true;
+                // A first line longer than max_bytes is shown cut rather than not at all
+                if content.is_empty() {
+                    content.push_str(truncate_bytes(&numbered, max_bytes));
+                    shown_end = total_lines;
+                }
+                continue;
             }
+            content.push_str(&numbered);
+            shown_end = total_lines;
+        }
+
+        if start > total_lines && total_lines > 0 {
+            return Ok(failure(&path, format!("start_line {} is past the end of {} ({} lines)", start, path, total_lines)));
         }
+        let shown_start = if total_lines == 0 { 0 } else { start };
+        let wanted_end = last.min(total_lines);
+        let truncated = bytes_cut || shown_end < wanted_end || (args.end_line.is_none() && shown_end < total_lines);
+
+        let mut header = if total_lines == 0 {
+            format!("[{}: empty file]", path)
+        } else {
+            format!("[{}: lines {}-{} of {}]", path, shown_start, shown_end, total_lines)
+        };
+        if truncated {
+            let next = shown_end + 1;
+            let reason = if bytes_cut { format!("cut at {} bytes", max_bytes) } else { format!("{} more lines", total_lines - shown_end) };
+            header = format!("{} [truncated: {}; continue with start_line {}]", header, reason, next);
+        } else if let Some(end) = args.end_line.filter(|end| *end > total_lines) {
+            header = format!("{} [end_line {} is past the end of the file]", header, end);
+        }
+
+        let result = serde_json::json!({
+            "success": true,
+            "content": format!("{}\n{}", header, content.trim_end_matches('\n')),
+            "path": path,
+            "size_bytes": size,
+            "total_lines": total_lines,
+            "shown_range": format!("{}-{}", shown_start, shown_end),
+            "truncated": truncated
+        });
+        Ok(result.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0\x1f\x15\xc4\x89";
+
+    async fn read(args: Value) -> Value {
+        let output = ReadFileSkill.execute(&args.to_string()).await.unwrap();
+        serde_json::from_str(&output).unwrap()
+    }
+
+    /// 10,000 lines of multi-byte text: the sniffed head ends inside a character
+    fn long_file(dir: &tempfile::TempDir) -> String {
+        let path = dir.path().join("long.md");
+        let text: String = (1..=10_000).map(|i| format!("ligne {} — café ✓ 日本\n", i)).collect();
+        std::fs::write(&path, text).unwrap();
+        path.display().to_string()
+    }
+
+    #[tokio::test]
+    async fn long_utf8_file_shows_its_first_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = long_file(&dir);
+
+        let result = read(serde_json::json!({ "path": path })).await;
+        assert_eq!(result["success"], true, "{}", result);
+        assert_eq!((result["total_lines"].as_u64(), result["shown_range"].as_str(), result["truncated"].as_bool()), (Some(10_000), Some("1-500"), Some(true)));
+        let content = result["content"].as_str().unwrap();
+        assert!(content.starts_with(&format!("[{}: lines 1-500 of 10000] [truncated: 9500 more lines; continue with start_line 501]\n1: ligne 1 — café ✓ 日本\n", path)), "{}", content);
+        assert!(content.ends_with("500: ligne 500 — café ✓ 日本"), "{}", content);
+    }
+
+    #[tokio::test]
+    async fn ranges_of_a_long_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = long_file(&dir);
+
+        let middle = read(Value::String(format!("{} --lines=5000-5002", path))).await;
+        assert_eq!(middle["content"], format!("[{}: lines 5000-5002 of 10000]\n5000: ligne 5000 — café ✓ 日本\n5001: ligne 5001 — café ✓ 日本\n5002: ligne 5002 — café ✓ 日本", path));
+        assert_eq!(middle["truncated"], false);
+
+        let capped = read(serde_json::json!({ "path": path, "start_line": 10, "max_bytes": 100 })).await;
+        assert_eq!(capped["shown_range"], "10-11", "34-byte lines");
+        assert!(capped["content"].as_str().unwrap().contains("[truncated: cut at 100 bytes; continue with start_line 12]"), "{}", capped);
+    }
+
+    #[tokio::test]
+    async fn range_past_the_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = long_file(&dir);
+
+        let tail = read(serde_json::json!({ "path": path, "start_line": 9999, "end_line": 10_050 })).await;
+        assert_eq!(tail["success"], true);
+        assert_eq!(tail["shown_range"], "9999-10000");
+        assert_eq!(tail["truncated"], false);
+        assert!(tail["content"].as_str().unwrap().starts_with(&format!("[{}: lines 9999-10000 of 10000] [end_line 10050 is past the end of the file]\n", path)), "{}", tail);
+
+        let beyond = read(serde_json::json!({ "path": path, "start_line": 10_001, "end_line": 10_100 })).await;
+        assert_eq!(beyond["success"], false);
+        assert_eq!(beyond["error"], format!("start_line 10001 is past the end of {} (10000 lines)", path));
+
+        let backwards = read(serde_json::json!({ "path": path, "start_line": 20, "end_line": 10 })).await;
+        assert_eq!(backwards["error"], "end_line 10 is before start_line 20");
+    }
+
+    #[tokio::test]
+    async fn png_is_refused_unless_a_range_is_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pixel.png");
+        std::fs::write(&path, PNG).unwrap();
+        let path = path.display().to_string();
+
+        let refused = read(Value::String(path.clone())).await;
+        assert_eq!(refused["success"], false);
+        assert_eq!(refused["mime_hint"], "image/png");
+        assert_eq!(refused["size_bytes"], PNG.len());
+        assert!(refused["error"].as_str().unwrap().starts_with(&format!("{} looks like a binary file (image/png, ", path)), "{}", refused);
+
+        let forced = read(serde_json::json!({ "path": path, "start_line": 1, "end_line": 1 })).await;
+        assert_eq!(forced["success"], true, "a range reads it as text anyway");
+        assert!(forced["content"].as_str().unwrap().contains("1: \u{FFFD}PNG"), "{}", forced);
+    }
+
+    #[tokio::test]
+    async fn missing_empty_and_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = dir.path().join("empty.txt");
+        std::fs::write(&empty, "").unwrap();
+
+        let result = read(Value::String(empty.display().to_string())).await;
+        assert_eq!((result["success"].as_bool(), result["shown_range"].as_str()), (Some(true), Some("0-0")));
+        assert_eq!(result["content"], format!("[{}: empty file]\n", empty.display()));
+
+        let missing = read(Value::String(dir.path().join("nope.txt").display().to_string())).await;
+        assert!(missing["error"].as_str().unwrap().starts_with("Failed to read "), "{}", missing);
+        let folder = read(Value::String(dir.path().display().to_string())).await;
+        assert!(folder["error"].as_str().unwrap().ends_with("is a directory: use list_dir"), "{}", folder);
+    }
+
+    #[test]
+    fn binary_sniffing() {
+        assert_eq!(binary_kind(PNG), Some("image/png"));
+        assert_eq!(binary_kind(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(binary_kind(b"text\0with a nul"), Some("application/octet-stream"));
+        assert_eq!(binary_kind(b"bad \xFF\xFE utf-8 here"), Some("application/octet-stream"));
+        assert_eq!(binary_kind("café".as_bytes()), None);
+        assert_eq!(binary_kind(&"日本".as_bytes()[..4]), None, "a character cut at the end is text");
     }
 }
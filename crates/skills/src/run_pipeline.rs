@@ -0,0 +1,97 @@
+//! Run Pipeline Skill (Core)
+//!
+//! Runs a pipeline of workspace/pipelines (see openspore_autonomy::pipelines)
+//! as `openspore pipeline run <name> --json` in a child process and returns
+//! its report. Nobody can answer approvals in that process: gated steps are
+//! refused and the `ask` error policy aborts.
+
+use super::Skill;
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Longest a pipeline may run before it is stopped
+const RUN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Set in the child process: the think steps of a pipeline cannot start another
+const PIPELINE_ENV: &str = "OPENSPORE_IN_PIPELINE";
+
+pub struct RunPipelineSkill;
+
+/// `"name"` or `{"name": "...", "vars": {"k": "v"}}`
+fn parse_args(args: &str) -> (String, Vec<(String, String)>) {
+    let args = args.trim();
+    let Some(json) = crate::utils::try_parse_json(args).filter(|j| j.is_object()) else {
+        return (args.trim_matches('"').trim_matches('\'').to_string(), Vec::new());
+    };
+    let name = crate::utils::get_str_field(&json, "name").unwrap_or_default();
+    let vars = json.get("vars").and_then(|v| v.as_object()).into_iter().flatten()
+        .map(|(k, v)| (k.clone(), v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+        .collect();
+    (name, vars)
+}
+
+#[async_trait]
+impl Skill for RunPipelineSkill {
+    fn name(&self) -> &'static str { "run_pipeline" }
+
+    /// Its steps may write files and send messages
+    fn destructive(&self) -> bool { true }
+
+    fn description(&self) -> &'static str {
+        "Run a pipeline (a named routine of skill and think steps in workspace/pipelines; `openspore pipeline list` shows them). \
+         Returns JSON with success, status, the report of every step and content: the output of the last step. \
+         Usage: [RUN_PIPELINE: \"weekly-review\"] or [RUN_PIPELINE: {\"name\": \"weekly-review\", \"vars\": {\"topic\": \"rust\"}}]"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "description": "Pipeline name (its file name without .yaml)" },
+                "vars": { "type": "object", "description": "Values of the pipeline's variables", "additionalProperties": { "type": "string" } }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let (name, vars) = parse_args(args);
+        if std::env::var_os(PIPELINE_ENV).is_some() {
+            return Ok(serde_json::json!({ "success": false, "error": "A pipeline cannot run pipelines" }).to_string());
+        }
+        if name.trim().is_empty() {
+            return Ok(serde_json::json!({ "success": false, "error": "Which pipeline? Give its name" }).to_string());
+        }
+
+        let binary = std::env::current_exe().unwrap_or_else(|_| "openspore".into());
+        let mut command = Command::new(binary);
+        command.arg("pipeline").arg("run").arg(&name).arg("--json").env(PIPELINE_ENV, &name);
+        for (key, value) in &vars {
+            command.arg("--var").arg(format!("{}={}", key, value));
+        }
+        let child = command
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+
+        let output = match tokio::time::timeout(RUN_TIMEOUT, child).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => return Ok(serde_json::json!({ "success": false, "error": format!("Failed to start the pipeline: {}", e) }).to_string()),
+            Err(_) => return Ok(serde_json::json!({ "success": false, "error": format!("The pipeline ran over {} minutes and was stopped", RUN_TIMEOUT.as_secs() / 60) }).to_string()),
+        };
+
+        // The report is the last JSON line; logs may come before it
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let report = stdout.lines().rev()
+            .find_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok().filter(|v| v.is_object()));
+        match report {
+            Some(report) => Ok(report.to_string()),
+            None => Ok(serde_json::json!({
+                "success": false,
+                "error": format!("The pipeline printed no report: {}", openspore_core::fmt_utils::preview(&String::from_utf8_lossy(&output.stderr), 300))
+            }).to_string()),
+        }
+    }
+}
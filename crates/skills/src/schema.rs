@@ -0,0 +1,172 @@
+//! Minimal JSON Schema check for skill arguments and results
+//!
+//! Covers what skill schemas use: `type` (one or a list), `required`,
+//! `properties`, `additionalProperties: false`, `items`, `enum`, `minimum`
+//! and `maximum`. Other keywords are ignored rather than rejected.
+
+use serde_json::Value;
+
+/// Every mismatch of `value` against `schema`, as "path: problem" ("$" is the root)
+pub fn validate(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(value, schema, "$", &mut errors);
+    errors
+}
+
+/// Every mismatch of a skill's raw arguments against its `args_schema()`.
+/// JSON wrapped in quotes (`"{...}"`, as models sometimes write) is unwrapped.
+pub fn validate_args(args: &str, schema: &Value) -> Vec<String> {
+    let mut args = args.trim();
+    if args.len() >= 2 && args.starts_with('"') && args.ends_with('"') && args.contains('{') {
+        args = &args[1..args.len() - 1];
+    }
+    match serde_json::from_str::<Value>(args) {
+        Ok(value) => validate(&value, schema),
+        Err(e) => vec![format!("$: the arguments are not JSON ({})", e)],
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    let actual = type_name(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+fn check(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else { return };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(list) => list.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!("{}: expected {}, got {}", path, types.join(" or "), type_name(value)));
+            return;
+        }
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum")
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+        errors.push(format!("{}: {} is not one of {}", path, value, allowed.join(", ")));
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64())
+            && n < min
+        {
+            errors.push(format!("{}: {} is below the minimum {}", path, n, min));
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64())
+            && n > max
+        {
+            errors.push(format!("{}: {} is above the maximum {}", path, n, max));
+        }
+    }
+
+    if let Value::Object(object) = value {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if !object.contains_key(key) {
+                    errors.push(format!("{}: missing required \"{}\"", path, key));
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for (key, item) in object {
+            match properties.and_then(|p| p.get(key)) {
+                Some(property) => check(item, property, &format!("{}.{}", path, key), errors),
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    errors.push(format!("{}: unexpected \"{}\"", path, key));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check(item, item_schema, &format!("{}[{}]", path, i), errors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["path", "mode"],
+            "additionalProperties": false,
+            "properties": {
+                "path": { "type": "string" },
+                "mode": { "enum": ["read", "write"] },
+                "limit": { "type": "integer", "minimum": 1, "maximum": 100 },
+                "ratio": { "type": "number" },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "owner": { "type": ["string", "null"] }
+            }
+        })
+    }
+
+    #[test]
+    fn a_matching_value_has_no_errors() {
+        let value = json!({ "path": "a.md", "mode": "read", "limit": 100, "ratio": 1, "tags": ["x"], "owner": null });
+        assert!(validate(&value, &schema()).is_empty());
+    }
+
+    #[test]
+    fn every_mismatch_is_reported_with_its_path() {
+        let value = json!({ "path": 3, "mode": "delete", "limit": 0, "ratio": "high", "tags": ["x", 2], "owner": false, "extra": true });
+        assert_eq!(validate(&value, &schema()), [
+            "$: unexpected \"extra\"",
+            "$.limit: 0 is below the minimum 1",
+            "$.mode: \"delete\" is not one of \"read\", \"write\"",
+            "$.owner: expected string or null, got boolean",
+            "$.path: expected string, got integer",
+            "$.ratio: expected number, got string",
+            "$.tags[1]: expected string, got integer",
+        ]);
+        assert_eq!(validate(&json!({ "limit": 101.5 }), &schema()), [
+            "$: missing required \"path\"",
+            "$: missing required \"mode\"",
+            "$.limit: expected integer, got number",
+        ]);
+        assert_eq!(validate(&json!([1]), &schema()), ["$: expected object, got array"], "a wrong type stops there");
+    }
+
+    #[test]
+    fn unknown_keywords_and_open_objects_are_let_through() {
+        let open = json!({ "type": "object", "properties": { "a": { "type": "string", "pattern": "^x" } } });
+        assert!(validate(&json!({ "a": "y", "b": 1 }), &open).is_empty());
+        assert!(validate(&json!(42), &json!(true)).is_empty(), "a non-object schema checks nothing");
+        assert!(validate(&json!(1.5), &json!({ "type": "number", "maximum": 2 })).is_empty());
+    }
+
+    #[test]
+    fn raw_arguments_are_parsed_first() {
+        assert!(validate_args(r#" {"path": "a.md", "mode": "read"} "#, &schema()).is_empty());
+        assert!(validate_args(r#""{"path": "a.md", "mode": "write"}""#, &schema()).is_empty(), "quoted JSON is unwrapped");
+        let errors = validate_args("a.md", &schema());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("$: the arguments are not JSON"), "{}", errors[0]);
+        assert_eq!(validate_args(r#""a.md""#, &schema()), ["$: expected object, got string"]);
+    }
+}
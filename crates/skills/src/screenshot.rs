@@ -0,0 +1,44 @@
+//! Screenshot Skill (Core)
+//! Captures the whole screen through the platform's NativeBridge: screencapture
+//! on macOS, grim or scrot on Linux, PowerShell on Windows. Captures land in
+//! workspace/screenshots next to the browser's page screenshots, under the same
+//! retention.
+
+use super::Skill;
+use async_trait::async_trait;
+use openspore_core::fmt_utils::humanize_bytes;
+
+pub struct ScreenshotSkill;
+
+#[async_trait]
+impl Skill for ScreenshotSkill {
+    fn name(&self) -> &'static str { "screenshot" }
+
+    fn audited(&self, _args: &str) -> bool { true }
+
+    fn description(&self) -> &'static str {
+        "Capture the user's whole screen (the desktop, not a browser page) as a PNG in workspace/screenshots. \
+         Returns JSON with success and path. Usage: [SCREENSHOT: screen]"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object", "properties": {} })
+    }
+
+    async fn execute(&self, _args: &str) -> Result<String, String> {
+        let dir = openspore_core::path_utils::get_app_root().join("workspace").join(openspore_memory::SCREENSHOTS_DIR);
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            return Ok(serde_json::json!({ "success": false, "error": format!("Could not create {}: {}", dir.display(), e) }).to_string());
+        }
+        let path = dir.join(format!("screen_{}.png", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+
+        let res = match openspore_io::get_bridge().capture_screen(&path).await {
+            Ok(()) => {
+                let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                serde_json::json!({ "success": true, "path": path.display().to_string(), "size": humanize_bytes(size) })
+            }
+            Err(e) => serde_json::json!({ "success": false, "error": e.to_string() }),
+        };
+        Ok(res.to_string())
+    }
+}
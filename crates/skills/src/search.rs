@@ -10,6 +10,8 @@ pub struct SearchSkill;
 impl Skill for SearchSkill {
     fn name(&self) -> &'static str { "search" }
 
+    fn read_only(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
         "Search the workspace/memory for relevant files. Returns JSON with success and results (title/path/score). Usage: [SEARCH: \"query\"]"
     }
@@ -46,11 +48,15 @@ impl Skill for SearchSkill {
         match results_res {
             Ok(results) => {
                 let items: Vec<_> = results.into_iter().map(|r| {
-                    serde_json::json!({
+                    let mut item = serde_json::json!({
                         "title": r.title,
                         "path": r.path.to_string_lossy(),
                         "score": r.score
-                    })
+                    });
+                    if r.is_conflicted() {
+                        item["conflict"] = serde_json::json!("Unresolved sync conflict: the file holds two versions, neither is authoritative");
+                    }
+                    item
                 }).collect();
 
                 let res = serde_json::json!({
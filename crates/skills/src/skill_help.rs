@@ -0,0 +1,46 @@
+//! Skill Help (Meta Skill)
+//! Returns the full documentation of a skill that was only listed by name in the prompt.
+
+use super::Skill;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Shared name -> description catalog, refreshed by the SkillLoader on (re)load.
+pub type SkillDocs = Arc<RwLock<HashMap<String, String>>>;
+
+pub struct SkillHelpSkill {
+    pub docs: SkillDocs,
+}
+
+#[async_trait]
+impl Skill for SkillHelpSkill {
+    fn name(&self) -> &'static str { "skill_help" }
+
+    fn read_only(&self) -> bool { true }
+
+    fn description(&self) -> &'static str {
+        "Get the full description and usage example of any skill listed in the index. Usage: [SKILL_HELP: skill_name]"
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let name = args.trim().trim_matches('"').trim_matches('\'').to_lowercase();
+        if name.is_empty() {
+            return Err("Usage: [SKILL_HELP: skill_name]".to_string());
+        }
+
+        let docs = self.docs.read().map_err(|_| "Skill catalog unavailable".to_string())?;
+        match docs.get(&name) {
+            Some(description) => Ok(serde_json::json!({
+                "success": true,
+                "skill": name.to_uppercase(),
+                "description": description,
+            }).to_string()),
+            None => {
+                let mut available: Vec<&String> = docs.keys().collect();
+                available.sort();
+                Err(format!("Unknown skill '{}'. Available: {}", name, available.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")))
+            }
+        }
+    }
+}
@@ -41,9 +41,10 @@ impl Skill for SubmitSkill {
         // Path-pointing detection
         let trimmed_code = code.trim();
         if (trimmed_code.starts_with('/') || trimmed_code.starts_with('~') || trimmed_code.starts_with("./"))
-           && !trimmed_code.contains('\n') && !trimmed_code.contains(' ') && !trimmed_code.contains('{') {
-                let res = serde_json::json!({ "success": false, "error": format!("Blocked by Self-Defense: SUBMIT_SKILL requires the literal content of the code, not a path to it. You provided: '{}'", trimmed_code) });
-                return Ok(res.to_string());
+           && !trimmed_code.contains('\n') && !trimmed_code.contains(' ') && !trimmed_code.contains('{')
+        {
+            let res = serde_json::json!({ "success": false, "error": format!("Blocked by Self-Defense: SUBMIT_SKILL requires the literal content of the code, not a path to it. You provided: '{}'", trimmed_code) });
+            return Ok(res.to_string());
         }
 
         // Validation (Refined for real-world utility)
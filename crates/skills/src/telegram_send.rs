@@ -1,38 +1,108 @@
 //! Telegram Send Skill (Core)
+//! Messages go to the owner's chat, or to a contact of workspace/identity/CONTACTS.md
+//! given as `to`. Messages to anyone but the owner always wait for approval.
 
 use super::Skill;
 use async_trait::async_trait;
+use openspore_core::config::AppConfig;
+use openspore_memory::{ContactBook, ContactMatch};
 use reqwest::Client;
 
 pub struct TelegramSendSkill;
 
+/// The first TELEGRAM_ALLOWED_USERS entry: the owner's chat
+fn owner_chat() -> Option<String> {
+    AppConfig::current().telegram_owner_chat()
+}
+
+fn contact_book() -> ContactBook {
+    ContactBook::load(&openspore_core::path_utils::get_app_root().join("workspace"))
+}
+
+/// (recipient, message): `{"to": "Anna", "message": "..."}`, or the bare message for the owner
+fn parse_args(args: &str) -> (Option<String>, String) {
+    match crate::utils::try_parse_json(args.trim()) {
+        Some(json) if json.is_object() => (
+            crate::utils::get_str_field(&json, "to").filter(|t| !t.trim().is_empty()),
+            crate::utils::get_str_field(&json, "message").or_else(|| crate::utils::get_str_field(&json, "text")).unwrap_or_default(),
+        ),
+        _ => (None, args.trim().trim_matches('"').trim_matches('\'').to_string()),
+    }
+}
+
 #[async_trait]
 impl Skill for TelegramSendSkill {
     fn name(&self) -> &'static str { "telegram_send" }
 
+    fn audited(&self, _args: &str) -> bool { true }
+
+    /// A message to a contact other than the owner
+    fn requires_approval(&self, args: &str) -> bool {
+        let (Some(to), _) = parse_args(args) else { return false };
+        let book = contact_book();
+        match book.resolve(&to) {
+            ContactMatch::Found(contact) => !contact.owner && contact.telegram.is_some() && contact.telegram != owner_chat(),
+            _ => false,
+        }
+    }
+
     fn description(&self) -> &'static str {
-        "Send a message via Telegram. Returns JSON with success and message. Usage: [TELEGRAM_SEND: \"message\"]"
+        "Send a message via Telegram. Returns JSON with success and message. Usage: [TELEGRAM_SEND: \"message\"] messages the owner; \
+         [TELEGRAM_SEND: {\"to\": \"Anna\", \"message\": \"...\"}] messages a contact (see the contacts skill), after the user approves."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "message": { "type": "string", "description": "Message text (Markdown)" },
+                "to": { "type": "string", "description": "Contact name or alias; omit to message the owner" }
+            },
+            "required": ["message"]
+        })
     }
 
     async fn execute(&self, args: &str) -> Result<String, String> {
-        let message = args.trim().trim_matches('"').trim_matches('\'');
+        let (to, message) = parse_args(args);
 
-        let token = match std::env::var("TELEGRAM_BOT_TOKEN") {
-            Ok(t) => t,
-            Err(_) => {
+        let token = match AppConfig::current().telegram_bot_token.clone().filter(|t| !t.trim().is_empty()) {
+            Some(t) => t,
+            None => {
                 let res = serde_json::json!({ "success": false, "error": "TELEGRAM_BOT_TOKEN not set" });
                 return Ok(res.to_string());
             }
         };
-        let chat_id = match std::env::var("TELEGRAM_ALLOWED_USERS") {
-            Ok(c) => c,
-            Err(_) => {
-                let res = serde_json::json!({ "success": false, "error": "TELEGRAM_ALLOWED_USERS not set" });
-                return Ok(res.to_string());
+
+        let recipient = match to {
+            None => match owner_chat() {
+                Some(chat) => chat,
+                None => {
+                    let res = serde_json::json!({ "success": false, "error": "TELEGRAM_ALLOWED_USERS not set" });
+                    return Ok(res.to_string());
+                }
+            },
+            Some(to) => {
+                let book = contact_book();
+                match book.resolve(&to) {
+                    ContactMatch::Found(contact) => match &contact.telegram {
+                        Some(chat) => chat.clone(),
+                        None => {
+                            let res = serde_json::json!({ "success": false, "error": format!("{} has no telegram id in CONTACTS.md", contact.name), "contact": contact.to_json() });
+                            return Ok(res.to_string());
+                        }
+                    },
+                    ContactMatch::Ambiguous(candidates) => {
+                        let names: Vec<&str> = candidates.iter().map(|c| c.name.as_str()).collect();
+                        let res = serde_json::json!({ "success": false, "error": format!("\"{}\" matches several contacts; ask the user which one", to), "candidates": names });
+                        return Ok(res.to_string());
+                    }
+                    ContactMatch::NotFound => {
+                        let res = serde_json::json!({ "success": false, "error": format!("No contact \"{}\". Look it up or add it with the contacts skill", to) });
+                        return Ok(res.to_string());
+                    }
+                }
             }
         };
-
-        let recipient = chat_id.split(',').next().unwrap_or(&chat_id);
         let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
 
         let client = Client::new();
@@ -61,3 +131,31 @@ impl Skill for TelegramSendSkill {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::contact_book;
+
+    #[tokio::test]
+    async fn messages_to_anyone_but_the_owner_need_approval() {
+        let _book = contact_book().await;
+        assert!(TelegramSendSkill.requires_approval(r#"{"to": "Anna", "message": "The summary"}"#));
+        assert!(TelegramSendSkill.requires_approval(r#"{"to": "anna berg", "message": "The summary"}"#));
+        assert!(TelegramSendSkill.requires_approval(r#"{"to": "@1001", "message": "The summary"}"#));
+
+        assert!(!TelegramSendSkill.requires_approval(r#"{"to": "me", "message": "Done"}"#), "the owner");
+        assert!(!TelegramSendSkill.requires_approval("\"Done\""), "no recipient is the owner");
+        assert!(!TelegramSendSkill.requires_approval(r#"{"to": "", "message": "Done"}"#));
+    }
+
+    #[tokio::test]
+    async fn recipients_without_a_chat_need_no_approval() {
+        let _book = contact_book().await;
+        // Nothing to approve: execute refuses these before sending
+        assert!(!TelegramSendSkill.requires_approval(r#"{"to": "Ben", "message": "Hi"}"#), "no telegram id");
+        assert!(!TelegramSendSkill.requires_approval(r#"{"to": "Carol", "message": "Hi"}"#), "unknown");
+        assert_eq!(parse_args(r#"{"to": "Anna", "text": "Hi"}"#), (Some("Anna".to_string()), "Hi".to_string()));
+        assert_eq!(parse_args("'Hi there'"), (None, "Hi there".to_string()));
+    }
+}
@@ -0,0 +1,28 @@
+//! Shared test fixtures for the skills
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// App root of the test binary: a temp directory fixed once with
+/// set_app_root(), before any skill reads it
+pub fn app_root() -> PathBuf {
+    static ROOT: OnceLock<PathBuf> = OnceLock::new();
+    ROOT.get_or_init(|| {
+        let root = tempfile::tempdir().unwrap().keep();
+        openspore_core::path_utils::set_app_root(root.clone());
+        root
+    }).clone()
+}
+
+/// Contact book of the app root: the contacts and telegram_send tests share it
+pub const CONTACTS: &str = "---\nname: Anna Schmidt\naliases: Anna\ntelegram: 1001\n---\n---\nname: Anna Berg\ntelegram: 1002\n---\n---\nname: Ben Ortiz\nemail: ben@example.com\n---\n---\nname: Me\ntelegram: 42\nowner: true\n---\n";
+
+/// Write CONTACTS to the app root; hold the guard while the test reads or changes the book
+pub async fn contact_book() -> tokio::sync::MutexGuard<'static, ()> {
+    static LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+    let guard = LOCK.lock().await;
+    let path = app_root().join("workspace").join(openspore_memory::CONTACTS_FILE);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, CONTACTS).unwrap();
+    guard
+}
@@ -67,11 +67,9 @@ pub fn sanitize_path(raw: &str) -> String {
     }
 }
 
-/// Check if global safe mode is enabled via environment variable
+/// Check if global safe mode is enabled (SAFE_MODE_ENABLED, follows config reloads)
 pub fn is_safe_mode_active() -> bool {
-    std::env::var("SAFE_MODE_ENABLED")
-        .map(|v| v.to_lowercase() == "true")
-        .unwrap_or(false)
+    openspore_core::config::AppConfig::current().safe_mode_enabled
 }
 
 /// Check if a path is considered part of the "protected engine" (core logic/config)
@@ -117,14 +115,14 @@ pub fn parse_smart_args(s: &str) -> Vec<String> {
     if let Ok(Value::Array(arr)) = serde_json::from_str(trimmed) {
         return arr.iter().map(|v| v.as_str().unwrap_or_default().to_string()).collect();
     }
-    if let Ok(Value::Object(obj)) = serde_json::from_str(trimmed) {
-        if let Some(cmd) = obj.get("cmd").and_then(|v| v.as_str()) {
-             let mut res = vec![cmd.to_string()];
-             if let Some(wait) = obj.get("wait").and_then(|v| v.as_str()) {
-                 res.push(wait.to_string());
-             }
-             return res;
+    if let Ok(Value::Object(obj)) = serde_json::from_str(trimmed)
+        && let Some(cmd) = obj.get("cmd").and_then(|v| v.as_str())
+    {
+        let mut res = vec![cmd.to_string()];
+        if let Some(wait) = obj.get("wait").and_then(|v| v.as_str()) {
+            res.push(wait.to_string());
         }
+        return res;
     }
 
     // 2. Split by comma if it looks like a list: "arg1", "arg2"
@@ -0,0 +1,410 @@
+//! WASM Plugin Skills
+//!
+//! `.wasm` files in ~/.openspore/skills/ run in wasmtime, each call in a fresh
+//! instance with no files, network or environment. A module uses one of two
+//! conventions:
+//!
+//! - **run export**: it exports `memory`, `alloc(len: i32) -> i32` and
+//!   `run(args_ptr: i32, args_len: i32) -> i32`. The arguments are written as
+//!   UTF-8 where `alloc` says; `run` returns a pointer to a little-endian u32
+//!   length followed by that many bytes of UTF-8 result. A JSON object result
+//!   is returned as it is, anything else as `stdout`. A reactor's
+//!   `_initialize` is called first.
+//! - **WASI command**: it exports `_start`, reads the arguments on stdin and
+//!   prints its result; the result has the script plugins' shape (success,
+//!   exit_code, stdout, stderr).
+//!
+//! A custom section named `openspore.description` holds the description the
+//! agent sees. Every call gets WASM_SKILL_FUEL instructions and
+//! WASM_SKILL_MEMORY_MB of linear memory, and is abandoned after CALL_TIMEOUT,
+//! so a runaway module ends as a failed call instead of a hung agent.
+
+use super::Skill;
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+use openspore_core::config::AppConfig;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use wasmtime::{Config, Engine, Instance, Linker, Module, ResourceLimiter, Store, Trap};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::WasiP1Ctx;
+use wasmtime_wasi::{I32Exit, WasiCtxBuilder};
+
+/// Instructions per call when WASM_SKILL_FUEL is unset
+const DEFAULT_FUEL: u64 = 1_000_000_000;
+
+/// Linear memory per call when WASM_SKILL_MEMORY_MB is unset
+const DEFAULT_MEMORY_MB: u64 = 64;
+
+/// Table elements a module may grow to
+const MAX_TABLE_ELEMENTS: usize = 100_000;
+
+/// Bytes of result (or of stdout and stderr each) kept from a call
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Longest the agent waits for a call; fuel stops the module itself
+const CALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Custom section holding the skill's description
+const DESCRIPTION_SECTION: &str = "openspore.description";
+
+/// Per-call limits, read from the config
+#[derive(Debug, Clone, Copy)]
+pub struct WasmLimits {
+    pub fuel: u64,
+    pub memory_bytes: usize,
+}
+
+impl WasmLimits {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let memory_mb = config.wasm_skill_memory_mb.unwrap_or(DEFAULT_MEMORY_MB).max(1);
+        Self {
+            fuel: config.wasm_skill_fuel.unwrap_or(DEFAULT_FUEL).max(1),
+            memory_bytes: usize::try_from(memory_mb.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX),
+        }
+    }
+}
+
+/// How the module takes its arguments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Abi {
+    Run,
+    Command,
+}
+
+pub struct WasmSkill {
+    pub name: String,
+    pub description: String,
+    pub path: PathBuf,
+    module: Module,
+    abi: Abi,
+    limits: WasmLimits,
+}
+
+/// The engine all modules are compiled for (fuel metering on)
+fn engine() -> anyhow::Result<Engine> {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    if let Some(engine) = ENGINE.get() {
+        return Ok(engine.clone());
+    }
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config)?;
+    Ok(ENGINE.get_or_init(|| engine).clone())
+}
+
+/// An unsigned LEB128 number of the binary format
+fn read_leb(bytes: &[u8], pos: &mut usize) -> Option<u32> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= u32::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Contents of the first custom section called `name`
+fn custom_section<'a>(bytes: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    if bytes.get(..4) != Some(b"\0asm".as_slice()) {
+        return None;
+    }
+    let mut pos = 8;
+    while pos < bytes.len() {
+        let id = bytes[pos];
+        pos += 1;
+        let size = read_leb(bytes, &mut pos)? as usize;
+        let end = pos.checked_add(size).filter(|end| *end <= bytes.len())?;
+        if id == 0 {
+            let mut at = pos;
+            let len = read_leb(bytes, &mut at)? as usize;
+            let section_name = bytes.get(at..at.checked_add(len)?)?;
+            if section_name == name.as_bytes() {
+                return bytes.get(at + len..end);
+            }
+        }
+        pos = end;
+    }
+    None
+}
+
+impl WasmSkill {
+    /// Compile a module and work out its convention
+    pub fn load(path: &Path, limits: WasmLimits) -> anyhow::Result<Self> {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+        let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        let module = Module::new(&engine()?, &bytes).with_context(|| format!("compiling {}", path.display()))?;
+
+        let exports: Vec<&str> = module.exports().map(|e| e.name()).collect();
+        let abi = if ["memory", "alloc", "run"].iter().all(|e| exports.contains(e)) {
+            Abi::Run
+        } else if exports.contains(&"_start") {
+            Abi::Command
+        } else {
+            bail!("{} exports neither memory, alloc and run nor _start", path.display());
+        };
+
+        let description = custom_section(&bytes, DESCRIPTION_SECTION)
+            .map(|text| String::from_utf8_lossy(text).trim().to_string())
+            .filter(|text| !text.is_empty())
+            .unwrap_or_else(|| format!("WASM plugin skill: {}", name));
+
+        Ok(Self { name, description, path: path.to_path_buf(), module, abi, limits })
+    }
+}
+
+/// Store data of a call: WASI state and the memory limit
+struct CallState {
+    wasi: WasiP1Ctx,
+    limiter: Limiter,
+}
+
+struct Limiter {
+    max_memory: usize,
+    memory_exceeded: bool,
+}
+
+impl ResourceLimiter for Limiter {
+    fn memory_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> anyhow::Result<bool> {
+        if desired > self.max_memory {
+            self.memory_exceeded = true;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn table_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> anyhow::Result<bool> {
+        Ok(desired <= MAX_TABLE_ELEMENTS)
+    }
+}
+
+/// Result of the run export
+fn call_run(store: &mut Store<CallState>, instance: &Instance, args: &str) -> anyhow::Result<String> {
+    if let Ok(init) = instance.get_typed_func::<(), ()>(&mut *store, "_initialize") {
+        init.call(&mut *store, ())?;
+    }
+    let memory = instance.get_memory(&mut *store, "memory").ok_or_else(|| anyhow!("the `memory` export is not a memory"))?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "alloc").context("alloc must be (i32) -> i32")?;
+    let run = instance.get_typed_func::<(i32, i32), i32>(&mut *store, "run").context("run must be (i32, i32) -> i32")?;
+
+    let len = i32::try_from(args.len()).map_err(|_| anyhow!("the arguments are too long"))?;
+    let args_ptr = alloc.call(&mut *store, len)?;
+    memory.write(&mut *store, args_ptr as u32 as usize, args.as_bytes()).context("alloc returned a pointer outside memory")?;
+    let result_ptr = run.call(&mut *store, (args_ptr, len))? as u32 as usize;
+
+    let mut prefix = [0u8; 4];
+    memory.read(&*store, result_ptr, &mut prefix).context("run returned a pointer outside memory")?;
+    let result_len = u32::from_le_bytes(prefix) as usize;
+    if result_len > MAX_OUTPUT_BYTES {
+        bail!("the result is {} bytes, over the {} kept", result_len, MAX_OUTPUT_BYTES);
+    }
+    let mut result = vec![0u8; result_len];
+    memory.read(&*store, result_ptr + 4, &mut result).context("the result runs past the end of memory")?;
+    let result = String::from_utf8_lossy(&result).to_string();
+
+    match crate::utils::try_parse_json(&result).filter(|j| j.is_object()) {
+        Some(json) => Ok(json.to_string()),
+        None => Ok(serde_json::json!({ "success": true, "stdout": result }).to_string()),
+    }
+}
+
+/// Result of a WASI command run with the arguments on stdin
+fn call_start(store: &mut Store<CallState>, instance: &Instance, stdout: &MemoryOutputPipe, stderr: &MemoryOutputPipe) -> anyhow::Result<String> {
+    let start = instance.get_typed_func::<(), ()>(&mut *store, "_start").context("_start must take and return nothing")?;
+    let exit_code = match start.call(&mut *store, ()) {
+        Ok(()) => 0,
+        Err(e) => match e.downcast_ref::<I32Exit>() {
+            Some(exit) => exit.0,
+            None => return Err(e),
+        },
+    };
+    Ok(serde_json::json!({
+        "success": exit_code == 0,
+        "exit_code": exit_code,
+        "stdout": String::from_utf8_lossy(&stdout.contents()),
+        "stderr": String::from_utf8_lossy(&stderr.contents())
+    }).to_string())
+}
+
+/// One call in a fresh store; failures come back as the skill's JSON error
+fn call(module: &Module, abi: Abi, name: &str, args: &str, limits: WasmLimits) -> String {
+    let stdout = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+    let stderr = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.arg(name).stdout(stdout.clone()).stderr(stderr.clone());
+    if abi == Abi::Command {
+        wasi.stdin(MemoryInputPipe::new(args.to_string()));
+    }
+    let state = CallState {
+        wasi: wasi.build_p1(),
+        limiter: Limiter { max_memory: limits.memory_bytes, memory_exceeded: false },
+    };
+
+    let mut store = Store::new(module.engine(), state);
+    store.limiter(|state| &mut state.limiter);
+    let result = store.set_fuel(limits.fuel).and_then(|()| {
+        let mut linker = Linker::new(module.engine());
+        wasmtime_wasi::preview1::add_to_linker_sync(&mut linker, |state: &mut CallState| &mut state.wasi)?;
+        let instance = linker.instantiate(&mut store, module)?;
+        match abi {
+            Abi::Run => call_run(&mut store, &instance, args),
+            Abi::Command => call_start(&mut store, &instance, &stdout, &stderr),
+        }
+    });
+
+    result.unwrap_or_else(|e| {
+        let error = if e.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+            format!("{} ran out of fuel after {} instructions (WASM_SKILL_FUEL)", name, limits.fuel)
+        } else if store.data().limiter.memory_exceeded {
+            format!("{} needed more than {} MB of memory (WASM_SKILL_MEMORY_MB): {:#}", name, limits.memory_bytes / (1024 * 1024), e)
+        } else {
+            format!("{} failed: {:#}", name, e)
+        };
+        serde_json::json!({
+            "success": false,
+            "error": error,
+            "stderr": String::from_utf8_lossy(&stderr.contents())
+        }).to_string()
+    })
+}
+
+#[async_trait]
+impl Skill for WasmSkill {
+    fn name(&self) -> &'static str {
+        Box::leak(self.name.clone().into_boxed_str())
+    }
+
+    fn description(&self) -> &'static str {
+        Box::leak(self.description.clone().into_boxed_str())
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let (module, abi, limits) = (self.module.clone(), self.abi, self.limits);
+        let (name, args) = (self.name.clone(), args.to_string());
+        let running = tokio::task::spawn_blocking(move || call(&module, abi, &name, &args, limits));
+        match tokio::time::timeout(CALL_TIMEOUT, running).await {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(e)) => Ok(serde_json::json!({ "success": false, "error": format!("{} stopped: {}", self.name, e) }).to_string()),
+            Err(_) => Ok(serde_json::json!({
+                "success": false,
+                "error": format!("{} ran over {} seconds and was abandoned", self.name, CALL_TIMEOUT.as_secs())
+            }).to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: WasmLimits = WasmLimits { fuel: 10_000_000, memory_bytes: 1024 * 1024 };
+
+    /// Echoes its arguments: they land at 1024 and the length goes just before
+    const ECHO: &str = r#"(module
+        (@custom "openspore.description" "Echoes its arguments")
+        (memory (export "memory") 1)
+        (func (export "alloc") (param i32) (result i32) i32.const 1024)
+        (func (export "run") (param $ptr i32) (param $len i32) (result i32)
+            (i32.store (i32.sub (local.get $ptr) (i32.const 4)) (local.get $len))
+            (i32.sub (local.get $ptr) (i32.const 4))))"#;
+
+    /// Copies stdin to stdout, then exits with 3
+    const CAT: &str = r#"(module
+        (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+        (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+        (memory (export "memory") 1)
+        (func (export "_start")
+            (i32.store (i32.const 0) (i32.const 100))
+            (i32.store (i32.const 4) (i32.const 1000))
+            (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+            (i32.store (i32.const 4) (i32.load (i32.const 8)))
+            (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+            (call $proc_exit (i32.const 3))))"#;
+
+    /// A run export whose body is `body`
+    fn run_module(body: &str) -> String {
+        format!(r#"(module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) i32.const 1024)
+            (func (export "run") (param i32 i32) (result i32) {} i32.const 0))"#, body)
+    }
+
+    fn load(dir: &tempfile::TempDir, name: &str, wat: &str, limits: WasmLimits) -> anyhow::Result<WasmSkill> {
+        let path = dir.path().join(format!("{}.wasm", name));
+        std::fs::write(&path, wat::parse_str(wat).unwrap()).unwrap();
+        WasmSkill::load(&path, limits)
+    }
+
+    async fn execute(skill: &WasmSkill, args: &str) -> serde_json::Value {
+        serde_json::from_str(&skill.execute(args).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn run_abi_returns_json_as_is_and_text_as_stdout() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = load(&dir, "echo", ECHO, LIMITS).unwrap();
+        assert_eq!(skill.abi, Abi::Run);
+        assert_eq!(skill.description, "Echoes its arguments");
+
+        assert_eq!(execute(&skill, r#"{"success":true,"n":1}"#).await, serde_json::json!({ "success": true, "n": 1 }));
+        assert_eq!(execute(&skill, "héllo").await, serde_json::json!({ "success": true, "stdout": "héllo" }));
+    }
+
+    #[tokio::test]
+    async fn wasi_command_reads_its_arguments_on_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = load(&dir, "cat", CAT, LIMITS).unwrap();
+        assert_eq!(skill.abi, Abi::Command);
+        assert_eq!(skill.description, "WASM plugin skill: cat");
+
+        let result = execute(&skill, "from stdin").await;
+        assert_eq!(result["stdout"], "from stdin");
+        assert_eq!(result["exit_code"], 3);
+        assert_eq!(result["success"], false);
+    }
+
+    #[tokio::test]
+    async fn an_endless_loop_runs_out_of_fuel() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = load(&dir, "spin", &run_module("(loop $forever (br $forever))"), LIMITS).unwrap();
+
+        let result = execute(&skill, "").await;
+        assert_eq!(result["success"], false);
+        assert!(result["error"].as_str().unwrap().starts_with("spin ran out of fuel after 10000000 instructions"), "{}", result);
+    }
+
+    #[tokio::test]
+    async fn growing_past_the_memory_limit_fails_the_call() {
+        let dir = tempfile::tempdir().unwrap();
+        // 16 pages of 64 KiB fill the 1 MB limit; the grow is refused and the module traps
+        let body = "(if (i32.eq (memory.grow (i32.const 16)) (i32.const -1)) (then unreachable))";
+        let skill = load(&dir, "hog", &run_module(body), LIMITS).unwrap();
+
+        let result = execute(&skill, "").await;
+        assert_eq!(result["success"], false);
+        assert!(result["error"].as_str().unwrap().starts_with("hog needed more than 1 MB of memory"), "{}", result);
+
+        let roomy = load(&dir, "hog", &run_module(body), WasmLimits { memory_bytes: 2 * 1024 * 1024, ..LIMITS }).unwrap();
+        assert_eq!(execute(&roomy, "").await, serde_json::json!({ "success": true, "stdout": "" }));
+    }
+
+    #[test]
+    fn a_module_without_an_entry_point_is_not_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = load(&dir, "inert", r#"(module (memory (export "memory") 1) (func (export "main")))"#, LIMITS).err().unwrap();
+        assert!(error.to_string().ends_with("exports neither memory, alloc and run nor _start"), "{}", error);
+
+        let path = dir.path().join("garbage.wasm");
+        std::fs::write(&path, b"\0asm not really").unwrap();
+        assert!(WasmSkill::load(&path, LIMITS).is_err());
+    }
+}
@@ -1,21 +1,198 @@
 //! Web Fetch Skill (Core)
+//!
+//! A plain GET by default. Pages rendered by JavaScript come back from it as
+//! an empty shell, so in `auto` mode (the default) an HTML body with almost no
+//! visible text, or one asking to enable JavaScript, is loaded again in the
+//! browser skill's session (the same browser, never a second one) and returned
+//! as readable markdown. `http` and `browser` force one path.
 
 use super::Skill;
+use crate::browser::{BrowserSkill, RenderedPage};
 use async_trait::async_trait;
+use regex::Regex;
 use reqwest::Client;
+use openspore_core::fmt_utils::truncate_chars;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
-pub struct WebFetchSkill;
+/// Characters of content returned when the call doesn't say
+const DEFAULT_MAX_CHARS: usize = 10_000;
+
+/// Longest a page may take to render in the browser
+const BROWSER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Less visible text than this means the page is built by scripts
+const MIN_VISIBLE_CHARS: usize = 200;
+
+/// So does a large document (over LARGE_HTML_BYTES) that is almost all markup
+const MIN_TEXT_RATIO: f64 = 0.02;
+const LARGE_HTML_BYTES: usize = 20_000;
+
+/// Pages that ask for JavaScript, unless they have plenty of text anyway
+const JS_MARKERS: &[&str] = &[
+    "enable javascript", "javascript is required", "javascript is disabled", "requires javascript",
+    "turn on javascript", "javascript must be enabled", "please enable js",
+];
+const MARKER_MAX_VISIBLE_CHARS: usize = 3_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FetchMode {
+    Auto,
+    Http,
+    Browser,
+}
+
+pub struct WebFetchSkill {
+    browser: Arc<BrowserSkill>,
+}
+
+impl WebFetchSkill {
+    pub fn new(browser: Arc<BrowserSkill>) -> Self {
+        Self { browser }
+    }
+}
+
+/// `"https://..."` or `{"url": "...", "mode": "auto", "max_chars": 20000}`
+fn parse_args(args: &str) -> Result<(String, FetchMode, usize), String> {
+    let args = args.trim();
+    let Some(json) = crate::utils::try_parse_json(args).filter(|j| j.is_object()) else {
+        return Ok((args.trim_matches('"').trim_matches('\'').to_string(), FetchMode::Auto, DEFAULT_MAX_CHARS));
+    };
+    let url = crate::utils::get_str_field(&json, "url").unwrap_or_default();
+    let mode = match crate::utils::get_str_field(&json, "mode").as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("auto") => FetchMode::Auto,
+        Some("http") => FetchMode::Http,
+        Some("browser") => FetchMode::Browser,
+        Some(other) => return Err(format!("Unknown mode '{}': use auto, http or browser", other)),
+    };
+    let max_chars = json.get("max_chars").and_then(|v| v.as_u64()).map_or(DEFAULT_MAX_CHARS, |n| n.max(1) as usize);
+    Ok((url, mode, max_chars))
+}
+
+/// Text of an HTML document as a browser would show it without scripts
+fn visible_text(html: &str, keep_noscript: bool) -> String {
+    static HIDDEN: OnceLock<Regex> = OnceLock::new();
+    static NOSCRIPT: OnceLock<Regex> = OnceLock::new();
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+    let hidden = HIDDEN.get_or_init(|| Regex::new(r"(?is)<script\b.*?</script>|<style\b.*?</style>|<template\b.*?</template>|<!--.*?-->").expect("valid regex"));
+    let noscript = NOSCRIPT.get_or_init(|| Regex::new(r"(?is)<noscript\b.*?</noscript>").expect("valid regex"));
+    let tags = TAGS.get_or_init(|| Regex::new(r"(?s)<[^>]*>").expect("valid regex"));
+    let mut text = hidden.replace_all(html, " ").into_owned();
+    if !keep_noscript {
+        text = noscript.replace_all(&text, " ").into_owned();
+    }
+    tags.replace_all(&text, " ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether an HTML body only makes sense once its scripts have run
+fn needs_javascript(html: &str) -> bool {
+    let visible = visible_text(html, false).chars().count();
+    if visible < MIN_VISIBLE_CHARS {
+        return true;
+    }
+    if html.len() > LARGE_HTML_BYTES && (visible as f64) < html.len() as f64 * MIN_TEXT_RATIO {
+        return true;
+    }
+    if visible > MARKER_MAX_VISIBLE_CHARS {
+        return false;
+    }
+    let shown = visible_text(html, true).to_lowercase();
+    JS_MARKERS.iter().any(|marker| shown.contains(marker))
+}
+
+fn is_html(content_type: &str, body: &str) -> bool {
+    content_type.contains("html") || (content_type.is_empty() && body.trim_start().starts_with('<'))
+}
+
+/// `<title>` and the canonical link of an HTML document
+fn title_and_canonical(html: &str) -> (Option<String>, Option<String>) {
+    static TITLE: OnceLock<Regex> = OnceLock::new();
+    static CANONICAL: OnceLock<Regex> = OnceLock::new();
+    let title = TITLE.get_or_init(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("valid regex"));
+    let canonical = CANONICAL.get_or_init(|| {
+        Regex::new(r#"(?is)<link\b[^>]*rel=["']?canonical["']?[^>]*href=["']([^"']+)["']|<link\b[^>]*href=["']([^"']+)["'][^>]*rel=["']?canonical"#).expect("valid regex")
+    });
+    let title = title.captures(html).map(|c| c[1].split_whitespace().collect::<Vec<_>>().join(" ")).filter(|t| !t.is_empty());
+    let canonical = canonical.captures(html).and_then(|c| c.get(1).or(c.get(2)).map(|m| m.as_str().to_string()));
+    (title, canonical)
+}
+
+/// At most `max_chars` characters, with a notice of what was left out
+fn truncate_content(content: &str, max_chars: usize) -> (String, bool) {
+    let total = content.chars().count();
+    if total <= max_chars {
+        return (content.to_string(), false);
+    }
+    let head = truncate_chars(content, max_chars);
+    (format!("{}\n\n[Truncated: showing {} of {} characters. Fetch again with a larger max_chars for more]", head, max_chars, total), true)
+}
+
+fn rendered_result(url: &str, page: RenderedPage, max_chars: usize, status: Option<u16>) -> serde_json::Value {
+    let source = page.canonical.clone().unwrap_or_else(|| page.url.clone());
+    let mut markdown = String::new();
+    if !page.title.trim().is_empty() {
+        markdown.push_str(&format!("# {}\n\n", page.title.trim()));
+    }
+    markdown.push_str(&format!("Source: {}\n\n{}", source, page.markdown));
+    let full_length = markdown.chars().count();
+    let (content, truncated) = truncate_content(&markdown, max_chars);
+    serde_json::json!({
+        "success": true,
+        "mode": "browser",
+        "status_code": status,
+        "title": page.title,
+        "canonical_url": page.canonical,
+        "final_url": page.url,
+        "content": content,
+        "full_length": full_length,
+        "truncated": truncated,
+        "url": url
+    })
+}
 
 #[async_trait]
 impl Skill for WebFetchSkill {
     fn name(&self) -> &'static str { "web_fetch" }
 
+    fn read_only(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
-        "Fetch content from a URL. Returns JSON with success, status_code, and content. Usage: [WEB_FETCH: \"https://example.com\"]"
+        "Fetch content from a URL. Pages built by JavaScript are rendered in the browser and returned as readable markdown. \
+         Returns JSON with success, mode, status_code, title and content. \
+         Usage: [WEB_FETCH: \"https://example.com\"] or [WEB_FETCH: {\"url\": \"https://example.com\", \"mode\": \"auto|http|browser\", \"max_chars\": 20000}]"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string" },
+                "mode": {
+                    "type": "string",
+                    "enum": ["auto", "http", "browser"],
+                    "description": "auto (default): plain GET, rendered in the browser when the page needs JavaScript; http: GET only; browser: always render"
+                },
+                "max_chars": { "type": "integer", "minimum": 1, "description": "Characters of content returned (default 10000)" }
+            },
+            "required": ["url"]
+        })
     }
 
     async fn execute(&self, args: &str) -> Result<String, String> {
-        let url = args.trim().trim_matches('"').trim_matches('\'');
+        let (url, mode, max_chars) = match parse_args(args) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(serde_json::json!({ "success": false, "error": e }).to_string()),
+        };
+        let url = url.as_str();
+
+        if mode == FetchMode::Browser {
+            let res = match tokio::time::timeout(BROWSER_TIMEOUT, self.browser.render_readable(url)).await {
+                Ok(Ok(page)) => rendered_result(url, page, max_chars, None),
+                Ok(Err(e)) => serde_json::json!({ "success": false, "mode": "browser", "error": format!("Rendering failed: {}", e), "url": url }),
+                Err(_) => serde_json::json!({ "success": false, "mode": "browser", "error": format!("Rendering took over {}s", BROWSER_TIMEOUT.as_secs()), "url": url }),
+            };
+            return Ok(res.to_string());
+        }
 
         let client = Client::builder()
             .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
@@ -25,22 +202,40 @@ impl Skill for WebFetchSkill {
         match client.get(url).send().await {
             Ok(response) => {
                 let status = response.status().as_u16();
+                let content_type = response.headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_lowercase();
                 match response.text().await {
                     Ok(body) => {
-                        let full_length = body.len();
-                        let truncated = if full_length > 10000 {
-                            format!("{}...\n[Truncated {} bytes]", &body[..10000], full_length - 10000)
-                        } else {
-                            body
-                        };
+                        let html = is_html(&content_type, &body);
+                        let mut browser_error = None;
+                        if mode == FetchMode::Auto && html && needs_javascript(&body) {
+                            match tokio::time::timeout(BROWSER_TIMEOUT, self.browser.render_readable(url)).await {
+                                Ok(Ok(page)) => return Ok(rendered_result(url, page, max_chars, Some(status)).to_string()),
+                                Ok(Err(e)) => browser_error = Some(format!("Rendering failed, raw HTML returned: {}", e)),
+                                Err(_) => browser_error = Some(format!("Rendering took over {}s, raw HTML returned", BROWSER_TIMEOUT.as_secs())),
+                            }
+                        }
 
-                        let res = serde_json::json!({
+                        let (title, canonical) = if html { title_and_canonical(&body) } else { (None, None) };
+                        let full_length = body.chars().count();
+                        let (content, truncated) = truncate_content(&body, max_chars);
+                        let mut res = serde_json::json!({
                             "success": true,
+                            "mode": "http",
                             "status_code": status,
-                            "content": truncated,
+                            "title": title,
+                            "canonical_url": canonical,
+                            "content": content,
                             "full_length": full_length,
+                            "truncated": truncated,
                             "url": url
                         });
+                        if let Some(e) = browser_error {
+                            res["browser_error"] = e.into();
+                        }
                         Ok(res.to_string())
                     },
                     Err(e) => {
@@ -0,0 +1,128 @@
+//! Web Search Skill (Core)
+//!
+//! Searches the web through the providers of the configuration (see
+//! AppConfig::search_providers): the Brave Search API, SerpAPI, a SearXNG
+//! instance, and DuckDuckGo's HTML page, which needs no key. A provider that
+//! fails (no key, HTTP error, captcha) hands over to the next one. The top
+//! results come back as a numbered markdown list the answer can cite as [1],
+//! [2]... The `search` skill searches the workspace instead.
+
+pub mod providers;
+
+use super::Skill;
+use async_trait::async_trait;
+use openspore_core::config::{AppConfig, SearchProvider};
+use providers::SearchHit;
+use reqwest::Client;
+use std::time::Duration;
+
+/// Results returned when the call doesn't say
+const DEFAULT_COUNT: usize = 5;
+const MAX_COUNT: usize = 20;
+
+/// Longest one provider may take before the next is tried
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub struct WebSearchSkill;
+
+/// `"query"` or `{"query": "...", "count": 5, "provider": "brave"}`
+fn parse_args(args: &str) -> Result<(String, usize, Option<SearchProvider>), String> {
+    let args = args.trim();
+    let Some(json) = crate::utils::try_parse_json(args).filter(|j| j.is_object()) else {
+        return Ok((args.trim_matches('"').trim_matches('\'').to_string(), DEFAULT_COUNT, None));
+    };
+    let query = crate::utils::get_str_field(&json, "query").unwrap_or_default();
+    let count = json.get("count").and_then(|v| v.as_u64()).map_or(DEFAULT_COUNT, |n| (n as usize).clamp(1, MAX_COUNT));
+    let provider = crate::utils::get_str_field(&json, "provider").map(|p| p.parse()).transpose()?;
+    Ok((query, count, provider))
+}
+
+/// Numbered results with their sources, for the model to cite
+fn format_hits(hits: &[SearchHit]) -> String {
+    let mut markdown = String::new();
+    for (i, hit) in hits.iter().enumerate() {
+        markdown.push_str(&format!("[{}] [{}]({})\n", i + 1, hit.title, hit.url));
+        if !hit.snippet.is_empty() {
+            markdown.push_str(&format!("    {}\n", hit.snippet));
+        }
+    }
+    markdown.push_str("\nCite these results by their number, e.g. [1], and list the URLs you used.");
+    markdown
+}
+
+#[async_trait]
+impl Skill for WebSearchSkill {
+    fn name(&self) -> &'static str { "web_search" }
+
+    fn read_only(&self) -> bool { true }
+
+    fn description(&self) -> &'static str {
+        "Search the web. Returns JSON with success, provider, results (title/url/snippet) and content: the results as a numbered markdown list to cite as [1], [2]. \
+         Use web_fetch to read a result. Usage: [WEB_SEARCH: \"rust async traits\"] or [WEB_SEARCH: {\"query\": \"...\", \"count\": 10}]"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "count": { "type": "integer", "minimum": 1, "maximum": MAX_COUNT, "description": "Results returned (default 5)" },
+                "provider": {
+                    "type": "string",
+                    "enum": SearchProvider::ALL.map(|p| p.as_str()),
+                    "description": "Ask this provider only (default: the configured ones in order)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: &str) -> Result<String, String> {
+        let (query, count, only) = match parse_args(args) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(serde_json::json!({ "success": false, "error": e }).to_string()),
+        };
+        if query.trim().is_empty() {
+            return Ok(serde_json::json!({ "success": false, "error": "Empty query" }).to_string());
+        }
+
+        let config = AppConfig::current();
+        let chain = match only {
+            Some(provider) => vec![provider],
+            None => config.search_providers(),
+        };
+        let client = Client::builder()
+            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+            .timeout(PROVIDER_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        let mut errors = Vec::new();
+        for provider in chain {
+            match providers::search(&client, &config, provider, &query, count).await {
+                Ok(hits) => {
+                    let mut res = serde_json::json!({
+                        "success": true,
+                        "query": query,
+                        "provider": provider.as_str(),
+                        "results": hits,
+                        "content": if hits.is_empty() { "No results.".to_string() } else { format_hits(&hits) }
+                    });
+                    if !errors.is_empty() {
+                        res["failed_providers"] = serde_json::json!(errors);
+                    }
+                    return Ok(res.to_string());
+                }
+                Err(e) => {
+                    tracing::warn!("🔎 {} search failed: {}", provider.as_str(), e);
+                    errors.push(format!("{}: {}", provider.as_str(), e));
+                }
+            }
+        }
+        Ok(serde_json::json!({
+            "success": false,
+            "query": query,
+            "error": format!("Every search provider failed: {}", errors.join("; "))
+        }).to_string())
+    }
+}
@@ -0,0 +1,257 @@
+//! One request and one result parser per search provider. Every parser
+//! returns the same SearchHit list, whatever the provider's format.
+
+use openspore_core::config::{AppConfig, SearchProvider};
+use regex::Regex;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+const BRAVE_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const SERPAPI_URL: &str = "https://serpapi.com/search.json";
+const DUCKDUCKGO_URL: &str = "https://html.duckduckgo.com/html/";
+
+/// Pages DuckDuckGo serves instead of results when it suspects a bot
+const DUCKDUCKGO_BLOCKED: &[&str] = &["anomaly-modal", "captcha", "unusual traffic"];
+
+/// One normalized search result
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// Ask one provider for up to `count` results
+pub async fn search(client: &Client, config: &AppConfig, provider: SearchProvider, query: &str, count: usize) -> Result<Vec<SearchHit>, String> {
+    let not_set = |var: &str| format!("{} is not set", var);
+    let request = match provider {
+        SearchProvider::Brave => {
+            let key = config.search_api_key(provider).ok_or_else(|| not_set("BRAVE_SEARCH_API_KEY"))?;
+            client.get(BRAVE_URL)
+                .header("X-Subscription-Token", key)
+                .header("Accept", "application/json")
+                .query(&[("q", query), ("count", &count.min(20).to_string())])
+        }
+        SearchProvider::SerpApi => {
+            let key = config.search_api_key(provider).ok_or_else(|| not_set("SERPAPI_API_KEY"))?;
+            client.get(SERPAPI_URL).query(&[("engine", "google"), ("q", query), ("num", &count.to_string()), ("api_key", key)])
+        }
+        SearchProvider::SearxNg => {
+            let base = config.search_api_key(provider).ok_or_else(|| not_set("SEARXNG_URL"))?;
+            client.get(format!("{}/search", base.trim_end_matches('/'))).query(&[("q", query), ("format", "json")])
+        }
+        SearchProvider::DuckDuckGo => client.get(DUCKDUCKGO_URL).query(&[("q", query)]),
+    };
+
+    // The URL can hold the key (SerpAPI): errors leave it out
+    let response = request.send().await.map_err(|e| format!("Request failed: {}", e.without_url()))?;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| format!("Failed to read body: {}", e.without_url()))?;
+    if !status.is_success() {
+        return Err(format!("HTTP {}: {}", status, openspore_core::fmt_utils::preview(&body, 200)));
+    }
+    let mut hits = parse(provider, &body)?;
+    hits.retain(|hit| !hit.url.is_empty());
+    hits.truncate(count);
+    Ok(hits)
+}
+
+/// The results in a provider's response body
+pub fn parse(provider: SearchProvider, body: &str) -> Result<Vec<SearchHit>, String> {
+    if provider == SearchProvider::DuckDuckGo {
+        return parse_duckduckgo(body);
+    }
+    let json: Value = serde_json::from_str(body).map_err(|e| format!("Unreadable reply: {}", e))?;
+    let (results, url_field, snippet_field) = match provider {
+        SearchProvider::Brave => (&json["web"]["results"], "url", "description"),
+        SearchProvider::SerpApi => {
+            if let Some(error) = json["error"].as_str() {
+                return Err(error.to_string());
+            }
+            (&json["organic_results"], "link", "snippet")
+        }
+        _ => (&json["results"], "url", "content"),
+    };
+    let text = |item: &Value, field: &str| clean_text(item[field].as_str().unwrap_or_default());
+    Ok(results.as_array().into_iter().flatten()
+        .map(|item| SearchHit { title: text(item, "title"), url: item[url_field].as_str().unwrap_or_default().to_string(), snippet: text(item, snippet_field) })
+        .collect())
+}
+
+/// Results of DuckDuckGo's HTML page. Its links go through a redirect that
+/// carries the target in `uddg`.
+fn parse_duckduckgo(html: &str) -> Result<Vec<SearchHit>, String> {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    static SNIPPET: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r#"(?s)<a[^>]*class="result__a"[^>]*href="([^"]*)"[^>]*>(.*?)</a>"#).expect("valid regex"));
+    let snippet = SNIPPET.get_or_init(|| Regex::new(r#"(?s)class="result__snippet"[^>]*>(.*?)</a>"#).expect("valid regex"));
+
+    let links: Vec<_> = link.captures_iter(html).collect();
+    if links.is_empty() && DUCKDUCKGO_BLOCKED.iter().any(|marker| html.contains(marker)) {
+        return Err("DuckDuckGo answered with a captcha".to_string());
+    }
+    let snippets: Vec<String> = snippet.captures_iter(html).map(|c| clean_text(&c[1])).collect();
+    Ok(links.iter().enumerate()
+        .map(|(i, c)| SearchHit {
+            title: clean_text(&c[2]),
+            url: duckduckgo_target(&decode_entities(&c[1])),
+            snippet: snippets.get(i).cloned().unwrap_or_default(),
+        })
+        .collect())
+}
+
+fn duckduckgo_target(href: &str) -> String {
+    let absolute = if href.starts_with("//") { format!("https:{}", href) } else { href.to_string() };
+    reqwest::Url::parse(&absolute).ok()
+        .and_then(|url| url.query_pairs().find(|(k, _)| k == "uddg").map(|(_, v)| v.into_owned()))
+        .unwrap_or(absolute)
+}
+
+/// Text without tags (highlighted terms), entities decoded and whitespace collapsed
+fn clean_text(html: &str) -> String {
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+    let tags = TAGS.get_or_init(|| Regex::new(r"<[^>]*>").expect("valid regex"));
+    decode_entities(&tags.replace_all(html, "")).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(text: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    let entity = ENTITY.get_or_init(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|amp|lt|gt|quot|apos|nbsp);").expect("valid regex"));
+    entity.replace_all(text, |c: &regex::Captures| {
+        let name = &c[1];
+        let code = match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ if name.starts_with("#x") => u32::from_str_radix(&name[2..], 16).ok().and_then(char::from_u32),
+            _ => name[1..].parse().ok().and_then(char::from_u32),
+        };
+        code.map_or_else(|| c[0].to_string(), String::from)
+    }).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRAVE: &str = r#"{"web":{"results":[
+        {"title":"Rust <strong>Book</strong>","url":"https://doc.rust-lang.org/book/","description":"Learn  Rust &amp; more"},
+        {"title":"Crates","url":"https://crates.io/","description":"The &lt;registry&gt;"}
+    ]}}"#;
+    const SERPAPI: &str = r#"{"organic_results":[
+        {"title":"Rust <b>Book</b>","link":"https://doc.rust-lang.org/book/","snippet":"Learn Rust &#38; more"},
+        {"title":"Crates","link":"https://crates.io/","snippet":"The &#x3C;registry&#x3E;"}
+    ]}"#;
+    const SEARXNG: &str = r#"{"results":[
+        {"title":"Rust Book","url":"https://doc.rust-lang.org/book/","content":"Learn Rust\n& more"},
+        {"title":"Crates","url":"https://crates.io/","content":"The &lt;registry&gt;"}
+    ]}"#;
+    const DUCKDUCKGO: &str = r#"<div class="result">
+        <a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fdoc.rust-lang.org%2Fbook%2F&amp;rut=abc">Rust <b>Book</b></a>
+        <a class="result__snippet" href="x">Learn Rust &amp; more</a>
+    </div>
+    <div class="result">
+        <a rel="nofollow" class="result__a" href="https://crates.io/">Crates</a>
+        <a class="result__snippet" href="y">The &lt;registry&gt;</a>
+    </div>"#;
+
+    fn expected() -> Vec<SearchHit> {
+        let hit = |title: &str, url: &str, snippet: &str| SearchHit { title: title.into(), url: url.into(), snippet: snippet.into() };
+        vec![
+            hit("Rust Book", "https://doc.rust-lang.org/book/", "Learn Rust & more"),
+            hit("Crates", "https://crates.io/", "The <registry>"),
+        ]
+    }
+
+    #[test]
+    fn every_provider_normalizes_to_the_same_hits() {
+        for (provider, body) in [
+            (SearchProvider::Brave, BRAVE),
+            (SearchProvider::SerpApi, SERPAPI),
+            (SearchProvider::SearxNg, SEARXNG),
+            (SearchProvider::DuckDuckGo, DUCKDUCKGO),
+        ] {
+            assert_eq!(parse(provider, body).unwrap(), expected(), "{:?}", provider);
+        }
+    }
+
+    #[test]
+    fn missing_fields_and_results_stay_empty() {
+        let hits = parse(SearchProvider::Brave, r#"{"web":{"results":[{"title":"No link"}]}}"#).unwrap();
+        assert_eq!(hits, vec![SearchHit { title: "No link".into(), url: String::new(), snippet: String::new() }]);
+        assert_eq!(parse(SearchProvider::SearxNg, r#"{"query":"x"}"#).unwrap(), Vec::new());
+        assert_eq!(parse(SearchProvider::DuckDuckGo, "<html>No results.</html>").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn provider_errors_are_reported() {
+        assert_eq!(parse(SearchProvider::SerpApi, r#"{"error":"Invalid API key."}"#).unwrap_err(), "Invalid API key.");
+        assert!(parse(SearchProvider::Brave, "<html>").unwrap_err().starts_with("Unreadable reply"));
+        assert_eq!(
+            parse(SearchProvider::DuckDuckGo, r#"<div class="anomaly-modal">Are you a robot?</div>"#).unwrap_err(),
+            "DuckDuckGo answered with a captcha"
+        );
+    }
+
+    #[test]
+    fn entities_decode_once() {
+        assert_eq!(decode_entities("&amp;lt; &#233; &#xE9; &bogus; &nbsp;x"), "&lt; é é &bogus;  x");
+        assert_eq!(clean_text("  a <em>b</em>\n\tc "), "a b c");
+        assert_eq!(duckduckgo_target("https://example.com/page"), "https://example.com/page");
+    }
+
+    /// One real query against `provider`, skipped unless `var` holds its key
+    /// or URL. Run with `cargo test -p openspore-skills -- --ignored live_`.
+    async fn live(provider: SearchProvider, var: Option<&str>) {
+        let value = match var.map(std::env::var) {
+            Some(Ok(value)) if !value.trim().is_empty() => Some(value),
+            Some(_) => return eprintln!("{} is not set: skipping the live {} test", var.unwrap_or_default(), provider.as_str()),
+            None => None,
+        };
+        let config = AppConfig {
+            brave_search_api_key: value.clone().filter(|_| provider == SearchProvider::Brave),
+            serpapi_api_key: value.clone().filter(|_| provider == SearchProvider::SerpApi),
+            searxng_url: value.filter(|_| provider == SearchProvider::SearxNg),
+            ..Default::default()
+        };
+        let client = Client::builder().user_agent("Mozilla/5.0 (X11; Linux x86_64)").build().unwrap();
+
+        let hits = search(&client, &config, provider, "rust programming language", 3).await
+            .unwrap_or_else(|e| panic!("{} search failed: {}", provider.as_str(), e));
+        assert!(!hits.is_empty() && hits.len() <= 3, "{}: {:?}", provider.as_str(), hits);
+        for hit in &hits {
+            assert!(hit.url.starts_with("http"), "{}: {:?}", provider.as_str(), hit);
+            assert!(!hit.title.is_empty() && !hit.title.contains('<'), "{}: {:?}", provider.as_str(), hit);
+            assert!(!hit.snippet.contains("</"), "{}: {:?}", provider.as_str(), hit);
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "queries Brave Search with BRAVE_SEARCH_API_KEY"]
+    async fn live_brave_results_normalize_into_hits() {
+        live(SearchProvider::Brave, Some("BRAVE_SEARCH_API_KEY")).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "queries SerpAPI with SERPAPI_API_KEY"]
+    async fn live_serpapi_results_normalize_into_hits() {
+        live(SearchProvider::SerpApi, Some("SERPAPI_API_KEY")).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "queries the SearXNG instance at SEARXNG_URL"]
+    async fn live_searxng_results_normalize_into_hits() {
+        live(SearchProvider::SearxNg, Some("SEARXNG_URL")).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "queries DuckDuckGo over the network"]
+    async fn live_duckduckgo_results_normalize_into_hits() {
+        live(SearchProvider::DuckDuckGo, None).await;
+    }
+}
@@ -1,24 +1,82 @@
 //! Write File Skill (Core)
 //! Supports both --content= syntax and heredoc <<<EOF blocks for multi-line content.
+//!
+//! Large files can be sent in chunks so a reply cut off by max_tokens never
+//! leaves half a file behind: JSON calls with `"append": true` accumulate in a
+//! staging file under workspace/partial_writes (one per target path), and the
+//! call with `"final": true` moves it into place, after checking
+//! `expected_total_sha256` when given. A mismatch discards the staged chunks;
+//! the target is only ever written whole. Staged writes abandoned before their
+//! final chunk are removed by housekeeping.
+//!
+//! Chunks carry a `chunk_index` counted from 0. Chunk 0 starts the staging file
+//! afresh, so leftovers of an earlier attempt at the same path are never
+//! prepended, and a later chunk with nothing staged before it is refused.
 
 use super::Skill;
 use async_trait::async_trait;
 use tokio::fs;
-use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use openspore_memory::sha256_hex;
 
 pub struct WriteFileSkill;
 
+/// A staged write untouched this long was abandoned: the next chunk starts over
+const STALE_STAGING: Duration = Duration::from_secs(3600);
+
 #[async_trait]
 impl Skill for WriteFileSkill {
     fn name(&self) -> &'static str { "write_file" }
 
+    fn destructive(&self) -> bool { true }
+
+    /// Chunks of one file must land in the order they were sent
+    fn exclusive(&self) -> bool { true }
+
     fn description(&self) -> &'static str {
-        "Write content to a file. Supports two modes:\n\
+        "Write content to a file. Supports three modes:\n\
          1. Inline: [WRITE_FILE: \"/path\" --content=\"content\"]\n\
-         2. Heredoc (recommended for code): [WRITE_FILE: \"/path\" <<<EOF\ncontent here\nEOF]"
+         2. Heredoc (recommended for code): [WRITE_FILE: \"/path\" <<<EOF\ncontent here\nEOF]\n\
+         3. Chunked (content over ~4KB): one call per chunk, in order, numbered from 0, the last with \"final\": true. \
+         Nothing is written until the final chunk arrives:\n\
+         [WRITE_FILE: {\"path\": \"/path\", \"content\": \"first part\", \"append\": true, \"chunk_index\": 0}]\n\
+         [WRITE_FILE: {\"path\": \"/path\", \"content\": \"last part\", \"append\": true, \"chunk_index\": 1, \"final\": true}]\n\
+         Add \"expected_total_sha256\" to the final chunk to have the whole file checked."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "Absolute or ~ path of the file" },
+                "content": { "type": "string", "description": "Full file content (written verbatim), or one chunk of it with append" },
+                "append": { "type": "boolean", "description": "Stage this chunk; the file is written when the final chunk arrives" },
+                "chunk_index": { "type": "integer", "description": "Position of this chunk, from 0; chunk 0 discards anything staged before" },
+                "final": { "type": "boolean", "description": "Last chunk: write the staged content to the file" },
+                "expected_total_sha256": { "type": "string", "description": "Final chunk: SHA-256 (hex) the whole content must have" }
+            },
+            "required": ["path", "content"]
+        })
     }
 
     async fn execute(&self, args: &str) -> Result<String, String> {
+        // Structured arguments (native tool calls) are taken verbatim, no heredoc/marker scanning
+        if args.trim_start().starts_with('{')
+            && let Some(json_args) = crate::utils::try_parse_json(args)
+            && let Some(p) = crate::utils::get_str_field(&json_args, "path").or_else(|| crate::utils::get_str_field(&json_args, "file"))
+        {
+            let c = crate::utils::get_str_field(&json_args, "content").unwrap_or_default();
+            let flag = |name: &str| json_args.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+            if flag("append") || flag("final") {
+                let expected = crate::utils::get_str_field(&json_args, "expected_total_sha256");
+                let index = json_args.get("chunk_index").and_then(|v| v.as_u64());
+                return write_chunk(&p, &c, index, flag("final"), expected.as_deref()).await;
+            }
+            return write_content(&p, &c).await;
+        }
+
         // Try heredoc syntax first: "/path" <<<EOF\ncontent\nEOF
         if let Some((path_raw, content)) = try_parse_heredoc(args) {
             return write_content(&path_raw, &content).await;
@@ -48,11 +106,11 @@ impl Skill for WriteFileSkill {
         let path = path_part.trim().trim_matches('"').trim_matches('\'').trim();
 
         let mut content = content_part.trim();
-        if (content.starts_with('"') && content.ends_with('"')) ||
-           (content.starts_with('\'') && content.ends_with('\'')) {
-            if content.len() >= 2 {
-                content = &content[1..content.len()-1];
-            }
+        if ((content.starts_with('"') && content.ends_with('"')) ||
+           (content.starts_with('\'') && content.ends_with('\'')))
+            && content.len() >= 2
+        {
+            content = &content[1..content.len()-1];
         }
 
         let final_content = crate::utils::unescape(content);
@@ -99,6 +157,126 @@ fn try_parse_heredoc(args: &str) -> Option<(String, String)> {
     Some((path_part.to_string(), content.to_string()))
 }
 
+/// Staging file of a chunked write to `target`
+fn staging_path(target: &Path) -> PathBuf {
+    let key = sha256_hex(target.to_string_lossy().as_bytes());
+    openspore_core::path_utils::get_app_root()
+        .join("workspace")
+        .join(openspore_memory::PARTIAL_WRITES_DIR)
+        .join(format!("{}.part", &key[..16]))
+}
+
+fn is_stale(staging: &Path) -> bool {
+    std::fs::metadata(staging)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified.elapsed().unwrap_or_default() > STALE_STAGING)
+}
+
+/// Stage one chunk of `path`; the final one moves the staged content into place
+async fn write_chunk(path_raw: &str, chunk: &str, index: Option<u64>, is_final: bool, expected_sha256: Option<&str>) -> Result<String, String> {
+    let path = crate::utils::sanitize_path(path_raw);
+    if path.is_empty() {
+        return Ok(serde_json::json!({ "success": false, "error": "Empty file path." }).to_string());
+    }
+    if crate::utils::is_safe_mode_active() && crate::utils::is_path_protected(&path) {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": "SAFE_MODE_ENABLED: Modifying engine core is forbidden."
+        }).to_string());
+    }
+
+    let Some(index) = index else {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": "Chunked writes need \"chunk_index\" (0 for the first chunk, then 1, 2, ...). Nothing was staged; send the chunks again from 0.",
+            "path": path
+        }).to_string());
+    };
+
+    let target = PathBuf::from(&path);
+    let staging = staging_path(&target);
+    if index == 0 || is_stale(&staging) {
+        fs::remove_file(&staging).await.ok();
+    }
+    if index > 0 && !staging.exists() {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!("Chunk {} of {} has no earlier chunks staged. Nothing was written; send the chunks again from chunk_index 0.", index, path),
+            "path": path
+        }).to_string());
+    }
+    let failed = |e: std::io::Error| Ok(serde_json::json!({
+        "success": false,
+        "error": format!("Failed to stage a chunk of {}: {}", path, e),
+        "path": path
+    }).to_string());
+    if let Some(parent) = staging.parent()
+        && let Err(e) = fs::create_dir_all(parent).await
+    {
+        return failed(e);
+    }
+    let appended = async {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&staging).await?;
+        file.write_all(chunk.as_bytes()).await?;
+        file.flush().await
+    };
+    if let Err(e) = appended.await {
+        return failed(e);
+    }
+    let staged = match fs::read(&staging).await {
+        Ok(bytes) => bytes,
+        Err(e) => return failed(e),
+    };
+
+    if !is_final {
+        return Ok(serde_json::json!({
+            "success": true,
+            "staged": true,
+            "path": path,
+            "chunk_index": index,
+            "chunk_bytes": chunk.len(),
+            "staged_bytes": staged.len(),
+            "next": format!("Send chunk_index {} with \"append\": true, the last one with \"final\": true. The file is written then.", index + 1)
+        }).to_string());
+    }
+
+    let sha256 = sha256_hex(&staged);
+    if let Some(expected) = expected_sha256
+        && !expected.trim().eq_ignore_ascii_case(&sha256)
+    {
+        fs::remove_file(&staging).await.ok();
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!(
+                "SHA-256 mismatch: expected {}, the {} staged bytes hash to {}. {} was not touched and the staged chunks were discarded; send them again from the first.",
+                expected.trim(), staged.len(), sha256, path
+            ),
+            "path": path
+        }).to_string());
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).await.ok();
+    }
+    // The staging directory may be on another filesystem than the target
+    if fs::rename(&staging, &target).await.is_err() {
+        if let Err(e) = fs::write(&target, &staged).await {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to write {}: {} (the staged chunks are kept)", path, e),
+                "path": path
+            }).to_string());
+        }
+        fs::remove_file(&staging).await.ok();
+    }
+    Ok(serde_json::json!({
+        "success": true,
+        "bytes_written": staged.len(),
+        "sha256": sha256,
+        "path": path
+    }).to_string())
+}
+
 async fn write_content(path_raw: &str, content: &str) -> Result<String, String> {
     let path = crate::utils::sanitize_path(path_raw);
 
@@ -137,3 +315,111 @@ async fn write_content(path_raw: &str, content: &str) -> Result<String, String>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(raw: String) -> serde_json::Value {
+        serde_json::from_str(&raw).unwrap()
+    }
+
+    async fn chunk(path: &Path, content: &str, index: u64, is_final: bool, sha: Option<&str>) -> serde_json::Value {
+        reply(write_chunk(path.to_str().unwrap(), content, Some(index), is_final, sha).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn chunks_assemble_into_the_file() {
+        crate::test_support::app_root();
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("out.txt");
+        let parts = ["alpha\n", "beta\n", "gamma\n"];
+        let whole = parts.concat();
+
+        assert_eq!(chunk(&target, parts[0], 0, false, None).await["staged_bytes"], 6);
+        assert!(!target.exists(), "nothing is written before the final chunk");
+        chunk(&target, parts[1], 1, false, None).await;
+        let done = chunk(&target, parts[2], 2, true, Some(&sha256_hex(whole.as_bytes()))).await;
+
+        assert_eq!(done["success"], true);
+        assert_eq!(done["bytes_written"], whole.len());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), whole);
+        assert!(!staging_path(&target).exists());
+    }
+
+    #[tokio::test]
+    async fn hash_mismatch_leaves_the_target_alone() {
+        crate::test_support::app_root();
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("kept.txt");
+        std::fs::write(&target, "original").unwrap();
+
+        chunk(&target, "new ", 0, false, None).await;
+        let done = chunk(&target, "content", 1, true, Some(&sha256_hex(b"something else"))).await;
+
+        assert_eq!(done["success"], false);
+        assert!(done["error"].as_str().unwrap().contains("SHA-256 mismatch"));
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "original");
+        assert!(!staging_path(&target).exists(), "the staged chunks are discarded");
+    }
+
+    #[tokio::test]
+    async fn chunk_zero_drops_an_abandoned_attempt() {
+        crate::test_support::app_root();
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("retry.txt");
+
+        chunk(&target, "stale first try ", 0, false, None).await;
+        chunk(&target, "fresh ", 0, false, None).await;
+        chunk(&target, "start", 1, true, None).await;
+
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "fresh start");
+    }
+
+    #[tokio::test]
+    async fn later_chunk_without_a_start_is_refused() {
+        crate::test_support::app_root();
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("orphan.txt");
+
+        let done = chunk(&target, "middle", 3, true, None).await;
+        assert_eq!(done["success"], false);
+        assert!(!target.exists());
+
+        let unnumbered = reply(write_chunk(target.to_str().unwrap(), "x", None, false, None).await.unwrap());
+        assert_eq!(unnumbered["success"], false);
+        assert!(!staging_path(&target).exists());
+    }
+
+    #[tokio::test]
+    async fn interleaved_paths_stage_separately() {
+        crate::test_support::app_root();
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (dir.path().join("a.txt"), dir.path().join("b.txt"));
+        assert_ne!(staging_path(&a), staging_path(&b));
+
+        chunk(&a, "a1 ", 0, false, None).await;
+        chunk(&b, "b1 ", 0, false, None).await;
+        chunk(&a, "a2", 1, true, None).await;
+        chunk(&b, "b2", 1, true, None).await;
+
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "a1 a2");
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), "b1 b2");
+    }
+
+    #[tokio::test]
+    async fn json_arguments_route_to_chunks() {
+        crate::test_support::app_root();
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("json.txt");
+        let call = |content: &str, index: u64, is_final: bool| serde_json::json!({
+            "path": target, "content": content, "append": true, "chunk_index": index, "final": is_final
+        }).to_string();
+
+        WriteFileSkill.execute(&call("one ", 0, false)).await.unwrap();
+        let done = reply(WriteFileSkill.execute(&call("two", 1, true)).await.unwrap());
+
+        assert_eq!(done["success"], true);
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "one two");
+    }
+}
@@ -1,13 +1,26 @@
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command;
-use tracing::info;
+use tracing::{info, warn};
 use anyhow::Result;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use tokio::sync::Semaphore;
-use once_cell::sync::Lazy;
+use openspore_core::config::AppConfig;
+use openspore_core::fmt_utils::{humanize_duration, preview};
+use openspore_core::process::{self, Termination};
 
-static SWARM_SEMAPHORE: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(6));
+mod registry;
+mod result_file;
+
+pub use registry::{QueuedSpore, SporeRegistry};
+pub use result_file::{write_answer, SporeAnswer, RESULT_FILE_ENV};
+use result_file::ResultFile;
+
+/// Queue tickets of this process
+static NEXT_TICKET: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SporeInfo {
@@ -17,74 +30,582 @@ pub struct SporeInfo {
     pub start_time: chrono::DateTime<chrono::Local>,
 }
 
+/// Time a sub-spore may run when the config cannot be loaded (see AppConfig::spore_timeout)
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(openspore_core::config::DEFAULT_SPORE_TIMEOUT_SECS);
+
+/// Registered spores older than this are killed by reap(). Timed-out spores are
+/// killed by their parent, so only orphans of a crashed parent get this old
+/// (the longest accepted timeout is MAX_SPORE_TIMEOUT_SECS).
+pub const REAP_MAX_AGE: Duration = Duration::from_secs(3600);
+
+/// Time a reaped spore gets to exit after SIGTERM
+const REAP_GRACE: Duration = Duration::from_secs(5);
+
+/// stderr kept in a SporeResult
+const STDERR_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SporeStatus {
+    Succeeded,
+    /// Exited with a non-zero status
+    Failed,
+    TimedOut,
+    /// Could not be started
+    SpawnError,
+}
+
+/// Outcome of one sub-spore
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SporeResult {
+    pub role: String,
+    pub task: String,
+    pub status: SporeStatus,
+    /// None when the spore timed out or never started
+    pub exit_code: Option<i32>,
+    /// Run time, not counting the wait for a swarm permit
+    pub duration_ms: u64,
+    pub output: String,
+    /// Last STDERR_CHARS characters of stderr (or the spawn/timeout error)
+    pub stderr: String,
+}
+
+impl SporeResult {
+    pub fn success(&self) -> bool {
+        self.status == SporeStatus::Succeeded
+    }
+}
+
+/// What reap() did with the registry
+#[derive(Debug, Default)]
+pub struct ReapReport {
+    /// Entries of spores that had already exited
+    pub exited: usize,
+    /// Spores past the max age, now terminated
+    pub killed: Vec<SporeInfo>,
+    /// Spores past the max age that would not die: (spore, error)
+    pub failed: Vec<(SporeInfo, String)>,
+    /// Spores still running within the max age
+    pub running: Vec<SporeInfo>,
+}
+
+/// Load of the swarm: spores holding a permit and spores waiting for one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct SwarmStatus {
+    pub max: usize,
+    pub running: usize,
+    pub queued: usize,
+}
+
+impl std::fmt::Display for SwarmStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} spores running, {} queued", self.running, self.max, self.queued)
+    }
+}
+
+/// Clones share the concurrency limit; separate managers each have their own
+#[derive(Clone)]
 pub struct SwarmManager {
     pub binary_path: PathBuf,
+    pub registry: SporeRegistry,
+    limiter: Arc<Semaphore>,
+    max_concurrent: usize,
+    timeout: Duration,
+    /// Spawns waiting for a permit
+    waiting: Arc<AtomicUsize>,
 }
 
 impl SwarmManager {
+    /// Limit and timeout from the loaded config (defaults when it cannot be loaded)
     pub fn new() -> Self {
+        match AppConfig::load() {
+            Ok(config) => Self::from_config(&config),
+            Err(_) => Self::with_limits(&openspore_core::path_utils::get_app_root(), openspore_core::config::DEFAULT_SWARM_MAX, DEFAULT_TIMEOUT),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::with_limits(&config.project_root, config.swarm_max_concurrent(), config.spore_timeout())
+    }
+
+    fn with_limits(root: &std::path::Path, max_concurrent: usize, timeout: Duration) -> Self {
         let binary_path = std::env::current_exe()
             .unwrap_or_else(|_| PathBuf::from("openspore"));
-        Self { binary_path }
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            binary_path,
+            registry: SporeRegistry::new(root),
+            limiter: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+            timeout,
+            waiting: Arc::new(AtomicUsize::new(0)),
+        }
     }
 
-    /// Spawn a new sub-spore (delegation)
-    pub async fn spawn(&self, task: &str, role: &str) -> Result<String> {
-        info!("🐝 Swarm: Waiting for permit to spawn sub-spore (Role: {})", role);
-        let _permit = SWARM_SEMAPHORE.acquire().await?;
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
 
-        info!("🐝 Swarm: Spawning sub-spore (Role: {}) for task: {}", role, task);
+    /// Time spawn() gives a sub-spore (SPORE_TIMEOUT_SECS)
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
 
-        let child = Command::new(&self.binary_path)
+    /// Spores of this manager (and its clones) running and waiting
+    pub fn status(&self) -> SwarmStatus {
+        SwarmStatus {
+            max: self.max_concurrent,
+            running: self.max_concurrent - self.limiter.available_permits(),
+            queued: self.waiting.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Spores of every process, from the registry, against this manager's
+    /// limit. Entries of exited spores and dead owners are not counted.
+    pub fn registry_status(&self) -> SwarmStatus {
+        let running = self.list_active().len();
+        let queued = self.registry.queued().iter()
+            .filter(|q| process::command_line(q.owner).is_some())
+            .count();
+        SwarmStatus { max: self.max_concurrent, running, queued }
+    }
+
+    /// Spawn a new sub-spore (delegation) with the configured timeout
+    pub async fn spawn(&self, task: &str, role: &str) -> Result<SporeResult> {
+        self.spawn_with_timeout(task, role, self.timeout).await
+    }
+
+    /// Spawn a sub-spore and wait for it. Err only when no permit could be had;
+    /// failures of the spore itself are reported in the result.
+    pub async fn spawn_with_timeout(&self, task: &str, role: &str, limit: Duration) -> Result<SporeResult> {
+        let _permit = match self.limiter.try_acquire() {
+            Ok(permit) => permit,
+            Err(_) => {
+                info!("🐝 Swarm: Waiting for permit to spawn sub-spore (Role: {}), {}", role, self.status());
+                let _queued = QueueEntry::new(self, task, role);
+                self.limiter.acquire().await?
+            }
+        };
+
+        info!("🐝 Swarm: Spawning sub-spore (Role: {}) for task: {}", role, preview(task, 120));
+        let started = Instant::now();
+        let mut result = SporeResult {
+            role: role.to_string(),
+            task: task.to_string(),
+            status: SporeStatus::SpawnError,
+            exit_code: None,
+            duration_ms: 0,
+            output: String::new(),
+            stderr: String::new(),
+        };
+
+        let result_file = ResultFile::new();
+        let mut child = match Command::new(&self.binary_path)
             .arg("think")
             .arg(task)
             .arg("--role")
             .arg(role)
-            .env("IS_SPORE", "true")
+            .arg("--spore")
+            .env(RESULT_FILE_ENV, &result_file.path)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .spawn()?;
-
-        // 3 minute timeout for sub-spores
-        match timeout(Duration::from_secs(180), child.wait_with_output()).await {
-            Ok(Ok(output)) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if output.status.success() {
-                    Ok(stdout)
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    Err(anyhow::anyhow!("Sub-spore failed ({}):\n{}\n{}", output.status, stdout, stderr))
-                }
+            // If this future is dropped (e.g. the caller gave up), the spore goes too
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                result.stderr = format!("Failed to start sub-spore: {}", e);
+                return Ok(result);
+            }
+        };
+
+        // Recorded so `openspore stop` and reap() can find it
+        let pid = child.id();
+        let mut registration = Registration { registry: &self.registry, pid, keep: false };
+        if let Some(pid) = pid {
+            let info = SporeInfo { pid, role: role.to_string(), task: task.to_string(), start_time: chrono::Local::now() };
+            if let Err(e) = self.registry.add(info) {
+                warn!("🐝 Swarm: Could not record sub-spore {}: {}", pid, e);
+            }
+        }
+
+        let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+        let outcome = timeout(limit, async {
+            tokio::join!(child.wait(), read_pipe(stdout), read_pipe(stderr))
+        }).await;
+
+        match outcome {
+            Ok((Ok(status), stdout, stderr)) => {
+                let stdout = String::from_utf8_lossy(&stdout).trim().to_string();
+                // The answer file, or stdout from spores that do not write one
+                result.output = match result_file.read().filter(|_| status.success()) {
+                    Some(Ok(answer)) => answer,
+                    Some(Err(e)) => {
+                        warn!("🐝 Swarm: {}, using stdout", e);
+                        stdout
+                    }
+                    None => stdout,
+                };
+                result.stderr = tail_chars(String::from_utf8_lossy(&stderr).trim(), STDERR_CHARS);
+                result.exit_code = status.code();
+                result.status = if status.success() { SporeStatus::Succeeded } else { SporeStatus::Failed };
+            }
+            Ok((Err(e), _, _)) => {
+                result.status = SporeStatus::Failed;
+                result.stderr = format!("Sub-spore error: {}", e);
             }
-            Ok(Err(e)) => Err(anyhow::anyhow!("Sub-spore error: {}", e)),
             Err(_) => {
-                // The child is already moved into wait_with_output, so if it timeouts,
-                // the future is dropped, which kills the child if it's the only handle?
-                // Actually, tokio's Child does NOT kill on drop unless configured.
-                Err(anyhow::anyhow!("Sub-spore timeout (10m)"))
+                // Commands the spore started (shells, browsers) would outlive it
+                let orphans = pid.map(process::descendants).unwrap_or_default();
+                if let Err(e) = child.kill().await {
+                    warn!("🐝 Swarm: Could not kill timed-out sub-spore {:?}: {}", pid, e);
+                    // Stays registered for reap() and stop
+                    registration.keep = true;
+                }
+                process::kill_all(&orphans);
+                result.status = SporeStatus::TimedOut;
+                result.stderr = format!("Sub-spore timeout ({}), killed", humanize_duration(limit));
             }
         }
+        result.duration_ms = started.elapsed().as_millis() as u64;
+        if !result.success() {
+            warn!("🐝 Swarm: Sub-spore (Role: {}) ended {:?} after {}", role, result.status, humanize_duration(started.elapsed()));
+        }
+        Ok(result)
     }
 
-    /// Discover active sub-spores via process table
-    pub async fn discovery(&self) -> Result<Vec<String>> {
-        let output = Command::new("ps")
-            .args(["aux"])
-            .output()
-            .await?;
+    /// Run several (task, role, timeout) spores concurrently, within the swarm
+    /// limit. Results come back in input order; one failing does not stop the others.
+    pub async fn fan_out(&self, tasks: Vec<(String, String, Duration)>) -> Vec<SporeResult> {
+        let runs = tasks.into_iter().map(|(task, role, limit)| async move {
+            match self.spawn_with_timeout(&task, &role, limit).await {
+                Ok(result) => result,
+                Err(e) => SporeResult {
+                    role,
+                    task,
+                    status: SporeStatus::SpawnError,
+                    exit_code: None,
+                    duration_ms: 0,
+                    output: String::new(),
+                    stderr: e.to_string(),
+                },
+            }
+        });
+        futures::future::join_all(runs).await
+    }
 
-        let content = String::from_utf8_lossy(&output.stdout);
-        let lines: Vec<String> = content.lines()
-            .filter(|l| l.contains("openspore think") && !l.contains("grep"))
-            .map(|s| s.to_string())
+    /// Clean up the registry: drop spores that exited and queue entries of dead
+    /// processes, terminate spores running longer than `max_age` (orphans). Blocks while a spore is being stopped.
+    pub fn reap(&self, max_age: Duration) -> ReapReport {
+        let mut report = ReapReport::default();
+        let now = chrono::Local::now();
+        let own = std::process::id();
+
+        let examined = self.registry.read();
+        let examined_pids: Vec<u32> = examined.iter().map(|s| s.pid).collect();
+        for spore in examined {
+            // A recycled pid no longer runs `think`
+            let running = spore.pid != own && runs_spore(spore.pid);
+            if !running {
+                report.exited += 1;
+                continue;
+            }
+            let age = (now - spore.start_time).to_std().unwrap_or_default();
+            if age <= max_age {
+                report.running.push(spore);
+                continue;
+            }
+            let orphans = process::descendants(spore.pid);
+            match process::terminate(spore.pid, REAP_GRACE) {
+                Termination::Terminated | Termination::Killed => {
+                    process::kill_all(&orphans);
+                    warn!("🐝 Swarm: Reaped sub-spore {} (Role: {}) after {}", spore.pid, spore.role, humanize_duration(age));
+                    report.killed.push(spore);
+                }
+                Termination::NotRunning => report.exited += 1,
+                Termination::Failed(e) => report.failed.push((spore, e)),
+            }
+        }
+
+        // Keep what still runs; spores registered meanwhile are untouched
+        let kept: Vec<u32> = report.running.iter().chain(report.failed.iter().map(|(s, _)| s)).map(|s| s.pid).collect();
+        if let Err(e) = self.registry.retain(|s| !examined_pids.contains(&s.pid) || kept.contains(&s.pid)) {
+            warn!("🐝 Swarm: Could not update the registry: {}", e);
+        }
+        // Queue entries of processes that died while waiting
+        if let Err(e) = self.registry.retain_queued(|q| process::command_line(q.owner).is_some()) {
+            warn!("🐝 Swarm: Could not update the queue: {}", e);
+        }
+        report
+    }
+
+    /// Running sub-spores of every process, oldest first. Entries left by
+    /// spores that crashed (or whose pid now runs something else) are skipped;
+    /// reap() removes them.
+    pub fn list_active(&self) -> Vec<SporeInfo> {
+        let mut spores: Vec<SporeInfo> = self.registry.read().into_iter()
+            .filter(|s| runs_spore(s.pid))
             .collect();
+        spores.sort_by_key(|s| s.start_time);
+        spores
+    }
+}
+
+/// Whether `pid` is alive and still runs `think` (pids get recycled)
+fn runs_spore(pid: u32) -> bool {
+    process::command_line(pid).is_some_and(|cmd| cmd.contains("think"))
+}
+
+/// Unregisters a spore when its run ends, also when the spawn future is
+/// dropped (kill_on_drop stops the process itself)
+struct Registration<'a> {
+    registry: &'a SporeRegistry,
+    pid: Option<u32>,
+    keep: bool,
+}
 
-        Ok(lines)
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        if let Some(pid) = self.pid
+            && !self.keep
+        {
+            let _ = self.registry.remove(pid);
+        }
+    }
+}
+
+/// Counts a spawn as queued (here and in the registry) until it is dropped,
+/// also when the waiting future is cancelled
+struct QueueEntry<'a> {
+    manager: &'a SwarmManager,
+    ticket: u64,
+}
+
+impl<'a> QueueEntry<'a> {
+    fn new(manager: &'a SwarmManager, task: &str, role: &str) -> Self {
+        manager.waiting.fetch_add(1, Ordering::Relaxed);
+        let ticket = NEXT_TICKET.fetch_add(1, Ordering::Relaxed);
+        let queued = QueuedSpore {
+            owner: std::process::id(),
+            ticket,
+            role: role.to_string(),
+            task: preview(task, 200),
+            since: chrono::Local::now(),
+        };
+        if let Err(e) = manager.registry.enqueue(queued) {
+            warn!("🐝 Swarm: Could not record queued sub-spore: {}", e);
+        }
+        Self { manager, ticket }
     }
 }
 
+impl Drop for QueueEntry<'_> {
+    fn drop(&mut self) {
+        self.manager.waiting.fetch_sub(1, Ordering::Relaxed);
+        let _ = self.manager.registry.dequeue(std::process::id(), self.ticket);
+    }
+}
+
+/// Everything a child writes to one of its pipes
+async fn read_pipe(pipe: Option<impl AsyncRead + Unpin>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut buf).await;
+    }
+    buf
+}
+
+/// The end of `text`, where errors usually are
+fn tail_chars(text: &str, max: usize) -> String {
+    let count = text.chars().count();
+    if count <= max {
+        return text.to_string();
+    }
+    format!("...{}", text.chars().skip(count - max).collect::<String>())
+}
+
 impl Default for SwarmManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::sync::OnceLock;
+
+    /// Stand-in for the openspore binary, written once so no test execs it
+    /// while another still has it open. Called as `think <dir> --role <role>`:
+    /// records its pid and a sleeping helper's in <dir>, then the "slow" role
+    /// hangs while others print the registry and exit.
+    fn fake_openspore() -> &'static Path {
+        static SCRIPT: OnceLock<PathBuf> = OnceLock::new();
+        SCRIPT.get_or_init(|| {
+            use std::os::unix::fs::PermissionsExt;
+            let dir = tempfile::tempdir().unwrap().keep();
+            let script = dir.join("openspore");
+            std::fs::write(&script, concat!(
+                "#!/bin/sh\n",
+                "dir=\"$2\"\n",
+                "echo $$ > \"$dir/spore.pid\"\n",
+                "sleep 30 &\n",
+                "echo $! > \"$dir/helper.pid\"\n",
+                "case \"$4\" in\n",
+                "  slow) sleep 30 ;;\n",
+                "  *) sleep 0.3; cat \"$dir/workspace/autonomy/state/swarm.json\"; kill $! ;;\n",
+                "esac\n",
+            )).unwrap();
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+            script
+        })
+    }
+
+    fn manager_in(root: &Path) -> SwarmManager {
+        let mut manager = SwarmManager::with_limits(root, 2, Duration::from_secs(60));
+        manager.binary_path = fake_openspore().to_path_buf();
+        manager
+    }
+
+    fn recorded_pid(dir: &Path, name: &str) -> u32 {
+        std::fs::read_to_string(dir.join(name)).unwrap().trim().parse().unwrap()
+    }
+
+    /// Whether `pid` left the process table (or is a zombie, which has no
+    /// command line) within a few seconds
+    fn gone(pid: u32) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while process::command_line(pid).is_some() {
+            if Instant::now() > deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        true
+    }
+
+    /// A `think` process as the registry would list it; a thread reaps it
+    fn dummy_spore() -> u32 {
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "sleep 30; true", "think"])
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        std::thread::spawn(move || child.wait());
+        pid
+    }
+
+    #[tokio::test]
+    async fn a_timed_out_spore_is_killed_with_its_helpers() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager_in(dir.path());
+
+        let task = dir.path().to_str().unwrap();
+        let result = manager.spawn_with_timeout(task, "slow", Duration::from_millis(500)).await.unwrap();
+        assert_eq!(result.status, SporeStatus::TimedOut);
+        assert!(result.stderr.contains("timeout"), "{}", result.stderr);
+
+        let spore = recorded_pid(dir.path(), "spore.pid");
+        let helper = recorded_pid(dir.path(), "helper.pid");
+        assert!(process::command_line(spore).is_none(), "the spore was killed and waited for");
+        assert!(gone(helper), "commands the spore started are killed too");
+        assert!(manager.registry.read().is_empty());
+        assert!(manager.list_active().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fanned_out_spores_past_their_timeout_leave_no_process() {
+        let dirs: Vec<_> = (0..3).map(|_| tempfile::tempdir().unwrap()).collect();
+        let manager = manager_in(dirs[0].path());
+
+        let tasks = dirs.iter()
+            .map(|d| (d.path().to_str().unwrap().to_string(), "slow".to_string(), Duration::from_millis(500)))
+            .collect();
+        let results = manager.fan_out(tasks).await;
+        assert!(results.iter().all(|r| r.status == SporeStatus::TimedOut), "{:?}", results);
+
+        for dir in &dirs {
+            assert!(gone(recorded_pid(dir.path(), "spore.pid")));
+            assert!(gone(recorded_pid(dir.path(), "helper.pid")));
+        }
+        let leftovers: Vec<_> = process::list_processes().into_iter()
+            .filter(|(_, cmd)| dirs.iter().any(|d| cmd.contains(d.path().to_str().unwrap())))
+            .collect();
+        assert!(leftovers.is_empty(), "{:?}", leftovers);
+        assert!(manager.registry.read().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_cancelled_spawn_kills_its_spore() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager_in(dir.path());
+
+        let task = dir.path().to_str().unwrap();
+        let cancelled = timeout(Duration::from_millis(500), manager.spawn(task, "slow")).await;
+        assert!(cancelled.is_err());
+
+        let spore = recorded_pid(dir.path(), "spore.pid");
+        assert!(gone(spore), "kill_on_drop stops the spore");
+        assert!(manager.registry.read().is_empty());
+        assert_eq!(manager.status().running, 0);
+        process::kill_all(&[recorded_pid(dir.path(), "helper.pid")]);
+    }
+
+    #[tokio::test]
+    async fn a_running_spore_is_registered_until_it_exits() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager_in(dir.path());
+
+        let task = dir.path().to_str().unwrap();
+        let result = manager.spawn(task, "researcher").await.unwrap();
+        assert_eq!(result.status, SporeStatus::Succeeded, "{}", result.stderr);
+        assert_eq!(result.exit_code, Some(0));
+
+        // The spore printed the registry while it ran
+        let listed: Vec<SporeInfo> = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].pid, recorded_pid(dir.path(), "spore.pid"));
+        assert_eq!((listed[0].role.as_str(), listed[0].task.as_str()), ("researcher", task));
+        assert!(!manager.registry.path().exists(), "removed on completion");
+    }
+
+    #[test]
+    fn reap_kills_spores_past_the_max_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager_in(dir.path());
+        let now = chrono::Local::now();
+        let entry = |pid: u32, started: chrono::DateTime<chrono::Local>| SporeInfo {
+            pid,
+            role: "worker".to_string(),
+            task: format!("task of {}", pid),
+            start_time: started,
+        };
+
+        let (orphan, fresh) = (dummy_spore(), dummy_spore());
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        let exited_pid = exited.id();
+        exited.wait().unwrap();
+        manager.registry.add(entry(orphan, now - chrono::Duration::hours(2))).unwrap();
+        manager.registry.add(entry(fresh, now)).unwrap();
+        manager.registry.add(entry(exited_pid, now)).unwrap();
+
+        let report = manager.reap(REAP_MAX_AGE);
+        assert_eq!(report.killed.iter().map(|s| s.pid).collect::<Vec<_>>(), vec![orphan]);
+        assert_eq!(report.running.iter().map(|s| s.pid).collect::<Vec<_>>(), vec![fresh]);
+        assert_eq!(report.exited, 1);
+        assert!(report.failed.is_empty());
+        assert!(gone(orphan));
+        assert!(process::command_line(fresh).is_some(), "young spores are left alone");
+
+        let registered: Vec<u32> = manager.registry.read().iter().map(|s| s.pid).collect();
+        assert_eq!(registered, vec![fresh]);
+        assert_eq!(manager.list_active().len(), 1);
+        let mut leftovers = process::descendants(fresh);
+        leftovers.push(fresh);
+        process::kill_all(&leftovers);
+    }
+}
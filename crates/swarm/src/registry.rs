@@ -0,0 +1,120 @@
+//! Registry of running sub-spores (workspace/autonomy/state/swarm.json)
+//!
+//! Each spawned spore is recorded until it exits, so `openspore stop` and
+//! SwarmManager::reap() can find spores whose parent died or never cleaned up.
+//! Spores waiting for a swarm permit are listed next to it (swarm_queue.json),
+//! so other processes can show the queue depth.
+
+use crate::SporeInfo;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Spores of one process are added and removed concurrently (fan-out)
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+/// A spore waiting for a permit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedSpore {
+    /// Process that will spawn it
+    pub owner: u32,
+    /// Unique within the owner process
+    pub ticket: u64,
+    pub role: String,
+    pub task: String,
+    pub since: chrono::DateTime<chrono::Local>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SporeRegistry {
+    path: PathBuf,
+    queue_path: PathBuf,
+}
+
+impl SporeRegistry {
+    pub fn new(root: &Path) -> Self {
+        let state = root.join("workspace").join("autonomy").join("state");
+        Self { path: state.join("swarm.json"), queue_path: state.join("swarm_queue.json") }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn read(&self) -> Vec<SporeInfo> {
+        read_list(&self.path)
+    }
+
+    /// Read-modify-write under the process-wide lock
+    fn update(&self, change: impl FnOnce(&mut Vec<SporeInfo>)) -> std::io::Result<()> {
+        update_list(&self.path, change)
+    }
+
+    pub fn add(&self, spore: SporeInfo) -> std::io::Result<()> {
+        self.update(|spores| {
+            spores.retain(|s| s.pid != spore.pid);
+            spores.push(spore);
+        })
+    }
+
+    pub fn remove(&self, pid: u32) -> std::io::Result<()> {
+        self.update(|spores| spores.retain(|s| s.pid != pid))
+    }
+
+    /// Keep only the spores `keep` accepts
+    pub fn retain(&self, keep: impl FnMut(&SporeInfo) -> bool) -> std::io::Result<()> {
+        self.update(|spores| spores.retain(keep))
+    }
+
+    pub fn clear(&self) -> std::io::Result<()> {
+        self.update(|spores| spores.clear())?;
+        update_list::<QueuedSpore>(&self.queue_path, |queue| queue.clear())
+    }
+
+    /// Spores waiting for a permit, oldest first
+    pub fn queued(&self) -> Vec<QueuedSpore> {
+        read_list(&self.queue_path)
+    }
+
+    pub fn enqueue(&self, spore: QueuedSpore) -> std::io::Result<()> {
+        update_list(&self.queue_path, |queue: &mut Vec<QueuedSpore>| queue.push(spore))
+    }
+
+    pub fn dequeue(&self, owner: u32, ticket: u64) -> std::io::Result<()> {
+        update_list(&self.queue_path, |queue: &mut Vec<QueuedSpore>| {
+            queue.retain(|q| q.owner != owner || q.ticket != ticket)
+        })
+    }
+
+    /// Keep only the queue entries `keep` accepts
+    pub fn retain_queued(&self, keep: impl FnMut(&QueuedSpore) -> bool) -> std::io::Result<()> {
+        update_list(&self.queue_path, |queue| queue.retain(keep))
+    }
+}
+
+fn read_list<T: DeserializeOwned>(path: &Path) -> Vec<T> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_list<T: Serialize>(path: &Path, items: &[T]) -> std::io::Result<()> {
+    if items.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(items)?)
+}
+
+fn update_list<T: Serialize + DeserializeOwned>(path: &Path, change: impl FnOnce(&mut Vec<T>)) -> std::io::Result<()> {
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut items = read_list(path);
+    change(&mut items);
+    write_list(path, &items)
+}
@@ -0,0 +1,69 @@
+//! Answer of a sub-spore, handed back through a file
+//!
+//! stdout of `openspore think` mixes the answer with whatever the run prints
+//! on the way (tool lines, warnings). The parent passes a fresh path in
+//! SPORE_RESULT_FILE and the spore writes `{"answer": ...}` there when it is
+//! done; stdout is only used as the answer when that file is missing, e.g.
+//! with an older binary.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Variable naming the file a spore writes its answer to
+pub const RESULT_FILE_ENV: &str = "SPORE_RESULT_FILE";
+
+/// Result files of this process
+static NEXT_RESULT: AtomicU64 = AtomicU64::new(0);
+
+/// Contents of the result file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SporeAnswer {
+    pub answer: String,
+}
+
+/// Spore side: write the answer where the parent asked for it. Ok(false) when
+/// SPORE_RESULT_FILE is not set (not spawned by a swarm).
+pub fn write_answer(answer: &str) -> Result<bool> {
+    let Some(path) = std::env::var_os(RESULT_FILE_ENV).map(PathBuf::from) else {
+        return Ok(false);
+    };
+    // Written aside and renamed so the parent never reads half a file
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_string(&SporeAnswer { answer: answer.to_string() })?)
+        .with_context(|| format!("Cannot write the spore result to {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Cannot write the spore result to {}", path.display()))?;
+    Ok(true)
+}
+
+/// Parent side: a unique path for one spore, deleted when dropped
+pub(crate) struct ResultFile {
+    pub path: PathBuf,
+}
+
+impl ResultFile {
+    pub fn new() -> Self {
+        let n = NEXT_RESULT.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("openspore-spore-{}-{}.json", std::process::id(), n));
+        let _ = std::fs::remove_file(&path);
+        Self { path }
+    }
+
+    /// None when the spore wrote nothing
+    pub fn read(&self) -> Option<Result<String>> {
+        let content = std::fs::read_to_string(&self.path).ok()?;
+        Some(
+            serde_json::from_str::<SporeAnswer>(&content)
+                .map(|a| a.answer)
+                .with_context(|| format!("Unreadable spore result {}", self.path.display())),
+        )
+    }
+}
+
+impl Drop for ResultFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+        let _ = std::fs::remove_file(self.path.with_extension("tmp"));
+    }
+}
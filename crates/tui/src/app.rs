@@ -1,4 +1,7 @@
 use openspore_brain::events::BrainEvent;
+use openspore_core::event_bus::SystemEvent;
+use crate::input::{InputEditor, HISTORY_FILE};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 #[derive(Clone, Debug)]
 pub struct ThoughtLayer {
@@ -12,7 +15,6 @@ pub struct ThoughtLayer {
 pub enum MessageAuthor {
     User,
     Ai,
-    #[allow(dead_code)]
     System,
 }
 
@@ -23,9 +25,17 @@ pub enum SelectableLine {
     LayerHeader(usize, usize), // (turn_idx, layer_idx)
     LayerContent(usize, usize, usize), // (turn_idx, layer_idx, line_idx)
     Tool(usize, usize), // (turn_idx, tool_idx)
+    Suggestion(usize, usize), // (turn_idx, suggestion_idx)
     Spacing,
 }
 
+impl SelectableLine {
+    /// Lines ↑↓ stop on: message headers, answer lines and thinking layer headers
+    pub fn is_stop(&self) -> bool {
+        matches!(self, SelectableLine::Header(_) | SelectableLine::Content(_, _) | SelectableLine::LayerHeader(_, _))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct MessageTurn {
     pub author: MessageAuthor,
@@ -33,48 +43,138 @@ pub struct MessageTurn {
     pub layers: Vec<ThoughtLayer>,
     pub active_tools: Vec<(String, String)>, // (name, arg)
     pub is_thinking: bool,
-    pub wrapped_cache: std::cell::RefCell<Option<(usize, Vec<String>)>>,
+    pub is_private: bool,
+    /// Follow-up prompts offered under an AI turn (Alt+1/2/3)
+    pub suggestions: Vec<String>,
+    /// Content rendered for a width (markdown for AI turns), one line per Content row
+    pub wrapped_cache: std::cell::RefCell<Option<(usize, Vec<ratatui::text::Line<'static>>)>>,
+}
+
+/// A tool call waiting for y/n in the footer (APPROVAL_MODE)
+#[derive(Clone, Debug)]
+pub struct PendingApproval {
+    pub id: u64,
+    pub name: String,
+    pub arg: String,
 }
 
 pub struct App {
     pub messages: Vec<MessageTurn>,
-    pub input: String,
+    pub input: InputEditor,
     pub should_quit: bool,
     pub flat_selection: usize,
     pub scroll_offset: usize, // Manual scroll viewport offset
     pub current_path: String,
     pub last_activity: String,
+    /// Active memory session (shown in the header)
+    pub session: String,
     pub mouse_captured: bool,
     pub scroll_follow_cursor: bool,
+    /// Approval requests in arrival order; the first one is shown
+    pub approvals: std::collections::VecDeque<PendingApproval>,
+    /// Running sub-spores of all processes (swarm panel), refreshed by the event loop
+    pub spores: Vec<openspore_swarm::SporeInfo>,
+    /// Lines of the last health snapshot (header panel), refreshed by the event loop
+    pub health: Vec<String>,
+    /// The user moved through the messages with ↑↓: the next y/Y copies the
+    /// selection instead of typing
+    pub selecting: bool,
+    /// Short-lived footer confirmation ("Copied 42 lines")
+    status: Option<(String, std::time::Instant)>,
+    /// Kept open: on X11 the copied text lives as long as the clipboard handle
+    pub clipboard: Option<arboard::Clipboard>,
+    /// Proposal approved with `/proposal approve`, started by the event loop as a turn
+    pub approved_proposal: Option<openspore_autonomy::proposals::Proposal>,
 }
 
+/// How long a footer status stays visible
+const STATUS_TTL: std::time::Duration = std::time::Duration::from_secs(4);
+
 impl App {
     pub fn new() -> Self {
         let root = openspore_core::path_utils::get_app_root();
         Self {
             messages: Vec::new(),
-            input: String::new(),
+            input: InputEditor::with_history(root.join("workspace").join(HISTORY_FILE)),
             should_quit: false,
             flat_selection: 0,
             scroll_offset: 0,
             current_path: root.to_string_lossy().to_string(),
             last_activity: String::from("No recent activity"),
+            session: openspore_memory::session::active_session(&root.join("workspace"))
+                .unwrap_or_else(|| openspore_memory::session::DEFAULT_SESSION.to_string()),
             mouse_captured: true,
             scroll_follow_cursor: true,
+            approvals: std::collections::VecDeque::new(),
+            spores: Vec::new(),
+            health: Vec::new(),
+            selecting: false,
+            status: None,
+            clipboard: None,
+            approved_proposal: None,
+        }
+    }
+
+    pub fn set_status(&mut self, text: String) {
+        self.status = Some((text, std::time::Instant::now()));
+    }
+
+    /// The footer status, while it is fresh
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_ref().filter(|(_, at)| at.elapsed() < STATUS_TTL).map(|(text, _)| text.as_str())
+    }
+
+    /// Index of the turn the selector is on
+    pub fn selected_turn(&self, width: usize) -> Option<usize> {
+        match self.get_selectable_lines(width).get(self.flat_selection)? {
+            SelectableLine::Header(i) | SelectableLine::Content(i, _) | SelectableLine::LayerHeader(i, _)
+            | SelectableLine::LayerContent(i, _, _) | SelectableLine::Tool(i, _) | SelectableLine::Suggestion(i, _) => Some(*i),
+            SelectableLine::Spacing => None,
         }
     }
 
     pub fn add_user_message(&mut self, content: String) {
+        // Only the latest answer offers follow-ups
+        for msg in &mut self.messages {
+            msg.suggestions.clear();
+        }
         self.messages.push(MessageTurn {
             author: MessageAuthor::User,
             content,
             layers: Vec::new(),
             active_tools: Vec::new(),
             is_thinking: false,
+            is_private: false,
+            suggestions: Vec::new(),
+            wrapped_cache: std::cell::RefCell::new(None),
+        });
+    }
+
+    pub fn add_system_message(&mut self, content: String) {
+        self.messages.push(MessageTurn {
+            author: MessageAuthor::System,
+            content,
+            layers: Vec::new(),
+            active_tools: Vec::new(),
+            is_thinking: false,
+            is_private: false,
+            suggestions: Vec::new(),
             wrapped_cache: std::cell::RefCell::new(None),
         });
     }
 
+    /// A turn is waiting for the brain
+    pub fn is_busy(&self) -> bool {
+        self.messages.iter().any(|m| m.is_thinking)
+    }
+
+    /// Empty the message list (`/clear`); memory and the session are untouched
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.flat_selection = 0;
+        self.scroll_offset = 0;
+    }
+
     pub fn start_thinking(&mut self) {
         self.messages.push(MessageTurn {
             author: MessageAuthor::Ai,
@@ -82,84 +182,179 @@ impl App {
             layers: Vec::new(),
             active_tools: Vec::new(),
             is_thinking: true,
+            is_private: false,
+            suggestions: Vec::new(),
             wrapped_cache: std::cell::RefCell::new(None),
         });
     }
 
     pub fn handle_event(&mut self, event: BrainEvent) {
-        if let Some(last) = self.messages.last_mut() {
-            if last.is_thinking {
-                match event {
-                    BrainEvent::ThoughtLayer { depth, content } => {
-                        last.layers.push(ThoughtLayer {
-                            depth,
-                            content,
-                            is_collapsed: true, // Fold layers by default
-                            wrapped_cache: std::cell::RefCell::new(None),
-                        });
-                        last.active_tools.clear(); // Clear tools after a layer finishes (next layer starts)
-                    }
-                    BrainEvent::ToolExecution { name, arg } => {
-                        last.active_tools.push((name, arg));
-                    }
-                    BrainEvent::ToolResult { name, output: _, success: _ } => {
-                        // We could track results, but for now just removing from active tools
-                        last.active_tools.retain(|(n, _)| n != &name);
-                    }
-                    BrainEvent::FinalAnswer(content) => {
-                        last.content = content;
-                        last.is_thinking = false;
-                        last.active_tools.clear();
-                        *last.wrapped_cache.borrow_mut() = None; // Invalidate
-                    }
-                    BrainEvent::Error(e) => {
-                        last.content = format!("Error: {}", e);
-                        last.is_thinking = false;
-                        last.active_tools.clear();
-                        *last.wrapped_cache.borrow_mut() = None; // Invalidate
-                    }
+        if matches!(event, BrainEvent::PrivateTurn) {
+            // Lock both the user prompt and the reply of the current exchange
+            for msg in self.messages.iter_mut().rev().take(2) {
+                msg.is_private = true;
+            }
+            return;
+        }
+
+        if let BrainEvent::ApprovalRequest { id, name, arg } = event {
+            self.approvals.push_back(PendingApproval { id, name, arg });
+            return;
+        }
+
+        // Arrives after FinalAnswer, once the turn is no longer thinking
+        if let BrainEvent::Suggestions(suggestions) = event {
+            if let Some(last) = self.messages.iter_mut().rev().find(|m| matches!(m.author, MessageAuthor::Ai)) {
+                last.suggestions = suggestions;
+            }
+            return;
+        }
+
+        // The turn in progress (slash commands may have added system turns after it)
+        if let Some(last) = self.messages.iter_mut().rev().find(|m| m.is_thinking) {
+            match event {
+                BrainEvent::ThoughtLayer { depth, content } => {
+                    last.layers.push(ThoughtLayer {
+                        depth,
+                        content,
+                        is_collapsed: true, // Fold layers by default
+                        wrapped_cache: std::cell::RefCell::new(None),
+                    });
+                    last.active_tools.clear(); // Clear tools after a layer finishes (next layer starts)
+                }
+                BrainEvent::ToolExecution { name, arg } => {
+                    last.active_tools.push((name, arg));
+                }
+                BrainEvent::ToolResult { name, output: _, success: _ } => {
+                    // We could track results, but for now just removing from active tools
+                    last.active_tools.retain(|(n, _)| n != &name);
+                }
+                BrainEvent::FinalAnswer(content) => {
+                    last.content = content;
+                    last.is_thinking = false;
+                    last.active_tools.clear();
+                    *last.wrapped_cache.borrow_mut() = None; // Invalidate
+                }
+                BrainEvent::PrivateTurn | BrainEvent::Suggestions(_) | BrainEvent::ApprovalRequest { .. } => {}
+                BrainEvent::Stats(stats) => {
+                    self.last_activity = format!(
+                        "Last cycle: {} tokens, ${:.4}, {} tools, {}{}{}",
+                        stats.total.total_tokens(), stats.total.cost, stats.tool_calls,
+                        openspore_core::fmt_utils::humanize_duration(std::time::Duration::from_millis(stats.duration_ms)),
+                        if stats.prompt_mode == openspore_brain::prompt_mode::PromptMode::Slim { " (chat)" } else { "" },
+                        if stats.fast_path.is_empty() { String::new() } else { format!(", fast path: {} skipped", stats.fast_path.total()) }
+                    );
+                }
+                BrainEvent::Error(e) => {
+                    last.content = format!("Error: {}", e);
+                    last.is_thinking = false;
+                    last.active_tools.clear();
+                    *last.wrapped_cache.borrow_mut() = None; // Invalidate
                 }
             }
         }
     }
 
+    /// Follow-up `n` (1-based) of the latest answer, if no turn is in progress.
+    /// The chips are cleared once one is taken.
+    pub fn take_suggestion(&mut self, n: usize) -> Option<String> {
+        let last = self.messages.last_mut().filter(|m| !m.is_thinking)?;
+        let suggestion = last.suggestions.get(n.checked_sub(1)?)?.clone();
+        last.suggestions.clear();
+        Some(suggestion)
+    }
+
+    /// Alt+1/2/3: the follow-up of the latest answer that key sends
+    pub fn followup_for_key(&mut self, key: &KeyEvent) -> Option<String> {
+        let KeyCode::Char(c @ '1'..='3') = key.code else { return None };
+        if !key.modifiers.contains(KeyModifiers::ALT) {
+            return None;
+        }
+        self.take_suggestion(c.to_digit(10)? as usize)
+    }
+
+    pub fn has_suggestions(&self) -> bool {
+        self.messages.last().is_some_and(|m| !m.suggestions.is_empty())
+    }
+
+    /// Workspace events from the event bus. Notable ones become system messages,
+    /// except mid-turn where a message would detach the reply in progress.
+    pub fn handle_system_event(&mut self, event: SystemEvent) {
+        let (activity, message) = match event {
+            SystemEvent::MemorySaved { category, title, .. } => (format!("💾 Saved {}/{}", category, title), None),
+            SystemEvent::ProposalCreated { title, path, auto_applied } => {
+                let text = if auto_applied {
+                    format!("🤖 Auto-applied proposal: {}", title)
+                } else {
+                    let id = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+                    format!("✨ New proposal awaiting approval: {} (/proposal show {})", title, id)
+                };
+                (text.clone(), Some(text))
+            }
+            SystemEvent::InboxMessage { source, text } => {
+                (format!("📬 Message from {}", source), Some(format!("📬 [{}] {}", source, text)))
+            }
+            SystemEvent::ConfigReloaded { changed, restart_required } => {
+                let activity = if changed.is_empty() {
+                    "⚙️ Configuration reloaded".to_string()
+                } else {
+                    format!("⚙️ Configuration reloaded: {}", changed.join(", "))
+                };
+                let message = (!restart_required.is_empty())
+                    .then(|| format!("⚠️ {} changed: restart OpenSpore to apply it", restart_required.join(", ")));
+                (activity, message)
+            }
+            SystemEvent::ConfigRejected { problems } => {
+                ("⚙️ Configuration change not applied".to_string(), Some(format!("❌ Configuration change not applied:\n{}", problems.join("\n"))))
+            }
+            SystemEvent::HeartbeatCompleted { status, .. } => (format!("💓 Heartbeat: {}", status), None),
+            SystemEvent::SkillReloaded { count } => (format!("🧩 Plugins reloaded ({} loaded)", count), None),
+            SystemEvent::MemoryConflict { path } => {
+                let text = format!("⚠️ Sync conflict in {}: resolve with `openspore memory resolve {} --ours|--theirs|--llm`", path, path);
+                (format!("⚠️ Sync conflict in {}", path), Some(text))
+            }
+            // Bookkeeping for search ranking, nothing to show
+            SystemEvent::MemoryUsed { .. } => return,
+        };
+
+        self.last_activity = activity;
+        let thinking = self.messages.last().is_some_and(|m| m.is_thinking);
+        if let Some(text) = message
+            && !thinking
+        {
+            self.add_system_message(text);
+        }
+    }
+
     pub fn toggle_selected_layers(&mut self, width: usize) {
         let lines = self.get_selectable_lines(width);
         // Toggle based on the line where the magenta selector is (flat_selection)
-        if let Some(line) = lines.get(self.flat_selection) {
-            match line {
-                SelectableLine::LayerHeader(turn_idx, layer_idx) |
-                SelectableLine::LayerContent(turn_idx, layer_idx, _) => {
-                    if let Some(msg) = self.messages.get_mut(*turn_idx) {
-                        if let Some(layer) = msg.layers.get_mut(*layer_idx) {
-                            layer.is_collapsed = !layer.is_collapsed;
-                        }
-                    }
-                }
-                _ => {}
-            }
+        if let Some(SelectableLine::LayerHeader(turn_idx, layer_idx) | SelectableLine::LayerContent(turn_idx, layer_idx, _)) = lines.get(self.flat_selection)
+            && let Some(msg) = self.messages.get_mut(*turn_idx)
+            && let Some(layer) = msg.layers.get_mut(*layer_idx)
+        {
+            layer.is_collapsed = !layer.is_collapsed;
         }
     }
 
     pub fn next(&mut self, width: usize) {
         self.scroll_follow_cursor = true;
+        self.selecting = true;
         let lines = self.get_selectable_lines(width);
         let start = self.flat_selection + 1;
-        for i in start..lines.len() {
-            if matches!(lines[i], SelectableLine::LayerHeader(_, _)) {
-                self.flat_selection = i;
-                return;
-            }
+        if let Some(offset) = lines.iter().skip(start).position(SelectableLine::is_stop) {
+            self.flat_selection = start + offset;
         }
     }
 
     pub fn previous(&mut self, width: usize) {
         self.scroll_follow_cursor = true;
+        self.selecting = true;
         let lines = self.get_selectable_lines(width);
         if self.flat_selection == 0 { return; }
         let start = self.flat_selection - 1;
         for i in (0..=start).rev() {
-            if matches!(lines[i], SelectableLine::LayerHeader(_, _)) {
+            if lines[i].is_stop() {
                 self.flat_selection = i;
                 return;
             }
@@ -180,6 +375,7 @@ impl App {
 
     pub fn scroll_to_bottom(&mut self, width: usize) {
         self.scroll_follow_cursor = true;
+        self.selecting = false;
         let count = self.get_selectable_lines(width).len();
         if count > 0 {
             self.flat_selection = count - 1;
@@ -220,21 +416,73 @@ impl App {
             // AI Final Content (after layers/thinking)
             if !msg.is_thinking || msg.content != "Thinking..." {
                 let mut cache = msg.wrapped_cache.borrow_mut();
-                let wrapped = if let Some((w, l)) = &*cache {
-                    if *w == width { l } else {
-                        *cache = Some((width, textwrap::wrap(&msg.content, width).iter().map(|s| s.to_string()).collect()));
-                        &cache.as_ref().unwrap().1
-                    }
-                } else {
-                    *cache = Some((width, textwrap::wrap(&msg.content, width).iter().map(|s| s.to_string()).collect()));
-                    &cache.as_ref().unwrap().1
-                };
+                if cache.as_ref().is_none_or(|(w, _)| *w != width) {
+                    let rendered = match msg.author {
+                        MessageAuthor::Ai => crate::markdown::render(&msg.content, width),
+                        MessageAuthor::User | MessageAuthor::System => crate::markdown::plain(&msg.content, width),
+                    };
+                    *cache = Some((width, rendered));
+                }
+                let rows = cache.as_ref().map_or(0, |(_, lines)| lines.len());
 
-                for j in 0..wrapped.len() {
+                for j in 0..rows {
                     lines.push(SelectableLine::Content(i, j));
                 }
             }
+
+            for j in 0..msg.suggestions.len() {
+                lines.push(SelectableLine::Suggestion(i, j));
+            }
         }
         lines
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answered_with(suggestions: &[&str]) -> App {
+        let mut app = App::new();
+        app.add_user_message("fix the parser".to_string());
+        app.start_thinking();
+        app.handle_event(BrainEvent::FinalAnswer("Fixed.".to_string()));
+        app.handle_event(BrainEvent::Suggestions(suggestions.iter().map(|s| s.to_string()).collect()));
+        app
+    }
+
+    fn key(c: char, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), modifiers)
+    }
+
+    #[test]
+    fn alt_digit_sends_that_followup_once() {
+        let mut app = answered_with(&["Run the tests", "Show the diff"]);
+        assert!(app.has_suggestions());
+
+        assert_eq!(app.followup_for_key(&key('2', KeyModifiers::ALT)).as_deref(), Some("Show the diff"));
+        assert!(!app.has_suggestions(), "the chips go once one is taken");
+        assert_eq!(app.followup_for_key(&key('1', KeyModifiers::ALT)), None);
+    }
+
+    #[test]
+    fn other_keys_leave_the_followups() {
+        let mut app = answered_with(&["Run the tests"]);
+
+        assert_eq!(app.followup_for_key(&key('1', KeyModifiers::NONE)), None);
+        assert_eq!(app.followup_for_key(&key('4', KeyModifiers::ALT)), None);
+        assert_eq!(app.followup_for_key(&key('2', KeyModifiers::ALT)), None);
+        assert!(app.has_suggestions());
+        assert_eq!(app.followup_for_key(&key('1', KeyModifiers::ALT | KeyModifiers::SHIFT)).as_deref(), Some("Run the tests"));
+    }
+
+    #[test]
+    fn no_followup_while_a_turn_runs() {
+        let mut app = answered_with(&["Run the tests"]);
+        app.add_user_message("and the docs?".to_string());
+        app.start_thinking();
+
+        assert!(!app.has_suggestions(), "a new prompt clears the old chips");
+        assert_eq!(app.followup_for_key(&key('1', KeyModifiers::ALT)), None);
+    }
+}
@@ -0,0 +1,226 @@
+//! Terminal attention signals (bell, OSC 9 notification, title flash)
+//!
+//! Each event kind maps to one action, configured under `[tui.attention]`.
+//! A rate limiter keeps bursts from spamming, and signals are suppressed while
+//! the terminal reports that it has focus.
+
+use openspore_core::config::AttentionConfig;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TITLE: &str = "OpenSpore";
+const TITLE_FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttentionKind {
+    AnswerReady,
+    ApprovalNeeded,
+    Error,
+}
+
+impl AttentionKind {
+    fn message(&self) -> &'static str {
+        match self {
+            AttentionKind::AnswerReady => "OpenSpore: answer ready",
+            AttentionKind::ApprovalNeeded => "OpenSpore: approval needed",
+            AttentionKind::Error => "OpenSpore: error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttentionAction {
+    Bell,
+    Osc9,
+    TitleFlash,
+    None,
+}
+
+impl AttentionAction {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "bell" | "bel" => Some(Self::Bell),
+            "osc9" | "notify" => Some(Self::Osc9),
+            "title" | "flash" | "title_flash" => Some(Self::TitleFlash),
+            "none" | "off" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// Minimum-interval limiter shared by all attention kinds
+pub struct RateLimiter {
+    min_interval: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(min_interval: Duration) -> Self {
+        Self { min_interval, last: None }
+    }
+
+    pub fn allow(&mut self, now: Instant) -> bool {
+        match self.last {
+            Some(last) if now.duration_since(last) < self.min_interval => false,
+            _ => {
+                self.last = Some(now);
+                true
+            }
+        }
+    }
+}
+
+pub struct Attention {
+    config: AttentionConfig,
+    limiter: RateLimiter,
+    /// None until the terminal reports a focus change
+    pub focused: Option<bool>,
+    title_restore_at: Option<Instant>,
+}
+
+impl Attention {
+    pub fn new(config: AttentionConfig) -> Self {
+        let interval = Duration::from_millis(config.min_interval_ms.unwrap_or(3000));
+        Self {
+            config,
+            limiter: RateLimiter::new(interval),
+            focused: None,
+            title_restore_at: None,
+        }
+    }
+
+    /// Resolve the configured action for an event kind (with defaults)
+    pub fn action_for(&self, kind: AttentionKind) -> AttentionAction {
+        let (configured, default) = match kind {
+            AttentionKind::AnswerReady => (&self.config.answer_ready, AttentionAction::Bell),
+            AttentionKind::ApprovalNeeded => (&self.config.approval_needed, AttentionAction::Osc9),
+            AttentionKind::Error => (&self.config.error, AttentionAction::TitleFlash),
+        };
+        configured.as_deref().and_then(AttentionAction::parse).unwrap_or(default)
+    }
+
+    /// Signal an event, honoring focus suppression and the rate limiter
+    pub fn signal<W: Write>(&mut self, out: &mut W, kind: AttentionKind) {
+        if self.focused == Some(true) {
+            return;
+        }
+        if !self.limiter.allow(Instant::now()) {
+            return;
+        }
+        let action = self.action_for(kind);
+        self.emit(out, action, kind);
+    }
+
+    /// Fire every kind unconditionally (`/bell test`)
+    pub fn test<W: Write>(&mut self, out: &mut W) -> Vec<String> {
+        [AttentionKind::AnswerReady, AttentionKind::ApprovalNeeded, AttentionKind::Error]
+            .into_iter()
+            .map(|kind| {
+                let action = self.action_for(kind);
+                self.emit(out, action, kind);
+                format!("{:?} -> {:?}", kind, action)
+            })
+            .collect()
+    }
+
+    fn emit<W: Write>(&mut self, out: &mut W, action: AttentionAction, kind: AttentionKind) {
+        let seq = match action {
+            AttentionAction::Bell => "\x07".to_string(),
+            AttentionAction::Osc9 => format!("\x1b]9;{}\x07", kind.message()),
+            AttentionAction::TitleFlash => {
+                self.title_restore_at = Some(Instant::now() + TITLE_FLASH_DURATION);
+                format!("\x1b]2;⚠ {}\x07", kind.message())
+            }
+            AttentionAction::None => return,
+        };
+        let _ = out.write_all(seq.as_bytes());
+        let _ = out.flush();
+    }
+
+    /// Restore the window title after a flash (call from the draw loop)
+    pub fn tick<W: Write>(&mut self, out: &mut W) {
+        if let Some(at) = self.title_restore_at
+            && Instant::now() >= at
+        {
+            let _ = out.write_all(format!("\x1b]2;{}\x07", DEFAULT_TITLE).as_bytes());
+            let _ = out.flush();
+            self.title_restore_at = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limiter_allows_one_signal_per_interval() {
+        let mut limiter = RateLimiter::new(Duration::from_millis(3000));
+        let start = Instant::now();
+
+        assert!(limiter.allow(start));
+        assert!(!limiter.allow(start + Duration::from_millis(1000)));
+        assert!(!limiter.allow(start + Duration::from_millis(2999)));
+        assert!(limiter.allow(start + Duration::from_millis(3000)));
+        // Refused attempts don't push the window back
+        assert!(!limiter.allow(start + Duration::from_millis(5999)));
+        assert!(limiter.allow(start + Duration::from_millis(6000)));
+    }
+
+    #[test]
+    fn actions_parse_with_aliases() {
+        assert_eq!(AttentionAction::parse(" BEL "), Some(AttentionAction::Bell));
+        assert_eq!(AttentionAction::parse("notify"), Some(AttentionAction::Osc9));
+        assert_eq!(AttentionAction::parse("title_flash"), Some(AttentionAction::TitleFlash));
+        assert_eq!(AttentionAction::parse("off"), Some(AttentionAction::None));
+        assert_eq!(AttentionAction::parse("siren"), None);
+    }
+
+    #[test]
+    fn mapping_falls_back_to_the_defaults() {
+        let defaults = Attention::new(AttentionConfig::default());
+        assert_eq!(defaults.action_for(AttentionKind::AnswerReady), AttentionAction::Bell);
+        assert_eq!(defaults.action_for(AttentionKind::ApprovalNeeded), AttentionAction::Osc9);
+        assert_eq!(defaults.action_for(AttentionKind::Error), AttentionAction::TitleFlash);
+
+        let configured = Attention::new(AttentionConfig {
+            answer_ready: Some("none".to_string()),
+            approval_needed: Some("bell".to_string()),
+            error: Some("siren".to_string()),
+            min_interval_ms: None,
+        });
+        assert_eq!(configured.action_for(AttentionKind::AnswerReady), AttentionAction::None);
+        assert_eq!(configured.action_for(AttentionKind::ApprovalNeeded), AttentionAction::Bell);
+        assert_eq!(configured.action_for(AttentionKind::Error), AttentionAction::TitleFlash, "unknown values keep the default");
+    }
+
+    #[test]
+    fn signals_are_rate_limited_and_muted_while_focused() {
+        let mut attention = Attention::new(AttentionConfig { min_interval_ms: Some(60_000), ..Default::default() });
+        let mut out = Vec::new();
+
+        attention.signal(&mut out, AttentionKind::ApprovalNeeded);
+        attention.signal(&mut out, AttentionKind::AnswerReady);
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b]9;OpenSpore: approval needed\x07");
+
+        let mut focused = Attention::new(AttentionConfig::default());
+        focused.focused = Some(true);
+        let mut out = Vec::new();
+        focused.signal(&mut out, AttentionKind::AnswerReady);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn title_flash_is_restored_on_tick() {
+        let mut attention = Attention::new(AttentionConfig::default());
+        let mut out = Vec::new();
+        attention.signal(&mut out, AttentionKind::Error);
+        assert!(String::from_utf8_lossy(&out).starts_with("\x1b]2;⚠ OpenSpore: error"));
+
+        attention.title_restore_at = Some(Instant::now());
+        let mut out = Vec::new();
+        attention.tick(&mut out);
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b]2;OpenSpore\x07");
+        assert!(attention.title_restore_at.is_none());
+    }
+}
@@ -0,0 +1,354 @@
+//! Slash commands of the TUI
+//!
+//! Input starting with `/` is handled here instead of going to the brain,
+//! except `/plan`, `/private` and `/define`, which the brain understands, and
+//! paths such as `/etc/hosts is empty?`. Unknown commands get a hint rather
+//! than a model call. Each command and its reply are shown as a system turn.
+
+use crate::app::{App, MessageAuthor, MessageTurn};
+use crate::attention::Attention;
+use openspore_brain::prompt_mode::PromptMode;
+use openspore_brain::Brain;
+use openspore_core::fmt_utils::preview;
+use openspore_memory::EXPORTS_DIR;
+use std::path::PathBuf;
+
+/// Commands the brain handles itself (see think_internal and submit_prompt)
+const BRAIN_COMMANDS: [&str; 3] = ["/plan", openspore_brain::privacy::PRIVATE_COMMAND, openspore_brain::glossary::DEFINE_COMMAND];
+
+/// `/help` lines
+const HELP: &[(&str, &str)] = &[
+    ("/model [fast|reasoning|<name>]", "Show or change the model of this session"),
+    ("/skills [reload]", "Loaded skills and what they do, or read the plugin directory again"),
+    ("/clear", "Clear the message list (memory is kept)"),
+    ("/export [path]", "Save the conversation as markdown in workspace/exports/"),
+    ("/session [name]", "List sessions, or switch to one (created if needed)"),
+    ("/swarm", "Running and queued sub-spores"),
+    ("/status", "Health snapshot: models, skills, memories, last runs, budgets, last error"),
+    ("/loglevel [filter|reset]", "Show or change the log filter"),
+    ("/bell test", "Fire every attention signal"),
+    ("/reload", "Re-read the autonomy schedule from openspore.toml"),
+    ("/inspect", "Recent chat/task classifier replies and what was read from them"),
+    ("/proposal [show|approve|reject <id> [reason]]", "Autonomy proposals: list, read, run or close one"),
+    ("/plan <prompt>", "Plan a turn: tools are recorded, not run"),
+    ("/private [N]", "Keep the next N turns out of memory"),
+    ("/define [Term = aliases: definition]", "Glossary terms"),
+    ("/help", "This list"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashCommand {
+    Help,
+    Model(String),
+    Skills(String),
+    Clear,
+    Export(String),
+    Session(String),
+    Swarm,
+    Status,
+    LogLevel(String),
+    BellTest,
+    Inspect,
+    Reload,
+    Proposal(String),
+    Unknown(String),
+}
+
+/// The command `input` names, None for input that goes to the brain
+pub fn parse(input: &str) -> Option<SlashCommand> {
+    let input = input.trim();
+    let rest = input.strip_prefix('/')?;
+    let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    // "/" alone, or a path
+    if name.is_empty() || name.contains('/') {
+        return None;
+    }
+    if BRAIN_COMMANDS.iter().any(|c| c[1..].eq_ignore_ascii_case(name)) {
+        return None;
+    }
+    let args = args.trim().to_string();
+    Some(match name.to_lowercase().as_str() {
+        "help" | "?" => SlashCommand::Help,
+        "model" => SlashCommand::Model(args),
+        "skills" => SlashCommand::Skills(args),
+        "clear" => SlashCommand::Clear,
+        "export" => SlashCommand::Export(args),
+        "session" => SlashCommand::Session(args),
+        "swarm" => SlashCommand::Swarm,
+        "status" => SlashCommand::Status,
+        "loglevel" => SlashCommand::LogLevel(args),
+        "bell" if args == "test" => SlashCommand::BellTest,
+        "inspect" => SlashCommand::Inspect,
+        "reload" => SlashCommand::Reload,
+        "proposal" | "proposals" => SlashCommand::Proposal(args),
+        _ => SlashCommand::Unknown(input.to_string()),
+    })
+}
+
+/// Run a command and return its reply (`out` is the terminal, for `/bell test`)
+pub fn run<W: std::io::Write>(command: SlashCommand, app: &mut App, brain: &Brain, attention: &mut Attention, out: &mut W) -> String {
+    match command {
+        SlashCommand::Help => help(),
+        SlashCommand::Model(args) => model_command(brain, &args),
+        SlashCommand::Skills(args) => skills_command(brain, &args),
+        SlashCommand::Clear => {
+            if app.is_busy() {
+                return "⏳ A turn is still running; /clear once it has answered.".to_string();
+            }
+            app.clear();
+            "🧹 Conversation cleared (memory and session are unchanged)".to_string()
+        }
+        SlashCommand::Export(path) => match export(app, brain, &path) {
+            Ok(path) => format!("📤 Conversation exported to {}", path.display()),
+            Err(e) => format!("❌ Export failed: {}", e),
+        },
+        SlashCommand::Session(name) => {
+            let reply = session_command(brain, &name);
+            app.session = openspore_memory::SessionManager::new(&brain.memory).active();
+            reply
+        }
+        SlashCommand::Swarm => swarm_command(brain),
+        SlashCommand::Status => {
+            let status = brain.status();
+            app.health = status.lines();
+            format!("🩺 Status (collected in {}ms):\n{}", status.collect_ms, app.health.join("\n"))
+        }
+        SlashCommand::LogLevel(args) => loglevel_command(&args),
+        SlashCommand::BellTest => format!("🔔 Attention test:\n{}", attention.test(out).join("\n")),
+        SlashCommand::Inspect => inspect_command(brain),
+        SlashCommand::Reload => reload_command(),
+        SlashCommand::Proposal(args) => proposal_command(app, brain, &args),
+        SlashCommand::Unknown(input) => format!("❓ Unknown command {}. /help lists the commands.", preview(&input, 40)),
+    }
+}
+
+fn help() -> String {
+    let width = HELP.iter().map(|(usage, _)| usage.chars().count()).max().unwrap_or(0);
+    let lines: Vec<String> = HELP.iter().map(|(usage, what)| format!("{:width$}  {}", usage, what, width = width)).collect();
+    format!("⌨️ Commands:\n{}", lines.join("\n"))
+}
+
+/// `/model` shows the model, `/model fast` uses the Fast model, `/model reasoning`
+/// returns to the configured one and `/model <name>` picks any model
+fn model_command(brain: &Brain, args: &str) -> String {
+    match args {
+        "" => {
+            let source = if brain.model_override().is_some() { "set with /model" } else { "configured" };
+            format!("🧠 Model: {} ({})", brain.current_model(), source)
+        }
+        "reasoning" | "default" | "reset" => {
+            brain.set_model_override(None);
+            format!("🧠 Model: {} (configured)", brain.current_model())
+        }
+        "fast" => {
+            let fast = brain.fast_model();
+            brain.set_model_override(Some(fast));
+            format!("🧠 Model for this session: {} (fast)", brain.current_model())
+        }
+        name => {
+            brain.set_model_override(Some(name.to_string()));
+            format!("🧠 Model for this session: {}", brain.current_model())
+        }
+    }
+}
+
+/// `/skills` lists the skills, `/skills reload` picks up plugins added to,
+/// changed in or removed from the plugin directory
+fn skills_command(brain: &Brain, args: &str) -> String {
+    match args {
+        "" => {}
+        "reload" => {
+            let plugins = brain.reload_skills();
+            let total = brain.skills().summaries().len();
+            return format!("🧩 Skills reloaded: {} plugin{} loaded, {} skills in all", plugins, if plugins == 1 { "" } else { "s" }, total);
+        }
+        other => return format!("❓ Unknown /skills argument {}. Use /skills or /skills reload.", preview(other, 30)),
+    }
+    let skills = brain.skills().summaries();
+    let lines: Vec<String> = skills.iter()
+        .map(|(name, description)| format!("• {}: {}", name, preview(description.lines().next().unwrap_or_default(), 100)))
+        .collect();
+    format!("🧩 {} skills:\n{}", skills.len(), lines.join("\n"))
+}
+
+/// Markdown of the conversation, thinking layers folded in `<details>`.
+/// Private turns are left out.
+fn render_markdown(messages: &[MessageTurn], session: &str) -> String {
+    let mut doc = format!("# Conversation {}\n\nSession: {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M"), session);
+    for msg in messages {
+        let author = match msg.author {
+            MessageAuthor::User => "User",
+            MessageAuthor::Ai => "Spore",
+            MessageAuthor::System => "System",
+        };
+        doc.push_str(&format!("\n## {}\n\n", author));
+        if msg.is_private {
+            doc.push_str("_(private turn, not exported)_\n");
+            continue;
+        }
+        for layer in &msg.layers {
+            doc.push_str(&format!(
+                "<details>\n<summary>Thinking layer {}</summary>\n\n{}\n\n</details>\n\n",
+                layer.depth, layer.content.trim()
+            ));
+        }
+        let content = if msg.is_thinking && msg.content.trim().is_empty() { "_(still thinking)_" } else { msg.content.trim() };
+        doc.push_str(content);
+        doc.push('\n');
+    }
+    doc
+}
+
+/// Write the conversation to `path` (relative paths under workspace/exports,
+/// default conversation-<time>.md)
+fn export(app: &App, brain: &Brain, path: &str) -> anyhow::Result<PathBuf> {
+    let dir = brain.memory.memory_root.join(EXPORTS_DIR);
+    let mut target = match path.trim() {
+        "" => dir.join(format!("conversation-{}.md", chrono::Local::now().format("%Y%m%d-%H%M%S"))),
+        path => {
+            let path = openspore_core::path_utils::get_path(path);
+            if path.is_absolute() { path } else { dir.join(path) }
+        }
+    };
+    if target.extension().is_none() {
+        target.set_extension("md");
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&target, render_markdown(&app.messages, &app.session))?;
+    Ok(target)
+}
+
+/// `/session` lists sessions, `/session <name>` switches (creating it if needed)
+fn session_command(brain: &Brain, name: &str) -> String {
+    let sessions = openspore_memory::SessionManager::new(&brain.memory);
+    if name.is_empty() {
+        let list: Vec<String> = sessions.list().iter()
+            .map(|s| format!("{} {} ({} exchanges)", if s.active { "*" } else { " " }, s.name, s.exchanges))
+            .collect();
+        return format!("🗂️ Sessions:\n{}", list.join("\n"));
+    }
+
+    let exists = sessions.list().iter().any(|s| s.name == name);
+    let result = if exists {
+        sessions.switch(name)
+    } else {
+        sessions.create(name).and_then(|_| sessions.switch(name))
+    };
+    match result {
+        Ok(()) => format!("🗂️ Active session: {}", name),
+        Err(e) => format!("❌ {}", e),
+    }
+}
+
+/// `/swarm` shows running and queued sub-spores of all processes
+fn swarm_command(brain: &Brain) -> String {
+    let swarm = openspore_swarm::SwarmManager::from_config(&brain.config);
+    let mut lines = vec![format!("🐝 Swarm: {}", swarm.registry_status())];
+    for queued in swarm.registry.queued() {
+        lines.push(format!("⏳ {} queued {}: {}", queued.role, queued.since.format("%H:%M:%S"), preview(&queued.task, 60)));
+    }
+    lines.join("\n")
+}
+
+/// Classifier runs shown by `/inspect`
+const INSPECT_SAMPLES: usize = 10;
+
+/// `/inspect` summarizes the prompt-mode classifier samples and lists the latest
+fn inspect_command(brain: &Brain) -> String {
+    let samples = brain.classifier_samples();
+    if samples.is_empty() {
+        let hint = if brain.config.prompt_mode_llm_check { "" } else { " (PROMPT_MODE_LLM_CHECK is off)" };
+        return format!("🔎 No classifier runs yet{}", hint);
+    }
+    let clean = samples.iter().filter(|s| s.is_clean()).count();
+    let unusable = samples.iter().filter(|s| s.verdict.is_none()).count();
+    let retried = samples.iter().filter(|s| s.replies.len() > 1).count();
+    let mut lines = vec![format!(
+        "🔎 Classifier: {} runs, {} one-word replies, {} read from longer text, {} unusable ({} retried)",
+        samples.len(), clean, samples.len() - clean - unusable, unusable, retried
+    )];
+    for sample in samples.iter().rev().take(INSPECT_SAMPLES) {
+        let verdict = match sample.verdict {
+            Some(PromptMode::Slim) => "chat",
+            Some(PromptMode::Full) => "task",
+            None => "????",
+        };
+        let replies: Vec<String> = sample.replies.iter().map(|r| format!("{:?}", preview(r.trim(), 40))).collect();
+        lines.push(format!("{} {} {:?} ← {}", sample.at.format("%H:%M:%S"), verdict, sample.message, replies.join(" → ")));
+    }
+    lines.join("\n")
+}
+
+/// `/reload` hands the schedule of the current configuration to the scheduler
+fn reload_command() -> String {
+    use openspore_autonomy::scheduler;
+    match scheduler::reload() {
+        Ok((schedule, true)) => format!("🕒 Schedule reloaded: {}", scheduler::describe(&schedule)),
+        Ok((schedule, false)) => format!("🕒 Schedule is valid ({}), but no scheduler runs here (AUTONOMY_ENABLED=false)", scheduler::describe(&schedule)),
+        Err(e) => format!("❌ {}. The current schedule stays.", e),
+    }
+}
+
+/// `/proposal` lists the proposals, `/proposal show <id>` prints one,
+/// `/proposal approve <id>` runs its Action Plan as the next turn and
+/// `/proposal reject <id> [reason]` closes it
+fn proposal_command(app: &mut App, brain: &Brain, args: &str) -> String {
+    use openspore_autonomy::proposals::{self, ProposalStore};
+    let store = ProposalStore::new(&brain.config.project_root);
+    let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    let rest = rest.trim();
+    match action {
+        "" | "list" => {
+            let all = store.list();
+            if all.is_empty() {
+                return "📭 No proposals yet".to_string();
+            }
+            let lines: Vec<String> = all.iter().take(15)
+                .map(|p| format!("{:26} {:12} {}", p.id, p.status, preview(&p.title, 60)))
+                .collect();
+            let pending = all.iter().filter(|p| p.status == proposals::PENDING).count();
+            format!("💡 Proposals ({} pending):\n{}", pending, lines.join("\n"))
+        }
+        "show" => match store.find(rest) {
+            Ok(proposal) => proposal.content.trim_end().to_string(),
+            Err(e) => format!("❌ {}", e),
+        },
+        "approve" => {
+            if app.is_busy() {
+                return "⏳ A turn is still running; approve once it has answered.".to_string();
+            }
+            match store.begin(rest) {
+                Ok(proposal) => {
+                    let reply = format!("▶️ Executing {}: {}", proposal.id, proposal.title);
+                    app.approved_proposal = Some(proposal);
+                    reply
+                }
+                Err(e) => format!("❌ {}", e),
+            }
+        }
+        "reject" => {
+            let (id, reason) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            match store.reject(id, reason) {
+                Ok(proposal) => format!("🚫 {} rejected: {}", proposal.id, proposal.title),
+                Err(e) => format!("❌ {}", e),
+            }
+        }
+        other => format!("❓ Unknown proposal action {} (show, approve or reject)", preview(other, 20)),
+    }
+}
+
+/// `/loglevel` shows the filter, `/loglevel <target>=<level>` changes it, `/loglevel reset` restores the default
+fn loglevel_command(args: &str) -> String {
+    use openspore_core::logging;
+    let result = match args {
+        "" => return format!("📜 Log filter: {}", logging::current_filter()),
+        "reset" => logging::reset_filter(),
+        directives => logging::set_filter(directives),
+    };
+    match result {
+        Ok(filter) => format!("📜 Log filter: {}", filter),
+        Err(e) => format!("❌ {}", e),
+    }
+}
@@ -0,0 +1,213 @@
+//! Prompt editor of the TUI
+//!
+//! The input box keeps a cursor (a byte offset into the text, always on a char
+//! boundary): typing and pasting insert at the cursor, ←→, Home/End and
+//! Ctrl+A/E move it, Backspace, Delete and Ctrl+W/Alt+Backspace delete around it.
+//! Submitted inputs go to the prompt history, persisted as one JSON string per
+//! line in workspace/.tui_history. PageUp/PageDown and Ctrl+↑/↓ walk it, since
+//! ↑↓ alone move through the messages and thinking layers.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use unicode_width::UnicodeWidthChar;
+
+/// Prompt history, relative to the workspace
+pub const HISTORY_FILE: &str = ".tui_history";
+
+/// Inputs kept in the history
+const HISTORY_MAX: usize = 500;
+
+#[derive(Debug, Default)]
+pub struct InputEditor {
+    text: String,
+    cursor: usize,
+    history: Vec<String>,
+    /// History entry shown while browsing, and the text typed before browsing
+    browsing: Option<(usize, String)>,
+    history_path: Option<PathBuf>,
+}
+
+impl InputEditor {
+    /// An editor whose history is read from and appended to `path`
+    pub fn with_history(path: PathBuf) -> Self {
+        let history = load_history(&path);
+        Self { history, history_path: Some(path), ..Default::default() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The text, leaving the editor empty
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        self.browsing = None;
+        std::mem::take(&mut self.text)
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.text[..self.cursor].char_indices().next_back().map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..].chars().next().map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    pub fn backspace(&mut self) {
+        let start = self.previous_boundary();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn delete(&mut self) {
+        let end = self.next_boundary();
+        self.text.replace_range(self.cursor..end, "");
+    }
+
+    /// Delete the word before the cursor, with the whitespace after it
+    pub fn delete_word(&mut self) {
+        let before = &self.text[..self.cursor];
+        let word_end = before.trim_end().len();
+        let start = before[..word_end].char_indices().rev().find(|(_, c)| c.is_whitespace()).map_or(0, |(i, c)| i + c.len_utf8());
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.previous_boundary();
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    /// Start of the current line
+    pub fn home(&mut self) {
+        self.cursor = self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1);
+    }
+
+    /// End of the current line
+    pub fn end(&mut self) {
+        self.cursor = self.text[self.cursor..].find('\n').map_or(self.text.len(), |i| self.cursor + i);
+    }
+
+    /// Add a submitted input to the history. Only `persist`ed inputs are
+    /// written to disk (private turns stay in this session).
+    pub fn remember(&mut self, input: &str, persist: bool) {
+        let input = input.trim();
+        if input.is_empty() || self.history.last().is_some_and(|last| last == input) {
+            return;
+        }
+        self.history.push(input.to_string());
+        if self.history.len() > HISTORY_MAX {
+            self.history.remove(0);
+        }
+        if persist && let Some(path) = &self.history_path && let Err(e) = append_history(path, input) {
+            warn!("⌨️ Prompt history not saved: {}", e);
+        }
+    }
+
+    /// Show the previous (older) history entry
+    pub fn history_previous(&mut self) {
+        let index = match &self.browsing {
+            Some((0, _)) => return,
+            Some((index, _)) => index - 1,
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+        let draft = self.browsing.take().map_or_else(|| self.text.clone(), |(_, draft)| draft);
+        self.show(self.history[index].clone());
+        self.browsing = Some((index, draft));
+    }
+
+    /// Show the next (newer) history entry, then the text typed before browsing
+    pub fn history_next(&mut self) {
+        let Some((index, draft)) = self.browsing.take() else { return };
+        if index + 1 < self.history.len() {
+            self.show(self.history[index + 1].clone());
+            self.browsing = Some((index + 1, draft));
+        } else {
+            self.show(draft);
+        }
+    }
+
+    fn show(&mut self, text: String) {
+        self.text = text;
+        self.cursor = self.text.len();
+    }
+
+    /// `prefix` and the text wrapped to `width` columns (at whitespace when
+    /// possible, explicit newlines kept), with the cursor as (column, row)
+    pub fn layout(&self, prefix: &str, width: usize) -> (Vec<String>, (u16, u16)) {
+        let width = width.max(1);
+        let display = format!("{}{}", prefix, self.text);
+        let cursor = prefix.len() + self.cursor;
+        let mut rows = Vec::new();
+        let mut position = (0, 0);
+        let mut offset = 0;
+        for line in display.split('\n') {
+            let chars: Vec<(usize, char)> = line.char_indices().map(|(i, c)| (offset + i, c)).collect();
+            let line_end = offset + line.len();
+            let byte_at = |i: usize| chars.get(i).map_or(line_end, |(b, _)| *b);
+            let mut start = 0;
+            loop {
+                let mut used = 0;
+                let mut end = start;
+                while let Some((_, c)) = chars.get(end) {
+                    let w = c.width().unwrap_or(0);
+                    if used + w > width && end > start {
+                        break;
+                    }
+                    used += w;
+                    end += 1;
+                }
+                if end < chars.len() && let Some(space) = (start + 1..end).rev().find(|&i| chars[i].1.is_whitespace()) {
+                    end = space + 1;
+                }
+                let on_row = cursor >= byte_at(start) && (cursor < byte_at(end) || (end == chars.len() && cursor <= line_end));
+                if on_row {
+                    let column: usize = chars[start..end].iter().take_while(|(b, _)| *b < cursor).map(|(_, c)| c.width().unwrap_or(0)).sum();
+                    position = (column as u16, rows.len() as u16);
+                }
+                rows.push(chars[start..end].iter().map(|(_, c)| *c).collect());
+                if end >= chars.len() {
+                    break;
+                }
+                start = end;
+            }
+            offset = line_end + 1;
+        }
+        (rows, position)
+    }
+}
+
+/// The newest HISTORY_MAX entries of the history file (rewritten when longer)
+fn load_history(path: &Path) -> Vec<String> {
+    let Ok(text) = std::fs::read_to_string(path) else { return Vec::new() };
+    let mut history: Vec<String> = text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+    if history.len() > HISTORY_MAX {
+        history.drain(..history.len() - HISTORY_MAX);
+        let lines: Vec<String> = history.iter().filter_map(|entry| serde_json::to_string(entry).ok()).collect();
+        if let Err(e) = std::fs::write(path, format!("{}\n", lines.join("\n"))) {
+            warn!("⌨️ Prompt history not trimmed: {}", e);
+        }
+    }
+    history
+}
+
+fn append_history(path: &Path, input: &str) -> anyhow::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(input)?)?;
+    Ok(())
+}
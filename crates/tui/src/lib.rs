@@ -1,6 +1,6 @@
 use std::{
     io,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use crossterm::{
     event::{self, Event, KeyCode},
@@ -9,14 +9,53 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
-use openspore_brain::Brain;
+use openspore_brain::{Brain, ThinkOptions};
 use openspore_brain::events::BrainEvent;
+use openspore_core::event_bus::{self, SystemEvent};
 
 mod app;
+mod attention;
+mod commands;
+mod input;
+mod markdown;
 mod ui;
+mod yank;
 
 use app::App;
 
+/// How often the swarm panel re-reads the spore registry
+const SWARM_REFRESH: Duration = Duration::from_secs(1);
+
+/// How often the header's health snapshot is collected again
+const HEALTH_REFRESH: Duration = Duration::from_secs(15);
+
+/// Startup diagnosis: changes nothing unless a critical issue is found and
+/// the user agrees to the prescriptions
+fn preflight_doctor() {
+    use openspore_doctor::{DoctorMode, Severity, SporeDoctor};
+    use std::io::Write;
+
+    let mut doctor = SporeDoctor::new();
+    let report = doctor.run(DoctorMode::Check);
+    if !report.has_critical() {
+        return;
+    }
+    eprintln!("🩺 The doctor found critical issues:");
+    for issue in report.issues.iter().filter(|i| i.severity == Severity::Critical) {
+        eprintln!("   {}", issue.line());
+    }
+    eprint!("\nApply the prescriptions now? [y/N] ");
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+        eprintln!("   Skipped. `openspore doctor` repairs what it can.");
+        return;
+    }
+    for treatment in doctor.run(DoctorMode::Fix).treatments {
+        eprintln!("   {}: {}", treatment.id, treatment.line());
+    }
+}
+
 pub async fn run() -> anyhow::Result<()> {
     // 1. Pre-flight Checks (Outside of Terminal Alternate Screen)
     let config = match openspore_core::config::AppConfig::load() {
@@ -29,14 +68,13 @@ pub async fn run() -> anyhow::Result<()> {
         }
     };
 
-    let mut doctor = openspore_doctor::SporeDoctor::new();
-    doctor.check_all();
+    preflight_doctor();
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
     // 2. Setup TUI Terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, event::EnableMouseCapture, event::EnableBracketedPaste)?;
+    execute!(stdout, EnterAlternateScreen, event::EnableMouseCapture, event::EnableBracketedPaste, event::EnableFocusChange)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -45,30 +83,38 @@ pub async fn run() -> anyhow::Result<()> {
     // Initialize Core Components
     let state = openspore_core::state::AppState::new(config.clone());
     let memory = openspore_memory::MemorySystem::new(&state);
-    let brain = Brain::new(config.clone());
+    let brain = Brain::new(config.clone()).with_channel("tui");
+    // Model and endpoint edits in .env apply without a restart (shared by the clones below)
+    brain.follow_config(openspore_core::config::AppConfig::watch());
+    // Learning and compression a previous run left unfinished
+    brain.resume_pending_work();
 
     // Start Watchman in background
-    let watchman = std::sync::Arc::new(openspore_watchman::Watchman::new(config.clone(), brain.clone_brain(), memory.clone()));
+    let watchman = std::sync::Arc::new(openspore_watchman::Watchman::new(config.clone(), brain.clone_brain().with_channel("watchman"), memory.clone()));
     let wm = watchman.clone();
     tokio::spawn(async move {
-        if let Err(_) = wm.start().await {}
+        let _ = wm.start().await;
     });
 
     // Start Telegram Gateway in background
-    if let Some(token) = config.telegram_bot_token.as_ref() {
-        if !token.is_empty() {
-            if let Ok(tg) = openspore_telegram::TelegramChannel::new() {
-                let tg_clone = tg.clone();
-                tokio::spawn(async move {
-                    let _ = tg_clone.start().await;
-                });
-            }
-        }
+    if let Some(token) = config.telegram_bot_token.as_ref()
+        && !token.is_empty()
+            && let Ok(tg) = openspore_telegram::TelegramChannel::new()
+    {
+        let tg_clone = tg.clone();
+        tokio::spawn(async move {
+            let _ = tg_clone.start().await;
+        });
     }
 
+    // Bridge cross-process events (cron jobs, spores) onto the event bus
+    event_bus::spawn_inbox_bridge(memory.memory_root.clone());
+    // Injected/cited counts of memories, for search ranking and `memory review`
+    memory.spawn_usage_tracker();
+
     // Start Autonomy Scheduler in background
     if config.autonomy_enabled {
-        let brain_clone = brain.clone_brain();
+        let brain_clone = brain.clone_brain().with_channel("cron");
         let memory_clone = memory.clone();
         let tg_opt = openspore_telegram::TelegramChannel::new().ok();
         tokio::spawn(async move {
@@ -76,7 +122,9 @@ pub async fn run() -> anyhow::Result<()> {
         });
     }
 
-    let res = run_app(&mut terminal, &mut app, brain).await;
+    let res = run_app(&mut terminal, &mut app, brain.clone_brain()).await;
+    // Acknowledgments of the last turns still waiting for their journal line
+    brain.flush_journal().await;
 
     // Restore terminal
     disable_raw_mode()?;
@@ -84,7 +132,8 @@ pub async fn run() -> anyhow::Result<()> {
         terminal.backend_mut(),
         LeaveAlternateScreen,
         event::DisableMouseCapture,
-        event::DisableBracketedPaste
+        event::DisableBracketedPaste,
+        event::DisableFocusChange
     )?;
     terminal.show_cursor()?;
 
@@ -97,6 +146,12 @@ async fn run_app<B: ratatui::backend::Backend + std::io::Write>(
     brain: Brain,
 ) -> anyhow::Result<()> {
     let (tx_events, mut rx_events) = mpsc::channel::<BrainEvent>(32);
+    let mut system_events = event_bus::bus().subscribe();
+    let mut attention = attention::Attention::new(brain.config.tui.attention.clone());
+    let swarm = openspore_swarm::SwarmManager::from_config(&brain.config);
+    let mut swarm_checked: Option<Instant> = None;
+    let (tx_health, mut rx_health) = mpsc::channel::<Vec<String>>(1);
+    let mut health_checked: Option<Instant> = None;
 
     let area = terminal.size()?;
     let width = area.width.saturating_sub(4) as usize;
@@ -114,7 +169,25 @@ async fn run_app<B: ratatui::backend::Backend + std::io::Write>(
         let area = terminal.size()?;
         let width = area.width.saturating_sub(4) as usize;
 
+        if swarm_checked.is_none_or(|t| t.elapsed() >= SWARM_REFRESH) {
+            app.spores = swarm.list_active();
+            swarm_checked = Some(Instant::now());
+        }
+        // Collected off the draw loop: it reads the workspace
+        if health_checked.is_none_or(|t| t.elapsed() >= HEALTH_REFRESH) {
+            let b = brain.clone_brain();
+            let tx = tx_health.clone();
+            tokio::task::spawn_blocking(move || {
+                let _ = tx.try_send(b.status().lines());
+            });
+            health_checked = Some(Instant::now());
+        }
+        while let Ok(lines) = rx_health.try_recv() {
+            app.health = lines;
+        }
+
         terminal.draw(|f| ui::draw(f, app))?;
+        attention.tick(terminal.backend_mut());
 
         if event::poll(Duration::from_millis(50))? {
             match event::read()? {
@@ -123,22 +196,50 @@ async fn run_app<B: ratatui::backend::Backend + std::io::Write>(
                         KeyCode::Esc => app.should_quit = true,
                         KeyCode::Enter => {
                             if key.modifiers.contains(event::KeyModifiers::SHIFT) || key.modifiers.contains(event::KeyModifiers::ALT) {
-                                app.input.push('\n');
+                                app.input.insert('\n');
+                            } else if !app.approvals.is_empty() {
+                                let input = app.input.take();
+                                answer_approval(app, &brain, &input);
+                                app.scroll_to_bottom(width);
                             } else {
-                                let input = app.input.drain(..).collect::<String>();
-                                if !input.trim().is_empty() {
-                                    app.add_user_message(input.clone());
-                                    app.start_thinking();
+                                let input = app.input.take();
+                                // Private turns stay out of .tui_history
+                                app.input.remember(&input, !brain.is_private_turn(&input));
+                                if let Some(command) = commands::parse(&input) {
+                                    let reply = commands::run(command, app, &brain, &mut attention, terminal.backend_mut());
+                                    app.add_system_message(format!("{}\n{}", input.trim(), reply));
+                                    if let Some(proposal) = app.approved_proposal.take() {
+                                        execute_proposal(app, &brain, &tx_events, proposal);
+                                    }
                                     app.scroll_to_bottom(width);
-
-                                    let b = brain.clone_brain();
-                                    let tx = tx_events.clone();
-                                    tokio::spawn(async move {
-                                        b.think_with_observer(&input, Some(tx)).await;
-                                    });
+                                } else if !input.trim().is_empty() {
+                                    submit_prompt(app, &brain, &tx_events, input, width);
                                 }
                             }
                         }
+                        // One-key answers to the approval prompt
+                        KeyCode::Char(c @ ('y' | 'a')) if app.input.is_empty() && !app.approvals.is_empty() && key.modifiers.is_empty() => {
+                            answer_approval(app, &brain, if c == 'y' { "y" } else { "always" });
+                            app.scroll_to_bottom(width);
+                        }
+                        // After moving through the messages: y copies the selection, Y saves it
+                        KeyCode::Char(c @ ('y' | 'Y')) if app.selecting && app.input.is_empty() => {
+                            let workspace = &brain.memory.memory_root;
+                            let status = if c == 'y' { yank::copy_selection(app, workspace, width) } else { yank::save_selection(app, workspace, width) };
+                            app.set_status(status);
+                            app.selecting = false;
+                        }
+                        // Alt+1/2/3 sends a follow-up suggestion of the latest answer
+                        KeyCode::Char('1'..='3') if key.modifiers.contains(event::KeyModifiers::ALT) => {
+                            if let Some(suggestion) = app.followup_for_key(&key) {
+                                submit_prompt(app, &brain, &tx_events, suggestion, width);
+                            }
+                        }
+                        // Prompt history (plain ↑↓ move through the messages)
+                        KeyCode::PageUp => app.input.history_previous(),
+                        KeyCode::PageDown => app.input.history_next(),
+                        KeyCode::Up if key.modifiers.contains(event::KeyModifiers::CONTROL) => app.input.history_previous(),
+                        KeyCode::Down if key.modifiers.contains(event::KeyModifiers::CONTROL) => app.input.history_next(),
                         KeyCode::Up => {
                             if key.modifiers.contains(event::KeyModifiers::SHIFT) {
                                 for _ in 0..5 { app.previous(width); }
@@ -153,9 +254,16 @@ async fn run_app<B: ratatui::backend::Backend + std::io::Write>(
                                 app.next(width);
                             }
                         }
-                        KeyCode::Backspace => {
-                            app.input.pop();
-                        }
+                        KeyCode::Left => app.input.left(),
+                        KeyCode::Right => app.input.right(),
+                        KeyCode::Home => app.input.home(),
+                        KeyCode::End => app.input.end(),
+                        KeyCode::Char('a') if key.modifiers.contains(event::KeyModifiers::CONTROL) => app.input.home(),
+                        KeyCode::Char('e') if key.modifiers.contains(event::KeyModifiers::CONTROL) => app.input.end(),
+                        KeyCode::Char('w') if key.modifiers.contains(event::KeyModifiers::CONTROL) => app.input.delete_word(),
+                        KeyCode::Backspace if key.modifiers.contains(event::KeyModifiers::ALT) => app.input.delete_word(),
+                        KeyCode::Backspace => app.input.backspace(),
+                        KeyCode::Delete => app.input.delete(),
                         // Toggle Layer Folding with Space
                         KeyCode::Char(' ') if app.input.is_empty() => {
                             app.toggle_selected_layers(width);
@@ -168,9 +276,7 @@ async fn run_app<B: ratatui::backend::Backend + std::io::Write>(
                                 execute!(terminal.backend_mut(), event::DisableMouseCapture)?;
                             }
                         }
-                        KeyCode::Char(c) => {
-                            app.input.push(c);
-                        }
+                        KeyCode::Char(c) if !key.modifiers.contains(event::KeyModifiers::CONTROL) => app.input.insert(c),
                         _ => {}
                     }
                 }
@@ -189,9 +295,11 @@ async fn run_app<B: ratatui::backend::Backend + std::io::Write>(
                         _ => {}
                     }
                 }
+                Event::FocusGained => attention.focused = Some(true),
+                Event::FocusLost => attention.focused = Some(false),
                 Event::Paste(text) => {
                     let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
-                    app.input.push_str(&normalized);
+                    app.input.insert_str(&normalized);
                 }
                 _ => {}
             }
@@ -200,10 +308,84 @@ async fn run_app<B: ratatui::backend::Backend + std::io::Write>(
         // Handle Brain Events
         let was_at_bottom = app.flat_selection >= app.get_selectable_lines(width).len().saturating_sub(1);
         while let Ok(event) = rx_events.try_recv() {
+            match &event {
+                BrainEvent::FinalAnswer(_) => attention.signal(terminal.backend_mut(), attention::AttentionKind::AnswerReady),
+                BrainEvent::ApprovalRequest { .. } => attention.signal(terminal.backend_mut(), attention::AttentionKind::ApprovalNeeded),
+                BrainEvent::Error(_) => attention.signal(terminal.backend_mut(), attention::AttentionKind::Error),
+                _ => {}
+            }
             app.handle_event(event);
             if was_at_bottom {
                 app.scroll_to_bottom(width);
             }
         }
+
+        // Handle Workspace Events
+        while let Some(event) = system_events.try_recv() {
+            if matches!(&event, SystemEvent::ProposalCreated { auto_applied: false, .. } | SystemEvent::MemoryConflict { .. }) {
+                attention.signal(terminal.backend_mut(), attention::AttentionKind::ApprovalNeeded);
+            }
+            app.handle_system_event(event);
+            if was_at_bottom {
+                app.scroll_to_bottom(width);
+            }
+        }
     }
 }
+
+/// Show the prompt and think about it in the background. `/plan <prompt>`
+/// is a dry run: tools are planned, not run.
+fn submit_prompt(app: &mut App, brain: &Brain, tx_events: &mpsc::Sender<BrainEvent>, input: String, width: usize) {
+    let (prompt, options) = match input.trim().strip_prefix("/plan ") {
+        Some(rest) => (rest.trim().to_string(), ThinkOptions { dry_run: true }),
+        None => (input.clone(), ThinkOptions::default()),
+    };
+    app.add_user_message(input);
+    app.start_thinking();
+    app.scroll_to_bottom(width);
+
+    let b = brain.clone_brain();
+    let tx = tx_events.clone();
+    tokio::spawn(async move {
+        b.think_with_options(&prompt, Some(tx), options).await;
+    });
+}
+
+/// Run an approved proposal as a turn: its events (and approval requests)
+/// reach the TUI like those of a prompt
+fn execute_proposal(app: &mut App, brain: &Brain, tx_events: &mpsc::Sender<BrainEvent>, proposal: openspore_autonomy::proposals::Proposal) {
+    app.start_thinking();
+    let b = brain.clone_brain();
+    let tx = tx_events.clone();
+    let store = openspore_autonomy::ProposalStore::new(&brain.config.project_root);
+    tokio::spawn(async move {
+        store.execute(&b, &proposal, Some(tx)).await;
+    });
+}
+
+/// Answer the first pending approval: "y"/"yes", "a"/"always" (for the rest of
+/// the session), anything else denies with the text after "n"/"no" as the reason
+fn answer_approval(app: &mut App, brain: &Brain, input: &str) {
+    use openspore_brain::approval::ApprovalDecision;
+    let Some(approval) = app.approvals.pop_front() else { return };
+    let input = input.trim();
+    let (decision, note) = match input.to_lowercase().as_str() {
+        "y" | "yes" => (ApprovalDecision::Approve, "✅ Approved".to_string()),
+        "a" | "always" => (ApprovalDecision::AlwaysAllow, "✅ Always allowed for this session".to_string()),
+        _ => {
+            let reason = match input.split_once(char::is_whitespace) {
+                Some((first, rest)) if matches!(first.to_lowercase().as_str(), "n" | "no") => rest,
+                _ if matches!(input.to_lowercase().as_str(), "n" | "no") => "",
+                _ => input,
+            }.trim();
+            let reason = if reason.is_empty() { "no reason given" } else { reason };
+            (ApprovalDecision::Deny(reason.to_string()), format!("🚫 Denied ({})", reason))
+        }
+    };
+    let note = if brain.approvals.respond(approval.id, decision) {
+        format!("{} {}: {}", note, approval.name, openspore_core::fmt_utils::preview(&approval.arg, 80))
+    } else {
+        format!("⌛ The approval request for {} had already expired", approval.name)
+    };
+    app.last_activity = note;
+}
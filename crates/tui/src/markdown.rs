@@ -0,0 +1,275 @@
+//! Markdown in the message area
+//!
+//! Spore answers are rendered line by line with a small subset of markdown:
+//! fenced code blocks on a dark background (cut into rows, never rewrapped),
+//! headings bold and colored, bullet and numbered lists with their wrapped
+//! lines aligned under the text, dimmed quotes and rules, and inline `code`,
+//! **bold**, *italic* and ~~strikethrough~~. Underscores are left alone so
+//! snake_case and __dunder__ names survive. The rows are cached per width in
+//! MessageTurn::wrapped_cache, one per SelectableLine::Content.
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const CODE_BLOCK: Style = Style::new().fg(Color::LightYellow).bg(Color::Indexed(236));
+const CODE_LABEL: Style = Style::new().fg(Color::DarkGray).bg(Color::Indexed(236));
+const INLINE_CODE: Style = Style::new().fg(Color::LightYellow);
+const HEADING: Style = Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+const QUOTE: Style = Style::new().fg(Color::Gray).add_modifier(Modifier::ITALIC);
+const MARKER: Style = Style::new().fg(Color::Cyan);
+const RULE: Style = Style::new().fg(Color::DarkGray);
+
+/// Tabs in code blocks
+const TAB: &str = "    ";
+
+/// The fence `line` opens (``` or ~~~), if any
+pub fn fence_open(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim_start();
+    ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f))
+}
+
+/// Whether `line` closes a block opened with `fence`
+pub fn fence_closes(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with(fence) && trimmed.trim_start_matches(['`', '~']).trim().is_empty()
+}
+
+/// `text` wrapped to `width` without markup (user and system turns)
+pub fn plain(text: &str, width: usize) -> Vec<Line<'static>> {
+    textwrap::wrap(text, width.max(1)).into_iter().map(|l| Line::raw(l.into_owned())).collect()
+}
+
+/// `text` rendered as markdown in rows of at most `width` columns
+pub fn render(text: &str, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in text.lines() {
+        match fence {
+            Some(open) if fence_closes(line, open) => {
+                fence = None;
+                rows.push(padded(Vec::new(), 0, width, CODE_LABEL));
+            }
+            Some(_) => code_rows(line, width, &mut rows),
+            None => match fence_open(line) {
+                Some(open) => {
+                    fence = Some(open);
+                    let language = line.trim_start().trim_start_matches(['`', '~']).trim();
+                    let label = format!(" {}", language);
+                    let used = label.width();
+                    rows.push(padded(vec![Span::styled(label, CODE_LABEL)], used, width, CODE_LABEL));
+                }
+                None => block_rows(line, width, &mut rows),
+            },
+        }
+    }
+    if rows.is_empty() {
+        rows.push(Line::default());
+    }
+    rows
+}
+
+/// `spans` (`used` columns wide) filled up to `width` with `style`
+fn padded(mut spans: Vec<Span<'static>>, used: usize, width: usize, style: Style) -> Line<'static> {
+    if used < width {
+        spans.push(Span::styled(" ".repeat(width - used), style));
+    }
+    Line::from(spans)
+}
+
+/// One code block line, cut into rows of `width` columns
+fn code_rows(line: &str, width: usize, rows: &mut Vec<Line<'static>>) {
+    let line = line.replace('\t', TAB);
+    let mut row = String::new();
+    let mut used = 0;
+    for c in line.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width && used > 0 {
+            rows.push(padded(vec![Span::styled(std::mem::take(&mut row), CODE_BLOCK)], used, width, CODE_BLOCK));
+            used = 0;
+        }
+        row.push(c);
+        used += w;
+    }
+    rows.push(padded(vec![Span::styled(row, CODE_BLOCK)], used, width, CODE_BLOCK));
+}
+
+/// Marker of a list item (`- `, `* `, `+ `, `1. `, `1) `) and the text after it
+fn list_item(text: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = text.strip_prefix(bullet) {
+            return Some(("• ".to_string(), rest));
+        }
+    }
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    let rest = &text[digits..];
+    if (1..=3).contains(&digits) && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some((format!("{} ", &text[..digits + 1]), &rest[2..]));
+    }
+    None
+}
+
+fn is_rule(text: &str) -> bool {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3 && ["-", "*", "_"].iter().any(|c| compact.chars().all(|x| x.to_string() == *c))
+}
+
+/// Rows of a line outside code blocks
+fn block_rows(line: &str, width: usize, rows: &mut Vec<Line<'static>>) {
+    let text = line.trim_start();
+    let indent = " ".repeat(line.len() - text.len());
+    if text.is_empty() {
+        rows.push(Line::default());
+        return;
+    }
+    if is_rule(text) {
+        rows.push(Line::from(Span::styled("─".repeat(width), RULE)));
+        return;
+    }
+
+    let hashes = text.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && text[hashes..].starts_with(' ') {
+        rows.extend(wrap(Vec::new(), String::new(), inline(text[hashes..].trim(), HEADING), width));
+        return;
+    }
+    if let Some(quoted) = text.strip_prefix('>') {
+        let quoted = quoted.strip_prefix(' ').unwrap_or(quoted);
+        let prefix = vec![Span::styled(format!("{}│ ", indent), RULE)];
+        rows.extend(wrap(prefix, format!("{}│ ", indent), inline(quoted, QUOTE), width));
+        return;
+    }
+    if let Some((marker, item)) = list_item(text) {
+        let continuation = " ".repeat(indent.width() + marker.width());
+        let prefix = vec![Span::raw(indent), Span::styled(marker, MARKER)];
+        rows.extend(wrap(prefix, continuation, inline(item, Style::default()), width));
+        return;
+    }
+    rows.extend(wrap(vec![Span::raw(indent.clone())], indent, inline(text, Style::default()), width));
+}
+
+/// Styled pieces of `text`: inline code, bold, italic and strikethrough
+/// applied on top of `base`, their markers removed
+fn inline(text: &str, base: Style) -> Vec<(String, Style)> {
+    let mut out = Vec::new();
+    push_inline(text, base, &mut out);
+    out
+}
+
+fn push_inline(text: &str, base: Style, out: &mut Vec<(String, Style)>) {
+    let mut plain = String::new();
+    let mut rest = text;
+    'scan: while let Some(c) = rest.chars().next() {
+        if c == '\\' && let Some(next) = rest[1..].chars().next().filter(|n| n.is_ascii_punctuation()) {
+            plain.push(next);
+            rest = &rest[1 + next.len_utf8()..];
+            continue;
+        }
+        for marker in ["`", "**", "~~", "*"] {
+            let Some(after) = rest.strip_prefix(marker) else { continue };
+            if marker == "*" && after.starts_with('*') {
+                continue;
+            }
+            let Some(close) = after.find(marker) else { continue };
+            let inner = &after[..close];
+            let spaced = inner.starts_with(char::is_whitespace) || inner.ends_with(char::is_whitespace);
+            if inner.is_empty() || (marker != "`" && spaced) {
+                continue;
+            }
+            if !plain.is_empty() {
+                out.push((std::mem::take(&mut plain), base));
+            }
+            match marker {
+                "`" => out.push((inner.to_string(), base.patch(INLINE_CODE))),
+                "**" => push_inline(inner, base.add_modifier(Modifier::BOLD), out),
+                "~~" => push_inline(inner, base.add_modifier(Modifier::CROSSED_OUT), out),
+                _ => push_inline(inner, base.add_modifier(Modifier::ITALIC), out),
+            }
+            rest = &after[close + marker.len()..];
+            continue 'scan;
+        }
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !plain.is_empty() {
+        out.push((plain, base));
+    }
+}
+
+/// Words (or runs of whitespace), each made of styled pieces
+fn tokens(pieces: Vec<(String, Style)>) -> Vec<(bool, Vec<(String, Style)>)> {
+    let mut tokens: Vec<(bool, Vec<(String, Style)>)> = Vec::new();
+    for (text, style) in pieces {
+        for c in text.chars() {
+            let space = c.is_whitespace();
+            match tokens.last_mut() {
+                Some((is_space, parts)) if *is_space == space => match parts.last_mut() {
+                    Some((part, part_style)) if *part_style == style => part.push(c),
+                    _ => parts.push((c.to_string(), style)),
+                },
+                _ => tokens.push((space, vec![(c.to_string(), style)])),
+            }
+        }
+    }
+    tokens
+}
+
+/// Append `text` to `row`, extending the last span when the style matches
+fn push_span(row: &mut Vec<Span<'static>>, text: String, style: Style) {
+    match row.last_mut() {
+        Some(last) if last.style == style => last.content.to_mut().push_str(&text),
+        _ => row.push(Span::styled(text, style)),
+    }
+}
+
+/// Word-wrap `pieces` to `width`: the first row starts with `prefix`, the
+/// following ones with `continuation`; words longer than a row are cut
+fn wrap(prefix: Vec<Span<'static>>, continuation: String, pieces: Vec<(String, Style)>, width: usize) -> Vec<Line<'static>> {
+    let start: usize = prefix.iter().map(|s| s.width()).sum();
+    let next_start = continuation.width();
+    let mut rows = Vec::new();
+    let mut row = prefix;
+    let mut used = start;
+    let mut row_start = start;
+
+    let mut new_row = |row: &mut Vec<Span<'static>>, used: &mut usize, row_start: &mut usize| {
+        rows.push(Line::from(std::mem::replace(row, vec![Span::raw(continuation.clone())])));
+        *used = next_start;
+        *row_start = next_start;
+    };
+
+    for (space, parts) in tokens(pieces) {
+        let w: usize = parts.iter().map(|(t, _)| t.width()).sum();
+        if space {
+            if used > row_start && used + w <= width {
+                parts.into_iter().for_each(|(t, s)| push_span(&mut row, t, s));
+                used += w;
+            }
+            continue;
+        }
+        if used + w > width && used > row_start {
+            new_row(&mut row, &mut used, &mut row_start);
+        }
+        if used + w <= width {
+            parts.into_iter().for_each(|(t, s)| push_span(&mut row, t, s));
+            used += w;
+            continue;
+        }
+        // Longer than a row: cut it
+        for (text, style) in parts {
+            let mut chunk = String::new();
+            for c in text.chars() {
+                let cw = c.width().unwrap_or(0);
+                if used + cw > width && used > row_start {
+                    push_span(&mut row, std::mem::take(&mut chunk), style);
+                    new_row(&mut row, &mut used, &mut row_start);
+                }
+                chunk.push(c);
+                used += cw;
+            }
+            push_span(&mut row, chunk, style);
+        }
+    }
+    rows.push(Line::from(row));
+    rows
+}
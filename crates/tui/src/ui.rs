@@ -6,34 +6,31 @@ use ratatui::{
     Frame,
 };
 use crate::app::{App, MessageAuthor};
-use textwrap::wrap;
+
+/// Spores listed in the swarm panel; the title still counts all of them
+const MAX_SPORE_LINES: usize = 6;
+
+/// Lines of the health snapshot shown under the recent activity
+const HEALTH_LINES: usize = 4;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     let area = f.area();
     let width = area.width as usize;
 
-    // Calculate dynamic input height
-    let input_text = format!("❯ {}", app.input);
-    let mut wrapped_lines = Vec::new();
-
-    // Explicitly handle newlines first, then wrap each line
-    // This allows Shift+Enter (\n) to visually create a new line
-    for line in input_text.split('\n') {
-        let wrapped = wrap(line, width.saturating_sub(4));
-        if wrapped.is_empty() {
-             wrapped_lines.push(std::borrow::Cow::Borrowed(""));
-        } else {
-             wrapped_lines.extend(wrapped);
-        }
-    }
-
+    // Calculate dynamic input height (Shift+Enter newlines start a new line)
+    let (wrapped_lines, (cursor_x, cursor_y)) = app.input.layout("❯ ", width.saturating_sub(4));
     let input_height = wrapped_lines.len().max(1) as u16;
 
+    // Swarm panel: a title line plus one line per running spore (hidden when idle)
+    let shown_spores = app.spores.len().min(MAX_SPORE_LINES);
+    let swarm_height = if app.spores.is_empty() { 0 } else { shown_spores as u16 + 1 };
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(12), // Claude Dashboard Header
             Constraint::Min(1),    // Messages Stream
+            Constraint::Length(swarm_height), // Live Swarm Panel
 			Constraint::Length(1), // Footer Help
             Constraint::Length(1), // Top Separator
             Constraint::Length(input_height), // Dynamic Input Prompt
@@ -79,22 +76,21 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         Line::from(Span::styled("         ███         ", Style::default().fg(Color::White))),
     ];
     f.render_widget(Paragraph::new(mushroom).alignment(ratatui::layout::Alignment::Center), l_layout[1]);
-    f.render_widget(Paragraph::new(format!("{}", app.current_path.replace("/Users/william-mbp", ""))).alignment(ratatui::layout::Alignment::Center).bold(), l_layout[3]);
+    f.render_widget(Paragraph::new(app.current_path.replace("/Users/william-mbp", "").to_string()).alignment(ratatui::layout::Alignment::Center).bold(), l_layout[3]);
 
-    // Right Side: Activity and Tips
+    // Right Side: Activity and Status
     let r_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
-        ])
+        .constraints([Constraint::Length(1); 6 + HEALTH_LINES])
         .split(header_layout[1]);
 
+    f.render_widget(Paragraph::new(format!("│ Session: {}", app.session)).dim(), r_layout[1]);
     f.render_widget(Paragraph::new("│ Recent activity").dim(), r_layout[3]);
     f.render_widget(Paragraph::new(format!("│ {}", app.last_activity)).dim(), r_layout[4]);
+    // Most important first (see StatusSnapshot::lines); /status shows all of them
+    for (line, row) in app.health.iter().take(HEALTH_LINES).zip(&r_layout[6..]) {
+        f.render_widget(Paragraph::new(format!("│ {}", line)).dim(), *row);
+    }
 
     // --- MESSAGES AREA (Bottom-Up Logic) ---
     let mut list_items = Vec::new();
@@ -106,9 +102,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
     for (idx, line_type) in selectable_lines.iter().enumerate() {
         let is_selected_line = idx == app.flat_selection;
-        let is_layer_head = matches!(line_type, crate::app::SelectableLine::LayerHeader(_, _));
-
-        let sidebar = if is_selected_line && is_layer_head {
+        let sidebar = if is_selected_line && line_type.is_stop() {
             Span::styled("┃ ", Style::default().fg(Color::Magenta).bold())
         } else {
             Span::raw("  ")
@@ -125,62 +119,75 @@ pub fn draw(f: &mut Frame, app: &mut App) {
                         MessageAuthor::Ai => ("🍄 Spore", Style::default().cyan().bold()),
                         MessageAuthor::System => ("⚙ Sys", Style::default().dark_gray()),
                     };
-                    list_items.push(ListItem::new(Line::from(vec![
+                    let mut spans = vec![
                         sidebar.clone(),
                         Span::styled(author_prefix, author_style)
-                    ])));
+                    ];
+                    if msg.is_private {
+                        spans.push(Span::styled(" 🔒", Style::default().dark_gray()));
+                    }
+                    list_items.push(ListItem::new(Line::from(spans)));
                 }
             }
             crate::app::SelectableLine::Content(i, j) => {
                 if let Some(msg) = app.messages.get(*i) {
                     let cache = msg.wrapped_cache.borrow();
-                    if let Some((_, wrapped)) = &*cache {
-                        if let Some(line) = wrapped.get(*j) {
-                            list_items.push(ListItem::new(Line::from(vec![
-                                sidebar.clone(),
-                                Span::styled(line.clone(), Style::default().white()),
-                            ])));
-                        }
+                    if let Some((_, wrapped)) = &*cache
+                        && let Some(line) = wrapped.get(*j)
+                    {
+                        let mut spans = vec![sidebar.clone()];
+                        spans.extend(line.spans.iter().cloned());
+                        list_items.push(ListItem::new(Line::from(spans).style(Style::default().white())));
                     }
                 }
             }
             crate::app::SelectableLine::LayerHeader(i, j) => {
-                if let Some(msg) = app.messages.get(*i) {
-                    if let Some(layer) = msg.layers.get(*j) {
-                        let status_icon = if layer.is_collapsed { "○" } else { "●" };
-                        let layer_color = if layer.is_collapsed { Color::DarkGray } else { Color::Magenta };
+                if let Some(msg) = app.messages.get(*i)
+                    && let Some(layer) = msg.layers.get(*j)
+                {
+                    let status_icon = if layer.is_collapsed { "○" } else { "●" };
+                    let layer_color = if layer.is_collapsed { Color::DarkGray } else { Color::Magenta };
+                    list_items.push(ListItem::new(Line::from(vec![
+                        sidebar.clone(),
+                        Span::styled(format!("  {} Thinking Layer {}", status_icon, layer.depth), Style::default().fg(layer_color).dim()),
+                    ])));
+                }
+            }
+            crate::app::SelectableLine::LayerContent(i, j, k) => {
+                if let Some(msg) = app.messages.get(*i)
+                    && let Some(layer) = msg.layers.get(*j)
+                {
+                    let cache = layer.wrapped_cache.borrow();
+                    if let Some((_, wrapped)) = &*cache
+                        && let Some(line) = wrapped.get(*k)
+                    {
                         list_items.push(ListItem::new(Line::from(vec![
                             sidebar.clone(),
-                            Span::styled(format!("  {} Thinking Layer {}", status_icon, layer.depth), Style::default().fg(layer_color).dim()),
+                            Span::raw("    "),
+                            Span::styled(line.clone(), Style::default().gray().italic()),
                         ])));
                     }
                 }
             }
-            crate::app::SelectableLine::LayerContent(i, j, k) => {
-                if let Some(msg) = app.messages.get(*i) {
-                    if let Some(layer) = msg.layers.get(*j) {
-                        let cache = layer.wrapped_cache.borrow();
-                        if let Some((_, wrapped)) = &*cache {
-                            if let Some(line) = wrapped.get(*k) {
-                                list_items.push(ListItem::new(Line::from(vec![
-                                    sidebar.clone(),
-                                    Span::raw("    "),
-                                    Span::styled(line.clone(), Style::default().gray().italic()),
-                                ])));
-                            }
-                        }
-                    }
+            crate::app::SelectableLine::Tool(i, j) => {
+                if let Some(msg) = app.messages.get(*i)
+                    && let Some((name, arg)) = msg.active_tools.get(*j)
+                {
+                    list_items.push(ListItem::new(Line::from(vec![
+                        sidebar.clone(),
+                        Span::styled(format!("  🔧 Running {} ", name), Style::default().yellow()),
+                        Span::styled(format!("({})", openspore_core::fmt_utils::preview(arg, 80)), Style::default().dark_gray()),
+                    ])));
                 }
             }
-            crate::app::SelectableLine::Tool(i, j) => {
-                if let Some(msg) = app.messages.get(*i) {
-                    if let Some((name, arg)) = msg.active_tools.get(*j) {
-                        list_items.push(ListItem::new(Line::from(vec![
-                            sidebar.clone(),
-                            Span::styled(format!("  🔧 Running {} ", name), Style::default().yellow()),
-                            Span::styled(format!("({})", arg), Style::default().dark_gray()),
-                        ])));
-                    }
+            crate::app::SelectableLine::Suggestion(i, j) => {
+                if let Some(msg) = app.messages.get(*i)
+                    && let Some(suggestion) = msg.suggestions.get(*j)
+                {
+                    list_items.push(ListItem::new(Line::from(vec![
+                        sidebar.clone(),
+                        Span::styled(format!("  [{}] {}", j + 1, suggestion), Style::default().dark_gray()),
+                    ])));
                 }
             }
         }
@@ -223,17 +230,46 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     let messages = List::new(visible_items).style(Style::default().bg(Color::Reset));
     f.render_widget(messages, message_chunk);
 
+    // --- SWARM PANEL ---
+    if !app.spores.is_empty() {
+        let now = chrono::Local::now();
+        let mut lines = vec![Line::from(format!("🐝 Swarm: {} sub-spore(s) running", app.spores.len())).yellow()];
+        for spore in app.spores.iter().take(shown_spores) {
+            let age = (now - spore.start_time).to_std().unwrap_or_default();
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} ", spore.role), Style::default().yellow()),
+                Span::styled(format!("[pid {}, {}] ", spore.pid, openspore_core::fmt_utils::humanize_duration(age)), Style::default().dark_gray()),
+                Span::styled(openspore_core::fmt_utils::preview(&spore.task, width.saturating_sub(40).max(20)), Style::default().gray()),
+            ]));
+        }
+        f.render_widget(Paragraph::new(lines), chunks[2]);
+    }
+
     // --- FOOTER / INPUT AREA ---
     let mouse_status = if app.mouse_captured { "ON" } else { "OFF" };
-    let footer_text = Line::from(format!("ESC: Quit  •   §: Mouse Scrolling({})  •  ↑↓: Scroll Layers  •  Space: Toggle Layers", mouse_status)).gray();
-    f.render_widget(Paragraph::new(footer_text).alignment(ratatui::layout::Alignment::Right), chunks[2]);
+    let followups = if app.has_suggestions() { "  •  Alt+1-3: Follow-up" } else { "" };
+    if let Some(approval) = app.approvals.front() {
+        let queued = if app.approvals.len() > 1 { format!(" (+{} queued)", app.approvals.len() - 1) } else { String::new() };
+        let prompt = Line::from(format!(
+            "🛂 Allow {}: {}?{}  y: Approve  •  a: Always  •  n <reason> + Enter: Deny",
+            approval.name, openspore_core::fmt_utils::preview(&approval.arg, 60), queued
+        )).yellow().bold();
+        f.render_widget(Paragraph::new(prompt), chunks[3]);
+    } else if let Some(status) = app.status() {
+        f.render_widget(Paragraph::new(Line::from(status.to_string()).green().bold()).alignment(ratatui::layout::Alignment::Right), chunks[3]);
+    } else {
+        let copy = if app.selecting { "  •  y: Copy  •  Y: Save Snippet" } else { "" };
+        let footer_text = Line::from(format!("ESC: Quit  •   §: Mouse Scrolling({})  •  ↑↓: Select  •  PgUp/PgDn: History  •  Space: Toggle Layers{}{}", mouse_status, copy, followups)).gray();
+        f.render_widget(Paragraph::new(footer_text).alignment(ratatui::layout::Alignment::Right), chunks[3]);
+    }
 
-    f.render_widget(Paragraph::new("─".repeat(width)).dim(), chunks[3]);
+    f.render_widget(Paragraph::new("─".repeat(width)).dim(), chunks[4]);
 
     // Render multi-line input
     let input_para = Paragraph::new(wrapped_lines.iter().map(|s| Line::from(s.to_string())).collect::<Vec<_>>())
         .bold();
-    f.render_widget(input_para, chunks[4]);
+    f.render_widget(input_para, chunks[5]);
+    f.set_cursor_position((chunks[5].x + cursor_x, chunks[5].y + cursor_y));
 
-    f.render_widget(Paragraph::new("─".repeat(width)).dim(), chunks[5]);
+    f.render_widget(Paragraph::new("─".repeat(width)).dim(), chunks[6]);
 }
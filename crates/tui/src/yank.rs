@@ -0,0 +1,131 @@
+//! Copying messages out of the TUI
+//!
+//! After moving through the messages with ↑↓, `y` copies the selected message
+//! to the system clipboard, or only its fenced code blocks when it has some,
+//! with the original line breaks and indentation. A thinking layer copies the
+//! layer. `Y` saves the whole message as workspace/exports/snippet_<time>.md.
+//! Without a clipboard (headless, SSH without a display) `y` saves the snippet
+//! instead and says so. Private turns are copied but never written to disk.
+
+use crate::app::{App, MessageAuthor, SelectableLine};
+use crate::markdown::{fence_closes, fence_open};
+use openspore_memory::EXPORTS_DIR;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// What the selector is on
+struct Selection {
+    /// Message (or layer) text as received, unwrapped
+    text: String,
+    author: &'static str,
+    private: bool,
+}
+
+fn selection(app: &App, width: usize) -> Option<Selection> {
+    let index = app.selected_turn(width)?;
+    let msg = app.messages.get(index)?;
+    let author = match msg.author {
+        MessageAuthor::User => "User",
+        MessageAuthor::Ai => "Spore",
+        MessageAuthor::System => "System",
+    };
+    let layer = match app.get_selectable_lines(width).get(app.flat_selection) {
+        Some(SelectableLine::LayerHeader(_, j) | SelectableLine::LayerContent(_, j, _)) => msg.layers.get(*j),
+        _ => None,
+    };
+    let text = layer.map_or(&msg.content, |l| &l.content).trim_end().to_string();
+    (!text.trim().is_empty()).then_some(Selection { text, author, private: msg.is_private })
+}
+
+/// Contents of the fenced (``` or ~~~) code blocks of `text`; an unclosed
+/// fence runs to the end
+pub fn code_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(&str, Vec<&str>)> = None;
+    for line in text.lines() {
+        match &mut current {
+            Some((fence, lines)) => {
+                if fence_closes(line, fence) {
+                    blocks.push(lines.join("\n"));
+                    current = None;
+                } else {
+                    lines.push(line);
+                }
+            }
+            None => {
+                if let Some(fence) = fence_open(line) {
+                    current = Some((fence, Vec::new()));
+                }
+            }
+        }
+    }
+    if let Some((_, lines)) = current {
+        blocks.push(lines.join("\n"));
+    }
+    blocks.retain(|b| !b.trim().is_empty());
+    blocks
+}
+
+fn lines_of(text: &str) -> String {
+    let n = text.lines().count();
+    format!("{} line{}", n, if n == 1 { "" } else { "s" })
+}
+
+/// Write `selection` to workspace/exports/snippet_<time>.md
+fn write_snippet(workspace: &Path, selection: &Selection) -> anyhow::Result<PathBuf> {
+    let dir = workspace.join(EXPORTS_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let now = chrono::Local::now();
+    let path = dir.join(format!("snippet_{}.md", now.format("%Y%m%d_%H%M%S")));
+    let doc = format!("# Snippet {}\n\nFrom: {}\n\n{}\n", now.format("%Y-%m-%d %H:%M"), selection.author, selection.text);
+    std::fs::write(&path, doc)?;
+    Ok(path)
+}
+
+fn snippet_status(workspace: &Path, selection: &Selection, why: &str) -> String {
+    if selection.private {
+        return format!("🔒 {}Private turn, not saved", why);
+    }
+    match write_snippet(workspace, selection) {
+        Ok(path) => format!("📝 {}Saved {} to {}", why, lines_of(&selection.text), path.strip_prefix(workspace).unwrap_or(&path).display()),
+        Err(e) => format!("❌ Snippet not saved: {}", e),
+    }
+}
+
+/// `y`: copy the selection (or its code blocks). Returns the footer status.
+pub fn copy_selection(app: &mut App, workspace: &Path, width: usize) -> String {
+    let Some(selection) = selection(app, width) else { return "Nothing to copy here".to_string() };
+    let blocks = code_blocks(&selection.text);
+    let (text, what) = if blocks.is_empty() {
+        (selection.text.clone(), String::new())
+    } else {
+        (blocks.join("\n\n"), format!(" from {} code block{}", blocks.len(), if blocks.len() == 1 { "" } else { "s" }))
+    };
+
+    if app.clipboard.is_none() {
+        match arboard::Clipboard::new() {
+            Ok(clipboard) => app.clipboard = Some(clipboard),
+            Err(e) => {
+                warn!("📋 No clipboard: {}", e);
+                return snippet_status(workspace, &selection, "No clipboard. ");
+            }
+        }
+    }
+    let Some(clipboard) = app.clipboard.as_mut() else { return "Nothing to copy here".to_string() };
+    match clipboard.set_text(text.clone()) {
+        Ok(()) => format!("📋 Copied {}{}", lines_of(&text), what),
+        Err(e) => {
+            warn!("📋 Copy failed: {}", e);
+            app.clipboard = None;
+            snippet_status(workspace, &selection, "Copy failed. ")
+        }
+    }
+}
+
+/// `Y`: save the selected message as a snippet. Returns the footer status.
+pub fn save_selection(app: &App, workspace: &Path, width: usize) -> String {
+    match selection(app, width) {
+        Some(selection) => snippet_status(workspace, &selection, ""),
+        None => "Nothing to save here".to_string(),
+    }
+}
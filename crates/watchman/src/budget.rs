@@ -0,0 +1,173 @@
+//! Hourly analysis budget and per-directory counts
+//!
+//! `[watchman] analyses_per_hour` caps the model calls of all watched
+//! directories over a rolling hour, and an entry's `budget_share` caps its own
+//! part of it. A batch arriving when either is spent is not analyzed (it
+//! counts as over budget). The counts go to WATCH_STATS_FILE for the heartbeat.
+
+use crate::Watchman;
+use openspore_core::watch::{EntryStats, WatchEntry, WatchStats};
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const WINDOW: Duration = Duration::from_secs(3600);
+
+pub(crate) struct Budget {
+    per_hour: Option<u32>,
+    /// Analyses of the last hour, all directories
+    all: VecDeque<Instant>,
+    /// Analyses of the last hour, per directory
+    per_dir: Vec<VecDeque<Instant>>,
+}
+
+impl Budget {
+    pub(crate) fn new(per_hour: Option<u32>, dirs: usize) -> Self {
+        Self { per_hour: per_hour.filter(|n| *n > 0), all: VecDeque::new(), per_dir: vec![VecDeque::new(); dirs] }
+    }
+
+    /// Record an analysis of `entry` (directory `source`) if both the global
+    /// budget and the entry's share of it have room
+    pub(crate) fn try_spend(&mut self, source: usize, entry: &WatchEntry, now: Instant) -> bool {
+        let Some(per_hour) = self.per_hour else { return true };
+        let allowance = entry.hourly_allowance(Some(per_hour));
+        let expire = |queue: &mut VecDeque<Instant>| {
+            while queue.front().is_some_and(|t| now.saturating_duration_since(*t) >= WINDOW) {
+                queue.pop_front();
+            }
+        };
+        expire(&mut self.all);
+        let Some(own) = self.per_dir.get_mut(source) else { return false };
+        expire(own);
+        if self.all.len() >= per_hour as usize || allowance.is_some_and(|a| own.len() >= a) {
+            return false;
+        }
+        own.push_back(now);
+        self.all.push_back(now);
+        true
+    }
+}
+
+impl Watchman {
+    /// Update the counts of a directory; they are written on the next tick
+    pub(crate) fn count(&self, source: usize, update: impl FnOnce(&mut EntryStats)) {
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = stats.entries.get_mut(source) {
+            update(entry);
+            self.stats_dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Write the counts if they changed
+    pub(crate) fn save_stats(&self, force: bool) {
+        if !self.stats_dirty.swap(false, Ordering::SeqCst) && !force {
+            return;
+        }
+        let mut stats: WatchStats = self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Err(e) = stats.save(&self.project_root) {
+            warn!("👀 Watchman: could not write its counts: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_brain::Brain;
+    use openspore_brain::test_support::{config_in, fake_llm};
+    use openspore_core::config::{AppConfig, WatchEntryConfig};
+    use std::path::Path;
+
+    fn entry(share: Option<f64>) -> WatchEntry {
+        let config = AppConfig { project_root: std::env::temp_dir(), ..Default::default() };
+        WatchEntry { budget_share: share, ..openspore_core::watch::watch_entries(&config).unwrap().remove(0) }
+    }
+
+    #[test]
+    fn each_directory_spends_only_its_share_of_the_hour() {
+        let mut budget = Budget::new(Some(4), 2);
+        let (half, quarter) = (entry(Some(0.5)), entry(Some(0.25)));
+        let start = Instant::now();
+        assert!(budget.try_spend(0, &half, start));
+        assert!(budget.try_spend(0, &half, start));
+        assert!(!budget.try_spend(0, &half, start), "half of 4");
+        assert!(budget.try_spend(1, &quarter, start));
+        assert!(!budget.try_spend(1, &quarter, start), "a quarter of 4");
+
+        // An hour later both have room again
+        let later = start + WINDOW;
+        assert!(budget.try_spend(0, &half, later));
+        assert!(budget.try_spend(1, &quarter, later));
+        assert!(!budget.try_spend(5, &half, later), "unknown directory");
+    }
+
+    #[test]
+    fn the_global_cap_binds_before_the_shares() {
+        let mut budget = Budget::new(Some(2), 2);
+        let whole = entry(None);
+        let now = Instant::now();
+        assert!(budget.try_spend(0, &whole, now));
+        assert!(budget.try_spend(1, &whole, now));
+        assert!(!budget.try_spend(0, &whole, now));
+        assert!(!budget.try_spend(1, &whole, now));
+
+        let mut unlimited = Budget::new(Some(0), 1);
+        assert!((0..100).all(|_| unlimited.try_spend(0, &whole, now)));
+    }
+
+    fn touch(path: &Path, text: &str) -> std::path::PathBuf {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+        path.to_path_buf()
+    }
+
+    #[tokio::test]
+    async fn two_directories_filter_and_budget_on_their_own_rules() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path().canonicalize().unwrap();
+        let (client_dir, notes_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (client, notes) = (client_dir.path().canonicalize().unwrap(), notes_dir.path().canonicalize().unwrap());
+
+        let (url, requests) = fake_llm(|_| r#"{"should_save": false}"#.to_string()).await;
+        let mut config = AppConfig { watchman_debounce_ms: Some(0), watchman_batch_window_ms: Some(60_000), ..config_in(&root, &url) };
+        config.watchman.analyses_per_hour = Some(4);
+        config.watchman.watch = vec![
+            WatchEntryConfig { path: client.display().to_string(), name: Some("client-a".into()), extensions: Some(vec!["rs".into()]), budget_share: Some(0.5), ..Default::default() },
+            WatchEntryConfig { path: notes.display().to_string(), name: Some("notes".into()), ignore: vec!["drafts/**".into()], budget_share: Some(0.25), ..Default::default() },
+        ];
+        let brain = Brain::new(config.clone());
+        brain.memory.ensure_structure().await.unwrap();
+        let watchman = Watchman::new(config, brain.clone(), brain.memory.clone());
+        assert_eq!(watchman.dirs.len(), 3);
+
+        // Each directory's extension filter and ignore globs decide what is queued
+        let main_rs = touch(&client.join("src/main.rs"), "fn main() { deploy(); }");
+        let todo = touch(&notes.join("todo.md"), "Call the plumber");
+        for path in [main_rs.clone(), touch(&client.join("README.md"), "readme"), todo.clone(), touch(&notes.join("build.rs"), "fn x() {}"), touch(&notes.join("drafts/idea.md"), "idea")] {
+            watchman.enqueue("add", path).await;
+        }
+        assert_eq!(watchman.queue.lock().await.len(), 2);
+        watchman.process_queue().await;
+        assert_eq!(requests.len(), 2, "one prompt per directory");
+        assert_eq!((requests.mentioning("fn main() { deploy(); }"), requests.mentioning("Call the plumber")), (1, 1));
+
+        // client-a may use 2 of the 4 hourly analyses, notes only 1
+        for round in ["second", "third"] {
+            std::fs::write(&main_rs, format!("fn main() {{ {}(); }}", round)).unwrap();
+            std::fs::write(&todo, format!("{} call", round)).unwrap();
+            watchman.enqueue("change", main_rs.clone()).await;
+            watchman.enqueue("change", todo.clone()).await;
+            watchman.process_queue().await;
+        }
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests.mentioning("second();"), 1);
+        assert_eq!(requests.mentioning("second call") + requests.mentioning("third"), 0);
+
+        watchman.save_stats(true);
+        let stats = WatchStats::load(&root).unwrap();
+        let counts: Vec<(&str, u64, u64, u64)> = stats.entries.iter().map(|e| (e.name.as_str(), e.detected, e.analyzed, e.over_budget)).collect();
+        assert_eq!(counts, [("openspore", 0, 0, 0), ("client-a", 3, 2, 1), ("notes", 3, 1, 2)]);
+        assert!(stats.summary().contains("client-a 3 changed, 2 analyzed, 1 over budget · notes 3 changed, 1 analyzed, 2 over budget"));
+    }
+}
@@ -1,68 +1,268 @@
-use crate::Watchman;
+use crate::{Watchman, WatchedDir};
+use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ::ignore::Match;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::collections::HashSet;
 use std::path::Path;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Override layer read from the root of a watched directory, checked before any .gitignore
+const OVERRIDE_FILE: &str = ".watchmanignore";
+const GITIGNORE_FILE: &str = ".gitignore";
+
+/// How deep below a watched directory nested .gitignore files are looked for
+const MAX_GITIGNORE_DEPTH: usize = 8;
 
 impl Watchman {
-    /// Load additional ignore rules from .watchmanignore
+    /// Compile the ignore rules of every watched directory
     pub fn load_ignore_rules(&mut self) {
-        let ignore_file = self.project_root.join(".watchmanignore");
-        if ignore_file.exists() {
-            if let Ok(content) = std::fs::read_to_string(&ignore_file) {
-                for line in content.lines() {
-                    let line = line.trim();
-                    if !line.is_empty() && !line.starts_with('#') {
-                        self.ignore_rules.insert(line.to_string());
-                    }
-                }
-            }
+        for dir in &mut self.dirs {
+            dir.load_ignore_rules(&self.ignore_rules);
         }
-        info!("👀 Watchman Ignore Rules: {:?}", self.ignore_rules);
     }
 
-    /// Check if a file path should be ignored
+    /// The watched directory `path` belongs to, None when that directory ignores it
+    pub(crate) fn source_of(&self, path: &Path) -> Option<usize> {
+        let source = openspore_core::watch::entry_for(self.dirs.iter().map(|d| &d.entry), path)?;
+        (!self.dirs[source].should_ignore(path)).then_some(source)
+    }
+}
+
+impl WatchedDir {
+    /// Compile the built-in rules with the entry's own globs, and load
+    /// .watchmanignore and every .gitignore of the tree as gitignore-style
+    /// patterns (globs, anchored and nested paths, `dir/` and `!negation`)
+    pub fn load_ignore_rules(&mut self, builtin: &HashSet<String>) {
+        let rules: HashSet<String> = builtin.iter().chain(self.entry.ignore.iter()).cloned().collect();
+        self.ignore_globs = compile_rules(&rules);
+        self.ignore_patterns = build_layers(&self.entry.path, &self.ignore_globs);
+        let (ignores, exceptions) = self.ignore_patterns.iter()
+            .fold((0, 0), |(i, w), gi| (i + gi.num_ignores(), w + gi.num_whitelists()));
+        info!(
+            "👀 Watchman Ignore Rules of {} ({}): {} built-in + {:?} + {} pattern(s), {} exception(s) from {} file(s), extensions {:?}, learning {}",
+            self.entry.name,
+            self.entry.path.display(),
+            builtin.len(),
+            self.entry.ignore,
+            ignores,
+            exceptions,
+            self.ignore_patterns.len(),
+            self.entry.extensions,
+            self.entry.learning.as_str()
+        );
+    }
+
+    /// Check if a file path below this directory should be ignored
     pub(crate) fn should_ignore(&self, path: &Path) -> bool {
-        let rel_path = path.strip_prefix(&self.project_root).unwrap_or(path);
-        let rel_path_str = rel_path.to_string_lossy();
-
-        // Check each ignore rule
-        for rule in &self.ignore_rules {
-            // Handle glob patterns (e.g., *.log)
-            if rule.starts_with('*') {
-                let pattern = &rule[1..]; // Remove the *
-                if let Some(filename) = path.file_name() {
-                    if filename.to_string_lossy().ends_with(pattern) {
-                        return true;
-                    }
-                }
-            }
-            // Handle path patterns (e.g., workspace/context)
-            else if rule.contains('/') {
-                if rel_path_str.starts_with(rule) || rel_path_str.contains(&format!("/{}", rule)) {
-                    return true;
+        let rel_path = path.strip_prefix(&self.entry.path).unwrap_or(path);
+        if self.ignore_globs.is_match(rel_path) {
+            return true;
+        }
+
+        if matches_patterns(&self.ignore_patterns, path) {
+            return true;
+        }
+
+        // Extension allowlist (no extension = ignore)
+        !self.entry.accepts(path)
+    }
+}
+
+/// Built-in rules as globs against the path relative to the watched directory. A
+/// rule without `/` matches at any depth (`target`, `*.log`), one with `/`
+/// from the root (`build/**`, `docs/generated`); a match also covers
+/// everything below it. The extension allowlist stays a separate check.
+fn compile_rules(rules: &HashSet<String>) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for rule in rules {
+        let rule = rule.trim().trim_start_matches('/').trim_end_matches('/');
+        if rule.is_empty() {
+            continue;
+        }
+        let base = if rule.contains('/') { rule.to_string() } else { format!("**/{}", rule) };
+        let below = if base.ends_with("/**") { None } else { Some(format!("{}/**", base)) };
+        for pattern in std::iter::once(base.clone()).chain(below) {
+            match GlobBuilder::new(&pattern).literal_separator(true).build() {
+                Ok(glob) => {
+                    builder.add(glob);
                 }
+                Err(e) => warn!("👀 Watchman: ignore rule \"{}\" skipped: {}", rule, e),
             }
-            // Handle directory/file name patterns
-            else {
-                for component in rel_path.components() {
-                    if let std::path::Component::Normal(name) = component {
-                        if name.to_string_lossy().as_ref() == rule {
-                            return true;
-                        }
-                    }
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        warn!("👀 Watchman: ignore rules unavailable: {}", e);
+        GlobSet::empty()
+    })
+}
+
+/// The first layer with an opinion decides: an ignore skips the path, a
+/// `!negation` keeps it. Patterns apply to the path and every directory above
+/// it, below the layer's own directory.
+fn matches_patterns<'a>(layers: impl IntoIterator<Item = &'a Gitignore>, path: &Path) -> bool {
+    let is_dir = path.is_dir();
+    for layer in layers {
+        if !path.starts_with(layer.path()) {
+            continue;
+        }
+        match layer.matched_path_or_any_parents(path, is_dir) {
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+            Match::None => {}
+        }
+    }
+    false
+}
+
+/// Pattern layers by precedence: .watchmanignore, then .gitignore files from
+/// the deepest directory up to the watched directory (like git, closer files win)
+fn build_layers(root: &Path, rules: &GlobSet) -> Vec<Gitignore> {
+    let mut layers: Vec<Gitignore> = parse_file(root, OVERRIDE_FILE).into_iter().collect();
+
+    let mut gitignores = Vec::new();
+    let mut walk = GitignoreWalk { root, rules, overrides: &layers, ancestors: Vec::new(), found: &mut gitignores };
+    walk.visit(root, 0);
+    gitignores.sort_by_key(|(depth, _)| std::cmp::Reverse(*depth));
+
+    layers.extend(gitignores.into_iter().map(|(_, gi)| gi));
+    layers
+}
+
+/// Depth-first search for .gitignore files, skipping built-in and already
+/// ignored directories (their contents are never watched anyway)
+struct GitignoreWalk<'a> {
+    root: &'a Path,
+    rules: &'a GlobSet,
+    overrides: &'a [Gitignore],
+    /// .gitignore files of the directories above the current one
+    ancestors: Vec<Gitignore>,
+    found: &'a mut Vec<(usize, Gitignore)>,
+}
+
+impl GitignoreWalk<'_> {
+    fn visit(&mut self, dir: &Path, depth: usize) {
+        let own = parse_file(dir, GITIGNORE_FILE);
+        if let Some(gi) = &own {
+            self.found.push((depth, gi.clone()));
+            self.ancestors.push(gi.clone());
+        }
+
+        if depth < MAX_GITIGNORE_DEPTH
+            && let Ok(entries) = std::fs::read_dir(dir)
+        {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !entry.file_type().is_ok_and(|t| t.is_dir())
+                    || self.rules.is_match(path.strip_prefix(self.root).unwrap_or(&path))
+                    || matches_patterns(self.overrides.iter().chain(self.ancestors.iter().rev()), &path) {
+                    continue;
                 }
+                self.visit(&path, depth + 1);
             }
         }
 
-        // Check extension allowlist
-        if let Some(ext) = path.extension() {
-            let ext_str = format!(".{}", ext.to_string_lossy().to_lowercase());
-            if !self.allowed_extensions.contains(&ext_str) {
-                return true;
-            }
-        } else {
-            return true; // No extension = ignore
+        if own.is_some() {
+            self.ancestors.pop();
+        }
+    }
+}
+/// Patterns of `dir/name`, rooted at `dir` (None when missing or empty)
+fn parse_file(dir: &Path, name: &str) -> Option<Gitignore> {
+    let file = dir.join(name);
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(&file) {
+        warn!("👀 Watchman: some patterns in {} were skipped: {}", file.display(), e);
+    }
+    match builder.build() {
+        Ok(gi) if !gi.is_empty() => Some(gi),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("👀 Watchman: patterns in {} unavailable: {}", file.display(), e);
+            None
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_core::config::AppConfig;
+
+    /// A watched tree with a .watchmanignore, a root .gitignore and nested ones
+    fn watched(root: &Path) -> WatchedDir {
+        let files = [
+            (".watchmanignore", "*.tmp.md\n!important.tmp.md\n!secret-override.md\n"),
+            (".gitignore", "secret*.md\n/build-output\nlogs/\n"),
+            ("sub/.gitignore", "!secret-ok.md\nlocal.md\n"),
+            ("sub/deep/.gitignore", "*.json\n"),
+            // Not read: its directory is ignored
+            ("logs/.gitignore", "!*.md\n"),
+        ];
+        for (name, content) in files {
+            let path = root.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let config = AppConfig { project_root: root.to_path_buf(), ..Default::default() };
+        let mut dir = WatchedDir::new(openspore_core::watch::watch_entries(&config).unwrap().remove(0));
+        let builtin = HashSet::from(["target".to_string(), "*.log".to_string(), "docs/generated".to_string()]);
+        dir.load_ignore_rules(&builtin);
+        dir
+    }
+
+    fn ignored(dir: &WatchedDir, rel: &str) -> bool {
+        dir.should_ignore(&dir.entry.path.join(rel))
+    }
+
+    #[test]
+    fn builtin_rules_match_by_name_anywhere_and_by_path_from_the_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = watched(tmp.path());
+        assert!(!ignored(&dir, "notes.md"));
+        assert!(ignored(&dir, "target/a.md"));
+        assert!(ignored(&dir, "crates/x/target/debug/b.md"));
+        assert!(ignored(&dir, "docs/generated/api.md"));
+        assert!(!ignored(&dir, "other/docs/generated/api.md"));
+        assert!(ignored(&dir, "server.log"));
+    }
+
+    #[test]
+    fn extensions_outside_the_allowlist_are_ignored() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = watched(tmp.path());
+        assert!(!ignored(&dir, "a.TXT"));
+        assert!(!ignored(&dir, "config.json"));
+        assert!(ignored(&dir, "main.rs"));
+        assert!(ignored(&dir, "Makefile"));
+    }
+
+    #[test]
+    fn negations_keep_files_and_the_override_file_wins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = watched(tmp.path());
+        assert!(ignored(&dir, "draft.tmp.md"));
+        assert!(!ignored(&dir, "important.tmp.md"));
+        assert!(ignored(&dir, "secret-plan.md"));
+        assert!(!ignored(&dir, "secret-override.md"), ".watchmanignore is checked before .gitignore");
+    }
 
-        false
+    #[test]
+    fn nested_gitignores_apply_below_their_directory_and_closer_ones_win() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = watched(tmp.path());
+        assert!(!ignored(&dir, "sub/secret-ok.md"));
+        assert!(ignored(&dir, "sub/secret-x.md"));
+        assert!(ignored(&dir, "sub/local.md"));
+        assert!(!ignored(&dir, "local.md"));
+        assert!(ignored(&dir, "sub/deep/data.json"));
+        assert!(ignored(&dir, "sub/deep/deeper/data.json"));
+        assert!(!ignored(&dir, "sub/data.json"));
+        assert!(ignored(&dir, "build-output/report.md"));
+        assert!(!ignored(&dir, "sub/build-output/report.md"), "a leading / anchors to the file's directory");
+        assert!(ignored(&dir, "logs/today.md"), "the .gitignore of an ignored directory is not read");
+        assert!(ignored(&dir, "sub/logs/today.md"));
     }
 }
@@ -1,27 +1,35 @@
 //! OpenSpore Watchman - Modular Filesystem Observer
 //!
 //! This module is organized into:
-//! - types: Core data structures (WatchEvent)
-//! - ignore: Ignore rule management
-//! - queue: Event queue management
+//! - types: Core data structures (WatchEvent, WatchedDir)
+//! - ignore: Ignore rules (.watchmanignore and nested .gitignore patterns)
+//! - queue: Debounced event queue and batching
+//! - budget: Hourly analysis budget and per-directory counts
 //! - processing: Event processing and learning
 //! - watcher: Filesystem watching
+//!
+//! The app root and the `[[watchman.watch]]` entries of openspore.toml are
+//! watched, each with its own rules (see openspore_core::watch).
 
 mod types;
 mod ignore;
 mod queue;
+mod budget;
 mod processing;
 mod watcher;
 
 // Re-export public types
-pub use types::WatchEvent;
+pub use types::{WatchEvent, WatchedDir};
+pub use queue::EventQueue;
 
 use std::path::PathBuf;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use tokio::sync::Mutex;
-use tracing::info;
+use tracing::{info, warn};
 use openspore_core::config::AppConfig;
+use openspore_core::watch::{WatchEntry, WatchStats};
 use openspore_memory::MemorySystem;
 use openspore_brain::Brain;
 
@@ -30,14 +38,31 @@ pub struct Watchman {
     pub project_root: PathBuf,
     pub memory: MemorySystem,
     pub brain: Brain,
+    /// Built-in ignore globs of every watched directory: a bare name
+    /// (`target`, `*.log`) matches at any depth, a rule with `/` (`build/**`)
+    /// from the directory's root
     pub ignore_rules: HashSet<String>,
-    pub allowed_extensions: HashSet<String>,
-    pub queue: Arc<Mutex<Vec<WatchEvent>>>,
+    /// Watched directories, the app root first
+    pub dirs: Vec<WatchedDir>,
+    pub queue: Arc<Mutex<EventQueue>>,
+    budget: std::sync::Mutex<budget::Budget>,
+    stats: std::sync::Mutex<WatchStats>,
+    stats_dirty: AtomicBool,
 }
 
 impl Watchman {
     pub fn new(config: AppConfig, brain: Brain, memory: MemorySystem) -> Self {
+        let entries = openspore_core::watch::watch_entries(&config).unwrap_or_else(|e| {
+            warn!("👀 Watchman: {} (watching the app root only; `openspore doctor` checks the config)", e);
+            let mut defaults = config.clone();
+            defaults.watchman.watch.clear();
+            openspore_core::watch::watch_entries(&defaults).unwrap_or_default()
+        });
+        Self::with_entries(config, brain, memory, entries)
+    }
 
+    /// A Watchman of `entries` (as resolved by watch_entries: the app root first)
+    pub fn with_entries(config: AppConfig, brain: Brain, memory: MemorySystem, entries: Vec<WatchEntry>) -> Self {
         let mut watchman = Self {
             project_root: config.project_root.clone(),
             memory,
@@ -48,17 +73,22 @@ impl Watchman {
                 ".git".to_string(),
                 "dist".to_string(),
                 "build".to_string(),
+                ".runtime".to_string(),
+                "workspace/audit".to_string(),
+                "workspace/autonomy/state".to_string(),
+                "workspace/debug".to_string(),
+                "workspace/exports".to_string(),
+                format!("workspace/{}", openspore_memory::SNAPSHOTS_DIR),
             ]),
-            allowed_extensions: HashSet::from([
-                ".md".to_string(),
-                ".txt".to_string(),
-                ".json".to_string(),
-            ]),
-            queue: Arc::new(Mutex::new(Vec::new())),
+            budget: std::sync::Mutex::new(budget::Budget::new(config.watchman.analyses_per_hour, entries.len())),
+            stats: std::sync::Mutex::new(WatchStats::new(&entries)),
+            stats_dirty: AtomicBool::new(false),
+            dirs: entries.into_iter().map(WatchedDir::new).collect(),
+            queue: Arc::new(Mutex::new(EventQueue::from_config(&config))),
         };
 
         watchman.load_ignore_rules();
-        info!("👀 Watchman initialized");
+        info!("👀 Watchman initialized ({} director{})", watchman.dirs.len(), if watchman.dirs.len() == 1 { "y" } else { "ies" });
         watchman
     }
 }
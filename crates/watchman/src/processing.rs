@@ -1,28 +1,109 @@
 use crate::{Watchman, types::WatchEvent};
+use openspore_core::fmt_utils::truncate_chars;
+use openspore_core::watch::LearningPolicy;
+use std::time::Instant;
 use tracing::info;
 
-impl Watchman {
-    /// Process a single event - read file and trigger learn()
-    pub(crate) async fn process_event(&self, event: &WatchEvent) -> anyhow::Result<()> {
-        let content = tokio::fs::read_to_string(&event.file_path).await?;
+/// Preview characters for a single file; a batch shares a larger budget
+const PREVIEW_CHARS: usize = 1000;
+const BATCH_PREVIEW_BUDGET: usize = 4000;
+const MIN_BATCH_PREVIEW: usize = 300;
 
-        // Truncate content preview
-        let preview = if content.len() > 1000 {
-            format!("{}...", &content[..1000])
-        } else {
-            content.clone()
+impl Watchman {
+    /// Process a batch of events of one watched directory - read the files and,
+    /// as its learning policy says, analyze them in one LLM call or journal them
+    pub(crate) async fn process_batch(&self, source: usize, events: &[WatchEvent]) -> anyhow::Result<()> {
+        let entry = &self.dirs[source].entry;
+        // Shown relative to the app root, or with the directory's name
+        let label = |path: &std::path::Path| match path.strip_prefix(&self.project_root) {
+            Ok(rel) => rel.display().to_string(),
+            Err(_) => format!("{}/{}", entry.name, path.strip_prefix(&entry.path).unwrap_or(path).display()),
         };
+        let mut files = Vec::new();
+        let mut deleted = Vec::new();
+        for event in events {
+            // A deletion that settled: nothing to read or learn, only forget
+            if event.event_type == "unlink" {
+                if !event.file_path.exists() {
+                    self.memory.unindex(&event.file_path).await;
+                    deleted.push(label(&event.file_path));
+                }
+                continue;
+            }
+            match tokio::fs::read_to_string(&event.file_path).await {
+                Ok(content) => files.push((event, content)),
+                // Deleted or unreadable since the event: nothing to learn
+                Err(e) => info!("👀 Watchman: Skipping {:?}: {}", event.file_path, e),
+            }
+        }
+        if entry.learning == LearningPolicy::Off {
+            return Ok(());
+        }
+        if !deleted.is_empty() {
+            info!("👀 Watchman: Forgot deleted {}", deleted.join(", "));
+            let line = format!("\n[{}] Watchman: deleted {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), deleted.join(", "));
+            if let Err(e) = self.memory.save_journal(&line).await {
+                tracing::warn!("👀 Watchman: Could not journal deletions: {}", e);
+            }
+        }
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        if entry.learning == LearningPolicy::Journal {
+            let changed: Vec<String> = files.iter().map(|(event, _)| format!("{} ({})", label(&event.file_path), event.event_type)).collect();
+            let line = format!("\n[{}] Watchman: changed {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), changed.join(", "));
+            self.memory.save_journal(&line).await?;
+            info!("👀 Watchman: Journaled {}", changed.join(", "));
+            self.count(source, |stats| stats.journaled += files.len() as u64);
+            return Ok(());
+        }
+
+        if !self.budget.lock().unwrap_or_else(|e| e.into_inner()).try_spend(source, entry, Instant::now()) {
+            info!(
+                "👀 Watchman: Hourly analysis budget of {} spent, not analyzing {} file(s)",
+                entry.name,
+                files.len()
+            );
+            self.count(source, |stats| stats.over_budget += files.len() as u64);
+            return Ok(());
+        }
+        self.count(source, |stats| stats.analyzed += files.len() as u64);
 
         // Create context for learning
-        let context = format!(
-            "[System Event]: File {} detected at {:?}.\nContent Preview:\n{}",
-            event.event_type,
-            event.file_path,
-            preview
-        );
+        let context = if let [(event, content)] = files.as_slice() {
+            format!(
+                "[System Event]: File {} detected at {:?}.\nContent Preview:\n{}",
+                event.event_type,
+                event.file_path,
+                truncate_chars(content, PREVIEW_CHARS)
+            )
+        } else {
+            let per_file = (BATCH_PREVIEW_BUDGET / files.len()).max(MIN_BATCH_PREVIEW);
+            let previews: Vec<String> = files.iter()
+                .map(|(event, content)| format!(
+                    "--- File {} at {:?} ---\n{}",
+                    event.event_type,
+                    event.file_path,
+                    truncate_chars(content, per_file)
+                ))
+                .collect();
+            format!("[System Event]: {} files changed together.\n\n{}", files.len(), previews.join("\n\n"))
+        };
 
         // Use brain to analyze and extract knowledge
         // This matches memory.learn() in JS
+        let output_format = if files.len() == 1 {
+            r#"Output JSON:
+{"should_save": true/false, "category": "preferences"|"knowledge"|"context", "title": "Short Title", "content": "...", "tags": ["tag1"]}
+
+If nothing worth saving, set "should_save": false."#
+        } else {
+            r#"Output a JSON array with one object per distinct piece of knowledge (related files may share one):
+[{"should_save": true, "category": "preferences"|"knowledge"|"context", "title": "Short Title", "content": "...", "tags": ["tag1"]}]
+
+If nothing worth saving, output []."#
+        };
         let analysis_prompt = format!(
             r#"Analyze this file change for new knowledge:
 
@@ -33,38 +114,178 @@ If this contains:
 2. New factual knowledge
 3. Critical context for future tasks
 
-Output JSON:
-{{"should_save": true/false, "category": "preferences"|"knowledge"|"context", "title": "Short Title", "content": "...", "tags": ["tag1"]}}
-
-If nothing worth saving, set "should_save": false.
+{}
 Do not wrap in markdown blocks. Return raw JSON only."#,
-            context
+            context,
+            output_format
         );
 
         let response = self.brain.think_simple(&analysis_prompt).await;
 
-        // Robust JSON extraction
-        let start = response.find('{').unwrap_or(0);
-        let end = response.rfind('}').map(|i| i + 1).unwrap_or(response.len());
-        let clean_json = &response[start..end];
-
-        // Try to parse JSON response
-        if let Ok(data) = serde_json::from_str::<serde_json::Value>(clean_json) {
-            if data["should_save"].as_bool().unwrap_or(false) {
-                let category = data["category"].as_str().unwrap_or("context");
-                let title = data["title"].as_str().unwrap_or("untitled");
-                let save_content = data["content"].as_str().unwrap_or(&content);
-                let tags: Vec<String> = data["tags"]
-                    .as_array()
-                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                    .unwrap_or_default();
-
-                if let Ok(Some(path)) = self.memory.save_memory(category, title, save_content, tags, Some("learned")).await {
-                    info!("🧠 Watchman Learned: {} -> {:?}", title, path);
-                }
+        // A lone file falls back to its own content when the model omits "content"
+        let fallback = if let [(_, content)] = files.as_slice() { content.as_str() } else { "" };
+        for data in parse_items(&response) {
+            if !data["should_save"].as_bool().unwrap_or(false) {
+                continue;
+            }
+            let category = data["category"].as_str().unwrap_or("context");
+            let title = data["title"].as_str().unwrap_or("untitled");
+            let save_content = data["content"].as_str().unwrap_or(fallback);
+            if save_content.trim().is_empty() {
+                continue;
+            }
+            let tags: Vec<String> = data["tags"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                .unwrap_or_default();
+
+            if let Ok(Some(path)) = self.memory.save_memory(category, title, save_content, tags, Some("learned"), self.memory.learned_ttl(category)).await {
+                info!("🧠 Watchman Learned: {} -> {:?}", title, path);
+                self.count(source, |stats| stats.learned += 1);
             }
         }
 
         Ok(())
     }
 }
+
+/// Robust JSON extraction: a bare object or an array of them
+fn parse_items(response: &str) -> Vec<serde_json::Value> {
+    let array = response.find('[').zip(response.rfind(']'))
+        .filter(|(start, end)| start < end && response.find('{').is_none_or(|obj| *start < obj))
+        .and_then(|(start, end)| serde_json::from_str::<Vec<serde_json::Value>>(&response[start..=end]).ok());
+    if let Some(items) = array {
+        return items;
+    }
+
+    let start = response.find('{').unwrap_or(0);
+    let end = response.rfind('}').map(|i| i + 1).unwrap_or(response.len());
+    serde_json::from_str::<serde_json::Value>(&response[start..end])
+        .map(|v| vec![v])
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_brain::Brain;
+    use openspore_brain::test_support::{Requests, config_in, fake_llm};
+    use openspore_core::config::WatchEntryConfig;
+    use std::path::Path;
+
+    const ONE_ITEM: &str = r#"{"should_save": true, "category": "preferences", "title": "Editor", "tags": ["tools"]}"#;
+
+    /// A Watchman over `root` whose `notes` directory has the `learning` policy
+    async fn watchman(root: &Path, learning: &str, per_hour: Option<u32>) -> (Watchman, Requests) {
+        let (url, requests) = fake_llm(|_| ONE_ITEM.to_string()).await;
+        let mut config = config_in(root, &url);
+        config.watchman.analyses_per_hour = per_hour;
+        config.watchman.watch = vec![WatchEntryConfig { path: "notes".into(), learning: Some(learning.into()), ..Default::default() }];
+        let brain = Brain::new(config.clone());
+        brain.memory.ensure_structure().await.unwrap();
+        std::fs::create_dir_all(root.join("notes")).unwrap();
+        (Watchman::new(config, brain.clone(), brain.memory.clone()), requests)
+    }
+
+    fn write(root: &Path, name: &str, text: &str) -> WatchEvent {
+        let path = root.join("notes").join(name);
+        std::fs::write(&path, text).unwrap();
+        WatchEvent { event_type: "change".into(), file_path: path, source: 1 }
+    }
+
+    /// Whether searching memory for `query` turns up `path`
+    async fn finds(watchman: &Watchman, query: &str, path: &Path) -> bool {
+        watchman.memory.search(query, 5).await.unwrap().iter().any(|r| r.path == path)
+    }
+
+    #[test]
+    fn parse_items_takes_an_object_or_an_array() {
+        assert_eq!(parse_items(r#"Sure: {"should_save": false} done"#).len(), 1);
+        assert_eq!(parse_items(r#"```json
+[{"title": "a"}, {"title": "b"}]
+```"#).len(), 2);
+        assert_eq!(parse_items(r#"{"title": "has [brackets] inside"}"#)[0]["title"], "has [brackets] inside");
+        assert!(parse_items("[]").is_empty());
+        assert!(parse_items("not json at all").is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_lone_file_is_saved_with_its_own_content_when_the_model_omits_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (watchman, requests) = watchman(&root, "learn", None).await;
+        let event = write(&root, "editor.md", "I use helix with a light theme.");
+
+        watchman.process_batch(1, &[event]).await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests.message(0, "user").contains("Content Preview:\nI use helix"));
+        let saved = watchman.memory.get_memories("preferences");
+        assert!(saved.iter().any(|m| m.content.contains("I use helix with a light theme.")));
+    }
+
+    #[tokio::test]
+    async fn journal_policy_lists_changes_without_the_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (watchman, requests) = watchman(&root, "journal", None).await;
+        let event = write(&root, "plan.md", "Ship on Friday.");
+
+        watchman.process_batch(1, &[event]).await.unwrap();
+        assert!(requests.is_empty());
+        let logs = std::fs::read_to_string(watchman.memory.context_dir().join("LOGS.md")).unwrap();
+        assert!(logs.contains("Watchman: changed notes/plan.md (change)"));
+    }
+
+    #[tokio::test]
+    async fn a_spent_budget_skips_the_analysis() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (watchman, requests) = watchman(&root, "learn", Some(1)).await;
+
+        watchman.process_batch(1, &[write(&root, "a.md", "first")]).await.unwrap();
+        watchman.process_batch(1, &[write(&root, "b.md", "second")]).await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests.mentioning("second"), 0);
+    }
+
+    #[tokio::test]
+    async fn a_created_then_deleted_file_is_forgotten_without_reading_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (watchman, requests) = watchman(&root, "learn", None).await;
+        watchman.memory.rebuild_index().await.unwrap();
+
+        let created = write(&root, "zanzibar.md", "Zanzibar trip in March.");
+        watchman.memory.index_paths(std::slice::from_ref(&created.file_path)).await;
+        assert!(finds(&watchman, "zanzibar", &created.file_path).await);
+
+        std::fs::remove_file(&created.file_path).unwrap();
+        let deleted = WatchEvent { event_type: "unlink".into(), ..created };
+        watchman.process_batch(1, std::slice::from_ref(&deleted)).await.unwrap();
+
+        assert!(requests.is_empty(), "nothing to analyze");
+        assert_eq!(watchman.memory.unindex(&deleted.file_path).await, 0, "already dropped from the index");
+        assert!(!finds(&watchman, "zanzibar", &deleted.file_path).await);
+        let logs = std::fs::read_to_string(watchman.memory.context_dir().join("LOGS.md")).unwrap();
+        assert!(logs.contains("Watchman: deleted notes/zanzibar.md"));
+    }
+
+    #[tokio::test]
+    async fn an_unlink_for_a_recreated_file_keeps_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (watchman, requests) = watchman(&root, "journal", None).await;
+        watchman.memory.rebuild_index().await.unwrap();
+
+        // Deleted, then saved again before the event settled
+        let event = write(&root, "plan.md", "Ship on Friday.");
+        watchman.memory.index_paths(std::slice::from_ref(&event.file_path)).await;
+        let stale = WatchEvent { event_type: "unlink".into(), ..event };
+        watchman.process_batch(1, std::slice::from_ref(&stale)).await.unwrap();
+
+        assert!(requests.is_empty());
+        assert!(finds(&watchman, "friday", &stale.file_path).await);
+        let logs = std::fs::read_to_string(watchman.memory.context_dir().join("LOGS.md")).unwrap_or_default();
+        assert!(!logs.contains("Watchman: deleted"));
+    }
+}
@@ -1,6 +1,120 @@
+//! Debounced event queue
+//!
+//! Editors write a file several times per save. Events for the same path are
+//! coalesced until the file has been quiet for the debounce window (a delete
+//! followed by a create becomes one "change"), and files
+//! first changed within one batch window are released together so a burst is
+//! analyzed in a single prompt.
+
 use crate::{Watchman, types::WatchEvent};
+use openspore_core::config::AppConfig;
+use std::collections::VecDeque;
 use std::path::PathBuf;
-use tracing::{info, error};
+use std::time::{Duration, Instant};
+use tracing::{info, warn, error};
+
+/// Files waiting beyond this are dropped, oldest first
+const MAX_QUEUED: usize = 200;
+
+struct Pending {
+    event: WatchEvent,
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+pub struct EventQueue {
+    pending: VecDeque<Pending>,
+    debounce: Duration,
+    batch_window: Duration,
+    max_batch: usize,
+}
+
+impl EventQueue {
+    pub fn new(debounce: Duration, batch_window: Duration, max_batch: usize) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            debounce,
+            batch_window,
+            max_batch: max_batch.max(1),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(
+            Duration::from_millis(config.watchman_debounce_ms.unwrap_or(2000)),
+            Duration::from_millis(config.watchman_batch_window_ms.unwrap_or(5000)),
+            config.watchman_max_batch.unwrap_or(8),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn contains(&self, path: &std::path::Path) -> bool {
+        self.pending.iter().any(|p| p.event.file_path == path)
+    }
+
+    /// Queue an event, or fold it into the pending one for the same file.
+    /// Returns the oldest event if it had to be dropped to stay under the cap.
+    pub fn push(&mut self, event: WatchEvent, now: Instant) -> Option<WatchEvent> {
+        if let Some(i) = self.pending.iter().position(|p| p.event.file_path == event.file_path) {
+            let p = &mut self.pending[i];
+            match (p.event.event_type.as_str(), event.event_type.as_str()) {
+                // Created and deleted before it settled: nothing happened
+                ("add", "unlink") => {
+                    self.pending.remove(i);
+                    return None;
+                }
+                // A file created and then written is still new
+                ("add", _) => {}
+                // Deleted then recreated (atomic saves): the file changed
+                ("unlink", "add") => p.event.event_type = "change".to_string(),
+                _ => p.event.event_type = event.event_type,
+            }
+            p.last_seen = now;
+            return None;
+        }
+
+        let dropped = if self.pending.len() >= MAX_QUEUED {
+            self.pending.pop_front().map(|p| p.event)
+        } else {
+            None
+        };
+        self.pending.push_back(Pending { event, first_seen: now, last_seen: now });
+        dropped
+    }
+
+    /// Next batch to analyze: the files first changed within the batch window of
+    /// the oldest pending one, once all of them have settled (at most `max_batch`).
+    /// A file that keeps changing holds the batch back for one extra window at most.
+    pub fn take_batch(&mut self, now: Instant) -> Vec<WatchEvent> {
+        let Some(start) = self.pending.iter().map(|p| p.first_seen).min() else { return vec![] };
+        let settled = |p: &Pending| now.saturating_duration_since(p.last_seen) >= self.debounce;
+        let in_burst = |p: &Pending| p.first_seen.saturating_duration_since(start) <= self.batch_window;
+
+        let overdue = now.saturating_duration_since(start) >= self.batch_window * 2 + self.debounce;
+        if !overdue && !self.pending.iter().filter(|p| in_burst(p)).all(settled) {
+            return vec![];
+        }
+
+        let mut batch = Vec::new();
+        let mut waiting = VecDeque::new();
+        for p in std::mem::take(&mut self.pending) {
+            if batch.len() < self.max_batch && in_burst(&p) && settled(&p) {
+                batch.push(p.event);
+            } else {
+                waiting.push_back(p);
+            }
+        }
+        self.pending = waiting;
+        batch
+    }
+}
 
 impl Watchman {
     /// Enqueue a file event for processing
@@ -10,30 +124,167 @@ impl Watchman {
             return;
         }
 
-        if self.should_ignore(&file_path) {
-            return;
-        }
-
-        info!("👀 Watchman detected {}: {:?}", event_type, file_path);
+        let Some(source) = self.source_of(&file_path) else { return };
 
         let mut queue = self.queue.lock().await;
-        queue.push(WatchEvent {
+        if !queue.contains(&file_path) {
+            info!("👀 Watchman detected {} in {}: {:?}", event_type, self.dirs[source].entry.name, file_path);
+            self.count(source, |stats| stats.detected += 1);
+        }
+        let event = WatchEvent {
             event_type: event_type.to_string(),
             file_path,
-        });
+            source,
+        };
+        if let Some(dropped) = queue.push(event, Instant::now()) {
+            warn!("👀 Watchman queue full ({} files), dropped {:?}", MAX_QUEUED, dropped.file_path);
+        }
     }
 
-    /// Process queued events - trigger learning
+    /// Process settled events - one prompt per batch and watched directory,
+    /// under that directory's rules
     pub async fn process_queue(&self) {
-        let events: Vec<WatchEvent> = {
-            let mut queue = self.queue.lock().await;
-            std::mem::take(&mut *queue)
-        };
+        loop {
+            let batch = self.queue.lock().await.take_batch(Instant::now());
+            if batch.is_empty() {
+                break;
+            }
 
-        for event in events {
-            if let Err(e) = self.process_event(&event).await {
-                error!("Watchman Error processing {:?}: {}", event.file_path, e);
+            let mut sources: Vec<usize> = batch.iter().map(|e| e.source).collect();
+            sources.sort_unstable();
+            sources.dedup();
+            for source in sources {
+                let events: Vec<WatchEvent> = batch.iter().filter(|e| e.source == source).cloned().collect();
+                if let Err(e) = self.process_batch(source, &events).await {
+                    let files: Vec<_> = events.iter().map(|e| &e.file_path).collect();
+                    error!("Watchman Error processing {:?}: {}", files, e);
+                }
             }
         }
+        self.save_stats(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openspore_brain::Brain;
+    use openspore_brain::test_support::{config_in, fake_llm};
+
+    const DEBOUNCE: Duration = Duration::from_millis(100);
+    const WINDOW: Duration = Duration::from_millis(500);
+
+    fn event(kind: &str, name: &str) -> WatchEvent {
+        WatchEvent { event_type: kind.to_string(), file_path: PathBuf::from(name), source: 0 }
+    }
+
+    fn names(batch: &[WatchEvent]) -> Vec<String> {
+        batch.iter().map(|e| format!("{} {}", e.event_type, e.file_path.display())).collect()
+    }
+
+    #[test]
+    fn events_for_a_file_coalesce_until_it_settles() {
+        let mut queue = EventQueue::new(DEBOUNCE, WINDOW, 8);
+        let t0 = Instant::now();
+        queue.push(event("change", "a.md"), t0);
+        queue.push(event("change", "a.md"), t0 + Duration::from_millis(80));
+        assert_eq!(queue.len(), 1);
+
+        assert!(queue.take_batch(t0 + Duration::from_millis(150)).is_empty(), "still inside the debounce");
+        assert_eq!(names(&queue.take_batch(t0 + Duration::from_millis(180))), ["change a.md"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn create_delete_pairs_fold() {
+        let mut queue = EventQueue::new(DEBOUNCE, WINDOW, 8);
+        let t0 = Instant::now();
+        queue.push(event("add", "tmp.md"), t0);
+        queue.push(event("unlink", "tmp.md"), t0);
+        queue.push(event("unlink", "saved.md"), t0);
+        queue.push(event("add", "saved.md"), t0);
+        queue.push(event("add", "new.md"), t0);
+        queue.push(event("change", "new.md"), t0);
+        assert_eq!(names(&queue.take_batch(t0 + DEBOUNCE)), ["change saved.md", "add new.md"]);
+    }
+
+    #[test]
+    fn a_burst_is_released_together_up_to_max_batch() {
+        let mut queue = EventQueue::new(DEBOUNCE, WINDOW, 2);
+        let t0 = Instant::now();
+        queue.push(event("change", "a.md"), t0);
+        queue.push(event("change", "b.md"), t0 + Duration::from_millis(300));
+        queue.push(event("change", "c.md"), t0 + Duration::from_millis(400));
+        queue.push(event("change", "late.md"), t0 + Duration::from_millis(900));
+
+        // a.md settled long ago, but b.md of the same burst has not
+        assert!(queue.take_batch(t0 + Duration::from_millis(350)).is_empty());
+        let now = t0 + Duration::from_millis(1000);
+        assert_eq!(names(&queue.take_batch(now)), ["change a.md", "change b.md"]);
+        assert_eq!(names(&queue.take_batch(now)), ["change c.md", "change late.md"]);
+    }
+
+    #[test]
+    fn a_file_that_keeps_changing_only_holds_the_batch_back_so_long() {
+        let mut queue = EventQueue::new(DEBOUNCE, WINDOW, 8);
+        let t0 = Instant::now();
+        queue.push(event("change", "calm.md"), t0);
+        let mut t = t0;
+        while t < t0 + WINDOW * 2 + DEBOUNCE {
+            queue.push(event("change", "busy.md"), t);
+            assert!(queue.take_batch(t).is_empty());
+            t += Duration::from_millis(50);
+        }
+        assert_eq!(names(&queue.take_batch(t)), ["change calm.md"]);
+        assert!(queue.contains(std::path::Path::new("busy.md")));
+    }
+
+    #[test]
+    fn the_oldest_file_is_dropped_at_the_cap() {
+        let mut queue = EventQueue::new(DEBOUNCE, WINDOW, 8);
+        let t0 = Instant::now();
+        for i in 0..MAX_QUEUED {
+            assert!(queue.push(event("change", &format!("{}.md", i)), t0).is_none());
+        }
+        let dropped = queue.push(event("change", "one-more.md"), t0).unwrap();
+        assert_eq!(dropped.file_path, PathBuf::from("0.md"));
+        assert_eq!(queue.len(), MAX_QUEUED);
+    }
+
+    #[tokio::test]
+    async fn a_settled_burst_is_analyzed_in_one_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (url, requests) = fake_llm(|_| {
+            r#"[{"should_save": true, "category": "knowledge", "title": "Deploy steps", "content": "Deploys go through staging first.", "tags": ["deploy"]},
+                {"should_save": false}]"#.to_string()
+        }).await;
+        let config = AppConfig {
+            watchman_debounce_ms: Some(0),
+            watchman_batch_window_ms: Some(60_000),
+            ..config_in(&root, &url)
+        };
+        let brain = Brain::new(config.clone());
+        brain.memory.ensure_structure().await.unwrap();
+        let watchman = Watchman::new(config, brain.clone(), brain.memory.clone());
+
+        let notes = root.join("notes");
+        std::fs::create_dir_all(&notes).unwrap();
+        for (name, text) in [("deploy.md", "Always deploy to staging first."), ("todo.txt", "buy milk")] {
+            std::fs::write(notes.join(name), text).unwrap();
+            watchman.enqueue("add", notes.join(name)).await;
+        }
+        watchman.enqueue("change", notes.join("deploy.md")).await;
+        assert_eq!(watchman.queue.lock().await.len(), 2);
+
+        watchman.process_queue().await;
+        assert!(watchman.queue.lock().await.is_empty());
+        assert_eq!(requests.len(), 1, "one prompt for the burst");
+        let prompt = requests.message(0, "user");
+        assert!(prompt.contains("2 files changed together"));
+        assert!(prompt.contains("Always deploy to staging first.") && prompt.contains("buy milk"));
+
+        let saved = brain.memory.get_memories("knowledge");
+        assert!(saved.iter().any(|m| m.content.contains("Deploys go through staging first.")));
     }
 }
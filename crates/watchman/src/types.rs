@@ -1,7 +1,25 @@
+use openspore_core::watch::WatchEntry;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct WatchEvent {
     pub event_type: String,
     pub file_path: PathBuf,
+    /// Index of the watched directory (Watchman::dirs) the file belongs to
+    pub source: usize,
+}
+
+/// A watched directory with its compiled ignore rules
+pub struct WatchedDir {
+    pub entry: WatchEntry,
+    /// Built-in rules and the entry's `ignore` globs, compiled by load_ignore_rules()
+    pub ignore_globs: globset::GlobSet,
+    /// .watchmanignore and .gitignore pattern layers, highest precedence first
+    pub ignore_patterns: Vec<::ignore::gitignore::Gitignore>,
+}
+
+impl WatchedDir {
+    pub fn new(entry: WatchEntry) -> Self {
+        Self { entry, ignore_globs: globset::GlobSet::empty(), ignore_patterns: Vec::new() }
+    }
 }
@@ -1,39 +1,112 @@
 use crate::Watchman;
 use std::sync::Arc;
 use notify::{Watcher, RecursiveMode, Event, EventKind};
-use tracing::info;
+use notify::event::{ModifyKind, RenameMode};
+use tracing::{info, warn};
 
 impl Watchman {
-    /// Start the filesystem watcher
+    /// Start the filesystem watchers: one per watched directory, except
+    /// directories inside another one (its recursive watcher covers them)
     pub async fn start(self: Arc<Self>) -> anyhow::Result<()> {
-        info!("👀 Watchman: Starting filesystem observation at {:?}", self.project_root);
-
         let (tx, mut rx) = tokio::sync::mpsc::channel(100);
         let watchman = self.clone();
 
-        // Spawn watcher in blocking thread
-        let project_root = self.project_root.clone();
-        std::thread::spawn(move || {
-            let mut watcher = notify::recommended_watcher(move |res: Result<Event, _>| {
-                if let Ok(event) = res {
-                    let _ = tx.blocking_send(event);
+        let roots: Vec<_> = self.dirs.iter()
+            .map(|d| &d.entry)
+            .filter(|entry| !self.dirs.iter().any(|o| o.entry.path != entry.path && entry.path.starts_with(&o.entry.path)))
+            .cloned()
+            .collect();
+        for entry in roots {
+            info!("👀 Watchman: Starting filesystem observation of {} at {:?}", entry.name, entry.path);
+            let tx = tx.clone();
+            // Spawn watcher in blocking thread
+            std::thread::spawn(move || {
+                let watcher = notify::recommended_watcher(move |res: Result<Event, _>| {
+                    if let Ok(event) = res {
+                        let _ = tx.blocking_send(event);
+                    }
+                });
+                let mut watcher = match watcher {
+                    Ok(watcher) => watcher,
+                    Err(e) => {
+                        warn!("👀 Watchman: could not create a watcher for {}: {}", entry.name, e);
+                        return;
+                    }
+                };
+                if let Err(e) = watcher.watch(&entry.path, RecursiveMode::Recursive) {
+                    warn!("👀 Watchman: not watching {} ({:?}): {}", entry.name, entry.path, e);
+                    return;
                 }
-            }).expect("Failed to create watcher");
 
-            watcher.watch(&project_root, RecursiveMode::Recursive)
-                .expect("Failed to watch directory");
-
-            // Keep watcher alive
-            loop {
-                std::thread::sleep(std::time::Duration::from_secs(60));
-            }
-        });
+                // Keep watcher alive
+                loop {
+                    std::thread::sleep(std::time::Duration::from_secs(60));
+                }
+            });
+        }
+        drop(tx);
+        self.save_stats(true);
 
         info!("👀 Watchman: Ready and watching.");
 
+        // Settled files are picked up on each tick
+        let mut tick = tokio::time::interval(std::time::Duration::from_millis(250));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
         // Process events
-        while let Some(event) = rx.recv().await {
+        loop {
+            let event = tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = tick.tick() => {
+                    watchman.process_queue().await;
+                    continue;
+                }
+            };
+
+            // Keep the search index of the app root current (independent of the
+            // learning filters). A deleted directory takes the entries of every
+            // file below it along.
+            let indexed: Vec<_> = event.paths.iter().filter(|p| p.starts_with(&watchman.project_root)).cloned().collect();
+            if is_removal(&event.kind) {
+                for path in &indexed {
+                    watchman.memory.unindex(path).await;
+                }
+            } else if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                watchman.memory.index_paths(&indexed).await;
+                // save_memory embeds its own writes
+                if watchman.memory.embeddings_enabled() {
+                    let memory = watchman.memory.clone();
+                    let mut paths = Vec::new();
+                    for path in indexed.iter().filter(|p| p.extension().is_some_and(|e| e == "md")) {
+                        if !memory.is_internal_write(path).await {
+                            paths.push(path.clone());
+                        }
+                    }
+                    tokio::spawn(async move {
+                        if let Err(e) = memory.embed_paths(&paths).await {
+                            tracing::warn!("🧭 Embedding failed: {}", e);
+                        }
+                    });
+                }
+            }
+
             match event.kind {
+                _ if is_removal(&event.kind) => {
+                    for path in event.paths {
+                        watchman.enqueue("unlink", path).await;
+                    }
+                }
+                // Renamed within the tree: the old name is gone, the new one appears
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                    let mut paths = event.paths.into_iter();
+                    if let (Some(from), Some(to)) = (paths.next(), paths.next()) {
+                        watchman.enqueue("unlink", from).await;
+                        watchman.enqueue("add", to).await;
+                    }
+                }
                 EventKind::Create(_) => {
                     for path in event.paths {
                         watchman.enqueue("add", path).await;
@@ -46,11 +119,13 @@ impl Watchman {
                 }
                 _ => {}
             }
-
-            // Process queue after each batch
-            watchman.process_queue().await;
         }
 
         Ok(())
     }
 }
+
+/// Deleted, or moved away from the watched path
+fn is_removal(kind: &EventKind) -> bool {
+    matches!(kind, EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)))
+}